@@ -0,0 +1,125 @@
+// Walk, pack, and reachability over a generated TypeScript repo.
+//
+// Size it with `BABLUSHEED_BENCH_FILES` (default 1000) and `BABLUSHEED_BENCH_LINES` (lines per
+// file, default 60), e.g. `BABLUSHEED_BENCH_FILES=5000 cargo bench --bench pipeline`.
+use bablusheed_lib::commands::ast::analyze_reachability;
+use bablusheed_lib::commands::fs::walk_directory_flat;
+use bablusheed_lib::commands::pack::pack_files;
+use bablusheed_lib::models::{FileContent, PackRequest};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::path::{Path, PathBuf};
+
+fn env_usize(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// `src/m<i / 50>/f<i>.ts`: module `i` imports modules `2i + 1` and `2i + 2`, so every file is
+/// reachable from `f0` and half of each file's functions are used.
+fn synthetic_repo(file_count: usize, lines_per_file: usize) -> Vec<FileContent> {
+    let path = |i: usize| format!("src/m{}/f{i}.ts", i / 50);
+    let functions = (lines_per_file / 4).max(1);
+    (0..file_count)
+        .map(|i| {
+            let mut content = String::new();
+            let children: Vec<usize> = [2 * i + 1, 2 * i + 2]
+                .into_iter()
+                .filter(|&j| j < file_count)
+                .collect();
+            for &j in &children {
+                content.push_str(&format!(
+                    "import {{ f{j}_0 }} from \"../m{}/f{j}\";\n",
+                    j / 50
+                ));
+            }
+            for k in 0..functions {
+                let body = match (k, children.as_slice()) {
+                    (0, [first, ..]) => format!("f{first}_0(value)"),
+                    _ => format!("value * {k} + {i}"),
+                };
+                content.push_str(&format!(
+                    "\nexport function f{i}_{k}(value: number): number {{\n  return {body};\n}}\n"
+                ));
+            }
+            FileContent {
+                path: path(i),
+                content,
+                token_count: None,
+            }
+        })
+        .collect()
+}
+
+fn write_repo(files: &[FileContent]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("bablusheed-bench-{}", std::process::id()));
+    for file in files {
+        let path = root.join(&file.path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, &file.content).unwrap();
+    }
+    root
+}
+
+fn copies(files: &[FileContent]) -> Vec<FileContent> {
+    files
+        .iter()
+        .map(|file| FileContent {
+            path: file.path.clone(),
+            content: file.content.clone(),
+            token_count: None,
+        })
+        .collect()
+}
+
+fn pack_request(files: Vec<FileContent>) -> PackRequest {
+    PackRequest {
+        files,
+        num_packs: 5,
+        output_format: "markdown".into(),
+        ..Default::default()
+    }
+}
+
+fn pipeline(c: &mut Criterion) {
+    let file_count = env_usize("BABLUSHEED_BENCH_FILES", 1000);
+    let lines = env_usize("BABLUSHEED_BENCH_LINES", 60);
+    let files = synthetic_repo(file_count, lines);
+    let root = write_repo(&files);
+    let root_arg = root.to_string_lossy().to_string();
+
+    let mut group = c.benchmark_group(format!("{file_count} files x {lines} lines"));
+    group.sample_size(10);
+    group.bench_function("walk", |b| {
+        b.iter(|| {
+            tauri::async_runtime::block_on(walk_directory_flat(
+                root_arg.clone(),
+                true,
+                Vec::new(),
+                None,
+                None,
+            ))
+            .unwrap()
+        })
+    });
+    group.bench_function("pack_files", |b| {
+        b.iter(|| tauri::async_runtime::block_on(pack_files(pack_request(copies(&files)))).unwrap())
+    });
+    group.bench_function("analyze_reachability", |b| {
+        b.iter(|| {
+            tauri::async_runtime::block_on(analyze_reachability(
+                "src/m0/f0.ts".into(),
+                copies(&files),
+                None,
+            ))
+            .unwrap()
+        })
+    });
+    group.finish();
+
+    let _ = std::fs::remove_dir_all(Path::new(&root));
+}
+
+criterion_group!(benches, pipeline);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+// Packs arbitrary file sets and checks that every file lands in exactly one pack, token
+// totals add up, multi-file packs fit the budget, and only budget-added packs are marked as
+// spillover.
+//
+// Run with `cargo +nightly fuzz run pack_invariants` from `src-tauri/`.
+#![no_main]
+
+use bablusheed_lib::commands::pack::pack_files;
+use bablusheed_lib::models::{FileContent, PackRequest};
+use libfuzzer_sys::fuzz_target;
+
+const EXTENSIONS: &[&str] = &["ts", "tsx", "js", "py", "rs", "go", "md", "json"];
+
+/// The first bytes pick pack count, budget, and strategy; the rest splits on NUL into files
+/// whose first byte picks the extension.
+fn request_from(data: &[u8]) -> Option<PackRequest> {
+    let (&[packs, budget, strategy], rest) = data.split_first_chunk::<3>()?;
+    let files: Vec<FileContent> = rest
+        .split(|&b| b == 0)
+        .enumerate()
+        .filter_map(|(i, chunk)| {
+            let (&kind, content) = chunk.split_first()?;
+            let ext = EXTENSIONS[kind as usize % EXTENSIONS.len()];
+            let dir = if kind & 0x80 != 0 { "docs" } else { "src" };
+            Some(FileContent {
+                path: format!("{dir}/f{i}.{ext}"),
+                content: String::from_utf8_lossy(content).into_owned(),
+                token_count: (kind & 0x40 != 0).then_some(content.len()),
+            })
+        })
+        .collect();
+    Some(PackRequest {
+        files,
+        num_packs: (packs % 8) as usize + 1,
+        max_tokens_per_pack: (budget > 0).then_some(budget as usize * 16),
+        strategy: if strategy % 2 == 0 {
+            "grouped"
+        } else {
+            "dependency"
+        }
+        .into(),
+        ..Default::default()
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some(request) = request_from(data) else {
+        return;
+    };
+    let requested_packs = request.num_packs;
+    let budget = request.max_tokens_per_pack;
+    let mut expected: Vec<String> = request.files.iter().map(|f| f.path.clone()).collect();
+    expected.sort();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let response = runtime.block_on(pack_files(request)).unwrap();
+
+    let mut packed: Vec<String> = response
+        .packs
+        .iter()
+        .flat_map(|p| p.file_paths.clone())
+        .collect();
+    packed.sort();
+    assert_eq!(packed, expected, "every file is packed exactly once");
+
+    let pack_tokens: usize = response.packs.iter().map(|p| p.estimated_tokens).sum();
+    assert_eq!(pack_tokens, response.total_tokens);
+    if let Some(budget) = budget {
+        for pack in &response.packs {
+            assert!(
+                pack.file_count == 1 || pack.estimated_tokens <= budget,
+                "multi-file packs fit the budget"
+            );
+        }
+    }
+
+    assert_eq!(response.placements.len(), expected.len());
+    for placement in &response.placements {
+        let ordinal = response
+            .packs
+            .iter()
+            .position(|p| p.index == placement.pack_index)
+            .expect("placement names a pack");
+        assert_eq!(placement.spillover, ordinal >= requested_packs);
+    }
+});
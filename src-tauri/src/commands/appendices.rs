@@ -0,0 +1,365 @@
+use crate::models::FileContent;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// Locations listed per entry before the rest are summarized as a count.
+const LOCATIONS_PER_ENTRY: usize = 5;
+
+/// Tech-debt notes listed before the rest are summarized as a count.
+const TODO_LIMIT: usize = 500;
+
+/// Distinct error messages listed before the rest are summarized as a count.
+const ERROR_MESSAGE_LIMIT: usize = 1_000;
+
+/// Note kinds in the order they are listed, most urgent first.
+const TODO_KINDS: &[&str] = &["FIXME", "HACK", "XXX", "TODO"];
+
+/// Environment reads across JS/TS, Rust, Python and Go; the first group that matched holds
+/// the variable name.
+static ENV_READ: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r#"(?:process\.env|import\.meta\.env|Bun\.env)(?:\.([A-Za-z_]\w*)|\[\s*['"]([A-Za-z_]\w*)['"]\s*\])"#,
+        r#"|(?:\benv::var(?:_os)?|\bDeno\.env\.get|\bos\.getenv|\bos\.environ\.get|\bos\.Getenv|\bos\.LookupEnv)\(\s*['"]([A-Za-z_]\w*)['"]"#,
+        r#"|\b(?:option_)?env!\(\s*"([A-Za-z_]\w*)""#,
+        r#"|\bos\.environ\[\s*['"]([A-Za-z_]\w*)['"]\s*\]"#,
+    ))
+    .unwrap()
+});
+
+/// Flag checks from common feature-flag SDKs (LaunchDarkly, Unleash, GrowthBook, Statsig,
+/// PostHog, Split, Flagsmith, OpenFeature) and their React hooks. Statsig-style calls may
+/// pass a user first.
+static FLAG_READ: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\b(?:variation|boolVariation|stringVariation|numberVariation|jsonVariation|isEnabled|isOn|isOff",
+        r"|getFeatureValue|hasFeature|checkGate|useGate|isFeatureEnabled|getFeatureFlag|useFeatureFlag",
+        r"|useFeatureFlagEnabled|useFlag|getTreatment|getBooleanValue|getStringValue|getNumberValue|getObjectValue)",
+        r#"\(\s*(?:\w+\s*,\s*)?['"]([\w.:-]+)['"]"#,
+    ))
+    .unwrap()
+});
+
+/// `// TODO(ana): text`, `# FIXME text`, `/* HACK: text */`, `<!-- XXX text -->`. A comment
+/// marker must come first, so identifiers like `TODO_LIST` are not notes.
+static TODO_NOTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?://+|#+|/\*+|^\s*\*|--|<!--|;+)\s*\b(TODO|FIXME|HACK|XXX)\b(?:\(([^)]*)\))?:?\s*(.*?)\s*(?:\*/|-->)?\s*$")
+        .unwrap()
+});
+
+/// Error constructors and reporters whose first argument is a message: `new Error(`, `raise
+/// ValueError(`, `Err(format!(`, `bail!(`, `.expect(`, `#[error(`, `fmt.Errorf(`, and so on.
+/// Matches may span lines; the message is the first group that matched.
+static ERROR_MESSAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?:\bnew\s+(?:[A-Z]\w*)?(?:Error|Exception)|\braise\s+[A-Z]\w*|\bErr|\b(?:anyhow|bail|eyre|panic)!",
+        r"|\berrors\.New|\bfmt\.Errorf|\bconsole\.error|\btoast\.error|\bsetError|\.expect|#\[error)",
+        r#"\(\s*(?:format!\(\s*)?[fr]?(?:"((?:\\.|[^"\\])*)"|'((?:\\.|[^'\\])*)'|`([^`]*)`)"#,
+    ))
+    .unwrap()
+});
+
+/// `name -> ["path:line", ...]` for every match of `pattern`, in path then line order.
+fn collect_reads(files: &[FileContent], pattern: &Regex) -> BTreeMap<String, Vec<String>> {
+    let mut reads: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut sorted: Vec<&FileContent> = files.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+    for file in sorted {
+        let (mut line, mut counted_to) = (1, 0);
+        for c in pattern.captures_iter(&file.content) {
+            let Some(name) = c.iter().skip(1).flatten().next() else {
+                continue;
+            };
+            let start = c.get(0).map_or(0, |m| m.start());
+            line += file.content[counted_to..start].matches('\n').count();
+            counted_to = start;
+            let location = format!("{}:{line}", file.path);
+            let locations = reads.entry(name.as_str().to_string()).or_default();
+            if locations.last() != Some(&location) {
+                locations.push(location);
+            }
+        }
+    }
+    reads
+}
+
+/// `path:line, path:line (+3 more)`
+fn format_locations(locations: &[String]) -> String {
+    let shown = locations.len().min(LOCATIONS_PER_ENTRY);
+    let mut out = locations[..shown].join(", ");
+    if locations.len() > shown {
+        out.push_str(&format!(" (+{} more)", locations.len() - shown));
+    }
+    out
+}
+
+fn push_entries(out: &mut String, heading: &str, entries: &BTreeMap<String, Vec<String>>) {
+    if entries.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n## {heading}\n\n"));
+    for (name, locations) in entries {
+        out.push_str(&format!("- `{name}`: {}\n", format_locations(locations)));
+    }
+}
+
+/// The environment variables and feature flags the files read, each with where it is read,
+/// or `None` when they read neither.
+pub(crate) fn render_config_surface(files: &[FileContent]) -> Option<String> {
+    let env = collect_reads(files, &ENV_READ);
+    let flags = collect_reads(files, &FLAG_READ);
+    if env.is_empty() && flags.is_empty() {
+        return None;
+    }
+    let mut out = String::from(
+        "# Configuration surface\n\nEnvironment variables and feature flags the packed code reads.\n",
+    );
+    push_entries(&mut out, "Environment variables", &env);
+    push_entries(&mut out, "Feature flags", &flags);
+    Some(out)
+}
+
+/// TODO/FIXME/HACK/XXX comments with where they are, most urgent kind first, or `None` when
+/// the files have none.
+pub(crate) fn render_todo_index(files: &[FileContent]) -> Option<String> {
+    let mut sorted: Vec<&FileContent> = files.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut notes: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    let mut file_count = 0;
+    for file in sorted {
+        let mut found = false;
+        for (line_idx, line) in file.content.lines().enumerate() {
+            let Some(c) = TODO_NOTE.captures(line) else {
+                continue;
+            };
+            let kind = TODO_KINDS
+                .iter()
+                .position(|k| *k == &c[1])
+                .unwrap_or(TODO_KINDS.len());
+            let mut note = format!("- {}:{}", file.path, line_idx + 1);
+            if let Some(author) = c.get(2).filter(|a| !a.as_str().trim().is_empty()) {
+                note.push_str(&format!(" ({})", author.as_str().trim()));
+            }
+            if !c[3].is_empty() {
+                note.push_str(&format!(" {}", &c[3]));
+            }
+            notes.entry(kind).or_default().push(note);
+            found = true;
+        }
+        file_count += usize::from(found);
+    }
+
+    let total: usize = notes.values().map(Vec::len).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut out = format!(
+        "# Tech debt
+
+{total} TODO/FIXME/HACK/XXX comments in {file_count} files.
+"
+    );
+    let mut listed = 0;
+    for (kind, entries) in &notes {
+        let take = entries.len().min(TODO_LIMIT - listed);
+        if take == 0 {
+            break;
+        }
+        out.push_str(&format!("\n## {}\n\n", TODO_KINDS[*kind]));
+        for entry in &entries[..take] {
+            out.push_str(entry);
+            out.push('\n');
+        }
+        listed += take;
+    }
+    if total > listed {
+        out.push_str(&format!("\n… and {} more\n", total - listed));
+    }
+    Some(out)
+}
+
+/// User-facing error messages with where each is raised, so the source of an error can be
+/// found from the pack, or `None` when the files raise none.
+pub(crate) fn render_error_index(files: &[FileContent]) -> Option<String> {
+    let mut messages = collect_reads(files, &ERROR_MESSAGE);
+    messages
+        .retain(|message, _| message.trim().len() >= 3 && message.chars().any(char::is_alphabetic));
+    if messages.is_empty() {
+        return None;
+    }
+    let total = messages.len();
+    let mut out = String::from(
+        "# Error messages\n\nError text the packed code raises or reports, and where.\n\n",
+    );
+    for (message, locations) in messages.iter().take(ERROR_MESSAGE_LIMIT) {
+        out.push_str(&format!(
+            "- \"{message}\": {}\n",
+            format_locations(locations)
+        ));
+    }
+    if total > ERROR_MESSAGE_LIMIT {
+        out.push_str(&format!("\n… and {} more\n", total - ERROR_MESSAGE_LIMIT));
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> FileContent {
+        FileContent {
+            path: path.into(),
+            content: content.into(),
+            token_count: None,
+        }
+    }
+
+    // ── config surface ───────────────────────────────────────────────────────
+
+    #[test]
+    fn env_reads_are_found_across_languages() {
+        let files = [
+            file(
+                "web/api.ts",
+                "const url = process.env.API_URL;\nconst key = process.env[\"API_KEY\"] ?? import.meta.env.VITE_MODE;",
+            ),
+            file(
+                "src/main.rs",
+                "let port = std::env::var(\"PORT\")?;\nconst V: &str = env!(\"CARGO_PKG_VERSION\");",
+            ),
+            file(
+                "app.py",
+                "debug = os.environ.get('DEBUG')\nsecret = os.environ[\"SECRET\"]\nhome = os.getenv(\"HOME\")",
+            ),
+            file("main.go", "host := os.Getenv(\"HOST\")"),
+        ];
+        let rendered = render_config_surface(&files).unwrap();
+        for (name, location) in [
+            ("API_URL", "web/api.ts:1"),
+            ("API_KEY", "web/api.ts:2"),
+            ("VITE_MODE", "web/api.ts:2"),
+            ("PORT", "src/main.rs:1"),
+            ("CARGO_PKG_VERSION", "src/main.rs:2"),
+            ("DEBUG", "app.py:1"),
+            ("SECRET", "app.py:2"),
+            ("HOME", "app.py:3"),
+            ("HOST", "main.go:1"),
+        ] {
+            assert!(
+                rendered.contains(&format!("- `{name}`: {location}\n")),
+                "{name} in {rendered}"
+            );
+        }
+        assert!(!rendered.contains("## Feature flags"));
+    }
+
+    #[test]
+    fn flag_checks_are_listed_with_every_location() {
+        let files = [
+            file(
+                "a.tsx",
+                "if (useFlag(\"new-checkout\")) {}\nclient.variation(\"beta.search\", user, false);",
+            ),
+            file("b.ts", "statsig.checkGate(user, \"new-checkout\");"),
+        ];
+        let rendered = render_config_surface(&files).unwrap();
+        assert!(rendered.contains("- `beta.search`: a.tsx:2\n"));
+        assert!(rendered.contains("- `new-checkout`: a.tsx:1, b.ts:1\n"));
+        assert!(!rendered.contains("## Environment variables"));
+    }
+
+    #[test]
+    fn long_location_lists_are_summarized_and_quiet_files_render_nothing() {
+        let content = "process.env.TOKEN;\n".repeat(LOCATIONS_PER_ENTRY + 2);
+        let rendered = render_config_surface(&[file("a.js", &content)]).unwrap();
+        assert!(rendered.contains("a.js:5 (+2 more)\n"));
+        assert_eq!(render_config_surface(&[file("a.js", "const x = 1;")]), None);
+    }
+
+    // ── todo index ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn notes_are_grouped_by_kind_with_authors_and_locations() {
+        let files = [
+            file(
+                "src/a.ts",
+                "// TODO(ana): split this module\nconst TODO_LIST = [];\n/* HACK: works around #12 */",
+            ),
+            file(
+                "app.py",
+                "x = 1  # FIXME handle None\n# todo lowercase is prose",
+            ),
+            file("index.html", "<!-- XXX remove before launch -->"),
+            file("clean.rs", "fn main() {}"),
+        ];
+        let rendered = render_todo_index(&files).unwrap();
+        assert!(rendered.contains("4 TODO/FIXME/HACK/XXX comments in 3 files."));
+        let fixme = rendered
+            .find("## FIXME\n\n- app.py:1 handle None\n")
+            .unwrap();
+        let hack = rendered
+            .find("## HACK\n\n- src/a.ts:3 works around #12\n")
+            .unwrap();
+        let xxx = rendered
+            .find("## XXX\n\n- index.html:1 remove before launch\n")
+            .unwrap();
+        let todo = rendered
+            .find("## TODO\n\n- src/a.ts:1 (ana) split this module\n")
+            .unwrap();
+        assert!(fixme < hack && hack < xxx && xxx < todo);
+        assert!(!rendered.contains("TODO_LIST"));
+    }
+
+    #[test]
+    fn notes_past_the_limit_are_counted() {
+        let content = "// TODO tidy\n".repeat(TODO_LIMIT + 3);
+        let rendered = render_todo_index(&[file("a.js", &content)]).unwrap();
+        assert_eq!(rendered.matches("- a.js:").count(), TODO_LIMIT);
+        assert!(rendered.ends_with("… and 3 more\n"));
+        assert_eq!(render_todo_index(&[file("a.js", "const x = 1;")]), None);
+    }
+
+    // ── error index ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn error_messages_are_indexed_across_languages() {
+        let files = [
+            file(
+                "src/fs.rs",
+                "return Err(format!(\"Failed to read {path}: {e}\"));\nlet v = x.expect(\"config is loaded\");\n#[error(\"invalid token\")]\nbail!(\"invalid token\");",
+            ),
+            file(
+                "web/api.ts",
+                "throw new Error(`Request failed: ${status}`);\nthrow new ValidationError(\n  \"Email is required\",\n);\nconsole.log(\"not an error\");",
+            ),
+            file(
+                "app.py",
+                "raise ValueError(f\"bad value {x}\")\nraise KeyError('id')",
+            ),
+            file("main.go", "return fmt.Errorf(\"open %s: %w\", name, err)"),
+        ];
+        let rendered = render_error_index(&files).unwrap();
+        for entry in [
+            "- \"Failed to read {path}: {e}\": src/fs.rs:1\n",
+            "- \"config is loaded\": src/fs.rs:2\n",
+            "- \"invalid token\": src/fs.rs:3, src/fs.rs:4\n",
+            "- \"Request failed: ${status}\": web/api.ts:1\n",
+            "- \"Email is required\": web/api.ts:2\n",
+            "- \"bad value {x}\": app.py:1\n",
+            "- \"open %s: %w\": main.go:1\n",
+        ] {
+            assert!(rendered.contains(entry), "{entry} in {rendered}");
+        }
+        assert!(!rendered.contains("not an error"));
+        assert!(!rendered.contains("\"id\""), "too short to be a message");
+    }
+
+    #[test]
+    fn files_without_error_text_render_no_index() {
+        assert_eq!(
+            render_error_index(&[file("a.rs", "fn main() { Err(e)?; }")]),
+            None
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::commands::fs::resolve_project_file;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Image formats that can stand in the tree as their description.
+const DESCRIBABLE_IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "bmp", "ico", "tiff",
+];
+
+/// Sidecar suffixes checked next to an image, in order: `logo.png.alt.txt`, `logo.png.alt.md`.
+const SIDECAR_SUFFIXES: &[&str] = &[".alt.txt", ".alt.md"];
+
+/// Set once at startup; only sidecar descriptions are found before then.
+static DESCRIPTION_CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep cached descriptions under `dir` (inside the app data directory), creating it if needed.
+pub fn init_description_cache(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let _ = DESCRIPTION_CACHE_DIR.set(dir.to_path_buf());
+    Ok(())
+}
+
+pub(crate) fn is_describable_image_extension(ext: &str) -> bool {
+    DESCRIBABLE_IMAGE_EXTENSIONS
+        .iter()
+        .any(|image| image.eq_ignore_ascii_case(ext))
+}
+
+/// Somewhere a textual description of an image can come from. Sources are asked in
+/// `DESCRIPTION_SOURCES` order and the first description found wins.
+trait DescriptionSource: Sync {
+    fn describe(&self, image: &Path) -> Option<String>;
+}
+
+/// Alt text the user keeps beside the image.
+struct SidecarDescriptions;
+
+impl DescriptionSource for SidecarDescriptions {
+    fn describe(&self, image: &Path) -> Option<String> {
+        let name = image.file_name()?.to_string_lossy();
+        SIDECAR_SUFFIXES.iter().find_map(|suffix| {
+            let sidecar = image.with_file_name(format!("{name}{suffix}"));
+            read_description(&sidecar)
+        })
+    }
+}
+
+/// Descriptions saved through `set_image_description`, keyed by the image's canonical path.
+struct CachedDescriptions;
+
+impl CachedDescriptions {
+    fn entry(image: &Path) -> Option<PathBuf> {
+        let dir = DESCRIPTION_CACHE_DIR.get()?;
+        let canonical = std::fs::canonicalize(image).ok()?;
+        let hash = blake3::hash(canonical.to_string_lossy().as_bytes());
+        Some(dir.join(format!("{}.txt", &hash.to_hex()[..32])))
+    }
+}
+
+impl DescriptionSource for CachedDescriptions {
+    fn describe(&self, image: &Path) -> Option<String> {
+        read_description(&Self::entry(image)?)
+    }
+}
+
+const DESCRIPTION_SOURCES: &[&dyn DescriptionSource] = &[&SidecarDescriptions, &CachedDescriptions];
+
+fn read_description(path: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// The first description any source has for `image`.
+pub(crate) fn find_image_description(image: &Path) -> Option<String> {
+    DESCRIPTION_SOURCES
+        .iter()
+        .find_map(|source| source.describe(image))
+}
+
+/// What an image reads as in the tree and in packs: a header naming the asset, then its
+/// description.
+pub(crate) fn describe_image(image: &Path) -> std::io::Result<String> {
+    let description = find_image_description(image).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No description for image: {}", image.display()),
+        )
+    })?;
+    let name = image.file_name().unwrap_or_default().to_string_lossy();
+    let size = std::fs::metadata(image)?.len();
+    Ok(format!(
+        "# Image `{name}` ({size} bytes); binary content replaced by its description.\n\n{description}\n"
+    ))
+}
+
+/// Save (or, with an empty `description`, forget) the cached description of the image at
+/// `path`, so it appears in the tree and packs without a sidecar file.
+#[tauri::command]
+pub async fn set_image_description(path: String, description: String) -> Result<(), String> {
+    let image = resolve_project_file(&path)?;
+    let ext = image.extension().unwrap_or_default().to_string_lossy();
+    if !is_describable_image_extension(&ext) {
+        return Err(format!("Not an image: {path}"));
+    }
+    let entry = CachedDescriptions::entry(&image)
+        .ok_or_else(|| "Description cache is not initialized".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let description = description.trim();
+        if description.is_empty() {
+            match std::fs::remove_file(&entry) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            }
+        } else {
+            std::fs::write(&entry, description).map_err(|e| e.to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bablusheed-{label}-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // ── description sources ──
+
+    #[test]
+    fn sidecar_alt_text_describes_the_image() {
+        let dir = temp_dir("assets-sidecar");
+        let image = dir.join("logo.png");
+        std::fs::write(&image, [0x89, b'P', b'N', b'G', 0]).unwrap();
+        assert_eq!(find_image_description(&image), None);
+        assert!(describe_image(&image).is_err());
+
+        std::fs::write(dir.join("logo.png.alt.md"), "  Blue wordmark on white.\n").unwrap();
+        let text = describe_image(&image).unwrap();
+        assert!(text.starts_with("# Image `logo.png` (5 bytes); binary content replaced"));
+        assert!(text.ends_with("\n\nBlue wordmark on white.\n"));
+
+        std::fs::write(dir.join("logo.png.alt.txt"), "Preferred text sidecar").unwrap();
+        assert_eq!(
+            find_image_description(&image).as_deref(),
+            Some("Preferred text sidecar")
+        );
+    }
+
+    #[test]
+    fn cached_descriptions_are_keyed_by_canonical_path() {
+        let cache = temp_dir("assets-cache");
+        init_description_cache(&cache).unwrap();
+        let dir = temp_dir("assets-cached-project");
+        let image = dir.join("hero.jpg");
+        std::fs::write(&image, [0xff, 0xd8, 0]).unwrap();
+
+        let entry = CachedDescriptions::entry(&image).unwrap();
+        std::fs::write(&entry, "Team photo in the office").unwrap();
+        assert_eq!(
+            find_image_description(&dir.join(".").join("hero.jpg")).as_deref(),
+            Some("Team photo in the office")
+        );
+    }
+
+    #[test]
+    fn only_raster_images_are_describable() {
+        assert!(is_describable_image_extension("PNG"));
+        assert!(is_describable_image_extension("webp"));
+        assert!(!is_describable_image_extension("svg"));
+        assert!(!is_describable_image_extension("pdf"));
+    }
+}
@@ -1,5 +1,8 @@
-use crate::models::{FileContent, ReachabilityResult};
+use crate::commands::languages::{associated_language, grammar_extension};
+use crate::commands::pack::{estimate_tokens_for_path, module_specifier_candidates};
+use crate::models::{DeadExport, FileContent, FileSymbolTokens, ParseHealth, ReachabilityOptions, ReachabilityResult, SymbolTokens};
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
 use tree_sitter::{Node, Parser};
 
 fn get_language(extension: &str) -> Option<tree_sitter::Language> {
@@ -14,99 +17,343 @@ fn get_language(extension: &str) -> Option<tree_sitter::Language> {
     }
 }
 
+/// The extension picking `path`'s grammar: that of the language the user associated with it,
+/// if it has a grammar, else the path's own.
 fn get_extension(path: &str) -> &str {
+    if let Some(extension) = associated_language(path).as_deref().and_then(grammar_extension) {
+        return extension;
+    }
     std::path::Path::new(path)
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
 }
 
-/// Extract top-level symbol names from a parsed AST
-fn extract_symbols(source: &[u8], tree: &tree_sitter::Tree) -> Vec<String> {
+fn failed_parse_health() -> ParseHealth {
+    ParseHealth {
+        status: "failed".to_string(),
+        error_count: 0,
+        first_error_line: None,
+    }
+}
+
+/// Count ERROR and MISSING nodes in a parsed tree; `None` when it parsed cleanly.
+fn tree_parse_health(tree: &tree_sitter::Tree) -> Option<ParseHealth> {
     let root = tree.root_node();
-    let mut symbols = Vec::new();
-    extract_symbols_from_node(root, source, 0, &mut symbols);
-    symbols
+    if !root.has_error() {
+        return None;
+    }
+
+    let mut error_count = 0;
+    let mut first_error_line: Option<usize> = None;
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.is_error() || node.is_missing() {
+            error_count += 1;
+            let line = node.start_position().row + 1;
+            first_error_line = Some(first_error_line.map_or(line, |first| first.min(line)));
+        }
+        // Only subtrees that contain errors are worth descending into.
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor).filter(|child| child.has_error()));
+    }
+
+    Some(ParseHealth {
+        status: "partial".to_string(),
+        error_count,
+        first_error_line,
+    })
 }
 
-fn node_text<'a>(node: Node, source: &'a [u8]) -> &'a str {
-    node.utf8_text(source).unwrap_or("")
+/// Parse `content` with the grammar for `path` and report problems; `None` for clean
+/// parses and for languages without a grammar.
+pub(crate) fn check_parse_health(path: &str, content: &str) -> Option<ParseHealth> {
+    let language = get_language(get_extension(path))?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    match parser.parse(content.as_bytes(), None) {
+        Some(tree) => tree_parse_health(&tree),
+        None => Some(failed_parse_health()),
+    }
 }
 
-fn extract_symbols_from_node(
-    node: Node,
-    source: &[u8],
-    depth: usize,
-    symbols: &mut Vec<String>,
-) {
-    if depth > 2 {
+/// Node kinds that add a branch to a function's control flow, across supported grammars.
+const DECISION_NODE_KINDS: &[&str] = &[
+    "if_statement",
+    "if_expression",
+    "elif_clause",
+    "for_statement",
+    "for_in_statement",
+    "for_expression",
+    "while_statement",
+    "while_expression",
+    "loop_expression",
+    "do_statement",
+    "switch_case",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "case_clause",
+    "match_arm",
+    "catch_clause",
+    "except_clause",
+    "ternary_expression",
+    "conditional_expression",
+    "boolean_operator",
+];
+
+fn count_decision_points(node: Node, source: &[u8]) -> usize {
+    let is_short_circuit = node.kind() == "binary_expression"
+        && node
+            .child_by_field_name("operator")
+            .map(|op| matches!(node_text(op, source), "&&" | "||" | "??"))
+            .unwrap_or(false);
+    let own = usize::from(DECISION_NODE_KINDS.contains(&node.kind()) || is_short_circuit);
+    let mut cursor = node.walk();
+    let nested: usize = node
+        .children(&mut cursor)
+        .map(|child| count_decision_points(child, source))
+        .sum();
+    own + nested
+}
+
+/// Whole-file cyclomatic complexity (1 + decision points); `None` without a grammar.
+pub(crate) fn cyclomatic_complexity(path: &str, content: &str) -> Option<usize> {
+    let language = get_language(get_extension(path))?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content.as_bytes(), None)?;
+    Some(1 + count_decision_points(tree.root_node(), content.as_bytes()))
+}
+
+/// Comment node kinds across supported grammars.
+const COMMENT_NODE_KINDS: &[&str] = &["comment", "line_comment", "block_comment"];
+
+/// Comments that change how a file is read or built: shebangs and Go build directives.
+fn is_directive_comment(text: &str) -> bool {
+    text.starts_with("#!") || text.starts_with("//go:") || text.starts_with("// +build")
+}
+
+fn collect_comment_ranges(node: Node, source: &[u8], ranges: &mut Vec<std::ops::Range<usize>>) {
+    if COMMENT_NODE_KINDS.contains(&node.kind()) {
+        let text = node_text(node, source);
+        if !is_directive_comment(text) {
+            // Some grammars end line comments on the newline; the line itself stays.
+            let end = node.start_byte() + text.trim_end().len();
+            ranges.push(node.start_byte()..end);
+        }
         return;
     }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_comment_ranges(child, source, ranges);
+    }
+}
 
-    match node.kind() {
-        // JavaScript/TypeScript
-        "function" => {
-            if let Some(name_node) = node.child_by_field_name("name") {
-                symbols.push(node_text(name_node, source).to_string());
-            }
+/// Remove line and block comments with the grammar for `path`. Lines left blank by a removal
+/// go too. `None` without a grammar, when the file doesn't parse cleanly, or without comments.
+pub(crate) fn strip_comments(path: &str, content: &str) -> Option<String> {
+    let language = get_language(get_extension(path))?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content.as_bytes(), None)?;
+    if tree.root_node().has_error() {
+        return None;
+    }
+    let mut ranges = Vec::new();
+    collect_comment_ranges(tree.root_node(), content.as_bytes(), &mut ranges);
+    if ranges.is_empty() {
+        return None;
+    }
+
+    let mut stripped = String::with_capacity(content.len());
+    let mut kept_from = 0;
+    for range in ranges {
+        let line_start = content[..range.start].rfind('\n').map_or(0, |nl| nl + 1);
+        let line_end = content[range.end..].find('\n').map_or(content.len(), |nl| range.end + nl);
+        let before = &content[line_start.max(kept_from)..range.start];
+        let after = &content[range.end..line_end];
+        if line_start >= kept_from && before.trim().is_empty() && after.trim().is_empty() {
+            // The comment fills its lines: drop them with their newline.
+            stripped.push_str(&content[kept_from..line_start]);
+            kept_from = (line_end + 1).min(content.len());
+        } else {
+            stripped.push_str(content[kept_from..range.start].trim_end_matches([' ', '\t']));
+            kept_from = range.end;
         }
-        "class_declaration" | "class" => {
-            if let Some(name_node) = node.child_by_field_name("name") {
-                symbols.push(node_text(name_node, source).to_string());
-            }
+    }
+    stripped.push_str(&content[kept_from..]);
+    Some(stripped)
+}
+
+/// Functions and methods whose bodies an outline elides.
+const OUTLINED_FUNCTION_KINDS: &[&str] = &[
+    "function_declaration",
+    "generator_function_declaration",
+    "function_expression",
+    "function",
+    "arrow_function",
+    "method_definition",
+    "function_item",
+    "function_definition",
+    "method_declaration",
+    "func_literal",
+];
+
+/// Body ranges an outline replaces, with their replacements. Braced bodies become `{ ... }`;
+/// Python bodies keep their docstring and become `...`. Expression-bodied arrow functions stay.
+fn collect_elided_bodies(node: Node, source: &[u8], python: bool, elisions: &mut Vec<(std::ops::Range<usize>, String)>) {
+    if OUTLINED_FUNCTION_KINDS.contains(&node.kind())
+        && let Some(body) = node.child_by_field_name("body")
+    {
+        if node_text(body, source).starts_with('{') {
+            elisions.push((body.byte_range(), "{ ... }".to_string()));
+            return;
         }
-        "lexical_declaration" | "variable_declaration" => {
-            // const foo = ... or let foo = ...
-            let mut cursor = node.walk();
-            for child in node.children(&mut cursor) {
-                if child.kind() == "variable_declarator" {
-                    if let Some(name_node) = child.child_by_field_name("name") {
-                        symbols.push(node_text(name_node, source).to_string());
-                    }
+        if python {
+            let docstring = body
+                .named_child(0)
+                .filter(|first| first.kind() == "expression_statement")
+                .filter(|first| first.named_child(0).is_some_and(|value| value.kind() == "string"));
+            match docstring {
+                Some(docstring) if docstring.end_byte() < body.end_byte() => {
+                    let indent = " ".repeat(docstring.start_position().column);
+                    elisions.push((docstring.end_byte()..body.end_byte(), format!("\n{indent}...")));
                 }
+                Some(_) => {}
+                None => elisions.push((body.byte_range(), "...".to_string())),
             }
+            return;
         }
-        "export_statement" => {
-            let mut cursor = node.walk();
-            for child in node.children(&mut cursor) {
-                extract_symbols_from_node(child, source, depth + 1, symbols);
-            }
-        }
-        // Python
-        "function_definition" => {
-            if let Some(name_node) = node.child_by_field_name("name") {
-                symbols.push(node_text(name_node, source).to_string());
-            }
-        }
-        "class_definition" => {
-            if let Some(name_node) = node.child_by_field_name("name") {
-                symbols.push(node_text(name_node, source).to_string());
-            }
-        }
-        // Rust
-        "function_item" | "impl_item" | "struct_item" | "enum_item" | "trait_item" => {
-            if let Some(name_node) = node.child_by_field_name("name") {
-                symbols.push(node_text(name_node, source).to_string());
-            }
-        }
-        // Go
-        "function_declaration" | "method_declaration" | "type_declaration" => {
-            if let Some(name_node) = node.child_by_field_name("name") {
-                symbols.push(node_text(name_node, source).to_string());
-            }
-        }
-        _ => {}
     }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_elided_bodies(child, source, python, elisions);
+    }
+}
 
-    // Recurse for program/module top level
-    if matches!(node.kind(), "program" | "module" | "source_file" | "translation_unit") {
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            extract_symbols_from_node(child, source, depth, symbols);
-        }
+/// `content` with function and method bodies elided, keeping signatures, doc comments, types,
+/// and top-level statements. `None` without a grammar or when nothing has a body to elide.
+pub(crate) fn outline_source(path: &str, content: &str) -> Option<String> {
+    let ext = get_extension(path);
+    let language = get_language(ext)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content.as_bytes(), None)?;
+    let mut elisions = Vec::new();
+    collect_elided_bodies(tree.root_node(), content.as_bytes(), ext == "py", &mut elisions);
+    if elisions.is_empty() {
+        return None;
+    }
+
+    let mut outline = String::with_capacity(content.len() / 2);
+    let mut kept_from = 0;
+    for (range, replacement) in elisions {
+        outline.push_str(&content[kept_from..range.start]);
+        outline.push_str(&replacement);
+        kept_from = range.end;
+    }
+    outline.push_str(&content[kept_from..]);
+    Some(outline)
+}
+
+/// A symbol is identified by file, name, and kind so `User` the type and `User`
+/// the variable (or two `User`s in different files) never collide.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SymbolId {
+    file: String,
+    name: String,
+    kind: &'static str,
+}
+
+/// Per-symbol facts extracted from a single parsed file.
+struct ExtractedSymbol {
+    name: String,
+    kind: &'static str,
+    refs: HashSet<String>,
+    dynamic_imports: Vec<String>,
+    decorators: Vec<String>,
+    /// Paths of the Rust attributes on the item, e.g. `tauri::command`, `derive`.
+    attributes: Vec<String>,
+    /// 1-based line range of the declaration, decorators and export keyword included.
+    start_line: usize,
+    end_line: usize,
+    byte_range: std::ops::Range<usize>,
+    /// Rust `pub` items; other languages decide visibility from names or export statements.
+    public: bool,
+}
+
+fn symbol_kind(node_kind: &str) -> &'static str {
+    match node_kind {
+        "function" | "function_declaration" | "function_definition" | "function_item" => "function",
+        "method_declaration" => "method",
+        "class" | "class_declaration" | "class_definition" => "class",
+        "variable_declarator" => "variable",
+        "struct_item" => "struct",
+        "enum_item" => "enum",
+        "trait_item" => "trait",
+        "impl_item" => "impl",
+        _ => "type",
     }
 }
 
+/// Kind of a value bound by `const x = ...` or `export default ...`.
+fn value_kind(value_kind: &str) -> &'static str {
+    match value_kind {
+        "arrow_function" | "function_expression" | "function" | "generator_function" => "function",
+        "class" => "class",
+        _ => "variable",
+    }
+}
+
+fn is_default_export(node: Node) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|child| child.kind() == "default")
+}
+
+/// Extract symbols from a parsed AST, with the identifiers each one references
+fn extract_symbols(source: &[u8], tree: &tree_sitter::Tree, max_depth: usize) -> Vec<ExtractedSymbol> {
+    let mut symbols = Vec::new();
+    for_each_symbol_scope(tree.root_node(), source, 0, max_depth, "", &mut |name, kind, scope_node| {
+        let mut refs = HashSet::new();
+        collect_references(scope_node, source, &mut refs);
+        if let Some((owner, _)) = name.rsplit_once("::") {
+            refs = refs
+                .into_iter()
+                .map(|r| match r.strip_prefix("Self::") {
+                    Some(member) => format!("{owner}::{member}"),
+                    None => r,
+                })
+                .collect();
+        }
+        refs.remove(&name);
+        let mut dynamic_imports = Vec::new();
+        collect_dynamic_imports(scope_node, source, &mut dynamic_imports);
+        symbols.push(ExtractedSymbol {
+            name,
+            kind,
+            refs,
+            dynamic_imports,
+            decorators: decorator_names(scope_node, source),
+            attributes: attribute_paths(scope_node, source),
+            start_line: scope_node.start_position().row + 1,
+            end_line: scope_node.end_position().row + 1,
+            byte_range: scope_node.byte_range(),
+            public: has_visibility_modifier(scope_node),
+        });
+    });
+    symbols
+}
+
+fn has_visibility_modifier(node: Node) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|child| child.kind() == "visibility_modifier")
+}
+
+fn node_text<'a>(node: Node, source: &'a [u8]) -> &'a str {
+    node.utf8_text(source).unwrap_or("")
+}
+
 /// Find all identifier references in a node (for call graph building)
 fn collect_references(node: Node, source: &[u8], refs: &mut HashSet<String>) {
     if node.kind() == "identifier"
@@ -122,40 +369,89 @@ fn collect_references(node: Node, source: &[u8], refs: &mut HashSet<String>) {
             refs.insert(name.to_string());
         }
     }
+    // Rust `Type::method` paths, so calls resolve to the exact impl method.
+    if node.kind() == "scoped_identifier" {
+        let path = node.child_by_field_name("path").filter(|p| p.kind() == "identifier");
+        if let (Some(path), Some(name)) = (path, node.child_by_field_name("name")) {
+            refs.insert(format!("{}::{}", node_text(path, source), node_text(name, source)));
+        }
+    }
+    // TS namespace members: `NS.helper`, so calls resolve to the qualified member.
+    if node.kind() == "member_expression" {
+        let object = node.child_by_field_name("object").filter(|o| o.kind() == "identifier");
+        let property = node
+            .child_by_field_name("property")
+            .filter(|p| p.kind() == "property_identifier");
+        if let (Some(object), Some(property)) = (object, property) {
+            refs.insert(format!("{}.{}", node_text(object, source), node_text(property, source)));
+        }
+    }
+    // Method names in call position: `value.method()` (Rust) and `pkg.Func()` (Go).
+    if node.kind() == "field_identifier" && is_called_field(node) {
+        refs.insert(node_text(node, source).to_string());
+    }
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         collect_references(child, source, refs);
     }
 }
 
-fn collect_symbol_references(source: &[u8], tree: &tree_sitter::Tree) -> HashMap<String, HashSet<String>> {
-    let mut map: HashMap<String, HashSet<String>> = HashMap::new();
-    collect_symbol_references_from_node(tree.root_node(), source, 0, &mut map);
-    map
+fn is_called_field(field: Node) -> bool {
+    let Some(access) = field.parent() else {
+        return false;
+    };
+    if !matches!(access.kind(), "field_expression" | "selector_expression") {
+        return false;
+    }
+    access
+        .parent()
+        .filter(|call| call.kind() == "call_expression")
+        .and_then(|call| call.child_by_field_name("function"))
+        .map(|function| function.id() == access.id())
+        .unwrap_or(false)
 }
 
-fn insert_symbol_references(
-    symbol_refs: &mut HashMap<String, HashSet<String>>,
-    source: &[u8],
-    name: String,
-    scope_node: Node,
-) {
-    let mut refs = HashSet::new();
-    collect_references(scope_node, source, &mut refs);
-    refs.remove(&name);
-    symbol_refs
-        .entry(name)
-        .or_default()
-        .extend(refs.into_iter());
+/// Base name of an impl target, e.g. `Config<T>` -> `Config`.
+fn rust_type_name(type_node: Node, source: &[u8]) -> String {
+    let base = if type_node.kind() == "generic_type" {
+        type_node.child_by_field_name("type").unwrap_or(type_node)
+    } else {
+        type_node
+    };
+    node_text(base, source).to_string()
+}
+
+/// Languages whose idioms nest declarations deeper than `maxDepth` usually reaches: Rust
+/// inline `mod` trees (`mod tests { mod fixtures { ... } }`) and TS declaration-file
+/// namespaces (`declare namespace Express { namespace Multer { ... } }`).
+const LANGUAGE_MAX_DEPTHS: &[(&str, usize)] = &[("rs", 6), ("ts", 6), ("tsx", 6), ("mts", 6), ("cts", 6)];
+
+/// Nesting depth searched for declarations in files with extension `ext`: an explicit
+/// `maxDepthByLanguage` entry wins, otherwise `maxDepth` raised to the language default.
+fn symbol_depth(options: &ReachabilityOptions, ext: &str) -> usize {
+    let ext = ext.to_ascii_lowercase();
+    if let Some(&depth) = options.max_depth_by_language.get(&ext) {
+        return depth;
+    }
+    LANGUAGE_MAX_DEPTHS
+        .iter()
+        .find(|(language, _)| *language == ext)
+        .map_or(options.max_depth, |&(_, depth)| depth.max(options.max_depth))
 }
 
-fn collect_symbol_references_from_node(
-    node: Node,
+/// Visit each declared symbol together with its kind and the node that scopes its body.
+///
+/// Declarations nested in containers (`mod a {}`, `namespace NS {}`, Python classes) are
+/// qualified by `prefix` (`a::f`, `NS.f`, `Outer.method`) and searched up to `max_depth`.
+fn for_each_symbol_scope<'tree>(
+    node: Node<'tree>,
     source: &[u8],
     depth: usize,
-    symbol_refs: &mut HashMap<String, HashSet<String>>,
+    max_depth: usize,
+    prefix: &str,
+    visit: &mut dyn FnMut(String, &'static str, Node<'tree>),
 ) {
-    if depth > 2 {
+    if depth > max_depth {
         return;
     }
 
@@ -165,17 +461,63 @@ fn collect_symbol_references_from_node(
         | "class_declaration"
         | "class"
         | "function_definition"
-        | "class_definition"
         | "function_item"
-        | "impl_item"
         | "struct_item"
         | "enum_item"
         | "trait_item"
         | "method_declaration"
         | "type_declaration" => {
             if let Some(name_node) = node.child_by_field_name("name") {
-                let name = node_text(name_node, source).to_string();
-                insert_symbol_references(symbol_refs, source, name, node);
+                visit(
+                    format!("{prefix}{}", node_text(name_node, source)),
+                    symbol_kind(node.kind()),
+                    node,
+                );
+            }
+        }
+        "class_definition" => {
+            visit_python_class(node, node, source, depth, max_depth, prefix, visit);
+        }
+        // Rust: methods are qualified by their impl target (`Config::new`). Trait impl
+        // methods are called implicitly, so they are tagged to follow their type.
+        "impl_item" => {
+            let type_name = node
+                .child_by_field_name("type")
+                .map(|type_node| rust_type_name(type_node, source));
+            let kind = if node.child_by_field_name("trait").is_some() {
+                "trait_method"
+            } else {
+                "method"
+            };
+            if let (Some(type_name), Some(body)) = (type_name, node.child_by_field_name("body")) {
+                let mut cursor = body.walk();
+                for item in body.children(&mut cursor).filter(|c| c.kind() == "function_item") {
+                    if let Some(name_node) = item.child_by_field_name("name") {
+                        visit(
+                            format!("{prefix}{type_name}::{}", node_text(name_node, source)),
+                            kind,
+                            item,
+                        );
+                    }
+                }
+            }
+        }
+        // Rust `mod a { ... }` and TS `namespace A.B { ... }` only group declarations.
+        "mod_item" | "internal_module" => {
+            let separator = if node.kind() == "mod_item" { "::" } else { "." };
+            let name_node = node.child_by_field_name("name");
+            if let (Some(name_node), Some(body)) = (name_node, node.child_by_field_name("body")) {
+                let nested_prefix = format!("{prefix}{}{separator}", node_text(name_node, source));
+                let mut cursor = body.walk();
+                for child in body.children(&mut cursor) {
+                    for_each_symbol_scope(child, source, depth + 1, max_depth, &nested_prefix, visit);
+                }
+            }
+        }
+        // A top-level `namespace NS {}` parses as an expression statement.
+        "expression_statement" => {
+            if let Some(module) = node.named_child(0).filter(|c| c.kind() == "internal_module") {
+                for_each_symbol_scope(module, source, depth, max_depth, prefix, visit);
             }
         }
         "lexical_declaration" | "variable_declaration" => {
@@ -183,16 +525,53 @@ fn collect_symbol_references_from_node(
             for child in node.children(&mut cursor) {
                 if child.kind() == "variable_declarator" {
                     if let Some(name_node) = child.child_by_field_name("name") {
-                        let name = node_text(name_node, source).to_string();
-                        insert_symbol_references(symbol_refs, source, name, child);
+                        let kind = child
+                            .child_by_field_name("value")
+                            .map(|value| value_kind(value.kind()))
+                            .unwrap_or("variable");
+                        visit(format!("{prefix}{}", node_text(name_node, source)), kind, child);
                     }
                 }
             }
         }
         "export_statement" => {
-            let mut cursor = node.walk();
-            for child in node.children(&mut cursor) {
-                collect_symbol_references_from_node(child, source, depth + 1, symbol_refs);
+            // `@Dec() export class Foo {}` keeps its decorators on the export node, so
+            // scope the class to the whole statement to keep decorator references.
+            let decorated_class = node
+                .child_by_field_name("declaration")
+                .filter(|decl| matches!(decl.kind(), "class_declaration" | "class"))
+                .filter(|_| has_decorators(node));
+            // `export default function() {}`, `export default {...}`, `export default memo(App)`
+            // have no declared name, so they are tracked under the synthetic name `default`.
+            let anonymous_default = node
+                .child_by_field_name("value")
+                .filter(|value| value.kind() != "identifier");
+            if let Some(name_node) = decorated_class.and_then(|decl| decl.child_by_field_name("name")) {
+                visit(format!("{prefix}{}", node_text(name_node, source)), "class", node);
+            } else if let Some(value) = anonymous_default {
+                visit(format!("{prefix}default"), value_kind(value.kind()), node);
+            } else {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    for_each_symbol_scope(child, source, depth, max_depth, prefix, visit);
+                }
+            }
+        }
+        "decorated_definition" => {
+            let definition = node.child_by_field_name("definition");
+            let name_node = definition.and_then(|definition| definition.child_by_field_name("name"));
+            match (definition, name_node) {
+                (Some(definition), _) if definition.kind() == "class_definition" => {
+                    visit_python_class(definition, node, source, depth, max_depth, prefix, visit);
+                }
+                (Some(definition), Some(name_node)) => {
+                    visit(
+                        format!("{prefix}{}", node_text(name_node, source)),
+                        symbol_kind(definition.kind()),
+                        node,
+                    );
+                }
+                _ => {}
             }
         }
         _ => {}
@@ -201,9 +580,242 @@ fn collect_symbol_references_from_node(
     if matches!(node.kind(), "program" | "module" | "source_file" | "translation_unit") {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            collect_symbol_references_from_node(child, source, depth, symbol_refs);
+            for_each_symbol_scope(child, source, depth, max_depth, prefix, visit);
+        }
+    }
+}
+
+/// Visit a Python class (scoped to `scope_node`, which includes any decorators) and
+/// then its methods and nested classes as `Class.member`.
+fn visit_python_class<'tree>(
+    class_node: Node<'tree>,
+    scope_node: Node<'tree>,
+    source: &[u8],
+    depth: usize,
+    max_depth: usize,
+    prefix: &str,
+    visit: &mut dyn FnMut(String, &'static str, Node<'tree>),
+) {
+    let Some(name_node) = class_node.child_by_field_name("name") else {
+        return;
+    };
+    let class_name = format!("{prefix}{}", node_text(name_node, source));
+    visit(class_name.clone(), "class", scope_node);
+
+    let Some(body) = class_node.child_by_field_name("body") else {
+        return;
+    };
+    if depth + 1 > max_depth {
+        return;
+    }
+    let member_prefix = format!("{class_name}.");
+    let mut cursor = body.walk();
+    for child in body.children(&mut cursor) {
+        let definition = match child.kind() {
+            "decorated_definition" => child.child_by_field_name("definition"),
+            _ => Some(child),
+        };
+        match definition.map(|definition| definition.kind()) {
+            Some("function_definition") => {
+                if let Some(method_name) = definition.and_then(|d| d.child_by_field_name("name")) {
+                    visit(
+                        format!("{member_prefix}{}", node_text(method_name, source)),
+                        "method",
+                        child,
+                    );
+                }
+            }
+            Some("class_definition") => {
+                for_each_symbol_scope(child, source, depth + 1, max_depth, &member_prefix, visit);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Decorators whose classes act as DI module definitions (NestJS, Angular).
+const DI_MODULE_DECORATORS: &[&str] = &["Module", "NgModule"];
+
+fn has_decorators(node: Node) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|child| child.kind() == "decorator")
+}
+
+/// Bare names of the decorators attached to a symbol scope, e.g. `@app.get("/")` -> `get`.
+fn decorator_names(scope_node: Node, source: &[u8]) -> Vec<String> {
+    let mut holders = vec![scope_node];
+    if let Some(inner) = scope_node
+        .child_by_field_name("declaration")
+        .or_else(|| scope_node.child_by_field_name("definition"))
+    {
+        holders.push(inner);
+    }
+
+    let mut names = Vec::new();
+    for holder in holders {
+        let mut cursor = holder.walk();
+        for decorator in holder.children(&mut cursor).filter(|c| c.kind() == "decorator") {
+            let Some(mut target) = decorator.named_child(0) else {
+                continue;
+            };
+            if matches!(target.kind(), "call_expression" | "call") {
+                match target.child_by_field_name("function") {
+                    Some(function) => target = function,
+                    None => continue,
+                }
+            }
+            let name = match target.kind() {
+                "member_expression" => target.child_by_field_name("property"),
+                "attribute" => target.child_by_field_name("attribute"),
+                _ => Some(target),
+            };
+            if let Some(name) = name {
+                names.push(node_text(name, source).to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Paths of the Rust outer attributes (`#[tauri::command]` -> `tauri::command`) on an item.
+/// Tree-sitter parses them as siblings preceding the item, possibly with comments between.
+fn attribute_paths(scope_node: Node, source: &[u8]) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut sibling = scope_node.prev_named_sibling();
+    while let Some(node) = sibling {
+        match node.kind() {
+            "attribute_item" => {
+                if let Some(path) = node.named_child(0).and_then(|attribute| attribute.named_child(0)) {
+                    paths.push(node_text(path, source).to_string());
+                }
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        sibling = node.prev_named_sibling();
+    }
+    paths.reverse();
+    paths
+}
+
+/// Whether one of `attributes` is configured as a root: paths match exactly or when one is
+/// a `::` suffix of the other, so `command` and `tauri::command` find each other.
+fn has_root_attribute(attributes: &[String], root_attributes: &[String]) -> bool {
+    let suffix_of = |short: &str, long: &str| long.strip_suffix(short).is_some_and(|rest| rest.ends_with("::"));
+    attributes.iter().any(|attribute| {
+        root_attributes
+            .iter()
+            .any(|root| attribute == root || suffix_of(root, attribute) || suffix_of(attribute, root))
+    })
+}
+
+/// Whether the item is a Tauri IPC command (`#[tauri::command]`, or `#[command]` when imported).
+fn is_tauri_command(attributes: &[String]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| matches!(attribute.as_str(), "tauri::command" | "command"))
+}
+
+/// Bare names of the commands registered through `generate_handler![a, commands::fs::b]`:
+/// the last path segment of each comma-separated entry.
+fn extract_registered_commands(source: &[u8], tree: &tree_sitter::Tree) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let is_handler = node.kind() == "macro_invocation"
+            && node
+                .child_by_field_name("macro")
+                .is_some_and(|name| node_text(name, source).ends_with("generate_handler"));
+        if !is_handler {
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+            continue;
+        }
+        let Some(tokens) = node.named_children(&mut node.walk()).find(|child| child.kind() == "token_tree") else {
+            continue;
+        };
+        let mut last = None;
+        let mut cursor = tokens.walk();
+        for token in tokens.children(&mut cursor) {
+            match token.kind() {
+                "identifier" => last = Some(node_text(token, source)),
+                "," => commands.extend(last.take().map(str::to_string)),
+                _ => {}
+            }
+        }
+        commands.extend(last.map(str::to_string));
+    }
+    commands
+}
+
+/// Whether a DI container or framework registry instantiates the symbol without any
+/// visible reference, according to the configured heuristic:
+/// - `"modules"`: classes decorated with a module definition (`@Module`, `@NgModule`)
+/// - `"decorated"`: every decorated class or function (route handlers, components, services)
+/// - `"off"`: never
+fn is_di_root(decorators: &[String], heuristic: &str) -> bool {
+    match heuristic {
+        "off" => false,
+        "decorated" => !decorators.is_empty(),
+        _ => decorators
+            .iter()
+            .any(|decorator| DI_MODULE_DECORATORS.contains(&decorator.as_str())),
+    }
+}
+
+fn unquote_string_literal(raw: &str) -> Option<&str> {
+    let raw = raw.trim();
+    if raw.len() < 2 {
+        return None;
+    }
+    let quote = raw.chars().next()?;
+    if !matches!(quote, '"' | '\'' | '`') || !raw.ends_with(quote) {
+        return None;
+    }
+    let inner = &raw[1..raw.len() - 1];
+    if quote == '`' && inner.contains("${") {
+        return None;
+    }
+    Some(inner)
+}
+
+/// Collect specifiers of `import('...')` expressions (including those wrapped by
+/// `React.lazy`, `loadable`, or router `component: () => import(...)` entries).
+fn collect_dynamic_imports(node: Node, source: &[u8], specifiers: &mut Vec<String>) {
+    if node.kind() == "call_expression" {
+        let is_import = node
+            .child_by_field_name("function")
+            .map(|f| f.kind() == "import")
+            .unwrap_or(false);
+        if is_import {
+            let literal = node
+                .child_by_field_name("arguments")
+                .and_then(|args| args.named_child(0))
+                .filter(|arg| matches!(arg.kind(), "string" | "template_string"))
+                .and_then(|arg| unquote_string_literal(node_text(arg, source)))
+                .filter(|specifier| !specifier.is_empty());
+            if let Some(specifier) = literal {
+                specifiers.push(specifier.to_string());
+            }
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_dynamic_imports(child, source, specifiers);
+    }
+}
+
+/// Names declared inside top-level `export` statements.
+fn extract_exported_symbols(source: &[u8], tree: &tree_sitter::Tree, max_depth: usize) -> Vec<String> {
+    let root = tree.root_node();
+    let mut names = Vec::new();
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if child.kind() == "export_statement" {
+            for_each_symbol_scope(child, source, 0, max_depth, "", &mut |name, _, _| names.push(name));
         }
     }
+    names
 }
 
 fn parse_import_clause_bindings(clause_text: &str) -> Vec<(String, String)> {
@@ -213,18 +825,16 @@ fn parse_import_clause_bindings(clause_text: &str) -> Vec<(String, String)> {
         return bindings;
     }
 
-    let mut parts = text.splitn(2, ',');
-    let first = parts.next().unwrap_or("").trim();
-    let second = parts.next().map(str::trim);
-
-    if !first.is_empty() && !first.starts_with('{') && !first.starts_with('*') {
-        bindings.push((first.to_string(), "default".to_string()));
-    }
-
-    let named_segment = if first.starts_with('{') || first.starts_with('*') {
-        Some(first)
+    // Only a default binding can precede the comma; `{ a, b }` is one named segment.
+    let named_segment = if text.starts_with('{') || text.starts_with('*') {
+        Some(text)
     } else {
-        second
+        let mut parts = text.splitn(2, ',');
+        let first = parts.next().unwrap_or("").trim();
+        if !first.is_empty() {
+            bindings.push((first.to_string(), "default".to_string()));
+        }
+        parts.next().map(str::trim)
     };
 
     if let Some(segment) = named_segment {
@@ -367,165 +977,645 @@ fn resolve_import_target_file(
         .find(|candidate| known_files.contains(candidate))
 }
 
-fn extract_default_export_symbol(source: &str) -> Option<String> {
-    for line in source.lines() {
-        let trimmed = line.trim_start();
-        let Some(after) = trimmed.strip_prefix("export default ") else {
-            continue;
-        };
-        let without_comment = after.split("//").next().unwrap_or("").trim();
-        let expression = without_comment.trim_end_matches(';').trim();
-        if expression.is_empty() {
-            continue;
-        }
-        if expression.starts_with("function ") || expression.starts_with("class ") {
-            continue;
-        }
-        let token = expression
-            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
-            .next()
-            .unwrap_or("");
-        if token.is_empty() {
-            continue;
-        }
-        let first = token.chars().next();
-        if first.map(|c| c.is_alphabetic() || c == '_' || c == '$').unwrap_or(false) {
-            return Some(token.to_string());
-        }
+/// Name bound by the file's `export default`: the declared name, the exported
+/// identifier, or the synthetic `default` symbol for anonymous expressions.
+fn extract_default_export_symbol(source: &[u8], tree: &tree_sitter::Tree) -> Option<String> {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let export = root
+        .children(&mut cursor)
+        .find(|child| child.kind() == "export_statement" && is_default_export(*child))?;
+
+    if let Some(name_node) = export
+        .child_by_field_name("declaration")
+        .and_then(|declaration| declaration.child_by_field_name("name"))
+    {
+        return Some(node_text(name_node, source).to_string());
+    }
+    let value = export.child_by_field_name("value")?;
+    if value.kind() == "identifier" {
+        Some(node_text(value, source).to_string())
+    } else {
+        Some("default".to_string())
     }
-    None
 }
 
-#[tauri::command]
-pub async fn analyze_reachability(
-    entry_point: String,
-    files: Vec<FileContent>,
-) -> Result<ReachabilityResult, String> {
-    let mut symbol_map: HashMap<String, String> = HashMap::new(); // symbol -> file_path
-    let mut file_symbols: HashMap<String, Vec<String>> = HashMap::new(); // file_path -> symbols
-    let mut file_refs: HashMap<String, HashSet<String>> = HashMap::new(); // symbol -> refs
-    let mut file_level_refs: HashMap<String, HashSet<String>> = HashMap::new(); // file_path -> refs
-    let mut import_aliases_by_file: HashMap<String, HashMap<String, (String, String)>> = HashMap::new();
-    let mut default_export_symbol_by_file: HashMap<String, String> = HashMap::new();
+/// Everything reachability needs from one file, independent of its path so it can be
+/// reused while the content is unchanged.
+#[derive(Default)]
+struct FileFacts {
+    /// `false` when tree-sitter produced no tree at all.
+    parsed: bool,
+    parse_health: Option<ParseHealth>,
+    import_aliases: HashMap<String, (String, String)>,
+    default_export: Option<String>,
+    exported_symbols: Vec<String>,
+    symbols: Vec<ExtractedSymbol>,
+    /// File-level references, used when the file is the entry point.
+    root_refs: HashSet<String>,
+    root_dynamic_imports: Vec<String>,
+    /// Bare names of the commands listed in `tauri::generate_handler![...]` invocations.
+    registered_commands: Vec<String>,
+}
 
-    // Parse all files and extract symbols + refs
-    for file in &files {
-        let ext = get_extension(&file.path);
-        let lang_opt = get_language(ext);
-        let Some(language) = lang_opt else {
-            continue;
-        };
+/// (extension, max depth, blake3 hash of the content)
+type FileFactsKey = (String, usize, blake3::Hash);
 
-        let mut parser = Parser::new();
-        if parser.set_language(&language).is_err() {
-            continue;
+/// Upper bound on cached files; the cache is cleared rather than grown past it.
+const FILE_FACTS_CACHE_LIMIT: usize = 20_000;
+
+static FILE_FACTS_CACHE: LazyLock<Mutex<HashMap<FileFactsKey, Arc<FileFacts>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Parse `content` and extract its facts; `None` for languages without a grammar.
+fn extract_file_facts(ext: &str, content: &str, max_depth: usize) -> Option<FileFacts> {
+    let language = get_language(ext)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+
+    let source = content.as_bytes();
+    let Some(tree) = parser.parse(source, None) else {
+        return Some(FileFacts {
+            parse_health: Some(failed_parse_health()),
+            ..FileFacts::default()
+        });
+    };
+
+    let mut root_refs = HashSet::new();
+    collect_references(tree.root_node(), source, &mut root_refs);
+    let mut root_dynamic_imports = Vec::new();
+    collect_dynamic_imports(tree.root_node(), source, &mut root_dynamic_imports);
+
+    Some(FileFacts {
+        parsed: true,
+        parse_health: tree_parse_health(&tree),
+        import_aliases: extract_import_aliases(source, &tree),
+        default_export: extract_default_export_symbol(source, &tree),
+        exported_symbols: extract_exported_symbols(source, &tree, max_depth),
+        symbols: extract_symbols(source, &tree, max_depth),
+        root_refs,
+        root_dynamic_imports,
+        registered_commands: if ext == "rs" {
+            extract_registered_commands(source, &tree)
+        } else {
+            Vec::new()
+        },
+    })
+}
+
+/// Cached `extract_file_facts`, keyed by content hash so re-running reachability after
+/// editing one file only re-parses that file.
+fn load_file_facts(ext: &str, content: &str, max_depth: usize) -> Option<Arc<FileFacts>> {
+    let key = (ext.to_string(), max_depth, blake3::hash(content.as_bytes()));
+    if let Some(facts) = FILE_FACTS_CACHE.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+        return Some(facts);
+    }
+
+    let facts = Arc::new(extract_file_facts(ext, content, max_depth)?);
+    if let Ok(mut cache) = FILE_FACTS_CACHE.lock() {
+        if cache.len() >= FILE_FACTS_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(key, Arc::clone(&facts));
+    }
+    Some(facts)
+}
+
+/// Lookup tables for resolving an identifier seen in one file to the symbols it names.
+#[derive(Default)]
+struct SymbolIndex {
+    by_file: HashMap<String, Vec<SymbolId>>,
+    by_name: HashMap<String, Vec<SymbolId>>,
+    /// member name -> qualified symbols (`Type::method`, `a::f`, `NS.f`, `Class.method`)
+    members_by_name: HashMap<String, Vec<SymbolId>>,
+    /// (file, owner name) -> symbols declared inside that owner
+    members_by_owner: HashMap<(String, String), Vec<SymbolId>>,
+    known_files: HashSet<String>,
+    import_aliases_by_file: HashMap<String, HashMap<String, (String, String)>>,
+    default_export_by_file: HashMap<String, String>,
+    exported_by_file: HashMap<String, Vec<String>>,
+}
+
+impl SymbolIndex {
+    fn symbols_named(&self, file: &str, name: &str) -> Vec<SymbolId> {
+        self.by_file
+            .get(file)
+            .map(|ids| ids.iter().filter(|id| id.name == name).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The default export plus every named export of `file`.
+    fn module_exports(&self, file: &str) -> Vec<SymbolId> {
+        let mut names: Vec<&String> = self.default_export_by_file.get(file).into_iter().collect();
+        names.extend(self.exported_by_file.get(file).into_iter().flatten());
+        names
+            .into_iter()
+            .flat_map(|name| self.symbols_named(file, name))
+            .collect()
+    }
+
+    /// Resolve `name` as referenced from `file`: local declarations first, then the
+    /// import binding it comes from, then any same-named symbol in the selection.
+    fn resolve(&self, file: &str, name: &str) -> Vec<SymbolId> {
+        let local = self.symbols_named(file, name);
+        if !local.is_empty() {
+            return local;
+        }
+
+        let imported = self
+            .import_aliases_by_file
+            .get(file)
+            .and_then(|aliases| aliases.get(name))
+            .and_then(|(imported_name, specifier)| {
+                let target = resolve_import_target_file(file, specifier, &self.known_files)?;
+                let symbols = match imported_name.as_str() {
+                    "*" => self.module_exports(&target),
+                    "default" => {
+                        let default_name = self.default_export_by_file.get(&target)?;
+                        self.symbols_named(&target, default_name)
+                    }
+                    _ => self.symbols_named(&target, imported_name),
+                };
+                (!symbols.is_empty()).then_some(symbols)
+            });
+        if let Some(symbols) = imported {
+            return symbols;
+        }
+
+        if let Some(global) = self.by_name.get(name) {
+            return global.clone();
+        }
+
+        // Unqualified member reference: prefer members declared in the same file.
+        let members = self.members_by_name.get(name).cloned().unwrap_or_default();
+        let local_members: Vec<SymbolId> = members.iter().filter(|id| id.file == file).cloned().collect();
+        if local_members.is_empty() {
+            members
+        } else {
+            local_members
+        }
+    }
+
+    fn add_symbol(&mut self, id: SymbolId) {
+        if let Some((owner, member)) = split_qualified_name(&id.name) {
+            self.members_by_name
+                .entry(member.to_string())
+                .or_default()
+                .push(id.clone());
+            self.members_by_owner
+                .entry((id.file.clone(), owner.to_string()))
+                .or_default()
+                .push(id.clone());
+        }
+        self.by_name.entry(id.name.clone()).or_default().push(id.clone());
+        self.by_file.entry(id.file.clone()).or_default().push(id);
+    }
+
+    /// Symbols that become reachable when `importer` dynamically imports `specifier`.
+    fn dynamic_import_roots(&self, importer: &str, specifier: &str) -> Vec<SymbolId> {
+        match resolve_import_target_file(importer, specifier, &self.known_files) {
+            Some(target) => self.module_exports(&target),
+            None => Vec::new(),
         }
+    }
+}
+
+/// Split `Type::method` / `NS.member` into owner and member name.
+fn split_qualified_name(name: &str) -> Option<(&str, &str)> {
+    name.rsplit_once("::").or_else(|| name.rsplit_once('.'))
+}
+
+fn enqueue_symbol(id: SymbolId, reachable: &mut HashSet<SymbolId>, queue: &mut VecDeque<SymbolId>) {
+    if reachable.insert(id.clone()) {
+        queue.push_back(id);
+    }
+}
 
-        let source = file.content.as_bytes();
-        let tree = match parser.parse(source, None) {
-            Some(t) => t,
-            None => continue,
+/// Outcome of the reachability walk, shared by the commands built on it.
+struct ReachabilityAnalysis {
+    index: SymbolIndex,
+    reachable: HashSet<SymbolId>,
+    facts_by_file: HashMap<String, Arc<FileFacts>>,
+    parse_health: HashMap<String, ParseHealth>,
+}
+
+fn run_reachability(
+    entry_point: &str,
+    files: &[FileContent],
+    options: &ReachabilityOptions,
+) -> ReachabilityAnalysis {
+    let keep_patterns: Vec<glob::Pattern> = options
+        .keep_patterns
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+
+    let mut index = SymbolIndex::default();
+    let mut symbol_refs: HashMap<SymbolId, HashSet<String>> = HashMap::new();
+    let mut symbol_dynamic_imports: HashMap<SymbolId, Vec<String>> = HashMap::new();
+    let mut entry_refs: HashSet<String> = HashSet::new();
+    let mut entry_dynamic_imports: Vec<String> = Vec::new();
+    let mut extra_roots: Vec<SymbolId> = Vec::new(); // DI roots and keep-pattern matches
+    let mut tauri_commands: Vec<SymbolId> = Vec::new();
+    let mut registered_commands: HashSet<String> = HashSet::new();
+    let mut parse_health: HashMap<String, ParseHealth> = HashMap::new();
+
+    // Parse all files and extract symbols + refs
+    let mut facts_by_file: HashMap<String, Arc<FileFacts>> = HashMap::new();
+    for file in files {
+        let ext = get_extension(&file.path);
+        let Some(facts) = load_file_facts(ext, &file.content, symbol_depth(options, ext)) else {
+            continue;
         };
+        if let Some(health) = &facts.parse_health {
+            parse_health.insert(file.path.clone(), health.clone());
+        }
+        if !facts.parsed {
+            continue;
+        }
 
-        let symbols = extract_symbols(source, &tree);
-        let refs_by_symbol = collect_symbol_references(source, &tree);
-        let import_aliases = extract_import_aliases(source, &tree);
-        if !import_aliases.is_empty() {
-            import_aliases_by_file.insert(file.path.clone(), import_aliases);
+        if !facts.import_aliases.is_empty() {
+            index
+                .import_aliases_by_file
+                .insert(file.path.clone(), facts.import_aliases.clone());
+        }
+        if let Some(default_export) = &facts.default_export {
+            index.default_export_by_file.insert(file.path.clone(), default_export.clone());
+        }
+        if !facts.exported_symbols.is_empty() {
+            index.exported_by_file.insert(file.path.clone(), facts.exported_symbols.clone());
         }
 
-        if let Some(default_export) = extract_default_export_symbol(&file.content) {
-            default_export_symbol_by_file.insert(file.path.clone(), default_export);
+        if file.path == entry_point {
+            entry_refs.extend(facts.root_refs.iter().cloned());
+            entry_dynamic_imports.extend(facts.root_dynamic_imports.iter().cloned());
         }
 
-        let mut refs_for_file = HashSet::new();
-        collect_references(tree.root_node(), source, &mut refs_for_file);
-        file_level_refs.insert(file.path.clone(), refs_for_file);
+        registered_commands.extend(facts.registered_commands.iter().cloned());
 
-        for sym in &symbols {
-            symbol_map.insert(sym.clone(), file.path.clone());
-            if let Some(refs) = refs_by_symbol.get(sym) {
-                file_refs.insert(sym.clone(), refs.clone());
-            } else {
-                file_refs.insert(sym.clone(), HashSet::new());
+        let keep_file = keep_patterns.iter().any(|p| p.matches(&file.path));
+        let mut ids = Vec::new();
+        for symbol in &facts.symbols {
+            let id = SymbolId {
+                file: file.path.clone(),
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+            };
+            if keep_file
+                || keep_patterns.iter().any(|p| p.matches(&id.name))
+                || is_di_root(&symbol.decorators, &options.di_heuristic)
+                || has_root_attribute(&symbol.attributes, &options.root_attributes)
+            {
+                extra_roots.push(id.clone());
+            }
+            if is_tauri_command(&symbol.attributes) {
+                tauri_commands.push(id.clone());
+            }
+            symbol_refs.entry(id.clone()).or_default().extend(symbol.refs.iter().cloned());
+            symbol_dynamic_imports
+                .entry(id.clone())
+                .or_default()
+                .extend(symbol.dynamic_imports.iter().cloned());
+            if !ids.contains(&id) {
+                ids.push(id);
             }
         }
 
-        file_symbols.insert(file.path.clone(), symbols);
+        index.known_files.insert(file.path.clone());
+        index.by_file.insert(file.path.clone(), Vec::new());
+        facts_by_file.insert(file.path.clone(), Arc::clone(&facts));
+        for id in ids {
+            index.add_symbol(id);
+        }
     }
 
+    // Tauri invokes commands from the frontend: the ones `generate_handler!` registers, or
+    // every `#[tauri::command]` when the registration is not among the files.
+    let bare_name = |id: &SymbolId| id.name.rsplit("::").next().unwrap_or_default().to_string();
+    extra_roots.extend(
+        tauri_commands
+            .into_iter()
+            .filter(|id| registered_commands.is_empty() || registered_commands.contains(&bare_name(id))),
+    );
+
     // BFS from entry point
-    let entry_symbols = file_symbols.get(&entry_point).cloned().unwrap_or_default();
+    let mut reachable: HashSet<SymbolId> = HashSet::new();
+    let mut queue: VecDeque<SymbolId> = VecDeque::new();
+
+    let entry_symbols = index.by_file.get(entry_point).cloned().unwrap_or_default();
+    for id in entry_symbols.into_iter().chain(extra_roots) {
+        enqueue_symbol(id, &mut reachable, &mut queue);
+    }
+    for name in &entry_refs {
+        for id in index.resolve(entry_point, name) {
+            enqueue_symbol(id, &mut reachable, &mut queue);
+        }
+    }
+    for specifier in &entry_dynamic_imports {
+        for id in index.dynamic_import_roots(entry_point, specifier) {
+            enqueue_symbol(id, &mut reachable, &mut queue);
+        }
+    }
 
-    let mut reachable: HashSet<String> = HashSet::new();
-    let mut queue: VecDeque<String> = VecDeque::new();
-    let known_files: HashSet<String> = file_symbols.keys().cloned().collect();
+    while let Some(id) = queue.pop_front() {
+        // Trait impl methods follow their type; Python methods follow their class.
+        let owner_key = (id.file.clone(), id.name.clone());
+        if let Some(members) = index.members_by_owner.get(&owner_key) {
+            for member in members {
+                if member.kind == "trait_method" || id.kind == "class" {
+                    enqueue_symbol(member.clone(), &mut reachable, &mut queue);
+                }
+            }
+        }
+        if let Some(refs) = symbol_refs.get(&id) {
+            for name in refs {
+                for target in index.resolve(&id.file, name) {
+                    enqueue_symbol(target, &mut reachable, &mut queue);
+                }
+            }
+        }
+        if let Some(specifiers) = symbol_dynamic_imports.get(&id) {
+            for specifier in specifiers {
+                for target in index.dynamic_import_roots(&id.file, specifier) {
+                    enqueue_symbol(target, &mut reachable, &mut queue);
+                }
+            }
+        }
+    }
 
-    for sym in &entry_symbols {
-        reachable.insert(sym.clone());
-        queue.push_back(sym.clone());
+    ReachabilityAnalysis {
+        index,
+        reachable,
+        facts_by_file,
+        parse_health,
     }
-    if let Some(entry_refs) = file_level_refs.get(&entry_point) {
-        let import_aliases = import_aliases_by_file.get(&entry_point);
-        for sym in entry_refs {
-            if symbol_map.contains_key(sym) && reachable.insert(sym.clone()) {
-                queue.push_back(sym.clone());
-                continue;
+}
+
+/// Stop following type references after loading this many unselected files.
+const TYPE_CLOSURE_FILE_LIMIT: usize = 50;
+
+const TYPE_DECLARATION_KINDS: &[&str] = &["interface_declaration", "type_alias_declaration", "enum_declaration"];
+
+/// A top-level `interface`, `type` alias, or `enum`, with the type names its body mentions.
+struct TypeDeclaration {
+    name: String,
+    text: String,
+    references: Vec<String>,
+}
+
+/// What an unselected file can contribute to the type closure.
+#[derive(Default)]
+struct TypeSource {
+    declarations: Vec<TypeDeclaration>,
+    /// Local name -> (imported name, specifier), as in `extract_import_aliases`.
+    imports: HashMap<String, (String, String)>,
+    /// `export { A as B } from "./x"`: exported name -> (original name, specifier).
+    named_reexports: HashMap<String, (String, String)>,
+    /// `export * from "./x"` specifiers.
+    star_reexports: Vec<String>,
+}
+
+fn collect_type_identifiers(node: Node, source: &[u8], names: &mut Vec<String>) {
+    if node.kind() == "type_identifier" {
+        let name = node_text(node, source);
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_type_identifiers(child, source, names);
+    }
+}
+
+fn push_type_declaration(node: Node, text_node: Node, source: &[u8], declarations: &mut Vec<TypeDeclaration>) {
+    if !TYPE_DECLARATION_KINDS.contains(&node.kind()) {
+        return;
+    }
+    let Some(name) = node.child_by_field_name("name") else {
+        return;
+    };
+    let name = node_text(name, source).to_string();
+    let mut references = Vec::new();
+    collect_type_identifiers(node, source, &mut references);
+    references.retain(|reference| *reference != name);
+    declarations.push(TypeDeclaration {
+        name,
+        text: node_text(text_node, source).to_string(),
+        references,
+    });
+}
+
+fn extract_type_source(ext: &str, content: &str) -> Option<TypeSource> {
+    if ext != "ts" && ext != "tsx" {
+        return None;
+    }
+    let mut parser = Parser::new();
+    parser.set_language(&get_language(ext)?).ok()?;
+    let tree = parser.parse(content, None)?;
+    let source = content.as_bytes();
+    let mut type_source = TypeSource {
+        imports: extract_import_aliases(source, &tree),
+        ..TypeSource::default()
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if child.kind() != "export_statement" {
+            push_type_declaration(child, child, source, &mut type_source.declarations);
+            continue;
+        }
+        if let Some(declaration) = child.child_by_field_name("declaration") {
+            push_type_declaration(declaration, child, source, &mut type_source.declarations);
+            continue;
+        }
+        let Some(specifier) = child
+            .child_by_field_name("source")
+            .and_then(|node| unquote_string_literal(node_text(node, source)))
+        else {
+            continue;
+        };
+        let mut export_cursor = child.walk();
+        for part in child.children(&mut export_cursor) {
+            if part.kind() == "*" {
+                type_source.star_reexports.push(specifier.to_string());
+            } else if part.kind() == "export_clause" {
+                let mut clause_cursor = part.walk();
+                for spec in part.children(&mut clause_cursor) {
+                    let Some(name) = spec.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let original = node_text(name, source).to_string();
+                    let exported = spec
+                        .child_by_field_name("alias")
+                        .map(|alias| node_text(alias, source).to_string())
+                        .unwrap_or_else(|| original.clone());
+                    type_source
+                        .named_reexports
+                        .insert(exported, (original, specifier.to_string()));
+                }
             }
+        }
+    }
+    Some(type_source)
+}
+
+/// Unselected files read while closing over type references, keyed by path.
+struct TypeClosure<'a, F: Fn(&str) -> Option<String>> {
+    selected: HashSet<&'a str>,
+    read: F,
+    sources: HashMap<String, Option<TypeSource>>,
+}
 
-            let Some((imported_name, import_specifier)) =
-                import_aliases.and_then(|m| m.get(sym))
-            else {
+impl<F: Fn(&str) -> Option<String>> TypeClosure<'_, F> {
+    /// The unselected TS file `specifier` points at from `from`, loading it on first use.
+    /// Imports that land on a selected file resolve to `None`: that file is already packed.
+    fn resolve(&mut self, from: &str, specifier: &str) -> Option<String> {
+        if !specifier.starts_with('.') && !specifier.starts_with("@/") {
+            return None;
+        }
+        for candidate in module_specifier_candidates(specifier, from) {
+            if self.selected.contains(candidate.as_str()) {
+                return None;
+            }
+            let ext = get_extension(&candidate);
+            if ext != "ts" && ext != "tsx" {
                 continue;
-            };
-            let Some(target_file) =
-                resolve_import_target_file(&entry_point, import_specifier, &known_files)
-            else {
+            }
+            if let Some(loaded) = self.sources.get(&candidate) {
+                if loaded.is_some() {
+                    return Some(candidate);
+                }
                 continue;
-            };
-
-            let mapped_symbol = if imported_name == "default" {
-                default_export_symbol_by_file.get(&target_file).cloned()
-            } else {
-                Some(imported_name.clone())
-            };
+            }
+            if self.sources.len() >= TYPE_CLOSURE_FILE_LIMIT {
+                return None;
+            }
+            let loaded = (self.read)(&candidate).and_then(|content| extract_type_source(ext, &content));
+            let found = loaded.is_some();
+            self.sources.insert(candidate.clone(), loaded);
+            if found {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+}
 
-            if let Some(candidate) = mapped_symbol {
-                if symbol_map
-                    .get(&candidate)
-                    .map(|owner| owner == &target_file)
-                    .unwrap_or(false)
-                    && reachable.insert(candidate.clone())
-                {
-                    queue.push_back(candidate);
-                }
+/// The `interface`, `type`, and `enum` declarations that selected TS files import from files
+/// outside the selection, plus the declarations those depend on, grouped by source file.
+/// `read` loads an unselected file by project-relative path.
+pub(crate) fn render_type_closure(files: &[FileContent], read: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let mut closure = TypeClosure {
+        selected: files.iter().map(|file| file.path.as_str()).collect(),
+        read,
+        sources: HashMap::new(),
+    };
+    let mut queue: VecDeque<(String, String)> = VecDeque::new();
+    for file in files {
+        let ext = get_extension(&file.path);
+        let Some(selected) = extract_type_source(ext, &file.content) else {
+            continue;
+        };
+        for (imported, specifier) in selected.imports.values() {
+            if let Some(target) = closure.resolve(&file.path, specifier) {
+                queue.push_back((target, imported.clone()));
             }
         }
     }
 
-    while let Some(sym) = queue.pop_front() {
-        if let Some(refs) = file_refs.get(&sym) {
-            for r in refs {
-                if !reachable.contains(r) && symbol_map.contains_key(r) {
-                    reachable.insert(r.clone());
-                    queue.push_back(r.clone());
+    let mut visited: HashSet<(String, String)> = HashSet::new();
+    let mut included: HashMap<String, HashSet<usize>> = HashMap::new();
+    while let Some((path, name)) = queue.pop_front() {
+        if !visited.insert((path.clone(), name.clone())) {
+            continue;
+        }
+        let Some(Some(source)) = closure.sources.get(&path) else {
+            continue;
+        };
+        let mut next: Vec<(String, String)> = Vec::new();
+        let mut follow: Vec<(String, String)> = Vec::new();
+        if let Some(index) = source.declarations.iter().position(|declaration| declaration.name == name) {
+            included.entry(path.clone()).or_default().insert(index);
+            for reference in &source.declarations[index].references {
+                if source.declarations.iter().any(|declaration| declaration.name == *reference) {
+                    next.push((path.clone(), reference.clone()));
+                } else if let Some((imported, specifier)) = source.imports.get(reference) {
+                    follow.push((specifier.clone(), imported.clone()));
                 }
             }
+        } else if let Some((original, specifier)) = source.named_reexports.get(&name) {
+            follow.push((specifier.clone(), original.clone()));
+        } else {
+            for specifier in &source.star_reexports {
+                follow.push((specifier.clone(), name.clone()));
+            }
+        }
+        queue.extend(next);
+        for (specifier, imported) in follow {
+            if let Some(target) = closure.resolve(&path, &specifier) {
+                queue.push_back((target, imported));
+            }
+        }
+    }
+
+    if included.is_empty() {
+        return None;
+    }
+    let mut paths: Vec<&String> = included.keys().collect();
+    paths.sort();
+    let mut out = String::from("// Type declarations the packed files import from files that were not selected.\n");
+    for path in paths {
+        let Some(Some(source)) = closure.sources.get(path) else {
+            continue;
+        };
+        out.push_str(&format!("\n// {path}\n"));
+        let mut indices: Vec<usize> = included[path].iter().copied().collect();
+        indices.sort_unstable();
+        for index in indices {
+            out.push_str(&source.declarations[index].text);
+            out.push('\n');
         }
     }
+    Some(out)
+}
 
-    // Build result
+#[tauri::command]
+pub async fn analyze_reachability(
+    entry_point: String,
+    files: Vec<FileContent>,
+    options: Option<ReachabilityOptions>,
+) -> Result<ReachabilityResult, String> {
+    let options = options.unwrap_or_default();
+    let ReachabilityAnalysis {
+        index,
+        reachable,
+        parse_health,
+        ..
+    } = run_reachability(&entry_point, &files, &options);
+
+    // Build result. Consumers strip by name, so a name stays out of the unreachable
+    // list whenever any same-named symbol in that file is reachable.
     let mut reachable_symbols: HashMap<String, Vec<String>> = HashMap::new();
     let mut unreachable_symbols: HashMap<String, Vec<String>> = HashMap::new();
 
-    for (file_path, symbols) in &file_symbols {
+    for (file_path, ids) in &index.by_file {
+        let reachable_names: HashSet<&str> = ids
+            .iter()
+            .filter(|id| reachable.contains(*id))
+            .map(|id| id.name.as_str())
+            .collect();
         let mut reach = Vec::new();
         let mut unreach = Vec::new();
-        for sym in symbols {
-            if reachable.contains(sym) {
-                reach.push(sym.clone());
+        for id in ids {
+            let bucket = if reachable_names.contains(id.name.as_str()) {
+                &mut reach
             } else {
-                unreach.push(sym.clone());
+                &mut unreach
+            };
+            if !bucket.contains(&id.name) {
+                bucket.push(id.name.clone());
             }
         }
         if !reach.is_empty() {
@@ -539,9 +1629,138 @@ pub async fn analyze_reachability(
     Ok(ReachabilityResult {
         reachable_symbols,
         unreachable_symbols,
+        parse_health,
     })
 }
 
+/// Whether `symbol` is visible outside its file under the language's export rules.
+fn is_exported_symbol(ext: &str, symbol: &ExtractedSymbol, facts: &FileFacts) -> bool {
+    match ext {
+        "rs" => symbol.public,
+        "go" => symbol.name.chars().next().is_some_and(|c| c.is_uppercase()),
+        "py" => !symbol.name.contains('.') && !symbol.name.starts_with('_'),
+        _ => {
+            facts.exported_symbols.contains(&symbol.name)
+                || facts.default_export.as_deref() == Some(symbol.name.as_str())
+        }
+    }
+}
+
+/// Top-level names `path` exports (Rust `pub` items, capitalized Go names, public Python
+/// names), in source order; empty for languages without a grammar.
+pub(crate) fn exported_symbol_names(path: &str, content: &str) -> Vec<String> {
+    let ext = get_extension(path);
+    let Some(facts) = load_file_facts(ext, content, 0) else {
+        return Vec::new();
+    };
+    facts
+        .symbols
+        .iter()
+        .filter(|symbol| is_exported_symbol(ext, symbol, &facts))
+        .map(|symbol| symbol.name.clone())
+        .collect()
+}
+
+/// Exported symbols that nothing reachable from the entry point references. Entry files
+/// and keep-pattern matches never appear because they are roots.
+#[tauri::command]
+pub async fn report_dead_exports(
+    entry_point: String,
+    files: Vec<FileContent>,
+    options: Option<ReachabilityOptions>,
+) -> Result<Vec<DeadExport>, String> {
+    let options = options.unwrap_or_default();
+    let analysis = run_reachability(&entry_point, &files, &options);
+
+    let mut dead_exports = Vec::new();
+    for file in &files {
+        let Some(facts) = analysis.facts_by_file.get(&file.path) else {
+            continue;
+        };
+        let ext = get_extension(&file.path);
+        let reachable_names: HashSet<&str> = analysis
+            .index
+            .by_file
+            .get(&file.path)
+            .into_iter()
+            .flatten()
+            .filter(|id| analysis.reachable.contains(*id))
+            .map(|id| id.name.as_str())
+            .collect();
+
+        for symbol in &facts.symbols {
+            if reachable_names.contains(symbol.name.as_str()) || !is_exported_symbol(ext, symbol, facts) {
+                continue;
+            }
+            // Dead code elsewhere may still mention the name; deleting it would break that code.
+            let mentioned_elsewhere = analysis
+                .facts_by_file
+                .iter()
+                .any(|(path, other)| path != &file.path && other.root_refs.contains(&symbol.name));
+            dead_exports.push(DeadExport {
+                file_path: file.path.clone(),
+                name: symbol.name.clone(),
+                kind: symbol.kind.to_string(),
+                start_line: symbol.start_line,
+                end_line: symbol.end_line,
+                safe_to_delete: !mentioned_elsewhere,
+            });
+        }
+    }
+
+    dead_exports.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+    Ok(dead_exports)
+}
+
+/// Token cost of each symbol in each file, largest first. The file's token count from
+/// the frontend tokenizer is spread over its bytes when provided; otherwise each symbol's
+/// source is estimated directly. Nested symbols (methods in a class) overlap their parent.
+#[tauri::command]
+pub async fn attribute_symbol_tokens(files: Vec<FileContent>) -> Result<Vec<FileSymbolTokens>, String> {
+    let options = ReachabilityOptions::default();
+    let mut attributed = Vec::new();
+    for file in &files {
+        let ext = get_extension(&file.path);
+        let Some(facts) = load_file_facts(ext, &file.content, symbol_depth(&options, ext)) else {
+            continue;
+        };
+        let total_tokens = file
+            .token_count
+            .unwrap_or_else(|| estimate_tokens_for_path(&file.path, &file.content));
+        let total_bytes = file.content.len().max(1);
+
+        let mut symbols: Vec<SymbolTokens> = facts
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let tokens = match file.token_count {
+                    Some(count) => count * symbol.byte_range.len() / total_bytes,
+                    None => file
+                        .content
+                        .get(symbol.byte_range.clone())
+                        .map(|slice| estimate_tokens_for_path(&file.path, slice))
+                        .unwrap_or(0),
+                };
+                SymbolTokens {
+                    name: symbol.name.clone(),
+                    kind: symbol.kind.to_string(),
+                    start_line: symbol.start_line,
+                    end_line: symbol.end_line,
+                    tokens,
+                }
+            })
+            .collect();
+        symbols.sort_by(|a, b| b.tokens.cmp(&a.tokens).then(a.start_line.cmp(&b.start_line)));
+
+        attributed.push(FileSymbolTokens {
+            file_path: file.path.clone(),
+            total_tokens,
+            symbols,
+        });
+    }
+    Ok(attributed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -578,12 +1797,36 @@ mod tests {
 
     // ── extract_symbols ──
 
-    fn parse_and_extract(source: &str, ext: &str) -> Vec<String> {
+    fn parse(source: &str, ext: &str) -> tree_sitter::Tree {
         let lang = get_language(ext).unwrap();
         let mut parser = Parser::new();
         parser.set_language(&lang).unwrap();
-        let tree = parser.parse(source.as_bytes(), None).unwrap();
-        extract_symbols(source.as_bytes(), &tree)
+        parser.parse(source.as_bytes(), None).unwrap()
+    }
+
+    fn default_depth() -> usize {
+        ReachabilityOptions::default().max_depth
+    }
+
+    fn parse_and_extract(source: &str, ext: &str) -> Vec<String> {
+        let tree = parse(source, ext);
+        extract_symbols(source.as_bytes(), &tree, default_depth())
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect()
+    }
+
+    #[test]
+    fn extract_symbols_records_kinds() {
+        let source = "class User {}\nconst make = () => 1;\nfunction run() {}";
+        let tree = parse(source, "ts");
+        let kinds: Vec<(String, &str)> = extract_symbols(source.as_bytes(), &tree, default_depth())
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind))
+            .collect();
+        assert!(kinds.contains(&("User".to_string(), "class")));
+        assert!(kinds.contains(&("make".to_string(), "function")));
+        assert!(kinds.contains(&("run".to_string(), "function")));
     }
 
     #[test]
@@ -634,83 +1877,866 @@ mod tests {
     }
 
     #[test]
-    fn extract_go_functions() {
-        let source = "package main\n\nfunc Foo() {}\nfunc bar() {}";
-        let symbols = parse_and_extract(source, "go");
-        assert!(symbols.contains(&"Foo".to_string()));
-        assert!(symbols.contains(&"bar".to_string()));
-    }
-
-    // ── collect_symbol_references ──
-
-    fn parse_and_collect_refs(source: &str, ext: &str) -> HashMap<String, HashSet<String>> {
-        let lang = get_language(ext).unwrap();
-        let mut parser = Parser::new();
-        parser.set_language(&lang).unwrap();
-        let tree = parser.parse(source.as_bytes(), None).unwrap();
-        collect_symbol_references(source.as_bytes(), &tree)
+    fn extract_rust_impl_methods_with_parent_type() {
+        let source = "struct Config;\nimpl<T> Config<T> { fn new() {} }\nimpl fmt::Display for Config { fn fmt(&self) {} }";
+        let tree = parse(source, "rs");
+        let kinds: Vec<(String, &str)> = extract_symbols(source.as_bytes(), &tree, default_depth())
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind))
+            .collect();
+        assert!(kinds.contains(&("Config::new".to_string(), "method")));
+        assert!(kinds.contains(&("Config::fmt".to_string(), "trait_method")));
     }
 
     #[test]
-    fn collect_refs_for_ts_function_calling_another() {
-        let source = "function helper() { return 1; }\nfunction main() { return helper(); }";
-        let refs = parse_and_collect_refs(source, "ts");
-        let main_refs = refs.get("main").expect("main should have refs");
-        assert!(main_refs.contains("helper"), "main should reference helper");
+    fn collect_refs_for_rust_method_calls() {
+        let source = "impl Config { fn new() -> Self { Self::validate(); Registry::load(); self.apply(); } }";
+        let refs = parse_and_collect_refs(source, "rs");
+        let new_refs = refs.get("Config::new").expect("method should have refs");
+        assert!(new_refs.contains("Config::validate"), "Self:: paths resolve to the impl type");
+        assert!(new_refs.contains("Registry::load"));
+        assert!(new_refs.contains("apply"), "called fields are method references");
     }
 
-    #[test]
-    fn collect_refs_excludes_self() {
-        let source = "function foo() { return foo(); }";
-        let refs = parse_and_collect_refs(source, "ts");
-        let foo_refs = refs.get("foo").unwrap_or(&HashSet::new()).clone();
-        assert!(!foo_refs.contains("foo"), "self-references should be excluded");
-    }
+    // ── file facts cache ──
 
     #[test]
-    fn collect_refs_for_python_function() {
-        let source = "def helper():\n    return 1\n\ndef main():\n    return helper()\n";
-        let refs = parse_and_collect_refs(source, "py");
-        let main_refs = refs.get("main").expect("main should have refs");
-        assert!(main_refs.contains("helper"));
+    fn load_file_facts_reuses_unchanged_content() {
+        let content = "export function cachedFactsProbe() {}\n";
+        let first = load_file_facts("ts", content, default_depth()).unwrap();
+        let second = load_file_facts("ts", content, default_depth()).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let edited = load_file_facts("ts", "export function cachedFactsProbe() { return 1; }\n", default_depth()).unwrap();
+        assert!(!Arc::ptr_eq(&first, &edited));
+        let other_depth = load_file_facts("ts", content, default_depth() + 1).unwrap();
+        assert!(!Arc::ptr_eq(&first, &other_depth));
+        assert!(load_file_facts("md", "# notes\n", default_depth()).is_none());
     }
 
+    // ── report_dead_exports ──
+
     #[tokio::test]
-    async fn analyze_reachability_seeds_from_entry_refs_and_keeps_default_export_graph() {
+    async fn report_dead_exports_lists_unreferenced_exports_with_line_ranges() {
         let files = vec![
             FileContent {
-                path: "/project/src/main.tsx".into(),
-                content: "import Root from './App';\ncreateRoot(document.getElementById('root')!).render(<Root />);\n".into(),
+                path: "/project/src/index.ts".into(),
+                content: "import { used } from \"./lib\";\nexport function main() { used(); }\n".into(),
                 token_count: None,
             },
             FileContent {
-                path: "/project/src/App.tsx".into(),
-                content: "import LimitIndicator from './components/LimitIndicator';\nconst App = () => <LimitIndicator percent={50} />;\nexport default App;\n".into(),
+                path: "/project/src/lib.ts".into(),
+                content: "export function used() {}\n\nexport function stale() {\n  return 1;\n}\nfunction internal() {}\nexport const mentioned = 1;\n".into(),
                 token_count: None,
             },
             FileContent {
-                path: "/project/src/components/LimitIndicator.tsx".into(),
-                content: "const getColorClass = (percent: number): string => {\n  if (percent >= 85) return 'bg-red-500';\n  if (percent >= 60) return 'bg-amber-400';\n  return 'bg-emerald-400';\n};\nconst LimitIndicator = ({ percent }: { percent: number }) => {\n  const clampedPercent = Math.max(0, Math.min(percent, 100));\n  return <div className={getColorClass(clampedPercent)} />;\n};\nexport default LimitIndicator;\n".into(),
+                path: "/project/src/legacy.ts".into(),
+                content: "import { mentioned } from \"./lib\";\nexport const legacy = mentioned;\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/plugins/audit.ts".into(),
+                content: "export function register() {}\n".into(),
                 token_count: None,
             },
         ];
+        let options = ReachabilityOptions {
+            keep_patterns: vec!["**/plugins/**".into()],
+            ..ReachabilityOptions::default()
+        };
 
-        let result = analyze_reachability("/project/src/main.tsx".into(), files)
+        let report = report_dead_exports("/project/src/index.ts".into(), files, Some(options))
             .await
-            .expect("reachability should succeed");
-
-        let app_reachable = result
-            .reachable_symbols
-            .get("/project/src/App.tsx")
-            .cloned()
-            .unwrap_or_default();
-        assert!(
-            app_reachable.contains(&"App".to_string()),
-            "App should be reachable from entry refs"
+            .expect("report should succeed");
+        let summary: Vec<(&str, &str, usize, usize, bool)> = report
+            .iter()
+            .map(|dead| {
+                (
+                    dead.file_path.as_str(),
+                    dead.name.as_str(),
+                    dead.start_line,
+                    dead.end_line,
+                    dead.safe_to_delete,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/project/src/legacy.ts", "legacy", 2, 2, true),
+                ("/project/src/lib.ts", "stale", 3, 5, true),
+                ("/project/src/lib.ts", "mentioned", 7, 7, false),
+            ]
         );
+    }
 
-        let indicator_reachable = result
-            .reachable_symbols
+    // ── attribute_symbol_tokens ──
+
+    #[tokio::test]
+    async fn attribute_symbol_tokens_ranks_symbols_by_size() {
+        let big_body = "  const x = 1;\n".repeat(40);
+        let content = format!("function small() {{}}\nfunction big() {{\n{big_body}}}\n");
+        let files = vec![
+            FileContent { path: "/project/a.ts".into(), content: content.clone(), token_count: None },
+            FileContent { path: "/project/b.ts".into(), content, token_count: Some(1_000) },
+            FileContent { path: "/project/notes.md".into(), content: "# notes\n".into(), token_count: None },
+        ];
+
+        let attributed = attribute_symbol_tokens(files).await.expect("attribution should succeed");
+        assert_eq!(attributed.len(), 2, "unsupported files are skipped");
+
+        let estimated = &attributed[0];
+        assert_eq!(estimated.symbols[0].name, "big");
+        assert_eq!(estimated.symbols[0].start_line, 2);
+        assert_eq!(estimated.symbols[1].name, "small");
+        assert!(estimated.symbols[0].tokens > 10 * estimated.symbols[1].tokens);
+
+        let counted = &attributed[1];
+        assert_eq!(counted.total_tokens, 1_000);
+        let sum: usize = counted.symbols.iter().map(|symbol| symbol.tokens).sum();
+        assert!(sum <= 1_000 && sum > 950, "symbol shares should cover nearly the whole file: {sum}");
+    }
+
+    // ── cyclomatic_complexity ──
+
+    #[test]
+    fn cyclomatic_complexity_counts_branches_and_short_circuits() {
+        let ts = "function f(a, b) {\n  if (a && b) { return 1; }\n  for (const x of a) {}\n  return a ? 1 : 2;\n}\n";
+        assert_eq!(cyclomatic_complexity("a.ts", ts), Some(5));
+        let rs = "fn f(x: u8) -> u8 { match x { 0 => 1, _ => 2 } }";
+        assert_eq!(cyclomatic_complexity("a.rs", rs), Some(3));
+        assert_eq!(cyclomatic_complexity("a.py", "def f():\n    return 1\n"), Some(1));
+        assert_eq!(cyclomatic_complexity("README.md", "# hi"), None);
+    }
+
+    // ── strip_comments ──
+
+    #[test]
+    fn strip_comments_drops_comment_lines_and_trailing_comments() {
+        let ts = "// header\nimport { a } from \"./a\"; // why\n/**\n * Docs.\n */\nexport const url = \"http://x\"; /* inline */ const b = 1;\n";
+        assert_eq!(
+            strip_comments("a.ts", ts).unwrap(),
+            "import { a } from \"./a\";\nexport const url = \"http://x\"; const b = 1;\n"
+        );
+        let rs = "//! Crate docs.\n/// Adds.\nfn add(a: i32) -> i32 {\n    a + 1 // one\n}\n";
+        assert_eq!(strip_comments("lib.rs", rs).unwrap(), "fn add(a: i32) -> i32 {\n    a + 1\n}\n");
+        let py = "#!/usr/bin/env python\n# setup\nx = \"# not a comment\"  # trailing\n";
+        assert_eq!(strip_comments("run.py", py).unwrap(), "#!/usr/bin/env python\nx = \"# not a comment\"\n");
+    }
+
+    #[test]
+    fn strip_comments_keeps_directives_and_skips_unparsed_files() {
+        let go = "//go:build linux\n\n// Package main runs.\npackage main\n";
+        assert_eq!(strip_comments("main.go", go).unwrap(), "//go:build linux\n\npackage main\n");
+        assert_eq!(strip_comments("a.ts", "const a = 1;\n"), None);
+        assert_eq!(strip_comments("a.ts", "const a = ; // broken\n"), None);
+        assert_eq!(strip_comments("notes.md", "<!-- c -->"), None);
+    }
+
+    // ── outline_source ──
+
+    #[test]
+    fn outlines_elide_bodies_but_keep_signatures_and_docs() {
+        let ts = "/** Adds. */\nexport function add(a: number, b: number): number {\n  return a + b;\n}\n\
+            export class Cart {\n  total(): number {\n    return 0;\n  }\n}\nexport const twice = (n: number) => n * 2;\n";
+        assert_eq!(
+            outline_source("cart.ts", ts).unwrap(),
+            "/** Adds. */\nexport function add(a: number, b: number): number { ... }\n\
+            export class Cart {\n  total(): number { ... }\n}\nexport const twice = (n: number) => n * 2;\n"
+        );
+
+        let rs = "/// Packs.\npub fn pack(files: &[File]) -> Pack {\n    let mut pack = Pack::new();\n    pack\n}\n";
+        assert_eq!(outline_source("lib.rs", rs).unwrap(), "/// Packs.\npub fn pack(files: &[File]) -> Pack { ... }\n");
+
+        let py = "def load(path):\n    \"\"\"Read a file.\"\"\"\n    with open(path) as f:\n        return f.read()\n\n\
+            def noop():\n    pass\n";
+        assert_eq!(
+            outline_source("io.py", py).unwrap(),
+            "def load(path):\n    \"\"\"Read a file.\"\"\"\n    ...\n\ndef noop():\n    ...\n"
+        );
+
+        assert_eq!(outline_source("types.ts", "export type Id = string;\n"), None);
+        assert_eq!(outline_source("notes.md", "# fn x() {}\n"), None);
+    }
+
+    // ── parse health ──
+
+    #[test]
+    fn tree_parse_health_reports_error_nodes() {
+        assert_eq!(tree_parse_health(&parse("fn ok() {}\n", "rs")), None);
+
+        let health = tree_parse_health(&parse("fn ok() {}\nfn broken( {\n", "rs")).unwrap();
+        assert_eq!(health.status, "partial");
+        assert!(health.error_count >= 1);
+        assert_eq!(health.first_error_line, Some(2));
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_reports_parse_health_per_file() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/index.ts".into(),
+                content: "import { a } from \"./a\";\na();\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/a.ts".into(),
+                content: "export function a() {}\nconst = ;\n".into(),
+                token_count: None,
+            },
+        ];
+        let result = analyze_reachability("/project/src/index.ts".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+        assert!(!result.parse_health.contains_key("/project/src/index.ts"));
+        let health = result.parse_health.get("/project/src/a.ts").expect("a.ts should be reported");
+        assert_eq!(health.status, "partial");
+        assert_eq!(health.first_error_line, Some(2));
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_links_rust_methods_and_free_functions() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.rs".into(),
+                content: "fn main() { let c = Config::new(); c.apply(); }\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/config.rs".into(),
+                content: "pub struct Config;\nfn defaults() {}\nfn unused() {}\nimpl Config {\n    pub fn new() -> Self { defaults(); Self::validate(); Config }\n    fn validate() {}\n    pub fn apply(&self) {}\n    pub fn dead(&self) {}\n}\nimpl fmt::Display for Config {\n    fn fmt(&self) {}\n}\n".into(),
+                token_count: None,
+            },
+        ];
+
+        let result = analyze_reachability("/project/src/main.rs".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+        let reachable = result.reachable_symbols.get("/project/src/config.rs").cloned().unwrap_or_default();
+        for symbol in ["Config", "Config::new", "Config::validate", "Config::apply", "Config::fmt", "defaults"] {
+            assert!(reachable.contains(&symbol.to_string()), "{symbol} should be reachable");
+        }
+        let mut unreachable = result.unreachable_symbols.get("/project/src/config.rs").cloned().unwrap_or_default();
+        unreachable.sort();
+        assert_eq!(unreachable, vec!["Config::dead".to_string(), "unused".to_string()]);
+    }
+
+    // ── nested containers ──
+
+    #[test]
+    fn extract_rust_mod_items_qualified_by_module_path() {
+        let source = "mod outer {\n    fn a() {}\n    mod inner {\n        fn b() {}\n        mod deepest { fn c() {} }\n    }\n}";
+        let symbols = parse_and_extract(source, "rs");
+        assert!(symbols.contains(&"outer::inner::deepest::c".to_string()), "{symbols:?}");
+
+        let tree = parse(source, "rs");
+        let shallow: Vec<String> = extract_symbols(source.as_bytes(), &tree, 2)
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert_eq!(shallow, vec!["outer::a".to_string(), "outer::inner::b".to_string()]);
+    }
+
+    #[test]
+    fn symbol_depth_prefers_language_overrides_then_language_defaults() {
+        let mut options = ReachabilityOptions::default();
+        assert_eq!(symbol_depth(&options, "py"), options.max_depth);
+        assert_eq!(symbol_depth(&options, "rs"), 6);
+        assert_eq!(symbol_depth(&options, "TSX"), 6);
+
+        options.max_depth = 8;
+        assert_eq!(symbol_depth(&options, "rs"), 8);
+
+        options.max_depth_by_language.insert("rs".into(), 1);
+        options.max_depth_by_language.insert("py".into(), 0);
+        assert_eq!(symbol_depth(&options, "rs"), 1);
+        assert_eq!(symbol_depth(&options, "py"), 0);
+        assert_eq!(symbol_depth(&options, "go"), 8);
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_applies_per_language_depth() {
+        let files = || {
+            vec![
+                FileContent {
+                    path: "/project/main.py".into(),
+                    content: "from models import Outer\nOuter()\n".into(),
+                    token_count: None,
+                },
+                FileContent {
+                    path: "/project/models.py".into(),
+                    content: "class Outer:\n    def used(self):\n        pass\n    def unused(self):\n        pass\n".into(),
+                    token_count: None,
+                },
+            ]
+        };
+        let symbols = |result: &ReachabilityResult| {
+            let mut all: Vec<String> = result
+                .reachable_symbols
+                .get("/project/models.py")
+                .into_iter()
+                .chain(result.unreachable_symbols.get("/project/models.py"))
+                .flatten()
+                .cloned()
+                .collect();
+            all.sort();
+            all
+        };
+
+        let deep = analyze_reachability("/project/main.py".into(), files(), None).await.unwrap();
+        assert!(symbols(&deep).contains(&"Outer.unused".to_string()), "{:?}", symbols(&deep));
+
+        let mut options = ReachabilityOptions::default();
+        options.max_depth_by_language.insert("py".into(), 0);
+        let shallow = analyze_reachability("/project/main.py".into(), files(), Some(options)).await.unwrap();
+        assert_eq!(symbols(&shallow), vec!["Outer".to_string()]);
+    }
+
+    #[test]
+    fn extract_ts_namespace_members() {
+        let source = "namespace Utils {\n  export function format() {}\n  function pad() {}\n}\nexport namespace A.B {\n  export const x = 1;\n}";
+        let symbols = parse_and_extract(source, "ts");
+        for name in ["Utils.format", "Utils.pad", "A.B.x"] {
+            assert!(symbols.contains(&name.to_string()), "{name} missing from {symbols:?}");
+        }
+    }
+
+    #[test]
+    fn extract_python_class_members() {
+        let source = "class Outer:\n    def method(self):\n        pass\n    @staticmethod\n    def build():\n        pass\n    class Inner:\n        def run(self):\n            pass\n";
+        let tree = parse(source, "py");
+        let kinds: Vec<(String, &str)> = extract_symbols(source.as_bytes(), &tree, default_depth())
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Outer".to_string(), "class"),
+                ("Outer.method".to_string(), "method"),
+                ("Outer.build".to_string(), "method"),
+                ("Outer.Inner".to_string(), "class"),
+                ("Outer.Inner.run".to_string(), "method"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_resolves_namespace_and_module_members() {
+        let files = || {
+            vec![
+                FileContent {
+                    path: "/project/src/main.ts".into(),
+                    content: "Utils.format();\n".into(),
+                    token_count: None,
+                },
+                FileContent {
+                    path: "/project/src/utils.ts".into(),
+                    content: "namespace Utils {\n  export function format() { pad(); }\n  function pad() {}\n  function unused() {}\n}\n".into(),
+                    token_count: None,
+                },
+                FileContent {
+                    path: "/project/src/lib.rs".into(),
+                    content: "fn main() { net::connect(); }\n".into(),
+                    token_count: None,
+                },
+                FileContent {
+                    path: "/project/src/net.rs".into(),
+                    content: "mod net {\n    pub fn connect() { retry(); }\n    fn retry() {}\n    fn stale() {}\n}\n".into(),
+                    token_count: None,
+                },
+            ]
+        };
+
+        let result = analyze_reachability("/project/src/main.ts".into(), files(), None)
+            .await
+            .expect("reachability should succeed");
+        let mut unreachable = result.unreachable_symbols.get("/project/src/utils.ts").cloned().unwrap_or_default();
+        unreachable.sort();
+        assert_eq!(unreachable, vec!["Utils.unused".to_string()]);
+
+        let result = analyze_reachability("/project/src/lib.rs".into(), files(), None)
+            .await
+            .expect("reachability should succeed");
+        let unreachable = result.unreachable_symbols.get("/project/src/net.rs").cloned().unwrap_or_default();
+        assert_eq!(unreachable, vec!["net::stale".to_string()]);
+    }
+
+    #[test]
+    fn extract_go_functions() {
+        let source = "package main\n\nfunc Foo() {}\nfunc bar() {}";
+        let symbols = parse_and_extract(source, "go");
+        assert!(symbols.contains(&"Foo".to_string()));
+        assert!(symbols.contains(&"bar".to_string()));
+    }
+
+    #[test]
+    fn extract_ts_default_exports_and_expressions() {
+        let source = "export default function() { return helper(); }\nconst Klass = class Inner {};\nconst config = { a: 1 };\nexport const Button = () => null;\n";
+        let tree = parse(source, "tsx");
+        let kinds: Vec<(String, &str)> = extract_symbols(source.as_bytes(), &tree, default_depth())
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind))
+            .collect();
+        assert!(kinds.contains(&("default".to_string(), "function")));
+        assert!(kinds.contains(&("Klass".to_string(), "class")));
+        assert!(kinds.contains(&("config".to_string(), "variable")));
+        assert!(kinds.contains(&("Button".to_string(), "function")));
+    }
+
+    #[test]
+    fn extract_default_export_symbol_handles_all_forms() {
+        let cases = [
+            ("export default function Named() {}\n", "Named"),
+            ("export default class Widget {}\n", "Widget"),
+            ("const App = 1;\nexport default App;\n", "App"),
+            ("export default { setup() {} };\n", "default"),
+            ("export default memo(App);\n", "default"),
+            ("export default () => null;\n", "default"),
+        ];
+        for (source, expected) in cases {
+            let tree = parse(source, "tsx");
+            assert_eq!(
+                extract_default_export_symbol(source.as_bytes(), &tree).as_deref(),
+                Some(expected),
+                "unexpected default export for {source:?}"
+            );
+        }
+        let tree = parse("export const a = 1;\n", "ts");
+        assert_eq!(extract_default_export_symbol(b"export const a = 1;\n", &tree), None);
+    }
+
+    // ── symbol references ──
+
+    fn parse_and_collect_refs(source: &str, ext: &str) -> HashMap<String, HashSet<String>> {
+        let tree = parse(source, ext);
+        let mut map: HashMap<String, HashSet<String>> = HashMap::new();
+        for symbol in extract_symbols(source.as_bytes(), &tree, default_depth()) {
+            map.entry(symbol.name).or_default().extend(symbol.refs);
+        }
+        map
+    }
+
+    #[test]
+    fn collect_refs_for_ts_function_calling_another() {
+        let source = "function helper() { return 1; }\nfunction main() { return helper(); }";
+        let refs = parse_and_collect_refs(source, "ts");
+        let main_refs = refs.get("main").expect("main should have refs");
+        assert!(main_refs.contains("helper"), "main should reference helper");
+    }
+
+    #[test]
+    fn collect_refs_excludes_self() {
+        let source = "function foo() { return foo(); }";
+        let refs = parse_and_collect_refs(source, "ts");
+        let foo_refs = refs.get("foo").unwrap_or(&HashSet::new()).clone();
+        assert!(!foo_refs.contains("foo"), "self-references should be excluded");
+    }
+
+    #[test]
+    fn collect_refs_for_python_function() {
+        let source = "def helper():\n    return 1\n\ndef main():\n    return helper()\n";
+        let refs = parse_and_collect_refs(source, "py");
+        let main_refs = refs.get("main").expect("main should have refs");
+        assert!(main_refs.contains("helper"));
+    }
+
+    // ── dynamic imports ──
+
+    #[test]
+    fn collect_dynamic_imports_finds_lazy_and_route_imports() {
+        let source = "const Page = React.lazy(() => import('./pages/Page'));\nconst routes = [{ component: () => import(`./views/Home`) }];\nconst skip = import(`./views/${name}`);\n";
+        let tree = parse(source, "tsx");
+        let by_symbol: HashMap<String, Vec<String>> = extract_symbols(source.as_bytes(), &tree, default_depth())
+            .into_iter()
+            .filter(|symbol| !symbol.dynamic_imports.is_empty())
+            .map(|symbol| (symbol.name, symbol.dynamic_imports))
+            .collect();
+        assert_eq!(by_symbol.get("Page"), Some(&vec!["./pages/Page".to_string()]));
+        assert_eq!(by_symbol.get("routes"), Some(&vec!["./views/Home".to_string()]));
+        assert!(!by_symbol.contains_key("skip"));
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_follows_lazy_loaded_modules() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.tsx".into(),
+                content: "const Settings = React.lazy(() => import('./Settings'));\nrender(<Settings />);\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/Settings.tsx".into(),
+                content: "const formatLabel = (v: string) => v.trim();\nconst unused = () => 1;\nconst Settings = () => <div>{formatLabel('x')}</div>;\nexport default Settings;\n".into(),
+                token_count: None,
+            },
+        ];
+
+        let result = analyze_reachability("/project/src/main.tsx".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+
+        let reachable = result
+            .reachable_symbols
+            .get("/project/src/Settings.tsx")
+            .cloned()
+            .unwrap_or_default();
+        assert!(reachable.contains(&"Settings".to_string()));
+        assert!(reachable.contains(&"formatLabel".to_string()));
+        let unreachable = result
+            .unreachable_symbols
+            .get("/project/src/Settings.tsx")
+            .cloned()
+            .unwrap_or_default();
+        assert_eq!(unreachable, vec!["unused".to_string()]);
+    }
+
+    // ── DI / decorators ──
+
+    #[test]
+    fn extract_python_decorated_definitions() {
+        let symbols = parse_and_extract("@app.get('/')\ndef index():\n    pass\n", "py");
+        assert!(symbols.contains(&"index".to_string()));
+    }
+
+    #[test]
+    fn decorator_refs_are_attributed_to_exported_class() {
+        let refs = parse_and_collect_refs("@Module({ providers: [UserService] })\nexport class AppModule {}\n", "ts");
+        assert!(refs.get("AppModule").map(|r| r.contains("UserService")).unwrap_or(false));
+    }
+
+    #[test]
+    fn di_roots_respect_heuristic() {
+        let source = "@Module({ providers: [UserService] })\nexport class AppModule {}\n@Injectable()\nexport class UserService {}\nclass Plain {}\n";
+        let tree = parse(source, "ts");
+        let symbols = extract_symbols(source.as_bytes(), &tree, default_depth());
+        let roots = |heuristic: &str| -> Vec<String> {
+            symbols
+                .iter()
+                .filter(|symbol| is_di_root(&symbol.decorators, heuristic))
+                .map(|symbol| symbol.name.clone())
+                .collect()
+        };
+        assert_eq!(roots("modules"), vec!["AppModule".to_string()]);
+        assert_eq!(roots("decorated"), vec!["AppModule".to_string(), "UserService".to_string()]);
+        assert!(roots("off").is_empty());
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_keeps_services_registered_in_di_modules() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.ts".into(),
+                content: "bootstrap();\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/users.module.ts".into(),
+                content: "@Module({ providers: [UsersService] })\nexport class UsersModule {}\n@Injectable()\nexport class UsersService { constructor(private repo: UsersRepository) {} }\n@Injectable()\nexport class UsersRepository {}\nexport class Orphan {}\n".into(),
+                token_count: None,
+            },
+        ];
+
+        let result = analyze_reachability("/project/src/main.ts".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+        let reachable = result
+            .reachable_symbols
+            .get("/project/src/users.module.ts")
+            .cloned()
+            .unwrap_or_default();
+        assert!(reachable.contains(&"UsersModule".to_string()));
+        assert!(reachable.contains(&"UsersService".to_string()));
+        assert!(reachable.contains(&"UsersRepository".to_string()));
+        assert_eq!(
+            result.unreachable_symbols.get("/project/src/users.module.ts"),
+            Some(&vec!["Orphan".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_skips_di_roots_when_heuristic_is_off() {
+        let files = vec![FileContent {
+            path: "/project/src/app.module.ts".into(),
+            content: "@Module({})\nexport class AppModule {}\n".into(),
+            token_count: None,
+        }];
+        let options = ReachabilityOptions {
+            di_heuristic: "off".into(),
+            ..Default::default()
+        };
+
+        let result = analyze_reachability("/project/src/main.ts".into(), files, Some(options))
+            .await
+            .expect("reachability should succeed");
+        assert!(result.reachable_symbols.is_empty());
+    }
+
+    // ── Rust attributes ──
+
+    #[test]
+    fn rust_attribute_paths_are_collected_per_item() {
+        let source = "#[derive(Debug)]\n// note\n#[cfg(test)]\nstruct A;\n\n#[tauri::command]\npub async fn greet() {}\n\nfn plain() {}\n";
+        let tree = parse(source, "rs");
+        let attributes: Vec<(String, Vec<String>)> = extract_symbols(source.as_bytes(), &tree, default_depth())
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.attributes))
+            .collect();
+        assert_eq!(
+            attributes,
+            vec![
+                ("A".to_string(), vec!["derive".to_string(), "cfg".to_string()]),
+                ("greet".to_string(), vec!["tauri::command".to_string()]),
+                ("plain".to_string(), Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn root_attributes_match_by_path_suffix() {
+        let roots = vec!["tauri::command".to_string(), "test".to_string()];
+        assert!(has_root_attribute(&["tauri::command".into()], &roots));
+        assert!(has_root_attribute(&["command".into()], &roots));
+        assert!(has_root_attribute(&["tokio::test".into()], &roots));
+        assert!(!has_root_attribute(&["subcommand".into()], &roots));
+        assert!(!has_root_attribute(&["derive".into()], &roots));
+        assert!(!has_root_attribute(&["tauri::command".into()], &[]));
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_keeps_items_with_root_attributes() {
+        let files = || {
+            vec![
+                FileContent {
+                    path: "/project/src/main.rs".into(),
+                    content: "fn main() {}\n".into(),
+                    token_count: None,
+                },
+                FileContent {
+                    path: "/project/src/commands.rs".into(),
+                    content: "#[no_mangle]\npub extern \"C\" fn greet() { helper(); }\nfn helper() {}\nfn stale() {}\n".into(),
+                    token_count: None,
+                },
+            ]
+        };
+        let unreachable = |result: &ReachabilityResult| {
+            let mut names = result.unreachable_symbols.get("/project/src/commands.rs").cloned().unwrap_or_default();
+            names.sort();
+            names
+        };
+
+        let plain = analyze_reachability("/project/src/main.rs".into(), files(), None).await.unwrap();
+        assert_eq!(unreachable(&plain), vec!["greet".to_string(), "helper".to_string(), "stale".to_string()]);
+
+        let options = ReachabilityOptions {
+            root_attributes: vec!["no_mangle".into()],
+            ..Default::default()
+        };
+        let rooted = analyze_reachability("/project/src/main.rs".into(), files(), Some(options)).await.unwrap();
+        assert_eq!(unreachable(&rooted), vec!["stale".to_string()]);
+    }
+
+    // ── Tauri commands ──
+
+    #[test]
+    fn generate_handler_entries_are_registered_commands() {
+        let source = "pub fn run() {\n    tauri::Builder::default()\n        .invoke_handler(tauri::generate_handler![greet, commands::fs::walk_tree,])\n        .run(ctx());\n    println!(\"{}\", other);\n}\n";
+        let tree = parse(source, "rs");
+        assert_eq!(extract_registered_commands(source.as_bytes(), &tree), vec!["greet", "walk_tree"]);
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_roots_registered_tauri_commands() {
+        let file = |path: &str, content: &str| FileContent {
+            path: format!("/project/src/{path}"),
+            content: content.into(),
+            token_count: None,
+        };
+        let commands = "#[tauri::command]\npub fn greet() { helper(); }\nfn helper() {}\n#[command]\npub fn unregistered() {}\n";
+        let lib = "pub fn run() {\n    tauri::Builder::default().invoke_handler(tauri::generate_handler![commands::greet]);\n}\n";
+        let unreachable = |result: &ReachabilityResult| result.unreachable_symbols.get("/project/src/commands.rs").cloned();
+
+        let files = vec![file("main.rs", "fn main() {}\n"), file("commands.rs", commands)];
+        let unregistered = analyze_reachability("/project/src/main.rs".into(), files, None).await.unwrap();
+        assert_eq!(unreachable(&unregistered), None);
+
+        let files = vec![file("main.rs", "fn main() {}\n"), file("commands.rs", commands), file("lib.rs", lib)];
+        let registered = analyze_reachability("/project/src/main.rs".into(), files, None).await.unwrap();
+        assert_eq!(unreachable(&registered), Some(vec!["unregistered".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_keeps_files_and_symbols_matching_keep_patterns() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.ts".into(),
+                content: "run();\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/db/migrations/001_init.ts".into(),
+                content: "export function up() {}\nexport function down() {}\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/plugins.ts".into(),
+                content: "export function plugin_metrics() {}\nexport function helper() {}\n".into(),
+                token_count: None,
+            },
+        ];
+        let options = ReachabilityOptions {
+            keep_patterns: vec!["**/migrations/**".into(), "plugin_*".into(), "[".into()],
+            ..Default::default()
+        };
+
+        let result = analyze_reachability("/project/src/main.ts".into(), files, Some(options))
+            .await
+            .expect("reachability should succeed");
+        assert!(!result.unreachable_symbols.contains_key("/project/db/migrations/001_init.ts"));
+        assert_eq!(
+            result.reachable_symbols.get("/project/src/plugins.ts"),
+            Some(&vec!["plugin_metrics".to_string()])
+        );
+        assert_eq!(
+            result.unreachable_symbols.get("/project/src/plugins.ts"),
+            Some(&vec!["helper".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_follows_anonymous_default_exports() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.tsx".into(),
+                content: "import Page from './Page';\nimport Card from './Card';\nrender(<Page />, <Card />);\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/Page.tsx".into(),
+                content: "const title = () => 'x';\nexport default function() { return title(); }\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/Card.tsx".into(),
+                content: "const Card = () => null;\nconst unused = 1;\nexport default memo(Card);\n".into(),
+                token_count: None,
+            },
+        ];
+
+        let result = analyze_reachability("/project/src/main.tsx".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+        assert!(!result.unreachable_symbols.contains_key("/project/src/Page.tsx"));
+        assert_eq!(
+            result.unreachable_symbols.get("/project/src/Card.tsx"),
+            Some(&vec!["unused".to_string()])
+        );
+    }
+
+    // ── symbol identity ──
+
+    #[tokio::test]
+    async fn analyze_reachability_does_not_conflate_same_named_symbols_across_files() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.ts".into(),
+                content: "import { User } from './models';\nconsole.log(User);\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/models.ts".into(),
+                content: "export class User {}\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/legacy.ts".into(),
+                content: "export const User = { legacy: true };\nexport const audit = () => User;\n".into(),
+                token_count: None,
+            },
+        ];
+
+        let result = analyze_reachability("/project/src/main.ts".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+        assert_eq!(
+            result.reachable_symbols.get("/project/src/models.ts"),
+            Some(&vec!["User".to_string()])
+        );
+        assert!(!result.reachable_symbols.contains_key("/project/src/legacy.ts"));
+        assert_eq!(
+            result.unreachable_symbols.get("/project/src/legacy.ts"),
+            Some(&vec!["User".to_string(), "audit".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_resolves_local_declarations_before_global_names() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.ts".into(),
+                content: "import { run } from './a';\nrun();\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/a.ts".into(),
+                content: "const helper = () => 1;\nexport const run = () => helper();\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/b.ts".into(),
+                content: "export const helper = () => 2;\n".into(),
+                token_count: None,
+            },
+        ];
+
+        let result = analyze_reachability("/project/src/main.ts".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+        assert_eq!(
+            result.unreachable_symbols.get("/project/src/b.ts"),
+            Some(&vec!["helper".to_string()])
+        );
+        let a_reachable = result.reachable_symbols.get("/project/src/a.ts").cloned().unwrap_or_default();
+        assert!(a_reachable.contains(&"helper".to_string()));
+        assert!(a_reachable.contains(&"run".to_string()));
+    }
+
+    #[tokio::test]
+    async fn analyze_reachability_seeds_from_entry_refs_and_keeps_default_export_graph() {
+        let files = vec![
+            FileContent {
+                path: "/project/src/main.tsx".into(),
+                content: "import Root from './App';\ncreateRoot(document.getElementById('root')!).render(<Root />);\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/App.tsx".into(),
+                content: "import LimitIndicator from './components/LimitIndicator';\nconst App = () => <LimitIndicator percent={50} />;\nexport default App;\n".into(),
+                token_count: None,
+            },
+            FileContent {
+                path: "/project/src/components/LimitIndicator.tsx".into(),
+                content: "const getColorClass = (percent: number): string => {\n  if (percent >= 85) return 'bg-red-500';\n  if (percent >= 60) return 'bg-amber-400';\n  return 'bg-emerald-400';\n};\nconst LimitIndicator = ({ percent }: { percent: number }) => {\n  const clampedPercent = Math.max(0, Math.min(percent, 100));\n  return <div className={getColorClass(clampedPercent)} />;\n};\nexport default LimitIndicator;\n".into(),
+                token_count: None,
+            },
+        ];
+
+        let result = analyze_reachability("/project/src/main.tsx".into(), files, None)
+            .await
+            .expect("reachability should succeed");
+
+        let app_reachable = result
+            .reachable_symbols
+            .get("/project/src/App.tsx")
+            .cloned()
+            .unwrap_or_default();
+        assert!(
+            app_reachable.contains(&"App".to_string()),
+            "App should be reachable from entry refs"
+        );
+
+        let indicator_reachable = result
+            .reachable_symbols
             .get("/project/src/components/LimitIndicator.tsx")
             .cloned()
             .unwrap_or_default();
@@ -731,4 +2757,84 @@ mod tests {
         assert!(!indicator_unreachable.contains(&"LimitIndicator".to_string()));
         assert!(!indicator_unreachable.contains(&"getColorClass".to_string()));
     }
+
+    // ── type closure ──
+
+    #[test]
+    fn import_clause_bindings_cover_every_named_specifier() {
+        assert_eq!(
+            parse_import_clause_bindings("{ type User, formatUser as format }"),
+            vec![
+                ("User".to_string(), "User".to_string()),
+                ("format".to_string(), "formatUser".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_import_clause_bindings("React, { useState, useMemo }"),
+            vec![
+                ("React".to_string(), "default".to_string()),
+                ("useState".to_string(), "useState".to_string()),
+                ("useMemo".to_string(), "useMemo".to_string()),
+            ]
+        );
+    }
+
+    fn type_closure_of(selected: &[(&str, &str)], unselected: &[(&str, &str)]) -> Option<String> {
+        let files: Vec<FileContent> = selected
+            .iter()
+            .map(|(path, content)| FileContent {
+                path: path.to_string(),
+                content: content.to_string(),
+                token_count: None,
+            })
+            .collect();
+        let disk: HashMap<String, String> = unselected
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.to_string()))
+            .collect();
+        render_type_closure(&files, |path| disk.get(path).cloned())
+    }
+
+    #[test]
+    fn type_closure_pulls_only_the_imported_declarations_and_their_dependencies() {
+        let closure = type_closure_of(
+            &[("src/app.ts", "import { type User, formatUser } from './models';\nexport const u: User = formatUser();\n")],
+            &[(
+                "src/models.ts",
+                "import { Role } from './roles';\nexport interface User { id: string; role: Role; address: Address }\ninterface Address { city: string }\nexport interface Unused { x: number }\nexport function formatUser(): User { return null as any; }\n",
+            ), (
+                "src/roles.ts",
+                "export enum Role { Admin, Member }\nexport type Other = string;\n",
+            )],
+        )
+        .unwrap();
+        assert!(closure.contains("// src/models.ts\nexport interface User"));
+        assert!(closure.contains("interface Address { city: string }"));
+        assert!(closure.contains("// src/roles.ts\nexport enum Role { Admin, Member }"));
+        assert!(!closure.contains("Unused"));
+        assert!(!closure.contains("formatUser"));
+        assert!(!closure.contains("Other"));
+    }
+
+    #[test]
+    fn type_closure_follows_barrel_reexports() {
+        let closure = type_closure_of(
+            &[("src/app.ts", "import type { Account } from './types';\n")],
+            &[
+                ("src/types/index.ts", "export * from './user';\nexport { Plan as Account } from './billing';\n"),
+                ("src/types/billing.ts", "export type Plan = 'free' | 'pro';\n"),
+            ],
+        )
+        .unwrap();
+        assert!(closure.contains("// src/types/billing.ts\nexport type Plan = 'free' | 'pro';"));
+    }
+
+    #[test]
+    fn type_closure_skips_selected_files_and_packages() {
+        let selected = [
+            ("src/app.ts", "import type { User } from './models';\nimport type { FC } from 'react';\n"),
+            ("src/models.ts", "export interface User { id: string }\n"),
+        ];
+        assert_eq!(type_closure_of(&selected, &[]), None);
+    }
 }
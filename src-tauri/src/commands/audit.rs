@@ -0,0 +1,123 @@
+use crate::commands::pack::utc_now;
+use crate::models::ExportRecord;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// File name of the export audit log inside the app data directory.
+const AUDIT_LOG_FILE: &str = "export-audit.jsonl";
+
+/// `get_export_history` returns at most this many records when no limit is given.
+const DEFAULT_HISTORY_LIMIT: usize = 200;
+
+/// Set once at startup; exports are not recorded before then.
+static AUDIT_LOG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Serializes appends so concurrent exports never interleave lines.
+static AUDIT_LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Point the audit log at `dir` (the app data directory), creating it if needed.
+pub fn init_audit_log(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let _ = AUDIT_LOG_PATH.set(dir.join(AUDIT_LOG_FILE));
+    Ok(())
+}
+
+fn audit_log_path() -> Result<&'static PathBuf, String> {
+    AUDIT_LOG_PATH
+        .get()
+        .ok_or_else(|| "Export audit log is not initialized".to_string())
+}
+
+/// Append `record` as one JSON line.
+fn append_record(path: &Path, record: &ExportRecord) -> Result<(), String> {
+    let mut line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    line.push('\n');
+    let _guard = AUDIT_LOG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open export audit log: {e}"))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write export audit log: {e}"))
+}
+
+/// The newest `limit` records, newest first. A missing log is an empty history, and lines
+/// that fail to parse (e.g. a torn final write) are skipped.
+fn read_history(path: &Path, limit: usize) -> Result<Vec<ExportRecord>, String> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read export audit log: {e}")),
+    };
+    let mut records: Vec<ExportRecord> = std::io::BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+    records.reverse();
+    records.truncate(limit);
+    Ok(records)
+}
+
+/// Log a pack export or copy. The timestamp is set here rather than trusted from the caller.
+#[tauri::command]
+pub async fn record_export(mut record: ExportRecord) -> Result<(), String> {
+    record.timestamp = utc_now();
+    let path = audit_log_path()?;
+    tauri::async_runtime::spawn_blocking(move || append_record(path, &record))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Past exports, newest first.
+#[tauri::command]
+pub async fn get_export_history(limit: Option<usize>) -> Result<Vec<ExportRecord>, String> {
+    let path = audit_log_path()?;
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || read_history(path, limit))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn record(destination: &str, file_count: usize) -> ExportRecord {
+        ExportRecord {
+            timestamp: "2026-10-14T12:00:00Z".into(),
+            project: Some("app".into()),
+            kind: "file".into(),
+            file_count,
+            token_count: file_count * 100,
+            destination: destination.into(),
+        }
+    }
+
+    // ── audit log ──
+
+    #[test]
+    fn history_is_newest_first_and_limited() {
+        let path = std::env::temp_dir().join(format!("bablusheed-audit-{}.jsonl", Uuid::new_v4()));
+        assert!(read_history(&path, 10).unwrap().is_empty(), "missing log is empty history");
+
+        for i in 1..=3 {
+            append_record(&path, &record(&format!("/out/pack_{i}.txt"), i)).unwrap();
+        }
+        let history = read_history(&path, 2).unwrap();
+        assert_eq!(history, vec![record("/out/pack_3.txt", 3), record("/out/pack_2.txt", 2)]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn history_skips_malformed_lines() {
+        let path = std::env::temp_dir().join(format!("bablusheed-audit-{}.jsonl", Uuid::new_v4()));
+        append_record(&path, &record("clipboard", 1)).unwrap();
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"timestamp\":").unwrap();
+        assert_eq!(read_history(&path, 10).unwrap(), vec![record("clipboard", 1)]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,439 @@
+use crate::commands::pack::normalize_path;
+use crate::models::FileContent;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+/// Extensions a Makefile object file (`main.o`) may have been compiled from.
+const OBJECT_SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx", "m", "mm", "s", "S"];
+/// `add_library(core STATIC ...)` keywords that are not source files.
+const CMAKE_KEYWORDS: &[&str] = &[
+    "STATIC", "SHARED", "MODULE", "OBJECT", "INTERFACE", "IMPORTED", "ALIAS", "GLOBAL", "WIN32", "MACOSX_BUNDLE",
+    "EXCLUDE_FROM_ALL", "PRIVATE", "PUBLIC", "LINK_PRIVATE", "LINK_PUBLIC", "LINK_INTERFACE_LIBRARIES",
+];
+
+/// `add_executable(app main.c)`, `target_link_libraries(app PRIVATE core)`, `set(SRCS a.c b.c)`;
+/// arguments never hold parentheses outside generator expressions, which are dropped.
+static CMAKE_COMMAND: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(add_executable|add_library|target_sources|target_link_libraries|add_dependencies|set)\s*\(([^()]*)\)")
+        .unwrap()
+});
+/// `$(SRCS)`, `${SRCS}` and `$(OBJS:.o=.c)` in Makefiles and `${SRCS}` in CMake.
+static VARIABLE_REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$[({]([A-Za-z_][\w.]*)(?::([^=)}]*)=([^)}]*))?[)}]").unwrap());
+/// `project(':core')`, `project(path: ":libs:net")` in Groovy and Kotlin Gradle scripts.
+static GRADLE_PROJECT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\bproject\(\s*(?:path\s*[:=]\s*)?['"](:[^'"]*)['"]"#).unwrap());
+static MAVEN_PARENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<parent>.*?</parent>").unwrap());
+static MAVEN_DEPENDENCY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<dependency>.*?<artifactId>\s*([^<\s]+)\s*</artifactId>").unwrap());
+static MAVEN_ARTIFACT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<artifactId>\s*([^<\s]+)\s*</artifactId>").unwrap());
+
+/// A build target (Makefile rule, CMake target) or module (Gradle project, Maven module).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BuildTarget {
+    pub name: String,
+    /// Index of the build file that declares it.
+    pub build_file: usize,
+    /// Files it builds, in file order; modules include their own build file.
+    pub members: Vec<usize>,
+    /// Targets it links or depends on, as indices into the returned targets.
+    pub depends_on: Vec<usize>,
+}
+
+/// A target before its dependency names are resolved.
+struct DeclaredTarget {
+    name: String,
+    build_file: usize,
+    members: Vec<usize>,
+    depends_on: Vec<String>,
+}
+
+enum BuildSystem {
+    Make,
+    CMake,
+    Gradle,
+    Maven,
+}
+
+fn build_system(path: &str) -> Option<BuildSystem> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name {
+        "Makefile" | "makefile" | "GNUmakefile" => Some(BuildSystem::Make),
+        "CMakeLists.txt" => Some(BuildSystem::CMake),
+        "build.gradle" | "build.gradle.kts" => Some(BuildSystem::Gradle),
+        "pom.xml" => Some(BuildSystem::Maven),
+        _ if name.ends_with(".mk") => Some(BuildSystem::Make),
+        _ => None,
+    }
+}
+
+fn directory_of(path: &str) -> &str {
+    path.rfind('/').map_or("", |idx| &path[..idx])
+}
+
+fn join_path(dir: &str, relative: &str) -> String {
+    if dir.is_empty() { normalize_path(relative) } else { normalize_path(&format!("{dir}/{relative}")) }
+}
+
+/// Replace variable references with their values; `$(OBJS:.o=.c)` also swaps each word's suffix.
+fn expand_variables(text: &str, variables: &HashMap<String, String>) -> String {
+    VARIABLE_REFERENCE
+        .replace_all(text, |c: &regex::Captures| {
+            let value = variables.get(&c[1]).map_or("", String::as_str);
+            match (c.get(2), c.get(3)) {
+                (Some(from), Some(to)) => value
+                    .split_whitespace()
+                    .map(|word| match word.strip_suffix(from.as_str()) {
+                        Some(stem) => format!("{stem}{}", to.as_str()),
+                        None => word.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                _ => value.to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Files a build file names relative to its directory; object files stand for their sources.
+fn named_files(dir: &str, words: &[&str], path_to_idx: &HashMap<&str, usize>) -> Vec<usize> {
+    let mut found = Vec::new();
+    for word in words {
+        let path = join_path(dir, word);
+        if let Some(&idx) = path_to_idx.get(path.as_str()) {
+            found.push(idx);
+        } else if let Some(stem) = path.strip_suffix(".o").or_else(|| path.strip_suffix(".obj")) {
+            found.extend(
+                OBJECT_SOURCE_EXTENSIONS
+                    .iter()
+                    .find_map(|ext| path_to_idx.get(format!("{stem}.{ext}").as_str()).copied()),
+            );
+        }
+    }
+    found
+}
+
+fn parse_makefile(
+    build_file: usize,
+    dir: &str,
+    content: &str,
+    path_to_idx: &HashMap<&str, usize>,
+) -> Vec<DeclaredTarget> {
+    let joined = content.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut variables: HashMap<String, String> = HashMap::new();
+    let mut rules: Vec<(String, Vec<String>)> = Vec::new();
+    for line in joined.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let line = line.split('#').next().unwrap_or("").trim();
+        if let Some((name, value)) = line.split_once('=')
+            && (!name.contains(':') || name.ends_with(':'))
+        {
+            let name = name.trim_end_matches([':', '+', '?', '!']).trim();
+            let value = expand_variables(value.trim(), &variables);
+            let entry = variables.entry(name.to_string()).or_default();
+            if line.contains("+=") && !entry.is_empty() {
+                entry.push(' ');
+                entry.push_str(&value);
+            } else {
+                *entry = value;
+            }
+            continue;
+        }
+        let Some((targets, prerequisites)) = line.split_once(':') else {
+            continue;
+        };
+        let prerequisites = expand_variables(prerequisites.split(';').next().unwrap_or(""), &variables);
+        let prerequisites: Vec<String> =
+            prerequisites.split_whitespace().filter(|word| *word != "|").map(str::to_string).collect();
+        for target in expand_variables(targets, &variables).split_whitespace() {
+            if !target.starts_with('.') && !target.contains('%') {
+                rules.push((target.to_string(), prerequisites.clone()));
+            }
+        }
+    }
+
+    // An object rule (`main.o: main.c util.h`) adds its prerequisites to the targets linking it.
+    let object_rules: HashMap<&str, &[String]> = rules
+        .iter()
+        .filter(|(target, _)| target.ends_with(".o") || target.ends_with(".obj"))
+        .map(|(target, prerequisites)| (target.as_str(), prerequisites.as_slice()))
+        .collect();
+    let rule_names: HashSet<&str> = rules.iter().map(|(target, _)| target.as_str()).collect();
+    let mut targets: Vec<DeclaredTarget> = Vec::new();
+    for (target, prerequisites) in rules.iter().filter(|(target, _)| !object_rules.contains_key(target.as_str())) {
+        let mut words: Vec<&str> = Vec::new();
+        let mut depends_on = Vec::new();
+        for prerequisite in prerequisites {
+            words.push(prerequisite);
+            if let Some(inputs) = object_rules.get(prerequisite.as_str()) {
+                words.extend(inputs.iter().map(String::as_str));
+            } else if rule_names.contains(prerequisite.as_str()) {
+                depends_on.push(prerequisite.clone());
+            }
+        }
+        let members = named_files(dir, &words, path_to_idx);
+        // A target may be spread over several rules.
+        match targets.iter_mut().find(|known| known.name == *target) {
+            Some(known) => {
+                known.members.extend(members);
+                known.depends_on.extend(depends_on);
+            }
+            None => targets.push(DeclaredTarget { name: target.clone(), build_file, members, depends_on }),
+        }
+    }
+    targets
+}
+
+fn parse_cmake(build_file: usize, dir: &str, content: &str, path_to_idx: &HashMap<&str, usize>) -> Vec<DeclaredTarget> {
+    let uncommented: String = content.lines().map(|line| line.split('#').next().unwrap_or("")).collect::<Vec<_>>().join("\n");
+    let mut variables: HashMap<String, String> = HashMap::new();
+    let mut targets: Vec<DeclaredTarget> = Vec::new();
+    for command in CMAKE_COMMAND.captures_iter(&uncommented) {
+        let name = command[1].to_ascii_lowercase();
+        let expanded = expand_variables(&command[2], &variables);
+        let args: Vec<&str> = expanded
+            .split_whitespace()
+            .map(|arg| arg.trim_matches('"'))
+            .filter(|arg| !arg.is_empty() && !arg.starts_with("$<") && !CMAKE_KEYWORDS.contains(arg))
+            .collect();
+        let Some((&first, rest)) = args.split_first() else {
+            continue;
+        };
+        if name == "set" {
+            variables.insert(first.to_string(), rest.join(" "));
+            continue;
+        }
+        // A target only named by links or `target_sources` has no build file until one declares
+        // it, possibly another CMakeLists.txt.
+        let position = match targets.iter().position(|target| target.name == first) {
+            Some(position) => position,
+            None => {
+                targets.push(DeclaredTarget {
+                    name: first.to_string(),
+                    build_file: usize::MAX,
+                    members: Vec::new(),
+                    depends_on: Vec::new(),
+                });
+                targets.len() - 1
+            }
+        };
+        let target = &mut targets[position];
+        if name.starts_with("add_") && name != "add_dependencies" {
+            target.build_file = build_file;
+        }
+        match name.as_str() {
+            "target_link_libraries" | "add_dependencies" => {
+                target.depends_on.extend(rest.iter().map(|lib| lib.to_string()))
+            }
+            _ => target.members.extend(named_files(dir, rest, path_to_idx)),
+        }
+    }
+    targets
+}
+
+/// The module's artifactId, skipping the `<parent>` block, and the artifactIds it depends on.
+fn parse_pom(content: &str) -> Option<(String, Vec<String>)> {
+    let own = MAVEN_PARENT.replace(content, "");
+    let without_dependencies = MAVEN_DEPENDENCY.replace_all(&own, "");
+    let artifact = MAVEN_ARTIFACT.captures(&without_dependencies)?[1].to_string();
+    let dependencies = MAVEN_DEPENDENCY.captures_iter(&own).map(|c| c[1].to_string()).collect();
+    Some((artifact, dependencies))
+}
+
+/// Build targets and modules declared by the Makefiles, CMake lists, Gradle scripts and Maven
+/// poms among `files`, with the files each builds. A file belongs to the innermost Gradle or
+/// Maven module around it; Makefile and CMake targets list their own sources.
+pub(crate) fn build_targets(files: &[FileContent], normalized_paths: &[String]) -> Vec<BuildTarget> {
+    let build_files: Vec<(usize, BuildSystem)> = normalized_paths
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, path)| build_system(path).map(|system| (idx, system)))
+        .collect();
+    if build_files.is_empty() {
+        return Vec::new();
+    }
+    let path_to_idx: HashMap<&str, usize> =
+        normalized_paths.iter().enumerate().map(|(idx, path)| (path.as_str(), idx)).collect();
+
+    let mut declared: Vec<DeclaredTarget> = Vec::new();
+    // (module dir, declared index) for Gradle and Maven modules.
+    let mut module_dirs: Vec<(&str, usize)> = Vec::new();
+    for (idx, system) in build_files {
+        let dir = directory_of(&normalized_paths[idx]);
+        let content = &files[idx].content;
+        match system {
+            BuildSystem::Make => declared.extend(parse_makefile(idx, dir, content, &path_to_idx)),
+            BuildSystem::CMake => {
+                for target in parse_cmake(idx, dir, content, &path_to_idx) {
+                    // `target_sources` and links for a target another CMakeLists.txt declares.
+                    match declared.iter_mut().find(|known| known.name == target.name) {
+                        Some(known) => {
+                            known.members.extend(target.members);
+                            known.depends_on.extend(target.depends_on);
+                            if known.build_file == usize::MAX {
+                                known.build_file = target.build_file;
+                            }
+                        }
+                        None => declared.push(target),
+                    }
+                }
+            }
+            BuildSystem::Gradle => {
+                let depends_on = GRADLE_PROJECT.captures_iter(content).map(|c| c[1].to_string()).collect();
+                let name = format!(":{}", dir.replace('/', ":"));
+                module_dirs.push((dir, declared.len()));
+                declared.push(DeclaredTarget { name, build_file: idx, members: Vec::new(), depends_on });
+            }
+            BuildSystem::Maven => {
+                let (name, depends_on) = parse_pom(content).unwrap_or_else(|| (dir.to_string(), Vec::new()));
+                module_dirs.push((dir, declared.len()));
+                declared.push(DeclaredTarget { name, build_file: idx, members: Vec::new(), depends_on });
+            }
+        }
+    }
+    declared.retain(|target| target.build_file != usize::MAX);
+
+    // Innermost module first, so each file joins the deepest module directory holding it.
+    module_dirs.sort_by_key(|(dir, _)| std::cmp::Reverse(dir.len()));
+    for (idx, path) in normalized_paths.iter().enumerate() {
+        let module = module_dirs
+            .iter()
+            .find(|(dir, _)| dir.is_empty() || path.strip_prefix(dir).is_some_and(|rest| rest.starts_with('/')));
+        if let Some(&(_, target)) = module {
+            declared[target].members.push(idx);
+        }
+    }
+
+    let resolve = |wanted: &str, from: usize| {
+        declared.iter().position(|target| {
+            if target.name == wanted {
+                return true;
+            }
+            // Gradle project paths are matched on their trailing directories, since the scanned
+            // root need not be the Gradle root.
+            wanted.starts_with(':')
+                && wanted.len() > 1
+                && target.name.starts_with(':')
+                && target.name.ends_with(wanted)
+                && target.build_file != from
+        })
+    };
+    declared
+        .iter()
+        .map(|target| {
+            let mut depends_on: Vec<usize> = target
+                .depends_on
+                .iter()
+                .filter_map(|wanted| resolve(wanted, target.build_file))
+                .filter(|&dependency| declared[dependency].name != target.name)
+                .collect();
+            depends_on.sort_unstable();
+            depends_on.dedup();
+            let mut members = target.members.clone();
+            members.sort_unstable();
+            members.dedup();
+            BuildTarget { name: target.name.clone(), build_file: target.build_file, members, depends_on }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each target as `name: members -> dependencies`.
+    fn targets_of(files: &[(&str, &str)]) -> Vec<String> {
+        let files: Vec<FileContent> = files
+            .iter()
+            .map(|(path, content)| FileContent { path: path.to_string(), content: content.to_string(), token_count: None })
+            .collect();
+        let paths: Vec<String> = files.iter().map(|f| normalize_path(&f.path)).collect();
+        let targets = build_targets(&files, &paths);
+        targets
+            .iter()
+            .map(|target| {
+                let members: Vec<&str> = target.members.iter().map(|&idx| files[idx].path.as_str()).collect();
+                let depends_on: Vec<&str> = target.depends_on.iter().map(|&dep| targets[dep].name.as_str()).collect();
+                format!("{}: {} -> {}", target.name, members.join(" "), depends_on.join(" "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn makefile_targets_collect_sources_through_object_rules_and_variables() {
+        let makefile = "CORE_SRCS := core/log.c \\\n  core/buf.c\nCORE_OBJS = $(CORE_SRCS:.c=.o)\n\n\
+            .PHONY: all\nall: app\n\nlibcore.a: $(CORE_OBJS)\n\tar rcs $@ $^\n\n\
+            app: main.o libcore.a\n\t$(CC) -o $@ $^\n\nmain.o: main.c core/log.h\n";
+        let targets = targets_of(&[
+            ("Makefile", makefile),
+            ("main.c", ""),
+            ("core/log.c", ""),
+            ("core/log.h", ""),
+            ("core/buf.c", ""),
+        ]);
+        assert_eq!(
+            targets,
+            ["all:  -> app", "libcore.a: core/log.c core/buf.c -> ", "app: main.c core/log.h -> libcore.a"]
+        );
+    }
+
+    #[test]
+    fn cmake_targets_span_subdirectories() {
+        let root = "set(APP_SOURCES src/main.cpp)\nadd_subdirectory(net)\n\
+            add_executable(app ${APP_SOURCES}) # the binary\ntarget_link_libraries(app PRIVATE net)\n";
+        let net = "add_library(net STATIC socket.cpp)\ntarget_sources(net PRIVATE $<$<BOOL:WIN32>:win.cpp> socket.h)\n";
+        let targets = targets_of(&[
+            ("CMakeLists.txt", root),
+            ("src/main.cpp", ""),
+            ("net/CMakeLists.txt", net),
+            ("net/socket.cpp", ""),
+            ("net/socket.h", ""),
+        ]);
+        assert_eq!(
+            targets,
+            ["app: src/main.cpp -> net", "net: net/socket.cpp net/socket.h -> "]
+        );
+    }
+
+    #[test]
+    fn gradle_and_maven_modules_own_the_files_under_them() {
+        let targets = targets_of(&[
+            ("settings.gradle", "include ':app', ':libs:core'"),
+            ("app/build.gradle.kts", "dependencies { implementation(project(\":libs:core\")) }"),
+            ("app/src/App.java", ""),
+            ("libs/core/build.gradle", ""),
+            ("libs/core/src/Core.java", ""),
+        ]);
+        assert_eq!(
+            targets,
+            [
+                ":app: app/build.gradle.kts app/src/App.java -> :libs:core",
+                ":libs:core: libs/core/build.gradle libs/core/src/Core.java -> ",
+            ]
+        );
+
+        let parent = "<project><artifactId>shop</artifactId><modules><module>api</module></modules></project>";
+        let api = "<project><parent><artifactId>shop</artifactId></parent><artifactId>shop-api</artifactId>\
+            <dependencies><dependency><groupId>x</groupId><artifactId>shop-model</artifactId></dependency></dependencies></project>";
+        let targets = targets_of(&[
+            ("pom.xml", parent),
+            ("README.md", ""),
+            ("api/pom.xml", api),
+            ("api/src/Api.java", ""),
+            ("model/pom.xml", "<project><artifactId>shop-model</artifactId></project>"),
+            ("model/src/Order.java", ""),
+        ]);
+        assert_eq!(
+            targets,
+            [
+                "shop: pom.xml README.md -> ",
+                "shop-api: api/pom.xml api/src/Api.java -> shop-model",
+                "shop-model: model/pom.xml model/src/Order.java -> ",
+            ]
+        );
+    }
+}
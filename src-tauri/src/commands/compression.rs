@@ -0,0 +1,38 @@
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use serde::Serialize;
+use std::io::Write;
+
+/// Serialize `value` to JSON and gzip it. Fast compression: the goal is to cut IPC
+/// transfer and parse time for huge trees and packs, not to minimize size.
+pub(crate) fn gzip_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+    let mut encoder = GzEncoder::new(Vec::with_capacity(json.len() / 4), Compression::fast());
+    encoder.write_all(&json).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+/// Gzipped JSON as a raw IPC response, which the frontend receives as an `ArrayBuffer`
+/// instead of a JSON string it has to parse on the main thread.
+pub(crate) fn gzip_json_response<T: Serialize>(value: &T) -> Result<tauri::ipc::Response, String> {
+    gzip_json(value).map(tauri::ipc::Response::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    // ── gzip_json ──
+
+    #[test]
+    fn gzip_json_round_trips() {
+        let value = vec!["src/main.rs".to_string(); 500];
+        let compressed = gzip_json(&value).unwrap();
+        let mut json = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut json).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<String>>(&json).unwrap(), value);
+        assert!(compressed.len() < json.len() / 10, "repetitive payloads should shrink");
+    }
+}
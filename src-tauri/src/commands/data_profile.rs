@@ -0,0 +1,685 @@
+use crate::models::FileContent;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Data rows shown in a delimited file's profile; files with no more rows than this are packed as-is.
+const SAMPLE_ROWS: usize = 5;
+
+/// Refuse Parquet footers larger than this rather than reading them into memory.
+const MAX_PARQUET_FOOTER_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Extensions profiled on read because their bytes are not text.
+const PROFILED_BINARY_EXTENSIONS: &[&str] = &["parquet"];
+
+pub(crate) fn is_profiled_binary_extension(ext: &str) -> bool {
+    PROFILED_BINARY_EXTENSIONS
+        .iter()
+        .any(|profiled| profiled.eq_ignore_ascii_case(ext))
+}
+
+fn delimiter_for(path: &str) -> Option<u8> {
+    let lower = path.to_ascii_lowercase();
+    if lower.ends_with(".csv") {
+        Some(b',')
+    } else if lower.ends_with(".tsv") || lower.ends_with(".tab") {
+        Some(b'\t')
+    } else {
+        None
+    }
+}
+
+/// One parsed row and the byte range it occupied, so samples can be quoted verbatim.
+struct Record {
+    fields: Vec<String>,
+    end: usize,
+}
+
+/// RFC 4180-style records: quoted fields may hold delimiters, doubled quotes, and newlines.
+fn parse_delimited(content: &str, delimiter: u8) -> Vec<Record> {
+    let bytes = content.as_bytes();
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field: Vec<u8> = Vec::new();
+    let mut in_quotes = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if in_quotes {
+            if b == b'"' && bytes.get(i + 1) == Some(&b'"') {
+                field.push(b'"');
+                i += 1;
+            } else if b == b'"' {
+                in_quotes = false;
+            } else {
+                field.push(b);
+            }
+        } else if b == b'"' && field.is_empty() {
+            in_quotes = true;
+        } else if b == delimiter {
+            fields.push(String::from_utf8_lossy(&field).into_owned());
+            field.clear();
+        } else if b == b'\n' || b == b'\r' {
+            if b == b'\r' && bytes.get(i + 1) == Some(&b'\n') {
+                i += 1;
+            }
+            fields.push(String::from_utf8_lossy(&field).into_owned());
+            field.clear();
+            records.push(Record {
+                fields: std::mem::take(&mut fields),
+                end: i + 1,
+            });
+        } else {
+            field.push(b);
+        }
+        i += 1;
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(String::from_utf8_lossy(&field).into_owned());
+        records.push(Record {
+            fields,
+            end: bytes.len(),
+        });
+    }
+    records.retain(|record| record.fields.iter().any(|field| !field.is_empty()));
+    records
+}
+
+#[derive(Default)]
+struct ColumnStats {
+    empty: usize,
+    integer: usize,
+    decimal: usize,
+    boolean: usize,
+    date: usize,
+    text: usize,
+}
+
+impl ColumnStats {
+    fn observe(&mut self, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            self.empty += 1;
+        } else if value.parse::<i64>().is_ok() {
+            self.integer += 1;
+        } else if value.parse::<f64>().is_ok_and(f64::is_finite) {
+            self.decimal += 1;
+        } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            self.boolean += 1;
+        } else if is_iso_date(value) {
+            self.date += 1;
+        } else {
+            self.text += 1;
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match (
+            self.integer,
+            self.decimal,
+            self.boolean,
+            self.date,
+            self.text,
+        ) {
+            (0, 0, 0, 0, 0) => "empty",
+            (_, _, 0, 0, 0) if self.decimal > 0 => "decimal",
+            (_, 0, 0, 0, 0) => "integer",
+            (0, 0, _, 0, 0) => "boolean",
+            (0, 0, 0, _, 0) => "date",
+            _ => "text",
+        }
+    }
+}
+
+/// `2024-01-31`, optionally followed by a time.
+fn is_iso_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && [0, 1, 2, 3, 5, 6, 8, 9]
+            .iter()
+            .all(|&i| bytes[i].is_ascii_digit())
+        && (bytes.len() == 10 || matches!(bytes[10], b'T' | b' '))
+}
+
+/// Profile of a CSV/TSV file: its header, per-column types and blank counts, and the first
+/// rows verbatim. `None` for other files and for files short enough to pack whole.
+fn profile_delimited(path: &str, content: &str) -> Option<String> {
+    let delimiter = delimiter_for(path)?;
+    let records = parse_delimited(content, delimiter);
+    let (header, rows) = records.split_first()?;
+    if rows.len() <= SAMPLE_ROWS {
+        return None;
+    }
+
+    let mut stats: Vec<ColumnStats> = header
+        .fields
+        .iter()
+        .map(|_| ColumnStats::default())
+        .collect();
+    let mut ragged = 0;
+    for row in rows {
+        if row.fields.len() != header.fields.len() {
+            ragged += 1;
+        }
+        for (column, value) in stats.iter_mut().zip(&row.fields) {
+            column.observe(value);
+        }
+    }
+
+    let mut out = format!(
+        "# Data profile; the {} bytes of rows were omitted.\nRows: {} (plus a header row)\nColumns ({}):\n",
+        content.len(),
+        rows.len(),
+        header.fields.len()
+    );
+    for (name, column) in header.fields.iter().zip(&stats) {
+        out.push_str(&format!("- {}: {}", name.trim(), column.kind()));
+        if column.empty > 0 {
+            out.push_str(&format!(", {} empty", column.empty));
+        }
+        out.push('\n');
+    }
+    if ragged > 0 {
+        out.push_str(&format!("Rows with a different column count: {ragged}\n"));
+    }
+    out.push_str(&format!("First {SAMPLE_ROWS} rows:\n"));
+    out.push_str(content[..rows[SAMPLE_ROWS - 1].end].trim_end());
+    out.push('\n');
+    Some(out)
+}
+
+/// Profiles standing in for the CSV/TSV files among `files`, keyed by index into `files`.
+pub(crate) fn summarize_data_files(files: &[FileContent]) -> HashMap<usize, String> {
+    files
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, file)| {
+            profile_delimited(&file.path, &file.content).map(|profile| (idx, profile))
+        })
+        .collect()
+}
+
+// ── Parquet ──
+//
+// Only the footer is read: the Thrift-encoded `FileMetaData` carries the schema and row count.
+// Decoding pages for sample rows is out of scope.
+
+/// Thrift compact protocol, as far as `FileMetaData` needs it.
+struct CompactReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+const THRIFT_BOOL_TRUE: u8 = 1;
+const THRIFT_BOOL_FALSE: u8 = 2;
+const THRIFT_I32: u8 = 5;
+const THRIFT_I64: u8 = 6;
+const THRIFT_BINARY: u8 = 8;
+const THRIFT_LIST: u8 = 9;
+const THRIFT_STRUCT: u8 = 12;
+
+/// Nesting beyond this is treated as corrupt rather than recursed into.
+const MAX_THRIFT_DEPTH: usize = 64;
+
+impl<'a> CompactReader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let b = *self.bytes.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            value |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn zigzag(&mut self) -> Option<i64> {
+        let n = self.varint()?;
+        Some((n >> 1) as i64 ^ -((n & 1) as i64))
+    }
+
+    fn binary(&mut self) -> Option<&'a [u8]> {
+        let len = usize::try_from(self.varint()?).ok()?;
+        let end = self.pos.checked_add(len)?;
+        let slice = self.bytes.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    /// Next field's id and type, or `None` at the struct's stop byte.
+    fn field_header(&mut self, last_id: &mut i64) -> Option<Option<(i64, u8)>> {
+        let b = self.byte()?;
+        if b == 0 {
+            return Some(None);
+        }
+        let delta = i64::from(b >> 4);
+        *last_id = if delta == 0 {
+            self.zigzag()?
+        } else {
+            *last_id + delta
+        };
+        Some(Some((*last_id, b & 0x0f)))
+    }
+
+    fn list_header(&mut self) -> Option<(usize, u8)> {
+        let b = self.byte()?;
+        let size = match b >> 4 {
+            15 => usize::try_from(self.varint()?).ok()?,
+            size => usize::from(size),
+        };
+        Some((size, b & 0x0f))
+    }
+
+    /// Skip a value of `kind`; `in_list` because list elements store booleans as a byte.
+    fn skip(&mut self, kind: u8, in_list: bool, depth: usize) -> Option<()> {
+        if depth > MAX_THRIFT_DEPTH {
+            return None;
+        }
+        match kind {
+            THRIFT_BOOL_TRUE | THRIFT_BOOL_FALSE => {
+                if in_list {
+                    self.byte()?;
+                }
+            }
+            3 => {
+                self.byte()?;
+            }
+            4 | THRIFT_I32 | THRIFT_I64 => {
+                self.varint()?;
+            }
+            7 => {
+                self.pos = self
+                    .pos
+                    .checked_add(8)
+                    .filter(|&end| end <= self.bytes.len())?;
+            }
+            THRIFT_BINARY => {
+                self.binary()?;
+            }
+            THRIFT_LIST | 10 => {
+                let (size, element) = self.list_header()?;
+                for _ in 0..size {
+                    self.skip(element, true, depth + 1)?;
+                }
+            }
+            11 => {
+                let size = self.varint()?;
+                if size > 0 {
+                    let kinds = self.byte()?;
+                    for _ in 0..size {
+                        self.skip(kinds >> 4, true, depth + 1)?;
+                        self.skip(kinds & 0x0f, true, depth + 1)?;
+                    }
+                }
+            }
+            THRIFT_STRUCT => {
+                let mut last_id = 0;
+                while let Some((_, field_kind)) = self.field_header(&mut last_id)? {
+                    self.skip(field_kind, false, depth + 1)?;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+struct SchemaElement {
+    name: String,
+    physical_type: Option<i64>,
+    repetition: Option<i64>,
+    num_children: i64,
+    converted_type: Option<i64>,
+}
+
+fn read_schema_element(reader: &mut CompactReader) -> Option<SchemaElement> {
+    let mut element = SchemaElement {
+        name: String::new(),
+        physical_type: None,
+        repetition: None,
+        num_children: 0,
+        converted_type: None,
+    };
+    let mut last_id = 0;
+    while let Some((id, kind)) = reader.field_header(&mut last_id)? {
+        match (id, kind) {
+            (1, THRIFT_I32) => element.physical_type = Some(reader.zigzag()?),
+            (3, THRIFT_I32) => element.repetition = Some(reader.zigzag()?),
+            (4, THRIFT_BINARY) => {
+                element.name = String::from_utf8_lossy(reader.binary()?).into_owned()
+            }
+            (5, THRIFT_I32) => element.num_children = reader.zigzag()?,
+            (6, THRIFT_I32) => element.converted_type = Some(reader.zigzag()?),
+            _ => reader.skip(kind, false, 1)?,
+        }
+    }
+    Some(element)
+}
+
+struct ParquetMetadata {
+    schema: Vec<SchemaElement>,
+    num_rows: i64,
+    created_by: Option<String>,
+}
+
+fn read_file_metadata(footer: &[u8]) -> Option<ParquetMetadata> {
+    let mut reader = CompactReader {
+        bytes: footer,
+        pos: 0,
+    };
+    let mut metadata = ParquetMetadata {
+        schema: Vec::new(),
+        num_rows: 0,
+        created_by: None,
+    };
+    let mut last_id = 0;
+    while let Some((id, kind)) = reader.field_header(&mut last_id)? {
+        match (id, kind) {
+            (2, THRIFT_LIST) => {
+                let (size, _) = reader.list_header()?;
+                for _ in 0..size {
+                    metadata.schema.push(read_schema_element(&mut reader)?);
+                }
+            }
+            (3, THRIFT_I64) => metadata.num_rows = reader.zigzag()?,
+            (6, THRIFT_BINARY) => {
+                metadata.created_by = Some(String::from_utf8_lossy(reader.binary()?).into_owned());
+            }
+            _ => reader.skip(kind, false, 1)?,
+        }
+    }
+    Some(metadata)
+}
+
+fn parquet_type_name(element: &SchemaElement) -> &'static str {
+    match (element.converted_type, element.physical_type) {
+        (Some(0), _) => "string",
+        (Some(5), _) => "decimal",
+        (Some(6), _) => "date",
+        (Some(7 | 8), _) => "time",
+        (Some(9 | 10), _) => "timestamp",
+        (Some(19), _) => "json",
+        (_, Some(0)) => "boolean",
+        (_, Some(1)) => "int32",
+        (_, Some(2)) => "int64",
+        (_, Some(3)) => "int96",
+        (_, Some(4)) => "float",
+        (_, Some(5)) => "double",
+        (_, Some(6)) => "binary",
+        (_, Some(7)) => "fixed_len_binary",
+        _ => "unknown",
+    }
+}
+
+/// Leaf columns as dotted paths, walking the flattened schema tree depth-first.
+fn leaf_columns(schema: &[SchemaElement]) -> Vec<(String, &SchemaElement)> {
+    fn walk<'s>(
+        schema: &'s [SchemaElement],
+        pos: &mut usize,
+        prefix: &str,
+        columns: &mut Vec<(String, &'s SchemaElement)>,
+    ) {
+        let Some(element) = schema.get(*pos) else {
+            return;
+        };
+        *pos += 1;
+        let path = if prefix.is_empty() {
+            element.name.clone()
+        } else {
+            format!("{prefix}.{}", element.name)
+        };
+        if element.num_children <= 0 {
+            columns.push((path, element));
+            return;
+        }
+        for _ in 0..element.num_children {
+            walk(schema, pos, &path, columns);
+        }
+    }
+
+    let mut columns = Vec::new();
+    let Some(root) = schema.first() else {
+        return columns;
+    };
+    let mut pos = 1;
+    for _ in 0..root.num_children {
+        walk(schema, &mut pos, "", &mut columns);
+    }
+    columns
+}
+
+fn render_parquet_profile(metadata: &ParquetMetadata, file_len: u64) -> String {
+    let columns = leaf_columns(&metadata.schema);
+    let mut out = format!(
+        "# Parquet data profile; the {file_len} bytes of data were omitted.\nRows: {}\nColumns ({}):\n",
+        metadata.num_rows,
+        columns.len()
+    );
+    for (path, element) in columns {
+        out.push_str(&format!("- {path}: {}", parquet_type_name(element)));
+        match element.repetition {
+            Some(1) => out.push_str(", optional"),
+            Some(2) => out.push_str(", repeated"),
+            _ => {}
+        }
+        out.push('\n');
+    }
+    if let Some(created_by) = &metadata.created_by {
+        out.push_str(&format!("Written by: {created_by}\n"));
+    }
+    out.push_str("Sample rows are not available for Parquet files.\n");
+    out
+}
+
+/// Profile of a Parquet file from its footer: row count and column names and types.
+pub(crate) fn profile_parquet(path: &Path) -> std::io::Result<String> {
+    use std::io::{Error, ErrorKind, Read, Seek, SeekFrom};
+
+    let invalid = |message: &str| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("{}: {message}", path.display()),
+        )
+    };
+    let mut file = std::fs::File::open(path)?;
+    let file_len = file.metadata()?.len();
+    if file_len < 12 {
+        return Err(invalid("too short to be a Parquet file"));
+    }
+    let mut tail = [0u8; 8];
+    file.seek(SeekFrom::End(-8))?;
+    file.read_exact(&mut tail)?;
+    if &tail[4..] != b"PAR1" {
+        return Err(invalid("missing the Parquet magic bytes"));
+    }
+    let footer_len = u64::from(u32::from_le_bytes([tail[0], tail[1], tail[2], tail[3]]));
+    if footer_len > MAX_PARQUET_FOOTER_BYTES || footer_len + 8 > file_len {
+        return Err(invalid("footer length is out of range"));
+    }
+    let mut footer = vec![0u8; footer_len as usize];
+    file.seek(SeekFrom::End(-8 - footer_len as i64))?;
+    file.read_exact(&mut footer)?;
+    let metadata = read_file_metadata(&footer).ok_or_else(|| invalid("unreadable footer"))?;
+    Ok(render_parquet_profile(&metadata, file_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv(rows: usize) -> String {
+        let mut content = String::from("id,name,price,active,created\n");
+        for i in 1..=rows {
+            let name = if i == 2 {
+                String::new()
+            } else {
+                format!("\"Item, #{i}\"")
+            };
+            content.push_str(&format!("{i},{name},{}.5,true,2024-01-{:02}\n", i * 2, i));
+        }
+        content
+    }
+
+    // ── parse_delimited ──
+
+    #[test]
+    fn quoted_fields_keep_delimiters_quotes_and_newlines() {
+        let records = parse_delimited("a,b\r\n\"x, \"\"y\"\"\",\"multi\nline\"\n\n1,2", b',');
+        let fields: Vec<Vec<&str>> = records
+            .iter()
+            .map(|record| record.fields.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                vec!["a", "b"],
+                vec!["x, \"y\"", "multi\nline"],
+                vec!["1", "2"]
+            ]
+        );
+    }
+
+    // ── profile_delimited ──
+
+    #[test]
+    fn csv_profile_lists_column_types_and_samples() {
+        let profile = profile_delimited("data/items.csv", &csv(40)).unwrap();
+        assert!(profile.contains("Rows: 40 (plus a header row)\nColumns (5):\n"));
+        assert!(profile.contains("- id: integer\n- name: text, 1 empty\n- price: decimal\n- active: boolean\n- created: date\n"));
+        assert!(profile.contains(
+            "First 5 rows:\nid,name,price,active,created\n1,\"Item, #1\",2.5,true,2024-01-01\n"
+        ));
+        assert!(profile.ends_with("5,\"Item, #5\",10.5,true,2024-01-05\n"));
+        assert!(!profile.contains("Item, #6"));
+    }
+
+    #[test]
+    fn short_or_unknown_files_are_not_profiled() {
+        assert_eq!(profile_delimited("data/items.csv", &csv(SAMPLE_ROWS)), None);
+        assert_eq!(profile_delimited("data/items.json", &csv(40)), None);
+        let tsv = csv(10).replace(',', "\t");
+        assert!(
+            profile_delimited("data/items.tsv", &tsv)
+                .unwrap()
+                .contains("Columns (5)")
+        );
+    }
+
+    // ── Parquet ──
+
+    /// Field header for a field `delta` ids after the previous one.
+    fn field(bytes: &mut Vec<u8>, delta: u8, kind: u8) {
+        bytes.push((delta << 4) | kind);
+    }
+
+    fn varint(bytes: &mut Vec<u8>, mut n: u64) {
+        while n >= 0x80 {
+            bytes.push((n as u8) | 0x80);
+            n >>= 7;
+        }
+        bytes.push(n as u8);
+    }
+
+    fn zigzag(bytes: &mut Vec<u8>, n: i64) {
+        varint(bytes, ((n << 1) ^ (n >> 63)) as u64);
+    }
+
+    fn string(bytes: &mut Vec<u8>, text: &str) {
+        varint(bytes, text.len() as u64);
+        bytes.extend_from_slice(text.as_bytes());
+    }
+
+    fn schema_element(
+        bytes: &mut Vec<u8>,
+        name: &str,
+        physical: Option<i64>,
+        repetition: i64,
+        children: i64,
+        converted: Option<i64>,
+    ) {
+        let mut last = 0;
+        if let Some(physical) = physical {
+            field(bytes, 1, THRIFT_I32);
+            zigzag(bytes, physical);
+            last = 1;
+        }
+        field(bytes, 3 - last, THRIFT_I32);
+        zigzag(bytes, repetition);
+        field(bytes, 1, THRIFT_BINARY);
+        string(bytes, name);
+        if children > 0 {
+            field(bytes, 1, THRIFT_I32);
+            zigzag(bytes, children);
+        }
+        if let Some(converted) = converted {
+            field(bytes, if children > 0 { 1 } else { 2 }, THRIFT_I32);
+            zigzag(bytes, converted);
+        }
+        bytes.push(0);
+    }
+
+    fn parquet_footer() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        field(&mut bytes, 1, THRIFT_I32);
+        zigzag(&mut bytes, 2);
+        field(&mut bytes, 1, THRIFT_LIST);
+        bytes.push((5 << 4) | THRIFT_STRUCT);
+        schema_element(&mut bytes, "schema", None, 0, 3, None);
+        schema_element(&mut bytes, "id", Some(2), 0, 0, None);
+        schema_element(&mut bytes, "name", Some(6), 1, 0, Some(0));
+        schema_element(&mut bytes, "location", None, 1, 1, None);
+        schema_element(&mut bytes, "lat", Some(5), 1, 0, None);
+        field(&mut bytes, 1, THRIFT_I64);
+        zigzag(&mut bytes, 1234);
+        // row_groups: an empty list, skipped.
+        field(&mut bytes, 1, THRIFT_LIST);
+        bytes.push(THRIFT_STRUCT);
+        field(&mut bytes, 2, THRIFT_BINARY);
+        string(&mut bytes, "parquet-rs version 53.0.0");
+        bytes.push(0);
+        bytes
+    }
+
+    #[test]
+    fn parquet_profile_reads_schema_and_row_count_from_the_footer() {
+        let footer = parquet_footer();
+        let mut file = b"PAR1".to_vec();
+        file.extend_from_slice(&[0u8; 32]);
+        file.extend_from_slice(&footer);
+        file.extend_from_slice(&(footer.len() as u32).to_le_bytes());
+        file.extend_from_slice(b"PAR1");
+        let path =
+            std::env::temp_dir().join(format!("bablusheed-{}.parquet", uuid::Uuid::new_v4()));
+        std::fs::write(&path, &file).unwrap();
+
+        let profile = profile_parquet(&path).unwrap();
+        assert!(profile.contains("Rows: 1234\nColumns (3):\n"));
+        assert!(
+            profile.contains(
+                "- id: int64\n- name: string, optional\n- location.lat: double, optional\n"
+            )
+        );
+        assert!(profile.contains("Written by: parquet-rs version 53.0.0\n"));
+    }
+
+    #[test]
+    fn files_without_the_parquet_magic_are_rejected() {
+        let path =
+            std::env::temp_dir().join(format!("bablusheed-{}.parquet", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"not a parquet file at all").unwrap();
+        assert!(
+            profile_parquet(&path)
+                .unwrap_err()
+                .to_string()
+                .contains("magic")
+        );
+    }
+}
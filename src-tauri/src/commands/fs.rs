@@ -1,11 +1,24 @@
-use crate::models::FileNode;
+use crate::commands::assets::{describe_image, find_image_description, is_describable_image_extension};
+use crate::commands::ast::cyclomatic_complexity;
+use crate::commands::compression::gzip_json_response;
+use crate::commands::data_profile::{is_profiled_binary_extension, profile_parquet};
+use crate::commands::merge::merge3;
+use crate::commands::pack::bytes_per_token;
+use crate::commands::undo::save_undo_snapshot;
+use crate::models::{
+    DegradedMode, FileContent, FileNode, FlatFileNode, FlatWalkResponse, FsError, HashedFileContent,
+    ProjectMetadata, WriteBackResult,
+};
 use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use ignore::WalkBuilder;
+use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
 use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
 use tauri::async_runtime;
 use tokio::fs as tokio_fs;
-use uuid::Uuid;
 
 const BINARY_EXTENSIONS: &[&str] = &[
     "png", "jpg", "jpeg", "gif", "bmp", "ico", "webp", "avif", "tiff", "pdf", "doc", "docx",
@@ -28,15 +41,56 @@ const ALWAYS_EXCLUDED_DIRS: &[&str] = &[
     ".cache",
 ];
 
+/// Files at least this large are memory-mapped instead of read into a buffer.
+const MMAP_THRESHOLD_BYTES: u64 = 256 * 1024;
+
+/// Files larger than this are skipped by `read_directory_contents`.
+pub(crate) const MAX_DIRECTORY_FILE_BYTES: u64 = 1024 * 1024;
+/// `read_directory_contents` stops once this much content has been read.
+const MAX_DIRECTORY_TOTAL_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Metadata lookups sampled by the latency probe before a walk.
+const NETWORK_PROBE_SAMPLES: usize = 8;
+/// A median lookup slower than this marks the root as a network mount. Local disks answer
+/// cached lookups in microseconds; SMB/NFS round trips take milliseconds.
+const NETWORK_LATENCY_THRESHOLD: Duration = Duration::from_millis(4);
+/// Files `read_directory_contents` reads in parallel per batch on a network mount.
+const DEGRADED_READ_BATCH: usize = 32;
+
 #[derive(Default)]
 struct FsScopeState {
     project_roots: Vec<PathBuf>,
+    /// Project roots the latency probe marked as network mounts.
+    network_roots: Vec<PathBuf>,
+    /// Project roots whose volume went away; refused until re-attached.
+    stale_roots: Vec<PathBuf>,
     export_roots: Vec<PathBuf>,
+    /// Single files outside every root that `authorize_file` granted read access to.
+    authorized_files: Vec<PathBuf>,
 }
 
 static FS_SCOPE_STATE: LazyLock<Mutex<FsScopeState>> =
     LazyLock::new(|| Mutex::new(FsScopeState::default()));
 
+/// Upper bound on remembered read hashes; the map is cleared rather than grown past it.
+const READ_HASH_LIMIT: usize = 65_536;
+
+/// Hash of each file's content when `read_file_content` last returned it (or write-back last
+/// wrote it), keyed by canonical path, so write-back can tell if it changed since packing.
+static READ_HASHES: LazyLock<Mutex<HashMap<PathBuf, blake3::Hash>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn remember_read_hash(path: &Path, content: &str) -> blake3::Hash {
+    let hash = blake3::hash(content.as_bytes());
+    if let Ok(mut hashes) = READ_HASHES.lock() {
+        if hashes.len() >= READ_HASH_LIMIT && !hashes.contains_key(path) {
+            hashes.clear();
+        }
+        hashes.insert(path.to_path_buf(), hash);
+    }
+    hash
+}
+
 fn path_has_parent_traversal(path: &Path) -> bool {
     path.components()
         .any(|component| matches!(component, Component::ParentDir))
@@ -68,12 +122,100 @@ fn canonicalize_for_write(path: &Path) -> Result<PathBuf, String> {
 
 fn remember_project_root(root: PathBuf) {
     if let Ok(mut state) = FS_SCOPE_STATE.lock() {
+        state.stale_roots.retain(|stale| stale != &root);
         if !state.project_roots.iter().any(|existing| existing == &root) {
             state.project_roots.push(root);
         }
     }
 }
 
+/// The open project root holding `path` if it is stale, or has just gone missing (then it is
+/// marked stale). Only a root that has vanished is checked on disk.
+fn unavailable_root(path: &Path) -> Option<PathBuf> {
+    let mut state = FS_SCOPE_STATE.lock().ok()?;
+    let root = state.project_roots.iter().find(|root| path.starts_with(root))?.clone();
+    if state.stale_roots.contains(&root) {
+        return Some(root);
+    }
+    if root.exists() {
+        return None;
+    }
+    state.stale_roots.push(root.clone());
+    Some(root)
+}
+
+/// `FsError::RootUnavailable` when `path`'s root is gone, else `message` as is.
+fn root_error(path: &Path, message: String) -> FsError {
+    match unavailable_root(path) {
+        Some(root) => FsError::RootUnavailable { root: root.to_string_lossy().into_owned() },
+        None => FsError::Other { message },
+    }
+}
+
+/// Refuse paths under a root already marked stale, without touching the disk.
+fn ensure_root_attached(path: &Path) -> Result<(), FsError> {
+    let stale = FS_SCOPE_STATE
+        .lock()
+        .ok()
+        .and_then(|state| state.stale_roots.iter().find(|root| path.starts_with(root)).cloned());
+    match stale {
+        Some(root) => Err(FsError::RootUnavailable { root: root.to_string_lossy().into_owned() }),
+        None => Ok(()),
+    }
+}
+
+fn remember_network_root(root: PathBuf) {
+    if let Ok(mut state) = FS_SCOPE_STATE.lock()
+        && !state.network_roots.iter().any(|existing| existing == &root)
+    {
+        state.network_roots.push(root);
+    }
+}
+
+fn is_on_network_root(target: &Path) -> bool {
+    FS_SCOPE_STATE
+        .lock()
+        .is_ok_and(|state| state.network_roots.iter().any(|root| target.starts_with(root)))
+}
+
+/// Median time one metadata lookup takes for entries directly under `root`.
+fn probe_latency(root: &Path) -> Duration {
+    let mut targets: Vec<PathBuf> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .take(NETWORK_PROBE_SAMPLES)
+        .map(|entry| entry.path())
+        .collect();
+    if targets.is_empty() {
+        targets.push(root.to_path_buf());
+    }
+    let mut samples: Vec<Duration> = targets
+        .iter()
+        .map(|target| {
+            let start = Instant::now();
+            let _ = std::fs::metadata(target);
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+/// Degraded settings for a root whose probe took `latency`; `None` for local disks.
+fn degraded_mode(latency: Duration) -> Option<DegradedMode> {
+    if latency < NETWORK_LATENCY_THRESHOLD {
+        return None;
+    }
+    let latency_ms = latency.as_millis() as u64;
+    Some(DegradedMode {
+        latency_ms,
+        notice: format!(
+            "This folder looks like a network mount ({latency_ms} ms per lookup): binary detection by content is off and files are read in larger batches."
+        ),
+    })
+}
+
 fn remember_export_root(root: PathBuf) {
     if let Ok(mut state) = FS_SCOPE_STATE.lock() {
         if !state.export_roots.iter().any(|existing| existing == &root) {
@@ -82,6 +224,14 @@ fn remember_export_root(root: PathBuf) {
     }
 }
 
+fn remember_authorized_file(file: PathBuf) {
+    if let Ok(mut state) = FS_SCOPE_STATE.lock()
+        && !state.authorized_files.iter().any(|existing| existing == &file)
+    {
+        state.authorized_files.push(file);
+    }
+}
+
 fn is_path_allowed(target: &Path) -> bool {
     if let Ok(state) = FS_SCOPE_STATE.lock() {
         state
@@ -94,8 +244,18 @@ fn is_path_allowed(target: &Path) -> bool {
     }
 }
 
+/// `is_path_allowed`, plus the individually authorized files; those are never writable.
+fn is_read_allowed(target: &Path) -> bool {
+    is_path_allowed(target)
+        || FS_SCOPE_STATE
+            .lock()
+            .is_ok_and(|state| state.authorized_files.iter().any(|file| file == target))
+}
+
 fn is_binary_by_extension(ext: &str) -> bool {
-    BINARY_EXTENSIONS.contains(&ext.to_lowercase().as_str())
+    BINARY_EXTENSIONS
+        .iter()
+        .any(|binary| binary.eq_ignore_ascii_case(ext))
 }
 
 fn is_binary_by_content(path: &Path) -> bool {
@@ -109,125 +269,352 @@ fn is_binary_by_content(path: &Path) -> bool {
     false
 }
 
+/// Extensions that are always text, so their content is never sniffed for NUL bytes.
+const TEXT_EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts", "json", "jsonc", "md", "mdx", "txt",
+    "rs", "py", "go", "java", "kt", "kts", "swift", "c", "h", "cc", "cpp", "hpp", "cs", "rb",
+    "php", "html", "htm", "css", "scss", "sass", "less", "vue", "svelte", "astro", "yaml",
+    "yml", "toml", "xml", "svg", "sh", "bash", "zsh", "fish", "ps1", "sql", "graphql", "gql",
+    "proto", "ini", "cfg", "conf", "env", "csv", "tsv", "gradle", "lua", "dart", "ex", "exs",
+    "erl", "hs", "ml", "scala", "clj", "tf", "hcl", "lock", "gitignore", "editorconfig",
+];
+
+/// Directories with fewer files needing a sniff than this are sniffed on the walking thread.
+const PARALLEL_SNIFF_THRESHOLD: usize = 16;
+
+/// When `build_tree` reads file heads to detect binaries that have no binary extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinarySniffing {
+    /// Trust extensions only.
+    Off,
+    /// Sniff only files without a known text extension (default).
+    UnknownExtensions,
+    /// Sniff every file that is not binary by extension.
+    All,
+}
+
+impl BinarySniffing {
+    /// Parse the `binarySniffing` command option: "off", "all", or "unknown" (default).
+    fn from_mode(mode: Option<&str>) -> Self {
+        match mode {
+            Some("off") => Self::Off,
+            Some("all") => Self::All,
+            _ => Self::UnknownExtensions,
+        }
+    }
+
+    fn should_sniff(self, extension: &str) -> bool {
+        match self {
+            Self::Off => false,
+            Self::UnknownExtensions => !TEXT_EXTENSIONS
+                .iter()
+                .any(|text| text.eq_ignore_ascii_case(extension)),
+            Self::All => true,
+        }
+    }
+}
+
+/// How `build_tree` orders the entries of a directory (directories always come first).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TreeOrder {
+    /// Case-insensitive, with digit runs compared by value (`File2` before `File10`), as file
+    /// managers sort (default).
+    Natural,
+    /// Raw byte order of the names.
+    Bytewise,
+}
+
+impl TreeOrder {
+    /// Parse the `sortOrder` command option: "bytewise" or "natural" (default).
+    fn from_mode(mode: Option<&str>) -> Self {
+        match mode {
+            Some("bytewise") => Self::Bytewise,
+            _ => Self::Natural,
+        }
+    }
+
+    fn compare(self, a: &OsStr, b: &OsStr) -> Ordering {
+        match self {
+            Self::Natural => natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()).then_with(|| a.cmp(b)),
+            Self::Bytewise => a.cmp(b),
+        }
+    }
+}
+
+/// Compare names case-insensitively, with runs of ASCII digits compared as numbers.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(digit);
+                    }
+                    digits.trim_start_matches('0').to_string()
+                };
+                let (x, y) = (take_number(&mut a), take_number(&mut b));
+                let order = x.len().cmp(&y.len()).then_with(|| x.cmp(&y));
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let order = x.to_lowercase().cmp(y.to_lowercase());
+                if order != Ordering::Equal {
+                    return order;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Sniff files for binary content, spreading large batches across threads so slow
+/// (e.g. network) filesystems are not read one file at a time.
+fn sniff_binary_contents(paths: &[&Path]) -> Vec<bool> {
+    if paths.len() < PARALLEL_SNIFF_THRESHOLD {
+        return paths.iter().map(|path| is_binary_by_content(path)).collect();
+    }
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(8);
+    let chunk_size = paths.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let handle = scope.spawn(move || {
+                    chunk.iter().map(|path| is_binary_by_content(path)).collect::<Vec<_>>()
+                });
+                (chunk.len(), handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|(len, handle)| handle.join().unwrap_or_else(|_| vec![false; len]))
+            .collect()
+    })
+}
+
+/// Whether `path` may be memory-mapped: it lies under an open project root that is neither
+/// stale nor a network mount. A mapped file truncated underneath us faults on the next read;
+/// local roots make that rare enough, while shares and unplugged drives do not.
+fn mmap_allowed(path: &Path) -> bool {
+    FS_SCOPE_STATE.lock().is_ok_and(|state| {
+        let under = |roots: &[PathBuf]| roots.iter().any(|root| path.starts_with(root));
+        under(&state.project_roots) && !under(&state.network_roots) && !under(&state.stale_roots)
+    })
+}
+
+/// Read a file as (lossy) UTF-8. Large files on local project roots are memory-mapped and
+/// copied straight into the returned `String`; everything else is read into a buffer, and
+/// valid UTF-8 becomes the `String` without a copy.
+pub(crate) fn read_text_file(path: &Path) -> std::io::Result<String> {
+    // Binary data formats and images read as their profile or description, so the tree can
+    // list them and packs describe them.
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    if is_profiled_binary_extension(&extension) {
+        return profile_parquet(path);
+    }
+    if is_describable_image_extension(&extension) {
+        return describe_image(path);
+    }
+    let file = std::fs::File::open(path)?;
+    if file.metadata()?.len() >= MMAP_THRESHOLD_BYTES && mmap_allowed(path) {
+        // SAFETY: the map lives only for the copy below, and only on local, attached roots
+        // (see `mmap_allowed`); a concurrent truncation there is the accepted residual risk.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        return Ok(String::from_utf8_lossy(&map).into_owned());
+    }
+    let mut bytes = Vec::new();
+    std::io::Read::read_to_end(&mut &file, &mut bytes)?;
+    Ok(String::from_utf8(bytes).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()))
+}
+
 fn should_exclude_dir(name: &str) -> bool {
     ALWAYS_EXCLUDED_DIRS.contains(&name)
 }
 
-fn build_tree(
-    root: &Path,
-    dir: &Path,
+/// Node id derived from the relative path, so the same entry keeps its id across
+/// re-walks: 16 hex chars of its blake3 hash.
+fn stable_node_id(relative_path: &str) -> String {
+    let normalized = relative_path.replace('\\', "/");
+    let hash = blake3::hash(normalized.as_bytes());
+    hash.as_bytes()[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Per-walk state shared by every level of `build_tree`.
+struct TreeBuilder<'a> {
+    root: &'a Path,
     respect_gitignore: bool,
-) -> Result<Vec<FileNode>> {
-    let mut entries: Vec<FileNode> = Vec::new();
+    sniffing: BinarySniffing,
+    order: TreeOrder,
+}
 
-    let mut builder = WalkBuilder::new(dir);
-    builder
-        .max_depth(Some(1))
-        .hidden(false)
-        .git_ignore(respect_gitignore)
-        .git_global(false)
-        .git_exclude(false);
+impl TreeBuilder<'_> {
+    fn needs_sniff(&self, path: &Path) -> bool {
+        let extension = path.extension().unwrap_or_default().to_string_lossy();
+        !is_binary_by_extension(&extension)
+            && !is_profiled_binary_extension(&extension)
+            && self.sniffing.should_sniff(&extension)
+    }
 
-    let walker = builder.build();
+    fn build_level(&mut self, dir: &Path) -> Result<Vec<FileNode>> {
+        let mut entries: Vec<FileNode> = Vec::new();
 
-    let mut dir_entries: Vec<_> = walker
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path() != dir)
-        .collect();
+        let mut builder = WalkBuilder::new(dir);
+        builder
+            .max_depth(Some(1))
+            .hidden(false)
+            .git_ignore(self.respect_gitignore)
+            .git_global(false)
+            .git_exclude(false);
 
-    // Sort: dirs first, then files alphabetically
-    dir_entries.sort_by(|a, b| {
-        let a_dir = a.path().is_dir();
-        let b_dir = b.path().is_dir();
-        if a_dir == b_dir {
-            a.path().file_name().cmp(&b.path().file_name())
-        } else if a_dir {
-            std::cmp::Ordering::Less
-        } else {
-            std::cmp::Ordering::Greater
-        }
-    });
+        let walker = builder.build();
 
-    for entry in dir_entries {
-        let path = entry.path();
-        let name = path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
+        // Resolve the directory flag once per entry rather than on every comparison.
+        let mut dir_entries: Vec<(bool, _)> = walker
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path() != dir)
+            .map(|e| (e.path().is_dir(), e))
+            .collect();
 
-        let is_dir = path.is_dir();
+        // Sort: dirs first, then by name in the requested order
+        dir_entries.sort_by(|(a_dir, a), (b_dir, b)| {
+            b_dir
+                .cmp(a_dir)
+                .then_with(|| self.order.compare(a.file_name(), b.file_name()))
+        });
 
-        // Skip always-excluded directories
-        if is_dir && should_exclude_dir(&name) {
-            continue;
-        }
+        // Sniff up front so the reads for this directory can run in parallel.
+        let sniff_targets: Vec<&Path> = dir_entries
+            .iter()
+            .filter(|(is_dir, entry)| !is_dir && self.needs_sniff(entry.path()))
+            .map(|(_, entry)| entry.path())
+            .collect();
+        let binary_by_content: HashSet<&Path> = sniff_targets
+            .iter()
+            .zip(sniff_binary_contents(&sniff_targets))
+            .filter(|(_, is_binary)| *is_binary)
+            .map(|(path, _)| *path)
+            .collect();
 
-        // Skip .DS_Store and similar
-        if name == ".DS_Store" || name == "Thumbs.db" {
-            continue;
-        }
+        for (is_dir, entry) in &dir_entries {
+            let is_dir = *is_dir;
+            let path = entry.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
 
-        let extension = path
-            .extension()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_lowercase()
-            .to_string();
+            // Skip always-excluded directories
+            if is_dir && should_exclude_dir(&name) {
+                continue;
+            }
 
-        let relative_path = path
-            .strip_prefix(root)
-            .unwrap_or(path)
-            .to_string_lossy()
-            .to_string();
+            // Skip .DS_Store and similar
+            if name == ".DS_Store" || name == "Thumbs.db" {
+                continue;
+            }
 
-        let metadata = std::fs::metadata(path).ok();
-        let size = metadata.map(|m| if is_dir { 0 } else { m.len() }).unwrap_or(0);
+            let raw_extension = path.extension().unwrap_or_default().to_string_lossy();
 
-        // Skip binary files
-        if !is_dir && (is_binary_by_extension(&extension) || is_binary_by_content(path)) {
-            continue;
-        }
+            // Skip binary files, except images with a description to stand in for them
+            if !is_dir
+                && (is_binary_by_extension(&raw_extension) || binary_by_content.contains(path))
+                && !(is_describable_image_extension(&raw_extension) && find_image_description(path).is_some())
+            {
+                continue;
+            }
 
-        let id = Uuid::new_v4().to_string();
+            let extension = if raw_extension.bytes().any(|b| b.is_ascii_uppercase()) {
+                raw_extension.to_lowercase()
+            } else {
+                raw_extension.into_owned()
+            };
 
-        let children = if is_dir {
-            Some(build_tree(root, path, respect_gitignore)?)
-        } else {
-            None
-        };
+            let relative_path = path
+                .strip_prefix(self.root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned();
 
-        entries.push(FileNode {
-            id,
-            path: path.to_string_lossy().to_string(),
-            relative_path,
-            name,
-            extension,
-            size,
-            is_dir,
-            children,
-        });
+            let size = if is_dir {
+                0
+            } else {
+                std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+            };
+
+            let id = stable_node_id(&relative_path);
+
+            let children = if is_dir {
+                Some(self.build_level(path)?)
+            } else {
+                None
+            };
+
+            entries.push(FileNode {
+                id,
+                path: path.to_string_lossy().into_owned(),
+                relative_path,
+                name: name.into_owned(),
+                extension,
+                size,
+                is_dir,
+                children,
+            });
+        }
+
+        Ok(entries)
     }
+}
 
-    Ok(entries)
+fn build_tree(
+    root: &Path,
+    dir: &Path,
+    respect_gitignore: bool,
+    sniffing: BinarySniffing,
+    order: TreeOrder,
+) -> Result<Vec<FileNode>> {
+    TreeBuilder {
+        root,
+        respect_gitignore,
+        sniffing,
+        order,
+    }
+    .build_level(dir)
 }
 
-#[tauri::command]
-pub async fn walk_directory(
-    path: String,
+/// Walk `path` into a tree, probing its latency first: a root that looks like a network
+/// mount is walked without content sniffing and returned with its `DegradedMode`.
+fn walk_tree(
+    path: &str,
     respect_gitignore: bool,
-    custom_ignore_patterns: Vec<String>,
-) -> Result<Vec<FileNode>, String> {
-    let root = Path::new(&path);
+    custom_ignore_patterns: &[String],
+    sniffing: BinarySniffing,
+    order: TreeOrder,
+) -> Result<(Vec<FileNode>, Option<DegradedMode>), FsError> {
+    let root = Path::new(path);
     if !root.exists() || !root.is_dir() {
-        return Err(format!(
+        return Err(root_error(root, format!(
             "Path does not exist or is not a directory: {}",
             path
-        ));
+        )));
     }
 
-    let mut nodes = build_tree(root, root, respect_gitignore).map_err(|e| e.to_string())?;
+    let degraded = degraded_mode(probe_latency(root));
+    let sniffing = if degraded.is_some() { BinarySniffing::Off } else { sniffing };
+    let mut nodes = build_tree(root, root, respect_gitignore, sniffing, order).map_err(|e| root_error(root, e.to_string()))?;
     if let Ok(canonical_root) = canonicalize_existing_path(root) {
+        if degraded.is_some() {
+            remember_network_root(canonical_root.clone());
+        }
         remember_project_root(canonical_root);
     }
 
@@ -258,33 +645,288 @@ pub async fn walk_directory(
         }
     }
 
-    Ok(nodes)
+    Ok((nodes, degraded))
+}
+
+/// Pre-order flattening, so every parent precedes its children.
+fn flatten_nodes(nodes: Vec<FileNode>, parent_index: Option<usize>, flat: &mut Vec<FlatFileNode>) {
+    for node in nodes {
+        let index = flat.len();
+        flat.push(FlatFileNode {
+            id: node.id,
+            path: node.path,
+            relative_path: node.relative_path,
+            name: node.name,
+            extension: node.extension,
+            size: node.size,
+            is_dir: node.is_dir,
+            parent_index,
+        });
+        if let Some(children) = node.children {
+            flatten_nodes(children, Some(index), flat);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn walk_directory(
+    path: String,
+    respect_gitignore: bool,
+    custom_ignore_patterns: Vec<String>,
+    binary_sniffing: Option<String>,
+    sort_order: Option<String>,
+) -> Result<Vec<FileNode>, FsError> {
+    let sniffing = BinarySniffing::from_mode(binary_sniffing.as_deref());
+    let order = TreeOrder::from_mode(sort_order.as_deref());
+    walk_tree(&path, respect_gitignore, &custom_ignore_patterns, sniffing, order).map(|(nodes, _)| nodes)
+}
+
+/// Same tree as `walk_directory`, as a flat array with parent indices. Much cheaper to
+/// serialize and parse than deeply nested JSON for huge trees. Carries the `DegradedMode`
+/// when the root looked like a network mount.
+#[tauri::command]
+pub async fn walk_directory_flat(
+    path: String,
+    respect_gitignore: bool,
+    custom_ignore_patterns: Vec<String>,
+    binary_sniffing: Option<String>,
+    sort_order: Option<String>,
+) -> Result<FlatWalkResponse, FsError> {
+    let sniffing = BinarySniffing::from_mode(binary_sniffing.as_deref());
+    let order = TreeOrder::from_mode(sort_order.as_deref());
+    let (nodes, degraded) = walk_tree(&path, respect_gitignore, &custom_ignore_patterns, sniffing, order)?;
+    let mut flat = Vec::new();
+    flatten_nodes(nodes, None, &mut flat);
+    Ok(FlatWalkResponse { nodes: flat, degraded })
 }
 
+/// `walk_directory_flat` as gzipped JSON, for monorepos where the JSON payload stalls IPC.
 #[tauri::command]
-pub async fn read_file_content(path: String) -> Result<String, String> {
+pub async fn walk_directory_flat_compressed(
+    path: String,
+    respect_gitignore: bool,
+    custom_ignore_patterns: Vec<String>,
+    binary_sniffing: Option<String>,
+    sort_order: Option<String>,
+) -> Result<tauri::ipc::Response, FsError> {
+    let flat =
+        walk_directory_flat(path, respect_gitignore, custom_ignore_patterns, binary_sniffing, sort_order).await?;
+    Ok(gzip_json_response(&flat)?)
+}
+
+#[tauri::command]
+pub async fn read_file_content(path: String) -> Result<String, FsError> {
+    read_allowed_file(path).await.map(|(content, _)| content)
+}
+
+/// `read_file_content` plus the blake3 hash `pack_files` fingerprints the content by, so cached
+/// contents and pack fingerprints can be checked against disk without reading the file again.
+#[tauri::command]
+pub async fn read_file_content_hashed(path: String) -> Result<HashedFileContent, FsError> {
+    let (content, hash) = read_allowed_file(path).await?;
+    Ok(HashedFileContent {
+        content,
+        hash: hash.to_hex().to_string(),
+    })
+}
+
+async fn read_allowed_file(path: String) -> Result<(String, blake3::Hash), FsError> {
     let file_path = PathBuf::from(&path);
     if path_has_parent_traversal(&file_path) {
-        return Err(format!("Parent traversal is not allowed: {path}"));
+        return Err(format!("Parent traversal is not allowed: {path}").into());
     }
+    ensure_root_attached(&file_path)?;
     let metadata = tokio_fs::metadata(&file_path).await.map_err(|_| {
-        format!("Path does not exist or is not a file: {}", path)
+        root_error(&file_path, format!("Path does not exist or is not a file: {}", path))
     })?;
     if !metadata.is_file() {
-        return Err(format!("Path does not exist or is not a file: {}", path));
+        return Err(format!("Path does not exist or is not a file: {}", path).into());
     }
 
     let canonical_path = tokio_fs::canonicalize(&file_path)
         .await
-        .map_err(|e| e.to_string())?;
-    if !is_path_allowed(&canonical_path) {
+        .map_err(|e| root_error(&file_path, e.to_string()))?;
+    if !is_read_allowed(&canonical_path) {
+        return Err(format!("Read path is outside allowed roots: {}", path).into());
+    }
+
+    async_runtime::spawn_blocking(move || {
+        let content = read_text_file(&canonical_path).map_err(|e| root_error(&canonical_path, e.to_string()))?;
+        let hash = remember_read_hash(&canonical_path, &content);
+        Ok((content, hash))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Read a file by the path it was packed under: absolute, or relative to an open project
+/// root (most recently opened first).
+pub(crate) fn read_project_file(path: &str) -> Result<String, String> {
+    let canonical_path = resolve_project_file(path)?;
+    read_text_file(&canonical_path).map_err(|e| e.to_string())
+}
+
+/// Canonical location of an existing file named like `read_project_file` takes it, refusing
+/// traversal and anything outside the allowed roots.
+pub(crate) fn resolve_project_file(path: &str) -> Result<PathBuf, String> {
+    let requested = Path::new(path);
+    if path_has_parent_traversal(requested) {
+        return Err(format!("Parent traversal is not allowed: {path}"));
+    }
+    let candidates: Vec<PathBuf> = if requested.is_absolute() {
+        vec![requested.to_path_buf()]
+    } else {
+        let state = FS_SCOPE_STATE.lock().map_err(|e| e.to_string())?;
+        state.project_roots.iter().rev().map(|root| root.join(requested)).collect()
+    };
+    let Some(file) = candidates.iter().find(|candidate| candidate.is_file()) else {
+        // An absolute path under a vanished root says so, rather than "not found".
+        let unavailable = requested.is_absolute().then(|| unavailable_root(requested)).flatten();
+        return Err(match unavailable {
+            Some(root) => FsError::RootUnavailable { root: root.to_string_lossy().into_owned() }.to_string(),
+            None => format!("File not found in any open project: {path}"),
+        });
+    };
+
+    let canonical_path = canonicalize_existing_path(file)?;
+    if !is_read_allowed(&canonical_path) {
         return Err(format!("Read path is outside allowed roots: {}", path));
     }
+    Ok(canonical_path)
+}
 
-    let bytes = tokio_fs::read(&canonical_path)
-        .await
+/// Resolve `relative` under the open project `root` for writing. Absolute paths, parent
+/// traversal, and symlinks that lead outside the root are refused.
+pub(crate) fn resolve_in_project(root: &str, relative: &str) -> Result<PathBuf, String> {
+    let requested = Path::new(relative);
+    if requested.is_absolute() || path_has_parent_traversal(requested) {
+        return Err(format!("Path must stay inside the project: {relative}"));
+    }
+    let canonical_root = canonicalize_existing_path(Path::new(root))?;
+    let is_open = FS_SCOPE_STATE
+        .lock()
+        .map_err(|e| e.to_string())?
+        .project_roots
+        .contains(&canonical_root);
+    if !is_open {
+        return Err(format!("Project root is not open: {root}"));
+    }
+    let target = canonicalize_for_write(&canonical_root.join(requested))?;
+    if !target.starts_with(&canonical_root) {
+        return Err(format!("Path must stay inside the project: {relative}"));
+    }
+    Ok(target)
+}
+
+/// Read every text file under `dir` in one call. `filters` are globs matched against the
+/// path relative to `dir` or the file name; an empty list keeps everything. Files over
+/// `MAX_DIRECTORY_FILE_BYTES` are skipped and reading stops at `MAX_DIRECTORY_TOTAL_BYTES`.
+/// Under a network root, files are read `DEGRADED_READ_BATCH` at a time in parallel.
+#[tauri::command]
+pub async fn read_directory_contents(
+    dir: String,
+    recursive: bool,
+    filters: Vec<String>,
+) -> Result<Vec<FileContent>, FsError> {
+    let dir_path = PathBuf::from(&dir);
+    if path_has_parent_traversal(&dir_path) {
+        return Err(format!("Parent traversal is not allowed: {dir}").into());
+    }
+    ensure_root_attached(&dir_path)?;
+    if !dir_path.is_dir() {
+        return Err(root_error(&dir_path, format!("Path does not exist or is not a directory: {}", dir)));
+    }
+    let canonical_dir = canonicalize_existing_path(&dir_path)?;
+    if !is_path_allowed(&canonical_dir) {
+        return Err(format!("Read path is outside allowed roots: {}", dir).into());
+    }
+    let batch = if is_on_network_root(&canonical_dir) { DEGRADED_READ_BATCH } else { 1 };
+
+    let patterns: Vec<glob::Pattern> = filters
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+
+    async_runtime::spawn_blocking(move || -> Result<Vec<FileContent>, FsError> {
+        let nodes = build_tree(&dir_path, &dir_path, true, BinarySniffing::UnknownExtensions, TreeOrder::Natural)
+            .map_err(|e| root_error(&canonical_dir, e.to_string()))?;
+        let mut files = Vec::new();
+        if recursive {
+            collect_file_nodes(&nodes, &mut files);
+        } else {
+            files.extend(nodes.iter().filter(|node| !node.is_dir));
+        }
+
+        let mut contents = Vec::new();
+        let mut total_bytes = 0;
+        let mut pending: Vec<&FileNode> = Vec::new();
+        for file in files {
+            let matches_filters = patterns.is_empty()
+                || patterns
+                    .iter()
+                    .any(|p| p.matches(&file.relative_path) || p.matches(&file.name));
+            if !matches_filters || file.size > MAX_DIRECTORY_FILE_BYTES {
+                continue;
+            }
+            if total_bytes + file.size > MAX_DIRECTORY_TOTAL_BYTES {
+                break;
+            }
+            total_bytes += file.size;
+            pending.push(file);
+        }
+        for chunk in pending.chunks(batch) {
+            let read: Vec<std::io::Result<String>> = if chunk.len() == 1 {
+                vec![read_text_file(Path::new(&chunk[0].path))]
+            } else {
+                std::thread::scope(|scope| {
+                    let handles: Vec<_> = chunk
+                        .iter()
+                        .map(|file| scope.spawn(move || read_text_file(Path::new(&file.path))))
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|handle| handle.join().unwrap_or_else(|_| Err(std::io::Error::other("read panicked"))))
+                        .collect()
+                })
+            };
+            for (file, content) in chunk.iter().zip(read) {
+                let Ok(content) = content else {
+                    // One unreadable file is skipped; a vanished drive fails the whole read.
+                    if let Some(root) = unavailable_root(&canonical_dir) {
+                        return Err(FsError::RootUnavailable { root: root.to_string_lossy().into_owned() });
+                    }
+                    continue;
+                };
+                contents.push(FileContent {
+                    path: file.path.clone(),
+                    content,
+                    token_count: None,
+                });
+            }
+        }
+        Ok(contents)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Every file the tree walk finds under `root`, which must lie in an open project root.
+pub(crate) fn walk_allowed_files(root: &str) -> Result<Vec<FileNode>, String> {
+    let root_path = PathBuf::from(root);
+    if path_has_parent_traversal(&root_path) {
+        return Err(format!("Parent traversal is not allowed: {root}"));
+    }
+    if !root_path.is_dir() {
+        return Err(format!("Path does not exist or is not a directory: {}", root));
+    }
+    if !is_path_allowed(&canonicalize_existing_path(&root_path)?) {
+        return Err(format!("Read path is outside allowed roots: {}", root));
+    }
+    let nodes = build_tree(&root_path, &root_path, true, BinarySniffing::UnknownExtensions, TreeOrder::Natural)
         .map_err(|e| e.to_string())?;
-    Ok(String::from_utf8_lossy(&bytes).into_owned())
+    let mut files = Vec::new();
+    collect_file_nodes(&nodes, &mut files);
+    Ok(files.into_iter().cloned().collect())
 }
 
 #[tauri::command]
@@ -301,12 +943,66 @@ pub async fn authorize_export_directory(path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Grant read access to one file outside the project roots (a shared config from a sibling
+/// repo, say) without opening its directory. Writes to it are still refused.
 #[tauri::command]
-pub async fn write_file_content(path: String, content: String) -> Result<(), String> {
+pub async fn authorize_file(path: String) -> Result<(), String> {
     let file_path = PathBuf::from(&path);
     if path_has_parent_traversal(&file_path) {
         return Err(format!("Parent traversal is not allowed: {path}"));
     }
+    if !file_path.is_file() {
+        return Err(format!("Path does not exist or is not a file: {}", path));
+    }
+    let canonical = canonicalize_existing_path(&file_path)?;
+    remember_authorized_file(canonical);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn write_file_content(path: String, content: String) -> Result<(), FsError> {
+    let target = PathBuf::from(&path);
+    ensure_root_attached(&target)?;
+    write_file_bytes(path, content.into_bytes())
+        .await
+        .map_err(|message| root_error(&target, message))
+}
+
+/// Re-attach a project root marked stale after its volume went away, once it is back
+/// (e.g. the drive was plugged in again). Reads and writes under it work again. Only a root
+/// marked stale can be re-attached; this grants no access to any other directory.
+#[tauri::command]
+pub async fn reattach_root(path: String) -> Result<(), FsError> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(FsError::RootUnavailable { root: path });
+    }
+    let root = canonicalize_existing_path(&root)?;
+    let mut state = FS_SCOPE_STATE.lock().map_err(|e| e.to_string())?;
+    if !state.stale_roots.contains(&root) {
+        return Err(FsError::from(format!("Path is not a disconnected project root: {path}")));
+    }
+    state.stale_roots.retain(|stale| stale != &root);
+    Ok(())
+}
+
+/// `write_file_content` for binary payloads, under the same traversal and root checks.
+pub(crate) async fn write_file_bytes(path: String, content: Vec<u8>) -> Result<(), String> {
+    let canonical_target = resolve_write_target(&path)?;
+    async_runtime::spawn_blocking(move || write_to_disk(&canonical_target, &content))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(())
+}
+
+/// Canonical target for writing `path`, refusing traversal, directories, and anything outside
+/// the open project and export roots.
+pub(crate) fn resolve_write_target(path: &str) -> Result<PathBuf, String> {
+    let file_path = PathBuf::from(path);
+    if path_has_parent_traversal(&file_path) {
+        return Err(format!("Parent traversal is not allowed: {path}"));
+    }
 
     if let Ok(metadata) = std::fs::metadata(&file_path) {
         if metadata.is_dir() {
@@ -318,24 +1014,299 @@ pub async fn write_file_content(path: String, content: String) -> Result<(), Str
     if !is_path_allowed(&canonical_target) {
         return Err(format!("Write path is outside allowed roots: {}", path));
     }
+    Ok(canonical_target)
+}
+
+/// Write `content` to a resolved target, keeping what it replaces on the undo stack.
+fn write_to_disk(path: &Path, content: &[u8]) -> Result<(), String> {
+    save_undo_snapshot(path)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
 
-    let write_path = canonical_target.clone();
-    async_runtime::spawn_blocking(move || -> Result<(), String> {
-        if let Some(parent) = write_path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+/// Decide what write-back stores at `path`: `proposed` when the file is missing, was never
+/// read, or is unchanged since it was read; otherwise a three-way merge from `base`, which
+/// must be the content that was read.
+fn plan_write_back(path: &Path, display: &str, proposed: String, base: Option<&str>) -> WriteBackResult {
+    let mut result = WriteBackResult {
+        path: display.to_string(),
+        status: "written".to_string(),
+        conflicts: Vec::new(),
+        merged: Some(proposed),
+        message: None,
+    };
+    let read_hash = READ_HASHES.lock().ok().and_then(|hashes| hashes.get(path).copied());
+    let (Some(read_hash), Ok(disk)) = (read_hash, read_text_file(path)) else {
+        return result;
+    };
+    if blake3::hash(disk.as_bytes()) == read_hash {
+        return result;
+    }
+
+    result.status = "conflict".to_string();
+    let Some(base) = base.filter(|base| blake3::hash(base.as_bytes()) == read_hash) else {
+        result.merged = None;
+        result.message = Some("File changed on disk since it was packed and no base was given to merge from".to_string());
+        return result;
+    };
+    let merge = merge3(base, &disk, result.merged.as_deref().unwrap_or_default());
+    result.merged = Some(merge.text);
+    if merge.conflicts.is_empty() {
+        result.status = "merged".to_string();
+    }
+    result.conflicts = merge.conflicts;
+    result
+}
+
+/// Write model-proposed `content` to a project file without losing edits made since it was
+/// packed. When the file changed on disk, `base` (the content as read for packing) is merged
+/// three ways; overlapping edits return a conflict report and nothing is written.
+#[tauri::command]
+pub async fn write_back_file(path: String, content: String, base: Option<String>) -> Result<WriteBackResult, String> {
+    let canonical_target = resolve_write_target(&path)?;
+    async_runtime::spawn_blocking(move || {
+        let mut result = plan_write_back(&canonical_target, &path, content, base.as_deref());
+        if result.status != "conflict" {
+            let written = result.merged.take().unwrap_or_default();
+            write_to_disk(&canonical_target, written.as_bytes())?;
+            remember_read_hash(&canonical_target, &written);
         }
-        std::fs::write(&write_path, content).map_err(|e| e.to_string())
+        Ok(result)
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| e.to_string())?
+}
 
-    Ok(())
+/// Lines of unchanged context `diff_against_disk` keeps around each change.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Unified diff from `before` to `after`; `before` is `None` for a file that does not exist.
+fn unified_diff(label: &str, before: Option<&str>, after: &str) -> String {
+    let old_header = if before.is_some() { format!("a/{label}") } else { "/dev/null".to_string() };
+    similar::TextDiff::from_lines(before.unwrap_or_default(), after)
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_LINES)
+        .header(&old_header, &format!("b/{label}"))
+        .to_string()
+}
+
+/// What writing `new_content` to `path` would change, as a unified diff; empty when nothing
+/// would. Diffing here keeps large files off the UI thread.
+#[tauri::command]
+pub async fn diff_against_disk(path: String, new_content: String) -> Result<String, String> {
+    let canonical_target = resolve_write_target(&path)?;
+    async_runtime::spawn_blocking(move || {
+        let before = match read_text_file(&canonical_target) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.to_string()),
+        };
+        let label = path.trim_start_matches('/');
+        Ok(unified_diff(label, before.as_deref(), &new_content))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn collect_file_nodes<'a>(nodes: &'a [FileNode], files: &mut Vec<&'a FileNode>) {
+    for node in nodes {
+        match &node.children {
+            Some(children) => collect_file_nodes(children, files),
+            None if !node.is_dir => files.push(node),
+            None => {}
+        }
+    }
+}
+
+/// Count how often each path appears in `git log --name-only --format=` output.
+fn count_git_changes(log_output: &str) -> HashMap<String, u64> {
+    let mut counts = HashMap::new();
+    for line in log_output.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        *counts.entry(line.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Commits `git_recency_ranks` looks back over.
+const RECENCY_COMMITS: usize = 200;
+
+/// Rank each path by the first time it appears in newest-first `git log --name-only
+/// --format=` output: 0 for the most recently changed file.
+fn rank_git_recency(log_output: &str) -> HashMap<String, usize> {
+    let mut ranks = HashMap::new();
+    for line in log_output.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let next = ranks.len();
+        ranks.entry(line.to_string()).or_insert(next);
+    }
+    ranks
+}
+
+/// Recency ranks (see `rank_git_recency`) of the files changed in the last `RECENCY_COMMITS`
+/// commits under `root`, keyed by path relative to it; empty outside a git work tree.
+pub(crate) fn git_recency_ranks(root: &Path) -> HashMap<String, usize> {
+    let max_count = format!("--max-count={RECENCY_COMMITS}");
+    run_git(root, &["log", &max_count, "--name-only", "--format=", "--relative", "--", "."])
+        .map(|log| rank_git_recency(&log))
+        .unwrap_or_default()
+}
+
+fn git_change_counts(root: &Path) -> Result<HashMap<String, u64>, String> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "--name-only", "--format=", "--relative", "--", "."])
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(count_git_changes(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// `name` and `version` from the `[package]` table of a Cargo manifest. Inherited
+/// (`version.workspace = true`) and non-string values are ignored.
+fn cargo_package_metadata(manifest: &str) -> (Option<String>, Option<String>) {
+    let (mut name, mut version) = (None, None);
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some(value) = value.trim().strip_prefix('"').and_then(|rest| rest.split_once('"')).map(|(text, _)| text.to_string()) else {
+            continue;
+        };
+        match key.trim() {
+            "name" => name = Some(value),
+            "version" => version = Some(value),
+            _ => {}
+        }
+    }
+    (name, version)
+}
+
+fn package_json_metadata(manifest: &str) -> (Option<String>, Option<String>) {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(manifest) else {
+        return (None, None);
+    };
+    let field = |key: &str| json.get(key).and_then(|value| value.as_str()).map(str::to_string);
+    (field("name"), field("version"))
+}
+
+fn run_git(root: &Path, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git").arg("-C").arg(root).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Name and version from `package.json` (preferred) or `Cargo.toml`, plus the git
+/// `HEAD` commit. Missing manifests or a missing git binary leave fields empty.
+fn detect_project_metadata(root: &Path) -> ProjectMetadata {
+    let read = |file: &str| std::fs::read_to_string(root.join(file)).ok();
+    let (mut name, mut version) = read("package.json").map(|text| package_json_metadata(&text)).unwrap_or_default();
+    if name.is_none() || version.is_none() {
+        let (cargo_name, cargo_version) = read("Cargo.toml").map(|text| cargo_package_metadata(&text)).unwrap_or_default();
+        name = name.or(cargo_name);
+        version = version.or(cargo_version);
+    }
+
+    let commit = run_git(root, &["rev-parse", "HEAD"]).filter(|hash| !hash.is_empty());
+    let dirty = commit.is_some()
+        && run_git(root, &["status", "--porcelain", "--untracked-files=no"]).is_some_and(|status| !status.is_empty());
+    ProjectMetadata { name, version, commit, dirty }
+}
+
+/// Project name, version, and commit for provenance headers on packs.
+#[tauri::command]
+pub async fn get_project_metadata(root: String) -> Result<ProjectMetadata, String> {
+    let root_path = PathBuf::from(&root);
+    if path_has_parent_traversal(&root_path) {
+        return Err(format!("Parent traversal is not allowed: {root}"));
+    }
+    if !root_path.is_dir() {
+        return Err(format!("Path does not exist or is not a directory: {}", root));
+    }
+    if !is_path_allowed(&canonicalize_existing_path(&root_path)?) {
+        return Err(format!("Read path is outside allowed roots: {}", root));
+    }
+    async_runtime::spawn_blocking(move || detect_project_metadata(&root_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Per-file values for coloring the tree, keyed by absolute path. `metric` is
+/// "tokens" (estimated), "complexity" (cyclomatic, AST-supported files only), or
+/// "churn" (number of commits touching the file).
+#[tauri::command]
+pub async fn get_heatmap(root: String, metric: String) -> Result<HashMap<String, u64>, String> {
+    let root_path = PathBuf::from(&root);
+    if path_has_parent_traversal(&root_path) {
+        return Err(format!("Parent traversal is not allowed: {root}"));
+    }
+    if !root_path.is_dir() {
+        return Err(format!("Path does not exist or is not a directory: {}", root));
+    }
+    if !is_path_allowed(&canonicalize_existing_path(&root_path)?) {
+        return Err(format!("Read path is outside allowed roots: {}", root));
+    }
+
+    async_runtime::spawn_blocking(move || compute_heatmap(&root_path, &metric))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn compute_heatmap(root_path: &Path, metric: &str) -> Result<HashMap<String, u64>, String> {
+    let nodes = build_tree(root_path, root_path, true, BinarySniffing::UnknownExtensions, TreeOrder::Natural)
+        .map_err(|e| e.to_string())?;
+    let mut files = Vec::new();
+    collect_file_nodes(&nodes, &mut files);
+
+    let mut heatmap = HashMap::new();
+    match metric {
+        "tokens" => {
+            for file in files {
+                let tokens = (file.size as f64 / bytes_per_token(&file.path).unwrap_or(4.0)).ceil() as u64;
+                heatmap.insert(file.path.clone(), tokens.max(1));
+            }
+        }
+        "complexity" => {
+            for file in files {
+                let Ok(content) = read_text_file(Path::new(&file.path)) else {
+                    continue;
+                };
+                if let Some(complexity) = cyclomatic_complexity(&file.path, &content) {
+                    heatmap.insert(file.path.clone(), complexity as u64);
+                }
+            }
+        }
+        "churn" => {
+            let counts = git_change_counts(root_path)?;
+            for file in files {
+                let key = file.relative_path.replace('\\', "/");
+                heatmap.insert(file.path.clone(), counts.get(&key).copied().unwrap_or(0));
+            }
+        }
+        _ => return Err(format!("Unknown heatmap metric: {metric}")),
+    }
+
+    Ok(heatmap)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::Path;
+    use uuid::Uuid;
 
     // ── path_has_parent_traversal ──
 
@@ -414,4 +1385,476 @@ mod tests {
         let canonical = result.unwrap();
         assert!(canonical.to_string_lossy().contains("nonexistent_test_file.txt"));
     }
+
+    // ── get_heatmap ──
+
+    #[test]
+    fn count_git_changes_tallies_paths() {
+        let counts = count_git_changes("src/a.ts\nsrc/b.ts\n\nsrc/a.ts\n");
+        assert_eq!(counts.get("src/a.ts"), Some(&2));
+        assert_eq!(counts.get("src/b.ts"), Some(&1));
+    }
+
+    #[test]
+    fn rank_git_recency_keeps_each_paths_newest_change() {
+        let ranks = rank_git_recency("src/b.ts\n\nsrc/a.ts\nsrc/b.ts\nsrc/c.ts\n");
+        assert_eq!(ranks.get("src/b.ts"), Some(&0));
+        assert_eq!(ranks.get("src/a.ts"), Some(&1));
+        assert_eq!(ranks.get("src/c.ts"), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn get_heatmap_reports_tokens_and_complexity() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-heatmap-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.ts"), "if (x) { y(); }\n").unwrap();
+        std::fs::write(dir.join("notes.md"), "12345678").unwrap();
+        let root = dir.to_string_lossy().to_string();
+        let ts_path = dir.join("src/a.ts").to_string_lossy().to_string();
+        let md_path = dir.join("notes.md").to_string_lossy().to_string();
+        assert!(get_heatmap(root.clone(), "tokens".into()).await.is_err(), "unopened roots are refused");
+        remember_project_root(canonicalize_existing_path(&dir).unwrap());
+
+        let tokens = get_heatmap(root.clone(), "tokens".into()).await.unwrap();
+        assert_eq!(tokens.get(&md_path), Some(&3));
+        assert_eq!(tokens.get(&ts_path), Some(&5));
+
+        let complexity = get_heatmap(root.clone(), "complexity".into()).await.unwrap();
+        assert_eq!(complexity.get(&ts_path), Some(&2));
+        assert!(!complexity.contains_key(&md_path));
+
+        assert!(get_heatmap(root, "bogus".into()).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── project metadata ──
+
+    #[test]
+    fn cargo_package_metadata_reads_package_table_only() {
+        let manifest = "[workspace]\nname = \"ws\"\n\n[package]\nname = \"bablusheed\" # app\nversion.workspace = true\nedition = \"2021\"\n\n[dependencies]\nversion = \"9\"\n";
+        assert_eq!(cargo_package_metadata(manifest), (Some("bablusheed".into()), None));
+        assert_eq!(
+            cargo_package_metadata("[package]\nversion = \"0.3.1\"\nname = \"x\""),
+            (Some("x".into()), Some("0.3.1".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn get_project_metadata_prefers_package_json() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-metadata-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("package.json"), r#"{"name":"web","private":true}"#).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[package]\nname = \"core\"\nversion = \"1.2.0\"\n").unwrap();
+        let root = dir.to_string_lossy().to_string();
+        assert!(get_project_metadata(root.clone()).await.is_err(), "unopened roots are refused");
+        remember_project_root(canonicalize_existing_path(&dir).unwrap());
+
+        let metadata = get_project_metadata(root).await.unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("web"));
+        assert_eq!(metadata.version.as_deref(), Some("1.2.0"), "falls back to Cargo.toml per field");
+        assert!(metadata.commit.is_none(), "temp dir is not a git work tree");
+        assert!(!metadata.dirty);
+
+        assert!(get_project_metadata(dir.join("missing").to_string_lossy().to_string()).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── read_project_file ──
+
+    #[test]
+    fn read_project_file_resolves_paths_against_open_roots() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-additional-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.ts"), "export const a = 1;").unwrap();
+        let outside = std::env::temp_dir().join(format!("bablusheed-outside-{}.ts", Uuid::new_v4()));
+        std::fs::write(&outside, "secret").unwrap();
+        remember_project_root(canonicalize_existing_path(&dir).unwrap());
+
+        assert_eq!(read_project_file("src/a.ts").unwrap(), "export const a = 1;");
+        let absolute = dir.join("src/a.ts").to_string_lossy().to_string();
+        assert_eq!(read_project_file(&absolute).unwrap(), "export const a = 1;");
+        assert!(read_project_file("src/missing.ts").is_err());
+        assert!(read_project_file("src/../src/a.ts").is_err());
+        assert!(read_project_file(&outside.to_string_lossy()).is_err());
+
+        std::fs::remove_file(&outside).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn authorize_file_grants_reads_of_that_file_only() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-authorized-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("shared.json"), "{\"strict\": true}").unwrap();
+        std::fs::write(dir.join("sibling.json"), "{}").unwrap();
+        let shared = dir.join("shared.json").to_string_lossy().to_string();
+        let sibling = dir.join("sibling.json").to_string_lossy().to_string();
+
+        assert!(read_file_content(shared.clone()).await.is_err());
+        authorize_file(shared.clone()).await.unwrap();
+        assert_eq!(read_file_content(shared.clone()).await.unwrap(), "{\"strict\": true}");
+        assert_eq!(read_project_file(&shared).unwrap(), "{\"strict\": true}");
+        assert!(read_file_content(sibling).await.is_err());
+        assert!(write_file_content(shared, "{}".into()).await.is_err());
+        assert!(authorize_file(dir.to_string_lossy().to_string()).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── write_back_file ──
+
+    #[tokio::test]
+    async fn write_back_merges_edits_made_since_the_file_was_read() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-writeback-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        remember_project_root(canonicalize_existing_path(&dir).unwrap());
+        let file = dir.join("a.ts");
+        let path = file.to_string_lossy().to_string();
+        let base = "one\ntwo\nthree\nfour\nfive\n";
+        std::fs::write(&file, base).unwrap();
+
+        assert_eq!(read_file_content(path.clone()).await.unwrap(), base);
+        let hashed = read_file_content_hashed(path.clone()).await.unwrap();
+        assert_eq!(hashed.content, base);
+        assert_eq!(hashed.hash, blake3::hash(base.as_bytes()).to_hex().to_string());
+        let unchanged = write_back_file(path.clone(), "ONE\ntwo\nthree\nfour\nfive\n".into(), None).await.unwrap();
+        assert_eq!(unchanged.status, "written");
+
+        // The last write is the new base; an edit elsewhere on disk merges in.
+        let base = "ONE\ntwo\nthree\nfour\nfive\n";
+        std::fs::write(&file, "ONE\ntwo\nthree\nfour\nFIVE\n").unwrap();
+        let merged = write_back_file(path.clone(), "ONE\nTWO\nthree\nfour\nfive\n".into(), Some(base.into()))
+            .await
+            .unwrap();
+        assert_eq!(merged.status, "merged");
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "ONE\nTWO\nthree\nfour\nFIVE\n");
+
+        std::fs::write(&file, "ONE\ndisk\nthree\nfour\nFIVE\n").unwrap();
+        let base = "ONE\nTWO\nthree\nfour\nFIVE\n";
+        let conflict = write_back_file(path.clone(), "ONE\nmodel\nthree\nfour\nFIVE\n".into(), Some(base.into()))
+            .await
+            .unwrap();
+        assert_eq!(conflict.status, "conflict");
+        assert_eq!(conflict.conflicts.len(), 1);
+        assert!(conflict.merged.unwrap().contains("<<<<<<< on disk\ndisk\n=======\nmodel\n"));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "ONE\ndisk\nthree\nfour\nFIVE\n");
+
+        let no_base = write_back_file(path.clone(), "x\n".into(), Some("not the base".into())).await.unwrap();
+        assert_eq!(no_base.status, "conflict");
+        assert!(no_base.message.is_some());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "ONE\ndisk\nthree\nfour\nFIVE\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn write_back_writes_files_that_were_never_read() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-writeback-new-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        remember_project_root(canonicalize_existing_path(&dir).unwrap());
+        let path = dir.join("src/new.ts").to_string_lossy().to_string();
+
+        let result = write_back_file(path.clone(), "export {};\n".into(), None).await.unwrap();
+        assert_eq!(result.status, "written");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "export {};\n");
+        assert!(write_back_file(format!("{path}/../../x.ts"), String::new(), None).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── diff_against_disk ──
+
+    #[tokio::test]
+    async fn diff_against_disk_shows_what_a_write_would_change() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-diff-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        remember_project_root(canonicalize_existing_path(&dir).unwrap());
+        let file = dir.join("a.ts");
+        std::fs::write(&file, "one\ntwo\nthree\n").unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let diff = diff_against_disk(path.clone(), "one\n2\nthree\n".into()).await.unwrap();
+        assert!(diff.contains("@@ -1,3 +1,3 @@"), "{diff}");
+        assert!(diff.contains("\n-two\n+2\n"), "{diff}");
+        assert!(diff_against_disk(path.clone(), "one\ntwo\nthree\n".into()).await.unwrap().is_empty());
+
+        let new_file = dir.join("b.ts").to_string_lossy().to_string();
+        let diff = diff_against_disk(new_file, "fresh\n".into()).await.unwrap();
+        assert!(diff.starts_with("--- /dev/null\n"), "{diff}");
+        assert!(diff.contains("+fresh\n"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unified_diff_uses_git_style_headers() {
+        let diff = unified_diff("src/a.ts", Some("a\nb\n"), "a\nc\n");
+        assert!(diff.starts_with("--- a/src/a.ts\n+++ b/src/a.ts\n"), "{diff}");
+    }
+
+    // ── read_directory_contents ──
+
+    #[tokio::test]
+    async fn read_directory_contents_applies_recursion_filters_and_caps() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-hydrate-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.ts"), "export const a = 1;").unwrap();
+        std::fs::write(dir.join("notes.md"), "# notes").unwrap();
+        std::fs::write(dir.join("nested/b.ts"), "export const b = 2;").unwrap();
+        std::fs::write(dir.join("huge.ts"), "x".repeat(MAX_DIRECTORY_FILE_BYTES as usize + 1)).unwrap();
+        remember_project_root(canonicalize_existing_path(&dir).unwrap());
+        let root = dir.to_string_lossy().to_string();
+        let names = |files: Vec<FileContent>| -> Vec<String> {
+            let mut names: Vec<String> = files
+                .into_iter()
+                .map(|f| Path::new(&f.path).file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let top = read_directory_contents(root.clone(), false, Vec::new()).await.unwrap();
+        assert_eq!(names(top), vec!["a.ts", "notes.md"]);
+
+        let all_ts = read_directory_contents(root.clone(), true, vec!["*.ts".into()]).await.unwrap();
+        assert_eq!(names(all_ts), vec!["a.ts", "b.ts"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn read_directory_contents_rejects_unauthorized_dirs() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-unauthorized-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let result = read_directory_contents(dir.to_string_lossy().to_string(), true, Vec::new()).await;
+        assert!(result.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── build_tree ──
+
+    #[test]
+    fn build_tree_assigns_unique_ids_and_lowercases_extensions() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-tree-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("README.MD"), "# hi").unwrap();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.join("logo.PNG"), "not really a png").unwrap();
+
+        let nodes = build_tree(&dir, &dir, true, BinarySniffing::UnknownExtensions, TreeOrder::Natural).unwrap();
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["src", "README.MD"], "dirs first, binaries skipped");
+        assert_eq!(nodes[1].extension, "md");
+        assert_eq!(nodes[1].size, 4);
+
+        let child = &nodes[0].children.as_ref().unwrap()[0];
+        assert_eq!(child.relative_path, Path::new("src").join("main.rs").to_string_lossy());
+        let mut ids = vec![nodes[0].id.clone(), nodes[1].id.clone(), child.id.clone()];
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 3);
+
+        let rewalked = build_tree(&dir, &dir, true, BinarySniffing::UnknownExtensions, TreeOrder::Natural).unwrap();
+        assert_eq!(rewalked[0].id, nodes[0].id, "ids survive re-walks");
+        assert_eq!(child.id, stable_node_id("src/main.rs"));
+        assert_eq!(child.id.len(), 16);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── walk_directory_flat ──
+
+    #[tokio::test]
+    async fn walk_directory_flat_lists_parents_before_children() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-flat-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src/nested")).unwrap();
+        std::fs::write(dir.join("src/nested/a.ts"), "a").unwrap();
+        std::fs::write(dir.join("b.md"), "b").unwrap();
+        std::fs::write(dir.join("skip.log"), "x").unwrap();
+
+        let flat = walk_directory_flat(dir.to_string_lossy().to_string(), true, vec!["*.log".into()], None, None)
+            .await
+            .unwrap();
+        let summary: Vec<(&str, Option<usize>)> =
+            flat.nodes.iter().map(|n| (n.name.as_str(), n.parent_index)).collect();
+        assert_eq!(
+            summary,
+            vec![("src", None), ("nested", Some(0)), ("a.ts", Some(1)), ("b.md", None)]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── unavailable roots ──
+
+    #[tokio::test]
+    async fn vanished_roots_fail_typed_until_reattached() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-volume-{}", Uuid::new_v4()));
+        let unplugged = dir.with_extension("unplugged");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.ts"), "export const a = 1;").unwrap();
+        let root = canonicalize_existing_path(&dir).unwrap();
+        remember_project_root(root.clone());
+        let file = root.join("a.ts").to_string_lossy().to_string();
+        let unavailable = FsError::RootUnavailable { root: root.to_string_lossy().into_owned() };
+
+        std::fs::rename(&dir, &unplugged).unwrap();
+        assert_eq!(read_file_content(file.clone()).await.unwrap_err(), unavailable);
+        let walk = walk_directory_flat(root.to_string_lossy().to_string(), true, Vec::new(), None, None).await;
+        assert_eq!(walk.unwrap_err(), unavailable);
+
+        std::fs::rename(&unplugged, &dir).unwrap();
+        assert_eq!(read_file_content(file.clone()).await.unwrap_err(), unavailable, "stays stale until re-attached");
+        assert_eq!(write_file_content(file.clone(), "x".into()).await.unwrap_err(), unavailable);
+        reattach_root(dir.to_string_lossy().to_string()).await.unwrap();
+        assert_eq!(read_file_content(file).await.unwrap(), "export const a = 1;");
+        assert!(reattach_root(unplugged.to_string_lossy().to_string()).await.is_err());
+        assert!(reattach_root(dir.to_string_lossy().to_string()).await.is_err(), "already attached");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reattach_root_refuses_directories_that_were_never_opened() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-never-opened-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.ts"), "export const a = 1;").unwrap();
+
+        let error = reattach_root(dir.to_string_lossy().to_string()).await.unwrap_err();
+        assert!(matches!(error, FsError::Other { .. }), "{error:?}");
+        assert!(!is_path_allowed(&canonicalize_existing_path(&dir.join("a.ts")).unwrap()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fs_errors_serialize_with_a_kind_tag() {
+        let error = FsError::RootUnavailable { root: "/Volumes/usb".into() };
+        assert_eq!(serde_json::to_value(&error).unwrap(), serde_json::json!({"kind": "rootUnavailable", "root": "/Volumes/usb"}));
+        assert!(String::from(error).contains("/Volumes/usb"));
+    }
+
+    // ── network mounts ──
+
+    #[test]
+    fn degraded_mode_starts_at_network_latencies() {
+        assert_eq!(degraded_mode(Duration::from_micros(40)), None);
+        let degraded = degraded_mode(Duration::from_millis(12)).unwrap();
+        assert_eq!(degraded.latency_ms, 12);
+        assert!(degraded.notice.contains("network mount"));
+    }
+
+    #[tokio::test]
+    async fn read_directory_contents_reads_network_roots_in_batches() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-network-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..40 {
+            std::fs::write(dir.join(format!("f{i:02}.ts")), format!("export const v{i} = {i};")).unwrap();
+        }
+        let canonical = canonicalize_existing_path(&dir).unwrap();
+        remember_project_root(canonical.clone());
+        remember_network_root(canonical);
+
+        let files = read_directory_contents(dir.to_string_lossy().to_string(), true, Vec::new()).await.unwrap();
+        assert_eq!(files.len(), 40);
+        assert!(files[7].path.ends_with("f07.ts"), "batches keep walk order");
+        assert_eq!(files[7].content, "export const v7 = 7;");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── binary sniffing ──
+
+    #[test]
+    fn binary_sniffing_modes_choose_which_files_are_read() {
+        assert_eq!(BinarySniffing::from_mode(None), BinarySniffing::UnknownExtensions);
+        assert_eq!(BinarySniffing::from_mode(Some("off")), BinarySniffing::Off);
+        assert!(!BinarySniffing::UnknownExtensions.should_sniff("ts"));
+        assert!(!BinarySniffing::UnknownExtensions.should_sniff("JSON"));
+        assert!(BinarySniffing::UnknownExtensions.should_sniff("dat"));
+        assert!(BinarySniffing::UnknownExtensions.should_sniff(""));
+        assert!(BinarySniffing::All.should_sniff("ts"));
+        assert!(!BinarySniffing::Off.should_sniff("dat"));
+    }
+
+    #[test]
+    fn build_tree_sniffs_unknown_extensions_in_parallel() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-sniff-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..PARALLEL_SNIFF_THRESHOLD * 2 {
+            let content: &[u8] = if i % 2 == 0 { b"text" } else { b"bin\0ary" };
+            std::fs::write(dir.join(format!("blob{i:02}.dat")), content).unwrap();
+        }
+        std::fs::write(dir.join("nul.ts"), b"looks\0binary").unwrap();
+
+        let sniffed = build_tree(&dir, &dir, true, BinarySniffing::UnknownExtensions, TreeOrder::Natural).unwrap();
+        let names: Vec<&str> = sniffed.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names.len(), PARALLEL_SNIFF_THRESHOLD + 1);
+        assert!(names.contains(&"blob00.dat") && !names.contains(&"blob01.dat"));
+        assert!(names.contains(&"nul.ts"), "known text extensions are not sniffed");
+
+        let unsniffed = build_tree(&dir, &dir, true, BinarySniffing::Off, TreeOrder::Natural).unwrap();
+        assert_eq!(unsniffed.len(), PARALLEL_SNIFF_THRESHOLD * 2 + 1);
+        let all = build_tree(&dir, &dir, true, BinarySniffing::All, TreeOrder::Natural).unwrap();
+        assert_eq!(all.len(), PARALLEL_SNIFF_THRESHOLD);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── tree order ──
+
+    #[test]
+    fn natural_order_compares_numbers_by_value_and_ignores_case() {
+        let mut names = vec!["File10.ts", "file2.ts", "File1.ts", "b.ts", "A.ts", "v01.ts", "v1.ts"];
+        names.sort_by(|a, b| TreeOrder::Natural.compare(OsStr::new(a), OsStr::new(b)));
+        assert_eq!(names, vec!["A.ts", "b.ts", "File1.ts", "file2.ts", "File10.ts", "v01.ts", "v1.ts"]);
+        assert_eq!(TreeOrder::from_mode(Some("bytewise")), TreeOrder::Bytewise);
+        assert_eq!(TreeOrder::from_mode(None), TreeOrder::Natural);
+    }
+
+    #[test]
+    fn build_tree_sorts_entries_in_the_requested_order() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-order-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("lib")).unwrap();
+        for name in ["File10.md", "File2.md", "apple.md"] {
+            std::fs::write(dir.join(name), "x").unwrap();
+        }
+        let names = |order| {
+            let nodes = build_tree(&dir, &dir, true, BinarySniffing::Off, order).unwrap();
+            nodes.into_iter().map(|node| node.name).collect::<Vec<_>>()
+        };
+        assert_eq!(names(TreeOrder::Natural), vec!["lib", "apple.md", "File2.md", "File10.md"]);
+        assert_eq!(names(TreeOrder::Bytewise), vec!["lib", "File10.md", "File2.md", "apple.md"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── read_text_file ──
+
+    #[test]
+    fn read_text_file_handles_small_large_and_invalid_utf8() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-read-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let small = dir.join("small.ts");
+        std::fs::write(&small, "export const a = 1;").unwrap();
+        let large = dir.join("large.ts");
+        let large_content = "// line\n".repeat(MMAP_THRESHOLD_BYTES as usize / 8 + 1);
+        std::fs::write(&large, &large_content).unwrap();
+        let invalid = dir.join("invalid.txt");
+        std::fs::write(&invalid, b"ok\xffok").unwrap();
+
+        assert_eq!(read_text_file(&small).unwrap(), "export const a = 1;");
+        assert_eq!(read_text_file(&large).unwrap(), large_content, "read into a buffer outside open roots");
+        assert_eq!(read_text_file(&invalid).unwrap(), "ok\u{fffd}ok");
+
+        let root = canonicalize_existing_path(&dir).unwrap();
+        let large = root.join("large.ts");
+        assert!(!mmap_allowed(&large));
+        remember_project_root(root.clone());
+        assert!(mmap_allowed(&large));
+        assert_eq!(read_text_file(&large).unwrap(), large_content, "mapped on a local open root");
+        remember_network_root(root.clone());
+        assert!(!mmap_allowed(&large), "network mounts are read into a buffer");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
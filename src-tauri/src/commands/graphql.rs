@@ -0,0 +1,534 @@
+use crate::commands::pack::with_transitive_dependencies;
+use crate::models::{FileContent, GraphqlEdge};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::LazyLock;
+
+const ROOT_TYPES: &[&str] = &["Query", "Mutation", "Subscription"];
+const SDL_EXTENSIONS: &[&str] = &["graphql", "gql", "graphqls"];
+const JS_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs"];
+
+/// SDL embedded in code: ``gql`...` `` or ``graphql`...` ``.
+static EMBEDDED_SDL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:gql|graphql)\s*`([^`]*)`").unwrap());
+/// `type Invoice implements Node {`, `extend type Query {`, `interface Node {`
+static SDL_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\bextend\s+)?\b(?:type|interface)\s+(\w+)[^{}]*\{([^{}]*)\}").unwrap()
+});
+/// `(id: ID!)`; dropped before reading fields so arguments aren't read as fields.
+static SDL_ARGUMENTS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\([^)]*\)").unwrap());
+/// `invoice: Invoice` inside a type body, once arguments are gone.
+static SDL_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\w+)\s*:\s*\[*\s*(\w+)").unwrap());
+/// `@Resolver(() => Invoice)`, `@Resolver(of => Invoice)`, `@Resolver("Invoice")`
+static CLASS_RESOLVER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"@Resolver\(\s*(?:\w*\s*\(?\s*\)?\s*=>\s*)?['"]?(\w+)"#).unwrap()
+});
+static DECORATED_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^@(Query|Mutation|Subscription|ResolveField|FieldResolver)\b").unwrap()
+});
+static DECORATOR_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\bname:\s*['"](\w+)['"]"#).unwrap());
+static METHOD_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(?:public|private|protected|static|async)\s+)*\*?([A-Za-z_$][\w$]*)\s*[(<]")
+        .unwrap()
+});
+/// Ariadne: `query = QueryType()` / `invoice = ObjectType("Invoice")`
+static ARIADNE_BINDING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(\w+)\s*=\s*(?:(Query|Mutation|Subscription)Type\(\s*\)|(?:ObjectType|InterfaceType)\(\s*["'](\w+)["'])"#)
+        .unwrap()
+});
+static ARIADNE_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"@(\w+)\.field\(\s*["'](\w+)["']"#).unwrap());
+
+struct SchemaField {
+    /// Named type the field returns, without list and non-null wrappers.
+    returns: String,
+    /// File whose SDL defines the field.
+    file: usize,
+}
+
+/// Fields of one SDL object type, and the files defining or extending it.
+#[derive(Default)]
+struct SchemaType {
+    fields: BTreeMap<String, SchemaField>,
+    files: BTreeSet<usize>,
+}
+
+fn extension(path: &str) -> &str {
+    path.rsplit_once('.').map_or("", |(_, ext)| ext)
+}
+
+/// Drop `#` comments and `"..."` / `"""..."""` descriptions so their text isn't read as fields.
+fn strip_sdl_noise(sdl: &str) -> String {
+    let mut out = String::with_capacity(sdl.len());
+    let mut rest = sdl;
+    while let Some(pos) = rest.find(['#', '"']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        rest = if tail.starts_with('#') {
+            tail.find('\n').map_or("", |nl| &tail[nl..])
+        } else if let Some(block) = tail.strip_prefix("\"\"\"") {
+            block.find("\"\"\"").map_or("", |end| &block[end + 3..])
+        } else {
+            tail[1..]
+                .find(['"', '\n'])
+                .map_or("", |end| &tail[end + 2..])
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+fn parse_schema(files: &[FileContent]) -> BTreeMap<String, SchemaType> {
+    let mut types: BTreeMap<String, SchemaType> = BTreeMap::new();
+    for (idx, file) in files.iter().enumerate() {
+        let ext = extension(&file.path);
+        let sources: Vec<&str> = if SDL_EXTENSIONS.contains(&ext) {
+            vec![file.content.as_str()]
+        } else if JS_EXTENSIONS.contains(&ext) {
+            EMBEDDED_SDL
+                .captures_iter(&file.content)
+                .filter_map(|c| c.get(1).map(|m| m.as_str()))
+                .collect()
+        } else {
+            continue;
+        };
+        for source in sources {
+            let sdl = strip_sdl_noise(source);
+            for c in SDL_TYPE.captures_iter(&sdl) {
+                let entry = types.entry(c[1].to_string()).or_default();
+                entry.files.insert(idx);
+                let body = SDL_ARGUMENTS.replace_all(&c[2], "");
+                for field in SDL_FIELD.captures_iter(&body) {
+                    let returns = field[2].to_string();
+                    entry
+                        .fields
+                        .insert(field[1].to_string(), SchemaField { returns, file: idx });
+                }
+            }
+        }
+    }
+    types
+}
+
+/// The keys of the object literal opening at `open` (a `{`), skipping nested braces,
+/// parentheses, strings and line comments.
+fn top_level_keys(content: &str, open: usize) -> Vec<&str> {
+    let bytes = content.as_bytes();
+    let mut depth = 0usize;
+    let mut segments = Vec::new();
+    let mut segment_start = open + 1;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' | b'"' | b'`' => {
+                let quote = bytes[i];
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'{' | b'(' | b'[' => depth += 1,
+            b'}' | b')' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    segments.push(&content[segment_start..i]);
+                    break;
+                }
+            }
+            b',' if depth == 1 => {
+                segments.push(&content[segment_start..i]);
+                segment_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    segments
+        .into_iter()
+        .filter_map(|segment| {
+            let mut segment = segment.trim_start();
+            while segment.starts_with("//") {
+                segment = segment
+                    .find('\n')
+                    .map_or("", |nl| segment[nl..].trim_start());
+            }
+            let segment = segment
+                .strip_prefix("async ")
+                .unwrap_or(segment)
+                .trim_start_matches('*');
+            let end = segment
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(segment.len());
+            (end > 0).then(|| &segment[..end])
+        })
+        .collect()
+}
+
+/// Resolver map entries: `Invoice: { total: ..., customer(parent) { ... } }`.
+fn map_resolvers(content: &str, type_keys: &[(&str, Regex)], out: &mut Vec<(String, String)>) {
+    for (name, pattern) in type_keys {
+        for m in pattern.find_iter(content) {
+            for key in top_level_keys(content, m.end() - 1) {
+                out.push((name.to_string(), key.to_string()));
+            }
+        }
+    }
+}
+
+/// NestJS / TypeGraphQL classes: `@Resolver(() => Invoice)` with `@Query()`, `@Mutation()`
+/// and `@ResolveField()` methods.
+fn decorated_resolvers(content: &str, out: &mut Vec<(String, String)>) {
+    let mut class_type: Option<String> = None;
+    let mut pending: Option<(String, Option<String>)> = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(c) = CLASS_RESOLVER.captures(line) {
+            class_type = Some(c[1].to_string());
+            continue;
+        }
+        if let Some(c) = DECORATED_FIELD.captures(line) {
+            let owner = match &c[1] {
+                "ResolveField" | "FieldResolver" => class_type.clone(),
+                root => Some(root.to_string()),
+            };
+            let renamed = DECORATOR_NAME.captures(line).map(|n| n[1].to_string());
+            pending = owner.map(|owner| (owner, renamed));
+            continue;
+        }
+        if line.starts_with('@') {
+            continue;
+        }
+        if let Some((owner, renamed)) = pending.take()
+            && let Some(method) = METHOD_NAME.captures(line)
+        {
+            out.push((owner, renamed.unwrap_or_else(|| method[1].to_string())));
+        }
+    }
+}
+
+/// Ariadne bindables: `@invoice.field("total")` after `invoice = ObjectType("Invoice")`.
+fn ariadne_resolvers(content: &str, out: &mut Vec<(String, String)>) {
+    let bindings: HashMap<&str, &str> = ARIADNE_BINDING
+        .captures_iter(content)
+        .filter_map(|c| Some((c.get(1)?.as_str(), c.get(2).or_else(|| c.get(3))?.as_str())))
+        .collect();
+    for c in ARIADNE_FIELD.captures_iter(content) {
+        if let Some(type_name) = bindings.get(&c[1]) {
+            out.push((type_name.to_string(), c[2].to_string()));
+        }
+    }
+}
+
+/// One edge per SDL field and each file implementing its resolver, with the resolver's index.
+fn build_edges(
+    files: &[FileContent],
+    types: &BTreeMap<String, SchemaType>,
+) -> Vec<(GraphqlEdge, usize)> {
+    let type_keys: Vec<(&str, Regex)> = types
+        .keys()
+        .filter_map(|name| {
+            let pattern = Regex::new(&format!(r"\b{}\s*:\s*\{{", regex::escape(name))).ok()?;
+            Some((name.as_str(), pattern))
+        })
+        .collect();
+    let mut edges = Vec::new();
+    for (idx, file) in files.iter().enumerate() {
+        let ext = extension(&file.path);
+        let mut found = Vec::new();
+        if JS_EXTENSIONS.contains(&ext) {
+            map_resolvers(&file.content, &type_keys, &mut found);
+            decorated_resolvers(&file.content, &mut found);
+        } else if ext == "py" {
+            ariadne_resolvers(&file.content, &mut found);
+        }
+        let found: BTreeSet<(String, String)> = found.into_iter().collect();
+        for (type_name, field) in found {
+            let Some(defined) = types.get(&type_name).and_then(|t| t.fields.get(&field)) else {
+                continue;
+            };
+            let schema_file = files[defined.file].path.clone();
+            let resolver_file = file.path.clone();
+            edges.push((
+                GraphqlEdge {
+                    type_name,
+                    field,
+                    schema_file,
+                    resolver_file,
+                },
+                idx,
+            ));
+        }
+    }
+    edges.sort_by(|(a, _), (b, _)| {
+        (&a.type_name, &a.field, &a.schema_file, &a.resolver_file).cmp(&(
+            &b.type_name,
+            &b.field,
+            &b.schema_file,
+            &b.resolver_file,
+        ))
+    });
+    edges
+}
+
+/// `type_name` and the object types its fields return, up to `depth` hops away.
+fn domain_types(
+    types: &BTreeMap<String, SchemaType>,
+    type_name: &str,
+    depth: usize,
+) -> HashSet<String> {
+    let mut domain: HashSet<String> = HashSet::from([type_name.to_string()]);
+    let mut frontier = vec![type_name.to_string()];
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for name in &frontier {
+            for field in types
+                .get(name)
+                .map(|t| t.fields.values())
+                .into_iter()
+                .flatten()
+            {
+                let returned = &field.returns;
+                let is_object =
+                    types.contains_key(returned) && !ROOT_TYPES.contains(&returned.as_str());
+                if is_object && domain.insert(returned.clone()) {
+                    next.push(returned.clone());
+                }
+            }
+        }
+        frontier = next;
+    }
+    domain
+}
+
+/// Files for one GraphQL domain: the SDL defining its types, the resolvers for their fields
+/// and for root fields returning them, and everything those resolvers import.
+fn files_for_domain(
+    type_name: &str,
+    depth: usize,
+    files: &[FileContent],
+) -> Result<Vec<String>, String> {
+    let types = parse_schema(files);
+    if !types.contains_key(type_name) {
+        return Err(format!(
+            "GraphQL type is not defined in the selection: {type_name}"
+        ));
+    }
+    let domain = domain_types(&types, type_name, depth);
+    let mut seeds: BTreeSet<usize> = domain
+        .iter()
+        .flat_map(|name| types[name].files.iter().copied())
+        .collect();
+    for root in ROOT_TYPES.iter().filter_map(|name| types.get(*name)) {
+        seeds.extend(
+            root.fields
+                .values()
+                .filter(|f| domain.contains(&f.returns))
+                .map(|f| f.file),
+        );
+    }
+    for (edge, resolver) in build_edges(files, &types) {
+        let root_field_in_domain = ROOT_TYPES.contains(&edge.type_name.as_str())
+            && domain.contains(&types[&edge.type_name].fields[&edge.field].returns);
+        if domain.contains(&edge.type_name) || root_field_in_domain {
+            seeds.insert(resolver);
+        }
+    }
+    Ok(with_transitive_dependencies(
+        files,
+        &seeds.into_iter().collect::<Vec<_>>(),
+    ))
+}
+
+/// Link each GraphQL SDL field (from `.graphql` files or `gql` templates) to the files
+/// implementing its resolver: resolver maps, NestJS/TypeGraphQL classes, or Ariadne bindables.
+#[tauri::command]
+pub async fn link_graphql_resolvers(files: Vec<FileContent>) -> Result<Vec<GraphqlEdge>, String> {
+    let types = parse_schema(&files);
+    Ok(build_edges(&files, &types)
+        .into_iter()
+        .map(|(edge, _)| edge)
+        .collect())
+}
+
+/// Every file needed to pack one GraphQL domain end-to-end, starting from `type_name` and
+/// following field types `depth` hops (default 1).
+#[tauri::command]
+pub async fn files_for_graphql_type(
+    type_name: String,
+    depth: Option<usize>,
+    files: Vec<FileContent>,
+) -> Result<Vec<String>, String> {
+    files_for_domain(&type_name, depth.unwrap_or(1), &files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> FileContent {
+        FileContent {
+            path: path.into(),
+            content: content.into(),
+            token_count: None,
+        }
+    }
+
+    fn edges(files: &[FileContent]) -> Vec<(String, String, String)> {
+        let types = parse_schema(files);
+        build_edges(files, &types)
+            .into_iter()
+            .map(|(e, _)| {
+                (
+                    format!("{}.{}", e.type_name, e.field),
+                    e.schema_file,
+                    e.resolver_file,
+                )
+            })
+            .collect()
+    }
+
+    fn edge(field: &str, schema: &str, resolver: &str) -> (String, String, String) {
+        (field.to_string(), schema.to_string(), resolver.to_string())
+    }
+
+    const SCHEMA: &str = r#"
+"""An invoice"""
+type Invoice {
+  id: ID!
+  # internal: do not expose
+  total(currency: String = "USD"): Float!
+  customer: Customer
+  lines: [InvoiceLine!]!
+}
+type InvoiceLine { amount: Float }
+type Customer { name: String, orders: [Order] }
+type Order { id: ID! }
+type Query {
+  invoice(id: ID!): Invoice
+  orders: [Order]
+}
+"#;
+
+    // ── schema ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn sdl_fields_ignore_comments_and_descriptions() {
+        let types = parse_schema(&[file("schema.graphql", SCHEMA)]);
+        let invoice = &types["Invoice"];
+        assert_eq!(
+            invoice.fields.keys().collect::<Vec<_>>(),
+            vec!["customer", "id", "lines", "total"]
+        );
+        assert_eq!(invoice.fields["lines"].returns, "InvoiceLine");
+        assert_eq!(types["Query"].fields["invoice"].returns, "Invoice");
+    }
+
+    #[test]
+    fn embedded_sdl_and_extensions_merge_into_one_type() {
+        let files = [
+            file("a.graphql", "type Query { a: Int }"),
+            file(
+                "b.ts",
+                "export const typeDefs = gql`\n  extend type Query { b: Int }\n`;",
+            ),
+        ];
+        let types = parse_schema(&files);
+        assert_eq!(types["Query"].fields.len(), 2);
+        assert_eq!(types["Query"].files.len(), 2);
+    }
+
+    // ── resolvers ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn resolver_maps_link_only_fields_the_schema_defines() {
+        let files = [
+            file("schema.graphql", SCHEMA),
+            file(
+                "resolvers/invoice.ts",
+                "export const resolvers = {\n  Query: {\n    invoice: async (_, { id }) => load(id, { cache: true }),\n  },\n  Invoice: {\n    // computed\n    total(parent, args) { return sum(parent, \"a,b\"); },\n    customer,\n    __resolveType() {},\n  },\n};",
+            ),
+        ];
+        assert_eq!(
+            edges(&files),
+            vec![
+                edge("Invoice.customer", "schema.graphql", "resolvers/invoice.ts"),
+                edge("Invoice.total", "schema.graphql", "resolvers/invoice.ts"),
+                edge("Query.invoice", "schema.graphql", "resolvers/invoice.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn decorated_classes_and_ariadne_bindables_link_their_fields() {
+        let files = [
+            file("schema.graphql", SCHEMA),
+            file(
+                "invoice.resolver.ts",
+                "@Resolver(() => Invoice)\nexport class InvoiceResolver {\n  @Query(() => Invoice, { name: \"invoice\" })\n  async findOne(@Args('id') id: string) {}\n\n  @ResolveField()\n  @UseGuards(Auth)\n  lines(@Parent() invoice: Invoice) {}\n}",
+            ),
+            file(
+                "customer.py",
+                "customer = ObjectType(\"Customer\")\nquery = QueryType()\n\n@customer.field(\"orders\")\ndef resolve_orders(obj, info): ...\n\n@query.field(\"orders\")\ndef resolve_all(obj, info): ...",
+            ),
+        ];
+        assert_eq!(
+            edges(&files),
+            vec![
+                edge("Customer.orders", "schema.graphql", "customer.py"),
+                edge("Invoice.lines", "schema.graphql", "invoice.resolver.ts"),
+                edge("Query.invoice", "schema.graphql", "invoice.resolver.ts"),
+                edge("Query.orders", "schema.graphql", "customer.py"),
+            ]
+        );
+    }
+
+    // ── files_for_graphql_type ───────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn a_domain_pulls_in_its_schema_resolvers_and_their_imports() {
+        let files = vec![
+            file(
+                "schema/invoice.graphql",
+                "type Invoice { id: ID!, customer: Customer }\nextend type Query { invoice: Invoice }",
+            ),
+            file(
+                "schema/customer.graphql",
+                "type Customer { name: String, orders: [Order] }",
+            ),
+            file(
+                "schema/order.graphql",
+                "type Order { id: ID! }\ntype Query { orders: [Order] }",
+            ),
+            file(
+                "src/invoice.ts",
+                "import { db } from \"./db\";\nexport default { Query: { invoice: () => db }, Invoice: { customer: () => null } };",
+            ),
+            file(
+                "src/order.ts",
+                "export default { Query: { orders: () => [] } };",
+            ),
+            file("src/db.ts", ""),
+        ];
+        assert!(files_for_domain("Missing", 1, &files).is_err());
+        let selected = files_for_graphql_type("Invoice".into(), None, files)
+            .await
+            .unwrap();
+        assert_eq!(
+            selected,
+            vec![
+                "schema/customer.graphql",
+                "schema/invoice.graphql",
+                "src/db.ts",
+                "src/invoice.ts"
+            ]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use crate::commands::pack::{ImportResolver, file_module_specifiers, module_specifier_candidates, normalize_path};
+use crate::models::FileContent;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// Extensions protoc plugins write; stripped before reading the `_pb`-style suffix.
+const GENERATED_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "mjs", "cjs", "py", "pyi", "go", "rs"];
+/// Longest first, so `user_pb2_grpc` yields `user` rather than `user_pb2`.
+const PROTO_OUTPUT_SUFFIXES: &[&str] = &["_pb2_grpc", "_pb2", "_grpc_pb", "_grpc.pb", "_connectweb", "_connect", "_pb", ".pb"];
+/// Directory names generated OpenAPI clients are usually written to.
+const OPENAPI_CLIENT_DIRS: &[&str] = &["openapi", "swagger", "api-client", "generated"];
+const OPENAPI_EXTENSIONS: &[&str] = &["yaml", "yml", "json"];
+/// Directories `.proto` sources are commonly kept under, relative to the project root.
+const SCHEMA_ROOTS: &[&str] = &["proto", "protos", "protobuf", "schema", "schemas", "api"];
+/// Generated files name their source in the first lines.
+const HEADER_LINES: usize = 30;
+/// Follow-up schemas are a handful of files, not a second selection.
+const MAX_LINKED_SCHEMAS: usize = 20;
+
+/// `// source: api/user.proto` (protoc) or `// @generated from file api/user.proto (package ...)`
+/// (protobuf-es) in a generated file's header.
+static GENERATED_FROM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?://|#|\*)\s*(?:source:|@generated from file)\s+([\w./-]+\.proto)\b").unwrap()
+});
+/// Headers of clients generated from an OpenAPI document: openapi-typescript, OpenAPI
+/// Generator, orval, swagger-codegen.
+static OPENAPI_GENERATED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:openapi|swagger)\b[^\n]*\bgenerat|\bgenerat[^\n]*\b(?:openapi|swagger|orval)\b").unwrap()
+});
+/// `openapi: 3.1.0` / `swagger: "2.0"` in YAML, `"openapi": "3.0.0"` in JSON.
+static OPENAPI_VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^(?:openapi|swagger)\s*:|^\s*"(?:openapi|swagger)"\s*:"#).unwrap());
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn header(content: &str) -> String {
+    content.lines().take(HEADER_LINES).collect::<Vec<_>>().join("\n")
+}
+
+/// `user` for protoc outputs like `gen/user_pb.ts`, `user_pb2.py`, `user.pb.go`, `./user_grpc_pb`.
+fn proto_output_stem(path: &str) -> Option<&str> {
+    let name = file_name(path);
+    let name = name.strip_suffix(".d.ts").unwrap_or(name);
+    let stem = match name.rsplit_once('.') {
+        Some((stem, ext)) if GENERATED_EXTENSIONS.contains(&ext) => stem,
+        _ => name,
+    };
+    PROTO_OUTPUT_SUFFIXES
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+        .filter(|stem| !stem.is_empty())
+}
+
+/// The `.proto` a generated file names in its header.
+fn generated_from(content: &str) -> Option<String> {
+    GENERATED_FROM.captures(&header(content)).map(|c| normalize_path(&c[1]))
+}
+
+fn is_openapi_client(content: &str) -> bool {
+    OPENAPI_GENERATED.is_match(&header(content))
+}
+
+/// Whether `path` is an OpenAPI (or Swagger) document.
+pub(crate) fn is_openapi_spec(path: &str, content: &str) -> bool {
+    let ext = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+    OPENAPI_EXTENSIONS.contains(&ext) && OPENAPI_VERSION.is_match(&header(content))
+}
+
+fn is_schema_path(path: &str) -> bool {
+    let name = file_name(path).to_ascii_lowercase();
+    name.ends_with(".proto")
+        || ((name.contains("openapi") || name.contains("swagger"))
+            && OPENAPI_EXTENSIONS.iter().any(|ext| name.ends_with(&format!(".{ext}"))))
+}
+
+/// Schema sources in the selection: `.proto` files and OpenAPI documents.
+pub(crate) struct SchemaIndex {
+    /// `(path, index)` of every `.proto` file
+    protos: Vec<(String, usize)>,
+    openapi: Vec<usize>,
+}
+
+impl SchemaIndex {
+    pub(crate) fn new(files: &[FileContent], normalized_paths: &[String]) -> Self {
+        let mut protos = Vec::new();
+        let mut openapi = Vec::new();
+        for (idx, path) in normalized_paths.iter().enumerate() {
+            if path.ends_with(".proto") {
+                protos.push((path.clone(), idx));
+            } else if is_openapi_spec(path, &files[idx].content) {
+                openapi.push(idx);
+            }
+        }
+        Self { protos, openapi }
+    }
+
+    fn protos_at(&self, source: &str) -> Vec<usize> {
+        let suffix = format!("/{source}");
+        self.protos
+            .iter()
+            .filter(|(path, _)| path == source || path.ends_with(&suffix))
+            .map(|&(_, idx)| idx)
+            .collect()
+    }
+
+    fn protos_named(&self, stem: &str) -> Vec<usize> {
+        self.protos_at(&format!("{stem}.proto"))
+    }
+
+    /// Schemas a generated file was produced from, by its header or its protoc output name.
+    pub(crate) fn sources_of(&self, path: &str, content: &str) -> Vec<usize> {
+        if self.protos.is_empty() && self.openapi.is_empty() {
+            return Vec::new();
+        }
+        if let Some(source) = generated_from(content) {
+            return self.protos_at(&source);
+        }
+        if let Some(stem) = proto_output_stem(path) {
+            return self.protos_named(stem);
+        }
+        if is_openapi_client(content) {
+            return self.openapi.clone();
+        }
+        Vec::new()
+    }
+
+    /// Schemas behind an import nothing in the selection resolves: a `.proto` import, a protoc
+    /// output module, or a module in a generated OpenAPI client directory.
+    pub(crate) fn sources_of_import(&self, specifier: &str) -> Vec<usize> {
+        if let Some(source) = specifier.strip_suffix(".proto") {
+            return self.protos_at(&format!("{}.proto", normalize_path(source)));
+        }
+        if let Some(stem) = proto_output_stem(specifier) {
+            return self.protos_named(stem);
+        }
+        let in_client_dir = specifier.split('/').any(|segment| OPENAPI_CLIENT_DIRS.contains(&segment));
+        if in_client_dir && specifier.starts_with('.') {
+            return self.openapi.clone();
+        }
+        Vec::new()
+    }
+}
+
+/// Where a schema named by a generated file may live: as named, next to the generated file,
+/// under a common schema root, or at any known project path ending in it.
+fn schema_candidates(source: &str, generated_path: &str, known_paths: &[String]) -> Vec<String> {
+    let suffix = format!("/{source}");
+    let mut candidates = vec![source.to_string()];
+    if let Some((dir, _)) = generated_path.rsplit_once('/') {
+        candidates.push(normalize_path(&format!("{dir}/{source}")));
+    }
+    candidates.extend(SCHEMA_ROOTS.iter().map(|root| format!("{root}/{source}")));
+    candidates.extend(known_paths.iter().filter(|path| path.ends_with(&suffix)).cloned());
+    candidates
+}
+
+/// Schema paths a generated file at `path` comes from, for lookup outside the selection.
+fn schema_paths_for(path: &str, content: &str, known_paths: &[String]) -> Vec<String> {
+    if let Some(source) = generated_from(content) {
+        return schema_candidates(&source, path, known_paths);
+    }
+    if let Some(stem) = proto_output_stem(path) {
+        return schema_candidates(&format!("{stem}.proto"), path, known_paths);
+    }
+    if is_openapi_client(content) {
+        return known_paths
+            .iter()
+            .filter(|known| is_schema_path(known) && !known.ends_with(".proto"))
+            .cloned()
+            .collect();
+    }
+    Vec::new()
+}
+
+/// Schemas left out of the selection that selected code uses through generated modules,
+/// read with `read`. Generated modules are followed whether or not they were selected;
+/// `known_paths` (e.g. the excluded paths) are searched for schemas by name.
+pub(crate) fn unselected_schemas(
+    files: &[FileContent],
+    known_paths: &[String],
+    read: impl Fn(&str) -> Option<String>,
+) -> Vec<FileContent> {
+    let normalized_paths: Vec<String> = files.iter().map(|f| normalize_path(&f.path)).collect();
+    let selected: HashSet<&str> = normalized_paths.iter().map(String::as_str).collect();
+    let known_paths: Vec<String> = known_paths.iter().map(|path| normalize_path(path)).collect();
+    let resolver = ImportResolver::new(files, &normalized_paths);
+
+    // (path, content) of the selected files and of the unselected modules they import; any of
+    // them may be generated.
+    let mut generated: Vec<(String, String)> = Vec::new();
+    let mut followed: HashSet<String> = HashSet::new();
+    for (idx, file) in files.iter().enumerate() {
+        let path = &normalized_paths[idx];
+        generated.push((path.clone(), file.content.clone()));
+        for spec in file_module_specifiers(file) {
+            let relative = spec.starts_with("./") || spec.starts_with("../") || spec.starts_with("@/");
+            if !relative || !resolver.resolve(&spec, path).is_empty() {
+                continue;
+            }
+            let candidates = module_specifier_candidates(&spec, path);
+            if candidates.first().is_none_or(|base| !followed.insert(base.clone())) {
+                continue;
+            }
+            generated.extend(
+                candidates
+                    .into_iter()
+                    .find_map(|candidate| read(&candidate).map(|content| (candidate, content))),
+            );
+        }
+    }
+
+    let mut added: Vec<FileContent> = Vec::new();
+    let mut tried: HashSet<String> = HashSet::new();
+    for (path, content) in &generated {
+        for schema in schema_paths_for(path, content, &known_paths) {
+            if added.len() >= MAX_LINKED_SCHEMAS {
+                break;
+            }
+            if selected.contains(schema.as_str()) || !tried.insert(schema.clone()) {
+                continue;
+            }
+            if let Some(content) = read(&schema)
+                && (schema.ends_with(".proto") || is_openapi_spec(&schema, &content))
+            {
+                added.push(FileContent { path: schema, content, token_count: None });
+            }
+        }
+    }
+    added.sort_by(|a, b| a.path.cmp(&b.path));
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn file(path: &str, content: &str) -> FileContent {
+        FileContent {
+            path: path.into(),
+            content: content.into(),
+            token_count: None,
+        }
+    }
+
+    #[test]
+    fn protoc_output_names_map_to_their_proto() {
+        assert_eq!(proto_output_stem("gen/user_pb.ts"), Some("user"));
+        assert_eq!(proto_output_stem("gen/user_pb.d.ts"), Some("user"));
+        assert_eq!(proto_output_stem("api/user_pb2_grpc.py"), Some("user"));
+        assert_eq!(proto_output_stem("api/user_grpc.pb.go"), Some("user"));
+        assert_eq!(proto_output_stem("./gen/user_connect"), Some("user"));
+        assert_eq!(proto_output_stem("src/_pb.ts"), None);
+        assert_eq!(proto_output_stem("src/users.ts"), None);
+    }
+
+    #[test]
+    fn schema_index_links_generated_files_and_imports_to_schemas() {
+        let files = [
+            file("proto/api/user.proto", "syntax = \"proto3\";\nimport \"api/common.proto\";"),
+            file("proto/api/common.proto", "syntax = \"proto3\";"),
+            file("openapi.yaml", "openapi: 3.1.0\ninfo:\n  title: API\n"),
+            file("gen/user.pb.go", "// Code generated by protoc-gen-go. DO NOT EDIT.\n// source: api/user.proto\n"),
+            file("web/src/client/schema.ts", "/**\n * This file was auto-generated by openapi-typescript.\n */\n"),
+            file("config.yaml", "port: 8080\n"),
+        ];
+        let paths: Vec<String> = files.iter().map(|f| f.path.clone()).collect();
+        let index = SchemaIndex::new(&files, &paths);
+
+        assert_eq!(index.sources_of("gen/user.pb.go", &files[3].content), vec![0]);
+        assert_eq!(index.sources_of("web/src/client/schema.ts", &files[4].content), vec![2]);
+        assert_eq!(index.sources_of("gen/common_pb.ts", ""), vec![1]);
+        assert!(index.sources_of("config.yaml", &files[5].content).is_empty());
+
+        assert_eq!(index.sources_of_import("api/common.proto"), vec![1]);
+        assert_eq!(index.sources_of_import("./gen/user_pb"), vec![0]);
+        assert_eq!(index.sources_of_import("../generated/api"), vec![2]);
+        assert!(index.sources_of_import("./utils").is_empty());
+    }
+
+    #[test]
+    fn unselected_schemas_follow_excluded_generated_modules() {
+        let disk: HashMap<&str, &str> = HashMap::from([
+            ("src/gen/user_pb.ts", "// @generated from file api/user.proto (package api, syntax proto3)\n"),
+            ("src/gen/order_pb.ts", "export class Order {}\n"),
+            ("proto/api/user.proto", "syntax = \"proto3\";\nmessage User {}\n"),
+            ("src/gen/order.proto", "syntax = \"proto3\";\nmessage Order {}\n"),
+            ("src/api/types.ts", "// Generated by orval from the OpenAPI spec.\n"),
+            ("spec/openapi.json", "{\n  \"openapi\": \"3.0.0\"\n}\n"),
+            ("spec/swagger.json", "{ \"not\": \"a spec\" }"),
+        ]);
+        let files = [
+            file(
+                "src/users.ts",
+                "import { User } from \"./gen/user_pb\";\nimport { Order } from \"./gen/order_pb\";\nimport { paths } from \"./api/types\";\nimport { ok } from \"./missing\";",
+            ),
+            file("src/order.ts", "import { Order } from \"./gen/order_pb\";"),
+        ];
+        let known = vec!["proto/api/user.proto".to_string(), "spec/openapi.json".into(), "spec/swagger.json".into()];
+        let added = unselected_schemas(&files, &known, |path| disk.get(path).map(|content| content.to_string()));
+        let paths: Vec<&str> = added.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["proto/api/user.proto", "spec/openapi.json", "src/gen/order.proto"]);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::models::FileContent;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+const JS_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs", "mts", "cts", "vue", "svelte"];
+
+/// `invoke("greet")`, `invoke<Reply>('greet', args)`, and `invoke*` wrappers such as
+/// `invokeCompressed("greet")`.
+static INVOKE_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\binvoke\w*\s*(?:<[^<>()]*>)?\s*\(\s*['"`](\w+)['"`]"#).unwrap()
+});
+/// `#[tauri::command]` (or `#[command]`, with or without arguments) over a function, with
+/// other attributes and comments allowed in between.
+static TAURI_COMMAND: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"#\[(?:tauri::)?command\b[^\]]*\](?:\s*(?:#\[[^\]]*\]|//[^\n]*))*\s*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?fn\s+(\w+)",
+    )
+    .unwrap()
+});
+
+fn extension(path: &str) -> &str {
+    path.rsplit_once('.').map_or("", |(_, ext)| ext)
+}
+
+/// Names of the Tauri commands a JS/TS file calls, in order of first call.
+pub(crate) fn invoked_commands(file: &FileContent) -> Vec<String> {
+    if !JS_EXTENSIONS.contains(&extension(&file.path)) {
+        return Vec::new();
+    }
+    let mut names: Vec<String> = Vec::new();
+    for c in INVOKE_CALL.captures_iter(&file.content) {
+        if !names.iter().any(|name| name == &c[1]) {
+            names.push(c[1].to_string());
+        }
+    }
+    names
+}
+
+/// Command name -> the Rust files defining a `#[tauri::command]` function of that name.
+pub(crate) fn command_handlers(files: &[FileContent]) -> HashMap<String, Vec<usize>> {
+    let mut handlers: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, file) in files.iter().enumerate() {
+        if extension(&file.path) != "rs" || !file.content.contains("command") {
+            continue;
+        }
+        for c in TAURI_COMMAND.captures_iter(&file.content) {
+            let files = handlers.entry(c[1].to_string()).or_default();
+            if !files.contains(&idx) {
+                files.push(idx);
+            }
+        }
+    }
+    handlers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> FileContent {
+        FileContent {
+            path: path.into(),
+            content: content.into(),
+            token_count: None,
+        }
+    }
+
+    #[test]
+    fn invoke_calls_name_their_commands() {
+        let caller = file(
+            "src/api.ts",
+            "await invoke(\"greet\", { name });\nconst r = await invoke<Reply>('walk_tree');\ninvokeCompressed<Pack>(`pack_files`, {});\ninvoke(\"greet\");\ninvoke(commandName);\n",
+        );
+        assert_eq!(invoked_commands(&caller), vec!["greet", "walk_tree", "pack_files"]);
+        assert!(invoked_commands(&file("src/lib.rs", "invoke(\"greet\")")).is_empty());
+    }
+
+    #[test]
+    fn tauri_command_attributes_mark_handlers() {
+        let files = [
+            file(
+                "src/commands.rs",
+                "#[tauri::command]\npub async fn greet() {}\n\n#[command(rename_all = \"snake_case\")]\n// walks\n#[allow(dead_code)]\npub(crate) fn walk_tree() {}\n\n#[commander]\nfn not_a_command() {}\nfn plain() {}\n",
+            ),
+            file("src/other.rs", "#[tauri::command]\nfn greet() {}\n"),
+            file("src/api.ts", "// #[tauri::command] fn nope() {}"),
+        ];
+        let handlers = command_handlers(&files);
+        let mut names: Vec<&str> = handlers.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, vec!["greet", "walk_tree"]);
+        assert_eq!(handlers["greet"], vec![0, 1]);
+    }
+}
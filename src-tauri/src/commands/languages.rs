@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock, RwLock};
+
+/// File name of the saved associations inside the app data directory.
+const ASSOCIATIONS_FILE: &str = "language-associations.json";
+
+/// Set once at startup; associations are kept in memory only before then.
+static ASSOCIATIONS_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Lowercased extension (without the dot) or file name -> language.
+static ASSOCIATIONS: LazyLock<RwLock<HashMap<String, String>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Keep associations in `dir` (the app data directory) and load the saved ones. A file that
+/// no longer parses is ignored rather than blocking startup.
+pub fn init_language_associations(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(ASSOCIATIONS_FILE);
+    if let Ok(text) = std::fs::read_to_string(&path)
+        && let Ok(saved) = serde_json::from_str::<HashMap<String, String>>(&text)
+        && let Ok(associations) = normalize_associations(saved)
+        && let Ok(mut current) = ASSOCIATIONS.write()
+    {
+        *current = associations;
+    }
+    let _ = ASSOCIATIONS_PATH.set(path);
+    Ok(())
+}
+
+/// Lowercase keys without a leading dot, and language names that fit a markdown fence.
+fn normalize_associations(associations: HashMap<String, String>) -> Result<HashMap<String, String>, String> {
+    let mut normalized = HashMap::with_capacity(associations.len());
+    for (key, language) in associations {
+        let key = key.trim().trim_start_matches('.').to_lowercase();
+        let language = language.trim().to_lowercase();
+        if key.is_empty() || key.contains(['/', '\\']) {
+            return Err(format!("Not an extension or file name: {key:?}"));
+        }
+        if language.is_empty() || !language.chars().all(|c| c.is_ascii_alphanumeric() || "+#-_".contains(c)) {
+            return Err(format!("Not a language name: {language:?}"));
+        }
+        normalized.insert(key, language);
+    }
+    Ok(normalized)
+}
+
+/// The language the user associated with `path`, by its file name first and then its
+/// extension, e.g. `Justfile` -> make or `.mjsx` -> javascript.
+pub(crate) fn associated_language(path: &str) -> Option<String> {
+    let associations = ASSOCIATIONS.read().ok()?;
+    if associations.is_empty() {
+        return None;
+    }
+    let path = Path::new(path);
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if let Some(language) = associations.get(&name) {
+        return Some(language.clone());
+    }
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    associations.get(&extension).cloned()
+}
+
+/// The extension whose tree-sitter grammar parses `language`, for the languages we have
+/// grammars for.
+pub(crate) fn grammar_extension(language: &str) -> Option<&'static str> {
+    match language {
+        "typescript" | "ts" => Some("ts"),
+        "tsx" => Some("tsx"),
+        "javascript" | "js" | "jsx" => Some("js"),
+        "python" | "py" => Some("py"),
+        "rust" | "rs" => Some("rs"),
+        "go" | "golang" => Some("go"),
+        _ => None,
+    }
+}
+
+fn store_associations(associations: HashMap<String, String>) -> Result<(), String> {
+    if let Some(path) = ASSOCIATIONS_PATH.get() {
+        let json = serde_json::to_string_pretty(&associations).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())?;
+    }
+    *ASSOCIATIONS.write().map_err(|e| e.to_string())? = associations;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_language_associations() -> Result<HashMap<String, String>, String> {
+    Ok(ASSOCIATIONS.read().map_err(|e| e.to_string())?.clone())
+}
+
+/// Replace the saved extension/file name -> language associations, which pick the markdown
+/// fence language and the tree-sitter grammar of matching files. Returns them normalized.
+#[tauri::command]
+pub async fn set_language_associations(
+    associations: HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    let associations = normalize_associations(associations)?;
+    store_associations(associations.clone())?;
+    Ok(associations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn associations_are_normalized_and_validated() {
+        let normalized = normalize_associations(HashMap::from([
+            (".MJSX".into(), " JavaScript ".into()),
+            ("Justfile".into(), "make".into()),
+        ]))
+        .unwrap();
+        assert_eq!(normalized["mjsx"], "javascript");
+        assert_eq!(normalized["justfile"], "make");
+        assert!(normalize_associations(HashMap::from([("gqls".into(), "graph ql".into())])).is_err());
+        assert!(normalize_associations(HashMap::from([("src/x".into(), "go".into())])).is_err());
+        assert_eq!(grammar_extension("javascript"), Some("js"));
+        assert_eq!(grammar_extension("make"), None);
+    }
+}
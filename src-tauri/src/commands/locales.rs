@@ -0,0 +1,288 @@
+use crate::models::FileContent;
+use std::collections::{BTreeSet, HashMap};
+
+/// Directory names that hold translation resources.
+const LOCALE_DIRS: &[&str] = &["i18n", "lang", "langs", "locale", "locales", "translations"];
+
+/// How many missing or extra keys a summary names before only counting the rest.
+const KEY_SAMPLE_LIMIT: usize = 10;
+
+/// `en`, `pt-BR`, `zh_Hans`: a language code with an optional region or script.
+fn is_locale_code(segment: &str) -> bool {
+    let mut parts = segment.splitn(2, ['-', '_']);
+    let language = parts.next().unwrap_or("");
+    let language_ok =
+        (2..=3).contains(&language.len()) && language.bytes().all(|b| b.is_ascii_lowercase());
+    let region_ok = parts.next().is_none_or(|region| {
+        (2..=4).contains(&region.len()) && region.bytes().all(|b| b.is_ascii_alphanumeric())
+    });
+    language_ok && region_ok
+}
+
+fn is_locale_dir(segment: &str) -> bool {
+    LOCALE_DIRS.contains(&segment.to_ascii_lowercase().as_str())
+}
+
+/// Split a translation resource path into its group (the path with the locale replaced by
+/// `{locale}`) and its locale: `locales/fr.json`, `locales/fr/common.json`,
+/// `locale/fr/LC_MESSAGES/django.po`, `po/fr.po`.
+fn locale_slot(path: &str) -> Option<(String, String)> {
+    let (dir, file_name) = path.rsplit_once('/').unwrap_or(("", path));
+    let (stem, ext) = file_name.rsplit_once('.')?;
+    if ext != "json" && ext != "po" {
+        return None;
+    }
+    let mut segments: Vec<&str> = dir
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    let parent_is_locale_dir = segments.last().is_some_and(|parent| is_locale_dir(parent));
+    if is_locale_code(stem) && (ext == "po" || parent_is_locale_dir) {
+        segments.push("{locale}");
+        return Some((format!("{}.{ext}", segments.join("/")), stem.to_string()));
+    }
+
+    let position = (0..segments.len()).rev().find(|&i| {
+        is_locale_code(segments[i]) && (ext == "po" || (i > 0 && is_locale_dir(segments[i - 1])))
+    })?;
+    let locale = segments[position].to_string();
+    segments[position] = "{locale}";
+    segments.push(file_name);
+    Some((segments.join("/"), locale))
+}
+
+fn flatten_json_keys(value: &serde_json::Value, prefix: &str, keys: &mut BTreeSet<String>) {
+    match value {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_json_keys(child, &path, keys);
+            }
+        }
+        _ => {
+            keys.insert(prefix.to_string());
+        }
+    }
+}
+
+/// The `msgid`s of a gettext catalog, joining continuation lines and skipping the header entry.
+fn po_message_ids(content: &str) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    let mut current: Option<String> = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("msgid ") {
+            if let Some(id) = current.take().filter(|id| !id.is_empty()) {
+                ids.insert(id);
+            }
+            current = Some(rest.trim_matches('"').to_string());
+        } else if line.starts_with('"') {
+            if let Some(id) = current.as_mut() {
+                id.push_str(line.trim_matches('"'));
+            }
+        } else if let Some(id) = current.take().filter(|id| !id.is_empty()) {
+            ids.insert(id);
+        }
+    }
+    if let Some(id) = current.filter(|id| !id.is_empty()) {
+        ids.insert(id);
+    }
+    ids
+}
+
+/// Translation keys of a resource file, or `None` when it doesn't parse.
+fn translation_keys(path: &str, content: &str) -> Option<BTreeSet<String>> {
+    if path.ends_with(".po") {
+        return Some(po_message_ids(content));
+    }
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut keys = BTreeSet::new();
+    flatten_json_keys(&value, "", &mut keys);
+    Some(keys)
+}
+
+fn describe_keys(label: &str, keys: &[&String]) -> String {
+    let shown: Vec<&str> = keys
+        .iter()
+        .take(KEY_SAMPLE_LIMIT)
+        .map(|key| key.as_str())
+        .collect();
+    let mut line = format!("{label} ({}): {}", keys.len(), shown.join(", "));
+    if keys.len() > KEY_SAMPLE_LIMIT {
+        line.push_str(&format!(", +{} more", keys.len() - KEY_SAMPLE_LIMIT));
+    }
+    line
+}
+
+/// The reference locale of a group: English when present, otherwise the first alphabetically.
+fn reference_index(members: &[(String, usize)]) -> usize {
+    members
+        .iter()
+        .position(|(locale, _)| locale == "en")
+        .or_else(|| {
+            members
+                .iter()
+                .position(|(locale, _)| locale.starts_with("en-") || locale.starts_with("en_"))
+        })
+        .unwrap_or(0)
+}
+
+/// Replacement content for translation files that repeat another locale's key structure: each
+/// group of the same resource in several locales keeps one reference locale in full, and the
+/// others are summarized by key count and the keys they are missing or add. Keyed by index
+/// into `files`; files whose keys can't be read keep their content.
+pub(crate) fn summarize_translations(files: &[FileContent]) -> HashMap<usize, String> {
+    let mut groups: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+    for (idx, file) in files.iter().enumerate() {
+        if let Some((group, locale)) = locale_slot(&file.path) {
+            groups.entry(group).or_default().push((locale, idx));
+        }
+    }
+
+    let mut summaries = HashMap::new();
+    for mut members in groups.into_values().filter(|members| members.len() > 1) {
+        members.sort();
+        let (reference_locale, reference_idx) = members[reference_index(&members)].clone();
+        let reference = &files[reference_idx];
+        let Some(reference_keys) = translation_keys(&reference.path, &reference.content) else {
+            continue;
+        };
+        for (locale, idx) in members {
+            if idx == reference_idx {
+                continue;
+            }
+            let Some(keys) = translation_keys(&files[idx].path, &files[idx].content) else {
+                continue;
+            };
+            let missing: Vec<&String> = reference_keys.difference(&keys).collect();
+            let extra: Vec<&String> = keys.difference(&reference_keys).collect();
+            let mut summary = format!(
+                "Translation resource for locale `{locale}`; content omitted. `{}` (locale `{reference_locale}`) is packed in full and has {} keys; this file has {} of them.\n",
+                reference.path,
+                reference_keys.len(),
+                reference_keys.len() - missing.len(),
+            );
+            if !missing.is_empty() {
+                summary.push_str(&describe_keys("Missing", &missing));
+                summary.push('\n');
+            }
+            if !extra.is_empty() {
+                summary.push_str(&describe_keys("Extra", &extra));
+                summary.push('\n');
+            }
+            summaries.insert(idx, summary);
+        }
+    }
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> FileContent {
+        FileContent {
+            path: path.to_string(),
+            content: content.to_string(),
+            token_count: None,
+        }
+    }
+
+    // ── locale_slot ──
+
+    #[test]
+    fn locale_slot_recognizes_common_layouts() {
+        assert_eq!(
+            locale_slot("src/locales/pt-BR.json"),
+            Some(("src/locales/{locale}.json".to_string(), "pt-BR".to_string()))
+        );
+        assert_eq!(
+            locale_slot("public/locales/fr/common.json"),
+            Some((
+                "public/locales/{locale}/common.json".to_string(),
+                "fr".to_string()
+            ))
+        );
+        assert_eq!(
+            locale_slot("app/locale/de/LC_MESSAGES/django.po"),
+            Some((
+                "app/locale/{locale}/LC_MESSAGES/django.po".to_string(),
+                "de".to_string()
+            ))
+        );
+        assert_eq!(
+            locale_slot("po/es.po"),
+            Some(("po/{locale}.po".to_string(), "es".to_string()))
+        );
+    }
+
+    #[test]
+    fn locale_slot_ignores_other_json() {
+        assert_eq!(locale_slot("src/en.json"), None);
+        assert_eq!(locale_slot("locales/README.md"), None);
+        assert_eq!(locale_slot("locales/common.json"), None);
+        assert_eq!(locale_slot("package.json"), None);
+    }
+
+    // ── summarize_translations ──
+
+    #[test]
+    fn non_reference_locales_are_summarized_against_english() {
+        let files = vec![
+            file("locales/de.json", r#"{"greeting": {"hello": "Hallo"}}"#),
+            file(
+                "locales/en.json",
+                r#"{"greeting": {"hello": "Hello", "bye": "Bye"}, "title": "App"}"#,
+            ),
+            file(
+                "locales/fr.json",
+                r#"{"greeting": {"hello": "Bonjour", "bye": "Salut"}, "title": "App", "beta": "Bêta"}"#,
+            ),
+            file("src/app.ts", "export {};"),
+        ];
+        let summaries = summarize_translations(&files);
+        assert_eq!(summaries.len(), 2);
+        assert!(!summaries.contains_key(&1));
+
+        let german = &summaries[&0];
+        assert!(german.contains("`locales/en.json` (locale `en`) is packed in full and has 3 keys; this file has 1 of them."));
+        assert!(german.contains("Missing (2): greeting.bye, title"));
+
+        let french = &summaries[&2];
+        assert!(french.contains("has 3 of them"));
+        assert!(french.contains("Extra (1): beta"));
+        assert!(!french.contains("Missing"));
+    }
+
+    #[test]
+    fn gettext_catalogs_compare_message_ids() {
+        let files = vec![
+            file(
+                "locale/es/LC_MESSAGES/django.po",
+                "msgid \"\"\nmsgstr \"Language: es\"\n\nmsgid \"Hello\"\nmsgstr \"Hola\"\n",
+            ),
+            file(
+                "locale/ar/LC_MESSAGES/django.po",
+                "msgid \"\"\nmsgstr \"\"\n\nmsgid \"Hello\"\nmsgstr \"مرحبا\"\n\nmsgid \"\"\n\"Good \"\n\"bye\"\nmsgstr \"\"\n",
+            ),
+        ];
+        let summaries = summarize_translations(&files);
+        assert_eq!(summaries.len(), 1);
+        assert!(summaries[&0].contains("locale `es`; content omitted"));
+        assert!(summaries[&0].contains("`locale/ar/LC_MESSAGES/django.po` (locale `ar`) is packed in full and has 2 keys; this file has 1 of them."));
+    }
+
+    #[test]
+    fn lone_or_unparseable_resources_keep_their_content() {
+        assert!(summarize_translations(&[file("locales/en.json", "{}")]).is_empty());
+        let files = vec![
+            file("locales/en.json", "{\"a\": 1}"),
+            file("locales/fr.json", "{ broken"),
+        ];
+        assert!(summarize_translations(&files).is_empty());
+    }
+}
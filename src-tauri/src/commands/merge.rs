@@ -0,0 +1,179 @@
+use crate::models::MergeConflict;
+use similar::{Algorithm, DiffTag, capture_diff_slices};
+use std::ops::Range;
+
+const MARKER_OURS: &str = "<<<<<<< on disk\n";
+const MARKER_SPLIT: &str = "=======\n";
+const MARKER_THEIRS: &str = ">>>>>>> proposed\n";
+
+/// A line-based three-way merge: the merged text, with conflict markers around each region
+/// both sides changed differently.
+pub(crate) struct Merge {
+    pub text: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// One side's changes against the base: `(base lines, side lines)` pairs in order, with
+/// adjacent edits coalesced.
+fn changes(base: &[&str], side: &[&str]) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut hunks: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    for op in capture_diff_slices(Algorithm::Myers, base, side) {
+        let (tag, old, new) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        match hunks.last_mut() {
+            Some((last_old, last_new)) if last_old.end == old.start && last_new.end == new.start => {
+                last_old.end = old.end;
+                last_new.end = new.end;
+            }
+            _ => hunks.push((old, new)),
+        }
+    }
+    hunks
+}
+
+/// Where base line `at` falls in the side, given every side hunk that ends at or before it.
+fn map_line(hunks: &[(Range<usize>, Range<usize>)], at: usize) -> usize {
+    hunks
+        .iter()
+        .take_while(|(old, _)| old.end <= at)
+        .last()
+        .map_or(at, |(old, new)| new.end + (at - old.end))
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for line in lines {
+        out.push_str(line);
+    }
+}
+
+/// Keep conflict markers on their own lines when a side ends without a newline.
+fn ensure_newline(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Merge `ours` (the file on disk) and `theirs` (the proposed content), both derived from
+/// `base`. Changes on one side apply as-is, identical changes on both apply once, and edits
+/// that overlap or touch conflict.
+pub(crate) fn merge3(base: &str, ours: &str, theirs: &str) -> Merge {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let ours_hunks = changes(&base, &ours);
+    let theirs_hunks = changes(&base, &theirs);
+
+    let mut text = String::new();
+    let mut conflicts = Vec::new();
+    let mut cursor = 0;
+    let (mut next_ours, mut next_theirs) = (0, 0);
+    loop {
+        let start = match (ours_hunks.get(next_ours), theirs_hunks.get(next_theirs)) {
+            (Some((a, _)), Some((b, _))) => a.start.min(b.start),
+            (Some((a, _)), None) => a.start,
+            (None, Some((b, _))) => b.start,
+            (None, None) => break,
+        };
+        // Grow the region while either side has a hunk starting inside or right at its end.
+        let (first_ours, first_theirs) = (next_ours, next_theirs);
+        let mut end = start;
+        loop {
+            if let Some((old, _)) = ours_hunks.get(next_ours).filter(|(old, _)| old.start <= end) {
+                end = end.max(old.end);
+                next_ours += 1;
+            } else if let Some((old, _)) = theirs_hunks.get(next_theirs).filter(|(old, _)| old.start <= end) {
+                end = end.max(old.end);
+                next_theirs += 1;
+            } else {
+                break;
+            }
+        }
+
+        push_lines(&mut text, &base[cursor..start]);
+        cursor = end;
+        let ours_region = map_line(&ours_hunks[..first_ours], start)..map_line(&ours_hunks[..next_ours], end);
+        let theirs_region =
+            map_line(&theirs_hunks[..first_theirs], start)..map_line(&theirs_hunks[..next_theirs], end);
+        let ours_lines = &ours[ours_region];
+        let theirs_lines = &theirs[theirs_region];
+        if first_theirs == next_theirs || ours_lines == theirs_lines {
+            push_lines(&mut text, ours_lines);
+        } else if first_ours == next_ours {
+            push_lines(&mut text, theirs_lines);
+        } else {
+            ensure_newline(&mut text);
+            conflicts.push(MergeConflict {
+                line: text.matches('\n').count() + 1,
+                disk: ours_lines.concat(),
+                proposed: theirs_lines.concat(),
+            });
+            text.push_str(MARKER_OURS);
+            push_lines(&mut text, ours_lines);
+            ensure_newline(&mut text);
+            text.push_str(MARKER_SPLIT);
+            push_lines(&mut text, theirs_lines);
+            ensure_newline(&mut text);
+            text.push_str(MARKER_THEIRS);
+        }
+    }
+    push_lines(&mut text, &base[cursor..]);
+    Merge { text, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── merge3 ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn changes_to_different_regions_both_apply() {
+        let base = "a\nb\nc\nd\ne\n";
+        let merge = merge3(base, "A\nb\nc\nd\ne\n", "a\nb\nc\nd\nE\n");
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(merge.text, "A\nb\nc\nd\nE\n");
+    }
+
+    #[test]
+    fn one_sided_insertions_and_deletions_apply() {
+        let base = "a\nb\nc\nd\n";
+        let merge = merge3(base, "a\nnew\nb\nc\nd\n", "a\nb\nc\n");
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(merge.text, "a\nnew\nb\nc\n");
+    }
+
+    #[test]
+    fn identical_changes_on_both_sides_apply_once() {
+        let merge = merge3("a\nb\nc\n", "a\nB\nc\n", "a\nB\nc\n");
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(merge.text, "a\nB\nc\n");
+    }
+
+    #[test]
+    fn overlapping_changes_conflict_with_markers() {
+        let merge = merge3("a\nb\nc\n", "a\ndisk\nc\n", "a\nproposed\nc\n");
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].line, 2);
+        assert_eq!(merge.conflicts[0].disk, "disk\n");
+        assert_eq!(merge.conflicts[0].proposed, "proposed\n");
+        assert_eq!(
+            merge.text,
+            "a\n<<<<<<< on disk\ndisk\n=======\nproposed\n>>>>>>> proposed\nc\n"
+        );
+    }
+
+    #[test]
+    fn markers_stay_on_their_own_lines_without_a_final_newline() {
+        let merge = merge3("a\nb", "a\nx", "a\ny");
+        assert_eq!(merge.text, "a\n<<<<<<< on disk\nx\n=======\ny\n>>>>>>> proposed\n");
+    }
+
+    #[test]
+    fn unchanged_sides_return_the_other_side() {
+        assert_eq!(merge3("a\nb\n", "a\nb\n", "z\n").text, "z\n");
+        assert_eq!(merge3("a\nb\n", "z\n", "a\nb\n").text, "z\n");
+        assert_eq!(merge3("", "", "new\n").text, "new\n");
+    }
+}
@@ -1,3 +1,26 @@
+pub mod appendices;
+pub mod assets;
 pub mod ast;
+pub mod audit;
+pub mod build_systems;
+pub mod compression;
+pub mod data_profile;
 pub mod fs;
+pub mod graphql;
+pub mod idl;
+pub mod ipc;
+pub mod languages;
+pub mod locales;
+pub mod merge;
 pub mod pack;
+pub mod patch;
+pub mod profiles;
+pub mod redaction;
+pub mod response;
+pub mod routes;
+pub mod schema;
+pub mod select;
+pub mod stacks;
+pub mod template;
+pub mod tokenizer;
+pub mod undo;
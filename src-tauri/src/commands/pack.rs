@@ -1,48 +1,613 @@
-use crate::models::{FileContent, PackItem, PackRequest, PackResponse};
+use crate::commands::appendices::{render_config_surface, render_error_index, render_todo_index};
+use crate::commands::ast::{
+    check_parse_health, exported_symbol_names, outline_source, render_type_closure, strip_comments,
+};
+use crate::commands::build_systems::build_targets;
+use crate::commands::compression::gzip_json_response;
+use crate::commands::data_profile::summarize_data_files;
+use crate::commands::fs::{
+    read_directory_contents, read_project_file, read_text_file, walk_allowed_files, write_file_bytes, write_file_content,
+    MAX_DIRECTORY_FILE_BYTES,
+};
+use crate::commands::idl::{SchemaIndex, unselected_schemas};
+use crate::commands::ipc;
+use crate::commands::languages::associated_language;
+use crate::commands::locales::summarize_translations;
+use crate::commands::profiles::{apply_llm_profile, apply_pack_profile};
+use crate::commands::redaction::Redactor;
+use crate::commands::routes::client_route_dependencies;
+use crate::commands::schema::summarize_config_schemas;
+use crate::commands::template::PackTemplate;
+use crate::commands::tokenizer::TokenCounter;
+use crate::models::{
+    ClipboardChunk, DeltaResponse, FileContent, FileFragment, FilePlacement, FileTruncation, ImportDiagnostic, PackItem, PackPreview, PackRequest, PackResponse,
+    PackWrapper, PriorityRule, ProjectMetadata, RedactionRule, SectionBoundaries,
+};
+use std::borrow::Cow;
 use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
 
 /// Estimate tokens using a simple approximation (1 token ≈ 4 characters)
-fn estimate_tokens(content: &str) -> usize {
+pub(crate) fn estimate_tokens(content: &str) -> usize {
     (content.len() / 4).max(1)
 }
 
-fn format_file_header(path: &str, content: &str, format: &str) -> String {
+/// Bytes per token for BPE tokenizers (cl100k/o200k-style), by extension. Bytes rather
+/// than characters because callers often only know a file's size; the two agree for the
+/// ASCII that dominates source. Measured with cl100k over this repository's own sources
+/// (Rust backend, TypeScript frontend, docs and config) and rounded down so estimates lean
+/// high; `estimate_tokens_for_path_tracks_cl100k_on_the_fixtures` checks them against the
+/// test fixtures. Symbol-dense config and markup tokenize tighter than 4 bytes/token. `None`
+/// for extensions with no calibration.
+pub(crate) fn bytes_per_token(path: &str) -> Option<f64> {
+    let ratio = match path_extension(path).as_str() {
+        "svg" => 2.0,
+        "yaml" | "yml" | "toml" | "sh" | "bash" => 2.9,
+        "json" | "lock" => 3.0,
+        "css" | "scss" | "html" | "xml" => 3.1,
+        "md" | "mdx" | "rst" | "txt" => 3.6,
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "go" | "java" | "c" | "cpp" | "h" => 3.7,
+        "rs" | "py" | "rb" => 3.8,
+        _ => return None,
+    };
+    Some(ratio)
+}
+
+/// Fallback token estimate for a file when the frontend did not send a real count.
+pub(crate) fn estimate_tokens_for_path(path: &str, content: &str) -> usize {
+    match bytes_per_token(path) {
+        Some(ratio) => ((content.len() as f64 / ratio).ceil() as usize).max(1),
+        None => estimate_tokens(content),
+    }
+}
+
+/// Separator between file sections inside a pack, unless the request sets its own.
+const SECTION_SEPARATOR: &str = "\n\n";
+
+fn markdown_fence_language(path: &str) -> Cow<'static, str> {
+    if let Some(language) = associated_language(path) {
+        return Cow::Owned(language);
+    }
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    Cow::Borrowed(match ext {
+        "ts" | "tsx" => "typescript",
+        "js" | "jsx" => "javascript",
+        "rs" => "rust",
+        "py" => "python",
+        "go" => "go",
+        "md" => "markdown",
+        "json" => "json",
+        "css" => "css",
+        "html" => "html",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "sh" | "bash" => "bash",
+        _ => "text",
+    })
+}
+
+/// Bytes a section adds around the path and content, at most (before escaping); the JSON
+/// object with the longest language name and token count is the widest.
+const SECTION_OVERHEAD: usize =
+    r#"{"path": "", "language": "javascript", "content": "", "tokens": 18446744073709551615}"#.len();
+
+/// Joins the file objects of a JSON pack; custom boundaries would break the document.
+const JSON_SECTION_SEPARATOR: &str = ",\n";
+
+/// How sections are joined and fenced in a pack: the request's `SectionBoundaries`, with
+/// empty markers dropped and the separator falling back to `SECTION_SEPARATOR`.
+struct SectionLayout<'a> {
+    separator: &'a str,
+    file_start: Option<&'a str>,
+    file_end: Option<&'a str>,
+    /// Path -> short ID shown in the section header, when `file_ids` is on.
+    file_ids: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> SectionLayout<'a> {
+    fn new(boundaries: Option<&'a SectionBoundaries>) -> Self {
+        let marker = |text: &'a Option<String>| text.as_deref().filter(|text| !text.is_empty());
+        Self {
+            separator: boundaries
+                .and_then(|b| b.separator.as_deref())
+                .unwrap_or(SECTION_SEPARATOR),
+            file_start: boundaries.and_then(|b| marker(&b.file_start)),
+            file_end: boundaries.and_then(|b| marker(&b.file_end)),
+            file_ids: HashMap::new(),
+        }
+    }
+
+    fn with_file_ids(mut self, file_ids: HashMap<&'a str, &'a str>) -> Self {
+        self.file_ids = file_ids;
+        self
+    }
+
+    /// Bytes the separator and markers add around one section, at most.
+    fn overhead(&self, path: &str) -> usize {
+        let marker_len = |marker: Option<&str>| {
+            marker.map_or(0, |m| m.len() + 1 + m.matches("{path}").count() * path.len())
+        };
+        self.separator.len() + marker_len(self.file_start) + marker_len(self.file_end)
+    }
+
+    /// Append the separator (unless `first`) and one marked section, returning the section's
+    /// range, markers included. JSON sections are always joined by `JSON_SECTION_SEPARATOR`.
+    fn push(
+        &self,
+        out: &mut String,
+        first: bool,
+        path: &str,
+        content: &str,
+        tokens: usize,
+        format: &str,
+    ) -> Range<usize> {
+        let id = self.file_ids.get(path).copied();
+        if format == "json" {
+            if !first {
+                out.push_str(JSON_SECTION_SEPARATOR);
+            }
+            let start = out.len();
+            push_json_section(out, path, content, tokens, id);
+            return start..out.len();
+        }
+        if !first {
+            out.push_str(self.separator);
+        }
+        let start = out.len();
+        if let Some(marker) = self.file_start {
+            out.push_str(&marker.replace("{path}", path));
+            out.push('\n');
+        }
+        push_file_section(out, path, content, format, id);
+        if let Some(marker) = self.file_end {
+            out.push('\n');
+            out.push_str(&marker.replace("{path}", path));
+        }
+        start..out.len()
+    }
+}
+
+/// Escape text for an XML/HTML attribute value or element body.
+fn push_escaped_markup(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+/// Wrap `content` in CDATA, splitting any `]]>` so it cannot end the section early.
+fn push_cdata(out: &mut String, content: &str) {
+    out.push_str("<![CDATA[");
+    out.push_str(&content.replace("]]>", "]]]]><![CDATA[>"));
+    out.push_str("]]>");
+}
+
+/// Seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+fn format_utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days (Howard Hinnant), valid for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// One-line provenance header, e.g. `Source: app 1.2.0 @ 1a2b3c4d5e6f (uncommitted changes)`.
+/// `None` when nothing was detected.
+fn render_provenance(metadata: &ProjectMetadata) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(name) = metadata.name.as_deref().filter(|name| !name.is_empty()) {
+        parts.push(name.to_string());
+    }
+    if let Some(version) = metadata.version.as_deref().filter(|version| !version.is_empty()) {
+        parts.push(version.to_string());
+    }
+    if let Some(commit) = metadata.commit.as_deref().filter(|commit| !commit.is_empty()) {
+        let short = &commit[..floor_char_boundary(commit, 12)];
+        parts.push(format!("@ {short}"));
+        if metadata.dirty {
+            parts.push("(uncommitted changes)".to_string());
+        }
+    }
+    (!parts.is_empty()).then(|| format!("Source: {}", parts.join(" ")))
+}
+
+/// The current time as an RFC 3339 UTC timestamp.
+pub(crate) fn utc_now() -> String {
+    format_utc_timestamp(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default(),
+    )
+}
+
+/// Where a pack sits in its run, for the wrapper around its sections.
+struct PackFrame<'a> {
+    format: &'a str,
+    wrapper: Option<&'a PackWrapper>,
+    index: usize,
+    total: usize,
+    generated_at: &'a str,
+    provenance: Option<&'a str>,
+    /// (id, path) of each file in this pack when `file_ids` is on, listed after the opening.
+    file_index: &'a [(&'a str, &'a str)],
+    file_count: usize,
+    /// Overview of the pack's directories, languages, and symbols when `pack_notes` is on.
+    note: Option<&'a str>,
+}
+
+impl PackFrame<'_> {
+    /// Fill `{index}`, `{total}`, and `{project}` in a delimiter.
+    fn expand(&self, delimiter: &str) -> String {
+        let project = self.wrapper.and_then(|w| w.project_name.as_deref()).unwrap_or_default();
+        delimiter
+            .replace("{index}", &self.index.to_string())
+            .replace("{total}", &self.total.to_string())
+            .replace("{project}", project)
+    }
+
+    /// (label, value) pairs the wrapper asks for, in display order.
+    fn metadata(&self) -> Vec<(&'static str, String)> {
+        let Some(wrapper) = self.wrapper else {
+            return Vec::new();
+        };
+        let mut fields = Vec::new();
+        if wrapper.include_index {
+            fields.push(("pack", format!("{}/{}", self.index, self.total)));
+        }
+        if let Some(project) = wrapper.project_name.as_deref().filter(|name| !name.is_empty()) {
+            fields.push(("project", project.to_string()));
+        }
+        if wrapper.include_timestamp {
+            fields.push(("generated", self.generated_at.to_string()));
+        }
+        fields
+    }
+
+    /// Fill `{{pack_index}}`, `{{total_packs}}`, `{{file_count}}`, and `{{project_name}}` in a
+    /// preamble or epilogue.
+    fn render(&self, template: &str) -> String {
+        let project = self.wrapper.and_then(|w| w.project_name.as_deref()).unwrap_or_default();
+        template
+            .replace("{{pack_index}}", &self.index.to_string())
+            .replace("{{total_packs}}", &self.total.to_string())
+            .replace("{{file_count}}", &self.file_count.to_string())
+            .replace("{{project_name}}", project)
+    }
+
+    fn preamble(&self) -> Option<String> {
+        let template = self.wrapper?.preamble.as_deref().filter(|text| !text.trim().is_empty())?;
+        Some(self.render(template.trim_end()))
+    }
+
+    fn epilogue(&self) -> Option<String> {
+        let template = self.wrapper?.epilogue.as_deref().filter(|text| !text.trim().is_empty())?;
+        Some(self.render(template.trim_end()))
+    }
+
+    fn opening(&self) -> Option<String> {
+        self.wrapper?.opening.as_deref().map(|delimiter| self.expand(delimiter))
+    }
+
+    fn closing(&self) -> Option<String> {
+        self.wrapper?.closing.as_deref().map(|delimiter| self.expand(delimiter))
+    }
+}
+
+/// Append `text` as a quoted, escaped JSON string.
+fn push_json_string(out: &mut String, text: &str) {
+    out.push_str(&serde_json::Value::from(text).to_string());
+}
+
+/// One file of a JSON pack: `{"path", "language", "content", "tokens"}` on a single line,
+/// led by `"id"` when the file has one.
+fn push_json_section(out: &mut String, path: &str, content: &str, tokens: usize, id: Option<&str>) {
+    out.push('{');
+    if let Some(id) = id {
+        out.push_str("\"id\": ");
+        push_json_string(out, id);
+        out.push_str(", ");
+    }
+    out.push_str("\"path\": ");
+    push_json_string(out, path);
+    out.push_str(", \"language\": ");
+    push_json_string(out, &markdown_fence_language(path));
+    out.push_str(", \"content\": ");
+    push_json_string(out, content);
+    out.push_str(&format!(", \"tokens\": {tokens}}}"));
+}
+
+/// Markup comments cannot contain `--`.
+fn push_markup_comment(out: &mut String, text: &str) {
+    out.push_str("<!-- ");
+    out.push_str(&text.replace("--", "- -"));
+    out.push_str(" -->\n");
+}
+
+/// Open a pack: delimiter and metadata for text formats; a root element (metadata as
+/// attributes, delimiters as comments) for markup formats so the pack stays well-formed; an
+/// object (metadata as fields) whose `files` array holds the sections for JSON.
+fn push_pack_open(out: &mut String, frame: &PackFrame) {
+    let metadata = frame.metadata();
+    if frame.format == "json" {
+        out.push_str("{\n");
+        let fields = [
+            ("provenance", frame.provenance.map(str::to_string)),
+            ("opening", frame.opening()),
+            ("preamble", frame.preamble()),
+            ("note", frame.note.map(str::to_string)),
+        ];
+        let fields = fields
+            .into_iter()
+            .filter_map(|(label, value)| Some((label, value?)))
+            .chain(metadata);
+        for (label, value) in fields {
+            push_json_string(out, label);
+            out.push_str(": ");
+            push_json_string(out, &value);
+            out.push_str(",\n");
+        }
+        if !frame.file_index.is_empty() {
+            out.push_str("\"fileIndex\": {");
+            for (position, (id, path)) in frame.file_index.iter().enumerate() {
+                out.push_str(if position == 0 { "\n" } else { ",\n" });
+                push_json_string(out, id);
+                out.push_str(": ");
+                push_json_string(out, path);
+            }
+            out.push_str("\n},\n");
+        }
+        out.push_str("\"files\": [\n");
+        return;
+    }
+    let markup = matches!(frame.format, "xml" | "html");
+    if let Some(provenance) = frame.provenance {
+        if markup {
+            push_markup_comment(out, provenance);
+        } else {
+            out.push_str(provenance);
+            out.push('\n');
+        }
+    }
+    let root = match frame.format {
+        "xml" => "pack",
+        "html" => "div class=\"pack\"",
+        _ => {
+            if let Some(opening) = frame.opening() {
+                out.push_str(&opening);
+                out.push('\n');
+            }
+            if !metadata.is_empty() {
+                let line: Vec<String> = metadata.iter().map(|(label, value)| format!("{label}: {value}")).collect();
+                out.push_str(&line.join(" · "));
+                out.push('\n');
+            }
+            if let Some(preamble) = frame.preamble() {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&preamble);
+                out.push('\n');
+            }
+            if let Some(note) = frame.note {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(note);
+                out.push('\n');
+            }
+            if !frame.file_index.is_empty() {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str("Files in this pack:\n");
+                for (id, path) in frame.file_index {
+                    out.push_str(&format!("[{id}] {path}\n"));
+                }
+            }
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            return;
+        }
+    };
+
+    if let Some(opening) = frame.opening() {
+        push_markup_comment(out, &opening);
+    }
+    out.push('<');
+    out.push_str(root);
+    for (label, value) in &metadata {
+        let attribute = if frame.format == "html" { format!(" data-{label}=\"") } else { format!(" {label}=\"") };
+        out.push_str(&attribute);
+        push_escaped_markup(out, value);
+        out.push('"');
+    }
+    out.push_str(">\n");
+    if let Some(preamble) = frame.preamble() {
+        out.push_str(if frame.format == "html" { "<p class=\"preamble\">" } else { "<preamble>" });
+        push_escaped_markup(out, &preamble);
+        out.push_str(if frame.format == "html" { "</p>\n\n" } else { "</preamble>\n\n" });
+    }
+    if let Some(note) = frame.note {
+        out.push_str(if frame.format == "html" { "<p class=\"pack-note\">" } else { "<pack_note>" });
+        push_escaped_markup(out, note);
+        out.push_str(if frame.format == "html" { "</p>\n\n" } else { "</pack_note>\n\n" });
+    }
+    if !frame.file_index.is_empty() {
+        let (open, close) = if frame.format == "html" {
+            ("<ul class=\"file-index\">\n", "</ul>\n\n")
+        } else {
+            ("<file_index>\n", "</file_index>\n\n")
+        };
+        out.push_str(open);
+        for (id, path) in frame.file_index {
+            out.push_str(if frame.format == "html" { "<li data-id=\"" } else { "<entry id=\"" });
+            push_escaped_markup(out, id);
+            out.push_str(if frame.format == "html" { "\">" } else { "\" path=\"" });
+            push_escaped_markup(out, path);
+            out.push_str(if frame.format == "html" { "</li>\n" } else { "\"/>\n" });
+        }
+        out.push_str(close);
+    }
+}
+
+fn push_pack_close(out: &mut String, frame: &PackFrame) {
+    let epilogue = frame.epilogue();
+    match frame.format {
+        "json" => {
+            out.push_str("\n]");
+            if let Some(epilogue) = &epilogue {
+                out.push_str(",\n\"epilogue\": ");
+                push_json_string(out, epilogue);
+            }
+            if let Some(closing) = frame.closing() {
+                out.push_str(",\n\"closing\": ");
+                push_json_string(out, &closing);
+            }
+            out.push_str("\n}");
+            return;
+        }
+        "xml" => {
+            if let Some(epilogue) = &epilogue {
+                out.push_str("\n\n<epilogue>");
+                push_escaped_markup(out, epilogue);
+                out.push_str("</epilogue>");
+            }
+            out.push_str("\n</pack>");
+        }
+        "html" => {
+            if let Some(epilogue) = &epilogue {
+                out.push_str("\n\n<p class=\"epilogue\">");
+                push_escaped_markup(out, epilogue);
+                out.push_str("</p>");
+            }
+            out.push_str("\n</div>");
+        }
+        _ => {
+            if let Some(epilogue) = &epilogue {
+                out.push_str("\n\n");
+                out.push_str(epilogue);
+            }
+            if let Some(closing) = frame.closing() {
+                out.push_str("\n\n");
+                out.push_str(&closing);
+            }
+            return;
+        }
+    }
+    if let Some(closing) = frame.closing() {
+        out.push('\n');
+        push_markup_comment(out, &closing);
+    }
+}
+
+/// Repomix-style `<directory_structure>` listing every packed file, opening the first XML pack.
+fn push_directory_structure(out: &mut String, files: &[FileContent]) {
+    let mut entries: Vec<(&str, &str)> = files.iter().map(|file| (file.path.as_str(), "")).collect();
+    entries.sort();
+    let mut tree = String::new();
+    push_path_tree(&mut tree, &entries);
+    out.push_str("<directory_structure>\n");
+    push_escaped_markup(out, &tree);
+    out.push_str("</directory_structure>\n\n");
+}
+
+/// Short per-file IDs (`F001`, `F002`, ...) by sorted path, indexed like `files`, so a file
+/// keeps its ID for the same selection however the packs are split.
+fn assign_file_ids(files: &[FileContent]) -> Vec<String> {
+    let mut order: Vec<usize> = (0..files.len()).collect();
+    order.sort_by(|&a, &b| files[a].path.cmp(&files[b].path));
+    let width = files.len().to_string().len().max(3);
+    let mut ids = vec![String::new(); files.len()];
+    for (position, idx) in order.into_iter().enumerate() {
+        ids[idx] = format!("F{:0width$}", position + 1);
+    }
+    ids
+}
+
+/// `path`, or `[F017] path` when the file has an ID.
+fn push_section_label(out: &mut String, path: &str, id: Option<&str>) {
+    if let Some(id) = id {
+        out.push('[');
+        out.push_str(id);
+        out.push_str("] ");
+    }
+    out.push_str(path);
+}
+
+/// Append one file's section straight into the pack buffer.
+fn push_file_section(out: &mut String, path: &str, content: &str, format: &str, id: Option<&str>) {
     match format {
         "markdown" => {
-            let ext = std::path::Path::new(path)
-                .extension()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string();
-            let lang = match ext.as_str() {
-                "ts" | "tsx" => "typescript",
-                "js" | "jsx" => "javascript",
-                "rs" => "rust",
-                "py" => "python",
-                "go" => "go",
-                "md" => "markdown",
-                "json" => "json",
-                "css" => "css",
-                "html" => "html",
-                "toml" => "toml",
-                "yaml" | "yml" => "yaml",
-                "sh" | "bash" => "bash",
-                _ => "text",
-            };
-            format!("```{lang}\n// {path}\n{content}\n```")
+            out.push_str("```");
+            out.push_str(&markdown_fence_language(path));
+            out.push_str("\n// ");
+            push_section_label(out, path, id);
+            out.push('\n');
+            out.push_str(content);
+            out.push_str("\n```");
+        }
+        "xml" => {
+            out.push_str("<file ");
+            if let Some(id) = id {
+                out.push_str("id=\"");
+                push_escaped_markup(out, id);
+                out.push_str("\" ");
+            }
+            out.push_str("path=\"");
+            push_escaped_markup(out, path);
+            out.push_str("\">\n");
+            push_cdata(out, content);
+            out.push_str("\n</file>");
+        }
+        "html" => {
+            out.push_str("<pre ");
+            if let Some(id) = id {
+                out.push_str("data-id=\"");
+                push_escaped_markup(out, id);
+                out.push_str("\" ");
+            }
+            out.push_str("data-path=\"");
+            push_escaped_markup(out, path);
+            out.push_str("\">");
+            push_escaped_markup(out, content);
+            out.push_str("</pre>");
         }
         _ => {
             // plaintext
-            format!("// {path}\n{content}")
+            out.push_str("// ");
+            push_section_label(out, path, id);
+            out.push('\n');
+            out.push_str(content);
         }
     }
 }
 
-fn wrap_pack(content: &str) -> String {
-    content.to_string()
-}
-
-fn normalize_path(path: &str) -> String {
+pub(crate) fn normalize_path(path: &str) -> String {
     let mut parts: Vec<&str> = Vec::new();
     let replaced = path.replace('\\', "/");
 
@@ -92,6 +657,9 @@ fn is_doc_file(path: &str) -> bool {
 }
 
 fn doc_priority(path: &str) -> (u8, String) {
+    // Parts of a split doc share their original's key, so a stable sort keeps them in order.
+    let origin = split_part_origin(path).map(|(origin, _, _)| origin);
+    let path = origin.as_deref().unwrap_or(path);
     let normalized = normalize_path(path).to_ascii_lowercase();
     let basename = file_basename(path);
 
@@ -154,6 +722,8 @@ fn extract_quoted_segments(line: &str) -> Vec<String> {
 
 fn extract_module_specifiers(content: &str) -> Vec<String> {
     let mut specifiers: HashSet<String> = HashSet::new();
+    let mut in_go_import_block = false;
+    let mut pending_dynamic_import = false;
 
     for raw_line in content.lines() {
         let line = raw_line.trim();
@@ -165,6 +735,41 @@ fn extract_module_specifiers(content: &str) -> Vec<String> {
             continue;
         }
 
+        // Go: import ( "fmt"\n alias "github.com/org/app/pkg" )
+        if in_go_import_block {
+            if line.starts_with(')') {
+                in_go_import_block = false;
+            } else if let Some(q) = extract_quoted_segments(line).into_iter().find(|q| !q.is_empty()) {
+                specifiers.insert(q);
+            }
+            continue;
+        }
+        if line == "import (" {
+            in_go_import_block = true;
+            continue;
+        }
+
+        // Multi-line dynamic import: `import(\n  './Page'\n)`
+        if pending_dynamic_import {
+            pending_dynamic_import = false;
+            if let Some(q) = extract_quoted_segments(line).into_iter().find(|q| !q.is_empty()) {
+                specifiers.insert(q);
+            }
+        }
+        if line.ends_with("import(") || line.ends_with("require(") {
+            pending_dynamic_import = true;
+        }
+
+        // Legacy Angular lazy routes: loadChildren: './admin/admin.module#AdminModule'
+        if line.contains("loadChildren") || line.contains("loadComponent") {
+            for q in extract_quoted_segments(line) {
+                let module = q.split('#').next().unwrap_or("");
+                if module.starts_with('.') {
+                    specifiers.insert(module.to_string());
+                }
+            }
+        }
+
         // JS/TS/Rust/Go style quoted imports: import/export/from/require/import()
         if line.starts_with("import ")
             || line.starts_with("export ")
@@ -219,17 +824,71 @@ fn extract_module_specifiers(content: &str) -> Vec<String> {
     specifiers.into_iter().collect()
 }
 
-fn resolve_module_specifier(
-    specifier: &str,
-    current_path: &str,
-    path_to_idx: &HashMap<String, usize>,
-) -> Option<usize> {
+/// Lines inside the fenced code blocks of a Markdown document.
+fn markdown_fenced_code(content: &str) -> String {
+    let mut code = String::new();
+    let mut fence: Option<(char, usize)> = None;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        let run = marker.map_or(0, |c| trimmed.chars().take_while(|&ch| ch == c).count());
+        match (fence, marker) {
+            (None, Some(c)) if run >= 3 => fence = Some((c, run)),
+            (Some((c, len)), Some(m)) if m == c && run >= len && trimmed[run..].trim().is_empty() => fence = None,
+            (Some(_), _) => {
+                code.push_str(line);
+                code.push('\n');
+            }
+            _ => {}
+        }
+    }
+    code
+}
+
+/// Source of a Jupyter notebook's code cells, one cell after another.
+fn notebook_code_cells(content: &str) -> String {
+    let Ok(notebook) = serde_json::from_str::<serde_json::Value>(content) else {
+        return String::new();
+    };
+    let mut code = String::new();
+    let cells = notebook.get("cells").and_then(|cells| cells.as_array());
+    for cell in cells.into_iter().flatten() {
+        if cell.get("cell_type").and_then(|kind| kind.as_str()) != Some("code") {
+            continue;
+        }
+        match cell.get("source") {
+            Some(serde_json::Value::String(source)) => code.push_str(source),
+            Some(serde_json::Value::Array(lines)) => {
+                for line in lines.iter().filter_map(|line| line.as_str()) {
+                    code.push_str(line);
+                }
+            }
+            _ => {}
+        }
+        code.push('\n');
+    }
+    code
+}
+
+/// Specifiers a file imports. Markdown docs and notebooks contribute only their embedded code,
+/// so tutorials pull the source files their examples import into the graph.
+pub(crate) fn file_module_specifiers(file: &FileContent) -> Vec<String> {
+    match path_extension(&file.path).as_str() {
+        "md" | "mdx" => extract_module_specifiers(&markdown_fenced_code(&file.content)),
+        "ipynb" => extract_module_specifiers(&notebook_code_cells(&file.content)),
+        _ => extract_module_specifiers(&file.content),
+    }
+}
+
+/// Expand a module specifier into the normalized file paths it could refer to.
+/// Returns no candidates for URLs and `node:` builtins, which never resolve locally.
+pub(crate) fn module_specifier_candidates(specifier: &str, current_path: &str) -> Vec<String> {
     if specifier.is_empty()
         || specifier.starts_with("http://")
         || specifier.starts_with("https://")
         || specifier.starts_with("node:")
     {
-        return None;
+        return Vec::new();
     }
 
     const EXTENSIONS: [&str; 10] = ["ts", "tsx", "js", "jsx", "py", "rs", "go", "json", "md", "mdx"];
@@ -267,23 +926,147 @@ fn resolve_module_specifier(
         }
     }
 
-    for candidate in expanded {
-        if let Some(idx) = path_to_idx.get(&candidate) {
-            return Some(*idx);
+    expanded
+}
+
+fn resolve_module_specifier(
+    specifier: &str,
+    current_path: &str,
+    path_to_idx: &HashMap<String, usize>,
+) -> Option<usize> {
+    module_specifier_candidates(specifier, current_path)
+        .into_iter()
+        .find_map(|candidate| path_to_idx.get(&candidate).copied())
+}
+
+/// Parse the `module` directive of a `go.mod` file.
+fn parse_go_module_path(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("module")?;
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let module = rest.trim().trim_matches('"');
+        (!module.is_empty()).then(|| module.to_string())
+    })
+}
+
+/// Resolves import specifiers against the selected files, including Go package
+/// imports that are relative to the module path declared in a selected `go.mod`.
+pub(crate) struct ImportResolver {
+    path_to_idx: HashMap<String, usize>,
+    /// (module path, module root dir), longest module path first.
+    go_modules: Vec<(String, String)>,
+    /// package dir -> `.go` files directly inside it
+    go_packages: HashMap<String, Vec<usize>>,
+    /// original path of a split file -> its parts, in part order
+    fragments: HashMap<String, Vec<usize>>,
+    /// Tauri command name -> Rust files defining it
+    command_handlers: HashMap<String, Vec<usize>>,
+    schemas: SchemaIndex,
+}
+
+/// Parts of every split file among `normalized_paths`, keyed by the original path, in part order.
+fn fragment_groups(normalized_paths: &[String]) -> HashMap<String, Vec<usize>> {
+    let mut parts: Vec<(String, usize, usize)> = normalized_paths
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, path)| split_part_origin(path).map(|(origin, part, _)| (origin, part, idx)))
+        .collect();
+    parts.sort();
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (origin, _, idx) in parts {
+        groups.entry(origin).or_default().push(idx);
+    }
+    groups
+}
+
+impl ImportResolver {
+    pub(crate) fn new(files: &[FileContent], normalized_paths: &[String]) -> Self {
+        let mut path_to_idx: HashMap<String, usize> = HashMap::new();
+        let mut go_modules: Vec<(String, String)> = Vec::new();
+        let mut go_packages: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (idx, path) in normalized_paths.iter().enumerate() {
+            path_to_idx.insert(path.clone(), idx);
+
+            if file_basename(path) == "go.mod" {
+                if let Some(module) = parse_go_module_path(&files[idx].content) {
+                    go_modules.push((module, parent_dir(path).to_string()));
+                }
+            } else if path_extension(path) == "go" {
+                go_packages
+                    .entry(parent_dir(path).to_string())
+                    .or_default()
+                    .push(idx);
+            }
+        }
+
+        go_modules.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            path_to_idx,
+            go_modules,
+            go_packages,
+            fragments: fragment_groups(normalized_paths),
+            command_handlers: ipc::command_handlers(files),
+            schemas: SchemaIndex::new(files, normalized_paths),
+        }
+    }
+
+    /// Map a Go import path onto a package directory inside the selection.
+    fn go_package_dir(&self, specifier: &str) -> Option<String> {
+        self.go_modules.iter().find_map(|(module, root)| {
+            let rest = if specifier == module {
+                ""
+            } else {
+                specifier.strip_prefix(module.as_str())?.strip_prefix('/')?
+            };
+            Some(normalize_path(&format!("{root}/{rest}")))
+        })
+    }
+
+    pub(crate) fn resolve(&self, specifier: &str, current_path: &str) -> Vec<usize> {
+        if let Some(idx) = resolve_module_specifier(specifier, current_path, &self.path_to_idx) {
+            return vec![idx];
         }
+        // An import of a split file depends on all of its parts.
+        if let Some(parts) = module_specifier_candidates(specifier, current_path)
+            .iter()
+            .find_map(|candidate| self.fragments.get(candidate))
+        {
+            return parts.clone();
+        }
+
+        self.go_package_dir(specifier)
+            .and_then(|dir| self.go_packages.get(&dir).cloned())
+            .unwrap_or_default()
     }
 
-    None
+    /// Everything `file` depends on: resolved imports, the schemas behind generated code it is
+    /// or imports, and the Rust handlers of the Tauri commands it `invoke`s.
+    pub(crate) fn dependencies(&self, file: &FileContent, current_path: &str) -> Vec<usize> {
+        let mut deps: Vec<usize> = Vec::new();
+        for spec in file_module_specifiers(file) {
+            match self.resolve(&spec, current_path) {
+                resolved if resolved.is_empty() => deps.extend(self.schemas.sources_of_import(&spec)),
+                resolved => deps.extend(resolved),
+            }
+        }
+        deps.extend(self.schemas.sources_of(current_path, &file.content));
+        for command in ipc::invoked_commands(file) {
+            deps.extend(self.command_handlers.get(&command).into_iter().flatten());
+        }
+        deps
+    }
 }
 
-fn build_dependency_graph(files: &[FileContent]) -> (Vec<String>, Vec<HashSet<usize>>, Vec<usize>) {
+/// Normalized paths, dependency -> dependents edges, and each file's dependency count.
+pub(crate) fn build_dependency_graph(files: &[FileContent]) -> (Vec<String>, Vec<HashSet<usize>>, Vec<usize>) {
     let n = files.len();
     let normalized_paths: Vec<String> = files.iter().map(|f| normalize_path(&f.path)).collect();
-
-    let mut path_to_idx: HashMap<String, usize> = HashMap::new();
-    for (idx, path) in normalized_paths.iter().enumerate() {
-        path_to_idx.insert(path.clone(), idx);
-    }
+    let resolver = ImportResolver::new(files, &normalized_paths);
+    let route_dependencies = client_route_dependencies(files);
 
     // dependency -> dependents
     let mut edges: Vec<HashSet<usize>> = vec![HashSet::new(); n];
@@ -291,10 +1074,22 @@ fn build_dependency_graph(files: &[FileContent]) -> (Vec<String>, Vec<HashSet<us
 
     for (idx, file) in files.iter().enumerate() {
         let current_path = &normalized_paths[idx];
-        for spec in extract_module_specifiers(&file.content) {
-            if let Some(dep_idx) = resolve_module_specifier(&spec, current_path, &path_to_idx) {
-                if dep_idx != idx && edges[dep_idx].insert(idx) {
-                    indegree[idx] += 1;
+        let route_deps = route_dependencies[idx].iter().copied();
+        for dep_idx in resolver.dependencies(file, current_path).into_iter().chain(route_deps) {
+            if dep_idx != idx && edges[dep_idx].insert(idx) {
+                indegree[idx] += 1;
+            }
+        }
+    }
+    // A target's files come after the files of the targets it links.
+    let targets = build_targets(files, &normalized_paths);
+    for target in &targets {
+        for dependency in target.depends_on.iter().map(|&dependency| &targets[dependency]) {
+            for &dep_idx in dependency.members.iter().filter(|idx| target.members.binary_search(idx).is_err()) {
+                for &idx in target.members.iter().filter(|idx| dependency.members.binary_search(idx).is_err()) {
+                    if edges[dep_idx].insert(idx) {
+                        indegree[idx] += 1;
+                    }
                 }
             }
         }
@@ -345,42 +1140,80 @@ fn compute_dependency_order(files: &[FileContent]) -> Vec<usize> {
         order.extend(remaining);
     }
 
-    order
+    keep_fragments_together(order, &fragment_groups(&normalized_paths))
+}
+
+/// Move every part of a split file up to where its first part sits in `order`, in part order.
+fn keep_fragments_together(order: Vec<usize>, fragments: &HashMap<String, Vec<usize>>) -> Vec<usize> {
+    if fragments.is_empty() {
+        return order;
+    }
+    let group_of: HashMap<usize, &Vec<usize>> = fragments
+        .values()
+        .flat_map(|parts| parts.iter().map(move |&idx| (idx, parts)))
+        .collect();
+    let mut placed = vec![false; order.len()];
+    let mut together = Vec::with_capacity(order.len());
+    for idx in order {
+        for &member in group_of.get(&idx).map_or(std::slice::from_ref(&idx), |parts| parts.as_slice()) {
+            if !placed[member] {
+                placed[member] = true;
+                together.push(member);
+            }
+        }
+    }
+    together
 }
 
-/// Build undirected file adjacency graph from imports for related-file grouping.
+/// Build undirected file adjacency graph from imports, Tauri `invoke` calls, HTTP client calls
+/// and build targets for related-file grouping.
 fn build_related_adjacency(files: &[FileContent]) -> Vec<HashSet<usize>> {
     let n = files.len();
     let normalized_paths: Vec<String> = files.iter().map(|f| normalize_path(&f.path)).collect();
-
-    let mut path_to_idx: HashMap<String, usize> = HashMap::new();
-    for (idx, path) in normalized_paths.iter().enumerate() {
-        path_to_idx.insert(path.clone(), idx);
-    }
+    let resolver = ImportResolver::new(files, &normalized_paths);
+    let route_dependencies = client_route_dependencies(files);
 
     let mut adjacency: Vec<HashSet<usize>> = vec![HashSet::new(); n];
 
     for (idx, file) in files.iter().enumerate() {
         let current_path = &normalized_paths[idx];
-        for spec in extract_module_specifiers(&file.content) {
-            if let Some(dep_idx) = resolve_module_specifier(&spec, current_path, &path_to_idx) {
-                if dep_idx != idx {
-                    adjacency[idx].insert(dep_idx);
-                    adjacency[dep_idx].insert(idx);
-                }
+        let route_deps = route_dependencies[idx].iter().copied();
+        for dep_idx in resolver.dependencies(file, current_path).into_iter().chain(route_deps) {
+            if dep_idx != idx {
+                adjacency[idx].insert(dep_idx);
+                adjacency[dep_idx].insert(idx);
             }
         }
     }
+    // Parts of a split file stay in one group even when only the first carries imports.
+    for parts in resolver.fragments.values() {
+        for pair in parts.windows(2) {
+            adjacency[pair[0]].insert(pair[1]);
+            adjacency[pair[1]].insert(pair[0]);
+        }
+    }
+    // So do the files one build target or module builds, with a Makefile or CMakeLists.txt that
+    // declares no other target.
+    let targets = build_targets(files, &normalized_paths);
+    let mut declared: HashMap<usize, usize> = HashMap::new();
+    for target in &targets {
+        *declared.entry(target.build_file).or_default() += 1;
+    }
+    for target in &targets {
+        let build_file = (declared[&target.build_file] == 1).then_some(target.build_file);
+        let group: Vec<usize> = build_file.into_iter().chain(target.members.iter().copied()).collect();
+        for pair in group.windows(2).filter(|pair| pair[0] != pair[1]) {
+            adjacency[pair[0]].insert(pair[1]);
+            adjacency[pair[1]].insert(pair[0]);
+        }
+    }
 
     adjacency
 }
 
 /// Group code files by import-connected components and keep dependency order inside each group.
-fn group_code_by_related_components(code_order: &[usize], related: &[HashSet<usize>]) -> Vec<usize> {
-    if code_order.len() <= 1 {
-        return code_order.to_vec();
-    }
-
+/// Components reached from `lead` come first, in `lead` order.
+fn related_components(code_order: &[usize], lead: &[usize], related: &[HashSet<usize>]) -> Vec<Vec<usize>> {
     let allowed: HashSet<usize> = code_order.iter().copied().collect();
     let mut position: HashMap<usize, usize> = HashMap::new();
     for (pos, idx) in code_order.iter().enumerate() {
@@ -388,10 +1221,10 @@ fn group_code_by_related_components(code_order: &[usize], related: &[HashSet<usi
     }
 
     let mut visited: HashSet<usize> = HashSet::new();
-    let mut grouped: Vec<usize> = Vec::with_capacity(code_order.len());
+    let mut grouped: Vec<Vec<usize>> = Vec::new();
 
-    for &start in code_order {
-        if visited.contains(&start) {
+    for &start in lead.iter().chain(code_order) {
+        if !allowed.contains(&start) || visited.contains(&start) {
             continue;
         }
 
@@ -411,7 +1244,7 @@ fn group_code_by_related_components(code_order: &[usize], related: &[HashSet<usi
         }
 
         component.sort_by_key(|idx| *position.get(idx).unwrap_or(&usize::MAX));
-        grouped.extend(component);
+        grouped.push(component);
     }
 
     grouped
@@ -433,26 +1266,275 @@ fn split_docs_and_code(ordered_indices: &[usize], files: &[FileContent]) -> (Vec
     (docs, code)
 }
 
-/// Preserve relative order and split into near-equal token packs.
-fn distribute_files(ordered_indices: &[usize], num_packs: usize, token_counts: &[usize]) -> Vec<Vec<usize>> {
-    let n = ordered_indices.len();
-    if n == 0 {
-        return Vec::new();
+/// Code files the docs' examples import, in doc order, so they can open the code region.
+fn doc_referenced_code(docs: &[usize], files: &[FileContent], related: &[HashSet<usize>]) -> Vec<usize> {
+    let mut referenced = Vec::new();
+    let mut seen = HashSet::new();
+    for &doc in docs {
+        let mut neighbors: Vec<usize> = related[doc]
+            .iter()
+            .copied()
+            .filter(|&idx| !is_doc_file(&files[idx].path))
+            .collect();
+        neighbors.sort_by(|a, b| files[*a].path.cmp(&files[*b].path));
+        for idx in neighbors {
+            if seen.insert(idx) {
+                referenced.push(idx);
+            }
+        }
     }
+    referenced
+}
 
-    let pack_count = num_packs.min(n).max(1);
-    if pack_count == 1 {
-        return vec![ordered_indices.to_vec()];
+/// Weight of a file under `PackRequest::priorities`, most important first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Priority {
+    High,
+    Normal,
+    Low,
+}
+
+impl Priority {
+    fn parse(weight: &str) -> Result<Self, String> {
+        match weight.trim().to_ascii_lowercase().as_str() {
+            "high" => Ok(Self::High),
+            "normal" => Ok(Self::Normal),
+            "low" => Ok(Self::Low),
+            other => Err(format!("Unknown priority weight {other:?}; use high, normal or low")),
+        }
     }
 
-    let total_tokens: usize = ordered_indices.iter().map(|idx| token_counts[*idx]).sum();
-    let mut bins: Vec<Vec<usize>> = vec![Vec::new(); pack_count];
-    let mut cumulative_tokens = 0usize;
-    let mut current_bin = 0usize;
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::Low => "low",
+        }
+    }
+}
 
-    for (position, idx) in ordered_indices.iter().enumerate() {
-        bins[current_bin].push(*idx);
-        cumulative_tokens += token_counts[*idx];
+/// The weight of the first rule matching each file's path; `None` where no rule matches.
+fn file_priorities(files: &[FileContent], rules: &[PriorityRule]) -> Result<Vec<Option<Priority>>, String> {
+    let rules = rules
+        .iter()
+        .map(|rule| {
+            let pattern = glob::Pattern::new(&rule.pattern)
+                .map_err(|e| format!("Invalid priority glob {:?}: {e}", rule.pattern))?;
+            Ok((pattern, Priority::parse(&rule.weight)?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(files
+        .iter()
+        .map(|file| rules.iter().find(|(pattern, _)| pattern.matches(&file.path)).map(|&(_, priority)| priority))
+        .collect())
+}
+
+/// Add `demoted` files, in order, after the last pack's files. With a token budget they only
+/// fill the last pack's free space and then new packs up to `requested_packs`; the files that
+/// don't fit are returned.
+fn demote_to_last_pack(
+    bins: &mut Vec<Vec<usize>>,
+    demoted: &[usize],
+    token_counts: &[usize],
+    budget: Option<usize>,
+    requested_packs: usize,
+) -> Vec<usize> {
+    let mut last = bins.iter().rposition(|bin| !bin.is_empty());
+    let Some(budget) = budget.filter(|&budget| budget > 0) else {
+        match last {
+            Some(last) => bins[last].extend_from_slice(demoted),
+            None if !demoted.is_empty() => bins.push(demoted.to_vec()),
+            None => {}
+        }
+        return Vec::new();
+    };
+    let mut packs = bins.iter().filter(|bin| !bin.is_empty()).count();
+    let mut room = last.map_or(0, |last| budget.saturating_sub(bins[last].iter().map(|&idx| token_counts[idx]).sum()));
+    let mut dropped = Vec::new();
+    for &idx in demoted {
+        let tokens = token_counts[idx];
+        match last {
+            Some(last) if tokens <= room => {
+                bins[last].push(idx);
+                room -= tokens;
+            }
+            _ if packs < requested_packs => {
+                bins.push(vec![idx]);
+                last = Some(bins.len() - 1);
+                room = budget.saturating_sub(tokens);
+                packs += 1;
+            }
+            _ => dropped.push(idx),
+        }
+    }
+    dropped
+}
+
+/// What the ordering steps decided about each file, kept to explain its placement afterwards.
+struct OrderingNotes {
+    dependency_rank: Vec<usize>,
+    doc_bucket: HashMap<usize, u8>,
+    component: HashMap<usize, usize>,
+    pinned: HashSet<usize>,
+    priority: Vec<Option<Priority>>,
+}
+
+impl OrderingNotes {
+    fn new(dependency_order: &[usize]) -> Self {
+        let mut dependency_rank = vec![0; dependency_order.len()];
+        for (rank, &idx) in dependency_order.iter().enumerate() {
+            dependency_rank[idx] = rank;
+        }
+        Self {
+            dependency_rank,
+            doc_bucket: HashMap::new(),
+            component: HashMap::new(),
+            pinned: HashSet::new(),
+            priority: Vec::new(),
+        }
+    }
+
+    fn record_grouping(
+        &mut self,
+        files: &[FileContent],
+        docs: &[usize],
+        lead: &[usize],
+        components: &[Vec<usize>],
+    ) {
+        for &idx in docs {
+            self.doc_bucket.insert(idx, doc_priority(&files[idx].path).0);
+        }
+        for (id, component) in components.iter().enumerate() {
+            self.component.extend(component.iter().map(|&idx| (idx, id)));
+        }
+        self.pinned.extend(lead.iter().copied().filter(|idx| self.component.contains_key(idx)));
+    }
+
+    /// One placement per file in `bins`, in pack order; packs past `requested_packs` only exist
+    /// because the token budget asked for more.
+    fn explain(
+        &self,
+        files: &[FileContent],
+        bins: &[Vec<usize>],
+        requested_packs: usize,
+    ) -> Vec<FilePlacement> {
+        let mut placements = Vec::with_capacity(files.len());
+        let packs = bins.iter().enumerate().filter(|(_, bin)| !bin.is_empty());
+        for (ordinal, (pack_index, bin)) in packs.enumerate() {
+            for (position, &idx) in bin.iter().enumerate() {
+                let mut placement = FilePlacement {
+                    path: files[idx].path.clone(),
+                    pack_index,
+                    position,
+                    dependency_rank: self.dependency_rank[idx],
+                    doc_bucket: self.doc_bucket.get(&idx).copied(),
+                    component: self.component.get(&idx).copied(),
+                    pinned: self.pinned.contains(&idx),
+                    spillover: ordinal >= requested_packs,
+                    priority: self.priority.get(idx).copied().flatten().map(|priority| priority.as_str().to_string()),
+                    reason: String::new(),
+                };
+                placement.reason = placement_reason(&placement, files.len());
+                placements.push(placement);
+            }
+        }
+        placements
+    }
+}
+
+fn placement_reason(placement: &FilePlacement, file_count: usize) -> String {
+    let mut reason = match (placement.doc_bucket, placement.component) {
+        (Some(bucket), _) => format!(
+            "Doc packed ahead of code, {}",
+            match bucket {
+                0 => "READMEs first",
+                1 => "overview and architecture docs second",
+                2 => "`docs/` third",
+                _ => "other docs last",
+            }
+        ),
+        (None, Some(component)) if placement.pinned => format!(
+            "Import group {} opens the code because a doc example imports this file",
+            component + 1
+        ),
+        (None, Some(component)) => format!("Kept with import group {}", component + 1),
+        (None, None) => "Placed in dependency order".to_string(),
+    };
+    reason.push_str(&format!(
+        "; dependency rank {} of {file_count}",
+        placement.dependency_rank + 1
+    ));
+    match placement.priority.as_deref() {
+        Some("high") => reason.push_str("; led its region by a high priority rule"),
+        Some("low") => reason.push_str("; held for the last pack by a low priority rule"),
+        _ => {}
+    }
+    if placement.spillover {
+        reason.push_str("; in an extra pack added to stay under the token budget");
+    }
+    reason.push('.');
+    reason
+}
+
+/// Preserve relative order and split into near-equal token packs. With a token `budget`, the
+/// pack count grows until every pack holding more than one file fits it: near-equal packs
+/// when they fit, otherwise packs filled in order up to the budget.
+fn distribute_files(
+    ordered_indices: &[usize],
+    num_packs: usize,
+    token_counts: &[usize],
+    budget: Option<usize>,
+) -> Vec<Vec<usize>> {
+    let Some(budget) = budget.filter(|&budget| budget > 0) else {
+        return split_evenly(ordered_indices, num_packs, token_counts);
+    };
+    let filled = fill_to_budget(ordered_indices, budget, token_counts);
+    let even = split_evenly(ordered_indices, num_packs.max(filled.len()), token_counts);
+    let fits = even
+        .iter()
+        .all(|bin| bin.len() == 1 || bin.iter().map(|&idx| token_counts[idx]).sum::<usize>() <= budget);
+    if fits { even } else { filled }
+}
+
+/// Fewest packs in order with every multi-file pack at most `budget` tokens; a file larger
+/// than the budget gets a pack of its own.
+fn fill_to_budget(ordered_indices: &[usize], budget: usize, token_counts: &[usize]) -> Vec<Vec<usize>> {
+    let mut bins: Vec<Vec<usize>> = Vec::new();
+    let mut bin_tokens = 0usize;
+    for &idx in ordered_indices {
+        match bins.last_mut() {
+            Some(bin) if bin_tokens + token_counts[idx] <= budget => {
+                bin.push(idx);
+                bin_tokens += token_counts[idx];
+            }
+            _ => {
+                bins.push(vec![idx]);
+                bin_tokens = token_counts[idx];
+            }
+        }
+    }
+    bins
+}
+
+fn split_evenly(ordered_indices: &[usize], num_packs: usize, token_counts: &[usize]) -> Vec<Vec<usize>> {
+    let n = ordered_indices.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let pack_count = num_packs.min(n).max(1);
+    if pack_count == 1 {
+        return vec![ordered_indices.to_vec()];
+    }
+
+    let total_tokens: usize = ordered_indices.iter().map(|idx| token_counts[*idx]).sum();
+    let mut bins: Vec<Vec<usize>> = vec![Vec::new(); pack_count];
+    let mut cumulative_tokens = 0usize;
+    let mut current_bin = 0usize;
+
+    for (position, idx) in ordered_indices.iter().enumerate() {
+        bins[current_bin].push(*idx);
+        cumulative_tokens += token_counts[*idx];
 
         if current_bin >= pack_count - 1 {
             continue;
@@ -476,12 +1558,13 @@ fn distribute_with_doc_strategy(
     code: &[usize],
     num_packs: usize,
     token_counts: &[usize],
+    budget: Option<usize>,
 ) -> Vec<Vec<usize>> {
     if docs.is_empty() || code.is_empty() || num_packs <= 1 {
         let mut merged = Vec::with_capacity(docs.len() + code.len());
         merged.extend_from_slice(docs);
         merged.extend_from_slice(code);
-        return distribute_files(&merged, num_packs, token_counts);
+        return distribute_files(&merged, num_packs, token_counts, budget);
     }
 
     let total_tokens: usize = docs
@@ -495,7 +1578,7 @@ fn distribute_with_doc_strategy(
         let mut merged = Vec::with_capacity(docs.len() + code.len());
         merged.extend_from_slice(docs);
         merged.extend_from_slice(code);
-        return distribute_files(&merged, num_packs, token_counts);
+        return distribute_files(&merged, num_packs, token_counts, budget);
     }
 
     // Allocate at least one docs pack and one code pack; use proportional split for context balance.
@@ -503,471 +1586,4587 @@ fn distribute_with_doc_strategy(
     docs_pack_count = docs_pack_count.clamp(1, num_packs - 1);
 
     let code_pack_count = num_packs - docs_pack_count;
-    let mut bins = distribute_files(docs, docs_pack_count, token_counts);
-    bins.extend(distribute_files(code, code_pack_count, token_counts));
+    let mut bins = distribute_files(docs, docs_pack_count, token_counts, budget);
+    bins.extend(distribute_files(code, code_pack_count, token_counts, budget));
     bins
 }
 
-#[tauri::command]
-pub async fn pack_files(request: PackRequest) -> Result<PackResponse, String> {
-    let files = &request.files;
-    if files.is_empty() {
-        return Ok(PackResponse {
-            packs: Vec::new(),
-            total_tokens: 0,
-        });
+/// What a strategy orders and splits: the files, the dependency-first order every strategy
+/// starts from, how many packs to fill, and the token budget that may add more.
+struct StrategyInput<'a> {
+    files: &'a [FileContent],
+    dependency_order: &'a [usize],
+    token_counts: &'a [usize],
+    num_packs: usize,
+    max_tokens_per_pack: Option<usize>,
+}
+
+/// A way of ordering files and splitting them into packs, chosen by `PackRequest::strategy`.
+/// Strategies are listed in `PACK_STRATEGIES`.
+trait PackStrategy: Sync {
+    fn name(&self) -> &'static str;
+
+    /// Indices into `input.files`, one list per pack. Record grouping decisions in `notes` so
+    /// placements can be explained.
+    fn distribute(&self, input: &StrategyInput, notes: &mut OrderingNotes) -> Vec<Vec<usize>>;
+}
+
+/// Dependency order split into near-equal token packs.
+struct DependencyStrategy;
+
+impl PackStrategy for DependencyStrategy {
+    fn name(&self) -> &'static str {
+        "dependency"
     }
 
-    let num_packs = request.num_packs.max(1);
-    let format = request.output_format.as_str();
+    fn distribute(&self, input: &StrategyInput, _notes: &mut OrderingNotes) -> Vec<Vec<usize>> {
+        distribute_files(input.dependency_order, input.num_packs, input.token_counts, input.max_tokens_per_pack)
+    }
+}
 
-    // Use pre-computed token counts from frontend when available, fall back to estimate.
-    let token_counts: Vec<usize> = files
-        .iter()
-        .map(|f| f.token_count.unwrap_or_else(|| estimate_tokens(&f.content)))
-        .collect();
-    let total_tokens: usize = token_counts.iter().sum();
+/// Docs first, then code kept together by import-connected groups.
+struct GroupedStrategy;
 
-    // 1) Dependency-aware ordering for code comprehension.
-    let dependency_order = compute_dependency_order(files);
+impl PackStrategy for GroupedStrategy {
+    fn name(&self) -> &'static str {
+        "grouped"
+    }
 
-    // 2) Split docs from code and place docs first (README/architecture docs prioritized).
-    let (docs_order, code_order_initial) = split_docs_and_code(&dependency_order, files);
+    fn distribute(&self, input: &StrategyInput, notes: &mut OrderingNotes) -> Vec<Vec<usize>> {
+        let files = input.files;
+        // 1) Split docs from code and place docs first (README/architecture docs prioritized).
+        let (docs_order, code_order_initial) = split_docs_and_code(input.dependency_order, files);
+
+        // 2) Group related code files via import-connected components, preserving dependency order inside groups.
+        //    Groups holding files the docs' examples import come first, right after the docs.
+        let related_graph = build_related_adjacency(files);
+        let lead = doc_referenced_code(&docs_order, files, &related_graph);
+        let components = related_components(&code_order_initial, &lead, &related_graph);
+        notes.record_grouping(files, &docs_order, &lead, &components);
+        let code_order = components.concat();
+
+        // 3) Keep docs and code in separate pack regions when possible to reduce context switching.
+        distribute_with_doc_strategy(
+            &docs_order,
+            &code_order,
+            input.num_packs,
+            input.token_counts,
+            input.max_tokens_per_pack,
+        )
+    }
+}
 
-    // 3) Group related code files via import-connected components, preserving dependency order inside groups.
-    let related_graph = build_related_adjacency(files);
-    let code_order = group_code_by_related_components(&code_order_initial, &related_graph);
+/// Every strategy by name; the first is the default for unknown names.
+const PACK_STRATEGIES: &[&dyn PackStrategy] = &[&GroupedStrategy, &DependencyStrategy];
 
-    // 4) Keep docs and code in separate pack regions when possible to reduce context switching.
-    let bins = distribute_with_doc_strategy(&docs_order, &code_order, num_packs, &token_counts);
+fn pack_strategy(name: &str) -> &'static dyn PackStrategy {
+    PACK_STRATEGIES
+        .iter()
+        .copied()
+        .find(|strategy| strategy.name() == name)
+        .unwrap_or(PACK_STRATEGIES[0])
+}
 
-    let mut packs = Vec::new();
-    for (i, bin) in bins.iter().enumerate() {
-        if bin.is_empty() {
-            continue;
+/// List every import specifier that could not be resolved against the selection,
+/// together with the candidate paths that were tried.
+#[tauri::command]
+pub async fn diagnose_imports(files: Vec<FileContent>) -> Result<Vec<ImportDiagnostic>, String> {
+    let normalized_paths: Vec<String> = files.iter().map(|f| normalize_path(&f.path)).collect();
+    let resolver = ImportResolver::new(&files, &normalized_paths);
+
+    let mut diagnostics = Vec::new();
+    for (idx, file) in files.iter().enumerate() {
+        let current_path = &normalized_paths[idx];
+        let mut specifiers = file_module_specifiers(file);
+        specifiers.sort();
+        for specifier in specifiers {
+            if !resolver.resolve(&specifier, current_path).is_empty() {
+                continue;
+            }
+            let mut candidates = module_specifier_candidates(&specifier, current_path);
+            candidates.extend(resolver.go_package_dir(&specifier));
+            diagnostics.push(ImportDiagnostic {
+                file_path: file.path.clone(),
+                specifier,
+                candidates,
+            });
         }
+    }
 
-        let mut pack_content_parts = Vec::new();
-        let mut pack_tokens = 0;
-        let mut file_paths = Vec::new();
+    Ok(diagnostics)
+}
 
-        for &file_idx in bin {
-            let file = &files[file_idx];
-            let formatted = format_file_header(&file.path, &file.content, format);
-            pack_tokens += token_counts[file_idx];
-            file_paths.push(file.path.clone());
-            pack_content_parts.push(formatted);
+/// Collect every file that transitively imports `target_idx` by walking the
+/// dependency -> dependents edges. Results are sorted by normalized path.
+fn collect_transitive_dependents(
+    target_idx: usize,
+    normalized_paths: &[String],
+    edges: &[HashSet<usize>],
+) -> Vec<usize> {
+    let mut visited: HashSet<usize> = HashSet::new();
+    visited.insert(target_idx);
+    let mut stack = vec![target_idx];
+    let mut dependents = Vec::new();
+
+    while let Some(node) = stack.pop() {
+        for &dependent in &edges[node] {
+            if visited.insert(dependent) {
+                dependents.push(dependent);
+                stack.push(dependent);
+            }
         }
+    }
 
-        let separator = "\n\n";
-        let inner = pack_content_parts.join(separator);
-        let content = wrap_pack(&inner);
+    dependents.sort_by(|a, b| normalized_paths[*a].cmp(&normalized_paths[*b]));
+    dependents
+}
 
-        packs.push(PackItem {
-            index: i,
-            content,
-            estimated_tokens: pack_tokens,
-            file_count: bin.len(),
-            file_paths,
-        });
+/// The paths of `seeds` and every file they transitively import, sorted by normalized path.
+pub(crate) fn with_transitive_dependencies(files: &[FileContent], seeds: &[usize]) -> Vec<String> {
+    let (normalized_paths, edges, _) = build_dependency_graph(files);
+    let mut dependencies: Vec<Vec<usize>> = vec![Vec::new(); files.len()];
+    for (dependency, dependents) in edges.iter().enumerate() {
+        for dependent in dependents {
+            dependencies[*dependent].push(dependency);
+        }
+    }
+
+    let mut visited: HashSet<usize> = HashSet::new();
+    let mut stack = seeds.to_vec();
+    while let Some(node) = stack.pop() {
+        if visited.insert(node) {
+            stack.extend(dependencies[node].iter().copied());
+        }
     }
 
-    Ok(PackResponse { packs, total_tokens })
+    let mut selected: Vec<usize> = visited.into_iter().collect();
+    selected.sort_by(|a, b| normalized_paths[*a].cmp(&normalized_paths[*b]));
+    selected.into_iter().map(|idx| files[idx].path.clone()).collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::FileContent;
+/// Paths of the `files` that transitively depend on `path`, sorted by normalized path.
+fn transitive_dependents(path: &str, files: &[FileContent]) -> Result<Vec<String>, String> {
+    let (normalized_paths, edges, _) = build_dependency_graph(files);
+    let target = normalize_path(path);
+    let Some(target_idx) = normalized_paths.iter().position(|p| p == &target) else {
+        return Err(format!("File is not part of the provided selection: {path}"));
+    };
 
-    // ── estimate_tokens ──
+    Ok(collect_transitive_dependents(target_idx, &normalized_paths, &edges)
+        .into_iter()
+        .map(|idx| files[idx].path.clone())
+        .collect())
+}
 
-    #[test]
-    fn estimate_tokens_basic() {
-        assert_eq!(estimate_tokens("abcd"), 1);
-        assert_eq!(estimate_tokens("abcdefgh"), 2);
-        assert_eq!(estimate_tokens(""), 1); // max(0,1) = 1
-    }
+/// Return every file under `root` that transitively depends on `path` ("what breaks if I
+/// change this"), as absolute paths ready to select and pack. Files over
+/// `MAX_DIRECTORY_FILE_BYTES` are not read, so their imports are not followed.
+#[tauri::command]
+pub async fn who_depends_on(root: String, path: String) -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let nodes = walk_allowed_files(&root)?;
+        let relative = |path: &str| {
+            let path = Path::new(path);
+            normalize_path(&path.strip_prefix(&root).unwrap_or(path).to_string_lossy())
+        };
+        let files: Vec<FileContent> = nodes
+            .iter()
+            .map(|node| {
+                let content = (node.size <= MAX_DIRECTORY_FILE_BYTES)
+                    .then(|| read_text_file(Path::new(&node.path)).ok())
+                    .flatten()
+                    .unwrap_or_default();
+                FileContent { path: relative(&node.path), content, token_count: None }
+            })
+            .collect();
+        let dependents: HashSet<String> = transitive_dependents(&relative(&path), &files)?.into_iter().collect();
+        Ok(nodes
+            .into_iter()
+            .zip(&files)
+            .filter(|(_, file)| dependents.contains(&file.path))
+            .map(|(node, _)| node.path)
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    // ── normalize_path ──
+/// `name.part-2-of-3.ext` -> (`name.ext`, 2, 3): the path a split part was cut from, its
+/// 1-based part number, and the part count.
+fn split_part_origin(path: &str) -> Option<(String, usize, usize)> {
+    let name_start = path.rfind(['/', '\\']).map_or(0, |idx| idx + 1);
+    let (stem, rest) = path[name_start..].split_once(".part-")?;
+    let (counts, ext) = match rest.split_once('.') {
+        Some((counts, ext)) => (counts, Some(ext)),
+        None => (rest, None),
+    };
+    let (part, total) = counts.split_once("-of-")?;
+    let (part, total) = (part.parse().ok()?, total.parse().ok()?);
+    let origin = match ext {
+        Some(ext) => format!("{}{stem}.{ext}", &path[..name_start]),
+        None => format!("{}{stem}", &path[..name_start]),
+    };
+    Some((origin, part, total))
+}
 
-    #[test]
-    fn normalize_removes_dot_segments() {
-        assert_eq!(normalize_path("a/./b"), "a/b");
-        assert_eq!(normalize_path("./a/b"), "a/b");
-    }
+/// Oversized files are split into `name.part-1-of-3.ext` chunks, which are not expected to
+/// parse on their own.
+fn is_split_part(path: &str) -> bool {
+    split_part_origin(path).is_some()
+}
 
-    #[test]
-    fn normalize_resolves_parent_segments() {
-        assert_eq!(normalize_path("a/b/../c"), "a/c");
-        assert_eq!(normalize_path("a/b/../../c"), "c");
+/// `name.ext` -> `name.part-{part}-of-{total}.ext`, keeping the extension last so the part is
+/// still recognized by language.
+fn split_part_path(path: &str, part: usize, total: usize) -> String {
+    let name_start = path.rfind(['/', '\\']).map_or(0, |idx| idx + 1);
+    let suffix = format!(".part-{part}-of-{total}");
+    match path[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => {
+            let (stem, ext) = path.split_at(name_start + dot);
+            format!("{stem}{suffix}{ext}")
+        }
+        _ => format!("{path}{suffix}"),
     }
+}
 
-    #[test]
-    fn normalize_handles_backslashes() {
-        assert_eq!(normalize_path("a\\b\\c"), "a/b/c");
+/// How far into a chunk a paragraph or line break must be to end it there instead of
+/// cutting mid-line.
+const MIN_BREAK_RATIO: f64 = 0.35;
+
+/// Cut `content` into chunks of at most `max_bytes`, each ending on its last paragraph
+/// break, else its last line break, when one lies past `MIN_BREAK_RATIO` of the chunk.
+fn split_at_breaks(content: &str, max_bytes: usize) -> Vec<&str> {
+    let max_bytes = max_bytes.max(1);
+    let min_break = (max_bytes as f64 * MIN_BREAK_RATIO) as usize;
+    let mut chunks = Vec::new();
+    let mut rest = content;
+    while rest.len() > max_bytes {
+        let window = &rest[..floor_char_boundary(rest, max_bytes)];
+        let cut = match (window.rfind("\n\n"), window.rfind('\n')) {
+            (Some(at), _) if at >= min_break => at + 2,
+            (_, Some(at)) if at >= min_break => at + 1,
+            // A single character wider than the chunk still has to go somewhere.
+            _ if window.is_empty() => rest.chars().next().map_or(rest.len(), char::len_utf8),
+            _ => window.len(),
+        };
+        let (chunk, tail) = rest.split_at(cut);
+        chunks.push(chunk);
+        rest = tail;
     }
-
-    #[test]
-    fn normalize_collapses_empty_segments() {
-        assert_eq!(normalize_path("a//b///c"), "a/b/c");
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest);
     }
+    chunks
+}
 
-    // ── parent_dir ──
+/// First line of every focus file's packed content.
+const FOCUS_BANNER: &str = "=== PRIMARY FILE UNDER DISCUSSION ===";
 
-    #[test]
-    fn parent_dir_returns_directory() {
-        assert_eq!(parent_dir("src/lib/foo.ts"), "src/lib");
+/// Share of a truncated file's kept bytes taken from its start; the rest come from its end.
+const TRUNCATION_HEAD_SHARE: f64 = 0.6;
+
+/// `content` cut to about `max_bytes` (marker excluded): its head and tail, each ending or
+/// starting on a paragraph break, else a line break, when one lies within `MIN_BREAK_RATIO`
+/// of the cut, around a line naming how many lines were left out. `None` when it already fits.
+fn truncate_middle(content: &str, max_bytes: usize, budget: usize) -> Option<(String, usize)> {
+    if content.len() <= max_bytes {
+        return None;
+    }
+    let head_bytes = (max_bytes as f64 * TRUNCATION_HEAD_SHARE) as usize;
+    let head = split_at_breaks(content, head_bytes.max(1))[0];
+    let tail_bytes = max_bytes.saturating_sub(head.len());
+    let mut tail_start = content.len() - tail_bytes.min(content.len() - head.len());
+    while !content.is_char_boundary(tail_start) {
+        tail_start += 1;
     }
+    let window = &content[tail_start..];
+    let slack = (window.len() as f64 * (1.0 - MIN_BREAK_RATIO)) as usize;
+    tail_start += match (window.find("\n\n"), window.find('\n')) {
+        (Some(at), _) if at <= slack => at + 2,
+        (_, Some(at)) if at <= slack => at + 1,
+        _ => 0,
+    };
+    let omitted = &content[head.len()..tail_start];
+    let omitted_lines = omitted.lines().count().max(1);
+    let mut truncated = String::with_capacity(head.len() + content.len() - tail_start + 96);
+    truncated.push_str(head);
+    if !head.is_empty() && !head.ends_with('\n') {
+        truncated.push('\n');
+    }
+    truncated.push_str(&format!(
+        "… [truncated: {omitted_lines} line(s) omitted to fit the {budget}-token pack budget] …\n"
+    ));
+    truncated.push_str(&content[tail_start..]);
+    Some((truncated, omitted_lines))
+}
 
-    #[test]
-    fn parent_dir_returns_empty_for_top_level() {
-        assert_eq!(parent_dir("foo.ts"), "");
+/// Replace every file over `max_tokens` with `name.part-i-of-n.ext` parts that each fit it,
+/// in place, and describe the parts made. Files in `keep_whole` are never split.
+fn split_oversized_files(
+    files: &mut Vec<FileContent>,
+    max_tokens: usize,
+    counter: &TokenCounter,
+    keep_whole: &HashSet<String>,
+) -> Vec<FileFragment> {
+    let mut fragments = Vec::new();
+    let mut split = Vec::with_capacity(files.len());
+    for file in files.drain(..) {
+        let tokens = file.token_count.unwrap_or_else(|| counter.count(&file.path, &file.content));
+        if tokens <= max_tokens || keep_whole.contains(&normalize_path(&file.path)) {
+            split.push(file);
+            continue;
+        }
+        let chunks = split_at_breaks(&file.content, file.content.len() * max_tokens / tokens);
+        let total = chunks.len();
+        if total == 1 {
+            split.push(file);
+            continue;
+        }
+        for (part, chunk) in (1..).zip(chunks) {
+            let path = split_part_path(&file.path, part, total);
+            fragments.push(FileFragment {
+                path: path.clone(),
+                original_path: file.path.clone(),
+                part,
+                total,
+            });
+            split.push(FileContent {
+                path,
+                content: chunk.to_string(),
+                // Exact counters recount each part; estimates are shared out by length.
+                token_count: file
+                    .token_count
+                    .filter(|_| !counter.is_exact())
+                    .map(|count| count * chunk.len() / file.content.len()),
+            });
+        }
     }
+    *files = split;
+    fragments
+}
 
-    // ── has_extension / path_extension / file_basename ──
+/// One warning per packed source file that tree-sitter could not parse cleanly.
+fn collect_parse_warnings(files: &[FileContent]) -> Vec<String> {
+    files
+        .iter()
+        .filter(|file| !is_split_part(&file.path))
+        .filter_map(|file| {
+            let health = check_parse_health(&file.path, &file.content)?;
+            Some(match health.first_error_line {
+                Some(line) => format!(
+                    "{}: {} syntax error(s), first at line {}; symbol analysis may be incomplete.",
+                    file.path, health.error_count, line
+                ),
+                None => format!("{}: could not be parsed; symbol analysis skipped.", file.path),
+            })
+        })
+        .collect()
+}
 
-    #[test]
-    fn has_extension_detects_ext() {
-        assert!(has_extension("file.ts"));
-        assert!(!has_extension("Makefile"));
+/// Drop BOMs, turn CRLF (and lone CR) into LF, and trim trailing spaces and tabs, so the
+/// same logical file packs byte-for-byte identically on every platform.
+fn normalize_content(content: &str) -> Cow<'_, str> {
+    let needs_work = content.contains(['\r', '\u{feff}'])
+        || content.split('\n').any(|line| line.ends_with([' ', '\t']));
+    if !needs_work {
+        return Cow::Borrowed(content);
     }
 
-    #[test]
-    fn path_extension_extracts_lowercase() {
-        assert_eq!(path_extension("file.TS"), "ts");
-        assert_eq!(path_extension("file.Rs"), "rs");
-        assert_eq!(path_extension("noext"), "");
+    let unified = content.replace('\u{feff}', "").replace("\r\n", "\n").replace('\r', "\n");
+    let mut out = String::with_capacity(unified.len());
+    for (i, line) in unified.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(line.trim_end_matches([' ', '\t']));
     }
+    Cow::Owned(out)
+}
 
-    #[test]
-    fn file_basename_extracts_name() {
-        assert_eq!(file_basename("src/lib/foo.ts"), "foo.ts");
-        assert_eq!(file_basename("README.md"), "readme.md");
+/// Extensions whose leading indentation carries no meaning; whitespace compression only
+/// strips indentation from these.
+const INDENTATION_FREE_EXTENSIONS: &[&str] = &[
+    "c", "h", "cc", "cpp", "hpp", "cs", "java", "kt", "kts", "scala", "swift", "go", "rs", "dart", "php", "js", "jsx",
+    "mjs", "cjs", "ts", "tsx", "mts", "cts", "css", "scss", "less", "json", "jsonc", "html", "xml", "svg", "vue",
+    "svelte", "sql", "graphql", "gql", "proto", "lua", "rb", "sh", "bash", "toml", "ini",
+];
+
+/// Collapse runs of blank lines to one, trim trailing whitespace, and with `strip_indentation`
+/// drop leading whitespace in languages that ignore it (multi-line string literals lose theirs too).
+fn compress_whitespace<'a>(path: &str, content: &'a str, strip_indentation: bool) -> Cow<'a, str> {
+    let strip_indentation = strip_indentation && INDENTATION_FREE_EXTENSIONS.contains(&path_extension(path).as_str());
+    let mut out = String::with_capacity(content.len());
+    let mut previous_blank = false;
+    for (i, line) in content.split('\n').enumerate() {
+        let mut line = line.trim_end();
+        if strip_indentation {
+            line = line.trim_start();
+        }
+        let blank = line.is_empty();
+        if blank && previous_blank {
+            continue;
+        }
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(line);
+        previous_blank = blank;
     }
+    if out.len() == content.len() {
+        return Cow::Borrowed(content);
+    }
+    Cow::Owned(out)
+}
 
-    // ── is_doc_file ──
+/// Prefix every line with its number, right-aligned, counting from `first_line`:
+/// `  9 | fn main() {`.
+fn number_lines(content: &str, first_line: usize) -> String {
+    let numbers: Vec<usize> = (first_line..).take(content.split('\n').count()).collect();
+    render_numbered_lines(content, &numbers)
+}
 
-    #[test]
-    fn is_doc_file_recognizes_doc_extensions() {
-        assert!(is_doc_file("README.md"));
-        assert!(is_doc_file("guide.mdx"));
-        assert!(is_doc_file("notes.txt"));
-        assert!(is_doc_file("spec.rst"));
-        assert!(is_doc_file("help.adoc"));
+/// How many replaced lines ahead `number_lines_from_original` looks for a rewritten line's source.
+const RENUMBER_WINDOW: usize = 64;
+
+/// Number each line of `packed` with the line of `original` it came from, so the numbers
+/// still point into the file on disk after transforms dropped or rewrote lines.
+fn number_lines_from_original(original: &str, packed: &str, first_line: usize) -> String {
+    if original == packed {
+        return number_lines(packed, first_line);
+    }
+    let diff = similar::TextDiff::from_lines(original, packed);
+    let (old_lines, new_lines) = (diff.old_slices(), diff.new_slices());
+    let mut numbers: Vec<usize> = Vec::new();
+    for op in diff.ops() {
+        let old = op.old_range();
+        let mut candidates = old.clone();
+        for new_idx in op.new_range() {
+            // A rewritten line takes the number of the most alike line it replaced, in order;
+            // one with none left repeats the previous number.
+            let similarity = |idx: usize| similar::TextDiff::from_chars(old_lines[idx], new_lines[new_idx]).ratio();
+            let window = candidates.start..candidates.end.min(candidates.start + RENUMBER_WINDOW);
+            let best = window.max_by(|&a, &b| similarity(a).total_cmp(&similarity(b)).then(b.cmp(&a)));
+            numbers.push(match best {
+                Some(idx) => {
+                    candidates.start = idx + 1;
+                    first_line + idx
+                }
+                None => numbers.last().copied().unwrap_or(first_line + old.start),
+            });
+        }
+    }
+    // A trailing newline leaves one more, empty, segment after the diffed lines.
+    while numbers.len() < packed.split('\n').count() {
+        numbers.push(numbers.last().map_or(first_line, |&last| last + 1));
     }
+    render_numbered_lines(packed, &numbers)
+}
 
-    #[test]
-    fn is_doc_file_rejects_code_files() {
-        assert!(!is_doc_file("main.ts"));
-        assert!(!is_doc_file("lib.rs"));
-        assert!(!is_doc_file("config.json"));
+fn render_numbered_lines(content: &str, numbers: &[usize]) -> String {
+    let width = numbers.iter().max().copied().unwrap_or(1).to_string().len();
+    let mut out = String::with_capacity(content.len() + numbers.len() * (width + 3));
+    for (idx, (n, line)) in numbers.iter().zip(content.split('\n')).enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!("{n:>width$} |"));
+        if !line.is_empty() {
+            out.push(' ');
+            out.push_str(line);
+        }
     }
+    out
+}
 
-    // ── doc_priority ──
+/// Apply `transform` to `text`, keeping the original borrow when nothing changed.
+fn chain_transform<'a>(text: Cow<'a, str>, transform: impl FnOnce(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    let changed = match transform(&text) {
+        Cow::Owned(changed) => Some(changed),
+        Cow::Borrowed(_) => None,
+    };
+    changed.map_or(text, Cow::Owned)
+}
 
-    #[test]
-    fn doc_priority_readme_first() {
-        let (bucket, _) = doc_priority("README.md");
-        assert_eq!(bucket, 0);
-    }
+/// Where a top-level import statement ends, by language.
+#[derive(Clone, Copy)]
+enum ImportEnd {
+    /// JS/TS: the line carrying the quoted module specifier.
+    Specifier,
+    /// Rust `use` and `extern crate`.
+    Semicolon,
+    /// Python `from x import (...)` and Go `import (...)`.
+    CloseParen,
+    /// Python backslash continuation.
+    Backslash,
+}
 
-    #[test]
-    fn doc_priority_architecture_docs_second() {
-        for name in &["OVERVIEW.md", "architecture.md", "design.md", "spec.md", "CONTRIBUTING.md"] {
-            let (bucket, _) = doc_priority(name);
-            assert_eq!(bucket, 1, "expected bucket 1 for {}", name);
+/// Classify a top-level line that opens an import statement and say where it ends.
+fn import_statement_start(ext: &str, line: &str) -> Option<ImportEnd> {
+    let is_js_import = (line.starts_with("import ") || line.starts_with("import{"))
+        && !line.starts_with("import(");
+    match ext {
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "mts" | "cts" if is_js_import => {
+            Some(ImportEnd::Specifier)
+        }
+        "rs" if line.starts_with("use ") || line.starts_with("extern crate ") => {
+            Some(ImportEnd::Semicolon)
+        }
+        "py" if line.starts_with("import ") || (line.starts_with("from ") && line.contains(" import ")) => {
+            if line.contains('(') {
+                Some(ImportEnd::CloseParen)
+            } else {
+                Some(ImportEnd::Backslash)
+            }
         }
+        "go" if line.starts_with("import (") => Some(ImportEnd::CloseParen),
+        "go" if line.starts_with("import ") => Some(ImportEnd::Specifier),
+        _ => None,
     }
+}
 
-    #[test]
-    fn doc_priority_docs_folder_third() {
-        let (bucket, _) = doc_priority("docs/guide.md");
-        assert_eq!(bucket, 2);
+fn import_statement_ends_on(end: ImportEnd, line: &str) -> bool {
+    let code = line.trim_end();
+    match end {
+        ImportEnd::Specifier => extract_quoted_segments(code).iter().any(|q| !q.is_empty()),
+        ImportEnd::Semicolon => code.ends_with(';'),
+        ImportEnd::CloseParen => code.contains(')'),
+        ImportEnd::Backslash => !code.ends_with('\\'),
     }
+}
 
-    #[test]
+/// Drop top-level import/use statements from a JS/TS, Rust, Python, or Go source, along
+/// with the blank lines that trailed them. Re-exports (`export ... from`, `pub use`) are
+/// part of a module's surface and are kept; nested imports are left alone.
+fn strip_import_statements<'a>(path: &str, content: &'a str) -> Cow<'a, str> {
+    let ext = path_extension(path);
+    let mut out = String::with_capacity(content.len());
+    let mut open_statement: Option<ImportEnd> = None;
+    let mut after_import = false;
+    let mut stripped = false;
+
+    for line in content.split_inclusive('\n') {
+        if let Some(end) = open_statement {
+            if import_statement_ends_on(end, line) {
+                open_statement = None;
+            }
+            continue;
+        }
+        if let Some(end) = import_statement_start(&ext, line) {
+            stripped = true;
+            after_import = true;
+            if !import_statement_ends_on(end, line) {
+                open_statement = Some(end);
+            }
+            continue;
+        }
+        if after_import && line.trim().is_empty() {
+            continue;
+        }
+        after_import = false;
+        out.push_str(line);
+    }
+
+    if stripped {
+        Cow::Owned(out)
+    } else {
+        Cow::Borrowed(content)
+    }
+}
+
+/// A packed string kept for `preview_pack`, with the byte range of every file section.
+struct StoredPack {
+    content: String,
+    sections: Vec<Range<usize>>,
+}
+
+/// Upper bound on stored packs; the store is cleared rather than grown past it.
+const PACK_STORE_LIMIT: usize = 64;
+
+static PACK_STORE: LazyLock<Mutex<HashMap<String, Arc<StoredPack>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// First 16 hex chars of a blake3 hash; used for pack ids and selection fingerprints.
+fn short_hex(hash: &blake3::Hash) -> String {
+    hash.as_bytes()[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn store_pack(id: &str, pack: StoredPack) {
+    if let Ok(mut store) = PACK_STORE.lock() {
+        if store.len() >= PACK_STORE_LIMIT {
+            store.clear();
+        }
+        store.insert(id.to_string(), Arc::new(pack));
+    }
+}
+
+/// Hash of a pack's sections as an unordered set: each section is hashed, and the sorted
+/// hashes are hashed again. The header and footer are left out since they carry the
+/// generation time.
+fn sections_content_hash(content: &str, sections: &[Range<usize>]) -> String {
+    let mut hashes: Vec<[u8; 32]> = sections
+        .iter()
+        .map(|range| *blake3::hash(content[range.clone()].as_bytes()).as_bytes())
+        .collect();
+    hashes.sort_unstable();
+    let mut hasher = blake3::Hasher::new();
+    for hash in &hashes {
+        hasher.update(hash);
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Hash of a response's packs as an unordered set of `PackItem::content_hash`es.
+fn combined_content_hash(packs: &[PackItem]) -> String {
+    let mut hashes: Vec<&str> = packs.iter().map(|pack| pack.content_hash.as_str()).collect();
+    hashes.sort_unstable();
+    let mut hasher = blake3::Hasher::new();
+    for hash in hashes {
+        hasher.update(hash.as_bytes());
+        hasher.update(&[0]);
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Content hash of every packed file, keyed by path.
+type PackManifest = HashMap<String, blake3::Hash>;
+
+static PACK_MANIFESTS: LazyLock<Mutex<HashMap<String, Arc<PackManifest>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn build_manifest(files: &[FileContent]) -> PackManifest {
+    files
+        .iter()
+        .map(|file| (file.path.clone(), blake3::hash(file.content.as_bytes())))
+        .collect()
+}
+
+/// Fingerprint of a manifest, which depends only on paths and contents.
+fn manifest_fingerprint(manifest: &PackManifest) -> String {
+    let mut entries: Vec<_> = manifest.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    let mut hasher = blake3::Hasher::new();
+    for (path, hash) in entries {
+        hasher.update(path.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(hash.as_bytes());
+    }
+    short_hex(&hasher.finalize())
+}
+
+/// Parse path -> hex blake3 pairs, as `read_file_content_hashed` and `PackResponse::manifest`
+/// give them.
+fn parse_manifest(hashes: HashMap<String, String>) -> Result<PackManifest, String> {
+    hashes
+        .into_iter()
+        .map(|(path, hex)| {
+            let hash = blake3::Hash::from_hex(&hex).map_err(|e| format!("Invalid hash for {path}: {e}"))?;
+            Ok((path, hash))
+        })
+        .collect()
+}
+
+/// Remember `manifest` and return its fingerprint.
+fn store_manifest(manifest: PackManifest) -> String {
+    let fingerprint = manifest_fingerprint(&manifest);
+
+    if let Ok(mut manifests) = PACK_MANIFESTS.lock() {
+        if manifests.len() >= PACK_STORE_LIMIT {
+            manifests.clear();
+        }
+        manifests.insert(fingerprint.clone(), Arc::new(manifest));
+    }
+    fingerprint
+}
+
+/// Upper bound on files whose packed text is kept for incremental runs; the cache is
+/// cleared rather than grown past it.
+const PACKED_FILE_CACHE_LIMIT: usize = 50_000;
+
+/// One file's packed text from an earlier run. Reusable while the file's content, the
+/// packing settings, and the line its numbering starts at are unchanged.
+#[derive(Clone)]
+struct PackedFile {
+    hash: blake3::Hash,
+    settings: blake3::Hash,
+    first_line: usize,
+    content: String,
+    tokens: usize,
+    saved_tokens: usize,
+}
+
+static PACKED_FILES: LazyLock<Mutex<HashMap<String, PackedFile>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// `pack_key`s of the packs each run produced, by the run's manifest fingerprint.
+static PACK_RUNS: LazyLock<Mutex<HashMap<String, Arc<HashSet<blake3::Hash>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Digest of every request setting that shapes the packed text, i.e. all but the files.
+fn packing_settings(request: &mut PackRequest) -> blake3::Hash {
+    let files = std::mem::take(&mut request.files);
+    let settings = blake3::hash(&serde_json::to_vec(&*request).unwrap_or_default());
+    request.files = files;
+    settings
+}
+
+fn cached_packed_file(path: &str, hash: blake3::Hash, settings: blake3::Hash, first_line: usize) -> Option<PackedFile> {
+    let cache = PACKED_FILES.lock().ok()?;
+    cache
+        .get(path)
+        .filter(|file| file.hash == hash && file.settings == settings && file.first_line == first_line)
+        .cloned()
+}
+
+fn remember_packed_file(path: &str, file: PackedFile) {
+    if let Ok(mut cache) = PACKED_FILES.lock() {
+        if cache.len() >= PACKED_FILE_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(path.to_string(), file);
+    }
+}
+
+/// Identifies a pack across runs: same settings, same place, same sections.
+fn pack_key(settings: &blake3::Hash, index: usize, total: usize, content_hash: &str) -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(settings.as_bytes());
+    hasher.update(&(index as u64).to_le_bytes());
+    hasher.update(&(total as u64).to_le_bytes());
+    hasher.update(content_hash.as_bytes());
+    hasher.finalize()
+}
+
+fn previous_pack_keys(manifest: &PackManifest) -> Option<Arc<HashSet<blake3::Hash>>> {
+    PACK_RUNS.lock().ok()?.get(&manifest_fingerprint(manifest)).cloned()
+}
+
+fn remember_pack_keys(fingerprint: &str, keys: HashSet<blake3::Hash>) {
+    if let Ok(mut runs) = PACK_RUNS.lock() {
+        if runs.len() >= PACK_STORE_LIMIT {
+            runs.clear();
+        }
+        runs.insert(fingerprint.to_string(), Arc::new(keys));
+    }
+}
+
+/// Largest char boundary at or below `index`.
+fn floor_char_boundary(content: &str, mut index: usize) -> usize {
+    index = index.min(content.len());
+    while !content.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Head and tail of a pack within `max_chars` bytes. Whole file sections are kept from each end;
+/// a section is only cut (on a line boundary) when not even the first or last one fits.
+fn build_pack_preview(pack: &StoredPack, max_chars: usize) -> PackPreview {
+    let content = pack.content.as_str();
+    let total_chars = content.len();
+    if total_chars <= max_chars {
+        return PackPreview {
+            content: content.to_string(),
+            truncated: false,
+            total_chars,
+            omitted_files: 0,
+        };
+    }
+
+    let head_budget = max_chars / 2;
+    let tail_budget = max_chars - head_budget;
+
+    let head_sections = pack.sections.iter().take_while(|section| section.end <= head_budget).count();
+    let head_end = match head_sections {
+        0 => {
+            let limit = floor_char_boundary(content, head_budget);
+            content[..limit].rfind('\n').map_or(limit, |idx| idx + 1)
+        }
+        n => pack.sections[n - 1].end,
+    };
+
+    let tail_sections = pack.sections[head_sections..]
+        .iter()
+        .rev()
+        .take_while(|section| total_chars - section.start <= tail_budget)
+        .count();
+    let tail_start = match tail_sections {
+        0 => {
+            let limit = floor_char_boundary(content, total_chars - tail_budget).max(head_end);
+            content[limit..].find('\n').map_or(total_chars, |idx| limit + idx + 1)
+        }
+        n => pack.sections[pack.sections.len() - n].start,
+    };
+
+    let omitted_chars = tail_start - head_end;
+    let mut preview = String::with_capacity(max_chars + 64);
+    preview.push_str(&content[..head_end]);
+    preview.push_str(&format!("\n\n… {omitted_chars} characters omitted …\n\n"));
+    preview.push_str(&content[tail_start..]);
+
+    PackPreview {
+        content: preview,
+        truncated: true,
+        total_chars,
+        omitted_files: pack.sections.len() - head_sections - tail_sections,
+    }
+}
+
+/// Truncated head+tail view of a pack from the latest `pack_files` runs, so the preview
+/// pane never has to render a multi-megabyte string.
+#[tauri::command]
+pub async fn preview_pack(pack_id: String, max_chars: usize) -> Result<PackPreview, String> {
+    let pack = PACK_STORE
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&pack_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown pack id: {}", pack_id))?;
+    Ok(build_pack_preview(&pack, max_chars))
+}
+
+/// Chunk size for clipboard copies when the caller gives none; below what common Linux
+/// clipboard managers truncate at.
+const DEFAULT_CLIPBOARD_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Smallest chunk size accepted, so a typo cannot produce thousands of chunks.
+const MIN_CLIPBOARD_CHUNK_BYTES: usize = 1024;
+
+/// In-progress chunked copies, keyed by pack id: the chunk ranges and the next to hand out.
+struct ClipboardCopy {
+    pack: Arc<StoredPack>,
+    chunks: Vec<Range<usize>>,
+    next: usize,
+}
+
+static CLIPBOARD_COPIES: LazyLock<Mutex<HashMap<String, ClipboardCopy>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Split `content` into ranges of at most `max_bytes` (at least one char each), cutting after
+/// the last blank line in the back two thirds of each window, else the last newline, else at
+/// a char boundary.
+fn clipboard_chunk_ranges(content: &str, max_bytes: usize) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < content.len() {
+        let limit = floor_char_boundary(content, start + max_bytes);
+        let end = if limit == content.len() {
+            limit
+        } else if limit == start {
+            start + content[start..].chars().next().map_or(1, char::len_utf8)
+        } else {
+            let window = &content[start..limit];
+            let cut = window
+                .rfind("\n\n")
+                .filter(|&at| at >= window.len() / 3)
+                .map(|at| at + 2)
+                .or_else(|| window.rfind('\n').map(|at| at + 1))
+                .unwrap_or(window.len());
+            start + cut
+        };
+        chunks.push(start..end);
+        start = end;
+    }
+    chunks
+}
+
+/// Chunk `position` of `copy`. Multi-part copies open with a `=== PART i/n ===` line so the
+/// model knows to wait for the rest.
+fn clipboard_chunk(copy: &ClipboardCopy, position: usize) -> ClipboardChunk {
+    let total = copy.chunks.len();
+    let text = &copy.pack.content[copy.chunks[position].clone()];
+    let content = if total > 1 {
+        format!("=== PART {}/{total} ===\n{text}", position + 1)
+    } else {
+        text.to_string()
+    };
+    ClipboardChunk { content, index: position + 1, total, done: position + 1 == total }
+}
+
+/// Begin copying a pack to the clipboard in chunks of at most `max_bytes`, returning the
+/// first. Restarting an in-progress copy starts over.
+#[tauri::command]
+pub async fn start_chunked_copy(pack_id: String, max_bytes: Option<usize>) -> Result<ClipboardChunk, String> {
+    let pack = PACK_STORE
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&pack_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown pack id: {}", pack_id))?;
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_CLIPBOARD_CHUNK_BYTES).max(MIN_CLIPBOARD_CHUNK_BYTES);
+    let mut chunks = clipboard_chunk_ranges(&pack.content, max_bytes);
+    if chunks.is_empty() {
+        chunks.push(0..0);
+    }
+
+    let copy = ClipboardCopy { pack, chunks, next: 1 };
+    let first = clipboard_chunk(&copy, 0);
+    let mut copies = CLIPBOARD_COPIES.lock().map_err(|e| e.to_string())?;
+    if copies.len() >= PACK_STORE_LIMIT {
+        copies.clear();
+    }
+    if first.done {
+        copies.remove(&pack_id);
+    } else {
+        copies.insert(pack_id, copy);
+    }
+    Ok(first)
+}
+
+/// The next chunk of a copy begun with `start_chunked_copy`; the copy is forgotten once its
+/// last chunk has been handed out.
+#[tauri::command]
+pub async fn copy_next_chunk(pack_id: String) -> Result<ClipboardChunk, String> {
+    let mut copies = CLIPBOARD_COPIES.lock().map_err(|e| e.to_string())?;
+    let copy = copies
+        .get_mut(&pack_id)
+        .ok_or_else(|| format!("No chunked copy in progress for pack: {}", pack_id))?;
+    let chunk = clipboard_chunk(copy, copy.next);
+    copy.next += 1;
+    if chunk.done {
+        copies.remove(&pack_id);
+    }
+    Ok(chunk)
+}
+
+/// All packs in one string, each introduced by an `=== PACK i/n ===` line so tools can
+/// split them again.
+fn combine_packs(contents: &[&str]) -> String {
+    let total = contents.len();
+    let capacity = contents.iter().map(|content| content.len() + 32).sum();
+    let mut out = String::with_capacity(capacity);
+    for (i, content) in contents.iter().enumerate() {
+        if i > 0 {
+            out.push_str("\n\n");
+        }
+        out.push_str(&format!("=== PACK {}/{} ===\n", i + 1, total));
+        out.push_str(content);
+    }
+    out
+}
+
+/// The stored packs for `pack_ids` (from `PackItem`), combined in order.
+fn combine_stored_packs(pack_ids: &[String]) -> Result<String, String> {
+    let store = PACK_STORE.lock().map_err(|e| e.to_string())?;
+    let contents = pack_ids
+        .iter()
+        .map(|id| {
+            store
+                .get(id)
+                .map(|pack| pack.content.as_str())
+                .ok_or_else(|| format!("Unknown pack id: {}", id))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(combine_packs(&contents))
+}
+
+/// Write the given packs (ids from `PackItem`, in order) to `path` as one combined file.
+#[tauri::command]
+pub async fn export_combined_packs(path: String, pack_ids: Vec<String>) -> Result<(), String> {
+    let combined = combine_stored_packs(&pack_ids)?;
+    write_file_content(path, combined).await.map_err(String::from)
+}
+
+/// Used by `export_packs` when no pattern is given.
+const DEFAULT_EXPORT_PATTERN: &str = "{project}-pack-{index:02}-of-{total}.md";
+
+/// Expand an export filename pattern for pack `index` (1-based) of `total`. Placeholders are
+/// `{project}`, `{index}`, and `{total}`; the numbers take a zero-padded width as in `{index:02}`.
+fn export_file_name(pattern: &str, project: &str, index: usize, total: usize) -> Result<String, String> {
+    // The project name lands in a filename: anything but a safe character becomes a dash.
+    let project: String = project
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+        .collect();
+    let project = project.trim_matches('.');
+    let mut name = String::with_capacity(pattern.len() + project.len());
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        name.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find('}')
+            .map(|at| open + at)
+            .ok_or_else(|| format!("Unclosed placeholder in export pattern: {pattern}"))?;
+        let placeholder = &rest[open + 1..close];
+        let (key, width) = match placeholder.split_once(':') {
+            Some((key, width)) => {
+                let width = width
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid width in export pattern placeholder: {{{placeholder}}}"))?;
+                (key, width)
+            }
+            None => (placeholder, 0),
+        };
+        match key {
+            "project" if width == 0 => name.push_str(if project.is_empty() { "project" } else { project }),
+            "index" => name.push_str(&format!("{index:0width$}")),
+            "total" => name.push_str(&format!("{total:0width$}")),
+            _ => return Err(format!("Unknown export pattern placeholder: {{{placeholder}}}")),
+        }
+        rest = &rest[close + 1..];
+    }
+    name.push_str(rest);
+    if name.trim().is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(format!("Export pattern must produce a plain file name: {pattern}"));
+    }
+    Ok(name)
+}
+
+/// Write each of the given packs (ids from `PackItem`, in order) to its own file in
+/// `directory`, an authorized export directory, named by `pattern` (default
+/// `DEFAULT_EXPORT_PATTERN`). Returns the paths written, in pack order.
+#[tauri::command]
+pub async fn export_packs(
+    directory: String,
+    pack_ids: Vec<String>,
+    project: String,
+    pattern: Option<String>,
+) -> Result<Vec<String>, String> {
+    let pattern = pattern.filter(|pattern| !pattern.trim().is_empty());
+    let pattern = pattern.as_deref().unwrap_or(DEFAULT_EXPORT_PATTERN);
+    let total = pack_ids.len();
+    let packs = {
+        let store = PACK_STORE.lock().map_err(|e| e.to_string())?;
+        pack_ids
+            .iter()
+            .map(|id| {
+                store
+                    .get(id)
+                    .map(|pack| pack.content.clone())
+                    .ok_or_else(|| format!("Unknown pack id: {}", id))
+            })
+            .collect::<Result<Vec<_>, String>>()?
+    };
+    let mut paths = Vec::with_capacity(total);
+    for index in 1..=total {
+        let path = std::path::Path::new(&directory).join(export_file_name(pattern, &project, index, total)?);
+        paths.push(path.to_string_lossy().to_string());
+    }
+    if paths.iter().collect::<HashSet<_>>().len() < paths.len() {
+        return Err(format!("Export pattern gives several packs the same file name: {pattern}"));
+    }
+    for (path, content) in paths.iter().zip(packs) {
+        write_file_content(path.clone(), content).await.map_err(String::from)?;
+    }
+    Ok(paths)
+}
+
+/// Passphrases shorter than this are refused; age's scrypt slows guessing, not short secrets.
+const MIN_PASSPHRASE_CHARS: usize = 12;
+
+/// `plaintext` as a binary age file sealed with `passphrase` (scrypt recipient), which the
+/// receiver opens with `age --decrypt` or any age implementation.
+fn encrypt_with_passphrase(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(format!("Passphrase must be at least {MIN_PASSPHRASE_CHARS} characters"));
+    }
+    let encryptor = age::Encryptor::with_user_passphrase(age::secrecy::SecretString::from(passphrase.to_string()));
+    let mut encrypted = Vec::with_capacity(plaintext.len() + 256);
+    let mut writer = encryptor.wrap_output(&mut encrypted).map_err(|e| e.to_string())?;
+    writer.write_all(plaintext).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(encrypted)
+}
+
+/// `export_combined_packs`, encrypted with `passphrase` so the file can travel over
+/// channels that are not fully trusted.
+#[tauri::command]
+pub async fn export_encrypted_packs(
+    path: String,
+    pack_ids: Vec<String>,
+    passphrase: String,
+) -> Result<(), String> {
+    let combined = combine_stored_packs(&pack_ids)?;
+    // scrypt is deliberately slow; keep it off the async runtime.
+    let encrypted = tauri::async_runtime::spawn_blocking(move || encrypt_with_passphrase(combined.as_bytes(), &passphrase))
+        .await
+        .map_err(|e| e.to_string())??;
+    write_file_bytes(path, encrypted).await
+}
+
+/// Used when prompts are requested without a template of their own.
+const DEFAULT_PROMPT_TEMPLATE: &str = "This is pack {index} of {total} containing {summary}. {instruction}";
+
+/// Short description of what a pack holds: its busiest directories, largest first.
+fn describe_pack_contents(paths: &[String]) -> String {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for path in paths {
+        *counts.entry(parent_dir(path)).or_default() += 1;
+    }
+    let mut areas: Vec<(&str, usize)> = counts.into_iter().collect();
+    areas.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let names: Vec<String> = areas
+        .iter()
+        .take(3)
+        .map(|(dir, _)| if dir.is_empty() { "top-level files".to_string() } else { format!("{dir}/") })
+        .collect();
+    let listed = match names.as_slice() {
+        [] => "no files".to_string(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    };
+    match areas.len().saturating_sub(3) {
+        0 => listed,
+        more => format!("{listed} (+{more} more)"),
+    }
+}
+
+/// Most entries listed per line of a pack note.
+const PACK_NOTE_ENTRIES: usize = 6;
+/// Most exported names a pack note lists, and most taken from any one file.
+const PACK_NOTE_SYMBOLS: usize = 12;
+const PACK_NOTE_SYMBOLS_PER_FILE: usize = 3;
+
+/// `name (count)` entries, most common first, with the overflow counted.
+fn ranked_counts(counts: HashMap<String, usize>) -> String {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let mut listed: Vec<String> =
+        counts.iter().take(PACK_NOTE_ENTRIES).map(|(name, count)| format!("{name} ({count})")).collect();
+    if counts.len() > PACK_NOTE_ENTRIES {
+        listed.push(format!("+{} more", counts.len() - PACK_NOTE_ENTRIES));
+    }
+    listed.join(", ")
+}
+
+/// A few lines opening a pack so a reader landing in the middle of a run knows what it holds:
+/// its directories and languages by file count, and the first names its files export. Built
+/// from paths and ASTs only.
+fn render_pack_note(files: &[FileContent], bin: &[usize], tokens: usize) -> String {
+    let mut directories: HashMap<String, usize> = HashMap::new();
+    let mut languages: HashMap<String, usize> = HashMap::new();
+    let mut symbols: Vec<String> = Vec::new();
+    for &idx in bin {
+        let path = &files[idx].path;
+        let dir = parent_dir(path);
+        let dir = if dir.is_empty() { "top-level files".to_string() } else { format!("{dir}/") };
+        *directories.entry(dir).or_default() += 1;
+        *languages.entry(markdown_fence_language(path).into_owned()).or_default() += 1;
+        if symbols.len() < PACK_NOTE_SYMBOLS {
+            let names = exported_symbol_names(path, &files[idx].content);
+            let room = PACK_NOTE_SYMBOLS - symbols.len();
+            symbols.extend(names.into_iter().take(PACK_NOTE_SYMBOLS_PER_FILE.min(room)));
+        }
+    }
+    let mut note = format!(
+        "Pack overview: {} file(s), ~{tokens} tokens.\nDirectories: {}\nLanguages: {}",
+        bin.len(),
+        ranked_counts(directories),
+        ranked_counts(languages)
+    );
+    if !symbols.is_empty() {
+        note.push_str("\nKey symbols: ");
+        note.push_str(&symbols.join(", "));
+    }
+    note
+}
+
+/// Fill a prompt template. Placeholders: `{index}`, `{total}`, `{summary}`, `{files}`,
+/// `{tokens}`, and `{instruction}` (wait for more packs, or answer after the last one).
+fn render_pack_prompt(template: &str, pack: &PackItem, index: usize, total: usize) -> String {
+    let instruction = if index < total {
+        "Wait for all packs before answering."
+    } else if total > 1 {
+        "That was the last pack; you can answer now."
+    } else {
+        "Answer using this code as context."
+    };
+    template
+        .replace("{index}", &index.to_string())
+        .replace("{total}", &total.to_string())
+        .replace("{summary}", &describe_pack_contents(&pack.file_paths))
+        .replace("{files}", &pack.file_count.to_string())
+        .replace("{tokens}", &pack.estimated_tokens.to_string())
+        .replace("{instruction}", instruction)
+}
+
+#[tauri::command]
+pub async fn pack_files(request: PackRequest) -> Result<PackResponse, String> {
+    pack_selection(request, true).await
+}
+
+/// `pack_files`, capping the per-pack budget at the LLM profile's context window only when
+/// `fit_to_window`; the profile's tokenizer and preferred format apply either way.
+async fn pack_selection(mut request: PackRequest, fit_to_window: bool) -> Result<PackResponse, String> {
+    apply_pack_profile(&mut request)?;
+    let requested_budget = request.max_tokens_per_pack;
+    let llm_profile = apply_llm_profile(&mut request);
+    if !fit_to_window {
+        request.max_tokens_per_pack = requested_budget;
+    }
+    let template = match request.template.as_deref().filter(|text| !text.trim().is_empty()) {
+        Some(text) => Some(PackTemplate::parse(text)?),
+        None => None,
+    };
+    if request.files.is_empty() {
+        return Ok(PackResponse {
+            packs: Vec::new(),
+            total_tokens: 0,
+            warnings: Vec::new(),
+            fingerprint: store_manifest(PackManifest::new()),
+            content_hash: combined_content_hash(&[]),
+            manifest: HashMap::new(),
+            placements: Vec::new(),
+        });
+    }
+
+    let mut warnings = Vec::new();
+    if request.include_schemas {
+        let schemas = unselected_schemas(&request.files, &request.excluded_paths, |path| read_project_file(path).ok());
+        if !schemas.is_empty() {
+            let paths: Vec<&str> = schemas.iter().map(|schema| schema.path.as_str()).collect();
+            warnings.push(format!(
+                "Added {} schema file(s) behind generated code the selection uses: {}.",
+                schemas.len(),
+                paths.join(", ")
+            ));
+            request.excluded_paths.retain(|path| !paths.contains(&normalize_path(path).as_str()));
+            request.files.extend(schemas);
+        }
+    }
+
+    // Fingerprint the files as selected, before any are split into parts.
+    let manifest = build_manifest(&request.files);
+    // Files unchanged since the previous run reuse its packed text; its packs tell which of
+    // this run's packs came out the same.
+    let previous = request.previous_manifest.take().map(parse_manifest).transpose()?;
+    let previous_packs = previous.as_ref().and_then(previous_pack_keys);
+    let settings = packing_settings(&mut request);
+    let format = request.output_format.as_str();
+
+    // Use pre-computed token counts from frontend when available, fall back to the profile's
+    // tokenizer (or an estimate).
+    let counter = TokenCounter::for_profile(request.llm_profile_id.as_deref());
+    let focus_paths: HashSet<String> = request.focus_paths.iter().map(|path| normalize_path(path)).collect();
+    if !focus_paths.is_empty() {
+        let selected: HashSet<String> = request.files.iter().map(|file| normalize_path(&file.path)).collect();
+        let mut missing: Vec<&str> = focus_paths.difference(&selected).map(String::as_str).collect();
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            warnings.push(format!("Focus path(s) not in the selection: {}.", missing.join(", ")));
+        }
+    }
+    let mut fragments: HashMap<String, FileFragment> = HashMap::new();
+    if let Some(max_tokens) = request.max_tokens_per_file.filter(|&max_tokens| max_tokens > 0) {
+        let split = split_oversized_files(&mut request.files, max_tokens, &counter, &focus_paths);
+        if !split.is_empty() {
+            let originals: HashSet<&str> = split.iter().map(|part| part.original_path.as_str()).collect();
+            warnings.push(format!(
+                "Split {} oversized file(s) into {} parts of at most {max_tokens} tokens.",
+                originals.len(),
+                split.len()
+            ));
+        }
+        fragments.extend(split.into_iter().map(|part| (part.path.clone(), part)));
+    }
+    let files = &request.files;
+    let mut token_counts: Vec<usize> = files
+        .iter()
+        .map(|f| f.token_count.unwrap_or_else(|| counter.count(&f.path, &f.content)))
+        .collect();
+
+    // Dependency-aware ordering for code comprehension; every strategy starts from it.
+    let dependency_order = compute_dependency_order(files);
+
+    // Text cleanups only touch the packed output; the ordering above read the original content.
+    let redactor = Redactor::new(&request.redactions)?;
+    // Focus files are packed as given: never summarized, outlined, cleaned up, or held back.
+    let focused: HashSet<usize> = (0..files.len())
+        .filter(|&idx| focus_paths.contains(&normalize_path(&files[idx].path)))
+        .collect();
+    let mut priorities = file_priorities(files, &request.priorities)?;
+    for &idx in &focused {
+        if priorities[idx] == Some(Priority::Low) {
+            priorities[idx] = None;
+        }
+    }
+    // Files packed as a summary instead of their content.
+    let mut substitutes: HashMap<usize, String> = HashMap::new();
+    if request.summarize_locales {
+        substitutes.extend(summarize_translations(files));
+    }
+    if request.profile_data {
+        substitutes.extend(summarize_data_files(files));
+    }
+    if let Some(inference) = &request.schema_inference {
+        for (idx, schema) in summarize_config_schemas(files, inference) {
+            substitutes.entry(idx).or_insert(schema);
+        }
+    }
+    substitutes.retain(|idx, _| !focused.contains(idx));
+    // Low-priority files as signatures; their doc comments survive comment stripping.
+    let mut outlined: HashSet<usize> = HashSet::new();
+    if request.outline_low_priority {
+        for (idx, file) in files.iter().enumerate().filter(|&(idx, _)| priorities[idx] == Some(Priority::Low)) {
+            if !substitutes.contains_key(&idx)
+                && let Some(outline) = outline_source(&file.path, &file.content)
+            {
+                substitutes.insert(idx, outline);
+                outlined.insert(idx);
+            }
+        }
+    }
+    // Where each file's numbering starts: 1, or the line a split part starts at in its original.
+    let mut first_lines = vec![1; files.len()];
+    if request.include_line_numbers {
+        let mut next_line: HashMap<&str, usize> = HashMap::new();
+        for (idx, file) in files.iter().enumerate() {
+            if let Some(fragment) = fragments.get(&file.path) {
+                let line = next_line.entry(fragment.original_path.as_str()).or_insert(1);
+                first_lines[idx] = *line;
+                *line += file.content.matches('\n').count();
+            }
+        }
+    }
+    // Tokens whitespace compression removed, per file.
+    let mut saved_tokens = vec![0; files.len()];
+    let mut contents: Vec<Cow<str>> = files
+        .iter()
+        .enumerate()
+        .map(|(idx, file)| {
+            let hash = manifest.get(&file.path).copied().filter(|_| !substitutes.contains_key(&idx));
+            if let Some(hash) = hash
+                && previous.as_ref().is_some_and(|previous| previous.get(&file.path) == Some(&hash))
+                && let Some(cached) = cached_packed_file(&file.path, hash, settings, first_lines[idx])
+            {
+                token_counts[idx] = cached.tokens;
+                saved_tokens[idx] = cached.saved_tokens;
+                return Cow::Owned(cached.content);
+            }
+            let mut packed = match substitutes.get(&idx) {
+                Some(summary) => Cow::Borrowed(summary.as_str()),
+                None => Cow::Borrowed(file.content.as_str()),
+            };
+            let focus = focused.contains(&idx);
+            if request.normalize_content && !focus {
+                packed = chain_transform(packed, normalize_content);
+            }
+            if request.strip_comments && !focus && !outlined.contains(&idx) {
+                packed = chain_transform(packed, |text| {
+                    strip_comments(&file.path, text).map_or(Cow::Borrowed(text), Cow::Owned)
+                });
+            }
+            if request.strip_imports && !focus {
+                packed = chain_transform(packed, |text| strip_import_statements(&file.path, text));
+            }
+            if !redactor.is_empty() {
+                packed = chain_transform(packed, |text| redactor.redact(text));
+            }
+            if let Some(compression) = request.compress_whitespace.as_ref().filter(|_| !focus)
+                && let Cow::Owned(compressed) = compress_whitespace(&file.path, &packed, compression.strip_indentation)
+            {
+                let before = counter.count(&file.path, &packed);
+                saved_tokens[idx] = before.saturating_sub(counter.count(&file.path, &compressed));
+                packed = Cow::Owned(compressed);
+            }
+            if request.include_line_numbers && !substitutes.contains_key(&idx) {
+                packed = Cow::Owned(number_lines_from_original(&file.content, &packed, first_lines[idx]));
+            }
+            if focus {
+                packed = Cow::Owned(format!("{FOCUS_BANNER}\n{packed}"));
+            }
+            if *packed != *file.content {
+                token_counts[idx] = match file.token_count {
+                    Some(count) if !counter.is_exact() && packed.len() != file.content.len() => {
+                        count * packed.len() / file.content.len().max(1)
+                    }
+                    _ => counter.count(&file.path, &packed),
+                };
+            }
+            if let Some(hash) = hash {
+                remember_packed_file(
+                    &file.path,
+                    PackedFile {
+                        hash,
+                        settings,
+                        first_line: first_lines[idx],
+                        content: packed.to_string(),
+                        tokens: token_counts[idx],
+                        saved_tokens: saved_tokens[idx],
+                    },
+                );
+            }
+            packed
+        })
+        .collect();
+    if let Some(profile) = &llm_profile {
+        let window = profile.context_window_tokens;
+        for (file, &tokens) in files.iter().zip(&token_counts).filter(|&(_, &tokens)| tokens > window) {
+            warnings.push(format!(
+                "{} ({tokens} tokens) alone exceeds the {window}-token context window of {}; set a per-file token limit to split it.",
+                file.path, profile.name
+            ));
+        }
+    }
+    // A file that alone exceeds the pack budget keeps its head and tail rather than overflow.
+    let mut truncations: HashMap<usize, FileTruncation> = HashMap::new();
+    if let Some(budget) = request.max_tokens_per_pack.filter(|&budget| budget > 0) {
+        let mut over_budget = Vec::new();
+        for (idx, file) in files.iter().enumerate() {
+            let original_tokens = token_counts[idx];
+            if original_tokens <= budget {
+                continue;
+            }
+            if focused.contains(&idx) {
+                warnings.push(format!(
+                    "Focus file {} ({original_tokens} tokens) exceeds the {budget}-token pack budget and is packed whole.",
+                    file.path
+                ));
+                continue;
+            }
+            // Bytes are scaled by the token ratio, then shrunk by the measured overshoot (and
+            // always by a byte, so this ends) while the recount is over.
+            let mut max_bytes = contents[idx].len() * budget / original_tokens;
+            let fitted = loop {
+                let Some((text, omitted_lines)) = truncate_middle(&contents[idx], max_bytes, budget) else {
+                    break None;
+                };
+                let kept_tokens = counter.count(&file.path, &text);
+                if kept_tokens <= budget {
+                    break Some((text, omitted_lines, kept_tokens));
+                }
+                if max_bytes == 0 {
+                    break None;
+                }
+                max_bytes = (max_bytes * budget / kept_tokens * 9 / 10).min(max_bytes - 1);
+            };
+            let Some((text, omitted_lines, kept_tokens)) = fitted else {
+                over_budget.push(file.path.as_str());
+                continue;
+            };
+            contents[idx] = Cow::Owned(text);
+            token_counts[idx] = kept_tokens;
+            truncations.insert(idx, FileTruncation { path: file.path.clone(), original_tokens, kept_tokens, omitted_lines });
+        }
+        if !over_budget.is_empty() {
+            warnings.push(format!(
+                "Could not cut {} file(s) to the {budget}-token pack budget, so they are packed over it: {}.",
+                over_budget.len(),
+                over_budget.join(", ")
+            ));
+        }
+        if !truncations.is_empty() {
+            warnings.push(format!(
+                "Truncated {} file(s) that alone exceeded the {budget}-token pack budget to their first and last lines.",
+                truncations.len()
+            ));
+        }
+    }
+    let mut total_tokens: usize = token_counts.iter().sum();
+
+    // High-priority files lead; low-priority ones are left to the last pack, after the strategy.
+    let weight = |idx: usize| priorities[idx].unwrap_or(Priority::Normal);
+    let (mut ranked_order, demoted): (Vec<usize>, Vec<usize>) =
+        dependency_order.iter().partition(|&&idx| weight(idx) != Priority::Low);
+    ranked_order.sort_by_key(|&idx| weight(idx));
+
+    // A profile's window is a hard limit, so each file is also charged the separator and
+    // markers around its section; the budget already leaves room for the pack header and footer.
+    let file_ids = if request.file_ids { assign_file_ids(files) } else { Vec::new() };
+    let mut bin_tokens = token_counts.clone();
+    if llm_profile.is_some() {
+        let layout = SectionLayout::new(request.boundaries.as_ref())
+            .with_file_ids(files.iter().zip(&file_ids).map(|(file, id)| (file.path.as_str(), id.as_str())).collect());
+        for (idx, file) in files.iter().enumerate() {
+            let mut frame = String::new();
+            layout.push(&mut frame, false, &file.path, "", 0, format);
+            bin_tokens[idx] += counter.count(&file.path, &frame);
+        }
+    }
+
+    // Enough packs for the budget on average; strategies add more when files split unevenly.
+    let mut num_packs = request.num_packs.max(1);
+    if let Some(budget) = request.max_tokens_per_pack.filter(|&budget| budget > 0) {
+        let ranked_tokens: usize = ranked_order.iter().map(|&idx| bin_tokens[idx]).sum();
+        num_packs = num_packs.max(ranked_tokens.div_ceil(budget));
+    }
+
+    let mut notes = OrderingNotes::new(&dependency_order);
+    notes.priority = priorities;
+    let input = StrategyInput {
+        files,
+        dependency_order: &ranked_order,
+        token_counts: &bin_tokens,
+        num_packs,
+        max_tokens_per_pack: request.max_tokens_per_pack,
+    };
+    let mut bins = pack_strategy(&request.strategy).distribute(&input, &mut notes);
+    num_packs = num_packs.max(bins.iter().filter(|bin| !bin.is_empty()).count());
+    let dropped = demote_to_last_pack(&mut bins, &demoted, &bin_tokens, request.max_tokens_per_pack, num_packs);
+    if !dropped.is_empty() {
+        warnings.push(format!(
+            "Dropped {} low-priority file(s) that did not fit {} pack(s) of at most {} tokens.",
+            dropped.len(),
+            num_packs,
+            request.max_tokens_per_pack.unwrap_or_default()
+        ));
+        total_tokens -= dropped.iter().map(|&idx| token_counts[idx]).sum::<usize>();
+        request.excluded_paths.extend(dropped.iter().map(|&idx| files[idx].path.clone()));
+    }
+
+    let pack_total = bins.iter().filter(|bin| !bin.is_empty()).count();
+    let placements = notes.explain(files, &bins, request.num_packs.max(1));
+    let generated_at = utc_now();
+    let provenance = request.provenance.as_ref().and_then(render_provenance);
+    // Appendices close the last pack, in this order.
+    let mut appendices: Vec<(&str, String)> = Vec::new();
+    if request.config_surface
+        && let Some(surface) = render_config_surface(files)
+    {
+        appendices.push((CONFIG_SURFACE_PATH, surface));
+    }
+    if request.todo_index
+        && let Some(index) = render_todo_index(files)
+    {
+        appendices.push((TECH_DEBT_PATH, index));
+    }
+    if request.error_index
+        && let Some(index) = render_error_index(files)
+    {
+        appendices.push((ERROR_MESSAGES_PATH, index));
+    }
+    if request.type_closure
+        && let Some(declarations) = render_type_closure(files, |path| read_project_file(path).ok())
+    {
+        appendices.push((REFERENCED_TYPES_PATH, declarations));
+    }
+    if !request.excluded_paths.is_empty() {
+        appendices.push((EXCLUDED_FILES_PATH, render_excluded_appendix(&request.excluded_paths)));
+    }
+    let layout = SectionLayout::new(request.boundaries.as_ref())
+        .with_file_ids(files.iter().zip(&file_ids).map(|(file, id)| (file.path.as_str(), id.as_str())).collect());
+    let source_tree = if template.is_some() { render_source_tree(files) } else { String::new() };
+    let mut pack_number = 0;
+    let mut packs = Vec::new();
+    let mut pack_keys = HashSet::new();
+    for (i, bin) in bins.iter().enumerate() {
+        if bin.is_empty() {
+            continue;
+        }
+
+        // Size the buffer up front so file contents are copied exactly once.
+        let capacity: usize = bin
+            .iter()
+            .map(|&idx| {
+                let path = &files[idx].path;
+                path.len() + contents[idx].len() + SECTION_OVERHEAD + layout.overhead(path)
+            })
+            .sum();
+        pack_number += 1;
+        let file_index: Vec<(&str, &str)> = if file_ids.is_empty() {
+            Vec::new()
+        } else {
+            let mut index: Vec<(&str, &str)> =
+                bin.iter().map(|&idx| (file_ids[idx].as_str(), files[idx].path.as_str())).collect();
+            index.sort();
+            index
+        };
+        let note = request
+            .pack_notes
+            .then(|| render_pack_note(files, bin, bin.iter().map(|&idx| token_counts[idx]).sum()));
+        let frame = PackFrame {
+            format,
+            wrapper: request.wrapper.as_ref(),
+            index: pack_number,
+            total: pack_total,
+            generated_at: &generated_at,
+            provenance: provenance.as_deref(),
+            file_index: &file_index,
+            file_count: bin.len(),
+            note: note.as_deref(),
+        };
+        let templated = template.as_ref().map(|template| (template, template_pack_context(&frame, &source_tree)));
+        let mut footer = String::new();
+        let mut content = String::new();
+        if let Some((template, context)) = &templated {
+            template.push_footer(&mut footer, context);
+            template.push_header(&mut content, context);
+        } else {
+            push_pack_close(&mut footer, &frame);
+            push_pack_open(&mut content, &frame);
+            if format == "xml" && pack_number == 1 {
+                push_directory_structure(&mut content, files);
+            }
+        }
+        let pack_appendices: &[(&str, String)] = if pack_number == pack_total { &appendices } else { &[] };
+        let section_count = bin.len() + pack_appendices.len();
+        let appendix_capacity: usize = pack_appendices
+            .iter()
+            .map(|(path, text)| layout.overhead(path) + path.len() + text.len() + SECTION_OVERHEAD)
+            .sum();
+        content.reserve_exact(capacity + appendix_capacity + footer.len());
+        let mut pack_tokens = 0;
+        let mut file_paths = Vec::with_capacity(bin.len());
+        let mut sections = Vec::with_capacity(bin.len());
+        let mut pack_fragments = Vec::new();
+        let mut pack_truncations = Vec::new();
+
+        for (position, &file_idx) in bin.iter().enumerate() {
+            let file = &files[file_idx];
+            let tokens = token_counts[file_idx];
+            sections.push(match &templated {
+                Some((template, context)) => {
+                    let id = layout.file_ids.get(file.path.as_str()).copied();
+                    let section = template_file(&file.path, &contents[file_idx], tokens, id);
+                    let start = content.len();
+                    template.push_file(&mut content, context, &section, position, section_count);
+                    start..content.len()
+                }
+                None => layout.push(&mut content, position == 0, &file.path, &contents[file_idx], tokens, format),
+            });
+            pack_tokens += tokens;
+            file_paths.push(file.path.clone());
+            pack_fragments.extend(fragments.get(&file.path).cloned());
+            pack_truncations.extend(truncations.get(&file_idx).cloned());
+        }
+        for (position, (path, appendix)) in pack_appendices.iter().enumerate() {
+            let appendix_tokens = counter.count(path, appendix);
+            sections.push(match &templated {
+                Some((template, context)) => {
+                    let start = content.len();
+                    let section = template_file(path, appendix, appendix_tokens, None);
+                    template.push_file(&mut content, context, &section, bin.len() + position, section_count);
+                    start..content.len()
+                }
+                None => layout.push(&mut content, false, path, appendix, appendix_tokens, format),
+            });
+            pack_tokens += appendix_tokens;
+            total_tokens += appendix_tokens;
+        }
+        content.push_str(&footer);
+
+        let id = short_hex(&blake3::hash(content.as_bytes()));
+        let content_hash = sections_content_hash(&content, &sections);
+        let key = pack_key(&settings, pack_number, pack_total, &content_hash);
+        let changed = !previous_packs.as_ref().is_some_and(|keys| keys.contains(&key));
+        pack_keys.insert(key);
+        store_pack(
+            &id,
+            StoredPack {
+                content: content.clone(),
+                sections,
+            },
+        );
+        packs.push(PackItem {
+            id,
+            content_hash,
+            index: i,
+            content,
+            estimated_tokens: pack_tokens,
+            file_count: bin.len(),
+            file_paths,
+            suggested_prompt: None,
+            fragments: pack_fragments,
+            truncations: pack_truncations,
+            saved_tokens: request
+                .compress_whitespace
+                .is_some()
+                .then(|| bin.iter().map(|&idx| saved_tokens[idx]).sum()),
+            changed,
+        });
+    }
+
+    if request.suggest_prompts {
+        let template = request.prompt_template.as_deref().unwrap_or(DEFAULT_PROMPT_TEMPLATE);
+        let total = packs.len();
+        for (position, pack) in packs.iter_mut().enumerate() {
+            pack.suggested_prompt = Some(render_pack_prompt(template, pack, position + 1, total));
+        }
+    }
+
+    warnings.extend(collect_parse_warnings(files));
+    let hashes = manifest.iter().map(|(path, hash)| (path.clone(), hash.to_hex().to_string())).collect();
+    let fingerprint = store_manifest(manifest);
+    remember_pack_keys(&fingerprint, pack_keys);
+    Ok(PackResponse {
+        content_hash: combined_content_hash(&packs),
+        packs,
+        total_tokens,
+        warnings,
+        fingerprint,
+        manifest: hashes,
+        placements,
+    })
+}
+
+/// Every selected path as an indented tree: the `source_tree` of a pack template.
+fn render_source_tree(files: &[FileContent]) -> String {
+    let mut entries: Vec<(&str, &str)> = files.iter().map(|file| (file.path.as_str(), "")).collect();
+    entries.sort();
+    let mut tree = String::new();
+    push_path_tree(&mut tree, &entries);
+    tree
+}
+
+/// Pack-level values of a `PackTemplate`, under both our names and code2prompt's.
+fn template_pack_context(frame: &PackFrame, source_tree: &str) -> serde_json::Value {
+    let project = frame.wrapper.and_then(|w| w.project_name.as_deref()).unwrap_or_default();
+    serde_json::json!({
+        "absolute_code_path": project,
+        "project_name": project,
+        "source_tree": source_tree,
+        "pack_index": frame.index,
+        "total_packs": frame.total,
+        "file_count": frame.file_count,
+        "pack_note": frame.note.unwrap_or_default(),
+    })
+}
+
+/// One file's values in a `PackTemplate`; `code` is the content fenced the way code2prompt
+/// renders it, so its templates keep working.
+fn template_file(path: &str, content: &str, tokens: usize, id: Option<&str>) -> serde_json::Value {
+    let language = markdown_fence_language(path);
+    serde_json::json!({
+        "path": path,
+        "extension": path.rsplit_once('.').map_or("", |(_, ext)| ext),
+        "language": language,
+        "content": content,
+        "code": format!("```{language}\n{content}\n```"),
+        "tokens": tokens,
+        "token_count": tokens,
+        "id": id,
+    })
+}
+
+/// Follow-up requests are for a handful of files, not a second full pack.
+const MAX_ADDITIONAL_FILES: usize = 50;
+
+/// Format `files` with the same section headers and fences as `pack_files`, as one
+/// paste-ready block (no wrapper, provenance, or appendix).
+fn format_additional_files(files: &[FileContent], format: &str) -> PackItem {
+    let frame = PackFrame {
+        format,
+        wrapper: None,
+        index: 1,
+        total: 1,
+        generated_at: "",
+        provenance: None,
+        file_index: &[],
+        file_count: files.len(),
+        note: None,
+    };
+    let layout = SectionLayout::new(None);
+    let mut content = String::new();
+    let mut sections = Vec::with_capacity(files.len());
+    let mut estimated_tokens = 0;
+    push_pack_open(&mut content, &frame);
+    for (position, file) in files.iter().enumerate() {
+        let tokens = estimate_tokens_for_path(&file.path, &file.content);
+        sections.push(layout.push(&mut content, position == 0, &file.path, &file.content, tokens, format));
+        estimated_tokens += tokens;
+    }
+    push_pack_close(&mut content, &frame);
+
+    let id = short_hex(&blake3::hash(content.as_bytes()));
+    let content_hash = sections_content_hash(&content, &sections);
+    store_pack(&id, StoredPack { content: content.clone(), sections });
+    PackItem {
+        id,
+        content_hash,
+        index: 0,
+        content,
+        estimated_tokens,
+        file_count: files.len(),
+        file_paths: files.iter().map(|file| file.path.clone()).collect(),
+        suggested_prompt: None,
+        fragments: Vec::new(),
+        truncations: Vec::new(),
+        saved_tokens: None,
+        changed: true,
+    }
+}
+
+/// Quick follow-up when the model asks for files it was not given: read `paths` (as they
+/// appear in pack headers, or absolute) and format them like the original pack, applying
+/// the same `redactions`.
+#[tauri::command]
+pub async fn pack_additional(
+    paths: Vec<String>,
+    format: String,
+    redactions: Option<Vec<RedactionRule>>,
+) -> Result<PackItem, String> {
+    if paths.is_empty() {
+        return Err("No files requested".to_string());
+    }
+    if paths.len() > MAX_ADDITIONAL_FILES {
+        return Err(format!(
+            "Too many files requested ({}); pack_additional takes at most {MAX_ADDITIONAL_FILES}",
+            paths.len()
+        ));
+    }
+
+    let redactor = Redactor::new(redactions.as_deref().unwrap_or_default())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut files = Vec::with_capacity(paths.len());
+        let mut failures = Vec::new();
+        for path in paths {
+            match read_project_file(&path) {
+                Ok(content) => {
+                    let content = match redactor.redact(&content) {
+                        Cow::Owned(redacted) => redacted,
+                        Cow::Borrowed(_) => content,
+                    };
+                    files.push(FileContent { path, content, token_count: None });
+                }
+                Err(e) => failures.push(e),
+            }
+        }
+        if !failures.is_empty() {
+            return Err(failures.join("; "));
+        }
+        Ok(format_additional_files(&files, &format))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// `pack_matches` keeps the first this many matching files and warns about the rest.
+const MAX_MATCHED_FILES: usize = 200;
+
+/// Lines containing `query` plus `context_lines` either side, numbered from 1. Overlapping
+/// windows merge and gaps are marked with `…`. `None` when nothing matches.
+fn match_regions(content: &str, query: &str, context_lines: usize) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut windows: Vec<Range<usize>> = Vec::new();
+    for (line_idx, line) in lines.iter().enumerate() {
+        if !line.contains(query) {
+            continue;
+        }
+        let window = line_idx.saturating_sub(context_lines)..(line_idx + context_lines + 1).min(lines.len());
+        match windows.last_mut() {
+            Some(last) if window.start <= last.end => last.end = window.end,
+            _ => windows.push(window),
+        }
+    }
+    if windows.is_empty() {
+        return None;
+    }
+
+    let width = windows.last().map_or(1, |window| window.end.to_string().len());
+    let mut out = String::new();
+    for (position, window) in windows.into_iter().enumerate() {
+        if position > 0 {
+            out.push_str("…\n");
+        }
+        for line_idx in window {
+            out.push_str(&format!("{:>width$}: {}\n", line_idx + 1, lines[line_idx]));
+        }
+    }
+    out.pop();
+    Some(out)
+}
+
+/// Search `root` for `query` (literal, case-sensitive) and pack the matching files, or only
+/// the matching regions with `context_lines` of context when given, as a single pack.
+#[tauri::command]
+pub async fn pack_matches(
+    root: String,
+    query: String,
+    context_lines: Option<usize>,
+    output_format: Option<String>,
+    redactions: Option<Vec<RedactionRule>>,
+) -> Result<PackResponse, String> {
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let root_path = std::path::PathBuf::from(&root);
+    let files = read_directory_contents(root, true, Vec::new()).await?;
+
+    let mut matched = Vec::new();
+    let mut skipped = 0;
+    for file in files {
+        let content = match context_lines {
+            Some(context) => match match_regions(&file.content, &query, context) {
+                Some(regions) => regions,
+                None => continue,
+            },
+            None if file.content.contains(query.as_str()) => file.content,
+            None => continue,
+        };
+        if matched.len() == MAX_MATCHED_FILES {
+            skipped += 1;
+            continue;
+        }
+        let path = std::path::Path::new(&file.path)
+            .strip_prefix(&root_path)
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+            .unwrap_or(file.path);
+        matched.push(FileContent { path, content, token_count: None });
+    }
+    if matched.is_empty() {
+        return Err(format!("No files contain \"{query}\""));
+    }
+    matched.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut response = pack_files(PackRequest {
+        files: matched,
+        output_format: output_format.unwrap_or_else(|| "markdown".to_string()),
+        redactions: redactions.unwrap_or_default(),
+        ..Default::default()
+    })
+    .await?;
+    if skipped > 0 {
+        response.warnings.push(format!(
+            "{skipped} more file(s) matched \"{query}\" beyond the first {MAX_MATCHED_FILES}; narrow the query to see them."
+        ));
+    }
+    Ok(response)
+}
+
+/// Path of the synthetic section listing files that exist but were not packed.
+const EXCLUDED_FILES_PATH: &str = "EXCLUDED_FILES.md";
+
+/// Path of the appendix listing the environment variables and feature flags the packed code reads.
+const CONFIG_SURFACE_PATH: &str = "CONFIGURATION.md";
+
+/// Path of the appendix collecting TODO/FIXME/HACK comments from the packed code.
+const TECH_DEBT_PATH: &str = "TECH_DEBT.md";
+
+/// Path of the appendix indexing the error messages the packed code raises.
+const ERROR_MESSAGES_PATH: &str = "ERROR_MESSAGES.md";
+
+/// Path of the appendix holding the type declarations imported from unselected files.
+const REFERENCED_TYPES_PATH: &str = "REFERENCED_TYPES.ts";
+
+/// Paths beyond this are summarized as a count so the appendix stays small.
+const EXCLUDED_APPENDIX_LIMIT: usize = 2_000;
+
+/// Excluded paths grouped one directory per line (`src/ui/: a.tsx, b.tsx`), so the model
+/// knows what exists and can ask for it by name.
+fn render_excluded_appendix(paths: &[String]) -> String {
+    let mut sorted: Vec<&str> = paths.iter().map(String::as_str).collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let overflow = sorted.len().saturating_sub(EXCLUDED_APPENDIX_LIMIT);
+    sorted.truncate(EXCLUDED_APPENDIX_LIMIT);
+
+    let mut out = String::from("# Excluded files\n\nThese files exist but were not included. Ask for any by path.\n");
+    let mut current_dir = None;
+    for path in sorted {
+        let (dir, name) = match path.rsplit_once('/') {
+            Some((dir, name)) => (dir, name),
+            None => ("", path),
+        };
+        if current_dir == Some(dir) {
+            out.push_str(", ");
+        } else {
+            out.push('\n');
+            out.push_str(if dir.is_empty() { "." } else { dir });
+            out.push_str("/: ");
+            current_dir = Some(dir);
+        }
+        out.push_str(name);
+    }
+    if overflow > 0 {
+        out.push_str(&format!("\n… and {overflow} more"));
+    }
+    out
+}
+
+/// Path of the synthetic file that carries the repo map in a delta pack. Markdown, so the
+/// grouped strategy places it ahead of the code.
+const REPO_MAP_PATH: &str = "REPO_MAP.md";
+
+/// Indented tree of `paths`, marking new and changed files and listing removed ones.
+fn render_repo_map(
+    paths: &[String],
+    changed: &HashSet<&str>,
+    added: &HashSet<&str>,
+    removed: &[String],
+) -> String {
+    let mut entries: Vec<(&str, &str)> = paths
+        .iter()
+        .map(|path| {
+            let marker = if added.contains(path.as_str()) {
+                " (new)"
+            } else if changed.contains(path.as_str()) {
+                " (changed)"
+            } else {
+                ""
+            };
+            (path.as_str(), marker)
+        })
+        .chain(removed.iter().map(|path| (path.as_str(), " (deleted)")))
+        .collect();
+    entries.sort();
+
+    let mut out = String::from("# Repository map\n\n");
+    push_path_tree(&mut out, &entries);
+    out
+}
+
+/// Write sorted `(path, marker)` entries as an indented tree, one directory level per two spaces.
+fn push_path_tree(out: &mut String, entries: &[(&str, &str)]) {
+    let mut open_dirs: Vec<&str> = Vec::new();
+    for &(path, marker) in entries {
+        let mut parts: Vec<&str> = path.split('/').collect();
+        let name = parts.pop().unwrap_or(path);
+        let shared = open_dirs.iter().zip(&parts).take_while(|(a, b)| a == b).count();
+        open_dirs.truncate(shared);
+        for dir in &parts[shared..] {
+            out.push_str(&"  ".repeat(open_dirs.len()));
+            out.push_str(dir);
+            out.push_str("/\n");
+            open_dirs.push(dir);
+        }
+        out.push_str(&"  ".repeat(open_dirs.len()));
+        out.push_str(name);
+        out.push_str(marker);
+        out.push('\n');
+    }
+}
+
+/// Pack only the files that changed since `previous_fingerprint` (a `PackResponse`
+/// fingerprint), led by a refreshed repo map, as one pack for an ongoing conversation.
+#[tauri::command]
+pub async fn generate_delta(
+    previous_fingerprint: String,
+    mut request: PackRequest,
+) -> Result<DeltaResponse, String> {
+    apply_pack_profile(&mut request)?;
+    let previous = PACK_MANIFESTS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&previous_fingerprint)
+        .cloned()
+        .ok_or_else(|| format!("Unknown pack fingerprint: {}", previous_fingerprint))?;
+
+    let manifest = build_manifest(&request.files);
+    let mut removed_paths: Vec<String> = previous
+        .keys()
+        .filter(|path| !manifest.contains_key(*path))
+        .cloned()
+        .collect();
+    removed_paths.sort();
+
+    let mut paths: Vec<String> = request.files.iter().map(|file| file.path.clone()).collect();
+    paths.sort();
+    let added: HashSet<&str> = paths
+        .iter()
+        .filter(|path| !previous.contains_key(*path))
+        .map(String::as_str)
+        .collect();
+    let changed: HashSet<&str> = paths
+        .iter()
+        .filter(|path| previous.get(*path).is_some_and(|hash| manifest.get(*path) != Some(hash)))
+        .map(String::as_str)
+        .collect();
+    let repo_map = render_repo_map(&paths, &changed, &added, &removed_paths);
+    let changed_paths: Vec<String> = paths
+        .iter()
+        .filter(|path| added.contains(path.as_str()) || changed.contains(path.as_str()))
+        .cloned()
+        .collect();
+
+    let mut delta_files = vec![FileContent {
+        path: REPO_MAP_PATH.to_string(),
+        content: repo_map,
+        token_count: None,
+    }];
+    delta_files.extend(
+        std::mem::take(&mut request.files)
+            .into_iter()
+            .filter(|file| changed_paths.binary_search(&file.path).is_ok()),
+    );
+    request.files = delta_files;
+    request.profile_id = None;
+    request.num_packs = 1;
+    request.max_tokens_per_pack = None;
+    request.strategy = "grouped".to_string();
+
+    // The delta is one pack however large: a window budget would split or truncate it.
+    let packed = pack_selection(request, false).await?;
+    let pack = packed
+        .packs
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Delta pack came out empty".to_string())?;
+
+    Ok(DeltaResponse {
+        pack,
+        fingerprint: store_manifest(manifest),
+        changed_paths,
+        removed_paths,
+        warnings: packed.warnings,
+    })
+}
+
+/// Whether `hashes` (path -> hex blake3, as `read_file_content_hashed` returns them) are
+/// exactly the files and contents a pack with `fingerprint` was built from.
+#[tauri::command]
+pub async fn verify_fingerprint(fingerprint: String, hashes: HashMap<String, String>) -> Result<bool, String> {
+    let manifest = parse_manifest(hashes)?;
+    Ok(manifest_fingerprint(&manifest) == fingerprint)
+}
+
+/// `pack_files` as gzipped JSON; pack contents dominate the payload for large selections.
+#[tauri::command]
+pub async fn pack_files_compressed(request: PackRequest) -> Result<tauri::ipc::Response, String> {
+    let response = pack_files(request).await?;
+    gzip_json_response(&response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FileContent;
+    use proptest::prelude::*;
+
+    // ── estimate_tokens ──
+
+    #[test]
+    fn estimate_tokens_for_path_calibrates_by_extension() {
+        let sample = "x".repeat(320);
+        assert_eq!(estimate_tokens_for_path("src/app.ts", &sample), 87);
+        assert_eq!(estimate_tokens_for_path("package.json", &sample), 107);
+        assert_eq!(estimate_tokens_for_path("README.md", &sample), 89);
+        assert_eq!(estimate_tokens_for_path("LICENSE", &sample), 80);
+        assert_eq!(estimate_tokens_for_path("empty.rs", ""), 1);
+    }
+
+    #[test]
+    fn estimate_tokens_for_path_tracks_cl100k_on_the_fixtures() {
+        let counter = TokenCounter::for_profile(Some("cl100k"));
+        // extension -> (estimated, cl100k) token totals
+        let mut totals: std::collections::BTreeMap<String, (usize, usize)> = Default::default();
+        let mut dirs = vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let name = path.to_string_lossy().to_string();
+                let content = std::fs::read_to_string(&path).unwrap();
+                if bytes_per_token(&name).is_none() || content.is_empty() {
+                    continue;
+                }
+                let total = totals.entry(path_extension(&name)).or_default();
+                total.0 += estimate_tokens_for_path(&name, &content);
+                total.1 += counter.count(&name, &content);
+            }
+        }
+        assert!(totals.len() >= 5, "fixtures cover too few extensions: {totals:?}");
+        for (extension, (estimated, exact)) in totals {
+            let error = estimated.abs_diff(exact) as f64 / exact as f64;
+            assert!(error <= 0.2, ".{extension}: estimated {estimated} tokens, cl100k counts {exact}");
+        }
+    }
+
+    #[test]
+    fn estimate_tokens_basic() {
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens(""), 1); // max(0,1) = 1
+    }
+
+    // ── normalize_path ──
+
+    #[test]
+    fn normalize_removes_dot_segments() {
+        assert_eq!(normalize_path("a/./b"), "a/b");
+        assert_eq!(normalize_path("./a/b"), "a/b");
+    }
+
+    #[test]
+    fn normalize_resolves_parent_segments() {
+        assert_eq!(normalize_path("a/b/../c"), "a/c");
+        assert_eq!(normalize_path("a/b/../../c"), "c");
+    }
+
+    #[test]
+    fn normalize_handles_backslashes() {
+        assert_eq!(normalize_path("a\\b\\c"), "a/b/c");
+    }
+
+    #[test]
+    fn normalize_collapses_empty_segments() {
+        assert_eq!(normalize_path("a//b///c"), "a/b/c");
+    }
+
+    // ── parent_dir ──
+
+    #[test]
+    fn parent_dir_returns_directory() {
+        assert_eq!(parent_dir("src/lib/foo.ts"), "src/lib");
+    }
+
+    #[test]
+    fn parent_dir_returns_empty_for_top_level() {
+        assert_eq!(parent_dir("foo.ts"), "");
+    }
+
+    // ── has_extension / path_extension / file_basename ──
+
+    #[test]
+    fn has_extension_detects_ext() {
+        assert!(has_extension("file.ts"));
+        assert!(!has_extension("Makefile"));
+    }
+
+    #[test]
+    fn path_extension_extracts_lowercase() {
+        assert_eq!(path_extension("file.TS"), "ts");
+        assert_eq!(path_extension("file.Rs"), "rs");
+        assert_eq!(path_extension("noext"), "");
+    }
+
+    #[test]
+    fn file_basename_extracts_name() {
+        assert_eq!(file_basename("src/lib/foo.ts"), "foo.ts");
+        assert_eq!(file_basename("README.md"), "readme.md");
+    }
+
+    // ── is_doc_file ──
+
+    #[test]
+    fn is_doc_file_recognizes_doc_extensions() {
+        assert!(is_doc_file("README.md"));
+        assert!(is_doc_file("guide.mdx"));
+        assert!(is_doc_file("notes.txt"));
+        assert!(is_doc_file("spec.rst"));
+        assert!(is_doc_file("help.adoc"));
+    }
+
+    #[test]
+    fn is_doc_file_rejects_code_files() {
+        assert!(!is_doc_file("main.ts"));
+        assert!(!is_doc_file("lib.rs"));
+        assert!(!is_doc_file("config.json"));
+    }
+
+    // ── doc_priority ──
+
+    #[test]
+    fn doc_priority_readme_first() {
+        let (bucket, _) = doc_priority("README.md");
+        assert_eq!(bucket, 0);
+    }
+
+    #[test]
+    fn doc_priority_architecture_docs_second() {
+        for name in &["OVERVIEW.md", "architecture.md", "design.md", "spec.md", "CONTRIBUTING.md"] {
+            let (bucket, _) = doc_priority(name);
+            assert_eq!(bucket, 1, "expected bucket 1 for {}", name);
+        }
+    }
+
+    #[test]
+    fn doc_priority_docs_folder_third() {
+        let (bucket, _) = doc_priority("docs/guide.md");
+        assert_eq!(bucket, 2);
+    }
+
+    #[test]
     fn doc_priority_other_docs_last() {
         let (bucket, _) = doc_priority("random-notes.md");
         assert_eq!(bucket, 3);
     }
 
-    // ── extract_quoted_segments ──
+    // ── extract_quoted_segments ──
+
+    #[test]
+    fn should_extract_closed_quoted_segments() {
+        let line = r#"import foo from "./foo"; const x = require('bar');"#;
+        let parts = extract_quoted_segments(line);
+        assert_eq!(parts, vec!["./foo".to_string(), "bar".to_string()]);
+    }
+
+    #[test]
+    fn should_ignore_unterminated_quoted_segments() {
+        let line = r#"import foo from "./foo"#;
+        let parts = extract_quoted_segments(line);
+        assert!(parts.is_empty());
+    }
+
+    #[test]
+    fn should_handle_escaped_quotes_in_segments() {
+        let line = r#"import foo from "path/with\"quote""#;
+        let parts = extract_quoted_segments(line);
+        assert_eq!(parts.len(), 1);
+        assert!(parts[0].contains("with"));
+    }
+
+    // ── extract_module_specifiers ──
+
+    #[test]
+    fn extract_js_imports() {
+        let content = r#"import { foo } from "./foo";
+import bar from "../bar";
+"#;
+        let specs = extract_module_specifiers(content);
+        assert!(specs.contains(&"./foo".to_string()));
+        assert!(specs.contains(&"../bar".to_string()));
+    }
+
+    #[test]
+    fn extract_python_from_import() {
+        let content = "from foo.bar import baz\n";
+        let specs = extract_module_specifiers(content);
+        assert!(specs.contains(&"foo/bar".to_string()));
+    }
+
+    #[test]
+    fn extract_python_plain_import() {
+        let content = "import os, sys\n";
+        let specs = extract_module_specifiers(content);
+        assert!(specs.contains(&"os".to_string()));
+        assert!(specs.contains(&"sys".to_string()));
+    }
+
+    #[test]
+    fn extract_rust_mod() {
+        let content = "mod utils;\npub mod helpers;\n";
+        let specs = extract_module_specifiers(content);
+        assert!(specs.contains(&"./utils".to_string()));
+        assert!(specs.contains(&"./helpers".to_string()));
+    }
+
+    #[test]
+    fn extract_dynamic_imports() {
+        let content = r#"const Page = React.lazy(() => import("./pages/Page"));
+const Other = lazy(() =>
+  import(
+    './pages/Other'
+  )
+);
+const routes = [{ path: "admin", loadChildren: './admin/admin.module#AdminModule' }];
+"#;
+        let specs = extract_module_specifiers(content);
+        assert!(specs.contains(&"./pages/Page".to_string()));
+        assert!(specs.contains(&"./pages/Other".to_string()));
+        assert!(specs.contains(&"./admin/admin.module".to_string()));
+    }
+
+    #[test]
+    fn extract_skips_comments_and_blanks() {
+        let content = "// import foo from 'bar';\n# comment\n\n";
+        let specs = extract_module_specifiers(content);
+        assert!(specs.is_empty());
+    }
+
+    // ── resolve_module_specifier ──
+
+    #[test]
+    fn resolve_relative_import() {
+        let mut path_to_idx = HashMap::new();
+        path_to_idx.insert("src/lib/utils.ts".to_string(), 0usize);
+        let result = resolve_module_specifier("./utils", "src/lib/foo.ts", &path_to_idx);
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn resolve_at_alias_import() {
+        let mut path_to_idx = HashMap::new();
+        path_to_idx.insert("src/lib/utils.ts".to_string(), 0usize);
+        let result = resolve_module_specifier("@/lib/utils", "src/components/App.tsx", &path_to_idx);
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn resolve_returns_none_for_external_modules() {
+        let path_to_idx = HashMap::new();
+        assert_eq!(resolve_module_specifier("react", "src/App.tsx", &path_to_idx), None);
+    }
+
+    #[test]
+    fn resolve_returns_none_for_http_urls() {
+        let path_to_idx = HashMap::new();
+        assert_eq!(resolve_module_specifier("https://cdn.example.com/lib.js", "src/App.tsx", &path_to_idx), None);
+    }
+
+    #[test]
+    fn resolve_returns_none_for_node_builtins() {
+        let path_to_idx = HashMap::new();
+        assert_eq!(resolve_module_specifier("node:path", "src/App.tsx", &path_to_idx), None);
+    }
+
+    #[test]
+    fn resolve_with_explicit_extension() {
+        let mut path_to_idx = HashMap::new();
+        path_to_idx.insert("src/lib/utils.ts".to_string(), 0usize);
+        let result = resolve_module_specifier("@/lib/utils.ts", "src/App.tsx", &path_to_idx);
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn resolve_tries_index_files() {
+        let mut path_to_idx = HashMap::new();
+        path_to_idx.insert("src/lib/index.ts".to_string(), 0usize);
+        let result = resolve_module_specifier("@/lib", "src/App.tsx", &path_to_idx);
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn candidates_are_empty_for_skipped_specifiers() {
+        assert!(module_specifier_candidates("node:fs", "src/App.tsx").is_empty());
+        assert!(module_specifier_candidates("https://cdn.example.com/lib.js", "src/App.tsx").is_empty());
+    }
+
+    #[test]
+    fn candidates_include_extension_and_index_variants() {
+        let candidates = module_specifier_candidates("./utils", "src/lib/foo.ts");
+        assert!(candidates.contains(&"src/lib/utils".to_string()));
+        assert!(candidates.contains(&"src/lib/utils.ts".to_string()));
+        assert!(candidates.contains(&"src/lib/utils/index.ts".to_string()));
+    }
+
+    // ── diagnose_imports ──
+
+    #[tokio::test]
+    async fn diagnose_imports_reports_only_unresolved_specifiers() {
+        let files = vec![
+            FileContent { path: "src/a.ts".into(), content: "import { b } from \"./b\";\nimport React from \"react\";\n".into(), token_count: None },
+            FileContent { path: "src/b.ts".into(), content: "export const b = 1;\n".into(), token_count: None },
+        ];
+        let diagnostics = diagnose_imports(files).await.unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file_path, "src/a.ts");
+        assert_eq!(diagnostics[0].specifier, "react");
+        assert!(diagnostics[0].candidates.contains(&"react.ts".to_string()));
+    }
+
+    // ── Go module resolution ──
+
+    #[test]
+    fn parse_go_module_path_reads_module_directive() {
+        assert_eq!(
+            parse_go_module_path("module github.com/org/app\n\ngo 1.22\n"),
+            Some("github.com/org/app".to_string())
+        );
+        assert_eq!(parse_go_module_path("go 1.22\n"), None);
+        assert_eq!(parse_go_module_path("modules x\n"), None);
+    }
+
+    #[test]
+    fn extract_go_import_block() {
+        let content = "package main\n\nimport (\n\t\"fmt\"\n\tfoo \"github.com/org/app/internal/foo\"\n)\n";
+        let specs = extract_module_specifiers(content);
+        assert!(specs.contains(&"fmt".to_string()));
+        assert!(specs.contains(&"github.com/org/app/internal/foo".to_string()));
+        assert!(!specs.contains(&"(".to_string()));
+    }
+
+    #[test]
+    fn go_imports_resolve_via_module_path() {
+        let files = vec![
+            FileContent { path: "go.mod".into(), content: "module github.com/org/app\n".into(), token_count: None },
+            FileContent { path: "cmd/main.go".into(), content: "package main\n\nimport (\n\t\"github.com/org/app/internal/foo\"\n)\n".into(), token_count: None },
+            FileContent { path: "internal/foo/foo.go".into(), content: "package foo\n".into(), token_count: None },
+            FileContent { path: "internal/foo/bar.go".into(), content: "package foo\n".into(), token_count: None },
+        ];
+        let (_, edges, indegree) = build_dependency_graph(&files);
+        assert!(edges[2].contains(&1));
+        assert!(edges[3].contains(&1));
+        assert_eq!(indegree[1], 2);
+    }
+
+    // ── format_file_header ──
+
+    fn format_file_header(path: &str, content: &str, format: &str) -> String {
+        let mut out = String::new();
+        push_file_section(&mut out, path, content, format, None);
+        out
+    }
+
+    #[test]
+    fn format_markdown_wraps_in_code_block() {
+        let result = format_file_header("src/main.ts", "const x = 1;", "markdown");
+        assert!(result.starts_with("```typescript"));
+        assert!(result.contains("// src/main.ts"));
+        assert!(result.contains("const x = 1;"));
+        assert!(result.ends_with("```"));
+    }
+
+    #[test]
+    fn format_plaintext_uses_path_comment() {
+        let result = format_file_header("src/main.ts", "const x = 1;", "plaintext");
+        assert!(result.starts_with("// src/main.ts"));
+        assert!(result.contains("const x = 1;"));
+        assert!(!result.contains("```"));
+    }
+
+    #[test]
+    fn format_markdown_maps_extensions_to_languages() {
+        let cases = vec![
+            ("file.rs", "rust"),
+            ("file.py", "python"),
+            ("file.go", "go"),
+            ("file.json", "json"),
+            ("file.md", "markdown"),
+            ("file.css", "css"),
+            ("file.xyz", "text"),
+        ];
+        for (path, expected_lang) in cases {
+            let result = format_file_header(path, "", "markdown");
+            assert!(result.starts_with(&format!("```{expected_lang}")), "expected {expected_lang} for {path}, got: {result}");
+        }
+    }
+
+    // ── split_docs_and_code ──
+
+    #[test]
+    fn split_docs_and_code_separates_correctly() {
+        let files = vec![
+            FileContent { path: "README.md".into(), content: "doc".into(), token_count: None },
+            FileContent { path: "main.ts".into(), content: "code".into(), token_count: None },
+            FileContent { path: "guide.txt".into(), content: "doc".into(), token_count: None },
+        ];
+        let ordered: Vec<usize> = (0..3).collect();
+        let (docs, code) = split_docs_and_code(&ordered, &files);
+
+        assert_eq!(docs.len(), 2);
+        assert_eq!(code.len(), 1);
+        assert!(docs.contains(&0));
+        assert!(docs.contains(&2));
+        assert!(code.contains(&1));
+    }
+
+    #[test]
+    fn split_docs_places_readme_first() {
+        let files = vec![
+            FileContent { path: "guide.md".into(), content: "".into(), token_count: None },
+            FileContent { path: "README.md".into(), content: "".into(), token_count: None },
+        ];
+        let ordered = vec![0, 1];
+        let (docs, _) = split_docs_and_code(&ordered, &files);
+        assert_eq!(docs[0], 1, "README should come first");
+    }
+
+    // ── distribute_files ──
+
+    #[test]
+    fn distribute_single_pack() {
+        let indices = vec![0, 1, 2];
+        let tokens = vec![100, 200, 300];
+        let bins = distribute_files(&indices, 1, &tokens, None);
+        assert_eq!(bins.len(), 1);
+        assert_eq!(bins[0], vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn distribute_empty_input() {
+        let bins = distribute_files(&[], 3, &[], None);
+        assert!(bins.is_empty());
+    }
+
+    #[test]
+    fn distribute_two_equal_packs() {
+        let indices = vec![0, 1, 2, 3];
+        let tokens = vec![100, 100, 100, 100];
+        let bins = distribute_files(&indices, 2, &tokens, None);
+        assert_eq!(bins.len(), 2);
+        let total: usize = bins.iter().map(|b| b.len()).sum();
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn distribute_more_packs_than_files_clamps() {
+        let indices = vec![0, 1];
+        let tokens = vec![200, 100];
+        let bins = distribute_files(&indices, 10, &tokens, None);
+        assert_eq!(bins.len(), 2);
+        assert_eq!(bins[0], vec![0]);
+        assert_eq!(bins[1], vec![1]);
+    }
+
+    #[test]
+    fn distribute_preserves_order() {
+        let indices = vec![0, 1, 2, 3, 4, 5];
+        let tokens = vec![10, 10, 10, 10, 10, 10];
+        let bins = distribute_files(&indices, 3, &tokens, None);
+        let flattened: Vec<usize> = bins.into_iter().flatten().collect();
+        assert_eq!(flattened, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn distribute_to_budget_prefers_near_equal_packs_that_fit() {
+        let bins = distribute_files(&[0, 1, 2, 3], 1, &[50, 50, 50, 50], Some(150));
+        assert_eq!(bins, vec![vec![0, 1], vec![2, 3]]);
+    }
+
+    #[test]
+    fn distribute_to_budget_fills_in_order_when_files_are_uneven() {
+        let tokens = vec![90, 20, 20, 90, 10];
+        assert!(distribute_files(&[0, 1, 2, 3, 4], 2, &tokens, None).len() == 2);
+        let bins = distribute_files(&[0, 1, 2, 3, 4], 2, &tokens, Some(100));
+        assert_eq!(bins, vec![vec![0], vec![1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn distribute_to_budget_isolates_oversized_files() {
+        let bins = distribute_files(&[0, 1, 2], 1, &[10, 500, 10], Some(100));
+        assert_eq!(bins, vec![vec![0], vec![1], vec![2]]);
+    }
+
+    // ── compute_dependency_order ──
+
+    #[test]
+    fn dependency_order_respects_imports() {
+        let files = vec![
+            FileContent { path: "a.ts".into(), content: "import { b } from \"./b\";\n".into(), token_count: None },
+            FileContent { path: "b.ts".into(), content: "export const b = 1;\n".into(), token_count: None },
+        ];
+        let order = compute_dependency_order(&files);
+        let pos_a = order.iter().position(|&i| i == 0).unwrap();
+        let pos_b = order.iter().position(|&i| i == 1).unwrap();
+        assert!(pos_b < pos_a, "b.ts (dependency) should appear before a.ts");
+    }
+
+    #[test]
+    fn dependency_order_links_invoke_calls_to_tauri_commands() {
+        let files = vec![
+            FileContent { path: "src/api.ts".into(), content: "await invoke(\"greet\", { name });\n".into(), token_count: None },
+            FileContent { path: "src/misc.ts".into(), content: "export const x = 1;\n".into(), token_count: None },
+            FileContent { path: "src-tauri/src/lib.rs".into(), content: "#[tauri::command]\nfn greet(name: &str) {}\n".into(), token_count: None },
+        ];
+        let order = compute_dependency_order(&files);
+        let pos_ts = order.iter().position(|&i| i == 0).unwrap();
+        let pos_rs = order.iter().position(|&i| i == 2).unwrap();
+        assert!(pos_rs < pos_ts, "the command handler should appear before its caller");
+
+        let related = build_related_adjacency(&files);
+        assert!(related[0].contains(&2) && related[2].contains(&0));
+        assert!(related[1].is_empty());
+    }
+
+    #[test]
+    fn dependency_order_places_schemas_before_generated_code_and_consumers() {
+        let files = vec![
+            FileContent { path: "web/users.ts".into(), content: "import { User } from \"./gen/user_pb\";\n".into(), token_count: None },
+            FileContent { path: "server/user.pb.go".into(), content: "// source: user.proto\npackage api\n".into(), token_count: None },
+            FileContent { path: "proto/user.proto".into(), content: "syntax = \"proto3\";\n".into(), token_count: None },
+        ];
+        let order = compute_dependency_order(&files);
+        assert_eq!(order[0], 2, "the schema should come before everything generated from it");
+
+        let related = build_related_adjacency(&files);
+        assert_eq!(related[2], HashSet::from([0, 1]));
+    }
+
+    #[test]
+    fn dependency_order_handles_single_file() {
+        let files = vec![
+            FileContent { path: "only.ts".into(), content: "const x = 1;\n".into(), token_count: None },
+        ];
+        let order = compute_dependency_order(&files);
+        assert_eq!(order, vec![0]);
+    }
+
+    #[test]
+    fn dependency_order_handles_empty() {
+        let order = compute_dependency_order(&[]);
+        assert!(order.is_empty());
+    }
+
+    // ── pack strategies ──
+
+    #[test]
+    fn pack_strategy_registry_resolves_names_and_defaults_to_grouped() {
+        assert_eq!(pack_strategy("dependency").name(), "dependency");
+        assert_eq!(pack_strategy("grouped").name(), "grouped");
+        assert_eq!(pack_strategy("no-such-strategy").name(), "grouped");
+        let mut names: Vec<&str> = PACK_STRATEGIES.iter().map(|strategy| strategy.name()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), PACK_STRATEGIES.len(), "names are unique");
+    }
+
+    #[test]
+    fn strategies_run_in_isolation_and_record_their_grouping() {
+        let files = vec![
+            FileContent { path: "src/a.ts".into(), content: "export const a = 1;".into(), token_count: None },
+            FileContent { path: "README.md".into(), content: "# Demo".into(), token_count: None },
+        ];
+        let input = StrategyInput {
+            files: &files,
+            dependency_order: &[1, 0],
+            token_counts: &[10, 10],
+            num_packs: 2,
+            max_tokens_per_pack: None,
+        };
+
+        let mut notes = OrderingNotes::new(input.dependency_order);
+        assert_eq!(DependencyStrategy.distribute(&input, &mut notes), vec![vec![1], vec![0]]);
+        assert!(notes.doc_bucket.is_empty(), "dependency order doesn't bucket docs");
+
+        let mut notes = OrderingNotes::new(input.dependency_order);
+        assert_eq!(GroupedStrategy.distribute(&input, &mut notes), vec![vec![1], vec![0]]);
+        assert_eq!(notes.doc_bucket.get(&1), Some(&0));
+        assert_eq!(notes.component.get(&0), Some(&0));
+    }
+
+    // ── related_components ──
+
+    #[test]
+    fn grouping_keeps_connected_files_adjacent() {
+        let files = vec![
+            FileContent { path: "a.ts".into(), content: "import { b } from \"./b\";\n".into(), token_count: None },
+            FileContent { path: "b.ts".into(), content: "export const b = 1;\n".into(), token_count: None },
+            FileContent { path: "c.ts".into(), content: "const c = 1;\n".into(), token_count: None },
+        ];
+        let order = compute_dependency_order(&files);
+        let related = build_related_adjacency(&files);
+        let grouped = related_components(&order, &[], &related).concat();
+        assert_eq!(grouped.len(), 3);
+
+        let pos_a = grouped.iter().position(|&i| i == 0).unwrap();
+        let pos_b = grouped.iter().position(|&i| i == 1).unwrap();
+        let distance = if pos_a > pos_b { pos_a - pos_b } else { pos_b - pos_a };
+        assert_eq!(distance, 1, "a and b should be adjacent since they're connected");
+    }
+
+    #[test]
+    fn grouping_leads_with_components_the_docs_import() {
+        let files = vec![
+            FileContent { path: "README.md".into(), content: "Intro: import x from './nope'\n\n```py\nfrom mypkg.train import fit\n```\n".into(), token_count: None },
+            FileContent { path: "a.ts".into(), content: "export const a = 1;\n".into(), token_count: None },
+            FileContent { path: "mypkg/data.py".into(), content: "X = 1\n".into(), token_count: None },
+            FileContent { path: "mypkg/train.py".into(), content: "from mypkg.data import X\n".into(), token_count: None },
+        ];
+        let order = compute_dependency_order(&files);
+        let related = build_related_adjacency(&files);
+        let (docs, code) = split_docs_and_code(&order, &files);
+        let lead = doc_referenced_code(&docs, &files, &related);
+        assert_eq!(lead, vec![3]);
+        let grouped = related_components(&code, &lead, &related).concat();
+        assert_eq!(grouped, vec![2, 3, 1], "train.py's component leads, dependency first");
+    }
+
+    #[test]
+    fn grouping_follows_build_targets() {
+        let cmake = "add_library(log lib/log.c lib/log.h)\nadd_executable(cli bin/cli.c)\n\
+            target_link_libraries(cli log)\nadd_executable(app app/main.c)\n";
+        let files = vec![
+            FileContent { path: "CMakeLists.txt".into(), content: cmake.into(), token_count: None },
+            FileContent { path: "app/main.c".into(), content: "int main(void) { return 0; }\n".into(), token_count: None },
+            FileContent { path: "bin/cli.c".into(), content: "#include <log.h>\n".into(), token_count: None },
+            FileContent { path: "lib/log.c".into(), content: "void log_line(void) {}\n".into(), token_count: None },
+            FileContent { path: "lib/log.h".into(), content: "void log_line(void);\n".into(), token_count: None },
+        ];
+        let order = compute_dependency_order(&files);
+        assert_eq!(order, vec![0, 1, 3, 4, 2], "cli links log, so log's files come first");
+        let related = build_related_adjacency(&files);
+        assert!(related[3].contains(&4));
+        assert!(related[2].is_empty() && related[0].is_empty());
+        let (_, code) = split_docs_and_code(&order, &files);
+        let components = related_components(&code, &[], &related);
+        assert_eq!(components, vec![vec![1], vec![3, 4], vec![2]]);
+    }
+
+    // ── embedded code ──
+
+    #[test]
+    fn markdown_contributes_only_fenced_code() {
+        let doc = "Run `import a from './prose'` first.\n\n```ts\nimport { b } from \"./src/b\";\n```\n\n~~~~python\n```\nfrom pkg.c import c\n~~~~\n";
+        let file = FileContent { path: "docs/guide.md".into(), content: doc.into(), token_count: None };
+        let mut specs = file_module_specifiers(&file);
+        specs.sort();
+        assert_eq!(specs, vec!["./src/b".to_string(), "pkg/c".to_string()]);
+    }
+
+    #[test]
+    fn notebooks_contribute_their_code_cells() {
+        let notebook = r#"{"cells": [
+            {"cell_type": "markdown", "source": ["from prose import nothing\n"]},
+            {"cell_type": "code", "source": ["import numpy as np\n", "from lib.model import Net\n"]},
+            {"cell_type": "code", "source": "from lib.data import load"}
+        ]}"#;
+        let file = FileContent { path: "tutorial.ipynb".into(), content: notebook.into(), token_count: None };
+        let mut specs = file_module_specifiers(&file);
+        specs.sort();
+        assert_eq!(specs, vec!["lib/data".to_string(), "lib/model".to_string(), "numpy".to_string()]);
+
+        let broken = FileContent { path: "broken.ipynb".into(), content: "{".into(), token_count: None };
+        assert!(file_module_specifiers(&broken).is_empty());
+    }
+
+    // ── who_depends_on ──
+
+    #[test]
+    fn transitive_dependents_follows_the_reversed_import_graph() {
+        let files = vec![
+            FileContent { path: "a.ts".into(), content: "import { b } from \"./b\";\n".into(), token_count: None },
+            FileContent { path: "b.ts".into(), content: "import { c } from \"./c\";\n".into(), token_count: None },
+            FileContent { path: "c.ts".into(), content: "export const c = 1;\n".into(), token_count: None },
+            FileContent { path: "d.ts".into(), content: "const d = 1;\n".into(), token_count: None },
+        ];
+        let dependents = transitive_dependents("c.ts", &files).unwrap();
+        assert_eq!(dependents, vec!["a.ts".to_string(), "b.ts".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn who_depends_on_walks_the_project_and_returns_absolute_paths() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-who-depends-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.ts"), "import { b } from \"./b\";\n").unwrap();
+        std::fs::write(dir.join("src/b.ts"), "import { c } from \"./c\";\n").unwrap();
+        std::fs::write(dir.join("src/c.ts"), "export const c = 1;\n").unwrap();
+        std::fs::write(dir.join("src/d.ts"), "const d = 1;\n").unwrap();
+        let root = dir.to_string_lossy().to_string();
+        let target = dir.join("src/c.ts").to_string_lossy().to_string();
+        assert!(who_depends_on(root.clone(), target.clone()).await.is_err(), "unopened roots are refused");
+        crate::commands::fs::walk_directory_flat(root.clone(), true, Vec::new(), None, None).await.unwrap();
+
+        let dependents = who_depends_on(root.clone(), target).await.unwrap();
+        let names: Vec<&str> = dependents.iter().map(|path| path.rsplit(['/', '\\']).next().unwrap()).collect();
+        assert_eq!(names, vec!["a.ts", "b.ts"]);
+        assert!(dependents.iter().all(|path| Path::new(path).is_absolute()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn transitive_dependents_rejects_unknown_path() {
+        let files = vec![
+            FileContent { path: "a.ts".into(), content: "const a = 1;\n".into(), token_count: None },
+        ];
+        assert!(transitive_dependents("missing.ts", &files).is_err());
+    }
+
+    // ── pack assembly ──
+
+    #[tokio::test]
+    async fn pack_files_joins_sections_without_reallocating() {
+        let request = PackRequest {
+            files: vec![
+                FileContent { path: "a.ts".into(), content: "const a = 1;".into(), token_count: None },
+                FileContent { path: "b.ts".into(), content: "const b = 2;".into(), token_count: None },
+            ],
+            output_format: "markdown".into(),
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        let content = &response.packs[0].content;
+        assert!(content.starts_with("```typescript\n// "));
+        assert_eq!(content.matches("\n\n```typescript").count(), 1, "one separator between two files");
+        assert!(content.capacity() >= content.len());
+        assert!(content.capacity() - content.len() <= 2 * SECTION_OVERHEAD);
+    }
+
+    #[tokio::test]
+    async fn pack_files_uses_custom_separator_and_file_markers() {
+        let request = PackRequest {
+            files: vec![
+                FileContent { path: "a.ts".into(), content: "const a = 1;".into(), token_count: None },
+                FileContent { path: "b.ts".into(), content: "const b = 2;".into(), token_count: None },
+            ],
+            boundaries: Some(SectionBoundaries {
+                separator: Some("\n\n---\n\n".into()),
+                file_start: Some(String::new()),
+                file_end: Some("<<<FILE_END {path}>>>".into()),
+            }),
+            excluded_paths: vec!["dist/app.js".into()],
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        let content = &response.packs[0].content;
+        assert!(content.starts_with("// a.ts\nconst a = 1;\n<<<FILE_END a.ts>>>\n\n---\n\n// b.ts\n"));
+        assert!(content.contains("const b = 2;\n<<<FILE_END b.ts>>>\n\n---\n\n"));
+        assert_eq!(content.matches("<<<FILE_END").count(), 3, "appendices are marked too");
+    }
+
+    #[tokio::test]
+    async fn pack_files_ids_sections_and_indexes_them_per_pack() {
+        let files = || {
+            vec![
+                FileContent { path: "src/b.ts".into(), content: "const b = 2;".into(), token_count: None },
+                FileContent { path: "src/a.ts".into(), content: "const a = 1;".into(), token_count: None },
+            ]
+        };
+        let mut request = plain_request(files(), 1, None, "dependency");
+        request.file_ids = true;
+        request.excluded_paths = vec!["dist/app.js".into()];
+        let content = &pack_files(request).await.unwrap().packs[0].content;
+        assert!(content.starts_with("Files in this pack:\n[F001] src/a.ts\n[F002] src/b.ts\n\n"), "{content}");
+        assert!(content.contains("// [F001] src/a.ts\nconst a = 1;"));
+        assert!(content.contains("// [F002] src/b.ts\nconst b = 2;"));
+        assert!(!content.contains("[F003]"), "appendices get no ID");
+
+        let mut request = plain_request(files(), 1, None, "dependency");
+        request.file_ids = true;
+        request.output_format = "xml".into();
+        let content = &pack_files(request).await.unwrap().packs[0].content;
+        assert!(content.contains("<file_index>\n<entry id=\"F001\" path=\"src/a.ts\"/>\n"));
+        assert!(content.contains("<file id=\"F002\" path=\"src/b.ts\">"));
+
+        let mut request = plain_request(files(), 1, None, "dependency");
+        request.file_ids = true;
+        request.output_format = "json".into();
+        let content = &pack_files(request).await.unwrap().packs[0].content;
+        let parsed: serde_json::Value = serde_json::from_str(content).unwrap();
+        assert_eq!(parsed["fileIndex"]["F002"], "src/b.ts");
+        let ids: Vec<&str> = parsed["files"].as_array().unwrap().iter().map(|f| f["id"].as_str().unwrap()).collect();
+        assert!(ids.contains(&"F001") && ids.contains(&"F002"));
+    }
+
+    #[tokio::test]
+    async fn pack_files_renders_each_file_through_a_custom_template() {
+        let files = || {
+            vec![
+                FileContent { path: "src/a.ts".into(), content: "const a = 1;".into(), token_count: Some(4) },
+                FileContent { path: "README.md".into(), content: "# Demo".into(), token_count: Some(2) },
+            ]
+        };
+        let mut request = plain_request(files(), 1, None, "grouped");
+        request.excluded_paths = vec!["dist/app.js".into()];
+        request.template = Some(
+            "Tree:\n{{source_tree}}\n{{#each files}}`{{path}}` ({{tokens}}):\n{{code}}\n{{#unless @last}}---\n{{/unless}}{{/each}}End {{pack_index}}/{{total_packs}}\n".into(),
+        );
+        let content = &pack_files(request).await.unwrap().packs[0].content;
+        assert!(content.starts_with("Tree:\nREADME.md\nsrc/\n  a.ts\n\n"), "{content}");
+        assert!(content.contains("`src/a.ts` (4):\n```typescript\nconst a = 1;\n```\n---\n"), "{content}");
+        assert!(content.contains("`EXCLUDED_FILES.md`"), "appendices go through the template too");
+        assert!(content.ends_with("```\nEnd 1/1\n"), "{content}");
+        assert!(!content.contains("// src/a.ts"), "the template replaces the default headers");
+
+        let mut request = plain_request(files(), 1, None, "grouped");
+        request.template = Some("{{#each files}}{{path}}".into());
+        assert!(pack_files(request).await.unwrap_err().contains("Unclosed"));
+    }
+
+    #[tokio::test]
+    async fn language_associations_pick_fences_and_grammars() {
+        use crate::commands::languages::set_language_associations;
+        set_language_associations(HashMap::from([
+            (".mjsx".into(), "javascript".into()),
+            ("Justfile".into(), "make".into()),
+        ]))
+        .await
+        .unwrap();
+        let files = vec![
+            FileContent { path: "src/view.mjsx".into(), content: "// note\nconst v = 1;".into(), token_count: None },
+            FileContent { path: "Justfile".into(), content: "build:\n\tcargo build".into(), token_count: None },
+        ];
+        let mut request = plain_request(files, 1, None, "grouped");
+        request.output_format = "markdown".into();
+        request.strip_comments = true;
+        let content = pack_files(request).await.unwrap().packs.remove(0).content;
+        set_language_associations(HashMap::new()).await.unwrap();
+
+        assert!(content.contains("```javascript\n// src/view.mjsx\n"), "{content}");
+        assert!(!content.contains("// note"), "the javascript grammar strips the comment");
+        assert!(content.contains("```make\n// Justfile\n"), "{content}");
+    }
+
+    #[tokio::test]
+    async fn pack_files_explains_each_placement() {
+        let file = |path: &str, content: &str, tokens: usize| FileContent {
+            path: path.into(),
+            content: content.into(),
+            token_count: Some(tokens),
+        };
+        let request = PackRequest {
+            files: vec![
+                file("src/util.ts", "export const u = 1;", 100),
+                file("src/app.ts", "import { u } from './util';", 100),
+                file("src/other.ts", "export const o = 2;", 100),
+                file("README.md", "```ts\nimport { o } from './src/other';\n```", 100),
+            ],
+            num_packs: 2,
+            max_tokens_per_pack: Some(100),
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        let placed: Vec<&str> = response.placements.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(placed, vec!["README.md", "src/other.ts", "src/util.ts", "src/app.ts"]);
+
+        let readme = &response.placements[0];
+        assert_eq!((readme.pack_index, readme.doc_bucket, readme.component), (0, Some(0), None));
+        assert!(readme.reason.starts_with("Doc packed ahead of code, READMEs first"));
+
+        let other = &response.placements[1];
+        assert!(other.pinned && other.component == Some(0));
+        assert!(!other.spillover, "second of two requested packs");
+        assert!(other.reason.contains("because a doc example imports this file"));
+
+        let app = &response.placements[3];
+        assert_eq!((app.component, app.position), (Some(1), 0));
+        assert!(!app.pinned && app.spillover);
+        assert!(app.reason.ends_with("; in an extra pack added to stay under the token budget."));
+        let dependency_rank = response.placements[2].dependency_rank;
+        assert!(dependency_rank < app.dependency_rank, "imports come before their importers");
+    }
+
+    #[tokio::test]
+    async fn pack_files_counts_tokens_with_the_profile_tokenizer() {
+        let source = "import { b } from './b';\nexport const a = b + 1;\n";
+        let file = |token_count| FileContent { path: "a.ts".into(), content: source.into(), token_count };
+        let mut request = plain_request(vec![file(None)], 1, None, "grouped");
+        request.llm_profile_id = Some("o200k".into());
+        let bpe = tiktoken_rs::o200k_base_singleton();
+        let exact = bpe.encode_ordinary(source).len();
+        assert_eq!(pack_files(request).await.unwrap().total_tokens, exact);
+
+        // Transformed text is recounted instead of scaled from the frontend's count.
+        let mut request = plain_request(vec![file(Some(1_000))], 1, None, "grouped");
+        request.llm_profile_id = Some("o200k".into());
+        request.strip_imports = true;
+        let stripped = strip_import_statements("a.ts", source);
+        assert_eq!(pack_files(request).await.unwrap().total_tokens, bpe.encode_ordinary(&stripped).len());
+    }
+
+    #[tokio::test]
+    async fn pack_files_sizes_packs_to_the_llm_profile_window() {
+        let file = |path: &str, tokens| FileContent {
+            path: path.into(),
+            content: format!("export const {} = 1;\n", &path[..1]),
+            token_count: Some(tokens),
+        };
+        let files = vec![file("a.ts", 5_000), file("b.ts", 5_000), file("c.ts", 9_000)];
+        let mut request = plain_request(files, 1, None, "grouped");
+        request.llm_profile_id = Some("local-llama-3-1-8b".into());
+        let response = pack_files(request).await.unwrap();
+        assert!(response.packs.len() >= 2, "an 8k window cannot hold two 5k files");
+        let oversized: Vec<_> = response.warnings.iter().filter(|w| w.contains("context window")).collect();
+        assert_eq!(oversized.len(), 1);
+        assert!(oversized[0].starts_with("c.ts (9000 tokens)"));
+        assert!(oversized[0].contains("Llama 3.1 8B (local)"));
+        let truncated: Vec<_> = response.packs.iter().flat_map(|pack| &pack.truncations).collect();
+        assert_eq!(truncated.len(), 1);
+        assert_eq!(truncated[0].path, "c.ts");
+    }
+
+    #[tokio::test]
+    async fn packs_sized_to_the_llm_profile_window_fit_in_it_with_their_framing() {
+        let files: Vec<FileContent> = (0..200)
+            .map(|i| FileContent {
+                path: format!("src/components/widget_{i}.tsx"),
+                content: (0..6).map(|line| format!("export const value{i}_{line} = compute({line}, '{i}');\n")).collect(),
+                token_count: None,
+            })
+            .collect();
+        let mut request = plain_request(files, 1, None, "grouped");
+        request.output_format = "markdown".into();
+        request.llm_profile_id = Some("local-llama-3-1-8b".into());
+        let counter = TokenCounter::for_profile(Some("local-llama-3-1-8b"));
+        let response = pack_files(request).await.unwrap();
+        assert!(response.packs.len() >= 2);
+        for pack in &response.packs {
+            let framed = counter.count("pack.md", &pack.content);
+            assert!(framed <= 8_192, "pack {} spends {framed} tokens of an 8192-token window", pack.index);
+        }
+    }
+
+    // ── focus files ──
+
+    #[tokio::test]
+    async fn focus_files_are_packed_whole_under_a_banner() {
+        let body: String = (0..400).map(|i| format!("import {{ v{i} }} from './v{i}';  // value {i}\n")).collect();
+        let files = vec![
+            FileContent { path: "src/focus.ts".into(), content: body.clone(), token_count: None },
+            FileContent { path: "src/other.ts".into(), content: body.clone(), token_count: None },
+        ];
+        let mut request = plain_request(files, 1, Some(500), "grouped");
+        request.strip_comments = true;
+        request.strip_imports = true;
+        request.focus_paths = vec!["./src/focus.ts".into(), "src/missing.ts".into()];
+        let response = pack_files(request).await.unwrap();
+
+        let pack_of = |path: &str| response.packs.iter().find(|pack| pack.file_paths.iter().any(|p| p == path)).unwrap();
+        let focus = pack_of("src/focus.ts");
+        assert!(focus.content.contains(FOCUS_BANNER));
+        assert!(focus.content.contains(&body));
+        assert!(focus.truncations.is_empty());
+        let other = pack_of("src/other.ts");
+        assert!(!other.content.contains(FOCUS_BANNER));
+        assert!(!other.content.contains("import { v0 }"));
+        assert!(response.warnings.iter().any(|w| w.starts_with("Focus file src/focus.ts (")));
+        assert!(response.warnings.iter().any(|w| w == "Focus path(s) not in the selection: src/missing.ts."));
+    }
+
+    // ── truncation ──
+
+    #[tokio::test]
+    async fn files_over_the_pack_budget_keep_their_head_and_tail() {
+        let body: String = (0..400).map(|i| format!("export const value{i} = {i};\n")).collect();
+        let files = vec![
+            FileContent { path: "big.ts".into(), content: body.clone(), token_count: None },
+            FileContent { path: "small.ts".into(), content: "export const s = 1;\n".into(), token_count: None },
+        ];
+        let response = pack_files(plain_request(files, 1, Some(500), "grouped")).await.unwrap();
+
+        let pack = response.packs.iter().find(|pack| pack.file_paths.contains(&"big.ts".to_string())).unwrap();
+        assert!(pack.content.contains("export const value0 = 0;"));
+        assert!(pack.content.contains("export const value399 = 399;"));
+        assert!(!pack.content.contains("export const value200 = 200;"));
+        assert!(pack.content.contains("line(s) omitted to fit the 500-token pack budget] …"));
+        assert_eq!(pack.truncations.len(), 1);
+        let truncation = &pack.truncations[0];
+        assert_eq!(truncation.path, "big.ts");
+        assert_eq!(truncation.original_tokens, estimate_tokens_for_path("big.ts", &body));
+        assert!(truncation.kept_tokens <= 500);
+        assert!(truncation.omitted_lines > 200);
+        assert!(response.warnings.iter().any(|w| w.starts_with("Truncated 1 file(s)")));
+    }
+
+    #[tokio::test]
+    async fn truncation_keeps_shrinking_until_a_file_denser_at_its_ends_fits() {
+        // Symbol-dense lines indented ever deeper toward the middle: the ends the cut keeps
+        // cost far more tokens per byte than the file does on average.
+        let body: String = (0..160usize)
+            .map(|i| format!("{}%!@#$^&*~`|<>?;:{{}}[]()\n", " ".repeat(i.min(159 - i) * 12)))
+            .collect();
+        let mut request = plain_request(
+            vec![FileContent { path: "dense.ts".into(), content: body.clone(), token_count: None }],
+            1,
+            Some(200),
+            "grouped",
+        );
+        request.llm_profile_id = Some("gpt-4o".into());
+        let response = pack_files(request).await.unwrap();
+
+        let counter = TokenCounter::for_profile(Some("gpt-4o"));
+        let first_guess = body.len() * 200 / counter.count("dense.ts", &body);
+        let (guess, _) = truncate_middle(&body, first_guess, 200).unwrap();
+        assert!(counter.count("dense.ts", &guess) > 200, "the byte-ratio estimate alone overshoots");
+        let truncation = &response.packs[0].truncations[0];
+        assert!(truncation.kept_tokens <= 200, "{truncation:?}");
+        assert!(response.packs[0].estimated_tokens <= 200);
+        assert!(!response.warnings.iter().any(|w| w.starts_with("Could not cut")));
+    }
+
+    #[tokio::test]
+    async fn files_that_cannot_be_cut_to_the_budget_are_reported() {
+        let body: String = (0..200).map(|i| format!("export const value{i} = {i};\n")).collect();
+        let files = vec![FileContent { path: "big.ts".into(), content: body, token_count: None }];
+        let response = pack_files(plain_request(files, 1, Some(5), "grouped")).await.unwrap();
+        assert!(response.packs[0].truncations.is_empty());
+        assert!(response
+            .warnings
+            .iter()
+            .any(|w| w == "Could not cut 1 file(s) to the 5-token pack budget, so they are packed over it: big.ts."));
+    }
+
+    // ── priorities ──
+
+    #[tokio::test]
+    async fn priority_rules_lead_with_high_files_and_hold_low_ones_back() {
+        let file = |path: &str, tokens| FileContent {
+            path: path.into(),
+            content: format!("export const x{tokens} = {tokens};\n"),
+            token_count: Some(tokens),
+        };
+        let files = || vec![file("tests/a.test.ts", 40), file("src/a.ts", 30), file("src/core/b.ts", 20), file("src/c.ts", 10)];
+        let rules = vec![
+            PriorityRule { pattern: "src/core/**".into(), weight: "high".into() },
+            PriorityRule { pattern: "tests/**".into(), weight: "Low".into() },
+        ];
+
+        let mut request = plain_request(files(), 2, None, "dependency");
+        request.priorities = rules.clone();
+        let response = pack_files(request).await.unwrap();
+        let order: Vec<&str> = response.packs.iter().flat_map(|pack| pack.file_paths.iter().map(String::as_str)).collect();
+        assert_eq!(order, vec!["src/core/b.ts", "src/a.ts", "src/c.ts", "tests/a.test.ts"]);
+        assert_eq!(response.packs.last().unwrap().file_paths.last().unwrap(), "tests/a.test.ts");
+        let low = response.placements.iter().find(|p| p.path == "tests/a.test.ts").unwrap();
+        assert_eq!(low.priority.as_deref(), Some("low"));
+        assert!(low.reason.contains("held for the last pack by a low priority rule"));
+
+        // Over budget, the low file is dropped instead of adding a pack, and listed as excluded.
+        let mut request = plain_request(files(), 1, Some(70), "dependency");
+        request.priorities = rules.clone();
+        let response = pack_files(request).await.unwrap();
+        assert_eq!(response.packs.len(), 1);
+        assert_eq!(response.packs[0].file_paths, vec!["src/core/b.ts", "src/a.ts", "src/c.ts"]);
+        assert!(response.packs[0].content.contains(EXCLUDED_FILES_PATH) && response.packs[0].content.contains("a.test.ts"));
+        assert!(response.warnings.iter().any(|w| w.starts_with("Dropped 1 low-priority file(s)")));
+
+        let mut request = plain_request(files(), 1, None, "dependency");
+        request.priorities = vec![PriorityRule { pattern: "**".into(), weight: "urgent".into() }];
+        assert!(pack_files(request).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn low_priority_files_fill_the_packs_the_budget_grew_to() {
+        let file = |path: &str, tokens| FileContent {
+            path: path.into(),
+            content: format!("export const x{tokens} = {tokens};\n"),
+            token_count: Some(tokens),
+        };
+        let files = vec![
+            file("src/a.ts", 60),
+            file("src/b.ts", 61),
+            file("src/c.ts", 62),
+            file("tests/fits.test.ts", 30),
+            file("tests/dropped.test.ts", 50),
+        ];
+        let mut request = plain_request(files, 1, Some(100), "dependency");
+        request.priorities = vec![PriorityRule { pattern: "tests/**".into(), weight: "low".into() }];
+        let response = pack_files(request).await.unwrap();
+
+        assert_eq!(response.packs.len(), 3);
+        assert_eq!(response.packs[2].file_paths.last().unwrap(), "tests/fits.test.ts");
+        assert!(response
+            .warnings
+            .iter()
+            .any(|w| w == "Dropped 1 low-priority file(s) that did not fit 3 pack(s) of at most 100 tokens."));
+    }
+
+    #[tokio::test]
+    async fn low_priority_files_pack_as_outlines() {
+        let body = "export function load(id: string) {\n  // fetch it\n  return fetch(`/items/${id}`);\n}\n";
+        let docs = format!("/** Loads an item. */\n{body}");
+        let files = vec![
+            FileContent { path: "src/core/load.ts".into(), content: body.into(), token_count: None },
+            FileContent { path: "src/legacy/load.ts".into(), content: docs, token_count: None },
+        ];
+        let mut request = plain_request(files, 1, None, "dependency");
+        request.strip_comments = true;
+        request.outline_low_priority = true;
+        request.priorities = vec![PriorityRule { pattern: "src/legacy/**".into(), weight: "low".into() }];
+        let response = pack_files(request).await.unwrap();
+        let content = &response.packs[0].content;
+        assert!(content.contains("return fetch"), "normal files keep their bodies");
+        assert!(!content.contains("// fetch it"));
+        assert!(content.contains("/** Loads an item. */\nexport function load(id: string) { ... }"));
+    }
+
+    // ── pack notes ──
+
+    #[tokio::test]
+    async fn pack_notes_open_each_pack_with_its_directories_languages_and_symbols() {
+        let files = || vec![
+            FileContent { path: "src/ui/app.ts".into(), content: "export function mount() {}\nfunction helper() {}\n".into(), token_count: Some(7) },
+            FileContent { path: "src/lib.rs".into(), content: "pub fn pack() {}\nfn private() {}\npub struct Pack;\n".into(), token_count: Some(5) },
+            FileContent { path: "README.md".into(), content: "# Demo\n".into(), token_count: Some(2) },
+        ];
+        let mut request = plain_request(files(), 1, None, "dependency");
+        request.pack_notes = true;
+        let response = pack_files(request).await.unwrap();
+        let content = &response.packs[0].content;
+        assert!(content.starts_with("Pack overview: 3 file(s), ~14 tokens.\n"));
+        assert!(content.contains("\nDirectories: src/ (1), src/ui/ (1), top-level files (1)\n"));
+        assert!(content.contains("\nLanguages: markdown (1), rust (1), typescript (1)\n"));
+        let symbols = content.lines().find(|line| line.starts_with("Key symbols: ")).unwrap();
+        assert!(symbols.contains("mount") && symbols.contains("pack") && symbols.contains("Pack"));
+        assert!(!symbols.contains("helper") && !symbols.contains("private"));
+
+        let mut request = plain_request(files(), 1, None, "dependency");
+        request.pack_notes = true;
+        request.output_format = "xml".into();
+        let response = pack_files(request).await.unwrap();
+        assert!(response.packs[0].content.contains("<pack_note>Pack overview: 3 file(s)"));
+    }
+
+    // ── parse warnings ──
+
+    #[tokio::test]
+    async fn pack_files_warns_about_unparseable_sources() {
+        let request = PackRequest {
+            files: vec![
+                FileContent { path: "ok.ts".into(), content: "export const a = 1;\n".into(), token_count: None },
+                FileContent { path: "broken.ts".into(), content: "const a = 1;\nfunction (\n".into(), token_count: None },
+                FileContent { path: "big.part-1-of-2.ts".into(), content: "function f() {\n".into(), token_count: None },
+                FileContent { path: "notes.md".into(), content: "# {{ not code\n".into(), token_count: None },
+            ],
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        assert_eq!(response.warnings.len(), 1, "{:?}", response.warnings);
+        assert!(response.warnings[0].starts_with("broken.ts: "));
+        assert!(response.warnings[0].contains("line 2"));
+    }
+
+    // ── import stripping ──
+
+    #[test]
+    fn strip_import_statements_removes_multiline_ts_imports() {
+        let source = "import React from \"react\";\nimport {\n  a,\n  b,\n} from \"./ab\";\n\nexport { c } from \"./c\";\nconst lazy = import(\"./lazy\");\n";
+        assert_eq!(
+            strip_import_statements("app.ts", source),
+            "export { c } from \"./c\";\nconst lazy = import(\"./lazy\");\n"
+        );
+    }
+
+    #[test]
+    fn strip_import_statements_handles_rust_python_and_go() {
+        let rust = "use std::collections::{\n    HashMap,\n};\npub use crate::models::Pack;\n\nfn main() {\n    use std::fmt;\n}\n";
+        assert_eq!(
+            strip_import_statements("main.rs", rust),
+            "pub use crate::models::Pack;\n\nfn main() {\n    use std::fmt;\n}\n"
+        );
+
+        let python = "import os, sys\nfrom .models import (\n    User,\n)\nfrom a import \\\n    b\n\ndef run():\n    import json\n";
+        assert_eq!(strip_import_statements("run.py", python), "def run():\n    import json\n");
+
+        let go = "package main\n\nimport \"fmt\"\nimport (\n\t\"os\"\n)\n\nfunc main() {}\n";
+        assert_eq!(strip_import_statements("main.go", go), "package main\n\nfunc main() {}\n");
+    }
+
+    #[test]
+    fn strip_import_statements_leaves_other_files_untouched() {
+        let content = "import this\n";
+        assert!(matches!(strip_import_statements("notes.md", content), Cow::Borrowed(_)));
+        assert!(matches!(strip_import_statements("a.ts", "const a = 1;\n"), Cow::Borrowed(_)));
+    }
+
+    #[tokio::test]
+    async fn pack_files_strips_imports_but_keeps_dependency_order() {
+        let request = PackRequest {
+            files: vec![
+                FileContent { path: "a.ts".into(), content: "import { b } from \"./b\";\nexport const a = b;\n".into(), token_count: Some(20) },
+                FileContent { path: "b.ts".into(), content: "export const b = 1;\n".into(), token_count: None },
+            ],
+            strip_imports: true,
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        let content = &response.packs[0].content;
+        assert!(!content.contains("import"));
+        assert!(content.find("b.ts").unwrap() < content.find("a.ts").unwrap());
+        assert_eq!(response.packs[0].file_paths, vec!["b.ts".to_string(), "a.ts".to_string()]);
+        assert!(response.total_tokens < 20 + estimate_tokens_for_path("b.ts", "export const b = 1;\n"));
+    }
+
+    #[tokio::test]
+    async fn pack_files_strips_comments_from_parsed_files_only() {
+        let files = vec![
+            FileContent { path: "a.ts".into(), content: "// Explains a.\nexport const a = 1; // one\n".into(), token_count: None },
+            FileContent { path: "notes.txt".into(), content: "// kept\n".into(), token_count: None },
+        ];
+        let mut request = plain_request(files, 1, None, "dependency");
+        request.strip_comments = true;
+        let response = pack_files(request).await.unwrap();
+        let content = &response.packs[0].content;
+        assert!(content.contains("export const a = 1;\n"));
+        assert!(!content.contains("Explains") && !content.contains("one"));
+        assert!(content.contains("// kept"));
+    }
+
+    // ── content normalization ──
+
+    #[test]
+    fn normalize_content_unifies_line_endings_boms_and_trailing_whitespace() {
+        let windows = "\u{feff}fn main() {  \r\n\tlet a = 1;\t\r\n}\r\n";
+        let unix = "fn main() {\n\tlet a = 1;\n}\n";
+        assert_eq!(normalize_content(windows), unix);
+        assert_eq!(normalize_content("a\rb\u{feff}c"), "a\nbc");
+        assert!(matches!(normalize_content(unix), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn compress_whitespace_collapses_blank_runs_and_strips_indentation_where_safe() {
+        let source = "fn main() {  \n\n\n    let a = 1;\n\t\n\n    a\n}\n";
+        assert_eq!(compress_whitespace("main.rs", source, false), "fn main() {\n\n    let a = 1;\n\n    a\n}\n");
+        assert_eq!(compress_whitespace("main.rs", source, true), "fn main() {\n\nlet a = 1;\n\na\n}\n");
+        let python = "def f():\n\n\n    return 1\n";
+        assert_eq!(compress_whitespace("f.py", python, true), "def f():\n\n    return 1\n");
+        assert!(matches!(compress_whitespace("a.ts", "const a = 1;\n", true), Cow::Borrowed(_)));
+    }
+
+    #[tokio::test]
+    async fn pack_files_reports_tokens_saved_by_whitespace_compression() {
+        let files = || {
+            let indented = format!("function f() {{\n{}}}\n", "        call();\n\n\n".repeat(40));
+            vec![
+                FileContent { path: "a.ts".into(), content: indented, token_count: None },
+                FileContent { path: "b.ts".into(), content: "const b = 1;\n".into(), token_count: None },
+            ]
+        };
+        let plain = pack_files(plain_request(files(), 2, None, "dependency")).await.unwrap();
+        assert!(plain.packs.iter().all(|pack| pack.saved_tokens.is_none()));
+
+        let mut request = plain_request(files(), 2, None, "dependency");
+        request.compress_whitespace = Some(crate::models::WhitespaceCompression { strip_indentation: true });
+        let compressed = pack_files(request).await.unwrap();
+        let saved: Vec<usize> = compressed.packs.iter().map(|pack| pack.saved_tokens.unwrap()).collect();
+        assert_eq!(saved, vec![saved[0], 0]);
+        assert!(saved[0] > 0);
+        assert_eq!(compressed.total_tokens + saved[0], plain.total_tokens);
+    }
+
+    #[tokio::test]
+    async fn pack_files_normalizes_content_when_requested() {
+        let request = |normalize_content| PackRequest {
+            files: vec![FileContent { path: "a.ts".into(), content: "\u{feff}import { b } from \"./b\";  \r\nconst a = 1; \r\n".into(), token_count: None }],
+            strip_imports: true,
+            normalize_content,
+            ..Default::default()
+        };
+        let normalized = pack_files(request(true)).await.unwrap();
+        assert!(normalized.packs[0].content.ends_with("\nconst a = 1;\n"));
+        assert!(!normalized.packs[0].content.contains(['\r', '\u{feff}']));
+
+        let raw = pack_files(request(false)).await.unwrap();
+        assert!(raw.packs[0].content.contains("const a = 1; \r\n"));
+    }
+
+    // ── pack preview ──
+
+    fn stored_pack(sections: &[&str]) -> StoredPack {
+        let mut content = String::new();
+        let mut ranges = Vec::new();
+        for (i, section) in sections.iter().enumerate() {
+            if i > 0 {
+                content.push_str(SECTION_SEPARATOR);
+            }
+            let start = content.len();
+            content.push_str(section);
+            ranges.push(start..content.len());
+        }
+        StoredPack { content, sections: ranges }
+    }
+
+    #[test]
+    fn build_pack_preview_returns_small_packs_whole() {
+        let pack = stored_pack(&["// a.ts\nconst a = 1;"]);
+        let preview = build_pack_preview(&pack, 1_000);
+        assert!(!preview.truncated);
+        assert_eq!(preview.content, pack.content);
+        assert_eq!(preview.omitted_files, 0);
+    }
+
+    #[test]
+    fn build_pack_preview_keeps_whole_sections_at_both_ends() {
+        let middle = format!("// b.ts\n{}", "x\n".repeat(500));
+        let pack = stored_pack(&["// a.ts\nconst a = 1;", &middle, "// c.ts\nconst c = 3;"]);
+        let preview = build_pack_preview(&pack, 60);
+        assert!(preview.truncated);
+        assert_eq!(preview.omitted_files, 1);
+        assert_eq!(preview.total_chars, pack.content.len());
+        assert!(preview.content.starts_with("// a.ts\nconst a = 1;\n\n… "));
+        assert!(preview.content.ends_with(" characters omitted …\n\n// c.ts\nconst c = 3;"));
+    }
+
+    #[test]
+    fn build_pack_preview_cuts_oversized_sections_on_line_boundaries() {
+        let body = "line é\n".repeat(200);
+        let pack = stored_pack(&[&body]);
+        let preview = build_pack_preview(&pack, 50);
+        assert!(preview.truncated);
+        assert_eq!(preview.omitted_files, 1);
+        let (head, rest) = preview.content.split_once("\n\n… ").unwrap();
+        assert!(head.ends_with("line é\n"));
+        let tail = rest.split_once(" …\n\n").unwrap().1;
+        assert!(tail.starts_with("line é\n") && tail.ends_with("line é\n"));
+    }
+
+    #[tokio::test]
+    async fn preview_pack_reads_packs_from_the_latest_run() {
+        let request = PackRequest {
+            files: vec![FileContent { path: "preview.ts".into(), content: "const preview = 1;\n".repeat(100), token_count: None }],
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        let pack = &response.packs[0];
+        let preview = preview_pack(pack.id.clone(), 200).await.unwrap();
+        assert!(preview.truncated);
+        assert!(preview.content.starts_with("// preview.ts\n"));
+        assert!(preview_pack("missing".into(), 200).await.is_err());
+    }
+
+    // ── pack profiles ──
+
+    #[tokio::test]
+    async fn pack_files_applies_profile_and_token_budget() {
+        let files = || {
+            (0..4)
+                .map(|i| FileContent { path: format!("f{i}.ts"), content: format!("const f{i} = {i};\n"), token_count: Some(100) })
+                .collect::<Vec<_>>()
+        };
+        let request = |profile_id: Option<&str>, max_tokens_per_pack| PackRequest {
+            files: files(),
+            output_format: "markdown".into(),
+            profile_id: profile_id.map(str::to_string),
+            max_tokens_per_pack,
+            ..Default::default()
+        };
+
+        let budgeted = pack_files(request(None, Some(150))).await.unwrap();
+        assert_eq!(budgeted.packs.len(), 4, "no two 100-token files fit in one 150-token pack");
+        assert!(budgeted.packs.iter().all(|pack| pack.estimated_tokens <= 150));
+
+        let quick = pack_files(request(Some("gpt-quick-question"), Some(150))).await.unwrap();
+        assert_eq!(quick.packs.len(), 1);
+        assert!(quick.packs[0].content.starts_with("// "), "plaintext from the profile");
+
+        assert!(pack_files(request(Some("missing"), None)).await.is_err());
+    }
+
+    // ── fingerprints ──
+
+    #[tokio::test]
+    async fn verify_fingerprint_accepts_hashes_of_the_packed_contents_only() {
+        let files = vec![
+            FileContent { path: "a.ts".into(), content: "const a = 1;\n".into(), token_count: None },
+            FileContent { path: "b.ts".into(), content: "const b = 2;\n".into(), token_count: None },
+        ];
+        let hashes: HashMap<String, String> = files
+            .iter()
+            .map(|file| (file.path.clone(), blake3::hash(file.content.as_bytes()).to_hex().to_string()))
+            .collect();
+        let fingerprint = pack_files(plain_request(files, 1, None, "grouped")).await.unwrap().fingerprint;
+        assert!(verify_fingerprint(fingerprint.clone(), hashes.clone()).await.unwrap());
+
+        let mut stale = hashes.clone();
+        stale.insert("b.ts".into(), blake3::hash(b"const b = 3;\n").to_hex().to_string());
+        assert!(!verify_fingerprint(fingerprint.clone(), stale).await.unwrap());
+        let mut missing = hashes.clone();
+        missing.remove("a.ts");
+        assert!(!verify_fingerprint(fingerprint.clone(), missing).await.unwrap());
+        let mut invalid = hashes;
+        invalid.insert("a.ts".into(), "not-hex".into());
+        assert!(verify_fingerprint(fingerprint, invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn content_hashes_ignore_file_order_and_the_pack_header() {
+        let files = |b: &str| {
+            vec![
+                FileContent { path: "a.md".into(), content: "# A".into(), token_count: None },
+                FileContent { path: "b.md".into(), content: b.into(), token_count: None },
+                FileContent { path: "c.md".into(), content: "# C".into(), token_count: None },
+            ]
+        };
+        let pack = |files: Vec<FileContent>| async {
+            let mut request = plain_request(files, 2, None, "grouped");
+            request.wrapper = Some(full_wrapper());
+            pack_files(request).await.unwrap()
+        };
+        let first = pack(files("# B")).await;
+        assert_eq!(first.content_hash.len(), 64);
+        assert!(first.packs.iter().all(|pack| pack.content_hash.len() == 64));
+
+        let mut reversed = files("# B");
+        reversed.reverse();
+        assert_eq!(pack(reversed).await.content_hash, first.content_hash);
+        assert_ne!(pack(files("# B!")).await.content_hash, first.content_hash);
+    }
+
+    #[tokio::test]
+    async fn previous_manifests_mark_unchanged_packs_and_reuse_packed_text() {
+        let dir = format!("incremental-{}", uuid::Uuid::new_v4());
+        let files = |b: &str| {
+            vec![
+                FileContent { path: format!("{dir}/a.md"), content: "# A".into(), token_count: None },
+                FileContent { path: format!("{dir}/b.md"), content: b.into(), token_count: None },
+            ]
+        };
+        let first = pack_files(plain_request(files("# B"), 1, None, "grouped")).await.unwrap();
+        assert!(first.packs[0].changed, "no previous run to compare with");
+        assert_eq!(first.manifest.len(), 2);
+
+        let mut request = plain_request(files("# B"), 1, None, "grouped");
+        request.previous_manifest = Some(first.manifest.clone());
+        let same = pack_files(request).await.unwrap();
+        assert!(!same.packs[0].changed);
+
+        let mut request = plain_request(files("# B2"), 1, None, "grouped");
+        request.previous_manifest = Some(first.manifest.clone());
+        assert!(pack_files(request).await.unwrap().packs[0].changed);
+
+        // Unchanged files come from the cache rather than being cleaned up again.
+        let mut request = plain_request(files("# B"), 1, None, "grouped");
+        let settings = packing_settings(&mut request);
+        let a_path = format!("{dir}/a.md");
+        let cached = PackedFile {
+            hash: blake3::hash(b"# A"),
+            settings,
+            first_line: 1,
+            content: "# cached A".into(),
+            tokens: 3,
+            saved_tokens: 0,
+        };
+        remember_packed_file(&a_path, cached);
+        request.previous_manifest = Some(first.manifest.clone());
+        assert!(pack_files(request).await.unwrap().packs[0].content.contains("# cached A"));
+
+        let mut request = plain_request(files("# B"), 1, None, "grouped");
+        request.previous_manifest = Some(HashMap::from([(a_path, "not-hex".into())]));
+        assert!(pack_files(request).await.is_err());
+    }
+
+    // ── delta packs ──
+
+    #[test]
+    fn render_repo_map_nests_directories_and_marks_changes() {
+        let paths = vec!["README.md".to_string(), "src/app.ts".to_string(), "src/lib/new.ts".to_string()];
+        let changed = HashSet::from(["src/app.ts"]);
+        let added = HashSet::from(["src/lib/new.ts"]);
+        let removed = vec!["src/old.ts".to_string()];
+        assert_eq!(
+            render_repo_map(&paths, &changed, &added, &removed),
+            "# Repository map\n\nREADME.md\nsrc/\n  app.ts (changed)\n  lib/\n    new.ts (new)\n  old.ts (deleted)\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn generate_delta_packs_only_changed_files_with_a_repo_map() {
+        let request = |files: Vec<(&str, &str)>| PackRequest {
+            files: files
+                .into_iter()
+                .map(|(path, content)| FileContent { path: path.into(), content: content.into(), token_count: None })
+                .collect(),
+            num_packs: 2,
+            ..Default::default()
+        };
+        let initial = pack_files(request(vec![("a.ts", "const a = 1;\n"), ("b.ts", "const b = 1;\n"), ("c.ts", "const c = 1;\n")]))
+            .await
+            .unwrap();
+
+        let next = || request(vec![("a.ts", "const a = 2;\n"), ("b.ts", "const b = 1;\n"), ("d.ts", "const d = 1;\n")]);
+        let delta = generate_delta(initial.fingerprint.clone(), next()).await.unwrap();
+        assert_eq!(delta.changed_paths, vec!["a.ts".to_string(), "d.ts".to_string()]);
+        assert_eq!(delta.removed_paths, vec!["c.ts".to_string()]);
+        assert_eq!(delta.pack.file_paths, vec![REPO_MAP_PATH.to_string(), "a.ts".to_string(), "d.ts".to_string()]);
+        assert!(delta.pack.content.contains("c.ts (deleted)"));
+        assert!(!delta.pack.content.contains("const b"));
+
+        let repeat = generate_delta(delta.fingerprint.clone(), next()).await.unwrap();
+        assert!(repeat.changed_paths.is_empty());
+        assert_eq!(repeat.pack.file_paths, vec![REPO_MAP_PATH.to_string()]);
+        assert_eq!(delta.fingerprint, pack_files(next()).await.unwrap().fingerprint);
+
+        assert!(generate_delta("unknown".into(), next()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn generate_delta_keeps_every_changed_file_past_the_llm_profile_window() {
+        let body = |name: &str, value: usize| -> String {
+            (0..2_500).map(|i| format!("export const {name}{i} = {value};\n")).collect()
+        };
+        let request = |value: usize| {
+            let files = vec![
+                FileContent { path: "a.ts".into(), content: body("a", value), token_count: None },
+                FileContent { path: "b.ts".into(), content: body("b", value), token_count: None },
+            ];
+            let mut request = plain_request(files, 1, None, "grouped");
+            request.llm_profile_id = Some("local-llama-3-1-8b".into());
+            request
+        };
+        let initial = pack_files(request(1)).await.unwrap();
+        assert!(initial.packs.len() >= 2, "the initial pack is fitted to the 8k window");
+
+        let delta = generate_delta(initial.fingerprint, request(2)).await.unwrap();
+        assert_eq!(delta.changed_paths, vec!["a.ts".to_string(), "b.ts".to_string()]);
+        assert_eq!(delta.pack.file_paths, vec![REPO_MAP_PATH.to_string(), "a.ts".to_string(), "b.ts".to_string()]);
+        assert!(delta.pack.estimated_tokens > 8_192);
+        assert!(delta.pack.truncations.is_empty());
+        assert!(delta.pack.content.contains("export const b2499 = 2;"));
+    }
+
+    // ── chunked clipboard copy ──
+
+    #[test]
+    fn clipboard_chunk_ranges_cut_on_line_breaks_within_budget() {
+        let content = "aaaa\n\nbbbb\ncccc\ndddddddddddd";
+        let ranges = clipboard_chunk_ranges(content, 10);
+        let chunks: Vec<&str> = ranges.iter().map(|range| &content[range.clone()]).collect();
+        assert_eq!(chunks, vec!["aaaa\n\n", "bbbb\ncccc\n", "dddddddddd", "dd"]);
+        assert_eq!(chunks.concat(), content);
+
+        let multibyte = "ééééé";
+        let ranges = clipboard_chunk_ranges(multibyte, 3);
+        assert!(ranges.iter().all(|range| range.len() == 2));
+        assert!(clipboard_chunk_ranges("", 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn chunked_copy_hands_out_every_part_once() {
+        let content = "line\n".repeat(1_000);
+        store_pack("clipboard-test", StoredPack { content: content.clone(), sections: vec![0..content.len()] });
+
+        let mut chunk = start_chunked_copy("clipboard-test".into(), Some(MIN_CLIPBOARD_CHUNK_BYTES)).await.unwrap();
+        let total = chunk.total;
+        assert_eq!(total, content.len().div_ceil(MIN_CLIPBOARD_CHUNK_BYTES));
+        let mut rebuilt = String::new();
+        loop {
+            let header = format!("=== PART {}/{total} ===\n", chunk.index);
+            rebuilt.push_str(chunk.content.strip_prefix(&header).expect("part header"));
+            if chunk.done {
+                break;
+            }
+            chunk = copy_next_chunk("clipboard-test".into()).await.unwrap();
+        }
+        assert_eq!(rebuilt, content);
+        assert!(copy_next_chunk("clipboard-test".into()).await.is_err(), "finished copies are forgotten");
+
+        store_pack("clipboard-small", StoredPack { content: "short".into(), sections: vec![0..5] });
+        let single = start_chunked_copy("clipboard-small".into(), None).await.unwrap();
+        assert_eq!(single, ClipboardChunk { content: "short".into(), index: 1, total: 1, done: true });
+        assert!(start_chunked_copy("missing".into(), None).await.is_err());
+    }
+
+    // ── combined export ──
 
     #[test]
-    fn should_extract_closed_quoted_segments() {
-        let line = r#"import foo from "./foo"; const x = require('bar');"#;
-        let parts = extract_quoted_segments(line);
-        assert_eq!(parts, vec!["./foo".to_string(), "bar".to_string()]);
+    fn combine_packs_separates_packs_with_markers() {
+        assert_eq!(
+            combine_packs(&["// a.ts\nconst a = 1;", "// b.ts\nconst b = 2;"]),
+            "=== PACK 1/2 ===\n// a.ts\nconst a = 1;\n\n=== PACK 2/2 ===\n// b.ts\nconst b = 2;"
+        );
+        assert_eq!(combine_packs(&[]), "");
     }
 
-    #[test]
-    fn should_ignore_unterminated_quoted_segments() {
-        let line = r#"import foo from "./foo"#;
-        let parts = extract_quoted_segments(line);
-        assert!(parts.is_empty());
+    #[tokio::test]
+    async fn export_combined_packs_rejects_unknown_ids() {
+        let path = std::env::temp_dir().join("bablusheed-combined.txt").to_string_lossy().to_string();
+        let err = export_combined_packs(path, vec!["missing".into()]).await.unwrap_err();
+        assert!(err.contains("Unknown pack id"));
     }
 
+    // ── per-pack export ──
+
     #[test]
-    fn should_handle_escaped_quotes_in_segments() {
-        let line = r#"import foo from "path/with\"quote""#;
-        let parts = extract_quoted_segments(line);
-        assert_eq!(parts.len(), 1);
-        assert!(parts[0].contains("with"));
+    fn export_file_name_expands_padded_placeholders() {
+        let name = |pattern: &str, project: &str| export_file_name(pattern, project, 3, 12);
+        assert_eq!(name(DEFAULT_EXPORT_PATTERN, "my app").unwrap(), "my-app-pack-03-of-12.md");
+        assert_eq!(name("{project}_{index}_{total:03}.txt", "../repo").unwrap(), "-repo_3_012.txt");
+        assert_eq!(name("pack-{index}.md", "").unwrap(), "pack-3.md");
+        assert!(name("{index}/{total}.md", "app").is_err());
+        assert!(name("{name}-{index}.md", "app").is_err());
+        assert!(name("{index:x}.md", "app").is_err());
+        assert!(name("{index.md", "app").is_err());
     }
 
-    // ── extract_module_specifiers ──
+    #[tokio::test]
+    async fn export_packs_writes_each_pack_under_the_pattern() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-export-packs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let directory = dir.to_string_lossy().to_string();
+        crate::commands::fs::authorize_export_directory(directory.clone()).await.unwrap();
+        store_pack("export-a", StoredPack { content: "// a.ts".into(), sections: Vec::new() });
+        store_pack("export-b", StoredPack { content: "// b.ts".into(), sections: Vec::new() });
+        let ids = vec!["export-a".to_string(), "export-b".to_string()];
+
+        let paths = export_packs(directory.clone(), ids.clone(), "demo".into(), None).await.unwrap();
+        let names: Vec<_> = paths.iter().map(|path| std::path::Path::new(path).file_name().unwrap().to_owned()).collect();
+        assert_eq!(names, vec!["demo-pack-01-of-2.md", "demo-pack-02-of-2.md"]);
+        assert_eq!(std::fs::read_to_string(&paths[1]).unwrap(), "// b.ts");
+
+        let err = export_packs(directory.clone(), ids.clone(), "demo".into(), Some("{project}.md".into())).await;
+        assert!(err.unwrap_err().contains("same file name"));
+        let err = export_packs(directory, vec!["missing".into()], "demo".into(), None).await;
+        assert!(err.unwrap_err().contains("Unknown pack id"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 
-    #[test]
-    fn extract_js_imports() {
-        let content = r#"import { foo } from "./foo";
-import bar from "../bar";
-"#;
-        let specs = extract_module_specifiers(content);
-        assert!(specs.contains(&"./foo".to_string()));
-        assert!(specs.contains(&"../bar".to_string()));
+    // ── encrypted export ──
+
+    fn decrypt_with_passphrase(encrypted: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+        use std::io::Read;
+
+        let decryptor = age::Decryptor::new(encrypted).map_err(|e| e.to_string())?;
+        let identity = age::scrypt::Identity::new(age::secrecy::SecretString::from(passphrase.to_string()));
+        let mut reader = decryptor
+            .decrypt(std::iter::once(&identity as &dyn age::Identity))
+            .map_err(|e| e.to_string())?;
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).map_err(|e| e.to_string())?;
+        Ok(decrypted)
     }
 
     #[test]
-    fn extract_python_from_import() {
-        let content = "from foo.bar import baz\n";
-        let specs = extract_module_specifiers(content);
-        assert!(specs.contains(&"foo/bar".to_string()));
+    fn encrypt_with_passphrase_round_trips_and_rejects_wrong_passphrases() {
+        let plaintext = "=== PACK 1/1 ===\n// secret.ts\nconst key = 1;".as_bytes();
+        let encrypted = encrypt_with_passphrase(plaintext, "correct horse battery staple").unwrap();
+        assert!(encrypted.starts_with(b"age-encryption.org/v1\n"));
+        assert!(!encrypted.windows(b"secret.ts".len()).any(|window| window == b"secret.ts"));
+
+        assert_eq!(decrypt_with_passphrase(&encrypted, "correct horse battery staple").unwrap(), plaintext);
+        assert!(decrypt_with_passphrase(&encrypted, "wrong horse battery staple").is_err());
+        assert!(encrypt_with_passphrase(plaintext, "short").is_err());
     }
 
-    #[test]
-    fn extract_python_plain_import() {
-        let content = "import os, sys\n";
-        let specs = extract_module_specifiers(content);
-        assert!(specs.contains(&"os".to_string()));
-        assert!(specs.contains(&"sys".to_string()));
+    // ── markup formats ──
+
+    const ADVERSARIAL_CONTENTS: &[&str] = &[
+        "if (a < b && c > d) { return \"<tag>\"; }",
+        "const end = \"]]>\"; // ]]]]> also ]]>]]>",
+        "<![CDATA[ nested ]]> &amp; &lt;/file>",
+        "</pack></file></pre>",
+        "",
+    ];
+
+    /// Parse a markup pack and return (path, content) for every file element.
+    fn parse_markup_pack(pack: &str, element: &[u8], path_attr: &[u8]) -> Vec<(String, String)> {
+        use quick_xml::events::Event;
+
+        let mut reader = quick_xml::Reader::from_str(pack);
+        let mut files = Vec::new();
+        let mut current: Option<(String, String)> = None;
+        loop {
+            match reader.read_event().expect("well-formed markup") {
+                Event::Start(tag) if tag.name().as_ref() == element => {
+                    let path = tag.try_get_attribute(path_attr).unwrap().unwrap().unescape_value().unwrap();
+                    current = Some((path.to_string(), String::new()));
+                }
+                Event::CData(data) => {
+                    let text = String::from_utf8(data.into_inner().to_vec()).unwrap();
+                    current.as_mut().unwrap().1.push_str(&text);
+                }
+                Event::Text(text) => {
+                    if let Some((_, content)) = current.as_mut() {
+                        content.push_str(&text.unescape().unwrap());
+                    }
+                }
+                Event::End(tag) if tag.name().as_ref() == element => files.push(current.take().unwrap()),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        files
     }
 
-    #[test]
-    fn extract_rust_mod() {
-        let content = "mod utils;\npub mod helpers;\n";
-        let specs = extract_module_specifiers(content);
-        assert!(specs.contains(&"./utils".to_string()));
-        assert!(specs.contains(&"./helpers".to_string()));
+    async fn pack_as(format: &str) -> (Vec<FileContent>, String) {
+        let files = || {
+            ADVERSARIAL_CONTENTS
+                .iter()
+                .enumerate()
+                .map(|(i, content)| FileContent { path: format!("dir/<odd> & \"{i}\".txt"), content: content.to_string(), token_count: None })
+                .collect::<Vec<_>>()
+        };
+        let request = PackRequest {
+            files: files(),
+            output_format: format.into(),
+            strategy: "dependency".into(),
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        (files(), response.packs[0].content.clone())
     }
 
     #[test]
-    fn extract_skips_comments_and_blanks() {
-        let content = "// import foo from 'bar';\n# comment\n\n";
-        let specs = extract_module_specifiers(content);
-        assert!(specs.is_empty());
+    fn push_cdata_splits_terminators() {
+        let mut out = String::new();
+        push_cdata(&mut out, "a]]>b");
+        assert_eq!(out, "<![CDATA[a]]]]><![CDATA[>b]]>");
     }
 
-    // ── resolve_module_specifier ──
+    #[tokio::test]
+    async fn xml_packs_round_trip_adversarial_contents() {
+        let (files, pack) = pack_as("xml").await;
+        assert!(pack.starts_with("<pack>\n") && pack.ends_with("\n</pack>"));
+        let mut parsed = parse_markup_pack(&pack, b"file", b"path");
+        parsed.iter_mut().for_each(|(_, content)| *content = content.trim_matches('\n').to_string());
+        let expected: Vec<_> = files.into_iter().map(|f| (f.path, f.content)).collect();
+        assert_eq!(parsed.len(), expected.len());
+        for entry in &expected {
+            assert!(parsed.contains(entry), "missing {entry:?} in {parsed:?}");
+        }
+    }
 
-    #[test]
-    fn resolve_relative_import() {
-        let mut path_to_idx = HashMap::new();
-        path_to_idx.insert("src/lib/utils.ts".to_string(), 0usize);
-        let result = resolve_module_specifier("./utils", "src/lib/foo.ts", &path_to_idx);
-        assert_eq!(result, Some(0));
+    #[tokio::test]
+    async fn html_packs_round_trip_adversarial_contents() {
+        let (files, pack) = pack_as("html").await;
+        let parsed = parse_markup_pack(&pack, b"pre", b"data-path");
+        let expected: Vec<_> = files.into_iter().map(|f| (f.path, f.content)).collect();
+        assert_eq!(parsed.len(), expected.len());
+        for entry in &expected {
+            assert!(parsed.contains(entry), "missing {entry:?} in {parsed:?}");
+        }
     }
 
-    #[test]
-    fn resolve_at_alias_import() {
-        let mut path_to_idx = HashMap::new();
-        path_to_idx.insert("src/lib/utils.ts".to_string(), 0usize);
-        let result = resolve_module_specifier("@/lib/utils", "src/components/App.tsx", &path_to_idx);
-        assert_eq!(result, Some(0));
+    // ── json format ──
+
+    #[tokio::test]
+    async fn json_packs_round_trip_adversarial_contents() {
+        let (files, pack) = pack_as("json").await;
+        let document: serde_json::Value = serde_json::from_str(&pack).expect("valid JSON");
+        let parsed = document["files"].as_array().unwrap();
+        assert_eq!(parsed.len(), files.len());
+        for (entry, file) in parsed.iter().zip(&files) {
+            assert_eq!(entry["path"], file.path.as_str());
+            assert_eq!(entry["content"], file.content.as_str());
+            assert_eq!(entry["language"], "text");
+            assert_eq!(entry["tokens"], estimate_tokens_for_path(&file.path, &file.content));
+        }
     }
 
-    #[test]
-    fn resolve_returns_none_for_external_modules() {
-        let path_to_idx = HashMap::new();
-        assert_eq!(resolve_module_specifier("react", "src/App.tsx", &path_to_idx), None);
+    #[tokio::test]
+    async fn json_packs_keep_wrapper_fields_and_ignore_custom_boundaries() {
+        let files = vec![
+            FileContent { path: "src/a.ts".into(), content: "export const a = 1;".into(), token_count: Some(7) },
+            FileContent { path: "src/b.ts".into(), content: "export const b = 2;".into(), token_count: Some(7) },
+        ];
+        let mut request = plain_request(files, 2, None, "dependency");
+        request.output_format = "json".into();
+        request.wrapper = Some(PackWrapper {
+            opening: Some("start {index}".into()),
+            closing: Some("end {index}".into()),
+            include_index: true,
+            project_name: None,
+            include_timestamp: false,
+            preamble: None,
+            epilogue: None,
+        });
+        request.boundaries = Some(SectionBoundaries {
+            separator: Some("\n---\n".into()),
+            file_start: Some("<<{path}".into()),
+            file_end: Some(">>".into()),
+        });
+        request.provenance = Some(ProjectMetadata { name: Some("app".into()), ..Default::default() });
+        request.excluded_paths = vec!["dist/bundle.js".into()];
+
+        let response = pack_files(request).await.unwrap();
+        assert_eq!(response.packs.len(), 2);
+        for (i, pack) in response.packs.iter().enumerate() {
+            let document: serde_json::Value = serde_json::from_str(&pack.content).expect("valid JSON");
+            assert_eq!(document["provenance"], "Source: app");
+            assert_eq!(document["opening"], format!("start {}", i + 1));
+            assert_eq!(document["closing"], format!("end {}", i + 1));
+            assert_eq!(document["pack"], format!("{}/2", i + 1));
+            assert_eq!(document["files"][0]["language"], "typescript");
+            assert_eq!(document["files"][0]["tokens"], 7);
+        }
+        let last: serde_json::Value = serde_json::from_str(&response.packs[1].content).unwrap();
+        let paths: Vec<&str> = last["files"].as_array().unwrap().iter().map(|f| f["path"].as_str().unwrap()).collect();
+        assert_eq!(paths, vec!["src/b.ts", EXCLUDED_FILES_PATH]);
     }
 
+    // ── suggested prompts ──
+
     #[test]
-    fn resolve_returns_none_for_http_urls() {
-        let path_to_idx = HashMap::new();
-        assert_eq!(resolve_module_specifier("https://cdn.example.com/lib.js", "src/App.tsx", &path_to_idx), None);
+    fn describe_pack_contents_lists_busiest_directories() {
+        let paths = |list: &[&str]| list.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            describe_pack_contents(&paths(&["src/pay/a.ts", "src/pay/b.ts", "README.md"])),
+            "src/pay/ and top-level files"
+        );
+        assert_eq!(
+            describe_pack_contents(&paths(&["a/x.ts", "b/x.ts", "c/x.ts", "d/x.ts", "d/y.ts"])),
+            "d/, a/ and b/ (+1 more)"
+        );
     }
 
-    #[test]
-    fn resolve_returns_none_for_node_builtins() {
-        let path_to_idx = HashMap::new();
-        assert_eq!(resolve_module_specifier("node:path", "src/App.tsx", &path_to_idx), None);
+    #[tokio::test]
+    async fn pack_files_suggests_a_prompt_per_pack() {
+        let files = (0..4)
+            .map(|i| FileContent { path: format!("src/pay/f{i}.ts"), content: format!("const f{i} = {i};\n"), token_count: Some(10) })
+            .collect();
+        let request = PackRequest {
+            files,
+            num_packs: 2,
+            strategy: "dependency".into(),
+            suggest_prompts: true,
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        let prompts: Vec<_> = response.packs.iter().map(|p| p.suggested_prompt.clone().unwrap()).collect();
+        assert_eq!(prompts[0], "This is pack 1 of 2 containing src/pay/. Wait for all packs before answering.");
+        assert!(prompts[1].ends_with("That was the last pack; you can answer now."));
+
+        let pack = &response.packs[0];
+        assert_eq!(render_pack_prompt("{files} files, ~{tokens} tokens", pack, 1, 1), "2 files, ~20 tokens");
     }
 
+    // ── pack wrapper ──
+
     #[test]
-    fn resolve_with_explicit_extension() {
-        let mut path_to_idx = HashMap::new();
-        path_to_idx.insert("src/lib/utils.ts".to_string(), 0usize);
-        let result = resolve_module_specifier("@/lib/utils.ts", "src/App.tsx", &path_to_idx);
-        assert_eq!(result, Some(0));
+    fn format_utc_timestamp_matches_known_dates() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc_timestamp(1_791_979_505), "2026-10-14T12:05:05Z");
     }
 
-    #[test]
-    fn resolve_tries_index_files() {
-        let mut path_to_idx = HashMap::new();
-        path_to_idx.insert("src/lib/index.ts".to_string(), 0usize);
-        let result = resolve_module_specifier("@/lib", "src/App.tsx", &path_to_idx);
-        assert_eq!(result, Some(0));
+    fn full_wrapper() -> PackWrapper {
+        PackWrapper {
+            opening: Some("=== BEGIN {project} {index}/{total} ===".into()),
+            closing: Some("=== END {index}/{total} ===".into()),
+            include_index: true,
+            project_name: Some("demo".into()),
+            include_timestamp: true,
+            preamble: None,
+            epilogue: None,
+        }
     }
 
-    // ── format_file_header ──
+    fn wrap(format: &str, wrapper: Option<&PackWrapper>, body: &str) -> String {
+        let frame = PackFrame {
+            format,
+            wrapper,
+            index: 2,
+            total: 3,
+            generated_at: "2026-10-14T12:05:05Z",
+            provenance: None,
+            file_index: &[],
+            file_count: 1,
+            note: None,
+        };
+        let mut out = String::new();
+        push_pack_open(&mut out, &frame);
+        out.push_str(body);
+        push_pack_close(&mut out, &frame);
+        out
+    }
 
     #[test]
-    fn format_markdown_wraps_in_code_block() {
-        let result = format_file_header("src/main.ts", "const x = 1;", "markdown");
-        assert!(result.starts_with("```typescript"));
-        assert!(result.contains("// src/main.ts"));
-        assert!(result.contains("const x = 1;"));
-        assert!(result.ends_with("```"));
+    fn pack_wrapper_frames_text_packs() {
+        let wrapper = full_wrapper();
+        assert_eq!(
+            wrap("plaintext", Some(&wrapper), "// a.ts\nconst a = 1;"),
+            "=== BEGIN demo 2/3 ===\npack: 2/3 · project: demo · generated: 2026-10-14T12:05:05Z\n\n// a.ts\nconst a = 1;\n\n=== END 2/3 ==="
+        );
+        assert_eq!(wrap("markdown", None, "body"), "body");
+        assert_eq!(wrap("markdown", Some(&PackWrapper::default()), "body"), "body");
     }
 
     #[test]
-    fn format_plaintext_uses_path_comment() {
-        let result = format_file_header("src/main.ts", "const x = 1;", "plaintext");
-        assert!(result.starts_with("// src/main.ts"));
-        assert!(result.contains("const x = 1;"));
-        assert!(!result.contains("```"));
+    fn pack_wrapper_keeps_markup_packs_well_formed() {
+        let mut wrapper = full_wrapper();
+        wrapper.project_name = Some("a \"quoted\" -- <name>".into());
+        let xml = wrap("xml", Some(&wrapper), "<file path=\"a\">\n<![CDATA[x]]>\n</file>");
+        assert!(xml.starts_with("<!-- === BEGIN a \"quoted\" - - <name> 2/3 === -->\n<pack pack=\"2/3\" project=\"a &quot;quoted&quot; -- &lt;name&gt;\""));
+        assert!(xml.ends_with("</pack>\n<!-- === END 2/3 === -->\n"));
+
+        let mut reader = quick_xml::Reader::from_str(&xml);
+        loop {
+            match reader.read_event().expect("well-formed markup") {
+                quick_xml::events::Event::Eof => break,
+                _ => continue,
+            }
+        }
+
+        let html = wrap("html", Some(&wrapper), "<pre data-path=\"a\">x</pre>");
+        assert!(html.contains("<div class=\"pack\" data-pack=\"2/3\""));
     }
 
     #[test]
-    fn format_markdown_maps_extensions_to_languages() {
-        let cases = vec![
-            ("file.rs", "rust"),
-            ("file.py", "python"),
-            ("file.go", "go"),
-            ("file.json", "json"),
-            ("file.md", "markdown"),
-            ("file.css", "css"),
-            ("file.xyz", "text"),
-        ];
-        for (path, expected_lang) in cases {
-            let result = format_file_header(path, "", "markdown");
-            assert!(result.starts_with(&format!("```{expected_lang}")), "expected {expected_lang} for {path}, got: {result}");
+    fn pack_wrapper_renders_preamble_and_epilogue_templates() {
+        let wrapper = PackWrapper {
+            project_name: Some("demo".into()),
+            preamble: Some("You are reviewing pack {{pack_index}} of {{total_packs}} ({{file_count}} files) from {{project_name}}.\n".into()),
+            epilogue: Some("End of pack {{pack_index}} & <more>.".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            wrap("plaintext", Some(&wrapper), "// a.ts"),
+            "project: demo\n\nYou are reviewing pack 2 of 3 (1 files) from demo.\n\n// a.ts\n\nEnd of pack 2 & <more>."
+        );
+        let xml = wrap("xml", Some(&wrapper), "");
+        assert!(xml.contains("<preamble>You are reviewing pack 2 of 3"));
+        assert!(xml.ends_with("<epilogue>End of pack 2 &amp; &lt;more&gt;.</epilogue>\n</pack>"));
+        let json: serde_json::Value = serde_json::from_str(&wrap("json", Some(&wrapper), "")).unwrap();
+        assert_eq!(json["preamble"], "You are reviewing pack 2 of 3 (1 files) from demo.");
+        assert_eq!(json["epilogue"], "End of pack 2 & <more>.");
+        let blank = PackWrapper { preamble: Some("  \n".into()), ..Default::default() };
+        assert_eq!(wrap("plaintext", Some(&blank), "body"), "body");
+    }
+
+    #[test]
+    fn render_provenance_pins_commit_and_dirty_state() {
+        let metadata = ProjectMetadata {
+            name: Some("app".into()),
+            version: Some("1.2.0".into()),
+            commit: Some("1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b".into()),
+            dirty: true,
+        };
+        assert_eq!(render_provenance(&metadata).as_deref(), Some("Source: app 1.2.0 @ 1a2b3c4d5e6f (uncommitted changes)"));
+        let no_git = ProjectMetadata { commit: None, ..metadata };
+        assert_eq!(render_provenance(&no_git).as_deref(), Some("Source: app 1.2.0"));
+        assert_eq!(render_provenance(&ProjectMetadata::default()), None);
+    }
+
+    #[tokio::test]
+    async fn pack_files_prefixes_every_pack_with_provenance() {
+        let request = PackRequest {
+            files: vec![
+                FileContent { path: "a.ts".into(), content: "const a = 1;".into(), token_count: None },
+                FileContent { path: "b.ts".into(), content: "const b = 2;".into(), token_count: None },
+            ],
+            num_packs: 2,
+            output_format: "xml".into(),
+            provenance: Some(ProjectMetadata { name: Some("app".into()), commit: Some("abc123".into()), ..Default::default() }),
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        assert_eq!(response.packs.len(), 2);
+        for pack in &response.packs {
+            assert!(pack.content.starts_with("<!-- Source: app @ abc123 -->\n<pack>\n"), "{}", pack.content);
         }
     }
 
-    // ── split_docs_and_code ──
+    // ── pack_additional ──
 
     #[test]
-    fn split_docs_and_code_separates_correctly() {
+    fn format_additional_files_matches_pack_sections() {
         let files = vec![
-            FileContent { path: "README.md".into(), content: "doc".into(), token_count: None },
-            FileContent { path: "main.ts".into(), content: "code".into(), token_count: None },
-            FileContent { path: "guide.txt".into(), content: "doc".into(), token_count: None },
+            FileContent { path: "src/a.ts".into(), content: "const a = 1;".into(), token_count: None },
+            FileContent { path: "src/b.ts".into(), content: "const b = 2;".into(), token_count: None },
         ];
-        let ordered: Vec<usize> = (0..3).collect();
-        let (docs, code) = split_docs_and_code(&ordered, &files);
+        for format in ["plaintext", "markdown", "xml", "html"] {
+            let mut expected = String::new();
+            push_file_section(&mut expected, "src/a.ts", "const a = 1;", format, None);
+            expected.push_str(SECTION_SEPARATOR);
+            push_file_section(&mut expected, "src/b.ts", "const b = 2;", format, None);
+
+            let item = format_additional_files(&files, format);
+            assert!(item.content.contains(&expected), "{format}: {}", item.content);
+            assert_eq!(item.file_paths, vec!["src/a.ts".to_string(), "src/b.ts".to_string()]);
+        }
+        assert!(format_additional_files(&files, "xml").content.starts_with("<pack>\n"));
+        assert!(format_additional_files(&files, "markdown").content.starts_with("```typescript\n"));
+    }
 
-        assert_eq!(docs.len(), 2);
-        assert_eq!(code.len(), 1);
-        assert!(docs.contains(&0));
-        assert!(docs.contains(&2));
-        assert!(code.contains(&1));
+    #[tokio::test]
+    async fn pack_additional_rejects_empty_and_oversized_requests() {
+        assert!(pack_additional(Vec::new(), "markdown".into(), None).await.is_err());
+        let many = (0..=MAX_ADDITIONAL_FILES).map(|i| format!("f{i}.ts")).collect();
+        assert!(pack_additional(many, "markdown".into(), None).await.is_err());
+        let missing = pack_additional(vec!["definitely/missing.ts".into()], "markdown".into(), None).await;
+        assert!(missing.unwrap_err().contains("definitely/missing.ts"));
     }
 
+    // ── pack_matches ──
+
     #[test]
-    fn split_docs_places_readme_first() {
-        let files = vec![
-            FileContent { path: "guide.md".into(), content: "".into(), token_count: None },
-            FileContent { path: "README.md".into(), content: "".into(), token_count: None },
-        ];
-        let ordered = vec![0, 1];
-        let (docs, _) = split_docs_and_code(&ordered, &files);
-        assert_eq!(docs[0], 1, "README should come first");
+    fn match_regions_merges_overlapping_context() {
+        let content = "a\nneedle one\nb\nc\nneedle two\nd\ne\nf\ng\nneedle three";
+        assert_eq!(
+            match_regions(content, "needle", 1).as_deref(),
+            Some(" 1: a\n 2: needle one\n 3: b\n 4: c\n 5: needle two\n 6: d\n…\n 9: g\n10: needle three")
+        );
+        assert_eq!(match_regions(content, "needle two", 0).as_deref(), Some("5: needle two"));
+        assert_eq!(match_regions(content, "absent", 3), None);
     }
 
-    // ── distribute_files ──
+    #[tokio::test]
+    async fn pack_matches_packs_files_or_regions_containing_the_query() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-matches-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.ts"), "import { useThing } from './b';\nuseThing();\n").unwrap();
+        std::fs::write(dir.join("src/b.ts"), "export function useThing() {}\n").unwrap();
+        std::fs::write(dir.join("src/c.ts"), "const unrelated = 1;\n").unwrap();
+        let root = dir.to_string_lossy().to_string();
+        crate::commands::fs::walk_directory_flat(root.clone(), true, Vec::new(), None, None).await.unwrap();
+
+        let whole = pack_matches(root.clone(), "useThing".into(), None, None, None).await.unwrap();
+        assert_eq!(whole.packs.len(), 1);
+        let mut paths = whole.packs[0].file_paths.clone();
+        paths.sort();
+        assert_eq!(paths, vec!["src/a.ts".to_string(), "src/b.ts".to_string()]);
+        assert!(whole.packs[0].content.contains("import { useThing } from './b';"));
+
+        let regions = pack_matches(root.clone(), "useThing();".into(), Some(0), Some("plaintext".into()), None).await.unwrap();
+        assert_eq!(regions.packs[0].file_paths, vec!["src/a.ts".to_string()]);
+        assert!(regions.packs[0].content.contains("2: useThing();"));
+        assert!(!regions.packs[0].content.contains("import"));
+
+        assert!(pack_matches(root.clone(), "nowhere".into(), None, None, None).await.is_err());
+        assert!(pack_matches(root, String::new(), None, None, None).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── redaction ──
+
+    #[tokio::test]
+    async fn pack_files_redacts_content_and_rejects_bad_rules() {
+        let request = |pattern: &str| PackRequest {
+            files: vec![FileContent { path: "a.ts".into(), content: "const url = 'https://api.acme.internal/v1';".into(), token_count: None }],
+            redactions: vec![RedactionRule { pattern: pattern.into(), replacement: None }],
+            ..Default::default()
+        };
+        let response = pack_files(request(r"[\w.]+\.internal")).await.unwrap();
+        assert!(response.packs[0].content.contains("'https://[REDACTED]/v1'"));
+        assert!(!response.packs[0].content.contains("acme"));
+        assert!(pack_files(request("(")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn pack_files_recounts_tokens_after_a_same_length_redaction() {
+        let content = "const password = 'correct horse battery staple';\n";
+        let mut request = plain_request(
+            vec![FileContent { path: "a.ts".into(), content: content.into(), token_count: None }],
+            1,
+            None,
+            "grouped",
+        );
+        request.llm_profile_id = Some("gpt-4o".into());
+        request.redactions = vec![RedactionRule { pattern: "[a-z]".into(), replacement: Some("#".into()) }];
+        let response = pack_files(request).await.unwrap();
+
+        let redacted = "##### ######## = '####### ##### ####### ######';\n";
+        assert!(response.packs[0].content.contains(redacted.trim_end()));
+        let counter = TokenCounter::for_profile(Some("gpt-4o"));
+        assert_ne!(counter.count("a.ts", redacted), counter.count("a.ts", content));
+        assert_eq!(response.packs[0].estimated_tokens, counter.count("a.ts", redacted));
+    }
+
+    // ── excluded files ──
 
     #[test]
-    fn distribute_single_pack() {
-        let indices = vec![0, 1, 2];
-        let tokens = vec![100, 200, 300];
-        let bins = distribute_files(&indices, 1, &tokens);
-        assert_eq!(bins.len(), 1);
-        assert_eq!(bins[0], vec![0, 1, 2]);
+    fn render_excluded_appendix_groups_paths_by_directory() {
+        let paths: Vec<String> = ["src/ui/b.tsx", "README.md", "src/ui/a.tsx", "src/lib.rs", "src/ui/a.tsx"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            render_excluded_appendix(&paths),
+            "# Excluded files\n\nThese files exist but were not included. Ask for any by path.\n\n./: README.md\nsrc/: lib.rs\nsrc/ui/: a.tsx, b.tsx"
+        );
+
+        let many: Vec<String> = (0..EXCLUDED_APPENDIX_LIMIT + 3).map(|i| format!("gen/{i:05}.ts")).collect();
+        assert!(render_excluded_appendix(&many).ends_with("\n… and 3 more"));
+    }
+
+    #[tokio::test]
+    async fn pack_files_appends_excluded_paths_to_last_pack_only() {
+        let request = PackRequest {
+            files: vec![
+                FileContent { path: "a.ts".into(), content: "const a = 1;".into(), token_count: None },
+                FileContent { path: "b.ts".into(), content: "const b = 2;".into(), token_count: None },
+            ],
+            num_packs: 2,
+            excluded_paths: vec!["src/big.json".into()],
+            ..Default::default()
+        };
+        let response = pack_files(request).await.unwrap();
+        let (last, rest) = response.packs.split_last().unwrap();
+        assert!(rest.iter().all(|pack| !pack.content.contains(EXCLUDED_FILES_PATH)));
+        assert!(last.content.ends_with("src/: big.json"));
+        assert!(!last.file_paths.contains(&EXCLUDED_FILES_PATH.to_string()), "appendix is not a packed file");
+        assert_eq!(response.total_tokens, response.packs.iter().map(|pack| pack.estimated_tokens).sum::<usize>());
+    }
+
+    #[tokio::test]
+    async fn pack_files_puts_the_config_surface_before_the_excluded_list() {
+        let request = PackRequest {
+            files: vec![
+                FileContent { path: "a.ts".into(), content: "const url = process.env.API_URL;".into(), token_count: None },
+            ],
+            excluded_paths: vec!["src/big.json".into()],
+            config_surface: true,
+            ..Default::default()
+        };
+        let content = &pack_files(request).await.unwrap().packs[0].content;
+        let surface = content.find(CONFIG_SURFACE_PATH).expect("config surface appended");
+        assert!(content[surface..].contains("- `API_URL`: a.ts:1"));
+        assert!(surface < content.find(EXCLUDED_FILES_PATH).unwrap());
     }
 
     #[test]
-    fn distribute_empty_input() {
-        let bins = distribute_files(&[], 3, &[]);
-        assert!(bins.is_empty());
+    fn is_split_part_matches_auto_balanced_chunks_only() {
+        assert!(is_split_part("src/big.part-1-of-3.ts"));
+        assert!(!is_split_part("src/part-of-speech.ts"));
+        assert!(!is_split_part("src/app.part-x-of-y.ts"));
     }
 
     #[test]
-    fn distribute_two_equal_packs() {
-        let indices = vec![0, 1, 2, 3];
-        let tokens = vec![100, 100, 100, 100];
-        let bins = distribute_files(&indices, 2, &tokens);
-        assert_eq!(bins.len(), 2);
-        let total: usize = bins.iter().map(|b| b.len()).sum();
-        assert_eq!(total, 4);
+    fn split_part_path_keeps_the_extension_last() {
+        assert_eq!(split_part_path("src/big.test.ts", 2, 3), "src/big.test.part-2-of-3.ts");
+        assert_eq!(split_part_path("Makefile", 1, 2), "Makefile.part-1-of-2");
+        assert_eq!(split_part_path(".env", 1, 2), ".env.part-1-of-2");
+        assert_eq!(
+            split_part_origin("src/big.test.part-2-of-3.ts"),
+            Some(("src/big.test.ts".to_string(), 2, 3))
+        );
+        assert_eq!(split_part_origin("Makefile.part-1-of-2"), Some(("Makefile".to_string(), 1, 2)));
     }
 
     #[test]
-    fn distribute_more_packs_than_files_clamps() {
-        let indices = vec![0, 1];
-        let tokens = vec![200, 100];
-        let bins = distribute_files(&indices, 10, &tokens);
-        assert_eq!(bins.len(), 2);
-        assert_eq!(bins[0], vec![0]);
-        assert_eq!(bins[1], vec![1]);
+    fn number_lines_pads_to_the_widest_number() {
+        assert_eq!(number_lines("a\n\nb", 1), "1 | a\n2 |\n3 | b");
+        assert_eq!(number_lines("x\ny\n", 9), " 9 | x\n10 | y\n11 |");
     }
 
     #[test]
-    fn distribute_preserves_order() {
-        let indices = vec![0, 1, 2, 3, 4, 5];
-        let tokens = vec![10, 10, 10, 10, 10, 10];
-        let bins = distribute_files(&indices, 3, &tokens);
-        let flattened: Vec<usize> = bins.into_iter().flatten().collect();
-        assert_eq!(flattened, vec![0, 1, 2, 3, 4, 5]);
+    fn number_lines_from_original_keeps_the_numbers_of_surviving_lines() {
+        let original = "// one\nconst a = 1; // two\n\nconst b = 2;\n";
+        let packed = "const a = 1;\nconst b = 2;\n";
+        assert_eq!(number_lines_from_original(original, packed, 1), "2 | const a = 1;\n4 | const b = 2;\n5 |");
+        assert_eq!(number_lines_from_original(packed, packed, 7), "7 | const a = 1;\n8 | const b = 2;\n9 |");
     }
 
-    // ── compute_dependency_order ──
+    #[tokio::test]
+    async fn pack_files_numbers_lines_of_the_file_on_disk_after_stripping_comments() {
+        let content = "// Header comment\n// spanning two lines\nexport const a = 1;\n/* block */\nexport const b = 2;\n";
+        let mut request = plain_request(
+            vec![FileContent { path: "src/a.ts".into(), content: content.into(), token_count: None }],
+            1,
+            None,
+            "dependency",
+        );
+        request.include_line_numbers = true;
+        request.strip_comments = true;
+        let content = &pack_files(request).await.unwrap().packs[0].content;
+        assert!(!content.contains("Header comment"), "{content}");
+        assert!(content.contains("3 | export const a = 1;\n"), "{content}");
+        assert!(content.contains("5 | export const b = 2;"), "{content}");
+    }
 
-    #[test]
-    fn dependency_order_respects_imports() {
-        let files = vec![
-            FileContent { path: "a.ts".into(), content: "import { b } from \"./b\";\n".into(), token_count: None },
-            FileContent { path: "b.ts".into(), content: "export const b = 1;\n".into(), token_count: None },
-        ];
-        let order = compute_dependency_order(&files);
-        let pos_a = order.iter().position(|&i| i == 0).unwrap();
-        let pos_b = order.iter().position(|&i| i == 1).unwrap();
-        assert!(pos_b < pos_a, "b.ts (dependency) should appear before a.ts");
+    #[tokio::test]
+    async fn pack_files_numbers_lines_and_continues_them_across_parts() {
+        let big: String = (1..=40).map(|i| format!("export const v{i} = {i};\n")).collect();
+        let mut request = plain_request(
+            vec![FileContent { path: "src/big.ts".into(), content: big, token_count: Some(400) }],
+            1,
+            None,
+            "dependency",
+        );
+        request.include_line_numbers = true;
+        request.max_tokens_per_file = Some(100);
+        let content = &pack_files(request).await.unwrap().packs[0].content;
+        assert!(content.contains(" 1 | export const v1 = 1;\n"), "{content}");
+        assert!(content.contains("40 | export const v40 = 40;\n"));
+        assert_eq!(content.matches(" | export const v").count(), 40, "each line numbered once");
+        assert!(!content.contains(" 1 | export const v2 ="), "parts continue the numbering");
     }
 
     #[test]
-    fn dependency_order_handles_single_file() {
+    fn split_at_breaks_prefers_paragraphs_then_lines() {
+        let content = "aaaa\n\nbbbb\ncccc\ndddd";
+        let chunks = split_at_breaks(content, 12);
+        assert_eq!(chunks, vec!["aaaa\n\n", "bbbb\ncccc\n", "dddd"]);
+        assert_eq!(split_at_breaks("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert_eq!(split_at_breaks("éé", 1), vec!["é", "é"], "never cuts inside a character");
+    }
+
+    #[tokio::test]
+    async fn pack_files_splits_oversized_files_into_fragments() {
+        let big: String = (0..40).map(|i| format!("export const v{i} = {i};\n")).collect();
         let files = vec![
-            FileContent { path: "only.ts".into(), content: "const x = 1;\n".into(), token_count: None },
+            FileContent { path: "src/big.ts".into(), content: big.clone(), token_count: Some(400) },
+            FileContent {
+                path: "src/app.ts".into(),
+                content: "import { v1 } from './big';".into(),
+                token_count: Some(10),
+            },
         ];
-        let order = compute_dependency_order(&files);
-        assert_eq!(order, vec![0]);
+        let mut request = plain_request(files, 1, None, "dependency");
+        request.max_tokens_per_file = Some(100);
+        let response = pack_files(request).await.unwrap();
+        let pack = &response.packs[0];
+
+        let parts: Vec<&str> = pack.fragments.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(parts.len(), pack.fragments[0].total);
+        assert!(parts.len() >= 4);
+        assert_eq!(parts[0], format!("src/big.part-1-of-{}.ts", parts.len()));
+        assert!(pack.fragments.iter().all(|f| f.original_path == "src/big.ts"));
+        assert_eq!(&pack.file_paths[..parts.len()], parts.as_slice(), "parts stay in order ahead of importers");
+        assert_eq!(pack.file_paths.last().unwrap(), "src/app.ts");
+        let section = |path: &str| {
+            let start = pack.content.find(&format!("// {path}\n")).unwrap() + path.len() + 4;
+            let end = start + pack.content[start..].find("\n\n// ").unwrap();
+            &pack.content[start..end]
+        };
+        assert_eq!(parts.iter().map(|path| section(path)).collect::<String>(), big);
+        assert!(response.warnings[0].starts_with("Split 1 oversized file(s)"));
+        assert_eq!(response.placements.len(), parts.len() + 1);
     }
 
-    #[test]
-    fn dependency_order_handles_empty() {
-        let order = compute_dependency_order(&[]);
-        assert!(order.is_empty());
+    // ── format snapshots ──
+
+    /// The files of `tests/fixtures/<name>`, by relative path.
+    fn fixture_files(name: &str) -> Vec<FileContent> {
+        fn walk(root: &std::path::Path, dir: &std::path::Path, files: &mut Vec<FileContent>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    walk(root, &path, files);
+                } else {
+                    let relative = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+                    let content = std::fs::read_to_string(&path).unwrap();
+                    files.push(FileContent { path: relative, content, token_count: None });
+                }
+            }
+        }
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+        let mut files = Vec::new();
+        walk(&root, &root, &mut files);
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
     }
 
-    // ── group_code_by_related_components ──
+    #[tokio::test]
+    async fn pack_formats_match_their_snapshots() {
+        for format in ["markdown", "plaintext", "xml", "html", "json"] {
+            let mut request = plain_request(fixture_files("mini-repo"), 2, None, "grouped");
+            request.output_format = format.into();
+            request.wrapper = Some(PackWrapper {
+                opening: Some("=== {project} {index}/{total} ===".into()),
+                closing: Some("=== end {index}/{total} ===".into()),
+                include_index: true,
+                project_name: Some("mini-repo".into()),
+                include_timestamp: false,
+                preamble: None,
+                epilogue: None,
+            });
+            request.excluded_paths = vec!["dist/bundle.js".into()];
+            let response = pack_files(request).await.unwrap();
+            let packs: Vec<&str> = response.packs.iter().map(|pack| pack.content.as_str()).collect();
+            insta::assert_snapshot!(format!("pack_{format}"), packs.join("\n\n<<< next pack >>>\n\n"));
+        }
+    }
 
-    #[test]
-    fn grouping_keeps_connected_files_adjacent() {
-        let files = vec![
-            FileContent { path: "a.ts".into(), content: "import { b } from \"./b\";\n".into(), token_count: None },
-            FileContent { path: "b.ts".into(), content: "export const b = 1;\n".into(), token_count: None },
-            FileContent { path: "c.ts".into(), content: "const c = 1;\n".into(), token_count: None },
-        ];
-        let order = compute_dependency_order(&files);
-        let related = build_related_adjacency(&files);
-        let grouped = group_code_by_related_components(&order, &related);
-        assert_eq!(grouped.len(), 3);
+    // ── pack invariants ──
+
+    /// Up to 40 files: docs, and code that imports earlier code files, with arbitrary token counts.
+    fn generated_files() -> impl Strategy<Value = Vec<FileContent>> {
+        prop::collection::vec(
+            (
+                any::<bool>(),
+                prop::collection::vec(any::<usize>(), 0..3),
+                0usize..5_000,
+            ),
+            0..40,
+        )
+        .prop_map(|specs| {
+            specs
+                .into_iter()
+                .enumerate()
+                .map(|(i, (is_doc, imports, tokens))| {
+                    let imports: String = imports
+                        .iter()
+                        .filter(|_| i > 0)
+                        .map(|target| format!("import {{ x }} from './f{}';\n", target % i))
+                        .collect();
+                    let (path, content) = if is_doc && i % 3 != 0 {
+                        (
+                            format!("docs/f{i}.md"),
+                            format!(
+                                "# Doc {i}\n```ts\n{}```\n",
+                                imports.replace("'./", "'../src/")
+                            ),
+                        )
+                    } else {
+                        (
+                            format!("src/f{i}.ts"),
+                            format!("{imports}export const x = {i};\n"),
+                        )
+                    };
+                    FileContent {
+                        path,
+                        content,
+                        token_count: Some(tokens),
+                    }
+                })
+                .collect()
+        })
+    }
 
-        let pos_a = grouped.iter().position(|&i| i == 0).unwrap();
-        let pos_b = grouped.iter().position(|&i| i == 1).unwrap();
-        let distance = if pos_a > pos_b { pos_a - pos_b } else { pos_b - pos_a };
-        assert_eq!(distance, 1, "a and b should be adjacent since they're connected");
+    fn plain_request(
+        files: Vec<FileContent>,
+        num_packs: usize,
+        budget: Option<usize>,
+        strategy: &str,
+    ) -> PackRequest {
+        PackRequest {
+            files,
+            num_packs,
+            max_tokens_per_pack: budget,
+            strategy: strategy.into(),
+            ..Default::default()
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn distribute_files_keeps_every_file_once_and_in_order(
+            tokens in prop::collection::vec(0usize..5_000, 0..40),
+            num_packs in 1usize..12,
+        ) {
+            let order: Vec<usize> = (0..tokens.len()).rev().collect();
+            let bins = distribute_files(&order, num_packs, &tokens, None);
+            prop_assert_eq!(bins.concat(), order);
+            prop_assert!(bins.len() <= num_packs);
+            prop_assert!(bins.iter().all(|bin| !bin.is_empty()));
+        }
+
+        #[test]
+        fn doc_strategy_never_mixes_docs_and_code(
+            tokens in prop::collection::vec(0usize..5_000, 1..40),
+            doc_count in 0usize..40,
+            num_packs in 1usize..12,
+        ) {
+            let doc_count = doc_count.min(tokens.len());
+            let docs: Vec<usize> = (0..doc_count).collect();
+            let code: Vec<usize> = (doc_count..tokens.len()).collect();
+            let bins = distribute_with_doc_strategy(&docs, &code, num_packs, &tokens, None);
+            prop_assert_eq!(bins.concat(), (0..tokens.len()).collect::<Vec<_>>());
+            prop_assert!(bins.len() <= num_packs);
+            if num_packs > 1 {
+                let mixed = bins
+                    .iter()
+                    .any(|bin| bin.iter().any(|&i| i < doc_count) && bin.iter().any(|&i| i >= doc_count));
+                prop_assert!(!mixed, "{:?}", bins);
+            }
+        }
+
+        #[test]
+        fn strategies_place_every_file_once(files in generated_files(), num_packs in 1usize..8) {
+            let dependency_order = compute_dependency_order(&files);
+            let token_counts: Vec<usize> = files.iter().map(|f| f.token_count.unwrap_or(0)).collect();
+            let input = StrategyInput {
+                files: &files,
+                dependency_order: &dependency_order,
+                token_counts: &token_counts,
+                num_packs,
+                max_tokens_per_pack: None,
+            };
+            for strategy in PACK_STRATEGIES {
+                let mut notes = OrderingNotes::new(&dependency_order);
+                let bins = strategy.distribute(&input, &mut notes);
+                let mut placed = bins.concat();
+                prop_assert!(bins.len() <= num_packs, "{}", strategy.name());
+                let order = placed.clone();
+                placed.sort_unstable();
+                prop_assert_eq!(placed, (0..files.len()).collect::<Vec<_>>(), "{}", strategy.name());
+
+                if strategy.name() == "grouped" {
+                    // Docs lead, then whole import groups, with the groups docs import first.
+                    let is_code = |i: &usize| !is_doc_file(&files[*i].path);
+                    let first_code = order.iter().position(is_code).unwrap_or(order.len());
+                    prop_assert!(order[first_code..].iter().all(is_code));
+                    let groups: Vec<usize> =
+                        order[first_code..].iter().map(|i| notes.component[i]).collect();
+                    prop_assert!(groups.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", groups);
+                    let pinned_groups: HashSet<usize> =
+                        notes.pinned.iter().map(|i| notes.component[i]).collect();
+                    let last_pinned = pinned_groups.iter().max();
+                    let first_unpinned = groups.iter().find(|group| !pinned_groups.contains(group));
+                    if let (Some(last), Some(first)) = (last_pinned, first_unpinned) {
+                        prop_assert!(last < first, "pinned groups lead: {:?}", groups);
+                    }
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn pack_files_packs_every_file_once_within_budget(
+            files in generated_files(),
+            num_packs in 1usize..6,
+            budget in prop::option::of(1usize..20_000),
+            dependency in any::<bool>(),
+        ) {
+            let strategy = if dependency { "dependency" } else { "grouped" };
+            let paths: Vec<String> = files.iter().map(|f| f.path.clone()).collect();
+            let total: usize = files.iter().map(|f| f.token_count.unwrap_or(0)).sum();
+            let request = plain_request(files, num_packs, budget, strategy);
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            let response = runtime.block_on(pack_files(request)).unwrap();
+
+            let mut packed: Vec<String> =
+                response.packs.iter().flat_map(|p| p.file_paths.clone()).collect();
+            let placed: Vec<String> = response.placements.iter().map(|p| p.path.clone()).collect();
+            prop_assert_eq!(&placed, &packed);
+            packed.sort();
+            let mut expected = paths;
+            expected.sort();
+            prop_assert_eq!(packed, expected);
+
+            prop_assert_eq!(response.total_tokens, total);
+            let pack_tokens: usize = response.packs.iter().map(|p| p.estimated_tokens).sum();
+            prop_assert_eq!(pack_tokens, total);
+
+            match budget {
+                Some(budget) => {
+                    for pack in &response.packs {
+                        prop_assert!(pack.file_count == 1 || pack.estimated_tokens <= budget);
+                    }
+                }
+                None => prop_assert!(response.packs.len() <= num_packs),
+            }
+            for placement in &response.placements {
+                let ordinal = response.packs.iter().position(|p| p.index == placement.pack_index).unwrap();
+                prop_assert_eq!(placement.spillover, ordinal >= num_packs);
+            }
+        }
     }
 }
@@ -0,0 +1,476 @@
+use crate::commands::fs::resolve_in_project;
+use crate::commands::undo::save_undo_snapshot;
+use crate::models::{PatchFileResult, PatchHunkResult, PatchReport};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Header path meaning "no file": the diff creates or deletes it.
+const DEV_NULL: &str = "/dev/null";
+
+#[derive(Debug, Default, PartialEq)]
+struct Hunk {
+    header: String,
+    /// 1-based; for a pure insertion, the line the new lines follow.
+    old_start: usize,
+    /// Context and removed lines: what the file must contain.
+    old_lines: Vec<String>,
+    /// Context and added lines: what replaces them.
+    new_lines: Vec<String>,
+    old_missing_newline: bool,
+    new_missing_newline: bool,
+}
+
+#[derive(Debug, PartialEq)]
+struct FilePatch {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+/// Path from a `---`/`+++` line, without the git `a/`/`b/` prefix or a trailing timestamp.
+fn header_path(rest: &str) -> Option<String> {
+    let path = rest.split('\t').next().unwrap_or_default().trim_end();
+    if path.is_empty() || path == DEV_NULL {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// `start[,count]` from a hunk header; the count defaults to 1.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// The hunk whose header is `lines[start]`, and the index of the line after it.
+fn parse_hunk(lines: &[&str], start: usize) -> Result<(Hunk, usize), String> {
+    let header = lines[start];
+    let invalid = || format!("Invalid hunk header: {header}");
+    let ranges = header
+        .strip_prefix("@@ -")
+        .and_then(|rest| rest.split(" @@").next())
+        .ok_or_else(invalid)?;
+    let (old, new) = ranges.split_once(" +").ok_or_else(invalid)?;
+    let (old_start, mut old_left) = parse_range(old).ok_or_else(invalid)?;
+    let (_, mut new_left) = parse_range(new).ok_or_else(invalid)?;
+
+    let mut hunk = Hunk { header: header.to_string(), old_start, ..Hunk::default() };
+    let mut index = start + 1;
+    let mut last_kind = ' ';
+    loop {
+        let Some(line) = lines.get(index) else {
+            if old_left > 0 || new_left > 0 {
+                return Err(format!("Hunk ends early: {header}"));
+            }
+            break;
+        };
+        // Models often drop the single space that marks a blank context line.
+        let kind = line.chars().next().unwrap_or(' ');
+        let text = line.get(1..).unwrap_or_default();
+        match kind {
+            '\\' => {
+                if last_kind != '+' {
+                    hunk.old_missing_newline = true;
+                }
+                if last_kind != '-' {
+                    hunk.new_missing_newline = true;
+                }
+            }
+            _ if old_left == 0 && new_left == 0 => break,
+            ' ' if old_left > 0 && new_left > 0 => {
+                hunk.old_lines.push(text.to_string());
+                hunk.new_lines.push(text.to_string());
+                old_left -= 1;
+                new_left -= 1;
+            }
+            '-' if old_left > 0 => {
+                hunk.old_lines.push(text.to_string());
+                old_left -= 1;
+            }
+            '+' if new_left > 0 => {
+                hunk.new_lines.push(text.to_string());
+                new_left -= 1;
+            }
+            _ => return Err(format!("Unexpected line in hunk {header}: {line}")),
+        }
+        last_kind = kind;
+        index += 1;
+    }
+    Ok((hunk, index))
+}
+
+/// Every file section in `text`. Anything outside them, such as prose or code fences
+/// around a model's answer, is ignored.
+fn parse_unified_diff(text: &str) -> Result<Vec<FilePatch>, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut patches = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let headers = lines[index]
+            .strip_prefix("--- ")
+            .zip(lines.get(index + 1).and_then(|line| line.strip_prefix("+++ ")));
+        let Some((old, new)) = headers else {
+            index += 1;
+            continue;
+        };
+        let mut patch = FilePatch { old_path: header_path(old), new_path: header_path(new), hunks: Vec::new() };
+        index += 2;
+        while lines.get(index).is_some_and(|line| line.starts_with("@@ ")) {
+            let (hunk, next) = parse_hunk(&lines, index)?;
+            patch.hunks.push(hunk);
+            index = next;
+        }
+        if patch.old_path.is_none() && patch.new_path.is_none() {
+            return Err("Diff section names no file".to_string());
+        }
+        if patch.hunks.is_empty() {
+            let path = patch.new_path.or(patch.old_path).unwrap_or_default();
+            return Err(format!("Diff for {path} has no hunks"));
+        }
+        patches.push(patch);
+    }
+    if patches.is_empty() {
+        return Err("No unified diff found".to_string());
+    }
+    Ok(patches)
+}
+
+/// A file as lines, remembering its line ending so untouched lines round-trip.
+struct Document {
+    lines: Vec<String>,
+    eol: &'static str,
+    trailing_newline: bool,
+}
+
+impl Document {
+    fn parse(content: &str) -> Self {
+        Self {
+            lines: content.lines().map(str::to_string).collect(),
+            eol: if content.contains("\r\n") { "\r\n" } else { "\n" },
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = self.lines.join(self.eol);
+        if self.trailing_newline && !self.lines.is_empty() {
+            out.push_str(self.eol);
+        }
+        out
+    }
+}
+
+/// Where `needle` occurs in `lines` at or after `floor`, nearest `expected` first.
+fn locate(lines: &[String], needle: &[String], expected: usize, floor: usize) -> Option<usize> {
+    let last = lines.len().checked_sub(needle.len())?;
+    if floor > last {
+        return None;
+    }
+    let expected = expected.clamp(floor, last);
+    let matches_at = |at: usize| lines[at..at + needle.len()] == *needle;
+    (0..=(expected - floor).max(last - expected)).find_map(|distance| {
+        [expected.checked_sub(distance), Some(expected + distance)]
+            .into_iter()
+            .flatten()
+            .find(|&at| at >= floor && at <= last && matches_at(at))
+    })
+}
+
+/// Apply `hunks` in order. Later hunks are searched from the offset the previous one
+/// matched at, and never before the end of that match.
+fn apply_hunks(doc: &mut Document, hunks: &[Hunk]) -> Vec<PatchHunkResult> {
+    // Lines added minus lines removed by the hunks applied so far.
+    let mut shift: i64 = 0;
+    let mut last_offset: i64 = 0;
+    let mut floor = 0;
+    hunks
+        .iter()
+        .map(|hunk| {
+            let start = if hunk.old_lines.is_empty() { hunk.old_start } else { hunk.old_start.saturating_sub(1) };
+            let stated = start as i64 + shift;
+            let expected = (stated + last_offset).max(0) as usize;
+            let Some(at) = locate(&doc.lines, &hunk.old_lines, expected, floor) else {
+                return PatchHunkResult {
+                    header: hunk.header.clone(),
+                    applied: false,
+                    offset: 0,
+                    message: Some("Context lines not found in the file".to_string()),
+                };
+            };
+            let reaches_end = at + hunk.old_lines.len() == doc.lines.len();
+            doc.lines.splice(at..at + hunk.old_lines.len(), hunk.new_lines.iter().cloned());
+            if reaches_end {
+                if hunk.new_missing_newline {
+                    doc.trailing_newline = false;
+                } else if hunk.old_missing_newline {
+                    doc.trailing_newline = true;
+                }
+            }
+            let offset = at as i64 - stated;
+            shift += hunk.new_lines.len() as i64 - hunk.old_lines.len() as i64;
+            last_offset = offset;
+            floor = at + hunk.new_lines.len();
+            PatchHunkResult {
+                header: hunk.header.clone(),
+                applied: true,
+                offset,
+                message: None,
+            }
+        })
+        .collect()
+}
+
+enum Change {
+    Write(PathBuf, String),
+    Delete(PathBuf),
+}
+
+fn file_error(path: &str, error: String) -> (PatchFileResult, Option<Change>) {
+    let result = PatchFileResult {
+        path: path.to_string(),
+        status: "conflict".to_string(),
+        hunks: Vec::new(),
+        error: Some(error),
+    };
+    (result, None)
+}
+
+/// Check `patch` against the file on disk and work out the change, without writing.
+fn plan_file(root: &str, patch: &FilePatch) -> (PatchFileResult, Option<Change>) {
+    let path = patch.new_path.as_deref().or(patch.old_path.as_deref()).unwrap_or_default();
+    if let (Some(old), Some(new)) = (&patch.old_path, &patch.new_path)
+        && old != new
+    {
+        return file_error(path, format!("Renames are not supported: {old} → {new}"));
+    }
+    let target = match resolve_in_project(root, path) {
+        Ok(target) => target,
+        Err(e) => return file_error(path, e),
+    };
+    let mut doc = if patch.old_path.is_none() {
+        if target.exists() {
+            return file_error(path, "Diff creates a file that already exists".to_string());
+        }
+        Document { lines: Vec::new(), eol: "\n", trailing_newline: true }
+    } else {
+        match std::fs::read_to_string(&target) {
+            Ok(content) => Document::parse(&content),
+            Err(e) => return file_error(path, format!("Failed to read {path}: {e}")),
+        }
+    };
+
+    let hunks = apply_hunks(&mut doc, &patch.hunks);
+    let clean = hunks.iter().all(|hunk| hunk.applied);
+    let (status, change) = match (clean, patch.new_path.is_none()) {
+        (false, _) => ("conflict", None),
+        (true, true) if !doc.lines.is_empty() => {
+            let result = PatchFileResult {
+                path: path.to_string(),
+                status: "conflict".to_string(),
+                hunks,
+                error: Some("File still has content after the diff deletes it".to_string()),
+            };
+            return (result, None);
+        }
+        (true, true) => ("deleted", Some(Change::Delete(target))),
+        (true, false) if patch.old_path.is_none() => ("created", Some(Change::Write(target, doc.render()))),
+        (true, false) => ("modified", Some(Change::Write(target, doc.render()))),
+    };
+    let result = PatchFileResult { path: path.to_string(), status: status.to_string(), hunks, error: None };
+    (result, change)
+}
+
+fn apply_patch(root: &str, patch_text: &str, dry_run: bool) -> Result<PatchReport, String> {
+    let patches = parse_unified_diff(patch_text)?;
+    let mut seen = HashSet::new();
+    let planned: Vec<_> = patches
+        .iter()
+        .map(|patch| {
+            let path = patch.new_path.as_deref().or(patch.old_path.as_deref()).unwrap_or_default();
+            if seen.insert(path.to_string()) {
+                plan_file(root, patch)
+            } else {
+                file_error(path, "File appears more than once in the diff".to_string())
+            }
+        })
+        .collect();
+
+    let clean = planned.iter().all(|(_, change)| change.is_some());
+    let written = clean && !dry_run;
+    let mut files = Vec::with_capacity(planned.len());
+    for (result, change) in planned {
+        if written {
+            let outcome = match change {
+                Some(Change::Write(target, content)) => save_undo_snapshot(&target).and_then(|()| {
+                    target
+                        .parent()
+                        .map_or(Ok(()), std::fs::create_dir_all)
+                        .and_then(|()| std::fs::write(&target, content))
+                        .map_err(|e| e.to_string())
+                }),
+                Some(Change::Delete(target)) => save_undo_snapshot(&target)
+                    .and_then(|()| std::fs::remove_file(&target).map_err(|e| e.to_string())),
+                None => Ok(()),
+            };
+            outcome.map_err(|e| {
+                format!("Failed to write {} after {} file(s) were changed: {e}", result.path, files.len())
+            })?;
+        }
+        files.push(result);
+    }
+    Ok(PatchReport { files, clean, written })
+}
+
+/// Check a unified diff (e.g. from an LLM's answer) against files under the open project
+/// `root` and report each hunk. Changes are written only when `dry_run` is false and every
+/// hunk matched; a single conflict leaves the project untouched.
+#[tauri::command]
+pub async fn apply_unified_diff(root: String, patch_text: String, dry_run: bool) -> Result<PatchReport, String> {
+    tauri::async_runtime::spawn_blocking(move || apply_patch(&root, &patch_text, dry_run))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn open_project(files: &[(&str, &str)]) -> (PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("bablusheed-patch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (path, content) in files {
+            let file = dir.join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+        }
+        let root = dir.to_string_lossy().to_string();
+        crate::commands::fs::walk_directory_flat(root.clone(), true, Vec::new(), None, None).await.unwrap();
+        (dir, root)
+    }
+
+    // ── parsing ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn parse_skips_prose_and_fences_and_strips_git_prefixes() {
+        let text = "Here is the fix:\n```diff\ndiff --git a/src/a.ts b/src/a.ts\n--- a/src/a.ts\t2024-01-01\n+++ b/src/a.ts\n@@ -1,2 +1,2 @@\n const a = 1;\n-const b = 2;\n+const b = 3;\n```\nDone.";
+        let patches = parse_unified_diff(text).unwrap();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].old_path.as_deref(), Some("src/a.ts"));
+        assert_eq!(patches[0].new_path.as_deref(), Some("src/a.ts"));
+        let hunk = &patches[0].hunks[0];
+        assert_eq!(hunk.old_start, 1);
+        assert_eq!(hunk.old_lines, vec!["const a = 1;", "const b = 2;"]);
+        assert_eq!(hunk.new_lines, vec!["const a = 1;", "const b = 3;"]);
+    }
+
+    #[test]
+    fn parse_accepts_blank_context_lines_without_the_leading_space() {
+        let text = "--- a/x\n+++ b/x\n@@ -1,3 +1,3 @@\n one\n\n-two\n+deux\n";
+        let hunk = &parse_unified_diff(text).unwrap()[0].hunks[0];
+        assert_eq!(hunk.old_lines, vec!["one", "", "two"]);
+        assert_eq!(hunk.new_lines, vec!["one", "", "deux"]);
+    }
+
+    #[test]
+    fn parse_records_missing_newline_markers_per_side() {
+        let text = "--- a/x\n+++ b/x\n@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n";
+        let hunk = &parse_unified_diff(text).unwrap()[0].hunks[0];
+        assert!(hunk.old_missing_newline);
+        assert!(!hunk.new_missing_newline);
+    }
+
+    #[test]
+    fn parse_rejects_text_without_a_diff_and_truncated_hunks() {
+        assert!(parse_unified_diff("no diff here").is_err());
+        assert!(parse_unified_diff("--- a/x\n+++ b/x\n@@ -1,3 +1,3 @@\n one\n").is_err());
+    }
+
+    // ── applying ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn hunks_apply_at_an_offset_and_carry_the_drift_forward() {
+        let mut doc = Document::parse("new top\na\nb\nc\nd\ne\n");
+        let patches = parse_unified_diff(
+            "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n a\n-b\n+B\n@@ -4,2 +4,2 @@\n d\n-e\n+E\n",
+        )
+        .unwrap();
+        let results = apply_hunks(&mut doc, &patches[0].hunks);
+        assert!(results.iter().all(|hunk| hunk.applied));
+        assert_eq!(results[0].offset, 1);
+        assert_eq!(results[1].offset, 1);
+        assert_eq!(doc.render(), "new top\na\nB\nc\nd\nE\n");
+    }
+
+    #[test]
+    fn crlf_files_keep_their_line_endings() {
+        let mut doc = Document::parse("a\r\nb\r\n");
+        let patches = parse_unified_diff("--- a/x\n+++ b/x\n@@ -1,2 +1,3 @@\n a\n+inserted\n b\n").unwrap();
+        apply_hunks(&mut doc, &patches[0].hunks);
+        assert_eq!(doc.render(), "a\r\ninserted\r\nb\r\n");
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_without_writing_then_apply_writes() {
+        let (dir, root) = open_project(&[("src/a.ts", "const a = 1;\nconst b = 2;\n")]).await;
+        let diff = "--- a/src/a.ts\n+++ b/src/a.ts\n@@ -1,2 +1,2 @@\n const a = 1;\n-const b = 2;\n+const b = 3;\n--- /dev/null\n+++ b/src/new.ts\n@@ -0,0 +1 @@\n+export {};\n";
+
+        let report = apply_patch(&root, diff, true).unwrap();
+        assert!(report.clean);
+        assert!(!report.written);
+        assert_eq!(report.files[0].status, "modified");
+        assert_eq!(report.files[1].status, "created");
+        assert_eq!(std::fs::read_to_string(dir.join("src/a.ts")).unwrap(), "const a = 1;\nconst b = 2;\n");
+        assert!(!dir.join("src/new.ts").exists());
+
+        let report = apply_patch(&root, diff, false).unwrap();
+        assert!(report.written);
+        assert_eq!(std::fs::read_to_string(dir.join("src/a.ts")).unwrap(), "const a = 1;\nconst b = 3;\n");
+        assert_eq!(std::fs::read_to_string(dir.join("src/new.ts")).unwrap(), "export {};\n");
+    }
+
+    #[tokio::test]
+    async fn a_conflict_anywhere_leaves_every_file_untouched() {
+        let (dir, root) = open_project(&[("a.ts", "one\n"), ("b.ts", "two\n")]).await;
+        let diff = "--- a/a.ts\n+++ b/a.ts\n@@ -1 +1 @@\n-one\n+ONE\n--- a/b.ts\n+++ b/b.ts\n@@ -1 +1 @@\n-missing\n+TWO\n";
+
+        let report = apply_patch(&root, diff, false).unwrap();
+        assert!(!report.clean);
+        assert!(!report.written);
+        assert_eq!(report.files[0].status, "modified");
+        assert_eq!(report.files[1].status, "conflict");
+        assert!(!report.files[1].hunks[0].applied);
+        assert_eq!(std::fs::read_to_string(dir.join("a.ts")).unwrap(), "one\n");
+    }
+
+    #[tokio::test]
+    async fn deletes_only_when_the_diff_removes_everything() {
+        let (dir, root) = open_project(&[("old.ts", "a\nb\n")]).await;
+        let partial = apply_patch(&root, "--- a/old.ts\n+++ /dev/null\n@@ -1,1 +0,0 @@\n-a\n", false).unwrap();
+        assert_eq!(partial.files[0].status, "conflict");
+        assert!(dir.join("old.ts").exists());
+
+        let full = apply_patch(&root, "--- a/old.ts\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-a\n-b\n", false).unwrap();
+        assert_eq!(full.files[0].status, "deleted");
+        assert!(!dir.join("old.ts").exists());
+    }
+
+    #[tokio::test]
+    async fn paths_outside_the_project_are_refused() {
+        let (_dir, root) = open_project(&[("a.ts", "one\n")]).await;
+        let diff = "--- a/../escape.ts\n+++ b/../escape.ts\n@@ -1 +1 @@\n-one\n+two\n";
+        let report = apply_patch(&root, diff, false).unwrap();
+        assert_eq!(report.files[0].status, "conflict");
+        assert!(report.files[0].error.as_deref().unwrap().contains("inside the project"));
+
+        let unopened = std::env::temp_dir().join(format!("bablusheed-closed-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&unopened).unwrap();
+        let report = apply_patch(&unopened.to_string_lossy(), "--- /dev/null\n+++ b/a.ts\n@@ -0,0 +1 @@\n+x\n", false).unwrap();
+        assert!(report.files[0].error.as_deref().unwrap().contains("not open"));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::models::{LlmProfile, PackProfile, PackRequest};
+
+/// Built-in pack profiles, in display order.
+pub(crate) fn pack_profiles() -> Vec<PackProfile> {
+    vec![
+        PackProfile {
+            id: "claude-review".to_string(),
+            name: "Claude review".to_string(),
+            description: "Markdown packs grouped by component, sized for a 200k context.".to_string(),
+            output_format: "markdown".to_string(),
+            num_packs: 3,
+            max_tokens_per_pack: Some(150_000),
+            strip_imports: false,
+            normalize_content: true,
+            strategy: "grouped".to_string(),
+        },
+        PackProfile {
+            id: "gpt-quick-question".to_string(),
+            name: "GPT quick question".to_string(),
+            description: "One compact plain-text pack without import noise.".to_string(),
+            output_format: "plaintext".to_string(),
+            num_packs: 1,
+            max_tokens_per_pack: None,
+            strip_imports: true,
+            normalize_content: true,
+            strategy: "dependency".to_string(),
+        },
+        PackProfile {
+            id: "full-audit".to_string(),
+            name: "Full audit".to_string(),
+            description: "Everything verbatim, split into as many packs as the budget needs."
+                .to_string(),
+            output_format: "markdown".to_string(),
+            num_packs: 5,
+            max_tokens_per_pack: Some(100_000),
+            strip_imports: false,
+            normalize_content: false,
+            strategy: "grouped".to_string(),
+        },
+    ]
+}
+
+/// Overwrite the request's pack settings with those of `request.profile_id`, if set.
+pub(crate) fn apply_pack_profile(request: &mut PackRequest) -> Result<(), String> {
+    let Some(profile_id) = request.profile_id.as_deref() else {
+        return Ok(());
+    };
+    let profile = pack_profiles()
+        .into_iter()
+        .find(|profile| profile.id == profile_id)
+        .ok_or_else(|| format!("Unknown pack profile: {}", profile_id))?;
+
+    request.output_format = profile.output_format;
+    request.num_packs = profile.num_packs;
+    request.max_tokens_per_pack = profile.max_tokens_per_pack;
+    request.strip_imports = profile.strip_imports;
+    request.normalize_content = profile.normalize_content;
+    request.strategy = profile.strategy;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_pack_profiles() -> Result<Vec<PackProfile>, String> {
+    Ok(pack_profiles())
+}
+
+/// Built-in LLM profiles (ids shared with the frontend's `llm-profiles.ts`), in display order.
+pub(crate) fn llm_profiles() -> Vec<LlmProfile> {
+    let profile = |id: &str, name: &str, context_window_tokens: usize, tokenizer: &str, preferred_format: &str| {
+        LlmProfile {
+            id: id.to_string(),
+            name: name.to_string(),
+            context_window_tokens,
+            tokenizer: tokenizer.to_string(),
+            preferred_format: preferred_format.to_string(),
+        }
+    };
+    vec![
+        profile("chatgpt-5-2", "ChatGPT 5.2", 200_000, "o200k", "markdown"),
+        profile("chatgpt-5-2-extended-thinking", "ChatGPT 5.2 Extended Thinking", 200_000, "o200k", "markdown"),
+        profile("chatgpt-5o-thinking-mini", "ChatGPT 5o Thinking Mini", 128_000, "o200k", "markdown"),
+        profile("gpt-4o", "GPT-4o", 128_000, "o200k", "markdown"),
+        profile("claude-sonnet-4-6-thinking", "Claude Sonnet 4.6 Thinking", 200_000, "approx", "xml"),
+        profile("gemini-3-1-pro", "Gemini 3.1 Pro", 1_048_576, "approx", "markdown"),
+        profile("glm-5", "GLM-5", 128_000, "approx", "markdown"),
+        profile("grok-4-20-beta", "Grok 4.20 Beta", 256_000, "approx", "markdown"),
+        profile("grok-4-expert", "Grok 4 Expert", 256_000, "approx", "markdown"),
+        profile("kimi-k2-5", "Kimi K2.5", 128_000, "approx", "markdown"),
+        profile("minimax-m2-5", "MiniMax M2.5", 128_000, "approx", "markdown"),
+        profile("nova-2-pro", "Nova 2 Pro", 200_000, "approx", "markdown"),
+        profile("qwen-3-5-plus", "Qwen 3.5 Plus", 128_000, "approx", "markdown"),
+        // Local models run with small default contexts; plain text spends the fewest tokens.
+        profile("local-llama-3-1-8b", "Llama 3.1 8B (local)", 8_192, "approx", "plaintext"),
+        profile("local-qwen-2-5-coder-32b", "Qwen 2.5 Coder 32B (local)", 32_768, "approx", "plaintext"),
+    ]
+}
+
+pub(crate) fn find_llm_profile(profile_id: &str) -> Option<LlmProfile> {
+    llm_profiles().into_iter().find(|profile| profile.id == profile_id)
+}
+
+/// Share of a context window held back from the pack budget for what the budget does not
+/// count: the pack header and footer, per-file section markers, the prompt template and the
+/// wrapper text. A sixteenth covers a marker line per file down to files of a few hundred tokens.
+const FRAMING_RESERVE_DIVISOR: usize = 16;
+
+/// Largest per-pack content budget whose framed pack still fits in `window` tokens.
+fn packing_budget(window: usize) -> usize {
+    window - window / FRAMING_RESERVE_DIVISOR
+}
+
+/// Fit the request to its LLM profile, if it names a known one: an empty output format
+/// becomes the profile's preferred one, and the per-pack budget is capped just below the
+/// context window, leaving room for framing, so the pack count follows from the selection's size.
+pub(crate) fn apply_llm_profile(request: &mut PackRequest) -> Option<LlmProfile> {
+    let profile = find_llm_profile(request.llm_profile_id.as_deref()?)?;
+    if request.output_format.trim().is_empty() {
+        request.output_format = profile.preferred_format.clone();
+    }
+    let budget = packing_budget(profile.context_window_tokens);
+    request.max_tokens_per_pack = Some(request.max_tokens_per_pack.map_or(budget, |requested| requested.min(budget)));
+    Some(profile)
+}
+
+#[tauri::command]
+pub async fn list_llm_profiles() -> Result<Vec<LlmProfile>, String> {
+    Ok(llm_profiles())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(profile_id: Option<&str>) -> PackRequest {
+        PackRequest {
+            num_packs: 2,
+            profile_id: profile_id.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    // ── pack_profiles ──
+
+    #[test]
+    fn pack_profiles_have_unique_ids_and_known_settings() {
+        let profiles = pack_profiles();
+        let mut ids: Vec<_> = profiles.iter().map(|p| p.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), profiles.len());
+        for profile in &profiles {
+            assert!(matches!(profile.output_format.as_str(), "plaintext" | "markdown"));
+            assert!(matches!(profile.strategy.as_str(), "grouped" | "dependency"));
+            assert!(profile.num_packs >= 1);
+        }
+    }
+
+    // ── apply_pack_profile ──
+
+    #[test]
+    fn apply_pack_profile_overrides_request_settings() {
+        let mut req = request(Some("gpt-quick-question"));
+        apply_pack_profile(&mut req).unwrap();
+        assert_eq!(req.num_packs, 1);
+        assert!(req.strip_imports);
+        assert_eq!(req.strategy, "dependency");
+    }
+
+    #[test]
+    fn apply_pack_profile_keeps_request_without_profile() {
+        let mut req = request(None);
+        apply_pack_profile(&mut req).unwrap();
+        assert_eq!(req.num_packs, 2);
+        assert_eq!(req.output_format, "plaintext");
+    }
+
+    #[test]
+    fn apply_pack_profile_rejects_unknown_ids() {
+        let mut req = request(Some("nope"));
+        assert!(apply_pack_profile(&mut req).is_err());
+    }
+
+    // ── llm_profiles ──
+
+    #[test]
+    fn llm_profiles_have_unique_ids_and_known_settings() {
+        let profiles = llm_profiles();
+        let mut ids: Vec<_> = profiles.iter().map(|p| p.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), profiles.len());
+        for profile in &profiles {
+            assert!(matches!(profile.preferred_format.as_str(), "plaintext" | "markdown" | "xml"));
+            assert!(matches!(profile.tokenizer.as_str(), "o200k" | "approx"));
+            assert!(profile.context_window_tokens >= 8_192);
+        }
+    }
+
+    // ── apply_llm_profile ──
+
+    #[test]
+    fn apply_llm_profile_caps_the_budget_and_fills_the_format() {
+        let mut req = request(None);
+        req.llm_profile_id = Some("local-llama-3-1-8b".into());
+        req.output_format = String::new();
+        req.max_tokens_per_pack = Some(20_000);
+        assert_eq!(apply_llm_profile(&mut req).map(|p| p.context_window_tokens), Some(8_192));
+        assert_eq!(req.max_tokens_per_pack, Some(7_680));
+        assert_eq!(req.output_format, "plaintext");
+
+        let mut req = request(None);
+        req.llm_profile_id = Some("claude-sonnet-4-6-thinking".into());
+        req.max_tokens_per_pack = Some(50_000);
+        apply_llm_profile(&mut req);
+        assert_eq!(req.max_tokens_per_pack, Some(50_000));
+        assert_eq!(req.output_format, "plaintext");
+
+        let mut req = request(None);
+        req.llm_profile_id = Some("local-llama-3-1-8b".into());
+        req.max_tokens_per_pack = Some(8_000);
+        apply_llm_profile(&mut req);
+        assert_eq!(req.max_tokens_per_pack, Some(7_680), "a budget inside the window still leaves room for framing");
+
+        let mut req = request(None);
+        req.llm_profile_id = Some("o200k".into());
+        assert!(apply_llm_profile(&mut req).is_none());
+        assert_eq!(req.max_tokens_per_pack, None);
+    }
+}
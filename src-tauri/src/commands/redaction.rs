@@ -0,0 +1,152 @@
+use crate::models::{FileContent, RedactionMatch, RedactionReport, RedactionRule};
+use regex::Regex;
+use std::borrow::Cow;
+
+const DEFAULT_REPLACEMENT: &str = "[REDACTED]";
+
+/// The dry-run report lists at most this many matches; the totals still count every one.
+const MAX_REPORTED_MATCHES: usize = 500;
+
+/// Rules whose regexes compiled, in the order they apply.
+pub(crate) struct Redactor {
+    rules: Vec<(Regex, String)>,
+}
+
+impl Redactor {
+    /// Compile `rules`, failing on the first invalid pattern so a typo cannot silently
+    /// leave content unredacted.
+    pub(crate) fn new(rules: &[RedactionRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .filter(|rule| !rule.pattern.is_empty())
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern)
+                    .map_err(|e| format!("Invalid redaction pattern `{}`: {e}", rule.pattern))?;
+                let replacement = rule.replacement.clone().unwrap_or_else(|| DEFAULT_REPLACEMENT.to_string());
+                Ok((regex, replacement))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { rules })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `content` with every rule applied in turn; borrowed when nothing matched.
+    pub(crate) fn redact<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(content);
+        for (regex, replacement) in &self.rules {
+            if let Cow::Owned(replaced) = regex.replace_all(&text, replacement.as_str()) {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+
+    /// What `redact` would replace in `files`. Matches are reported against the original
+    /// content, rule by rule.
+    fn report(&self, files: &[FileContent]) -> RedactionReport {
+        let mut matches = Vec::new();
+        let mut total_matches = 0;
+        let mut files_affected = 0;
+        for file in files {
+            let before = total_matches;
+            for (regex, replacement) in &self.rules {
+                for captures in regex.captures_iter(&file.content) {
+                    total_matches += 1;
+                    if matches.len() == MAX_REPORTED_MATCHES {
+                        continue;
+                    }
+                    let found = captures.get(0).expect("group 0 is the whole match");
+                    let mut expanded = String::new();
+                    captures.expand(replacement, &mut expanded);
+                    matches.push(RedactionMatch {
+                        path: file.path.clone(),
+                        line: file.content[..found.start()].matches('\n').count() + 1,
+                        pattern: regex.as_str().to_string(),
+                        text: found.as_str().to_string(),
+                        replacement: expanded,
+                    });
+                }
+            }
+            if total_matches > before {
+                files_affected += 1;
+            }
+        }
+        RedactionReport { matches, total_matches, files_affected }
+    }
+}
+
+/// Dry run: report what `rules` would redact in `files` before anything is packed or exported.
+#[tauri::command]
+pub async fn preview_redactions(
+    files: Vec<FileContent>,
+    rules: Vec<RedactionRule>,
+) -> Result<RedactionReport, String> {
+    let redactor = Redactor::new(&rules)?;
+    Ok(redactor.report(&files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: Option<&str>) -> RedactionRule {
+        RedactionRule {
+            pattern: pattern.to_string(),
+            replacement: replacement.map(str::to_string),
+        }
+    }
+
+    // ── redact ──
+
+    #[test]
+    fn redact_applies_rules_in_order_with_group_references() {
+        let redactor = Redactor::new(&[
+            rule(r"[a-z0-9-]+\.corp\.example\.com", None),
+            rule(r"customer-(\d+)", Some("customer-$1-anon")),
+        ])
+        .unwrap();
+        assert_eq!(
+            redactor.redact("fetch('https://billing.corp.example.com/customer-42')"),
+            "fetch('https://[REDACTED]/customer-42-anon')"
+        );
+        assert!(matches!(redactor.redact("nothing here"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected_and_empty_ones_skipped() {
+        let err = Redactor::new(&[rule("(unclosed", None)]).err().unwrap();
+        assert!(err.contains("(unclosed"));
+        assert!(Redactor::new(&[rule("", None)]).unwrap().is_empty());
+    }
+
+    // ── preview_redactions ──
+
+    #[tokio::test]
+    async fn preview_redactions_reports_matches_with_lines() {
+        let files = vec![
+            FileContent { path: "a.ts".into(), content: "const host = 'db.internal';\n// ACME Corp\nconst acme = 1;".into(), token_count: None },
+            FileContent { path: "b.ts".into(), content: "clean".into(), token_count: None },
+        ];
+        let report = preview_redactions(files, vec![rule(r"\w+\.internal", None), rule("(?i)acme", Some("Client"))])
+            .await
+            .unwrap();
+        assert_eq!(report.total_matches, 3);
+        assert_eq!(report.files_affected, 1);
+        assert_eq!(
+            report.matches[0],
+            RedactionMatch {
+                path: "a.ts".into(),
+                line: 1,
+                pattern: r"\w+\.internal".into(),
+                text: "db.internal".into(),
+                replacement: "[REDACTED]".into(),
+            }
+        );
+        let lines: Vec<usize> = report.matches.iter().map(|found| found.line).collect();
+        assert_eq!(lines, vec![1, 2, 3]);
+        assert!(preview_redactions(Vec::new(), vec![rule("[", None)]).await.is_err());
+    }
+}
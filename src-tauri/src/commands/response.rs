@@ -0,0 +1,200 @@
+use crate::models::FileContent;
+
+/// Fence languages whose blocks are patches rather than file contents.
+const PATCH_LANGUAGES: &[&str] = &["diff", "patch", "udiff"];
+
+/// Comment styles a block may open with to name its file, as `(prefix, suffix)`. The pack
+/// itself writes `// path`.
+const PATH_COMMENTS: &[(&str, &str)] = &[("//", ""), ("#", ""), ("--", ""), ("/*", "*/"), ("<!--", "-->")];
+
+/// A relative or absolute file path rather than prose or a URL.
+fn looks_like_path(text: &str) -> bool {
+    !text.is_empty()
+        && !text.starts_with('!')
+        && !text.contains(char::is_whitespace)
+        && !text.contains("://")
+        && (text.contains('/') || text.contains('.'))
+        && !text.ends_with('/')
+}
+
+fn clean_path(text: &str) -> Option<String> {
+    let path = text.trim().trim_matches(|c| matches!(c, '`' | '"' | '\'' | '*'));
+    let path = path.strip_prefix("./").unwrap_or(path);
+    looks_like_path(path).then(|| path.to_string())
+}
+
+/// `(fence, info)` when `line` opens a fence: three or more backticks or tildes.
+fn fence_open(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == marker).count();
+    (len >= 3).then(|| (&trimmed[..len], trimmed[len..].trim()))
+}
+
+fn closes_fence(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim();
+    let marker = char::from(fence.as_bytes()[0]);
+    trimmed.starts_with(fence) && trimmed.chars().all(|c| c == marker)
+}
+
+/// `(language, path)` from an info string such as `ts`, `ts:src/a.ts`, `ts path=src/a.ts`,
+/// or `ts src/a.ts`.
+fn parse_info(info: &str) -> (&str, Option<String>) {
+    let mut words = info.split_whitespace();
+    let first = words.next().unwrap_or_default();
+    if let Some((language, path)) = first.split_once(':') {
+        return (language, clean_path(path));
+    }
+    let path = words.find_map(|word| {
+        let value = word
+            .strip_prefix("path=")
+            .or_else(|| word.strip_prefix("file="))
+            .or_else(|| word.strip_prefix("filename="))
+            .or_else(|| word.strip_prefix("title="))
+            .unwrap_or(word);
+        clean_path(value)
+    });
+    (first, path)
+}
+
+/// The path named by a leading comment such as the pack's own `// path` line.
+fn comment_path(line: &str) -> Option<String> {
+    let line = line.trim();
+    PATH_COMMENTS.iter().find_map(|(prefix, suffix)| {
+        let inner = line.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        clean_path(inner)
+    })
+}
+
+/// The path in a heading or label just above a fence, e.g. "**src/a.ts**" or "File: `a.ts`:".
+fn label_path(line: &str) -> Option<String> {
+    let line = line.trim().trim_start_matches('#').trim().trim_end_matches(':');
+    let line = ["File:", "file:", "Path:", "path:"]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .unwrap_or(line);
+    clean_path(line)
+}
+
+/// Files from the fenced code blocks of a model's answer. A block's path comes from its info
+/// string, else a leading path comment (removed from the content), else a label on the line
+/// before the fence. Blocks without a path and diff blocks are skipped; when a path repeats,
+/// the last block wins in the first block's place.
+pub(crate) fn extract_code_blocks(text: &str) -> Vec<FileContent> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut files: Vec<FileContent> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let Some((fence, info)) = fence_open(lines[index]) else {
+            index += 1;
+            continue;
+        };
+        let label = index
+            .checked_sub(1)
+            .map(|previous| lines[previous])
+            .filter(|line| !line.trim().is_empty());
+        let body_start = index + 1;
+        let body_end = (body_start..lines.len())
+            .find(|&at| closes_fence(lines[at], fence))
+            .unwrap_or(lines.len());
+        index = body_end + 1;
+
+        let (language, info_path) = parse_info(info);
+        if PATCH_LANGUAGES.contains(&language) {
+            continue;
+        }
+        let mut body = &lines[body_start..body_end];
+        let path = info_path.or_else(|| {
+            let path = body.first().and_then(|first| comment_path(first))?;
+            body = &body[1..];
+            Some(path)
+        });
+        let Some(path) = path.or_else(|| label.and_then(label_path)) else {
+            continue;
+        };
+
+        let mut content = body.join("\n");
+        content.push('\n');
+        match files.iter_mut().find(|file| file.path == path) {
+            Some(existing) => existing.content = content,
+            None => files.push(FileContent { path, content, token_count: None }),
+        }
+    }
+    files
+}
+
+/// Extract the files a model's answer proposes, ready to review and write back.
+#[tauri::command]
+pub async fn parse_model_response(text: String) -> Result<Vec<FileContent>, String> {
+    Ok(extract_code_blocks(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(text: &str) -> Vec<String> {
+        extract_code_blocks(text).into_iter().map(|file| file.path).collect()
+    }
+
+    // ── path sources ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn the_packs_own_path_comment_names_the_block_and_is_removed() {
+        let files = extract_code_blocks("Updated:\n\n```typescript\n// src/main.ts\nconst a = 1;\n```\n");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/main.ts");
+        assert_eq!(files[0].content, "const a = 1;\n");
+    }
+
+    #[test]
+    fn other_comment_styles_name_the_block() {
+        assert_eq!(paths("```python\n# app/models.py\nx = 1\n```"), vec!["app/models.py"]);
+        assert_eq!(paths("```sql\n-- db/schema.sql\nselect 1;\n```"), vec!["db/schema.sql"]);
+        assert!(paths("```sh\n#!/bin/sh\nls\n```").is_empty());
+        assert_eq!(paths("```css\n/* styles/app.css */\nbody {}\n```"), vec!["styles/app.css"]);
+        assert_eq!(paths("```html\n<!-- public/index.html -->\n<p></p>\n```"), vec!["public/index.html"]);
+    }
+
+    #[test]
+    fn info_strings_name_the_block_and_keep_the_first_line() {
+        let files = extract_code_blocks("```ts:src/a.ts\n// not a path comment\n```\n```rust path=src/lib.rs\nfn main() {}\n```\n```go cmd/main.go\npackage main\n```");
+        assert_eq!(files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), vec!["src/a.ts", "src/lib.rs", "cmd/main.go"]);
+        assert_eq!(files[0].content, "// not a path comment\n");
+    }
+
+    #[test]
+    fn a_label_above_the_fence_names_the_block() {
+        assert_eq!(paths("**src/a.ts**\n```ts\nconst a = 1;\n```"), vec!["src/a.ts"]);
+        assert_eq!(paths("### File: `./lib/b.js`:\n```js\nb();\n```"), vec!["lib/b.js"]);
+    }
+
+    // ── skipping ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn unnamed_blocks_prose_comments_and_diffs_are_skipped() {
+        let text = "Run this:\n```bash\nnpm test\n```\n```python\n# compute the total\nx = 1\n```\n```diff\n// src/a.ts\n-a\n+b\n```";
+        assert!(extract_code_blocks(text).is_empty());
+    }
+
+    #[test]
+    fn longer_fences_and_tildes_contain_shorter_fences() {
+        let files = extract_code_blocks("````markdown\n// docs/README.md\n```sh\nls\n```\n````\n~~~\n// a.txt\nhi\n~~~");
+        assert_eq!(files[0].content, "```sh\nls\n```\n");
+        assert_eq!(files[1].path, "a.txt");
+    }
+
+    #[test]
+    fn a_repeated_path_keeps_the_last_block() {
+        let files = extract_code_blocks("```ts\n// a.ts\nv1\n```\n```ts\n// b.ts\nb\n```\n```ts\n// a.ts\nv2\n```");
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "a.ts");
+        assert_eq!(files[0].content, "v2\n");
+    }
+
+    #[test]
+    fn an_unclosed_fence_runs_to_the_end() {
+        let files = extract_code_blocks("```ts\n// a.ts\nconst a = 1;");
+        assert_eq!(files[0].content, "const a = 1;\n");
+    }
+}
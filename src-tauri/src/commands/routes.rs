@@ -0,0 +1,667 @@
+use crate::commands::pack::{ImportResolver, normalize_path, with_transitive_dependencies};
+use crate::models::{FileContent, RouteHandler};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::LazyLock;
+
+const NEXT_PAGE_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx"];
+const EXPRESS_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs"];
+const MAX_MOUNT_DEPTH: usize = 16;
+
+/// `export async function GET` / `export const POST = ...` in an App Router `route.ts`.
+static NEXT_ROUTE_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"export\s+(?:async\s+)?(?:function|const)\s+(GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS)\b",
+    )
+    .unwrap()
+});
+/// `app.get("/path", ...)`, `router.post('/path', ...)`; receivers are limited to app and
+/// router names so HTTP clients like `axios.get("/api")` are not read as routes.
+static EXPRESS_ROUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b(?:app|server|\w*[Rr]outer)\.(get|post|put|patch|delete|options|head|all)\(\s*['"`](/[^'"`]*)['"`]"#)
+        .unwrap()
+});
+/// `app.use("/api/billing", billingRouter)` or `app.use("/api", require("./api"))`
+static EXPRESS_MOUNT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b(?:app|server|\w*[Rr]outer)\.use\(\s*['"`](/[^'"`]*)['"`]\s*,\s*(?:require\(\s*['"`]([^'"`]+)['"`]\s*\)|([A-Za-z_$][\w$]*))"#)
+        .unwrap()
+});
+/// `import billing from "./billing"` or `const billing = require("./billing")`
+static JS_DEFAULT_BINDING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:import\s+([A-Za-z_$][\w$]*)\s+from|(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*require\()\s*['"`]([^'"`]+)['"`]"#,
+    )
+    .unwrap()
+});
+/// `fetch("/api/users")`, `axios.post('/api/users', body)`, `api.get<User[]>(`/users/${id}`)`;
+/// an absolute origin before the path is skipped and the query string left out.
+static HTTP_CLIENT_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\b(?:fetch|axios(?:\.(get|post|put|patch|delete|head|options))?|(?:ky|\w*[Aa]pi|\w*[Cc]lient|\w*[Hh]ttp)\.(get|post|put|patch|delete|head|options))\s*(?:<[^<>()]*>)?\(\s*['"`](?:https?://[^/'"`]+)?(/[^'"`?#]*)"#,
+    )
+    .unwrap()
+});
+/// `.route("/path", get(show).post(create))`
+static AXUM_ROUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\.route\(\s*"(/[^"]*)""#).unwrap());
+static AXUM_METHOD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(get|post|put|patch|delete|head|options|any)\(").unwrap());
+/// `.nest("/billing", billing::router())`
+static AXUM_NEST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\.nest\(\s*"(/[^"]*)"\s*,\s*(?:\w+::)*?(\w+)::\w+\("#).unwrap());
+
+/// A route declared in a router file, before mount prefixes are applied.
+struct Declared {
+    file: usize,
+    route: String,
+    method: Option<String>,
+    framework: &'static str,
+}
+
+fn join_route(prefix: &str, route: &str) -> String {
+    let joined = format!(
+        "{}/{}",
+        prefix.trim_end_matches('/'),
+        route.trim_start_matches('/')
+    );
+    match joined.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// The route a Next.js `pages/` or `app/` file serves, and whether it is an App Router file.
+fn next_route(path: &str) -> Option<(String, bool)> {
+    let path = path.strip_prefix("src/").unwrap_or(path);
+    let (dir, file) = path.rsplit_once('/')?;
+    let (stem, ext) = file.rsplit_once('.')?;
+    if !NEXT_PAGE_EXTENSIONS.contains(&ext) {
+        return None;
+    }
+
+    if let Some(rest) = dir
+        .strip_prefix("pages")
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+    {
+        let mut segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+        segments.push(stem);
+        if segments.iter().any(|s| s.starts_with('_')) {
+            return None;
+        }
+        if segments.last() == Some(&"index") {
+            segments.pop();
+        }
+        return Some((join_route("", &segments.join("/")), false));
+    }
+
+    let rest = dir
+        .strip_prefix("app")
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))?;
+    if stem != "page" && stem != "route" {
+        return None;
+    }
+    let mut segments = Vec::new();
+    for segment in rest.split('/').filter(|s| !s.is_empty()) {
+        if segment.starts_with('_') {
+            return None;
+        }
+        // Route groups and parallel-route slots don't appear in the URL.
+        let is_group = segment.starts_with('(') && segment.ends_with(')');
+        if !is_group && !segment.starts_with('@') {
+            segments.push(segment);
+        }
+    }
+    Some((join_route("", &segments.join("/")), true))
+}
+
+fn next_routes(idx: usize, file: &FileContent, path: &str, out: &mut Vec<Declared>) {
+    let Some((route, app_router)) = next_route(path) else {
+        return;
+    };
+    let framework = if app_router {
+        "nextjs-app"
+    } else {
+        "nextjs-pages"
+    };
+    let is_route_handler = path
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| name.starts_with("route."));
+    let methods: BTreeSet<&str> = if is_route_handler {
+        NEXT_ROUTE_METHOD
+            .captures_iter(&file.content)
+            .filter_map(|c| c.get(1).map(|m| m.as_str()))
+            .collect()
+    } else {
+        BTreeSet::new()
+    };
+    if methods.is_empty() {
+        out.push(Declared {
+            file: idx,
+            route,
+            method: None,
+            framework,
+        });
+        return;
+    }
+    for method in methods {
+        out.push(Declared {
+            file: idx,
+            route: route.clone(),
+            method: Some(method.to_string()),
+            framework,
+        });
+    }
+}
+
+fn express_routes(idx: usize, file: &FileContent, out: &mut Vec<Declared>) {
+    for c in EXPRESS_ROUTE.captures_iter(&file.content) {
+        let method = &c[1];
+        out.push(Declared {
+            file: idx,
+            route: c[2].to_string(),
+            method: (method != "all").then(|| method.to_uppercase()),
+            framework: "express",
+        });
+    }
+}
+
+fn axum_routes(idx: usize, file: &FileContent, out: &mut Vec<Declared>) {
+    let content = file.content.as_str();
+    let found: Vec<_> = AXUM_ROUTE.captures_iter(content).collect();
+    for (i, c) in found.iter().enumerate() {
+        // Method routers run to the end of the line or the next chained `.route(`.
+        let start = c.get(0).map_or(0, |m| m.end());
+        let next_route = found
+            .get(i + 1)
+            .and_then(|n| n.get(0))
+            .map_or(content.len(), |m| m.start());
+        let end = content[start..next_route]
+            .find('\n')
+            .map_or(next_route, |nl| start + nl);
+        let methods: BTreeSet<&str> = AXUM_METHOD
+            .captures_iter(&content[start..end])
+            .filter_map(|m| m.get(1).map(|m| m.as_str()))
+            .collect();
+        let route = c[1].to_string();
+        if methods.is_empty() || methods.contains("any") {
+            out.push(Declared {
+                file: idx,
+                route,
+                method: None,
+                framework: "axum",
+            });
+            continue;
+        }
+        for method in methods {
+            out.push(Declared {
+                file: idx,
+                route: route.clone(),
+                method: Some(method.to_uppercase()),
+                framework: "axum",
+            });
+        }
+    }
+}
+
+/// `(mounting file, prefix)` for every file another file mounts under a path prefix.
+fn collect_mounts(files: &[FileContent], paths: &[String]) -> HashMap<usize, Vec<(usize, String)>> {
+    let resolver = ImportResolver::new(files, paths);
+    let mut mounts: HashMap<usize, Vec<(usize, String)>> = HashMap::new();
+
+    for (idx, file) in files.iter().enumerate() {
+        let path = &paths[idx];
+        if path.ends_with(".rs") {
+            for c in AXUM_NEST.captures_iter(&file.content) {
+                let module = &c[2];
+                let target = paths.iter().position(|p| {
+                    p == &format!("{module}.rs")
+                        || p.ends_with(&format!("/{module}.rs"))
+                        || p.ends_with(&format!("/{module}/mod.rs"))
+                });
+                if let Some(target) = target.filter(|t| *t != idx) {
+                    mounts
+                        .entry(target)
+                        .or_default()
+                        .push((idx, c[1].to_string()));
+                }
+            }
+            continue;
+        }
+
+        let bindings: HashMap<&str, &str> = JS_DEFAULT_BINDING
+            .captures_iter(&file.content)
+            .filter_map(|c| {
+                let name = c.get(1).or_else(|| c.get(2))?.as_str();
+                Some((name, c.get(3)?.as_str()))
+            })
+            .collect();
+        for c in EXPRESS_MOUNT.captures_iter(&file.content) {
+            let specifier = match (c.get(2), c.get(3)) {
+                (Some(spec), _) => Some(spec.as_str()),
+                (None, Some(name)) => bindings.get(name.as_str()).copied(),
+                _ => None,
+            };
+            let Some(specifier) = specifier else {
+                continue;
+            };
+            for target in resolver.resolve(specifier, path) {
+                if target != idx {
+                    mounts
+                        .entry(target)
+                        .or_default()
+                        .push((idx, c[1].to_string()));
+                }
+            }
+        }
+    }
+    mounts
+}
+
+/// Every full prefix `file` is served under, following mounts up to the unmounted root.
+fn mount_prefixes(
+    file: usize,
+    mounts: &HashMap<usize, Vec<(usize, String)>>,
+    visiting: &mut HashSet<usize>,
+) -> Vec<String> {
+    let Some(parents) = mounts.get(&file) else {
+        return vec![String::new()];
+    };
+    if visiting.len() >= MAX_MOUNT_DEPTH || !visiting.insert(file) {
+        return Vec::new();
+    }
+    let mut prefixes = Vec::new();
+    for (parent, prefix) in parents {
+        for outer in mount_prefixes(*parent, mounts, visiting) {
+            prefixes.push(join_route(&outer, prefix));
+        }
+    }
+    visiting.remove(&file);
+    prefixes
+}
+
+/// Map each route declared by Next.js `pages/` and `app/` files, Express registrations and
+/// Axum routers onto the file that handles it, with mount prefixes applied.
+pub(crate) fn map_route_handlers(files: &[FileContent]) -> Vec<RouteHandler> {
+    let paths: Vec<String> = files.iter().map(|f| normalize_path(&f.path)).collect();
+    let mut declared = Vec::new();
+    for (idx, file) in files.iter().enumerate() {
+        let path = &paths[idx];
+        let ext = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+        if ext == "rs" {
+            axum_routes(idx, file, &mut declared);
+            continue;
+        }
+        let before = declared.len();
+        next_routes(idx, file, path, &mut declared);
+        if declared.len() == before && EXPRESS_EXTENSIONS.contains(&ext) {
+            express_routes(idx, file, &mut declared);
+        }
+    }
+
+    let mounts = collect_mounts(files, &paths);
+    let mut handlers: Vec<RouteHandler> = declared
+        .into_iter()
+        .flat_map(|d| {
+            let prefixes = if d.framework.starts_with("nextjs") {
+                vec![String::new()]
+            } else {
+                mount_prefixes(d.file, &mounts, &mut HashSet::new())
+            };
+            prefixes.into_iter().map(move |prefix| RouteHandler {
+                route: join_route(&prefix, &d.route),
+                method: d.method.clone(),
+                file: files[d.file].path.clone(),
+                framework: d.framework.to_string(),
+            })
+        })
+        .collect();
+    handlers.sort_by(|a, b| (&a.route, &a.method, &a.file).cmp(&(&b.route, &b.method, &b.file)));
+    handlers.dedup();
+    handlers
+}
+
+fn is_catch_all(segment: &str) -> bool {
+    segment.starts_with("[...")
+        || segment.starts_with("[[...")
+        || segment.starts_with('*')
+        || segment.starts_with("{*")
+}
+
+fn is_dynamic(segment: &str) -> bool {
+    segment.starts_with('[') || segment.starts_with(':') || segment.starts_with('{')
+}
+
+/// Whether `pattern` is `query` or sits behind it: `/api/billing` covers
+/// `/api/billing/[id]/refund`, and `/api/billing/42` matches `/api/billing/:id`.
+fn route_is_behind(pattern: &str, query: &str) -> bool {
+    let mut pattern = pattern.split('/').filter(|s| !s.is_empty());
+    for wanted in query.split('/').filter(|s| !s.is_empty()) {
+        match pattern.next() {
+            Some(segment) if is_catch_all(segment) => return true,
+            Some(segment) if segment == wanted || is_dynamic(segment) => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Whether a client request path hits `pattern` exactly; `${id}` segments in the request
+/// stand for any one segment.
+fn request_matches_route(pattern: &str, request: &str) -> bool {
+    let mut pattern = pattern.split('/').filter(|s| !s.is_empty());
+    for wanted in request.split('/').filter(|s| !s.is_empty()) {
+        match pattern.next() {
+            Some(segment) if is_catch_all(segment) => return true,
+            Some(segment) if segment == wanted || is_dynamic(segment) || wanted.contains("${") => {}
+            _ => return false,
+        }
+    }
+    pattern.next().is_none_or(|segment| segment.starts_with("[[..."))
+}
+
+/// For every file, the files handling the HTTP routes it calls with `fetch`, axios or an API
+/// client, matched on path and, where both sides name one, method.
+pub(crate) fn client_route_dependencies(files: &[FileContent]) -> Vec<Vec<usize>> {
+    let mut dependencies = vec![Vec::new(); files.len()];
+    let calls: Vec<(usize, Option<String>, String)> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| {
+            let ext = file.path.rsplit_once('.').map_or("", |(_, ext)| ext);
+            EXPRESS_EXTENSIONS.contains(&ext)
+        })
+        .flat_map(|(idx, file)| {
+            HTTP_CLIENT_CALL.captures_iter(&file.content).map(move |c| {
+                let method = c.get(1).or_else(|| c.get(2)).map(|m| m.as_str().to_uppercase());
+                (idx, method, c[3].to_string())
+            })
+        })
+        .collect();
+    if calls.is_empty() {
+        return dependencies;
+    }
+
+    let file_index: HashMap<&str, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(idx, file)| (file.path.as_str(), idx))
+        .collect();
+    let handlers = map_route_handlers(files);
+    for (caller, method, request) in calls {
+        for handler in &handlers {
+            let method_fits = match (&method, &handler.method) {
+                (Some(wanted), Some(served)) => wanted == served,
+                _ => true,
+            };
+            let Some(&target) = file_index.get(handler.file.as_str()) else {
+                continue;
+            };
+            if method_fits
+                && target != caller
+                && request_matches_route(&handler.route, &request)
+                && !dependencies[caller].contains(&target)
+            {
+                dependencies[caller].push(target);
+            }
+        }
+    }
+    dependencies
+}
+
+/// Handlers behind `route`, their App Router layouts, and everything they import.
+fn files_behind_route(route: &str, files: &[FileContent]) -> Result<Vec<String>, String> {
+    let handlers: Vec<RouteHandler> = map_route_handlers(files)
+        .into_iter()
+        .filter(|h| route_is_behind(&h.route, route))
+        .collect();
+    if handlers.is_empty() {
+        return Err(format!("No route handlers found behind {route}"));
+    }
+
+    let paths: Vec<String> = files.iter().map(|f| normalize_path(&f.path)).collect();
+    let mut seeds: Vec<usize> = Vec::new();
+    for handler in &handlers {
+        let handler_path = normalize_path(&handler.file);
+        seeds.extend(paths.iter().position(|p| p == &handler_path));
+        if handler.framework == "nextjs-app" {
+            let mut dir = handler_path.as_str();
+            while let Some((parent, _)) = dir.rsplit_once('/') {
+                dir = parent;
+                seeds.extend(paths.iter().enumerate().filter_map(|(idx, p)| {
+                    let (layout_dir, name) = p.rsplit_once('/')?;
+                    (layout_dir == dir && name.starts_with("layout.")).then_some(idx)
+                }));
+            }
+        }
+    }
+    Ok(with_transitive_dependencies(files, &seeds))
+}
+
+/// List the web routes the selection declares and the file handling each one.
+#[tauri::command]
+pub async fn map_routes(files: Vec<FileContent>) -> Result<Vec<RouteHandler>, String> {
+    Ok(map_route_handlers(&files))
+}
+
+/// Every file needed to pack the handlers behind a route prefix (e.g. `/api/billing`): the
+/// handlers themselves plus everything they transitively import.
+#[tauri::command]
+pub async fn files_for_route(
+    route: String,
+    files: Vec<FileContent>,
+) -> Result<Vec<String>, String> {
+    files_behind_route(&route, &files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> FileContent {
+        FileContent {
+            path: path.into(),
+            content: content.into(),
+            token_count: None,
+        }
+    }
+
+    fn routes(files: &[FileContent]) -> Vec<(String, Option<String>, String)> {
+        map_route_handlers(files)
+            .into_iter()
+            .map(|h| (h.route, h.method, h.file))
+            .collect()
+    }
+
+    fn route(route: &str, method: Option<&str>, file: &str) -> (String, Option<String>, String) {
+        (
+            route.to_string(),
+            method.map(str::to_string),
+            file.to_string(),
+        )
+    }
+
+    // ── next.js ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn pages_router_files_map_to_their_routes() {
+        let files = [
+            file("pages/index.tsx", ""),
+            file("pages/api/billing/[id].ts", ""),
+            file("src/pages/about/index.tsx", ""),
+            file("pages/_app.tsx", ""),
+            file("components/Button.tsx", ""),
+        ];
+        assert_eq!(
+            routes(&files),
+            vec![
+                route("/", None, "pages/index.tsx"),
+                route("/about", None, "src/pages/about/index.tsx"),
+                route("/api/billing/[id]", None, "pages/api/billing/[id].ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn app_router_skips_groups_and_reads_exported_methods() {
+        let files = [
+            file(
+                "app/(shop)/cart/page.tsx",
+                "export default function Cart() {}",
+            ),
+            file(
+                "app/api/billing/route.ts",
+                "export async function GET() {}\nexport const POST = handler;",
+            ),
+            file("app/api/billing/helpers.ts", "export function GET() {}"),
+            file("app/_internal/page.tsx", ""),
+        ];
+        assert_eq!(
+            routes(&files),
+            vec![
+                route("/api/billing", Some("GET"), "app/api/billing/route.ts"),
+                route("/api/billing", Some("POST"), "app/api/billing/route.ts"),
+                route("/cart", None, "app/(shop)/cart/page.tsx"),
+            ]
+        );
+    }
+
+    // ── express ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn express_routes_pick_up_mount_prefixes() {
+        let files = [
+            file(
+                "src/server.ts",
+                "import billing from \"./routes/billing\";\napp.use(\"/api/billing\", billing);\napp.get(\"/health\", ok);",
+            ),
+            file(
+                "src/routes/billing.ts",
+                "router.get(\"/\", list);\nrouter.post('/:id/refund', refund);",
+            ),
+            file(
+                "src/routes/users.js",
+                "const admin = require(\"./admin\");\nrouter.use(\"/admin\", admin);",
+            ),
+            file("src/routes/admin.js", "router.all(\"/stats\", stats);"),
+            file(
+                "src/app.js",
+                "app.use(\"/users\", require(\"./routes/users\"));",
+            ),
+        ];
+        assert_eq!(
+            routes(&files),
+            vec![
+                route("/api/billing", Some("GET"), "src/routes/billing.ts"),
+                route(
+                    "/api/billing/:id/refund",
+                    Some("POST"),
+                    "src/routes/billing.ts"
+                ),
+                route("/health", Some("GET"), "src/server.ts"),
+                route("/users/admin/stats", None, "src/routes/admin.js"),
+            ]
+        );
+    }
+
+    // ── axum ─────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn axum_routers_map_methods_and_nested_modules() {
+        let files = [
+            file(
+                "src/main.rs",
+                "mod billing;\nlet app = Router::new()\n    .route(\"/\", get(index))\n    .nest(\"/api/billing\", billing::router());",
+            ),
+            file(
+                "src/billing.rs",
+                "Router::new().route(\"/{id}\", get(show).delete(remove)).route(\"/\", post(create))",
+            ),
+        ];
+        assert_eq!(
+            routes(&files),
+            vec![
+                route("/", Some("GET"), "src/main.rs"),
+                route("/api/billing", Some("POST"), "src/billing.rs"),
+                route("/api/billing/{id}", Some("DELETE"), "src/billing.rs"),
+                route("/api/billing/{id}", Some("GET"), "src/billing.rs"),
+            ]
+        );
+    }
+
+    // ── files_for_route ──────────────────────────────────────────────────────
+
+    #[test]
+    fn route_prefixes_match_dynamic_and_catch_all_segments() {
+        assert!(route_is_behind("/api/billing/[id]", "/api/billing"));
+        assert!(route_is_behind("/api/billing/:id", "/api/billing/42"));
+        assert!(route_is_behind("/docs/[...slug]", "/docs/a/b"));
+        assert!(!route_is_behind("/api/billing-v2", "/api/billing"));
+        assert!(!route_is_behind("/api", "/api/billing"));
+    }
+
+    // ── client calls ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn request_paths_match_whole_routes() {
+        assert!(request_matches_route("/api/users/[id]", "/api/users/${user.id}"));
+        assert!(request_matches_route("/api/users/:id", "/api/users/42"));
+        assert!(request_matches_route("/docs/[[...slug]]", "/docs"));
+        assert!(!request_matches_route("/api/users/:id", "/api/users"));
+        assert!(!request_matches_route("/api", "/api/users"));
+    }
+
+    #[test]
+    fn client_calls_link_to_route_handlers_across_languages() {
+        let files = [
+            file(
+                "web/src/users.ts",
+                "await fetch(`/api/users/${id}`);\nawait axios.post('https://example.com/api/users?x=1', body);",
+            ),
+            file("web/src/health.ts", "api.get<Health>(\"/health\");\napi.delete(\"/health\");"),
+            file(
+                "server/src/main.rs",
+                "Router::new().route(\"/api/users\", post(create)).route(\"/api/users/{id}\", get(show))",
+            ),
+            file("server/src/health.js", "app.get(\"/health\", ok);"),
+            file("web/src/router.ts", "router.get(\"/local\", handler);"),
+        ];
+        assert_eq!(
+            client_route_dependencies(&files),
+            vec![vec![2], vec![3], vec![], vec![], vec![]]
+        );
+    }
+
+    #[tokio::test]
+    async fn files_behind_a_route_include_imports_and_layouts() {
+        let files = vec![
+            file("src/app/layout.tsx", ""),
+            file(
+                "src/app/api/billing/route.ts",
+                "import { charge } from \"@/lib/stripe\";\nexport async function POST() {}",
+            ),
+            file(
+                "src/app/api/users/route.ts",
+                "import { db } from \"@/lib/db\";\nexport async function GET() {}",
+            ),
+            file("src/lib/stripe.ts", "import { db } from \"./db\";"),
+            file("src/lib/db.ts", ""),
+            file("src/lib/unused.ts", ""),
+        ];
+        assert!(
+            files_behind_route("/admin", &files)
+                .unwrap_err()
+                .contains("No route handlers")
+        );
+        let selected = files_for_route("/api/billing".into(), files).await.unwrap();
+        assert_eq!(
+            selected,
+            vec![
+                "src/app/api/billing/route.ts",
+                "src/app/layout.tsx",
+                "src/lib/db.ts",
+                "src/lib/stripe.ts"
+            ]
+        );
+    }
+}
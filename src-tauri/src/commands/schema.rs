@@ -0,0 +1,505 @@
+use crate::models::{FileContent, SchemaInference};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// The structure shared by the values seen at one position in a document.
+enum Shape {
+    /// Scalar type names, plus `object`/`array` when different kinds met at this position.
+    Types(BTreeSet<&'static str>),
+    Array {
+        min_len: usize,
+        max_len: usize,
+        item: Option<Box<Shape>>,
+    },
+    /// Fields in first-seen order, each with the number of objects that had it.
+    Object {
+        fields: Vec<(String, Shape, usize)>,
+        seen: usize,
+    },
+}
+
+impl Shape {
+    fn of(value: &Value) -> Shape {
+        match value {
+            Value::Null => Shape::Types(BTreeSet::from(["null"])),
+            Value::Bool(_) => Shape::Types(BTreeSet::from(["boolean"])),
+            Value::Number(_) => Shape::Types(BTreeSet::from(["number"])),
+            Value::String(_) => Shape::Types(BTreeSet::from(["string"])),
+            Value::Array(items) => Shape::Array {
+                min_len: items.len(),
+                max_len: items.len(),
+                item: items
+                    .iter()
+                    .map(Shape::of)
+                    .reduce(Shape::merge)
+                    .map(Box::new),
+            },
+            Value::Object(map) => Shape::Object {
+                fields: map
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Shape::of(value), 1))
+                    .collect(),
+                seen: 1,
+            },
+        }
+    }
+
+    fn kind_names(&self) -> BTreeSet<&'static str> {
+        match self {
+            Shape::Types(names) => names.clone(),
+            Shape::Array { .. } => BTreeSet::from(["array"]),
+            Shape::Object { .. } => BTreeSet::from(["object"]),
+        }
+    }
+
+    fn merge(self, other: Shape) -> Shape {
+        match (self, other) {
+            (Shape::Types(mut a), Shape::Types(b)) => {
+                a.extend(b);
+                Shape::Types(a)
+            }
+            (
+                Shape::Array {
+                    min_len,
+                    max_len,
+                    item,
+                },
+                Shape::Array {
+                    min_len: other_min,
+                    max_len: other_max,
+                    item: other_item,
+                },
+            ) => Shape::Array {
+                min_len: min_len.min(other_min),
+                max_len: max_len.max(other_max),
+                item: match (item, other_item) {
+                    (Some(a), Some(b)) => Some(Box::new(a.merge(*b))),
+                    (a, b) => a.or(b),
+                },
+            },
+            (
+                Shape::Object { mut fields, seen },
+                Shape::Object {
+                    fields: other_fields,
+                    seen: other_seen,
+                },
+            ) => {
+                for (key, shape, count) in other_fields {
+                    match fields.iter().position(|(known, _, _)| *known == key) {
+                        Some(i) => {
+                            let (_, existing, existing_count) = fields.remove(i);
+                            fields.insert(i, (key, existing.merge(shape), existing_count + count));
+                        }
+                        None => fields.push((key, shape, count)),
+                    }
+                }
+                Shape::Object {
+                    fields,
+                    seen: seen + other_seen,
+                }
+            }
+            (a, b) => {
+                let mut names = a.kind_names();
+                names.extend(b.kind_names());
+                Shape::Types(names)
+            }
+        }
+    }
+
+    fn render(&self, indent: usize, out: &mut String) {
+        match self {
+            Shape::Types(names) => {
+                out.push_str(&names.iter().copied().collect::<Vec<_>>().join(" | "))
+            }
+            Shape::Array {
+                min_len,
+                max_len,
+                item,
+            } => {
+                if min_len == max_len {
+                    out.push_str(&format!("array[{min_len}]"));
+                } else {
+                    out.push_str(&format!("array[{min_len}..{max_len}]"));
+                }
+                if let Some(item) = item {
+                    out.push_str(" of ");
+                    item.render(indent, out);
+                }
+            }
+            Shape::Object { fields, seen } => {
+                if fields.is_empty() {
+                    out.push_str("{}");
+                    return;
+                }
+                out.push_str("{\n");
+                for (key, shape, count) in fields {
+                    out.push_str(&"  ".repeat(indent + 1));
+                    out.push_str(key);
+                    if count < seen {
+                        out.push('?');
+                    }
+                    out.push_str(": ");
+                    shape.render(indent + 1, out);
+                    out.push('\n');
+                }
+                out.push_str(&"  ".repeat(indent));
+                out.push('}');
+            }
+        }
+    }
+}
+
+// ── YAML outline ──
+//
+// Enough of YAML's block syntax to recover a config's shape: mappings, sequences, scalars,
+// inline `[...]`/`{...}` collections, and `|`/`>` block scalars. Anchors, tags, and multi-line
+// plain scalars are read as strings; only the first document is used.
+
+struct YamlLine<'a> {
+    indent: usize,
+    text: &'a str,
+}
+
+/// Drop a trailing ` # comment` that isn't inside quotes.
+fn strip_yaml_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return &line[..i],
+            None => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+fn yaml_lines(content: &str) -> Vec<YamlLine<'_>> {
+    let mut lines = Vec::new();
+    for raw in content.lines() {
+        let trimmed_start = raw.trim_start();
+        if trimmed_start.starts_with("---") || trimmed_start.starts_with("...") {
+            if lines.is_empty() {
+                continue;
+            }
+            break;
+        }
+        let text = strip_yaml_comment(trimmed_start).trim_end();
+        if text.is_empty() {
+            continue;
+        }
+        lines.push(YamlLine {
+            indent: raw.len() - trimmed_start.len(),
+            text,
+        });
+    }
+    lines
+}
+
+/// Split `key: value` (or `key:`) at the first colon outside quotes that ends the key.
+fn split_yaml_key(text: &str) -> Option<(&str, &str)> {
+    let mut quote: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && i == 0 => quote = Some(c),
+            None if c == ':' => {
+                let rest = &text[i + 1..];
+                if rest.is_empty() || rest.starts_with(' ') {
+                    let key = text[..i].trim().trim_matches(['"', '\'']);
+                    return Some((key, rest.trim()));
+                }
+            }
+            None if c == '[' || c == '{' => return None,
+            None => {}
+        }
+    }
+    None
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+fn yaml_scalar(text: &str) -> Value {
+    let text = text.trim();
+    if text.starts_with('[') || text.starts_with('{') {
+        if let Ok(value) = serde_json::from_str::<Value>(text) {
+            return value;
+        }
+        let inner = text[1..].trim_end_matches([']', '}']).trim();
+        let items = inner
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty());
+        return if text.starts_with('[') {
+            Value::Array(items.map(yaml_scalar).collect())
+        } else {
+            Value::Object(
+                items
+                    .filter_map(|item| {
+                        split_yaml_key(item)
+                            .map(|(key, value)| (key.to_string(), yaml_scalar(value)))
+                    })
+                    .collect(),
+            )
+        };
+    }
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        _ if text.starts_with('"') || text.starts_with('\'') => Value::String(text.to_string()),
+        _ => match text.parse::<f64>() {
+            Ok(number) if number.is_finite() => serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+            _ => Value::String(text.to_string()),
+        },
+    }
+}
+
+/// Parse the node starting at `lines[*pos]`, whose lines sit at `indent`.
+fn yaml_node(lines: &mut [YamlLine], pos: &mut usize, indent: usize) -> Value {
+    if is_sequence_item(lines[*pos].text) {
+        let mut items = Vec::new();
+        while *pos < lines.len()
+            && lines[*pos].indent == indent
+            && is_sequence_item(lines[*pos].text)
+        {
+            let rest = lines[*pos].text[1..].trim_start();
+            if rest.is_empty() {
+                *pos += 1;
+                items.push(yaml_child(lines, pos, indent));
+            } else if is_sequence_item(rest) || split_yaml_key(rest).is_some() {
+                // `- key: value` opens a mapping whose keys line up with `key`.
+                let offset = lines[*pos].text.len() - rest.len();
+                lines[*pos] = YamlLine {
+                    indent: indent + offset,
+                    text: rest,
+                };
+                items.push(yaml_node(lines, pos, indent + offset));
+            } else {
+                *pos += 1;
+                items.push(yaml_scalar(rest));
+            }
+        }
+        return Value::Array(items);
+    }
+
+    let Some(_) = split_yaml_key(lines[*pos].text) else {
+        let value = yaml_scalar(lines[*pos].text);
+        *pos += 1;
+        return value;
+    };
+    let mut map = serde_json::Map::new();
+    while *pos < lines.len() && lines[*pos].indent == indent {
+        let Some((key, rest)) = split_yaml_key(lines[*pos].text) else {
+            break;
+        };
+        let key = key.to_string();
+        *pos += 1;
+        let value = if rest.starts_with('|') || rest.starts_with('>') {
+            while *pos < lines.len() && lines[*pos].indent > indent {
+                *pos += 1;
+            }
+            Value::String(String::new())
+        } else if rest.is_empty() {
+            let same_indent_sequence = *pos < lines.len()
+                && lines[*pos].indent == indent
+                && is_sequence_item(lines[*pos].text);
+            if same_indent_sequence {
+                yaml_node(lines, pos, indent)
+            } else {
+                yaml_child(lines, pos, indent)
+            }
+        } else {
+            yaml_scalar(rest)
+        };
+        map.insert(key, value);
+    }
+    // Skip lines indented deeper than anything that claimed them.
+    while *pos < lines.len() && lines[*pos].indent > indent {
+        *pos += 1;
+    }
+    Value::Object(map)
+}
+
+/// The node nested under a key or `-` at `parent_indent`, or null when nothing is nested.
+fn yaml_child(lines: &mut [YamlLine], pos: &mut usize, parent_indent: usize) -> Value {
+    match lines.get(*pos).map(|line| line.indent) {
+        Some(child_indent) if child_indent > parent_indent => yaml_node(lines, pos, child_indent),
+        _ => Value::Null,
+    }
+}
+
+fn parse_yaml_outline(content: &str) -> Option<Value> {
+    let mut lines = yaml_lines(content);
+    if lines.is_empty() {
+        return None;
+    }
+    let indent = lines[0].indent;
+    let mut pos = 0;
+    Some(yaml_node(&mut lines, &mut pos, indent))
+}
+
+/// `{ ... }`-style outline of a JSON or YAML document: keys, value types, and array lengths.
+fn infer_schema(path: &str, content: &str) -> Option<String> {
+    let lower = path.to_ascii_lowercase();
+    let value = if lower.ends_with(".json") {
+        serde_json::from_str(content).ok()?
+    } else if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+        parse_yaml_outline(content)?
+    } else {
+        return None;
+    };
+    let mut out = String::new();
+    Shape::of(&value).render(0, &mut out);
+    Some(out)
+}
+
+/// Inferred schemas standing in for large JSON/YAML files matched by `inference.globs`, keyed by
+/// index into `files`. Files at or under `inference.min_bytes`, and files that don't parse,
+/// keep their content.
+pub(crate) fn summarize_config_schemas(
+    files: &[FileContent],
+    inference: &SchemaInference,
+) -> HashMap<usize, String> {
+    let patterns: Vec<glob::Pattern> = inference
+        .globs
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+    let mut schemas = HashMap::new();
+    if patterns.is_empty() {
+        return schemas;
+    }
+    for (idx, file) in files.iter().enumerate() {
+        if file.content.len() <= inference.min_bytes
+            || !patterns.iter().any(|p| p.matches(&file.path))
+        {
+            continue;
+        }
+        if let Some(schema) = infer_schema(&file.path, &file.content) {
+            schemas.insert(
+                idx,
+                format!(
+                    "# Inferred schema; the {} bytes of content were omitted. `key?` marks keys only some entries have.\n{schema}\n",
+                    file.content.len()
+                ),
+            );
+        }
+    }
+    schemas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema_of(path: &str, content: &str) -> String {
+        infer_schema(path, content).unwrap()
+    }
+
+    // ── JSON ──
+
+    #[test]
+    fn json_schema_merges_array_items_and_marks_optional_keys() {
+        let content = r#"{"name": "app", "port": 8080, "servers": [
+            {"host": "a", "tags": ["x", "y"]},
+            {"host": "b", "weight": 2, "tags": []},
+            {"host": "c", "tags": ["z"]}
+        ], "extra": null}"#;
+        assert_eq!(
+            schema_of("config.json", content),
+            "{\n  extra: null\n  name: string\n  port: number\n  servers: array[3] of {\n    host: string\n    tags: array[0..2] of string\n    weight?: number\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn mixed_kinds_become_a_union() {
+        assert_eq!(
+            schema_of("a.json", r#"[1, "two", {"x": 1}, null]"#),
+            "array[4] of null | number | object | string"
+        );
+    }
+
+    // ── YAML ──
+
+    #[test]
+    fn yaml_outline_reads_nested_mappings_and_sequences() {
+        let content = "\
+# deployment
+version: 3
+services:
+  web:
+    image: \"nginx:1.25\"  # pinned
+    ports:
+      - 80
+      - 443
+    env:
+    - name: MODE
+      value: prod
+    - name: DEBUG
+      value: false
+    command: |
+      run --port 80
+      --verbose
+  db:
+    image: postgres
+enabled: true
+list: [a, b, c]
+";
+        assert_eq!(
+            schema_of("compose.yml", content),
+            "{\n  enabled: boolean\n  list: array[3] of string\n  services: {\n    db: {\n      image: string\n    }\n    web: {\n      command: string\n      env: array[2] of {\n        name: string\n        value: boolean | string\n      }\n      image: string\n      ports: array[2] of number\n    }\n  }\n  version: number\n}"
+        );
+    }
+
+    #[test]
+    fn yaml_outline_stops_at_the_second_document() {
+        assert_eq!(
+            schema_of("a.yaml", "---\na: 1\n---\nb: 2\n"),
+            "{\n  a: number\n}"
+        );
+        assert_eq!(
+            schema_of("a.yaml", "- 1\n- x\n"),
+            "array[2] of number | string"
+        );
+    }
+
+    // ── summarize_config_schemas ──
+
+    #[test]
+    fn only_large_files_matching_a_glob_are_replaced() {
+        let big = format!("{{\"items\": [{}]}}", vec!["1"; 200].join(","));
+        let files = vec![
+            FileContent {
+                path: "data/big.json".to_string(),
+                content: big.clone(),
+                token_count: None,
+            },
+            FileContent {
+                path: "data/small.json".to_string(),
+                content: "{\"a\": 1}".to_string(),
+                token_count: None,
+            },
+            FileContent {
+                path: "src/big.json".to_string(),
+                content: big,
+                token_count: None,
+            },
+        ];
+        let inference = SchemaInference {
+            globs: vec!["data/**/*.json".to_string()],
+            min_bytes: 100,
+        };
+        let schemas = summarize_config_schemas(&files, &inference);
+        assert_eq!(schemas.len(), 1);
+        assert!(schemas[&0].ends_with("{\n  items: array[200] of number\n}\n"));
+    }
+}
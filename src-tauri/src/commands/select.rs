@@ -0,0 +1,270 @@
+use crate::commands::fs::{git_recency_ranks, read_text_file, walk_allowed_files, MAX_DIRECTORY_FILE_BYTES};
+use crate::commands::pack::{build_dependency_graph, bytes_per_token, estimate_tokens_for_path, normalize_path};
+use crate::commands::tokenizer::TokenCounter;
+use crate::models::{AutoSelectedFile, AutoSelection, FileContent};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Share of a file's score from each signal; they sum to 1.
+const REACHABILITY_WEIGHT: f64 = 0.5;
+const IMPORTANCE_WEIGHT: f64 = 0.3;
+const RECENCY_WEIGHT: f64 = 0.2;
+
+/// `path` relative to `root` with forward slashes, or as given when it lies outside.
+fn relative_to(root: &Path, path: &str) -> String {
+    let relative = Path::new(path)
+        .strip_prefix(root)
+        .map_or_else(|_| path.to_string(), |rest| rest.to_string_lossy().to_string());
+    normalize_path(&relative)
+}
+
+/// Import steps from the nearest entry point to each file; `None` when none reaches it.
+fn entry_distances(dependencies: &[Vec<usize>], entries: &[usize]) -> Vec<Option<usize>> {
+    let mut distances = vec![None; dependencies.len()];
+    let mut queue = VecDeque::new();
+    for &entry in entries {
+        if distances[entry].is_none() {
+            distances[entry] = Some(0);
+            queue.push_back(entry);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let next = distances[node].map(|distance| distance + 1);
+        for &dependency in &dependencies[node] {
+            if distances[dependency].is_none() {
+                distances[dependency] = next;
+                queue.push_back(dependency);
+            }
+        }
+    }
+    distances
+}
+
+/// Score every file (paths relative to the project root) by how close it sits to
+/// `entry_points` in the import graph, how many files import it, and how recently git saw
+/// it change (`recency` ranks, 0 = newest), then take the best that fit `budget` tokens.
+pub(crate) fn select_within_budget(
+    files: &[FileContent],
+    entry_points: &[String],
+    recency: &HashMap<String, usize>,
+    budget: usize,
+) -> (Vec<(usize, f64, String)>, Vec<usize>) {
+    let (normalized_paths, edges, _) = build_dependency_graph(files);
+    let mut dependencies: Vec<Vec<usize>> = vec![Vec::new(); files.len()];
+    for (dependency, dependents) in edges.iter().enumerate() {
+        for &dependent in dependents {
+            dependencies[dependent].push(dependency);
+        }
+    }
+    let entries: Vec<usize> = entry_points
+        .iter()
+        .map(|entry| normalize_path(entry))
+        .filter_map(|entry| normalized_paths.iter().position(|path| *path == entry))
+        .collect();
+    let distances = entry_distances(&dependencies, &entries);
+    let most_dependents = edges.iter().map(|dependents| dependents.len()).max().unwrap_or(0).max(1);
+    let ranked = recency.len().max(1);
+
+    let mut scored: Vec<(usize, f64, String)> = (0..files.len())
+        .map(|idx| {
+            let mut reasons = Vec::new();
+            let reachability = match distances[idx] {
+                Some(0) => {
+                    reasons.push("entry point".to_string());
+                    1.0
+                }
+                Some(steps) => {
+                    reasons.push(format!("reachable from an entry point in {steps} import step(s)"));
+                    1.0 / (steps + 1) as f64
+                }
+                None => 0.0,
+            };
+            let dependents = edges[idx].len();
+            if dependents > 0 {
+                reasons.push(format!("imported by {dependents} file(s)"));
+            }
+            let recency = match recency.get(&normalized_paths[idx]) {
+                Some(&rank) => {
+                    reasons.push(format!("#{} most recently changed", rank + 1));
+                    1.0 - rank as f64 / ranked as f64
+                }
+                None => 0.0,
+            };
+            let score = REACHABILITY_WEIGHT * reachability
+                + IMPORTANCE_WEIGHT * dependents as f64 / most_dependents as f64
+                + RECENCY_WEIGHT * recency;
+            if reasons.is_empty() {
+                reasons.push("fills the remaining budget".to_string());
+            }
+            (idx, score, reasons.join("; "))
+        })
+        .collect();
+    let tokens = |idx: usize| {
+        files[idx].token_count.unwrap_or_else(|| estimate_tokens_for_path(&files[idx].path, &files[idx].content))
+    };
+    // Best first; among equals the smaller file, so more of them fit.
+    scored.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then_with(|| tokens(a.0).cmp(&tokens(b.0)))
+            .then_with(|| normalized_paths[a.0].cmp(&normalized_paths[b.0]))
+    });
+
+    let mut used = 0;
+    let mut selected = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in scored {
+        let file_tokens = tokens(entry.0);
+        if used + file_tokens <= budget {
+            used += file_tokens;
+            selected.push(entry);
+        } else {
+            skipped.push(entry.0);
+        }
+    }
+    (selected, skipped)
+}
+
+/// Pick the highest-value files under `root` that fit `budget` tokens: those the
+/// `entry_points` (relative to `root`, or absolute) import, those many files import, and
+/// those changed recently, each with the reasons it was picked. Every file the walk finds is
+/// a candidate, counted with the tokenizer of `llm_profile_id`; files over
+/// `MAX_DIRECTORY_FILE_BYTES` are not read, so they are sized by their byte count and
+/// contribute no imports.
+#[tauri::command]
+pub async fn auto_select(
+    root: String,
+    entry_points: Vec<String>,
+    budget: usize,
+    llm_profile_id: Option<String>,
+) -> Result<AutoSelection, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let nodes = walk_allowed_files(&root)?;
+        let root_path = PathBuf::from(&root);
+        let counter = TokenCounter::for_profile(llm_profile_id.as_deref());
+        let mut unread = HashSet::new();
+        let files: Vec<FileContent> = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| {
+                let path = relative_to(&root_path, &node.path);
+                let content = (node.size <= MAX_DIRECTORY_FILE_BYTES)
+                    .then(|| read_text_file(Path::new(&node.path)).ok())
+                    .flatten();
+                let Some(content) = content else {
+                    unread.insert(idx);
+                    let tokens = (node.size as f64 / bytes_per_token(&path).unwrap_or(4.0)).ceil() as usize;
+                    return FileContent { path, content: String::new(), token_count: Some(tokens.max(1)) };
+                };
+                let tokens = counter.count(&path, &content);
+                FileContent { path, content, token_count: Some(tokens) }
+            })
+            .collect();
+        let entry_points: Vec<String> = entry_points.iter().map(|entry| relative_to(&root_path, entry)).collect();
+
+        let recency = git_recency_ranks(&root_path);
+        let (selected, skipped) = select_within_budget(&files, &entry_points, &recency, budget);
+        let selected: Vec<AutoSelectedFile> = selected
+            .into_iter()
+            .map(|(idx, score, reason)| AutoSelectedFile {
+                path: nodes[idx].path.clone(),
+                relative_path: files[idx].path.clone(),
+                tokens: files[idx].token_count.unwrap_or_default(),
+                score,
+                reason: if unread.contains(&idx) { format!("{reason}; sized from its byte count") } else { reason },
+            })
+            .collect();
+        let total_tokens = selected.iter().map(|file| file.tokens).sum();
+        let mut summary = format!(
+            "Selected {} of {} file(s), ~{total_tokens} of {budget} tokens; {} left out.",
+            selected.len(),
+            files.len(),
+            skipped.len()
+        );
+        if !unread.is_empty() {
+            summary.push_str(&format!(
+                " {} file(s) over {} KiB were sized from their byte count, without their imports.",
+                unread.len(),
+                MAX_DIRECTORY_FILE_BYTES / 1024
+            ));
+        }
+        Ok(AutoSelection {
+            files: selected,
+            total_tokens,
+            budget,
+            skipped_paths: skipped.into_iter().map(|idx| files[idx].path.clone()).collect(),
+            summary,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> FileContent {
+        FileContent { path: path.into(), content: content.into(), token_count: None }
+    }
+
+    // ── auto_select ──
+
+    #[tokio::test]
+    async fn auto_select_considers_every_walked_file_and_sizes_unread_ones() {
+        let dir = std::env::temp_dir().join(format!("bablusheed-auto-select-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/main.ts"), "import { util } from './util';\nutil();\n").unwrap();
+        std::fs::write(dir.join("src/util.ts"), "export function util() {}\n").unwrap();
+        std::fs::write(dir.join("src/huge.ts"), "x".repeat(MAX_DIRECTORY_FILE_BYTES as usize + 1)).unwrap();
+        let root = dir.to_string_lossy().to_string();
+        assert!(auto_select(root.clone(), Vec::new(), 1_000, None).await.is_err(), "unopened roots are refused");
+        crate::commands::fs::walk_directory_flat(root.clone(), true, Vec::new(), None, None).await.unwrap();
+
+        let selection = auto_select(root.clone(), vec!["src/main.ts".into()], 1_000, Some("gpt-4o".into())).await.unwrap();
+        let paths: Vec<&str> = selection.files.iter().map(|file| file.relative_path.as_str()).collect();
+        assert_eq!(paths, vec!["src/util.ts", "src/main.ts"]);
+        let counter = TokenCounter::for_profile(Some("gpt-4o"));
+        assert_eq!(selection.files[0].tokens, counter.count("src/util.ts", "export function util() {}\n"));
+        assert_eq!(selection.skipped_paths, vec!["src/huge.ts".to_string()]);
+        assert!(selection.summary.starts_with("Selected 2 of 3 file(s)"));
+        assert!(selection.summary.contains("1 file(s) over 1024 KiB were sized from their byte count"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ── select_within_budget ──
+
+    #[test]
+    fn select_within_budget_prefers_reachable_imported_and_recent_files() {
+        let files = vec![
+            file("src/main.ts", "import { util } from './util';\nimport { api } from './api';\n"),
+            file("src/util.ts", "export const util = 1;\n"),
+            file("src/api.ts", "import { util } from './util';\nexport const api = util;\n"),
+            file("scripts/old.ts", &"export const old = 1;\n".repeat(40)),
+            file("scripts/new.ts", "export const fresh = 1;\n"),
+        ];
+        let recency = HashMap::from([("src/api.ts".to_string(), 0), ("scripts/new.ts".to_string(), 1)]);
+        let (selected, skipped) = select_within_budget(&files, &["./src/main.ts".into()], &recency, 60);
+
+        let paths: Vec<&str> = selected.iter().map(|(idx, _, _)| files[*idx].path.as_str()).collect();
+        assert_eq!(paths, vec!["src/api.ts", "src/util.ts", "src/main.ts", "scripts/new.ts"]);
+        assert_eq!(skipped, vec![3]);
+        assert_eq!(
+            selected[0].2,
+            "reachable from an entry point in 1 import step(s); imported by 1 file(s); #1 most recently changed"
+        );
+        assert_eq!(selected[1].2, "reachable from an entry point in 1 import step(s); imported by 2 file(s)");
+        assert_eq!(selected[2].2, "entry point");
+        assert_eq!(selected[3].2, "#2 most recently changed");
+        assert!(selected.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn select_within_budget_fills_around_files_too_large_to_fit() {
+        let files = vec![file("big.ts", &"x".repeat(400)), file("small.ts", "export const s = 1;\n")];
+        let (selected, skipped) = select_within_budget(&files, &["big.ts".into()], &HashMap::new(), 20);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(files[selected[0].0].path, "small.ts");
+        assert_eq!(selected[0].2, "fills the remaining budget");
+        assert_eq!(skipped, vec![0]);
+    }
+}
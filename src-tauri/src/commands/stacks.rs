@@ -0,0 +1,273 @@
+use crate::models::DetectedStack;
+use std::path::Path;
+
+/// Defaults for one kind of project.
+struct StackTemplate {
+    id: &'static str,
+    name: &'static str,
+    /// Any of these paths existing under the root identifies the stack.
+    markers: &'static [&'static str],
+    /// When set, the marker found must also contain this text.
+    marker_contains: Option<&'static str>,
+    /// Extra ignore globs, matched like the user's custom patterns.
+    ignore_patterns: &'static [&'static str],
+    /// Candidate entry points for dead-code analysis; the first that exists is used.
+    entry_points: &'static [&'static str],
+    strategy: &'static str,
+}
+
+const JS_LOCKFILES: &[&str] = &["package-lock.json", "pnpm-lock.yaml", "*.lock"];
+const VITE_ENTRY_POINTS: &[&str] = &["src/main.tsx", "src/main.ts", "src/main.jsx", "src/main.js"];
+
+/// Most specific first: a Tauri app is also a Vite app and a Node package.
+const STACK_TEMPLATES: &[StackTemplate] = &[
+    StackTemplate {
+        id: "tauri",
+        name: "Tauri",
+        markers: &["src-tauri/tauri.conf.json", "src-tauri/Cargo.toml"],
+        marker_contains: None,
+        ignore_patterns: &[
+            "src-tauri/gen",
+            "src-tauri/icons",
+            "package-lock.json",
+            "pnpm-lock.yaml",
+            "*.lock",
+        ],
+        entry_points: VITE_ENTRY_POINTS,
+        strategy: "dependency",
+    },
+    StackTemplate {
+        id: "nextjs",
+        name: "Next.js",
+        markers: &["next.config.js", "next.config.mjs", "next.config.ts"],
+        marker_contains: None,
+        ignore_patterns: &[
+            "out",
+            "public",
+            "next-env.d.ts",
+            "package-lock.json",
+            "pnpm-lock.yaml",
+            "*.lock",
+        ],
+        entry_points: &[
+            "src/app/layout.tsx",
+            "app/layout.tsx",
+            "src/pages/_app.tsx",
+            "pages/_app.tsx",
+            "pages/_app.js",
+        ],
+        strategy: "dependency",
+    },
+    StackTemplate {
+        id: "django",
+        name: "Django",
+        markers: &["manage.py"],
+        marker_contains: Some("django"),
+        ignore_patterns: &[
+            "migrations",
+            "staticfiles",
+            "media",
+            "*.sqlite3",
+            "*.pyc",
+            ".venv",
+            "venv",
+        ],
+        entry_points: &["manage.py"],
+        strategy: "grouped",
+    },
+    StackTemplate {
+        id: "spring",
+        name: "Spring",
+        markers: &["pom.xml", "build.gradle", "build.gradle.kts"],
+        marker_contains: Some("springframework"),
+        ignore_patterns: &[
+            ".mvn",
+            ".gradle",
+            "gradle",
+            "mvnw",
+            "mvnw.cmd",
+            "gradlew",
+            "gradlew.bat",
+        ],
+        entry_points: &[],
+        strategy: "grouped",
+    },
+    StackTemplate {
+        id: "vite",
+        name: "Vite",
+        markers: &["vite.config.ts", "vite.config.js", "vite.config.mjs"],
+        marker_contains: None,
+        ignore_patterns: &["public", "package-lock.json", "pnpm-lock.yaml", "*.lock"],
+        entry_points: VITE_ENTRY_POINTS,
+        strategy: "dependency",
+    },
+    StackTemplate {
+        id: "rust",
+        name: "Rust",
+        markers: &["Cargo.toml"],
+        marker_contains: None,
+        ignore_patterns: &["Cargo.lock"],
+        entry_points: &["src/main.rs", "src/lib.rs"],
+        strategy: "grouped",
+    },
+    StackTemplate {
+        id: "go",
+        name: "Go",
+        markers: &["go.mod"],
+        marker_contains: None,
+        ignore_patterns: &["vendor", "go.sum"],
+        entry_points: &["main.go", "cmd/main.go"],
+        strategy: "grouped",
+    },
+    StackTemplate {
+        id: "python",
+        name: "Python",
+        markers: &["pyproject.toml", "setup.py", "requirements.txt"],
+        marker_contains: None,
+        ignore_patterns: &[
+            ".venv",
+            "venv",
+            "*.egg-info",
+            ".pytest_cache",
+            ".mypy_cache",
+            "*.pyc",
+        ],
+        entry_points: &["main.py", "app.py", "src/main.py"],
+        strategy: "grouped",
+    },
+    StackTemplate {
+        id: "node",
+        name: "Node.js",
+        markers: &["package.json"],
+        marker_contains: None,
+        ignore_patterns: JS_LOCKFILES,
+        entry_points: &[
+            "src/index.ts",
+            "src/index.tsx",
+            "src/index.js",
+            "index.ts",
+            "index.js",
+        ],
+        strategy: "dependency",
+    },
+];
+
+fn matches_template(root: &Path, template: &StackTemplate) -> bool {
+    template.markers.iter().any(|marker| {
+        let path = root.join(marker);
+        match template.marker_contains {
+            Some(needle) => std::fs::read_to_string(&path).is_ok_and(|text| text.contains(needle)),
+            None => path.is_file(),
+        }
+    })
+}
+
+/// The first stack whose markers are present under `root`, with its entry point resolved.
+fn detect(root: &Path) -> Option<DetectedStack> {
+    let template = STACK_TEMPLATES
+        .iter()
+        .find(|template| matches_template(root, template))?;
+    Some(DetectedStack {
+        id: template.id.to_string(),
+        name: template.name.to_string(),
+        ignore_patterns: template
+            .ignore_patterns
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+        entry_point: template
+            .entry_points
+            .iter()
+            .find(|entry| root.join(entry).is_file())
+            .map(|entry| entry.to_string()),
+        strategy: template.strategy.to_string(),
+    })
+}
+
+/// Recognize the project's stack (Next.js, Tauri, Django, Spring, ...) and return its tuned
+/// defaults, or `None` when nothing matches.
+#[tauri::command]
+pub async fn detect_stack(root: String) -> Result<Option<DetectedStack>, String> {
+    let root = std::path::PathBuf::from(root);
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {}", root.display()));
+    }
+    tauri::async_runtime::spawn_blocking(move || detect(&root))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("bablusheed-stack-{}", uuid::Uuid::new_v4()));
+        for (path, content) in files {
+            let file = dir.join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+        }
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn detected_id(files: &[(&str, &str)]) -> Option<String> {
+        detect(&project(files)).map(|stack| stack.id)
+    }
+
+    // ── detect ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn the_most_specific_stack_wins() {
+        let tauri = [
+            ("package.json", "{}"),
+            ("vite.config.ts", ""),
+            ("src-tauri/tauri.conf.json", "{}"),
+        ];
+        assert_eq!(detected_id(&tauri).as_deref(), Some("tauri"));
+        assert_eq!(
+            detected_id(&[("package.json", "{}"), ("next.config.mjs", "")]).as_deref(),
+            Some("nextjs")
+        );
+        assert_eq!(
+            detected_id(&[("package.json", "{}"), ("vite.config.ts", "")]).as_deref(),
+            Some("vite")
+        );
+        assert_eq!(
+            detected_id(&[("package.json", "{}")]).as_deref(),
+            Some("node")
+        );
+        assert_eq!(detected_id(&[("README.md", "")]), None);
+    }
+
+    #[test]
+    fn content_markers_must_mention_the_framework() {
+        let django = [
+            (
+                "manage.py",
+                "os.environ.setdefault('DJANGO_SETTINGS_MODULE', 'site.settings')\nfrom django.core import management",
+            ),
+            ("requirements.txt", ""),
+        ];
+        assert_eq!(detected_id(&django).as_deref(), Some("django"));
+        assert_eq!(
+            detected_id(&[("manage.py", "print('hi')"), ("requirements.txt", "")]).as_deref(),
+            Some("python")
+        );
+
+        let spring = [("pom.xml", "<groupId>org.springframework.boot</groupId>")];
+        assert_eq!(detected_id(&spring).as_deref(), Some("spring"));
+        assert_eq!(detected_id(&[("pom.xml", "<project/>")]), None);
+    }
+
+    #[test]
+    fn the_first_existing_entry_point_is_resolved() {
+        let stack = detect(&project(&[("Cargo.toml", "[package]"), ("src/lib.rs", "")])).unwrap();
+        assert_eq!(stack.entry_point.as_deref(), Some("src/lib.rs"));
+        assert_eq!(stack.ignore_patterns, vec!["Cargo.lock"]);
+
+        let stack = detect(&project(&[("go.mod", "module x")])).unwrap();
+        assert_eq!(stack.entry_point, None);
+    }
+}
@@ -0,0 +1,321 @@
+use serde_json::Value;
+use std::borrow::Cow;
+
+/// One parsed piece of a template.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    /// `{{path}}` or `{{{path}}}`; nothing is HTML-escaped, as in code2prompt.
+    Var(String),
+    /// `{{#each path}}...{{else}}...{{/each}}`
+    Each { path: String, body: Vec<Node>, otherwise: Vec<Node> },
+    /// `{{#if path}}` (or `{{#unless path}}` with `negate`) `...{{else}}...{{/if}}`
+    If { path: String, negate: bool, then: Vec<Node>, otherwise: Vec<Node> },
+}
+
+/// Text between tags, or the inside of a `{{...}}` tag.
+enum Token<'a> {
+    Text(&'a str),
+    Tag(&'a str),
+}
+
+/// Split `template` into text and tags. Comments are dropped and `~` trims the whitespace
+/// on its side of a tag, as in Handlebars.
+fn tokenize(template: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    let mut trim_next = false;
+    while let Some(start) = rest.find("{{") {
+        let (open, close) = if rest[start..].starts_with("{{{") { ("{{{", "}}}") } else { ("{{", "}}") };
+        let inner = &rest[start + open.len()..];
+        // `{{!-- ... --}}` comments may contain `}}`.
+        let end = if inner.trim_start_matches('~').starts_with("!--") {
+            inner.find("--}}").map(|at| at + 2)
+        } else {
+            inner.find(close)
+        }
+        .ok_or_else(|| format!("Unclosed `{open}` in template"))?;
+        let raw = &inner[..end];
+        let body = raw.trim_start_matches('~').trim_end_matches('~').trim();
+
+        let mut text = &rest[..start];
+        if trim_next {
+            text = text.trim_start();
+        }
+        if raw.starts_with('~') {
+            text = text.trim_end();
+        }
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+        if !body.starts_with('!') {
+            tokens.push(Token::Tag(body));
+        }
+        trim_next = raw.ends_with('~');
+        rest = &inner[end + close.len()..];
+    }
+    let text = if trim_next { rest.trim_start() } else { rest };
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+/// Parse nodes up to the closing tag of `block` (or the end, at the top level), returning
+/// the nodes before and after an `{{else}}`.
+fn parse_block<'a>(
+    tokens: &mut impl Iterator<Item = Token<'a>>,
+    block: Option<&str>,
+) -> Result<(Vec<Node>, Vec<Node>), String> {
+    let mut then = Vec::new();
+    let mut otherwise = Vec::new();
+    let mut in_else = false;
+    while let Some(token) = tokens.next() {
+        let nodes = if in_else { &mut otherwise } else { &mut then };
+        let tag = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text.to_string()));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+        if let Some(closed) = tag.strip_prefix('/') {
+            let closed = closed.trim();
+            return match block {
+                Some(open) if open == closed => Ok((then, otherwise)),
+                _ => Err(format!("Unexpected `{{{{/{closed}}}}}` in template")),
+            };
+        }
+        if tag == "else" {
+            if block.is_none() || in_else {
+                return Err("Unexpected `{{else}}` in template".to_string());
+            }
+            in_else = true;
+            continue;
+        }
+        if let Some(open) = tag.strip_prefix('#') {
+            let (helper, path) = open.split_once(char::is_whitespace).unwrap_or((open, ""));
+            let path = path.trim().to_string();
+            if path.is_empty() {
+                return Err(format!("`{{{{#{helper}}}}}` needs an argument"));
+            }
+            if !matches!(helper, "each" | "if" | "unless") {
+                return Err(format!("Unsupported template helper `#{helper}`"));
+            }
+            let (body, rest) = parse_block(tokens, Some(helper))?;
+            nodes.push(match helper {
+                "each" => Node::Each { path, body, otherwise: rest },
+                _ => Node::If { path, negate: helper == "unless", then: body, otherwise: rest },
+            });
+            continue;
+        }
+        if tag.is_empty() || tag.contains(char::is_whitespace) {
+            return Err(format!("Unsupported template expression `{{{{{tag}}}}}`"));
+        }
+        nodes.push(Node::Var(tag.to_string()));
+    }
+    match block {
+        Some(open) => Err(format!("Unclosed `{{{{#{open}}}}}` in template")),
+        None => Ok((then, otherwise)),
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Node>, String> {
+    let mut tokens = tokenize(template)?.into_iter();
+    parse_block(&mut tokens, None).map(|(nodes, _)| nodes)
+}
+
+/// An item's place in the array an `#each` walks, for `@index`, `@first`, and `@last`.
+#[derive(Clone, Copy)]
+struct Position {
+    index: usize,
+    count: usize,
+}
+
+/// One level of the lookup stack: its value and, inside `#each`, the item's position.
+struct Scope<'a> {
+    value: &'a Value,
+    position: Option<Position>,
+}
+
+/// Resolve `this`, `name.field`, `../name`, and `@index`/`@first`/`@last`. Like code2prompt
+/// templates expect, a plain name falls back to enclosing scopes.
+fn lookup<'a>(scopes: &[Scope<'a>], path: &str) -> Option<Cow<'a, Value>> {
+    let mut depth = scopes.len().checked_sub(1)?;
+    let mut path = path;
+    while let Some(rest) = path.strip_prefix("../") {
+        depth = depth.checked_sub(1)?;
+        path = rest;
+    }
+    if let Some(data) = path.strip_prefix('@') {
+        let Position { index, count } = scopes[depth].position?;
+        return match data {
+            "index" => Some(Cow::Owned(Value::from(index))),
+            "first" => Some(Cow::Owned(Value::from(index == 0))),
+            "last" => Some(Cow::Owned(Value::from(index + 1 == count))),
+            _ => None,
+        };
+    }
+    if path == "this" || path == "." {
+        return Some(Cow::Borrowed(scopes[depth].value));
+    }
+    let path = path.strip_prefix("this.").unwrap_or(path);
+    scopes[..=depth]
+        .iter()
+        .rev()
+        .find_map(|scope| path.split('.').try_fold(scope.value, |value, key| value.get(key)))
+        .map(Cow::Borrowed)
+}
+
+/// Handlebars truthiness: `false`, `null`, `""`, `0`, and `[]` are false.
+fn is_truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(flag)) => *flag,
+        Some(Value::String(text)) => !text.is_empty(),
+        Some(Value::Number(number)) => number.as_f64() != Some(0.0),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Object(_)) => true,
+    }
+}
+
+fn render_nodes<'a>(out: &mut String, nodes: &[Node], scopes: &mut Vec<Scope<'a>>) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(path) => match lookup(scopes, path).as_deref() {
+                Some(Value::String(text)) => out.push_str(text),
+                Some(Value::Null) | None => {}
+                Some(value) => out.push_str(&value.to_string()),
+            },
+            Node::If { path, negate, then, otherwise } => {
+                let branch = if is_truthy(lookup(scopes, path).as_deref()) != *negate { then } else { otherwise };
+                render_nodes(out, branch, scopes);
+            }
+            Node::Each { path, body, otherwise } => {
+                let items: Vec<&'a Value> = match lookup(scopes, path) {
+                    Some(Cow::Borrowed(Value::Array(items))) => items.iter().collect(),
+                    Some(Cow::Borrowed(Value::Object(fields))) => fields.values().collect(),
+                    _ => Vec::new(),
+                };
+                if items.is_empty() {
+                    render_nodes(out, otherwise, scopes);
+                }
+                let count = items.len();
+                for (index, value) in items.into_iter().enumerate() {
+                    scopes.push(Scope { value, position: Some(Position { index, count }) });
+                    render_nodes(out, body, scopes);
+                    scopes.pop();
+                }
+            }
+        }
+    }
+}
+
+/// A Handlebars-style pack template, compatible with code2prompt templates. The first
+/// top-level `{{#each files}}` block renders each file section; the text before and after
+/// it opens and closes every pack. A template without one is a file-section template.
+///
+/// Pack-level values: `absolute_code_path`, `source_tree`, `project_name`, `pack_index`,
+/// `total_packs`, `file_count`, and `pack_note` (empty unless pack notes are on). File values: `path`, `extension`, `language`, `content`,
+/// `code` (content in a markdown fence, as code2prompt renders it), `tokens`
+/// (`token_count`), and `id` when file IDs are on.
+pub(crate) struct PackTemplate {
+    header: Vec<Node>,
+    file: Vec<Node>,
+    footer: Vec<Node>,
+}
+
+impl PackTemplate {
+    pub(crate) fn parse(template: &str) -> Result<Self, String> {
+        let mut nodes = parse(template)?;
+        let split = nodes
+            .iter()
+            .position(|node| matches!(node, Node::Each { path, .. } if path == "files" || path == "this.files"));
+        let Some(split) = split else {
+            return Ok(Self { header: Vec::new(), file: nodes, footer: Vec::new() });
+        };
+        let footer = nodes.split_off(split + 1);
+        let Some(Node::Each { body, .. }) = nodes.pop() else {
+            unreachable!("split is an `#each files` node");
+        };
+        Ok(Self { header: nodes, file: body, footer })
+    }
+
+    /// Render the text before the files block with the pack-level `context`.
+    pub(crate) fn push_header(&self, out: &mut String, context: &Value) {
+        render_nodes(out, &self.header, &mut vec![Scope { value: context, position: None }]);
+    }
+
+    /// Render one file section: `file` is looked up first, then the pack `context`.
+    pub(crate) fn push_file(&self, out: &mut String, context: &Value, file: &Value, index: usize, count: usize) {
+        let mut scopes = vec![
+            Scope { value: context, position: None },
+            Scope { value: file, position: Some(Position { index, count }) },
+        ];
+        render_nodes(out, &self.file, &mut scopes);
+    }
+
+    pub(crate) fn push_footer(&self, out: &mut String, context: &Value) {
+        render_nodes(out, &self.footer, &mut vec![Scope { value: context, position: None }]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, context: &Value) -> String {
+        let mut out = String::new();
+        render_nodes(&mut out, &parse(template).unwrap(), &mut vec![Scope { value: context, position: None }]);
+        out
+    }
+
+    #[test]
+    fn renders_variables_blocks_and_positions() {
+        let context = json!({
+            "name": "demo",
+            "files": [{"path": "a.ts", "code": "x"}, {"path": "b.ts", "code": ""}],
+            "empty": [],
+        });
+        assert_eq!(render("{{ name }}/{{{name}}}", &context), "demo/demo");
+        assert_eq!(
+            render("{{#each files}}{{@index}}:{{path}}{{#if code}}={{code}}{{else}}-{{/if}}{{#unless @last}},{{/unless}}{{/each}}", &context),
+            "0:a.ts=x,1:b.ts-"
+        );
+        assert_eq!(render("{{#each files}}{{../name}}/{{this.path}} {{/each}}", &context), "demo/a.ts demo/b.ts ");
+        assert_eq!(render("{{#each empty}}x{{else}}none{{/each}}", &context), "none");
+        assert_eq!(render("a {{!-- {{gone}} --}}b{{! note }} c {{missing}}", &context), "a b c ");
+        assert_eq!(render("a\n  {{~name~}}  \nb", &context), "ademob");
+    }
+
+    #[test]
+    fn rejects_unbalanced_and_unsupported_tags() {
+        assert!(parse("{{#each files}}x").unwrap_err().contains("Unclosed"));
+        assert!(parse("{{#if a}}x{{/each}}").unwrap_err().contains("Unexpected"));
+        assert!(parse("{{#with a}}x{{/with}}").unwrap_err().contains("Unsupported"));
+        assert!(parse("{{lookup a b}}").unwrap_err().contains("Unsupported"));
+        assert!(parse("{{name").unwrap_err().contains("Unclosed"));
+    }
+
+    #[test]
+    fn code2prompt_default_template_splits_around_the_files_block() {
+        let template = PackTemplate::parse(
+            "Project Path: {{ absolute_code_path }}\n\nSource Tree:\n\n```\n{{ source_tree }}\n```\n\n{{#each files}}\n{{#if code}}\n`{{path}}`:\n\n{{code}}\n\n{{/if}}\n{{/each}}\n",
+        )
+        .unwrap();
+        let context = json!({"absolute_code_path": "demo", "source_tree": "a.ts\n"});
+        let mut out = String::new();
+        template.push_header(&mut out, &context);
+        template.push_file(&mut out, &context, &json!({"path": "a.ts", "code": "```ts\nx\n```"}), 0, 1);
+        template.push_footer(&mut out, &context);
+        assert_eq!(out, "Project Path: demo\n\nSource Tree:\n\n```\na.ts\n\n```\n\n\n\n`a.ts`:\n\n```ts\nx\n```\n\n\n\n");
+
+        let block = PackTemplate::parse("## {{path}}\n{{content}}").unwrap();
+        let mut out = String::new();
+        block.push_header(&mut out, &context);
+        block.push_file(&mut out, &context, &json!({"path": "a.ts", "content": "x"}), 0, 1);
+        assert_eq!(out, "## a.ts\nx");
+    }
+}
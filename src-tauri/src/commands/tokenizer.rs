@@ -0,0 +1,80 @@
+use crate::commands::pack::estimate_tokens_for_path;
+use crate::commands::profiles::find_llm_profile;
+use tiktoken_rs::CoreBPE;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Cl100k,
+    O200k,
+}
+
+/// The encoding of an LLM profile's tokenizer; `cl100k` and `o200k` name their encodings
+/// directly.
+fn profile_encoding(profile_id: &str) -> Option<Encoding> {
+    let tokenizer = find_llm_profile(profile_id).map_or_else(|| profile_id.to_string(), |profile| profile.tokenizer);
+    match tokenizer.as_str() {
+        "cl100k" => Some(Encoding::Cl100k),
+        "o200k" => Some(Encoding::O200k),
+        _ => None,
+    }
+}
+
+/// Counts tokens the way the target model will: with its BPE vocabulary when the profile has
+/// one, otherwise with the per-extension length estimate.
+pub(crate) enum TokenCounter {
+    Estimate,
+    Bpe(&'static CoreBPE),
+}
+
+impl TokenCounter {
+    pub(crate) fn for_profile(profile_id: Option<&str>) -> Self {
+        match profile_id.and_then(profile_encoding) {
+            Some(Encoding::Cl100k) => Self::Bpe(tiktoken_rs::cl100k_base_singleton()),
+            Some(Encoding::O200k) => Self::Bpe(tiktoken_rs::o200k_base_singleton()),
+            None => Self::Estimate,
+        }
+    }
+
+    /// Whether counts are real rather than estimated.
+    pub(crate) fn is_exact(&self) -> bool {
+        matches!(self, Self::Bpe(_))
+    }
+
+    pub(crate) fn count(&self, path: &str, text: &str) -> usize {
+        match self {
+            Self::Estimate => estimate_tokens_for_path(path, text),
+            Self::Bpe(bpe) => bpe.encode_ordinary(text).len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── TokenCounter ──
+
+    #[test]
+    fn known_profiles_count_with_their_vocabulary() {
+        let counter = TokenCounter::for_profile(Some("chatgpt-5-2"));
+        assert!(counter.is_exact());
+        assert_eq!(counter.count("a.txt", "hello world"), 2);
+        assert_eq!(counter.count("a.txt", ""), 0);
+
+        let cl100k = TokenCounter::for_profile(Some("cl100k"));
+        assert_eq!(cl100k.count("a.ts", "const x = 1;"), 6);
+    }
+
+    #[test]
+    fn unknown_profiles_fall_back_to_the_estimate() {
+        for profile in [None, Some("gemini-3-1-pro"), Some("")] {
+            let counter = TokenCounter::for_profile(profile);
+            assert!(!counter.is_exact());
+            let text = "fn main() {}\n".repeat(10);
+            assert_eq!(
+                counter.count("main.rs", &text),
+                estimate_tokens_for_path("main.rs", &text)
+            );
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::commands::fs::resolve_write_target;
+use crate::models::UndoResult;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Snapshots kept per file; the oldest is dropped past this.
+const MAX_UNDO_DEPTH: usize = 20;
+
+/// Snapshot file extensions: the previous bytes, or a marker that the write created the file.
+const PREVIOUS_EXT: &str = "prev";
+const ABSENT_EXT: &str = "absent";
+
+/// Set once at startup; writes are not snapshotted before then.
+static UNDO_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Serializes snapshot bookkeeping so concurrent writes never share a sequence number.
+static UNDO_LOCK: Mutex<()> = Mutex::new(());
+
+/// Keep undo snapshots under `dir` (inside the app data directory), creating it if needed.
+pub fn init_undo_store(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let _ = UNDO_DIR.set(dir.to_path_buf());
+    Ok(())
+}
+
+/// One directory per written file, named by a hash of its canonical path.
+fn stack_dir(root: &Path, target: &Path) -> PathBuf {
+    let hash = blake3::hash(target.to_string_lossy().as_bytes());
+    root.join(&hash.to_hex()[..32])
+}
+
+/// Snapshots in `dir`, oldest first, as `(sequence, path)`.
+fn snapshots(dir: &Path) -> Vec<(u64, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<(u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let ext = path.extension()?.to_str()?;
+            if ext != PREVIOUS_EXT && ext != ABSENT_EXT {
+                return None;
+            }
+            let sequence = path.file_stem()?.to_str()?.parse().ok()?;
+            Some((sequence, path))
+        })
+        .collect();
+    found.sort_by_key(|(sequence, _)| *sequence);
+    found
+}
+
+fn push_snapshot(root: &Path, target: &Path) -> Result<(), String> {
+    let _guard = UNDO_LOCK.lock().map_err(|e| e.to_string())?;
+    let dir = stack_dir(root, target);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create undo snapshot: {e}"))?;
+    let existing = snapshots(&dir);
+    let sequence = existing.last().map_or(1, |(last, _)| last + 1);
+
+    let saved = match std::fs::read(target) {
+        Ok(previous) => std::fs::write(dir.join(format!("{sequence:08}.{PREVIOUS_EXT}")), previous),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            std::fs::write(dir.join(format!("{sequence:08}.{ABSENT_EXT}")), [])
+        }
+        Err(e) => Err(e),
+    };
+    saved.map_err(|e| format!("Failed to save undo snapshot: {e}"))?;
+
+    let excess = (existing.len() + 1).saturating_sub(MAX_UNDO_DEPTH);
+    for (_, stale) in existing.iter().take(excess) {
+        let _ = std::fs::remove_file(stale);
+    }
+    Ok(())
+}
+
+/// Restore the newest snapshot of `target`: rewrite its previous bytes, or delete the file if
+/// the write created it.
+fn pop_snapshot(root: &Path, target: &Path, display: &str) -> Result<UndoResult, String> {
+    let _guard = UNDO_LOCK.lock().map_err(|e| e.to_string())?;
+    let dir = stack_dir(root, target);
+    let mut stack = snapshots(&dir);
+    let (_, snapshot) = stack
+        .pop()
+        .ok_or_else(|| format!("No earlier version of {display} to restore"))?;
+
+    let removed = snapshot.extension().is_some_and(|ext| ext == ABSENT_EXT);
+    let restored = if removed {
+        match std::fs::remove_file(target) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    } else {
+        std::fs::read(&snapshot).and_then(|previous| std::fs::write(target, previous))
+    };
+    restored.map_err(|e| format!("Failed to restore {display}: {e}"))?;
+    std::fs::remove_file(&snapshot).map_err(|e| e.to_string())?;
+
+    Ok(UndoResult { path: display.to_string(), removed, remaining: stack.len() })
+}
+
+/// Keep what is at `target` (or that nothing is) before a backend write replaces it. A no-op
+/// until the store is initialized.
+pub(crate) fn save_undo_snapshot(target: &Path) -> Result<(), String> {
+    match UNDO_DIR.get() {
+        Some(root) => push_snapshot(root, target),
+        None => Ok(()),
+    }
+}
+
+/// Put back the content `path` had before its last backend write (`write_file_content`,
+/// write-back, or an applied patch). Calling it again steps further back, up to
+/// `MAX_UNDO_DEPTH` writes.
+#[tauri::command]
+pub async fn undo_last_write(path: String) -> Result<UndoResult, String> {
+    let root = UNDO_DIR
+        .get()
+        .ok_or_else(|| "Undo store is not initialized".to_string())?;
+    let target = resolve_write_target(&path)?;
+    tauri::async_runtime::spawn_blocking(move || pop_snapshot(root, &target, &path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bablusheed-{label}-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // ── snapshots ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn undo_walks_back_through_each_write_then_deletes_a_created_file() {
+        let store = temp_dir("undo-store");
+        let file = temp_dir("undo-project").join("a.ts");
+
+        push_snapshot(&store, &file).unwrap();
+        std::fs::write(&file, "v1").unwrap();
+        push_snapshot(&store, &file).unwrap();
+        std::fs::write(&file, "v2").unwrap();
+
+        let first = pop_snapshot(&store, &file, "a.ts").unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "v1");
+        assert!(!first.removed);
+        assert_eq!(first.remaining, 1);
+
+        let second = pop_snapshot(&store, &file, "a.ts").unwrap();
+        assert!(second.removed);
+        assert!(!file.exists());
+        assert!(pop_snapshot(&store, &file, "a.ts").unwrap_err().contains("No earlier version"));
+    }
+
+    #[test]
+    fn only_the_newest_snapshots_are_kept() {
+        let store = temp_dir("undo-depth");
+        let file = temp_dir("undo-depth-project").join("a.ts");
+        for version in 0..MAX_UNDO_DEPTH + 5 {
+            std::fs::write(&file, version.to_string()).unwrap();
+            push_snapshot(&store, &file).unwrap();
+        }
+        let stack = snapshots(&stack_dir(&store, &file));
+        assert_eq!(stack.len(), MAX_UNDO_DEPTH);
+        assert_eq!(std::fs::read_to_string(&stack[0].1).unwrap(), "5");
+    }
+
+    #[test]
+    fn files_have_separate_stacks() {
+        let store = temp_dir("undo-separate");
+        let project = temp_dir("undo-separate-project");
+        let (a, b) = (project.join("a.ts"), project.join("b.ts"));
+        std::fs::write(&a, "a").unwrap();
+        std::fs::write(&b, "b").unwrap();
+        push_snapshot(&store, &a).unwrap();
+        std::fs::write(&a, "a2").unwrap();
+
+        assert!(pop_snapshot(&store, &b, "b.ts").is_err());
+        pop_snapshot(&store, &a, "a.ts").unwrap();
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "a");
+    }
+}
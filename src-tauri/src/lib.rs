@@ -1,9 +1,30 @@
 pub mod commands;
 pub mod models;
 
-use commands::ast::analyze_reachability;
-use commands::fs::{authorize_export_directory, read_file_content, walk_directory, write_file_content};
-use commands::pack::pack_files;
+use commands::assets::{init_description_cache, set_image_description};
+use commands::ast::{analyze_reachability, attribute_symbol_tokens, report_dead_exports};
+use commands::audit::{get_export_history, init_audit_log, record_export};
+use commands::fs::{
+    authorize_export_directory, authorize_file, diff_against_disk, get_heatmap, get_project_metadata,
+    read_directory_contents, read_file_content, read_file_content_hashed, reattach_root, walk_directory, walk_directory_flat,
+    walk_directory_flat_compressed, write_back_file, write_file_content,
+};
+use commands::pack::{
+    copy_next_chunk, diagnose_imports, export_combined_packs, export_encrypted_packs, export_packs, generate_delta,
+    pack_additional, pack_files, pack_files_compressed, pack_matches, preview_pack,
+    start_chunked_copy, verify_fingerprint, who_depends_on,
+};
+use commands::graphql::{files_for_graphql_type, link_graphql_resolvers};
+use commands::languages::{get_language_associations, init_language_associations, set_language_associations};
+use commands::patch::apply_unified_diff;
+use commands::profiles::{list_llm_profiles, list_pack_profiles};
+use commands::redaction::preview_redactions;
+use commands::response::parse_model_response;
+use commands::routes::{files_for_route, map_routes};
+use commands::select::auto_select;
+use commands::stacks::detect_stack;
+use commands::undo::{init_undo_store, undo_last_write};
+use tauri::Manager;
 #[cfg(target_os = "macos")]
 use tauri::menu::{AboutMetadata, MenuBuilder, SubmenuBuilder};
 
@@ -83,6 +104,11 @@ pub fn run() {
         .setup(|app| {
             #[cfg(target_os = "macos")]
             configure_macos_menu(app)?;
+            let data_dir = app.path().app_data_dir()?;
+            init_audit_log(&data_dir)?;
+            init_undo_store(&data_dir.join("undo"))?;
+            init_description_cache(&data_dir.join("image-descriptions"))?;
+            init_language_associations(&data_dir)?;
             Ok(())
         })
         .plugin(tauri_plugin_dialog::init())
@@ -92,11 +118,53 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             walk_directory,
+            walk_directory_flat,
+            walk_directory_flat_compressed,
             read_file_content,
+            read_file_content_hashed,
+            read_directory_contents,
+            reattach_root,
             authorize_export_directory,
+            authorize_file,
             write_file_content,
+            write_back_file,
+            diff_against_disk,
+            undo_last_write,
+            detect_stack,
+            get_heatmap,
+            get_project_metadata,
+            set_image_description,
+            get_language_associations,
+            set_language_associations,
             pack_files,
+            pack_files_compressed,
+            verify_fingerprint,
+            pack_additional,
+            pack_matches,
+            preview_pack,
+            start_chunked_copy,
+            copy_next_chunk,
+            generate_delta,
+            export_combined_packs,
+            export_encrypted_packs,
+            export_packs,
+            apply_unified_diff,
+            parse_model_response,
+            list_llm_profiles,
+            list_pack_profiles,
+            preview_redactions,
+            record_export,
+            get_export_history,
+            who_depends_on,
+            map_routes,
+            files_for_route,
+            link_graphql_resolvers,
+            files_for_graphql_type,
+            diagnose_imports,
             analyze_reachability,
+            report_dead_exports,
+            attribute_symbol_tokens,
+            auto_select,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -16,6 +16,43 @@ pub struct FileNode {
     pub children: Option<Vec<FileNode>>,
 }
 
+/// `FileNode` without `children`; the hierarchy is carried by `parent_index`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FlatFileNode {
+    pub id: String,
+    pub path: String,
+    #[serde(rename = "relativePath")]
+    pub relative_path: String,
+    pub name: String,
+    pub extension: String,
+    pub size: u64,
+    #[serde(rename = "isDir")]
+    pub is_dir: bool,
+    /// Index of the parent directory in the same array; `None` for top-level entries.
+    #[serde(rename = "parentIndex")]
+    pub parent_index: Option<usize>,
+}
+
+/// `walk_directory_flat` output: the flat tree, plus how the walk was degraded when the root
+/// looked like a network mount.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FlatWalkResponse {
+    pub nodes: Vec<FlatFileNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degraded: Option<DegradedMode>,
+}
+
+/// A project root whose latency probe looked like a network mount: the walk skipped content
+/// sniffing and `read_directory_contents` reads under it in parallel batches.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DegradedMode {
+    /// Median time of one metadata lookup under the root.
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: u64,
+    /// One-line explanation for the user.
+    pub notice: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileContent {
     pub path: String,
@@ -33,12 +70,343 @@ pub struct PackRequest {
     pub num_packs: usize,
     #[serde(rename = "outputFormat")]
     pub output_format: String,
-    #[serde(rename = "llmProfileId")]
-    pub llm_profile_id: String,
+    /// Pack profile to apply; its settings override the fields below.
+    #[serde(rename = "profileId", default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
+    /// LLM profile (or `cl100k`/`o200k`) whose tokenizer counts tokens; without a known one,
+    /// counts are estimated from length. A known profile's context window also caps every
+    /// pack, and its preferred format fills in an empty `output_format`.
+    #[serde(rename = "llmProfileId", default, skip_serializing_if = "Option::is_none")]
+    pub llm_profile_id: Option<String>,
+    /// Drop top-level import/use statements from the packed text. Dependency ordering
+    /// still reads them from the original content.
+    #[serde(rename = "stripImports", default)]
+    pub strip_imports: bool,
+    /// Drop line and block comments from the packed text of files with a tree-sitter grammar.
+    #[serde(rename = "stripComments", default)]
+    pub strip_comments: bool,
+    /// Strip BOMs, convert CRLF to LF, and trim trailing whitespace in the packed text.
+    #[serde(rename = "normalizeContent", default)]
+    pub normalize_content: bool,
+    /// Squeeze whitespace out of the packed text; savings are reported per pack.
+    #[serde(rename = "compressWhitespace", default, skip_serializing_if = "Option::is_none")]
+    pub compress_whitespace: Option<WhitespaceCompression>,
+    /// Head each file section with a short ID (`[F017] src/lib/foo.ts`) and open each pack with
+    /// an ID -> path index, so answers can cite files compactly.
+    #[serde(rename = "fileIds", default)]
+    pub file_ids: bool,
+    /// Prefix each packed line with its line number. Numbers count lines of the packed text, so
+    /// they match the file on disk unless comments, imports, or blank lines were stripped.
+    #[serde(rename = "includeLineNumbers", default)]
+    pub include_line_numbers: bool,
+    /// Upper bound on tokens per pack; more packs are produced when `num_packs` would exceed it.
+    #[serde(rename = "maxTokensPerPack", default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_pack: Option<usize>,
+    /// Files over this many tokens are packed as `name.part-1-of-3.ext` fragments that each fit it.
+    #[serde(rename = "maxTokensPerFile", default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_file: Option<usize>,
+    /// How files are ordered across packs: "grouped" (docs first, related code together)
+    /// or "dependency" (plain dependency order).
+    #[serde(default = "default_pack_strategy")]
+    pub strategy: String,
+    /// Attach a scaffold prompt to every pack for multi-pack pasting.
+    #[serde(rename = "suggestPrompts", default)]
+    pub suggest_prompts: bool,
+    /// Template for those prompts; see `render_pack_prompt` for placeholders.
+    #[serde(rename = "promptTemplate", default, skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
+    /// Delimiters and metadata placed around each pack; `None` leaves packs bare.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapper: Option<PackWrapper>,
+    /// Separator and markers placed between and around file sections; `None` keeps the defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boundaries: Option<SectionBoundaries>,
+    /// Handlebars-style template (code2prompt-compatible) that renders each pack in place of
+    /// the format's headers and fences; see `PackTemplate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Source of the packed files; rendered as a one-line header on every pack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<ProjectMetadata>,
+    /// Paths left out by filters or budget, listed (without content) at the end of the last pack.
+    #[serde(rename = "excludedPaths", default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_paths: Vec<String>,
+    /// Pack the `.proto` and OpenAPI files behind generated code the selection uses, even when
+    /// they (and the generated code) were left out.
+    #[serde(rename = "includeSchemas", default)]
+    pub include_schemas: bool,
+    /// Append a list of the environment variables and feature flags the packed files read.
+    #[serde(rename = "configSurface", default)]
+    pub config_surface: bool,
+    /// Append the TODO/FIXME/HACK comments found in the packed files, with locations.
+    #[serde(rename = "todoIndex", default)]
+    pub todo_index: bool,
+    /// Append an index of the error messages the packed files raise, with locations.
+    #[serde(rename = "errorIndex", default)]
+    pub error_index: bool,
+    /// Append the interface, type, and enum declarations selected TS files import from
+    /// files outside the selection.
+    #[serde(rename = "typeClosure", default)]
+    pub type_closure: bool,
+    /// Pack one reference locale of each translation resource in full and summarize the others by
+    /// key count.
+    #[serde(rename = "summarizeLocales", default)]
+    pub summarize_locales: bool,
+    /// Pack CSV/TSV files as column names, row counts, and a few sample rows.
+    #[serde(rename = "profileData", default)]
+    pub profile_data: bool,
+    /// Replace large JSON/YAML files with an inferred outline of their structure.
+    #[serde(rename = "schemaInference", default, skip_serializing_if = "Option::is_none")]
+    pub schema_inference: Option<SchemaInference>,
+    /// Project redaction rules, applied to packed content after the other cleanups.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<RedactionRule>,
+    /// Weighted globs: high files lead their region, low files wait for the last pack and are
+    /// the first dropped when the token budget runs out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub priorities: Vec<PriorityRule>,
+    /// Pack low-priority files as outlines: signatures and doc comments with bodies elided.
+    #[serde(rename = "outlineLowPriority", default)]
+    pub outline_low_priority: bool,
+    /// Open each pack with an overview of its directories, languages, and exported symbols,
+    /// derived from the files alone.
+    #[serde(rename = "packNotes", default)]
+    pub pack_notes: bool,
+    /// Files the prompt centers on: packed whole, under a "PRIMARY FILE UNDER DISCUSSION"
+    /// banner, with no cleanup, compression, summary, split, or truncation applied.
+    #[serde(rename = "focusPaths", default, skip_serializing_if = "Vec::is_empty")]
+    pub focus_paths: Vec<String>,
+    /// `PackResponse::manifest` of the last run. Files whose hash still matches reuse their
+    /// cleaned-up text from that run, and packs that come out the same are marked unchanged.
+    #[serde(rename = "previousManifest", default, skip_serializing_if = "Option::is_none")]
+    pub previous_manifest: Option<HashMap<String, String>>,
+}
+
+/// A single plaintext pack with the default strategy and every option off.
+impl Default for PackRequest {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            num_packs: 1,
+            output_format: "plaintext".to_string(),
+            profile_id: None,
+            llm_profile_id: None,
+            strip_imports: false,
+            strip_comments: false,
+            normalize_content: false,
+            compress_whitespace: None,
+            file_ids: false,
+            include_line_numbers: false,
+            max_tokens_per_pack: None,
+            max_tokens_per_file: None,
+            strategy: default_pack_strategy(),
+            suggest_prompts: false,
+            prompt_template: None,
+            wrapper: None,
+            boundaries: None,
+            template: None,
+            provenance: None,
+            excluded_paths: Vec::new(),
+            include_schemas: false,
+            config_surface: false,
+            todo_index: false,
+            error_index: false,
+            type_closure: false,
+            summarize_locales: false,
+            profile_data: false,
+            schema_inference: None,
+            redactions: Vec::new(),
+            priorities: Vec::new(),
+            outline_low_priority: false,
+            pack_notes: false,
+            focus_paths: Vec::new(),
+            previous_manifest: None,
+        }
+    }
+}
+
+/// A regex whose matches are replaced before packing. `replacement` may use `$1`-style
+/// group references and defaults to `[REDACTED]`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedactionRule {
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+}
+
+/// A glob and the weight ("high", "normal" or "low") of the files it matches. The first
+/// matching rule wins; unmatched files are normal.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PriorityRule {
+    pub pattern: String,
+    pub weight: String,
+}
+
+/// Dry run of redaction rules: what would be replaced, without packing anything.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedactionReport {
+    /// First matches in path order; `total_matches` counts all of them.
+    pub matches: Vec<RedactionMatch>,
+    #[serde(rename = "totalMatches")]
+    pub total_matches: usize,
+    #[serde(rename = "filesAffected")]
+    pub files_affected: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RedactionMatch {
+    pub path: String,
+    /// 1-based line of the match start.
+    pub line: usize,
+    pub pattern: String,
+    pub text: String,
+    pub replacement: String,
+}
+
+/// Identifies the code state a pack was built from.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct ProjectMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Full `HEAD` commit hash, when the root is inside a git work tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Working tree has uncommitted changes, so `commit` alone does not pin the content.
+    #[serde(default)]
+    pub dirty: bool,
+}
+
+/// A file `auto_select` picked, with why.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AutoSelectedFile {
+    pub path: String,
+    #[serde(rename = "relativePath")]
+    pub relative_path: String,
+    pub tokens: usize,
+    /// Weighted reachability, importance, and recency, from 0 to 1.
+    pub score: f64,
+    pub reason: String,
+}
+
+/// The highest-scoring files of a project that fit a token budget, best first.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AutoSelection {
+    pub files: Vec<AutoSelectedFile>,
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: usize,
+    pub budget: usize,
+    /// Relative paths of the files left out, best first.
+    #[serde(rename = "skippedPaths")]
+    pub skipped_paths: Vec<String>,
+    pub summary: String,
+}
+
+/// Pack-level framing. Delimiters may use `{index}`, `{total}`, and `{project}`; the preamble
+/// and epilogue may use `{{pack_index}}`, `{{total_packs}}`, `{{file_count}}`, and
+/// `{{project_name}}`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PackWrapper {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opening: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closing: Option<String>,
+    /// Emit "pack: i/n".
+    #[serde(rename = "includeIndex", default)]
+    pub include_index: bool,
+    #[serde(rename = "projectName", default, skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
+    /// Emit the UTC generation time.
+    #[serde(rename = "includeTimestamp", default)]
+    pub include_timestamp: bool,
+    /// Standing instructions placed at the top of every pack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preamble: Option<String>,
+    /// Text placed after the last section of every pack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epilogue: Option<String>,
+}
+
+/// Which config files `pack_files` packs as an inferred schema instead of their content.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaInference {
+    /// Globs matched against each file's relative path.
+    pub globs: Vec<String>,
+    /// Files at or under this size keep their content.
+    #[serde(rename = "minBytes", default)]
+    pub min_bytes: usize,
+}
+
+/// Whitespace `pack_files` squeezes out: runs of blank lines collapse to one and trailing
+/// whitespace goes.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WhitespaceCompression {
+    /// Also drop leading indentation, in languages where it carries no meaning.
+    #[serde(rename = "stripIndentation", default)]
+    pub strip_indentation: bool,
+}
+
+/// Text around file sections, for downstream chunkers that split on their own sentinels.
+/// Markers may use `{path}`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct SectionBoundaries {
+    /// Between consecutive sections; a blank line when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub separator: Option<String>,
+    /// Line placed before each section.
+    #[serde(rename = "fileStart", default, skip_serializing_if = "Option::is_none")]
+    pub file_start: Option<String>,
+    /// Line placed after each section.
+    #[serde(rename = "fileEnd", default, skip_serializing_if = "Option::is_none")]
+    pub file_end: Option<String>,
+}
+
+fn default_pack_strategy() -> String {
+    "grouped".to_string()
+}
+
+/// A named preset of pack settings.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackProfile {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "outputFormat")]
+    pub output_format: String,
+    #[serde(rename = "numPacks")]
+    pub num_packs: usize,
+    #[serde(rename = "maxTokensPerPack", skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_pack: Option<usize>,
+    #[serde(rename = "stripImports")]
+    pub strip_imports: bool,
+    #[serde(rename = "normalizeContent")]
+    pub normalize_content: bool,
+    pub strategy: String,
+}
+
+/// A target model: how much it reads at once, how its tokens are counted, and the pack
+/// format it follows best.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LlmProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "contextWindowTokens")]
+    pub context_window_tokens: usize,
+    /// BPE vocabulary ("o200k" or "cl100k"), or "approx" for the length estimate.
+    pub tokenizer: String,
+    #[serde(rename = "preferredFormat")]
+    pub preferred_format: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PackItem {
+    /// Content hash; pass to `preview_pack` for a truncated view.
+    pub id: String,
+    /// Hex blake3 of the pack's file sections, independent of their order and of the pack
+    /// header (timestamp, pack number), so repacking unchanged files yields the same hash.
+    #[serde(rename = "contentHash")]
+    pub content_hash: String,
     pub index: usize,
     pub content: String,
     #[serde(rename = "estimatedTokens")]
@@ -47,6 +415,45 @@ pub struct PackItem {
     pub file_count: usize,
     #[serde(rename = "filePaths")]
     pub file_paths: Vec<String>,
+    /// e.g. "This is pack 2 of 4 containing src/payments/. Wait for all packs before answering."
+    #[serde(rename = "suggestedPrompt", default, skip_serializing_if = "Option::is_none")]
+    pub suggested_prompt: Option<String>,
+    /// Entries of `file_paths` that are parts of a split file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<FileFragment>,
+    /// Entries of `file_paths` cut down because each alone exceeded the pack budget.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub truncations: Vec<FileTruncation>,
+    /// Tokens whitespace compression removed from this pack's files; `None` when it was off.
+    #[serde(rename = "savedTokens", default, skip_serializing_if = "Option::is_none")]
+    pub saved_tokens: Option<usize>,
+    /// False when the request's `previous_manifest` run produced this same pack.
+    pub changed: bool,
+}
+
+/// One part of a file split to fit `PackRequest::max_tokens_per_file`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FileFragment {
+    /// Part path, e.g. `src/big.part-2-of-3.ts`.
+    pub path: String,
+    #[serde(rename = "originalPath")]
+    pub original_path: String,
+    /// 1-based.
+    pub part: usize,
+    pub total: usize,
+}
+
+/// A file that alone exceeded `PackRequest::max_tokens_per_pack`, packed as its head and tail
+/// around a marker in place of the omitted lines.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FileTruncation {
+    pub path: String,
+    #[serde(rename = "originalTokens")]
+    pub original_tokens: usize,
+    #[serde(rename = "keptTokens")]
+    pub kept_tokens: usize,
+    #[serde(rename = "omittedLines")]
+    pub omitted_lines: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -54,10 +461,368 @@ pub struct PackResponse {
     pub packs: Vec<PackItem>,
     #[serde(rename = "totalTokens")]
     pub total_tokens: usize,
+    /// Non-fatal notes about the packed files, e.g. sources tree-sitter could not parse cleanly.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Identifies the packed paths and contents; pass to `generate_delta` later.
+    #[serde(default)]
+    pub fingerprint: String,
+    /// Hex blake3 of every pack's `content_hash`, independent of pack order; unchanged
+    /// output keeps the same hash across runs.
+    #[serde(rename = "contentHash", default)]
+    pub content_hash: String,
+    /// Hex blake3 of every packed file, keyed by path; pass back as `previous_manifest`.
+    #[serde(default)]
+    pub manifest: HashMap<String, String>,
+    /// Why each packed file sits where it does, in pack order.
+    #[serde(default)]
+    pub placements: Vec<FilePlacement>,
+}
+
+/// Where one file landed in a pack run and what put it there.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FilePlacement {
+    pub path: String,
+    /// `PackItem::index` of the pack holding the file.
+    #[serde(rename = "packIndex")]
+    pub pack_index: usize,
+    /// Position among the pack's files.
+    pub position: usize,
+    /// Position in the dependency-first order every strategy starts from.
+    #[serde(rename = "dependencyRank")]
+    pub dependency_rank: usize,
+    /// Doc priority (0 README, 1 overview/architecture, 2 `docs/`, 3 other) when the strategy
+    /// packs docs ahead of code.
+    #[serde(rename = "docBucket", default, skip_serializing_if = "Option::is_none")]
+    pub doc_bucket: Option<u8>,
+    /// Import-connected group the file was kept with, numbered in pack order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<usize>,
+    /// Pulled to the front of the code region because a doc's example imports it.
+    #[serde(default)]
+    pub pinned: bool,
+    /// In a pack beyond the requested count, added to stay under the per-pack token budget.
+    #[serde(default)]
+    pub spillover: bool,
+    /// Weight of the first priority rule matching the file, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// The above as one sentence for display.
+    pub reason: String,
+}
+
+/// Files changed since an earlier pack, as a single pack led by a repo map.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeltaResponse {
+    pub pack: PackItem,
+    /// Fingerprint of the full current selection, for the next delta.
+    pub fingerprint: String,
+    /// New or modified files included in the pack.
+    #[serde(rename = "changedPaths")]
+    pub changed_paths: Vec<String>,
+    #[serde(rename = "removedPaths")]
+    pub removed_paths: Vec<String>,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// One piece of a pack copied to the clipboard in parts.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClipboardChunk {
+    pub content: String,
+    /// 1-based position of this chunk.
+    pub index: usize,
+    pub total: usize,
+    /// No chunks remain after this one.
+    pub done: bool,
+}
+
+/// Head and tail of a pack, joined by an omission marker when it exceeds the preview budget.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackPreview {
+    pub content: String,
+    pub truncated: bool,
+    /// Length of the full pack.
+    #[serde(rename = "totalChars")]
+    pub total_chars: usize,
+    /// File sections not shown in full.
+    #[serde(rename = "omittedFiles")]
+    pub omitted_files: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportDiagnostic {
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    pub specifier: String,
+    /// Normalized paths that were tried; empty for URLs and `node:` builtins.
+    pub candidates: Vec<String>,
+}
+
+fn default_di_heuristic() -> String {
+    "modules".to_string()
+}
+
+fn default_max_depth() -> usize {
+    4
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReachabilityOptions {
+    /// Which decorated symbols count as DI roots: "modules", "decorated", or "off".
+    #[serde(rename = "diHeuristic", default = "default_di_heuristic")]
+    pub di_heuristic: String,
+    /// Globs matched against file paths and bare symbol names; matches are kept as roots
+    /// (e.g. `**/migrations/**` or plugin registries invoked by convention).
+    #[serde(rename = "keepPatterns", default)]
+    pub keep_patterns: Vec<String>,
+    /// Rust attribute paths whose items are kept as roots because a macro wires them up
+    /// (e.g. `tauri::command`, `test`); `command` also matches `#[tauri::command]`.
+    #[serde(rename = "rootAttributes", default)]
+    pub root_attributes: Vec<String>,
+    /// How many levels of nesting (`mod`, `namespace`, Python classes) are
+    /// searched for declarations.
+    #[serde(rename = "maxDepth", default = "default_max_depth")]
+    pub max_depth: usize,
+    /// Per-extension overrides of `max_depth` (e.g. `{"rs": 8}`), taking precedence over
+    /// both `max_depth` and the built-in language defaults.
+    #[serde(rename = "maxDepthByLanguage", default)]
+    pub max_depth_by_language: HashMap<String, usize>,
+}
+
+impl Default for ReachabilityOptions {
+    fn default() -> Self {
+        Self {
+            di_heuristic: default_di_heuristic(),
+            keep_patterns: Vec::new(),
+            root_attributes: Vec::new(),
+            max_depth: default_max_depth(),
+            max_depth_by_language: HashMap::new(),
+        }
+    }
+}
+
+/// Parse outcome for a file tree-sitter could not parse cleanly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseHealth {
+    /// "partial" when the tree contains error nodes, "failed" when no tree was produced.
+    pub status: String,
+    #[serde(rename = "errorCount")]
+    pub error_count: usize,
+    /// 1-based line of the first error node.
+    #[serde(rename = "firstErrorLine", skip_serializing_if = "Option::is_none")]
+    pub first_error_line: Option<usize>,
+}
+
+/// An exported symbol nothing reachable references.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeadExport {
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    pub name: String,
+    pub kind: String,
+    /// 1-based, inclusive line range of the declaration.
+    #[serde(rename = "startLine")]
+    pub start_line: usize,
+    #[serde(rename = "endLine")]
+    pub end_line: usize,
+    /// `false` when another selected file still mentions the name (likely other dead code).
+    #[serde(rename = "safeToDelete")]
+    pub safe_to_delete: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SymbolTokens {
+    pub name: String,
+    pub kind: String,
+    #[serde(rename = "startLine")]
+    pub start_line: usize,
+    #[serde(rename = "endLine")]
+    pub end_line: usize,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileSymbolTokens {
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: usize,
+    /// Largest first.
+    pub symbols: Vec<SymbolTokens>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReachabilityResult {
     pub reachable_symbols: HashMap<String, Vec<String>>,
     pub unreachable_symbols: HashMap<String, Vec<String>>,
+    /// Files whose symbols may be incomplete because they did not parse cleanly.
+    #[serde(default)]
+    pub parse_health: HashMap<String, ParseHealth>,
+}
+
+/// One pack export or copy, as kept in the local audit log.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExportRecord {
+    /// RFC 3339 UTC, stamped by the backend when the record is written.
+    #[serde(default)]
+    pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// "file", "combined", "encrypted", or "clipboard".
+    pub kind: String,
+    #[serde(rename = "fileCount")]
+    pub file_count: usize,
+    #[serde(rename = "tokenCount")]
+    pub token_count: usize,
+    /// Written path, or "clipboard".
+    pub destination: String,
+}
+
+/// How one hunk of a unified diff fared against the file on disk.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PatchHunkResult {
+    /// The `@@ -a,b +c,d @@` line.
+    pub header: String,
+    pub applied: bool,
+    /// Lines between where the hunk said it starts and where it matched.
+    pub offset: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PatchFileResult {
+    /// Project-relative path the diff targets.
+    pub path: String,
+    /// "modified", "created", "deleted", or "conflict".
+    pub status: String,
+    pub hunks: Vec<PatchHunkResult>,
+    /// Set when the file itself could not be resolved, read, or written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PatchReport {
+    pub files: Vec<PatchFileResult>,
+    /// True when every hunk matched; nothing is written otherwise.
+    pub clean: bool,
+    /// True when changes were written to disk.
+    pub written: bool,
+}
+
+/// A region where the file on disk and the proposed content changed the same lines.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// 1-based line of the `<<<<<<<` marker in the merged text.
+    pub line: usize,
+    pub disk: String,
+    pub proposed: String,
+}
+
+/// A file read together with the hash `pack_files` fingerprints its content by.
+/// Error from a command that reads or writes under an open project root.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FsError {
+    /// The volume holding `root` went away (drive unplugged, share dropped). The root stays
+    /// stale until `reattach_root` or a fresh walk finds it again.
+    RootUnavailable { root: String },
+    /// Any other failure, as a message.
+    Other { message: String },
+}
+
+impl std::fmt::Display for FsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RootUnavailable { root } => write!(f, "Project root is unavailable (was its drive disconnected?): {root}"),
+            Self::Other { message } => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for FsError {
+    fn from(message: String) -> Self {
+        Self::Other { message }
+    }
+}
+
+impl From<FsError> for String {
+    fn from(error: FsError) -> Self {
+        error.to_string()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HashedFileContent {
+    pub content: String,
+    /// Hex blake3 of `content`.
+    pub hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WriteBackResult {
+    pub path: String,
+    /// "written" (the file matched what was packed), "merged" (it had changed and merged
+    /// cleanly), or "conflict" (nothing was written).
+    pub status: String,
+    pub conflicts: Vec<MergeConflict>,
+    /// On conflict, the merge with conflict markers to resolve by hand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged: Option<String>,
+    /// Why the file could not be merged, when there is no base to merge from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UndoResult {
+    pub path: String,
+    /// True when the undone write had created the file, so it was deleted.
+    pub removed: bool,
+    /// Earlier writes that can still be undone.
+    pub remaining: usize,
+}
+
+/// A recognized project stack and the defaults tuned for it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DetectedStack {
+    /// Stable identifier, e.g. `nextjs` or `tauri`.
+    pub id: String,
+    pub name: String,
+    /// Ignore globs to add to the project's custom patterns.
+    #[serde(rename = "ignorePatterns")]
+    pub ignore_patterns: Vec<String>,
+    /// Suggested entry point for dead-code analysis, relative to the root.
+    #[serde(rename = "entryPoint", default, skip_serializing_if = "Option::is_none")]
+    pub entry_point: Option<String>,
+    /// Pack strategy that suits the stack: `grouped` or `dependency`.
+    pub strategy: String,
+}
+
+/// A web route and the file that handles it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RouteHandler {
+    /// Full route with mount prefixes applied, keeping the framework's parameter syntax.
+    pub route: String,
+    /// HTTP method, or `None` for pages and handlers that accept any method.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    pub file: String,
+    /// `nextjs-pages`, `nextjs-app`, `express`, or `axum`.
+    pub framework: String,
+}
+
+/// A GraphQL SDL field linked to a file implementing its resolver.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GraphqlEdge {
+    #[serde(rename = "typeName")]
+    pub type_name: String,
+    pub field: String,
+    /// File whose SDL defines the field.
+    #[serde(rename = "schemaFile")]
+    pub schema_file: String,
+    #[serde(rename = "resolverFile")]
+    pub resolver_file: String,
 }
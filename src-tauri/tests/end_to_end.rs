@@ -0,0 +1,163 @@
+use bablusheed_lib::commands::ast::analyze_reachability;
+use bablusheed_lib::commands::fs::{read_directory_contents, walk_directory_flat};
+use bablusheed_lib::commands::pack::pack_files;
+use bablusheed_lib::models::{FileContent, PackRequest, PackResponse};
+use std::path::Path;
+
+/// Walk a fixture under `tests/fixtures` (which also authorizes it as a project root) and read
+/// every file, with paths made relative to the fixture like the frontend sends them.
+async fn load_fixture(name: &str) -> Vec<FileContent> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    let root = root.to_string_lossy().to_string();
+    let walk = walk_directory_flat(root.clone(), true, Vec::new(), None, None)
+        .await
+        .unwrap();
+    assert!(!walk.nodes.is_empty(), "{name} walked to an empty tree");
+    let mut files = read_directory_contents(root.clone(), true, Vec::new())
+        .await
+        .unwrap();
+    for file in &mut files {
+        let relative = Path::new(&file.path).strip_prefix(&root).unwrap();
+        file.path = relative.to_string_lossy().replace('\\', "/");
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+fn copies(files: &[FileContent]) -> Vec<FileContent> {
+    files
+        .iter()
+        .map(|file| FileContent {
+            path: file.path.clone(),
+            content: file.content.clone(),
+            token_count: file.token_count,
+        })
+        .collect()
+}
+
+fn paths(files: &[FileContent]) -> Vec<&str> {
+    files.iter().map(|file| file.path.as_str()).collect()
+}
+
+async fn pack(files: &[FileContent], num_packs: usize) -> PackResponse {
+    pack_files(PackRequest {
+        files: copies(files),
+        num_packs,
+        output_format: "markdown".into(),
+        ..Default::default()
+    })
+    .await
+    .unwrap()
+}
+
+/// Packed paths in pack order; asserts each input file appears exactly once.
+fn packed_order(response: &PackResponse, files: &[FileContent]) -> Vec<String> {
+    let order: Vec<String> = response
+        .packs
+        .iter()
+        .flat_map(|pack| pack.file_paths.clone())
+        .collect();
+    let mut sorted = order.clone();
+    sorted.sort();
+    assert_eq!(sorted, paths(files));
+    order
+}
+
+fn position(order: &[String], path: &str) -> usize {
+    order
+        .iter()
+        .position(|p| p == path)
+        .unwrap_or_else(|| panic!("{path} not packed"))
+}
+
+async fn unreachable_symbols(entry: &str, files: &[FileContent], path: &str) -> Vec<String> {
+    let result = analyze_reachability(entry.into(), copies(files), None)
+        .await
+        .unwrap();
+    assert!(result.parse_health.is_empty(), "{:?}", result.parse_health);
+    result
+        .unreachable_symbols
+        .get(path)
+        .cloned()
+        .unwrap_or_default()
+}
+
+// ── TypeScript app with path aliases ──
+
+#[tokio::test]
+async fn ts_app_resolves_aliases_end_to_end() {
+    let files = load_fixture("ts-app").await;
+    assert_eq!(
+        paths(&files),
+        vec![
+            "src/lib/format.ts",
+            "src/lib/math.ts",
+            "src/main.ts",
+            "tsconfig.json"
+        ]
+    );
+
+    let order = packed_order(&pack(&files, 1).await, &files);
+    assert!(position(&order, "src/lib/math.ts") < position(&order, "src/lib/format.ts"));
+    assert!(position(&order, "src/lib/format.ts") < position(&order, "src/main.ts"));
+
+    let unreachable = unreachable_symbols("src/main.ts", &files, "src/lib/format.ts").await;
+    assert_eq!(unreachable, vec!["formatAverage".to_string()]);
+}
+
+// ── Rust crate ──
+
+#[tokio::test]
+async fn rust_crate_orders_modules_before_their_users() {
+    let files = load_fixture("rust-crate").await;
+    assert_eq!(
+        paths(&files),
+        vec!["Cargo.toml", "src/greeting.rs", "src/main.rs"]
+    );
+
+    let order = packed_order(&pack(&files, 2).await, &files);
+    assert!(position(&order, "src/greeting.rs") < position(&order, "src/main.rs"));
+
+    let unreachable = unreachable_symbols("src/main.rs", &files, "src/greeting.rs").await;
+    assert_eq!(unreachable, vec!["farewell".to_string()]);
+}
+
+// ── Python package ──
+
+#[tokio::test]
+async fn python_package_follows_package_imports() {
+    let files = load_fixture("python-package").await;
+    assert!(paths(&files).contains(&"pkg/core.py"));
+
+    let order = packed_order(&pack(&files, 1).await, &files);
+    assert!(position(&order, "pkg/helpers.py") < position(&order, "pkg/core.py"));
+    assert!(position(&order, "pkg/core.py") < position(&order, "main.py"));
+
+    let unreachable = unreachable_symbols("main.py", &files, "pkg/core.py").await;
+    assert_eq!(unreachable, vec!["unused_task".to_string()]);
+}
+
+// ── mixed monorepo ──
+
+#[tokio::test]
+async fn monorepo_keeps_docs_first_and_packages_together() {
+    let files = load_fixture("monorepo").await;
+    assert_eq!(files.len(), 5);
+
+    let response = pack(&files, 2).await;
+    let order = packed_order(&response, &files);
+    assert_eq!(response.packs[0].file_paths, vec!["README.md".to_string()]);
+    let config = position(&order, "packages/web/src/config.ts");
+    let index = position(&order, "packages/web/src/index.ts");
+    assert_eq!(index, config + 1, "the web package's files stay adjacent");
+
+    let unreachable = unreachable_symbols(
+        "packages/web/src/index.ts",
+        &files,
+        "packages/web/src/config.ts",
+    )
+    .await;
+    assert!(unreachable.is_empty(), "{unreachable:?}");
+}
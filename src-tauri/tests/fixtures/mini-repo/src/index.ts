@@ -0,0 +1,3 @@
+import { formatGreeting } from "./util";
+
+console.log(formatGreeting({ name: "Ada", tags: ["admin"] }));
@@ -0,0 +1,4 @@
+export interface User {
+  name: string;
+  tags: Array<"admin" | "guest">;
+}
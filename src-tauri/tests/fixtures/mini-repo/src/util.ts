@@ -0,0 +1,6 @@
+import type { User } from "./types";
+
+/** Markup-sensitive on purpose: <b>, &amp; and a CDATA terminator ]]> all need escaping. */
+export function formatGreeting(user: User): string {
+  return `<b>Hello, ${user.name}</b> & welcome`;
+}
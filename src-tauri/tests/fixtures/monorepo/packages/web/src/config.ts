@@ -0,0 +1 @@
+export const apiUrl = "http://localhost:8080";
@@ -0,0 +1,6 @@
+import { apiUrl } from "./config";
+
+export async function fetchStatus(): Promise<string> {
+  const response = await fetch(`${apiUrl}/status`);
+  return response.text();
+}
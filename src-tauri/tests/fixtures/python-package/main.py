@@ -0,0 +1,4 @@
+from pkg.core import run
+
+if __name__ == "__main__":
+    run()
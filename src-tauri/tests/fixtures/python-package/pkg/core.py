@@ -0,0 +1,9 @@
+from pkg.helpers import banner
+
+
+def run():
+    print(banner("fixture"))
+
+
+def unused_task():
+    return "never called"
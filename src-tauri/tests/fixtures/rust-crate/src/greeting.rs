@@ -0,0 +1,7 @@
+pub fn greet(name: &str) -> String {
+    format!("Hello, {name}!")
+}
+
+pub fn farewell(name: &str) -> String {
+    format!("Goodbye, {name}!")
+}
@@ -0,0 +1,7 @@
+mod greeting;
+
+use greeting::greet;
+
+fn main() {
+    println!("{}", greet("fixture"));
+}
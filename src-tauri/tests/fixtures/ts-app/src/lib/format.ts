@@ -0,0 +1,9 @@
+import { sum } from "./math";
+
+export function formatTotal(values: number[]): string {
+  return `Total: ${sum(values)}`;
+}
+
+export function formatAverage(values: number[]): string {
+  return `Average: ${sum(values) / values.length}`;
+}
@@ -0,0 +1,3 @@
+import { formatTotal } from "@/lib/format";
+
+console.log(formatTotal([1, 2, 3]));
@@ -8,39 +8,107 @@ import { useCallback, useEffect, useRef, useState } from "react";
 import { EmptyState } from "@/components/EmptyState";
 import { FilePreview } from "@/components/FilePreview";
 import { FileTree } from "@/components/FileTree";
+import { LanguageAssociations } from "@/components/LanguageAssociations";
 import { LLMSelector } from "@/components/LLMSelector";
+import { MatchSearch } from "@/components/MatchSearch";
 import { OutputPreview } from "@/components/OutputPreview";
 import { PackOptions } from "@/components/PackOptions";
+import { RedactionRules } from "@/components/RedactionRules";
 import { TokenBar } from "@/components/TokenBar";
 import { TopHeavyFiles } from "@/components/TopHeavyFiles";
 import { TooltipProvider } from "@/components/ui/tooltip";
 import { useFileTree } from "@/hooks/useFileTree";
 import { usePackager } from "@/hooks/usePackager";
 import { useTokenCount } from "@/hooks/useTokenCount";
+import { invokeCompressed } from "@/lib/compressed-ipc";
+import { buildTreeFromFlat } from "@/lib/flat-tree";
+import { describeError, unavailableRoot } from "@/lib/fs-errors";
+import { formatLanguageAssociations, parseLanguageAssociations } from "@/lib/language-associations";
 import { getProfile } from "@/lib/llm-profiles";
+import { parseRedactionRules } from "@/lib/redaction";
 import {
   buildOversizedFilesWarning,
   findOversizedFiles,
   forecastSplitPartCounts,
   resolveAdvisoryMaxTokensPerFile,
+  resolvePackingWindow,
 } from "@/lib/pack-strategy";
 import { useRenderDiagnostics } from "@/lib/render-diagnostics";
 import { cn } from "@/lib/utils";
-import type { FileNode, PackOptions as PackOptionsType } from "@/types";
+import type {
+  AutoSelection,
+  DetectedStack,
+  FileNode,
+  HeatmapMetric,
+  FlatWalkResponse,
+  OutputFormat,
+  PackOptions as PackOptionsType,
+  RedactionReport,
+} from "@/types";
+
+const OUTPUT_FORMATS: OutputFormat[] = ["plaintext", "markdown", "xml", "html", "json"];
+
+/** Store key for redaction rule text, keyed by project path */
+const REDACTIONS_STORE_KEY = "projectRedactions";
+
+/** Store key for each project's detected stack (null when none or turned off), keyed by path */
+const STACKS_STORE_KEY = "projectStacks";
 
 const DEFAULT_PACK_OPTIONS: PackOptionsType = {
   astDeadCode: false,
+  configSurface: false,
+  conversationReserve: 0,
+  customBoundaries: false,
   customIgnorePatterns: "**/*.test.ts\n**/*.spec.*\n**/__mocks__/**",
+  customTemplate: false,
   entryPoint: null,
+  errorIndex: false,
+  fileEndMarker: "",
+  fileIds: false,
+  exportPattern: "",
+  fileStartMarker: "",
+  focusFiles: false,
+  focusPaths: "",
+  includeLineNumbers: false,
+  includeProvenance: true,
+  includeSchemas: true,
+  listExcludedFiles: false,
   maxTokensPerPackFile: 0,
   minifyMarkdown: true,
+  naturalSort: true,
+  normalizeContent: false,
   numPacks: 3,
+  outlineLowPriority: false,
   outputFormat: "markdown",
+  packNotes: false,
+  packProfileId: null,
+  packTemplate: "",
+  prioritizeFiles: false,
+  priorityRules: "src/core/** = high\ntests/** = low",
+  profileData: false,
+  promptTemplate: "",
   reduceWhitespace: true,
   respectGitignore: true,
+  schemaGlobs: "**/*.json\n**/*.yaml\n**/*.yml",
+  schemaInference: false,
+  schemaMinKb: 64,
+  sectionSeparator: "\\n\\n",
   stripComments: true,
+  stripImports: false,
   stripMarkdownBlockquotes: false,
   stripMarkdownHeadings: false,
+  suggestPrompts: false,
+  summarizeLocales: false,
+  todoIndex: false,
+  typeClosure: false,
+  wrapPacks: false,
+  wrapperClosing: "",
+  wrapperEpilogue: "",
+  wrapperIncludeIndex: true,
+  wrapperIncludeProject: true,
+  wrapperIncludeTimestamp: false,
+  wrapperOpening: "",
+  wrapperPreamble: "",
 };
 
 /** Count total non-directory files in any tree whose nodes have isDir and optional children */
@@ -66,15 +134,55 @@ function buildPackFingerprint(
     llmId,
     options: {
       astDeadCode: options.astDeadCode,
+      configSurface: options.configSurface,
+      conversationReserve: options.conversationReserve,
+      customBoundaries: options.customBoundaries,
+      customTemplate: options.customTemplate,
       entryPoint: options.entryPoint,
+      errorIndex: options.errorIndex,
+      fileEndMarker: options.fileEndMarker,
+      fileIds: options.fileIds,
+      fileStartMarker: options.fileStartMarker,
+      focusFiles: options.focusFiles,
+      focusPaths: options.focusPaths,
+      includeLineNumbers: options.includeLineNumbers,
+      includeProvenance: options.includeProvenance,
+      includeSchemas: options.includeSchemas,
+      listExcludedFiles: options.listExcludedFiles,
       maxTokensPerPackFile: options.maxTokensPerPackFile,
       minifyMarkdown: options.minifyMarkdown,
+      normalizeContent: options.normalizeContent,
       numPacks: options.numPacks,
+      outlineLowPriority: options.outlineLowPriority,
       outputFormat: options.outputFormat,
+      packNotes: options.packNotes,
+      packProfileId: options.packProfileId,
+      packTemplate: options.packTemplate,
+      prioritizeFiles: options.prioritizeFiles,
+      priorityRules: options.priorityRules,
+      profileData: options.profileData,
+      promptTemplate: options.promptTemplate,
       reduceWhitespace: options.reduceWhitespace,
+      schemaGlobs: options.schemaGlobs,
+      schemaInference: options.schemaInference,
+      schemaMinKb: options.schemaMinKb,
+      sectionSeparator: options.sectionSeparator,
       stripComments: options.stripComments,
+      stripImports: options.stripImports,
       stripMarkdownBlockquotes: options.stripMarkdownBlockquotes,
       stripMarkdownHeadings: options.stripMarkdownHeadings,
+      suggestPrompts: options.suggestPrompts,
+      summarizeLocales: options.summarizeLocales,
+      todoIndex: options.todoIndex,
+      typeClosure: options.typeClosure,
+      wrapPacks: options.wrapPacks,
+      wrapperClosing: options.wrapperClosing,
+      wrapperEpilogue: options.wrapperEpilogue,
+      wrapperIncludeIndex: options.wrapperIncludeIndex,
+      wrapperIncludeProject: options.wrapperIncludeProject,
+      wrapperIncludeTimestamp: options.wrapperIncludeTimestamp,
+      wrapperOpening: options.wrapperOpening,
+      wrapperPreamble: options.wrapperPreamble,
     },
     selectedPaths: [...selectedPaths].sort(),
   });
@@ -148,13 +256,27 @@ export default function App() {
   const [projectPath, setProjectPath] = useState<string | null>(null);
   const [projectName, setProjectName] = useState<string>("");
   const [isLoadingTree, setIsLoadingTree] = useState(false);
+  const [walkNotice, setWalkNotice] = useState<string | null>(null);
+  /** Project root whose drive went away; reads fail until it is re-attached */
+  const [staleRoot, setStaleRoot] = useState<string | null>(null);
   const [selectedLlmId, setSelectedLlmId] = useState("chatgpt-5-2");
   const [packOptions, setPackOptions] = useState<PackOptionsType>(DEFAULT_PACK_OPTIONS);
+  /** Redaction rule text for the open project, persisted per project path */
+  const [redactionText, setRedactionText] = useState("");
+  const [associationText, setAssociationText] = useState("");
+  const [associationError, setAssociationError] = useState<string | null>(null);
+  // Text the backend last saved; null until the saved associations have loaded
+  const savedAssociationsRef = useRef<string | null>(null);
+  /** Stack detected the first time the open project was loaded; its defaults apply while set */
+  const [projectStack, setProjectStack] = useState<DetectedStack | null>(null);
+  const projectStackRef = useRef<DetectedStack | null>(null);
   const [fileContents, setFileContents] = useState<Map<string, string>>(new Map());
   const [isDragging, setIsDragging] = useState(false);
   const [showOutput, setShowOutput] = useState(false);
   // 2a: File preview state
   const [previewPath, setPreviewPath] = useState<string | null>(null);
+  const [heatmapMetric, setHeatmapMetric] = useState<HeatmapMetric | null>(null);
+  const [heatmap, setHeatmap] = useState<Map<string, number>>(new Map());
   const [centerTab, setCenterTab] = useState<"options" | "preview">("options");
   // 3l: Last project path for reopen
   const [lastProjectPath, setLastProjectPath] = useState<string | null>(null);
@@ -193,14 +315,19 @@ export default function App() {
   // 3i: Stable refs for gitignore/patterns to avoid loadProject recreation
   const gitignoreRef = useRef(packOptions.respectGitignore);
   const ignorePatternRef = useRef(packOptions.customIgnorePatterns);
+  const naturalSortRef = useRef(packOptions.naturalSort);
   useEffect(() => {
     gitignoreRef.current = packOptions.respectGitignore;
   }, [packOptions.respectGitignore]);
+  useEffect(() => {
+    naturalSortRef.current = packOptions.naturalSort;
+  }, [packOptions.naturalSort]);
   useEffect(() => {
     ignorePatternRef.current = packOptions.customIgnorePatterns;
   }, [packOptions.customIgnorePatterns]);
 
   const {
+    excludedPaths,
     flatItems,
     selectedFiles,
     searchQuery,
@@ -338,18 +465,38 @@ export default function App() {
     return () => clearInterval(timer);
   }, [debugLogging]);
 
-  const { packResult, isPacking, packError, packWarnings, pack, clearResult } = usePackager(
-    selectedFiles,
-    fileContents,
-    selectedLlmId,
+  const redactionRules = parseRedactionRules(redactionText);
+
+  const packingWindowTokens = resolvePackingWindow(
     llmProfile.contextWindowTokens,
-    tokenMap,
-    appendLog,
+    packOptions.conversationReserve,
   );
+  const {
+    packResult,
+    isPacking,
+    packError,
+    packWarnings,
+    pack,
+    packChanges,
+    packMatches,
+    clearResult,
+  } = usePackager(
+      selectedFiles,
+      fileContents,
+      selectedLlmId,
+      packingWindowTokens,
+      tokenMap,
+      appendLog,
+      projectName,
+      projectPath,
+      excludedPaths,
+      redactionRules,
+      projectStack?.strategy,
+    );
 
   const advisoryMaxTokensPerFile = resolveAdvisoryMaxTokensPerFile(
     packOptions.maxTokensPerPackFile,
-    llmProfile.contextWindowTokens,
+    packingWindowTokens,
   );
   const selectedPackFiles = selectedFiles
     .filter((f) => !f.isDir)
@@ -447,7 +594,7 @@ export default function App() {
         }
         if (savedPackOptions) {
           const merged = { ...DEFAULT_PACK_OPTIONS, ...savedPackOptions };
-          if (merged.outputFormat !== "markdown" && merged.outputFormat !== "plaintext") {
+          if (!OUTPUT_FORMATS.includes(merged.outputFormat)) {
             merged.outputFormat = "markdown";
           }
           setPackOptions(merged);
@@ -493,6 +640,57 @@ export default function App() {
     return () => clearTimeout(timer);
   }, [theme, selectedLlmId, packOptions, logLevel]);
 
+  useEffect(() => {
+    if (!projectPath) {
+      return;
+    }
+    const timer = setTimeout(async () => {
+      if (!storeRef.current) {
+        return;
+      }
+      try {
+        const saved = (await storeRef.current.get<Record<string, string>>(REDACTIONS_STORE_KEY)) ?? {};
+        if ((saved[projectPath] ?? "") === redactionText) {
+          return;
+        }
+        await storeRef.current.set(REDACTIONS_STORE_KEY, { ...saved, [projectPath]: redactionText });
+        await storeRef.current.save();
+      } catch (err) {
+        console.warn("Failed to save redaction rules:", err);
+      }
+    }, 1500);
+    return () => clearTimeout(timer);
+  }, [projectPath, redactionText]);
+
+  // Language associations live in the backend's settings; load them once, then save edits
+  useEffect(() => {
+    invoke<Record<string, string>>("get_language_associations")
+      .then((saved) => {
+        const text = formatLanguageAssociations(saved);
+        savedAssociationsRef.current = text;
+        setAssociationText(text);
+      })
+      .catch((err) => console.warn("Failed to load language associations:", err));
+  }, []);
+
+  useEffect(() => {
+    if (savedAssociationsRef.current === null || savedAssociationsRef.current === associationText) {
+      return;
+    }
+    const timer = setTimeout(async () => {
+      try {
+        await invoke("set_language_associations", {
+          associations: parseLanguageAssociations(associationText),
+        });
+        savedAssociationsRef.current = associationText;
+        setAssociationError(null);
+      } catch (err) {
+        setAssociationError(describeError(err));
+      }
+    }, 1000);
+    return () => clearTimeout(timer);
+  }, [associationText]);
+
   const readProjectFile = useCallback(
     async (path: string): Promise<string> => invoke<string>("read_file_content", { path }),
     [],
@@ -516,12 +714,40 @@ export default function App() {
         });
         appendLog("debug", `preview-read success path=${path} chars=${content.length}`);
       } catch (err) {
-        appendLog("error", `preview-read failed path=${path} err=${String(err)}`);
+        const root = unavailableRoot(err);
+        if (root) {
+          setStaleRoot(root);
+        }
+        appendLog("error", `preview-read failed path=${path} err=${describeError(err)}`);
       }
     },
     [appendLog, readProjectFile],
   );
 
+  // Recolor the tree when the heatmap metric or the project changes.
+  useEffect(() => {
+    if (!projectPath || !heatmapMetric) {
+      setHeatmap(new Map());
+      return;
+    }
+    let cancelled = false;
+    invoke<Record<string, number>>("get_heatmap", { metric: heatmapMetric, root: projectPath })
+      .then((values) => {
+        if (!cancelled) {
+          setHeatmap(new Map(Object.entries(values)));
+        }
+      })
+      .catch((err) => {
+        appendLog("error", `heatmap failed metric=${heatmapMetric} err=${describeError(err)}`);
+        if (!cancelled) {
+          setHeatmap(new Map());
+        }
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [appendLog, heatmapMetric, projectPath]);
+
   // Load content for selected files whenever selection changes.
   // Filter to only files not yet loaded to avoid redundant reads.
   useEffect(() => {
@@ -542,7 +768,11 @@ export default function App() {
             const content = await readProjectFile(path);
             updates.set(path, content);
           } catch (err) {
-            appendLog("error", `selection-preload failed path=${path} err=${String(err)}`);
+            const root = unavailableRoot(err);
+            if (root) {
+              setStaleRoot(root);
+            }
+            appendLog("error", `selection-preload failed path=${path} err=${describeError(err)}`);
           }
         }),
       );
@@ -568,8 +798,47 @@ export default function App() {
     };
   }, [appendLog, fileContents, readProjectFile, selectedFiles]);
 
+  /** The stack remembered for `folderPath`, detecting and remembering it on first open */
+  const loadProjectStack = async (folderPath: string): Promise<DetectedStack | null> => {
+    const saved =
+      (await storeRef.current?.get<Record<string, DetectedStack | null>>(STACKS_STORE_KEY)) ?? {};
+    if (folderPath in saved) {
+      return saved[folderPath] ?? null;
+    }
+    try {
+      const detected = await invoke<DetectedStack | null>("detect_stack", { root: folderPath });
+      appendLog("info", `stack-detect path=${folderPath} stack=${detected?.id ?? "none"}`);
+      if (storeRef.current) {
+        await storeRef.current.set(STACKS_STORE_KEY, { ...saved, [folderPath]: detected });
+        await storeRef.current.save();
+      }
+      return detected;
+    } catch (err) {
+      appendLog("error", `stack-detect failed path=${folderPath} err=${String(err)}`);
+      return null;
+    }
+  };
+
+  /** Stop applying the detected stack's defaults to the open project */
+  const handleClearStack = async () => {
+    if (!projectPath) {
+      return;
+    }
+    projectStackRef.current = null;
+    setProjectStack(null);
+    if (storeRef.current) {
+      const saved =
+        (await storeRef.current.get<Record<string, DetectedStack | null>>(STACKS_STORE_KEY)) ?? {};
+      await storeRef.current.set(STACKS_STORE_KEY, { ...saved, [projectPath]: null });
+      await storeRef.current.save();
+    }
+    appendLog("info", `stack-clear path=${projectPath}`);
+    await loadProject(projectPath);
+  };
+
   // 3i: loadProject reads from refs, stable reference
   const loadProject = async (folderPath: string) => {
+    const isRewalk = folderPath === projectPath;
     setIsLoadingTree(true);
     setHasManualNumPacksOverride(false);
     setLastPackedFingerprint(null);
@@ -579,24 +848,42 @@ export default function App() {
     const name = parts[parts.length - 1] ?? folderPath;
     setProjectName(name);
     appendLog("info", `project-load start path=${folderPath}`);
+    if (!isRewalk) {
+      const savedRedactions = await storeRef.current?.get<Record<string, string>>(
+        REDACTIONS_STORE_KEY,
+      );
+      setRedactionText(savedRedactions?.[folderPath] ?? "");
+      projectStackRef.current = await loadProjectStack(folderPath);
+      setProjectStack(projectStackRef.current);
+    }
 
     try {
-      const customIgnoreList = ignorePatternRef.current
-        .split("\n")
+      const customIgnoreList = [
+        ...ignorePatternRef.current.split("\n"),
+        ...(projectStackRef.current?.ignorePatterns ?? []),
+      ]
         .map((p) => p.trim())
         .filter(Boolean);
 
-      const nodes = await invoke<FileNode[]>("walk_directory", {
+      const walk = await invokeCompressed<FlatWalkResponse>("walk_directory_flat", {
         customIgnorePatterns: customIgnoreList,
         path: folderPath,
         respectGitignore: gitignoreRef.current,
+        sortOrder: naturalSortRef.current ? "natural" : "bytewise",
       });
+      const nodes = buildTreeFromFlat(walk.nodes);
+      setStaleRoot(null);
+      setWalkNotice(walk.degraded?.notice ?? null);
+      if (walk.degraded) {
+        appendLog("info", `project-load degraded path=${folderPath} latencyMs=${walk.degraded.latencyMs}`);
+      }
 
-      loadTree(nodes);
+      loadTree(nodes, { preserveState: isRewalk });
 
       const totalFileCount = countNodes(nodes);
       appendLog("info", `project-load tree-ready path=${folderPath} files=${totalFileCount}`);
-      if (totalFileCount < 50) {
+      // On a network mount, contents load on demand instead of all at once.
+      if (totalFileCount < 50 && !walk.degraded) {
         const contentMap = new Map<string, string>();
 
         async function loadContents(nodeList: FileNode[]) {
@@ -608,7 +895,7 @@ export default function App() {
                 const content = await readProjectFile(node.path);
                 contentMap.set(node.path, content);
               } catch (err) {
-                appendLog("error", `project-preload failed path=${node.path} err=${String(err)}`);
+                appendLog("error", `project-preload failed path=${node.path} err=${describeError(err)}`);
               }
             }
           });
@@ -630,7 +917,8 @@ export default function App() {
       appendLog("info", `project-load success path=${folderPath}`);
     } catch (err) {
       console.error("Failed to load project:", err);
-      appendLog("error", `project-load failed path=${folderPath} err=${String(err)}`);
+      setStaleRoot(unavailableRoot(err));
+      appendLog("error", `project-load failed path=${folderPath} err=${describeError(err)}`);
     } finally {
       setIsLoadingTree(false);
     }
@@ -649,6 +937,20 @@ export default function App() {
     }
   };
 
+  // Re-attach a root whose drive came back, then re-walk it
+  const handleReattachRoot = async () => {
+    if (!staleRoot || !projectPath) {
+      return;
+    }
+    try {
+      await invoke("reattach_root", { path: staleRoot });
+      appendLog("info", `root-reattach success path=${staleRoot}`);
+      await loadProject(projectPath);
+    } catch (err) {
+      appendLog("error", `root-reattach failed path=${staleRoot} err=${describeError(err)}`);
+    }
+  };
+
   // 3l: Reopen last project
   const handleReopenLastProject = async () => {
     if (lastProjectPath) {
@@ -691,6 +993,33 @@ export default function App() {
     setShowOutput(true);
   };
 
+  const handlePackChanges = async () => {
+    appendLog("info", `pack-changes-trigger selected=${selectedFileCount}`);
+    lastRequestedPackFingerprintRef.current = currentPackFingerprint;
+    await packChanges(packOptions);
+    setShowOutput(true);
+  };
+
+  const handlePackMatches = async (query: string, contextLines: number | null) => {
+    appendLog("info", `pack-matches-trigger query=${query}`);
+    lastRequestedPackFingerprintRef.current = null;
+    await packMatches(packOptions, query, contextLines);
+    setShowOutput(true);
+  };
+
+  const handlePreviewRedactions = () =>
+    invoke<RedactionReport>("preview_redactions", {
+      files: selectedFiles
+        .filter((f) => !f.isDir)
+        .map((f) => ({ content: fileContents.get(f.path) ?? "", path: f.relativePath })),
+      rules: redactionRules,
+    });
+
+  const handleSelectLlm = (id: string) => {
+    setSelectedLlmId(id);
+    setPackOptions((prev) => ({ ...prev, outputFormat: getProfile(id).preferredFormat }));
+  };
+
   const handleFileHighlight = (path: string) => {
     setHighlightedPath(path);
     setTimeout(() => setHighlightedPath(null), 2000);
@@ -702,6 +1031,44 @@ export default function App() {
     setCenterTab("preview");
   };
 
+  const handleAutoSelect = async () => {
+    if (!projectPath) {
+      return;
+    }
+    appendLog("info", `auto-select start budget=${packingWindowTokens}`);
+    try {
+      const selection = await invoke<AutoSelection>("auto_select", {
+        budget: packingWindowTokens,
+        entryPoints: packOptions.entryPoint ? [packOptions.entryPoint] : [],
+        llmProfileId: selectedLlmId,
+        root: projectPath,
+      });
+      selectAll(false);
+      selectAll(true, new Set(selection.files.map((file) => file.path)));
+      for (const file of selection.files) {
+        appendLog("debug", `auto-select pick path=${file.relativePath} reason=${file.reason}`);
+      }
+      appendLog("info", `auto-select success ${selection.summary}`);
+    } catch (err) {
+      appendLog("error", `auto-select failed err=${describeError(err)}`);
+    }
+  };
+
+  const handleSelectDependents = async (path: string) => {
+    if (!projectPath) {
+      return;
+    }
+    appendLog("info", `dependents start path=${path}`);
+    try {
+      const dependents = await invoke<string[]>("who_depends_on", { path, root: projectPath });
+      selectAll(false);
+      selectAll(true, new Set([path, ...dependents]));
+      appendLog("info", `dependents success path=${path} count=${dependents.length}`);
+    } catch (err) {
+      appendLog("error", `dependents failed path=${path} err=${describeError(err)}`);
+    }
+  };
+
   const handleClosePreview = () => {
     setPreviewPath(null);
     setCenterTab("options");
@@ -711,6 +1078,8 @@ export default function App() {
     appendLog("info", `project-close path=${projectPath ?? "none"}`);
     setProjectPath(null);
     setProjectName("");
+    setWalkNotice(null);
+    setStaleRoot(null);
     setPreviewPath(null);
     setCenterTab("options");
     setFileContents(new Map());
@@ -762,7 +1131,7 @@ export default function App() {
       appendLog("info", `bug-report export success path=${path} lines=${debugLogs.length}`);
     } catch (err) {
       console.error("Failed to export debug logs:", err);
-      appendLog("error", `bug-report export failed err=${String(err)}`);
+      appendLog("error", `bug-report export failed err=${describeError(err)}`);
     }
   };
 
@@ -875,6 +1244,27 @@ export default function App() {
                 )}
               </div>
 
+              {staleRoot && (
+                <div className="shrink-0 px-3 py-1.5 border-b border-border flex items-center gap-2 text-[10px] text-amber-600 dark:text-amber-400">
+                  <span className="truncate" title={staleRoot}>
+                    Project drive is unavailable. Reconnect it, then re-attach.
+                  </span>
+                  <button
+                    type="button"
+                    onClick={handleReattachRoot}
+                    className="ml-auto shrink-0 px-1.5 py-0.5 rounded border border-border text-foreground hover:bg-muted transition-colors"
+                  >
+                    Re-attach
+                  </button>
+                </div>
+              )}
+
+              {walkNotice && (
+                <div className="shrink-0 px-3 py-1.5 border-b border-border text-[10px] text-amber-600 dark:text-amber-400">
+                  {walkNotice}
+                </div>
+              )}
+
               {/* File tree */}
               <div className="flex-1 overflow-hidden">
                 {isLoadingTree ? (
@@ -894,10 +1284,15 @@ export default function App() {
                     onSearchChange={setSearchQuery}
                     onSelectAll={selectAll}
                     onQuickSelect={quickSelect}
+                    onAutoSelect={handleAutoSelect}
                     onFilePreview={handleFilePreview}
+                    onSelectDependents={handleSelectDependents}
                     totalSelected={selectedFiles.length}
                     totalFiles={totalFiles}
                     splitPartCountByPath={splitPartCountByAbsolutePath}
+                    heatmap={heatmap}
+                    heatmapMetric={heatmapMetric}
+                    onHeatmapMetricChange={setHeatmapMetric}
                     debugLogging={debugLogging}
                     onDebugLog={appendDebugLog}
                     onRenderSample={appendRenderSample}
@@ -911,7 +1306,7 @@ export default function App() {
               {/* Top bar: model selector + token bar */}
               <div className="shrink-0 px-3 py-2 border-b border-border space-y-2 bg-card/50">
                 <div className="flex items-center gap-2">
-                  <LLMSelector selectedId={selectedLlmId} onSelect={setSelectedLlmId} />
+                  <LLMSelector selectedId={selectedLlmId} onSelect={handleSelectLlm} />
                   <div className="inline-flex items-center gap-1.5 h-7 px-2 rounded border border-border bg-background">
                     <span className="text-[10px] font-medium text-muted-foreground">Logs</span>
                     <select
@@ -967,7 +1362,8 @@ export default function App() {
                 </div>
                 <TokenBar
                   usedTokens={totalTokens}
-                  maxTokens={llmProfile.contextWindowTokens}
+                  maxTokens={packingWindowTokens}
+                  reservedTokens={llmProfile.contextWindowTokens - packingWindowTokens}
                   selectedFileCount={selectedFiles.length}
                   numPacks={packOptions.numPacks}
                   advisoryMaxTokensPerFile={advisoryMaxTokensPerFile}
@@ -1072,7 +1468,23 @@ export default function App() {
                       }}
                       maxPacks={maxSensiblePacks}
                       selectedFiles={selectedFiles}
-                      contextWindowTokens={llmProfile.contextWindowTokens}
+                      contextWindowTokens={packingWindowTokens}
+                      stack={projectStack}
+                      onClearStack={handleClearStack}
+                    />
+
+                    <div className="h-px bg-border/60 mx-2" />
+                    <RedactionRules
+                      value={redactionText}
+                      onChange={setRedactionText}
+                      onDryRun={handlePreviewRedactions}
+                    />
+
+                    <div className="h-px bg-border/60 mx-2" />
+                    <LanguageAssociations
+                      value={associationText}
+                      onChange={setAssociationText}
+                      error={associationError}
                     />
 
                     {selectedFiles.length > 0 && (
@@ -1136,6 +1548,18 @@ export default function App() {
                         </>
                       )}
                     </button>
+                    {isPackOutdated && packResult?.fingerprint && (
+                      <button
+                        type="button"
+                        onClick={handlePackChanges}
+                        disabled={isPacking}
+                        title="Pack only files changed since the last pack, with a refreshed repo map"
+                        className="w-full h-7 mt-1.5 inline-flex items-center justify-center gap-2 text-[11px] font-medium rounded-md border border-border text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-40 disabled:cursor-not-allowed"
+                      >
+                        Pack Changes Only
+                      </button>
+                    )}
+                    <MatchSearch disabled={isPacking} onSearch={handlePackMatches} />
                   </div>
                 </>
               )}
@@ -1146,6 +1570,13 @@ export default function App() {
               <div className="w-[400px] shrink-0 flex flex-col overflow-hidden border-l border-border">
                 <OutputPreview
                   packResult={packResult}
+                  outputFormat={packOptions.outputFormat}
+                  exportPattern={packOptions.exportPattern}
+                  redactions={redactionRules}
+                  projectName={projectName || undefined}
+                  projectPath={projectPath ?? undefined}
+                  onPatchApplied={() => projectPath && loadProject(projectPath)}
+                  fileContents={fileContents}
                   tokenMap={relativeTokenMap}
                   debugLogging={debugLogging}
                   onDebugLog={appendDebugLog}
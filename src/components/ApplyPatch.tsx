@@ -0,0 +1,157 @@
+import { invoke } from "@tauri-apps/api/core";
+import { join } from "@tauri-apps/api/path";
+import { FileDiff, Undo2 } from "lucide-react";
+import { useState } from "react";
+import type { PatchReport, UndoResult } from "@/types";
+
+interface ApplyPatchProps {
+  projectPath: string;
+  /** Called after changes are written so the project can be re-read */
+  onApplied?: () => void;
+  onEventLog?: (level: "error" | "info" | "debug", message: string) => void;
+}
+
+const STATUS_CLASS: Record<string, string> = {
+  conflict: "text-red-500 dark:text-red-400",
+  created: "text-green-600 dark:text-green-400",
+  deleted: "text-amber-600 dark:text-amber-400",
+  modified: "text-foreground",
+};
+
+function describeOffset(offset: number): string {
+  return `matched ${Math.abs(offset)} line(s) ${offset > 0 ? "below" : "above"} where stated`;
+}
+
+/** Paste a unified diff from the model's answer, check it, then write it to the project */
+export function ApplyPatch({ projectPath, onApplied, onEventLog }: ApplyPatchProps) {
+  const [text, setText] = useState("");
+  const [report, setReport] = useState<PatchReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+
+  const run = async (dryRun: boolean) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      const result = await invoke<PatchReport>("apply_unified_diff", {
+        dryRun,
+        patchText: text,
+        root: projectPath,
+      });
+      setReport(result);
+      onEventLog?.(
+        "info",
+        `apply-patch dryRun=${dryRun} files=${result.files.length} clean=${result.clean} written=${result.written}`,
+      );
+      if (result.written) {
+        onApplied?.();
+      }
+    } catch (err) {
+      setReport(null);
+      setError(String(err));
+      onEventLog?.("error", `apply-patch failed err=${String(err)}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const undo = async () => {
+    if (!report?.written) {
+      return;
+    }
+    setIsBusy(true);
+    setError(null);
+    try {
+      for (const file of report.files) {
+        await invoke<UndoResult>("undo_last_write", { path: await join(projectPath, file.path) });
+      }
+      onEventLog?.("info", `apply-patch undone files=${report.files.length}`);
+      setReport(null);
+      onApplied?.();
+    } catch (err) {
+      setError(String(err));
+      onEventLog?.("error", `apply-patch undo failed err=${String(err)}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const buttonClass =
+    "inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-50";
+
+  return (
+    <div className="px-3 py-2 border-t border-border shrink-0 space-y-1">
+      <div className="flex items-start gap-1">
+        <textarea
+          value={text}
+          onChange={(e) => {
+            setText(e.target.value);
+            setReport(null);
+          }}
+          placeholder="Model answered with a diff? Paste it here to apply it"
+          className="flex-1 h-10 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+        />
+        <div className="flex flex-col gap-1">
+          <button
+            type="button"
+            onClick={() => run(true)}
+            disabled={isBusy || text.trim() === ""}
+            className={buttonClass}
+            title="Check every hunk against the project without writing"
+          >
+            <FileDiff className="h-3 w-3" />
+            Check
+          </button>
+          <button
+            type="button"
+            onClick={() => run(false)}
+            disabled={isBusy || !report?.clean || report.written}
+            className={buttonClass}
+            title="Write the changes; only enabled once every hunk matches"
+          >
+            Apply
+          </button>
+        </div>
+      </div>
+      {error && <p className="text-[10px] text-red-500 dark:text-red-400">{error}</p>}
+      {report && (
+        <div className="text-[10px] font-mono space-y-0.5 max-h-32 overflow-y-auto">
+          <p className="text-muted-foreground">
+            {report.written
+              ? `Applied to ${report.files.length} file(s)`
+              : report.clean
+                ? `All hunks match in ${report.files.length} file(s)`
+                : "Conflicts found; nothing was written"}
+            {report.written && (
+              <button
+                type="button"
+                onClick={undo}
+                disabled={isBusy}
+                className="ml-2 inline-flex items-center gap-0.5 hover:text-foreground disabled:opacity-50"
+                title="Put back every file this patch changed"
+              >
+                <Undo2 className="h-3 w-3" />
+                Undo
+              </button>
+            )}
+          </p>
+          {report.files.map((file) => (
+            <div key={file.path}>
+              <span className={STATUS_CLASS[file.status]}>
+                {file.status} {file.path}
+              </span>
+              {file.error && <span className="text-red-500 dark:text-red-400"> · {file.error}</span>}
+              {file.hunks
+                .filter((hunk) => !hunk.applied || hunk.offset !== 0)
+                .map((hunk) => (
+                  <div key={hunk.header} className="pl-3 text-muted-foreground">
+                    {hunk.header} {hunk.applied ? describeOffset(hunk.offset) : hunk.message}
+                  </div>
+                ))}
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,11 +1,11 @@
 import { useVirtualizer } from "@tanstack/react-virtual";
-import { ChevronDown, ChevronRight, File, Folder, FolderOpen, Search } from "lucide-react";
+import { ChevronDown, ChevronRight, File, Folder, FolderOpen, Network, Search } from "lucide-react";
 import { useRef } from "react";
 import { TokenBadge } from "@/components/TokenBadge";
 import type { QuickFilter } from "@/hooks/useFileTree";
 import { useRenderDiagnostics } from "@/lib/render-diagnostics";
 import { cn } from "@/lib/utils";
-import type { FlatTreeItem } from "@/types";
+import type { FlatTreeItem, HeatmapMetric } from "@/types";
 
 interface FileTreeProps {
   flatItems: FlatTreeItem[];
@@ -18,10 +18,18 @@ interface FileTreeProps {
   onSearchChange: (q: string) => void;
   onSelectAll: (selected: boolean, filteredPaths?: Set<string>) => void;
   onQuickSelect: (filter: QuickFilter) => void;
+  /** Select the highest-value files that fit the packing window */
+  onAutoSelect?: () => void;
   onFilePreview: (path: string) => void;
+  /** Select a file with every file that transitively imports it */
+  onSelectDependents?: (path: string) => void;
   totalSelected: number;
   totalFiles: number;
   splitPartCountByPath?: Map<string, number>;
+  /** `get_heatmap` values by absolute path, shown as a bar on each file row */
+  heatmap?: Map<string, number>;
+  heatmapMetric?: HeatmapMetric | null;
+  onHeatmapMetricChange?: (metric: HeatmapMetric | null) => void;
   debugLogging?: boolean;
   onDebugLog?: (line: string) => void;
   onRenderSample?: (component: string, timestampMs: number) => void;
@@ -83,6 +91,8 @@ function TreeRow({
   onToggleExpand,
   onFilePreview,
   splitPartCountByPath,
+  heat,
+  onSelectDependents,
 }: {
   item: FlatTreeItem;
   tokenMap: Map<string, number>;
@@ -92,6 +102,9 @@ function TreeRow({
   onToggleExpand: (id: string) => void;
   onFilePreview: (path: string) => void;
   splitPartCountByPath?: Map<string, number>;
+  /** This file's heatmap value and its share of the hottest file's, when a metric is on */
+  heat?: { value: number; ratio: number };
+  onSelectDependents?: (path: string) => void;
 }) {
   const { node, depth, hasChildren } = item;
   const tokens = tokenMap.get(node.path) ?? node.tokenCount;
@@ -185,6 +198,31 @@ function TreeRow({
         </span>
       </button>
 
+      {/* Heatmap bar */}
+      {!node.isDir && heat && (
+        <span
+          className="h-3 w-1 shrink-0 rounded-full"
+          style={{ backgroundColor: `hsl(var(--destructive) / ${(0.1 + 0.9 * heat.ratio).toFixed(2)})` }}
+          title={String(heat.value)}
+        />
+      )}
+
+      {/* What breaks if this file changes */}
+      {!node.isDir && onSelectDependents && (
+        <button
+          type="button"
+          onClick={(e) => {
+            e.stopPropagation();
+            onSelectDependents(node.path);
+          }}
+          className="flex items-center justify-center h-4 w-4 shrink-0 text-muted-foreground/60 hover:text-foreground opacity-0 group-hover:opacity-100 transition-opacity"
+          title="Select this file and every file that depends on it"
+          aria-label={`Select dependents of ${node.name}`}
+        >
+          <Network className="h-3 w-3" />
+        </button>
+      )}
+
       {/* Token count badge */}
       {!node.isDir && tokens > 0 && (
         <TokenBadge
@@ -224,10 +262,15 @@ export function FileTree({
   onSearchChange,
   onSelectAll,
   onQuickSelect,
+  onAutoSelect,
   onFilePreview,
+  onSelectDependents,
   totalSelected,
   totalFiles,
   splitPartCountByPath,
+  heatmap,
+  heatmapMetric = null,
+  onHeatmapMetricChange,
   debugLogging = false,
   onDebugLog,
   onRenderSample,
@@ -276,6 +319,17 @@ export function FileTree({
     }
   }
 
+  let maxHeat = 1;
+  for (const value of heatmap?.values() ?? []) {
+    if (value > maxHeat) {
+      maxHeat = value;
+    }
+  }
+  const heatOf = (path: string) => {
+    const value = heatmapMetric ? heatmap?.get(path) : undefined;
+    return value === undefined ? undefined : { ratio: value / maxHeat, value };
+  };
+
   // 3q: pass visibleFilePaths when search is active
   const handleSelectAll = (selected: boolean) => {
     if (searchQuery) {
@@ -304,6 +358,21 @@ export function FileTree({
             {totalSelected}/{totalFiles}
           </span>
           <div className="flex items-center gap-2">
+            {onHeatmapMetricChange && (
+              <select
+                value={heatmapMetric ?? ""}
+                onChange={(e) =>
+                  onHeatmapMetricChange((e.target.value || null) as HeatmapMetric | null)
+                }
+                title="Color files by a heatmap metric"
+                className="text-[10px] bg-transparent text-muted-foreground hover:text-foreground focus:outline-none"
+              >
+                <option value="">no heat</option>
+                <option value="tokens">tokens</option>
+                <option value="complexity">complexity</option>
+                <option value="churn">churn</option>
+              </select>
+            )}
             <button
               type="button"
               onClick={() => handleSelectAll(true)}
@@ -340,6 +409,16 @@ export function FileTree({
               {label}
             </button>
           ))}
+          {onAutoSelect && (
+            <button
+              type="button"
+              title="Select the files that matter most (entry point reach, imports, recent changes) within the packing window"
+              onClick={onAutoSelect}
+              className="text-[10px] px-1.5 py-0.5 rounded border font-medium transition-colors border-primary/30 text-primary/70 hover:text-primary hover:border-primary bg-primary/5 hover:bg-primary/10"
+            >
+              Best fit
+            </button>
+          )}
         </div>
       </div>
 
@@ -382,6 +461,8 @@ export function FileTree({
                     onToggleExpand={onToggleExpand}
                     onFilePreview={onFilePreview}
                     splitPartCountByPath={splitPartCountByPath}
+                    heat={heatOf(item.node.path)}
+                    onSelectDependents={onSelectDependents}
                   />
                 </div>
               );
@@ -0,0 +1,43 @@
+import { ChevronDown, ChevronRight } from "lucide-react";
+import { useState } from "react";
+import { Collapsible, CollapsibleContent, CollapsibleTrigger } from "@/components/ui/collapsible";
+
+interface LanguageAssociationsProps {
+  /** Raw association text, one `extension-or-file-name => language` per line */
+  value: string;
+  onChange: (value: string) => void;
+  /** Set when the backend rejected the last save */
+  error?: string | null;
+}
+
+export function LanguageAssociations({ value, onChange, error }: LanguageAssociationsProps) {
+  const [isOpen, setIsOpen] = useState(false);
+
+  return (
+    <Collapsible open={isOpen} onOpenChange={setIsOpen}>
+      <CollapsibleTrigger className="w-full hover:bg-muted/50 rounded transition-colors">
+        <div className="flex items-center justify-between py-1.5 px-2">
+          <span className="text-[10px] font-semibold text-muted-foreground uppercase tracking-widest">
+            Language Associations
+          </span>
+          {isOpen ? (
+            <ChevronDown className="h-3 w-3 text-muted-foreground/60" />
+          ) : (
+            <ChevronRight className="h-3 w-3 text-muted-foreground/60" />
+          )}
+        </div>
+      </CollapsibleTrigger>
+      <CollapsibleContent>
+        <div className="px-2 pb-2 space-y-1.5">
+          <textarea
+            value={value}
+            onChange={(e) => onChange(e.target.value)}
+            placeholder={"# fences and parsing for unusual files\n.mjsx => javascript\n.gqls => graphql\nJustfile => make"}
+            className="w-full h-20 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+          />
+          {error && <p className="text-[10px] text-red-500 dark:text-red-400 font-mono">{error}</p>}
+        </div>
+      </CollapsibleContent>
+    </Collapsible>
+  );
+}
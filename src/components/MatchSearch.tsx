@@ -0,0 +1,64 @@
+import { Search } from "lucide-react";
+import { useState } from "react";
+
+interface MatchSearchProps {
+  disabled?: boolean;
+  /** `contextLines` is null to pack whole files, otherwise lines kept around each match */
+  onSearch: (query: string, contextLines: number | null) => void;
+}
+
+const CONTEXT_CHOICES: Array<{ label: string; value: number | null }> = [
+  { label: "Whole files", value: null },
+  { label: "±3 lines", value: 3 },
+  { label: "±10 lines", value: 10 },
+];
+
+/** "Find all usages of X and show me": packs the project files that contain a search term */
+export function MatchSearch({ disabled, onSearch }: MatchSearchProps) {
+  const [query, setQuery] = useState("");
+  const [contextIndex, setContextIndex] = useState(0);
+
+  const submit = () => {
+    const trimmed = query.trim();
+    if (trimmed) {
+      onSearch(trimmed, CONTEXT_CHOICES[contextIndex].value);
+    }
+  };
+
+  return (
+    <div className="mt-1.5 flex items-center gap-1">
+      <input
+        type="text"
+        value={query}
+        onChange={(e) => setQuery(e.target.value)}
+        onKeyDown={(e) => {
+          if (e.key === "Enter") {
+            submit();
+          }
+        }}
+        placeholder="Pack files containing…"
+        className="flex-1 min-w-0 h-7 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+      />
+      <select
+        value={contextIndex}
+        onChange={(e) => setContextIndex(Number(e.target.value))}
+        className="h-7 text-[11px] bg-muted/40 border border-border rounded px-1 text-muted-foreground"
+      >
+        {CONTEXT_CHOICES.map((choice, i) => (
+          <option key={choice.label} value={i}>
+            {choice.label}
+          </option>
+        ))}
+      </select>
+      <button
+        type="button"
+        onClick={submit}
+        disabled={disabled || query.trim() === ""}
+        title="Search the project and pack the matches"
+        className="h-7 w-7 inline-flex items-center justify-center rounded-md border border-border text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-40 disabled:cursor-not-allowed"
+      >
+        <Search className="h-3.5 w-3.5" />
+      </button>
+    </div>
+  );
+}
@@ -1,16 +1,61 @@
 import { invoke } from "@tauri-apps/api/core";
-import { join } from "@tauri-apps/api/path";
-import { open } from "@tauri-apps/plugin-dialog";
-import { ChevronDown, ChevronRight, FileText, Package, X } from "lucide-react";
-import { useState } from "react";
+import { dirname, join } from "@tauri-apps/api/path";
+import { writeText } from "@tauri-apps/plugin-clipboard-manager";
+import { open, save } from "@tauri-apps/plugin-dialog";
+import {
+  ChevronDown,
+  ChevronRight,
+  Copy,
+  FilePlus,
+  FileText,
+  Lock,
+  Package,
+  X,
+} from "lucide-react";
+import { useEffect, useState } from "react";
+import { ApplyPatch } from "@/components/ApplyPatch";
+import { ResponseFiles } from "@/components/ResponseFiles";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
-import { buildPackFileTokenMap } from "@/lib/output-preview";
+import { describeError } from "@/lib/fs-errors";
+import { buildPackFileTokenMap, parseRequestedPaths } from "@/lib/output-preview";
 import { useRenderDiagnostics } from "@/lib/render-diagnostics";
 import { formatTokenCount } from "@/lib/utils";
-import type { PackResponse } from "@/types";
+import type {
+  ClipboardChunk,
+  ExportRecord,
+  FilePlacement,
+  OutputFormat,
+  PackItem,
+  PackPreview,
+  PackResponse,
+  RedactionRule,
+} from "@/types";
+
+/** Extension of exported pack files, by output format */
+const EXPORT_EXTENSIONS: Record<OutputFormat, string> = {
+  html: "html",
+  json: "json",
+  markdown: "md",
+  plaintext: "txt",
+  xml: "xml",
+};
 
 interface OutputPreviewProps {
   packResult: PackResponse;
+  /** Format of the packs, reused when formatting files the model asks for afterwards */
+  outputFormat: OutputFormat;
+  /** File name pattern for "export all"; "" uses `{project}-pack-{index:02}-of-{total}` and the format's extension */
+  exportPattern?: string;
+  /** Project redaction rules, also applied to files copied after packing */
+  redactions: RedactionRule[];
+  /** Recorded with each export in the audit log */
+  projectName?: string;
+  /** Root that diffs from the model's answer are applied to */
+  projectPath?: string;
+  /** Called after a diff or extracted files are written so the project can be re-read */
+  onPatchApplied?: () => void;
+  /** File contents as read for packing, by absolute path; write-back merges against them */
+  fileContents?: Map<string, string>;
   /** Optional real per-file token counts from the tokenizer; used to show accurate per-file estimates */
   tokenMap?: Map<string, number>;
   debugLogging?: boolean;
@@ -22,10 +67,12 @@ interface OutputPreviewProps {
 
 function PackManifest({
   filePaths,
+  placements,
   tokenMap,
   totalTokens,
 }: {
   filePaths: string[];
+  placements: Map<string, FilePlacement>;
   tokenMap: Map<string, number>;
   totalTokens: number;
 }) {
@@ -34,7 +81,7 @@ function PackManifest({
       {filePaths.map((p) => {
         const t = tokenMap.get(p);
         return (
-          <div key={p} className="flex items-center gap-2">
+          <div key={p} className="flex items-center gap-2" title={placements.get(p)?.reason}>
             <span className="text-muted-foreground">📄</span>
             <span className="flex-1 truncate text-foreground/70">{p}</span>
             {t !== undefined && (
@@ -50,23 +97,381 @@ function PackManifest({
   );
 }
 
+/** Packs longer than this are previewed as a head+tail excerpt fetched from the backend */
+const PREVIEW_MAX_CHARS = 200_000;
+
+function usePackPreview(packId: string, content: string): string {
+  const [preview, setPreview] = useState<string | null>(null);
+  const isLarge = content.length > PREVIEW_MAX_CHARS;
+
+  useEffect(() => {
+    setPreview(null);
+    if (!isLarge) {
+      return;
+    }
+    let cancelled = false;
+    invoke<PackPreview>("preview_pack", { maxChars: PREVIEW_MAX_CHARS, packId })
+      .then((result) => {
+        if (!cancelled) {
+          setPreview(result.content);
+        }
+      })
+      .catch((err) => console.warn("Pack preview failed:", err));
+    return () => {
+      cancelled = true;
+    };
+  }, [packId, isLarge]);
+
+  if (!isLarge) {
+    return content;
+  }
+  return preview ?? content.slice(0, PREVIEW_MAX_CHARS);
+}
+
+function SuggestedPrompt({ prompt }: { prompt: string }) {
+  return (
+    <div className="mb-2 p-2 bg-primary/5 border border-primary/20 rounded text-[10px] flex items-start gap-2">
+      <p className="flex-1 text-foreground/80">{prompt}</p>
+      <button
+        type="button"
+        onClick={() => navigator.clipboard.writeText(prompt)}
+        className="shrink-0 text-muted-foreground hover:text-foreground transition-colors"
+        title="Copy prompt"
+      >
+        <Copy className="h-3 w-3" />
+      </button>
+    </div>
+  );
+}
+
+/** Append an export to the backend audit log; failures are logged, never block the export */
+async function recordExport(
+  record: ExportRecord,
+  onEventLog?: (level: "error" | "info" | "debug", message: string) => void,
+) {
+  try {
+    await invoke("record_export", { record });
+  } catch (err) {
+    onEventLog?.("error", `export-audit failed err=${String(err)}`);
+  }
+}
+
+function ExportHistory() {
+  const [history, setHistory] = useState<ExportRecord[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<ExportRecord[]>("get_export_history", { limit: 20 })
+      .then(setHistory)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  if (error) {
+    return <p className="mt-1.5 text-[10px] text-red-500 dark:text-red-400">{error}</p>;
+  }
+  if (!history) {
+    return null;
+  }
+  if (history.length === 0) {
+    return <p className="mt-1.5 text-[10px] text-muted-foreground/70">No exports recorded yet.</p>;
+  }
+  return (
+    <div className="mt-1.5 space-y-0.5 text-[10px] font-mono text-muted-foreground/70">
+      {history.map((record) => (
+        <div
+          key={`${record.timestamp}:${record.destination}`}
+          className="truncate"
+          title={record.destination}
+        >
+          {record.timestamp} · {record.project ?? "—"} · {record.kind} · {record.fileCount} files · ~
+          {formatTokenCount(record.tokenCount)} → {record.destination}
+        </div>
+      ))}
+    </div>
+  );
+}
+
+/** Paste the paths the model asked for; the files are copied formatted like the packs */
+function RequestedFiles({
+  outputFormat,
+  redactions,
+  projectName,
+  onEventLog,
+}: {
+  outputFormat: OutputFormat;
+  redactions: RedactionRule[];
+  projectName?: string;
+  onEventLog?: (level: "error" | "info" | "debug", message: string) => void;
+}) {
+  const [text, setText] = useState("");
+  const [status, setStatus] = useState<string | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const paths = parseRequestedPaths(text);
+
+  const handleCopy = async () => {
+    setIsBusy(true);
+    setStatus(null);
+    try {
+      const item = await invoke<PackItem>("pack_additional", {
+        format: outputFormat,
+        paths,
+        redactions,
+      });
+      await navigator.clipboard.writeText(item.content);
+      await recordExport(
+        {
+          destination: "clipboard",
+          fileCount: item.fileCount,
+          kind: "clipboard",
+          project: projectName,
+          tokenCount: item.estimatedTokens,
+        },
+        onEventLog,
+      );
+      setStatus(`Copied ${item.fileCount} file(s) · ~${formatTokenCount(item.estimatedTokens)}`);
+      onEventLog?.("info", `pack-additional success files=${item.fileCount}`);
+    } catch (err) {
+      setStatus(String(err));
+      onEventLog?.("error", `pack-additional failed err=${String(err)}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  return (
+    <div className="px-3 py-2 border-t border-border shrink-0 space-y-1">
+      <div className="flex items-start gap-1">
+        <textarea
+          value={text}
+          onChange={(e) => setText(e.target.value)}
+          placeholder="Model asked for more files? Paste their paths here"
+          className="flex-1 h-10 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+        />
+        <button
+          type="button"
+          onClick={handleCopy}
+          disabled={isBusy || paths.length === 0}
+          className="inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-50"
+          title="Copy the requested files, formatted like the packs"
+        >
+          <FilePlus className="h-3 w-3" />
+          {isBusy ? "Copying..." : "Copy Files"}
+        </button>
+      </div>
+      {status && <p className="text-[10px] text-muted-foreground">{status}</p>}
+    </div>
+  );
+}
+
+/** Minimum passphrase length; the backend enforces the same rule */
+const MIN_PASSPHRASE_CHARS = 12;
+
+/** Passphrase form for exporting all packs as one age-encrypted file */
+function EncryptedExport({
+  packResult,
+  projectName,
+  onDone,
+  onEventLog,
+}: {
+  packResult: PackResponse;
+  projectName?: string;
+  onDone: () => void;
+  onEventLog?: (level: "error" | "info" | "debug", message: string) => void;
+}) {
+  const [passphrase, setPassphrase] = useState("");
+  const [confirmation, setConfirmation] = useState("");
+  const [isExporting, setIsExporting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const validation =
+    passphrase.length < MIN_PASSPHRASE_CHARS
+      ? `At least ${MIN_PASSPHRASE_CHARS} characters`
+      : passphrase !== confirmation
+        ? "Passphrases do not match"
+        : null;
+
+  const handleExport = async () => {
+    setIsExporting(true);
+    setError(null);
+    onEventLog?.("info", `export-encrypted start packs=${packResult.packs.length}`);
+    try {
+      const path = await save({
+        defaultPath: `bablusheed_packs_${packResult.packs.length}.age`,
+        filters: [{ extensions: ["age"], name: "age Encrypted File" }],
+      });
+      if (!path) {
+        onEventLog?.("info", "export-encrypted cancelled");
+        return;
+      }
+      await invoke("authorize_export_directory", { path: await dirname(path) });
+      await invoke("export_encrypted_packs", {
+        packIds: packResult.packs.map((pack) => pack.id),
+        passphrase,
+        path,
+      });
+      await recordExport(
+        {
+          destination: path,
+          fileCount: packResult.packs.reduce((sum, pack) => sum + pack.fileCount, 0),
+          kind: "encrypted",
+          project: projectName,
+          tokenCount: packResult.totalTokens,
+        },
+        onEventLog,
+      );
+      onEventLog?.("info", `export-encrypted success path=${path}`);
+      onDone();
+    } catch (err) {
+      setError(String(err));
+      onEventLog?.("error", `export-encrypted failed err=${String(err)}`);
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  return (
+    <div className="px-3 py-2 border-b border-border shrink-0 space-y-1">
+      <p className="text-[10px] text-muted-foreground">
+        Exports all packs as one file encrypted with age. Share the passphrase separately; the
+        recipient decrypts with <code>age --decrypt</code>.
+      </p>
+      <div className="flex items-center gap-1">
+        <input
+          type="password"
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          placeholder="Passphrase"
+          className="flex-1 min-w-0 h-6 text-[11px] bg-muted/40 border border-border rounded px-2 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background"
+        />
+        <input
+          type="password"
+          value={confirmation}
+          onChange={(e) => setConfirmation(e.target.value)}
+          placeholder="Confirm"
+          className="flex-1 min-w-0 h-6 text-[11px] bg-muted/40 border border-border rounded px-2 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background"
+        />
+        <button
+          type="button"
+          onClick={handleExport}
+          disabled={isExporting || validation !== null}
+          title={validation ?? "Choose where to save the encrypted file"}
+          className="inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-50"
+        >
+          {isExporting ? "Encrypting..." : "Save"}
+        </button>
+      </div>
+      {(error || (passphrase && validation)) && (
+        <p className="text-[10px] text-red-500 dark:text-red-400">{error ?? validation}</p>
+      )}
+    </div>
+  );
+}
+
+/**
+ * Copies a pack to the clipboard. Large packs go in backend-sized parts (some Linux
+ * clipboards truncate big strings); each click copies the next part.
+ */
+function CopyPackButton({
+  pack,
+  projectName,
+  onEventLog,
+}: {
+  pack: PackItem;
+  projectName?: string;
+  onEventLog?: (level: "error" | "info" | "debug", message: string) => void;
+}) {
+  const [lastChunk, setLastChunk] = useState<ClipboardChunk | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const inProgress = lastChunk !== null && !lastChunk.done;
+
+  const handleCopy = async () => {
+    setError(null);
+    try {
+      const chunk = inProgress
+        ? await invoke<ClipboardChunk>("copy_next_chunk", { packId: pack.id })
+        : await invoke<ClipboardChunk>("start_chunked_copy", { packId: pack.id });
+      await writeText(chunk.content);
+      setLastChunk(chunk);
+      onEventLog?.("debug", `copy-pack chunk=${chunk.index}/${chunk.total} pack=${pack.id}`);
+      if (chunk.index === 1) {
+        await recordExport(
+          {
+            destination: "clipboard",
+            fileCount: pack.fileCount,
+            kind: "clipboard",
+            project: projectName,
+            tokenCount: pack.estimatedTokens,
+          },
+          onEventLog,
+        );
+      }
+    } catch (err) {
+      setLastChunk(null);
+      setError(String(err));
+      onEventLog?.("error", `copy-pack failed err=${String(err)}`);
+    }
+  };
+
+  const label = inProgress
+    ? `Copy part ${lastChunk.index + 1}/${lastChunk.total}`
+    : lastChunk
+      ? lastChunk.total > 1
+        ? `Copied all ${lastChunk.total} parts`
+        : "Copied"
+      : "Copy";
+
+  return (
+    <div className="mb-2 flex items-center gap-2">
+      <button
+        type="button"
+        onClick={handleCopy}
+        className="inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors"
+        title={
+          inProgress
+            ? "Paste the part you copied, then copy the next one"
+            : "Copy this pack to the clipboard"
+        }
+      >
+        <Copy className="h-3 w-3" />
+        {label}
+      </button>
+      {inProgress && (
+        <span className="text-[10px] text-muted-foreground">
+          Part {lastChunk.index}/{lastChunk.total} copied; paste it before copying the next.
+        </span>
+      )}
+      {error && <span className="text-[10px] text-red-500 dark:text-red-400">{error}</span>}
+    </div>
+  );
+}
+
 function PackContent({
-  content,
-  filePaths,
+  pack,
+  placements,
   tokenMap,
-  packTokens,
+  projectName,
+  onEventLog,
 }: {
-  content: string;
-  filePaths: string[];
+  pack: PackItem;
+  placements: Map<string, FilePlacement>;
   tokenMap: Map<string, number>;
-  packTokens: number;
+  projectName?: string;
+  onEventLog?: (level: "error" | "info" | "debug", message: string) => void;
 }) {
+  const displayed = usePackPreview(pack.id, pack.content);
   return (
     <div className="relative flex flex-col h-full">
+      <CopyPackButton pack={pack} projectName={projectName} onEventLog={onEventLog} />
+      {pack.suggestedPrompt && <SuggestedPrompt prompt={pack.suggestedPrompt} />}
       {/* Pack manifest */}
-      <PackManifest filePaths={filePaths} tokenMap={tokenMap} totalTokens={packTokens} />
+      <PackManifest
+        filePaths={pack.filePaths}
+        placements={placements}
+        tokenMap={tokenMap}
+        totalTokens={pack.estimatedTokens}
+      />
       <pre className="flex-1 text-[11px] font-mono bg-muted/30 border border-border rounded overflow-auto whitespace-pre-wrap break-all leading-relaxed p-3 text-foreground/80">
-        {content}
+        {displayed}
       </pre>
     </div>
   );
@@ -74,6 +479,13 @@ function PackContent({
 
 export function OutputPreview({
   packResult,
+  outputFormat,
+  exportPattern = "",
+  redactions,
+  projectName,
+  projectPath,
+  onPatchApplied,
+  fileContents,
   tokenMap,
   debugLogging = false,
   onDebugLog,
@@ -91,9 +503,13 @@ export function OutputPreview({
   });
 
   const [showHowTo, setShowHowTo] = useState(false);
+  const [showHistory, setShowHistory] = useState(false);
+  const [showEncrypt, setShowEncrypt] = useState(false);
   const [exportingAll, setExportingAll] = useState(false);
+  const [exportingCombined, setExportingCombined] = useState(false);
 
   const fileTokenMap = buildPackFileTokenMap(packResult.packs, tokenMap);
+  const placements = new Map(packResult.placements?.map((p) => [p.path, p] as const));
 
   const handleExportAll = async () => {
     setExportingAll(true);
@@ -110,22 +526,75 @@ export function OutputPreview({
       }
       await invoke("authorize_export_directory", { path: folder });
 
-      for (const pack of packResult.packs) {
-        const filename = `bablusheed_pack_${pack.index + 1}_of_${packResult.packs.length}.txt`;
-        const path = await join(folder, filename);
-        onEventLog?.("debug", `export-all write start path=${path}`);
-        await invoke("write_file_content", { content: pack.content, path });
-        onEventLog?.("debug", `export-all write success path=${path}`);
+      const paths = await invoke<string[]>("export_packs", {
+        directory: folder,
+        packIds: packResult.packs.map((pack) => pack.id),
+        pattern:
+          exportPattern.trim() ||
+          `{project}-pack-{index:02}-of-{total}.${EXPORT_EXTENSIONS[outputFormat]}`,
+        project: projectName ?? "bablusheed",
+      });
+      for (const [i, pack] of packResult.packs.entries()) {
+        await recordExport(
+          {
+            destination: paths[i],
+            fileCount: pack.fileCount,
+            kind: "file",
+            project: projectName,
+            tokenCount: pack.estimatedTokens,
+          },
+          onEventLog,
+        );
+        onEventLog?.("debug", `export-all write success path=${paths[i]}`);
       }
       onEventLog?.("info", `export-all success packs=${packResult.packs.length} dir=${folder}`);
     } catch (err) {
       console.error("Export all failed:", err);
-      onEventLog?.("error", `export-all failed err=${String(err)}`);
+      onEventLog?.("error", `export-all failed err=${describeError(err)}`);
     } finally {
       setExportingAll(false);
     }
   };
 
+  const handleExportCombined = async () => {
+    setExportingCombined(true);
+    onEventLog?.("info", `export-combined start packs=${packResult.packs.length}`);
+    try {
+      const folder = await open({
+        directory: true,
+        multiple: false,
+        title: "Select folder for the combined pack file",
+      });
+      if (!folder || typeof folder !== "string") {
+        onEventLog?.("info", "export-combined cancelled");
+        return;
+      }
+      await invoke("authorize_export_directory", { path: folder });
+
+      const path = await join(folder, `bablusheed_packs_${packResult.packs.length}_combined.txt`);
+      await invoke("export_combined_packs", {
+        packIds: packResult.packs.map((pack) => pack.id),
+        path,
+      });
+      await recordExport(
+        {
+          destination: path,
+          fileCount: packResult.packs.reduce((sum, pack) => sum + pack.fileCount, 0),
+          kind: "combined",
+          project: projectName,
+          tokenCount: packResult.totalTokens,
+        },
+        onEventLog,
+      );
+      onEventLog?.("info", `export-combined success path=${path}`);
+    } catch (err) {
+      console.error("Export combined failed:", err);
+      onEventLog?.("error", `export-combined failed err=${String(err)}`);
+    } finally {
+      setExportingCombined(false);
+    }
+  };
+
   return (
     <div className="flex flex-col h-full bg-background">
       {/* Header */}
@@ -151,6 +620,27 @@ export function OutputPreview({
             <Package className="h-3 w-3" />
             {exportingAll ? "Exporting..." : packResult.packs.length > 1 ? "Export All" : "Export"}
           </button>
+          {packResult.packs.length > 1 && (
+            <button
+              type="button"
+              onClick={handleExportCombined}
+              disabled={exportingCombined}
+              className="inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-50"
+              title="Export all packs as one .txt file with === PACK i/n === markers"
+            >
+              <FileText className="h-3 w-3" />
+              {exportingCombined ? "Exporting..." : "Combined"}
+            </button>
+          )}
+          <button
+            type="button"
+            onClick={() => setShowEncrypt((v) => !v)}
+            className="inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors"
+            title="Export all packs as one passphrase-encrypted .age file"
+          >
+            <Lock className="h-3 w-3" />
+            Encrypted
+          </button>
           <button
             type="button"
             onClick={onClose}
@@ -161,6 +651,15 @@ export function OutputPreview({
         </div>
       </div>
 
+      {showEncrypt && (
+        <EncryptedExport
+          packResult={packResult}
+          projectName={projectName}
+          onDone={() => setShowEncrypt(false)}
+          onEventLog={onEventLog}
+        />
+      )}
+
       {/* Content */}
       <div className="flex-1 overflow-hidden p-3">
         <Tabs defaultValue="0" className="h-full flex flex-col">
@@ -175,7 +674,9 @@ export function OutputPreview({
                 Pack {pack.index + 1}
                 <span className="ml-1 opacity-60 font-mono">
                   ~{formatTokenCount(pack.estimatedTokens)}
+                  {pack.savedTokens ? ` (−${formatTokenCount(pack.savedTokens)})` : null}
                 </span>
+                {!pack.changed && <span className="ml-1 opacity-60">· unchanged</span>}
               </TabsTrigger>
             ))}
           </TabsList>
@@ -188,10 +689,11 @@ export function OutputPreview({
             >
               <div className="flex-1 overflow-hidden flex flex-col">
                 <PackContent
-                  content={pack.content}
-                  filePaths={pack.filePaths}
+                  pack={pack}
+                  placements={placements}
                   tokenMap={fileTokenMap}
-                  packTokens={pack.estimatedTokens}
+                  projectName={projectName}
+                  onEventLog={onEventLog}
                 />
               </div>
             </TabsContent>
@@ -199,6 +701,24 @@ export function OutputPreview({
         </Tabs>
       </div>
 
+      <RequestedFiles
+        outputFormat={outputFormat}
+        redactions={redactions}
+        projectName={projectName}
+        onEventLog={onEventLog}
+      />
+      {projectPath && (
+        <>
+          <ApplyPatch projectPath={projectPath} onApplied={onPatchApplied} onEventLog={onEventLog} />
+          <ResponseFiles
+            projectPath={projectPath}
+            fileContents={fileContents ?? new Map()}
+            onWritten={onPatchApplied}
+            onEventLog={onEventLog}
+          />
+        </>
+      )}
+
       {/* How-to hint (collapsible) */}
       <div className="px-3 py-2 border-t border-border shrink-0">
         <button
@@ -217,6 +737,15 @@ export function OutputPreview({
             <li>Paste your prompt and send.</li>
           </ol>
         )}
+        <button
+          type="button"
+          onClick={() => setShowHistory((v) => !v)}
+          className="mt-1 flex items-center gap-1 text-[10px] text-muted-foreground/70 hover:text-muted-foreground transition-colors w-full"
+        >
+          {showHistory ? <ChevronDown className="h-3 w-3" /> : <ChevronRight className="h-3 w-3" />}
+          Export history
+        </button>
+        {showHistory && <ExportHistory />}
       </div>
     </div>
   );
@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import { ChevronDown, ChevronRight, Info } from "lucide-react";
 import { useEffect, useMemo, useRef, useState } from "react";
 import { Collapsible, CollapsibleContent, CollapsibleTrigger } from "@/components/ui/collapsible";
@@ -5,11 +6,18 @@ import { Switch } from "@/components/ui/switch";
 import { Tooltip, TooltipContent, TooltipTrigger } from "@/components/ui/tooltip";
 import { AST_SUPPORTED_EXTENSIONS } from "@/lib/ast-reachability";
 import {
+  MAX_CONVERSATION_RESERVE,
   deriveAdvisoryMaxTokensPerFile,
   resolveAdvisoryMaxTokensPerFile,
 } from "@/lib/pack-strategy";
 import { cn } from "@/lib/utils";
-import type { FileTreeNode, PackOptions as PackOptionsType } from "@/types";
+import type {
+  DetectedStack,
+  FileTreeNode,
+  OutputFormat,
+  PackOptions as PackOptionsType,
+  PackProfile,
+} from "@/types";
 
 type PackOptionsProps = {
   options: PackOptionsType;
@@ -17,6 +25,9 @@ type PackOptionsProps = {
   maxPacks: number;
   selectedFiles: FileTreeNode[];
   contextWindowTokens: number;
+  /** Stack detected for the project, whose ignore patterns and entry point are applied */
+  stack?: DetectedStack | null;
+  onClearStack?: () => void;
 };
 
 function SectionHeader({ label, isOpen }: { label: string; isOpen: boolean }) {
@@ -83,17 +94,36 @@ function ToggleRow({
 // Derive the display hint from the constant so it always matches the actual supported list
 const AST_SUPPORTED_EXTENSIONS_HINT = Array.from(AST_SUPPORTED_EXTENSIONS).join(", ");
 
+const FORMAT_LABELS: Record<OutputFormat, string> = {
+  html: "HTML",
+  json: "JSON",
+  markdown: "Markdown",
+  plaintext: "Plain",
+  xml: "XML",
+};
+
+/** Options a pack profile sets; editing any of them by hand detaches the profile */
+const PROFILE_OPTION_KEYS = [
+  "normalizeContent",
+  "numPacks",
+  "outputFormat",
+  "stripImports",
+] as const;
+
 export function PackOptions({
   options,
   onChange,
   maxPacks,
   selectedFiles,
   contextWindowTokens,
+  stack,
+  onClearStack,
 }: PackOptionsProps) {
   const [outputOpen, setOutputOpen] = useState(true);
   const [optimizeOpen, setOptimizeOpen] = useState(true);
   const [ignoreOpen, setIgnoreOpen] = useState(false);
   const optionsRef = useRef(options);
+  const [packProfiles, setPackProfiles] = useState<PackProfile[]>([]);
 
   const hasMarkdownFiles = selectedFiles.some((f) => f.extension === "md");
   const autoAdvisoryMax = deriveAdvisoryMaxTokensPerFile(contextWindowTokens);
@@ -112,9 +142,31 @@ export function PackOptions({
   );
 
   const update = (partial: Partial<PackOptionsType>) => {
-    onChange({ ...options, ...partial });
+    const detachesProfile = PROFILE_OPTION_KEYS.some((key) => key in partial);
+    onChange({ ...options, ...(detachesProfile ? { packProfileId: null } : {}), ...partial });
+  };
+
+  const applyPackProfile = (profileId: string) => {
+    const profile = packProfiles.find((p) => p.id === profileId);
+    if (!profile) {
+      update({ packProfileId: null });
+      return;
+    }
+    update({
+      normalizeContent: profile.normalizeContent,
+      numPacks: Math.min(profile.numPacks, maxPacks),
+      outputFormat: profile.outputFormat,
+      packProfileId: profile.id,
+      stripImports: profile.stripImports,
+    });
   };
 
+  useEffect(() => {
+    invoke<PackProfile[]>("list_pack_profiles")
+      .then(setPackProfiles)
+      .catch((err) => console.warn("Loading pack profiles failed:", err));
+  }, []);
+
   useEffect(() => {
     optionsRef.current = options;
   }, [options]);
@@ -135,6 +187,26 @@ export function PackOptions({
         </CollapsibleTrigger>
         <CollapsibleContent>
           <div className="px-2 pb-2 space-y-3">
+            {/* Pack profile */}
+            {packProfiles.length > 0 && (
+              <div className="space-y-1.5">
+                <span className="text-xs text-foreground/80">Profile</span>
+                <select
+                  value={options.packProfileId ?? ""}
+                  onChange={(e) => applyPackProfile(e.target.value)}
+                  title={packProfiles.find((p) => p.id === options.packProfileId)?.description}
+                  className="w-full text-[10px] bg-muted/40 border border-border rounded px-1.5 py-1 focus:outline-none focus:ring-1 focus:ring-ring"
+                >
+                  <option value="">Custom</option>
+                  {packProfiles.map((profile) => (
+                    <option key={profile.id} value={profile.id}>
+                      {profile.name}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            )}
+
             {/* Number of packs */}
             <div className="space-y-1.5">
               <div className="flex items-center justify-between">
@@ -169,8 +241,8 @@ export function PackOptions({
             {/* Output format */}
             <div className="space-y-1.5">
               <span className="text-xs text-foreground/80">Format</span>
-              <div className="grid grid-cols-2 gap-1 mt-1">
-                {(["plaintext", "markdown"] as const).map((fmt) => (
+              <div className="grid grid-cols-5 gap-1 mt-1">
+                {(["plaintext", "markdown", "xml", "html", "json"] as const).map((fmt) => (
                   <button
                     key={fmt}
                     type="button"
@@ -182,12 +254,340 @@ export function PackOptions({
                         : "bg-transparent border-border text-muted-foreground hover:border-primary/50 hover:text-foreground",
                     )}
                   >
-                    {fmt === "plaintext" ? "Plain" : "Markdown"}
+                    {FORMAT_LABELS[fmt]}
                   </button>
                 ))}
               </div>
             </div>
 
+            <ToggleRow
+              label="Suggested Prompts"
+              description="Add a scaffold prompt to each pack, e.g. which pack this is and whether to wait for the rest"
+              checked={options.suggestPrompts}
+              onCheckedChange={(val) => update({ suggestPrompts: val })}
+            >
+              {options.suggestPrompts && (
+                <textarea
+                  value={options.promptTemplate}
+                  onChange={(e) => update({ promptTemplate: e.target.value })}
+                  placeholder="This is pack {index} of {total} containing {summary}. {instruction}"
+                  className="w-full h-14 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                />
+              )}
+            </ToggleRow>
+
+            <ToggleRow
+              label="Provenance Header"
+              description="Start each pack with the project name, version, and git commit so answers can be traced to an exact code state"
+              checked={options.includeProvenance}
+              onCheckedChange={(val) => update({ includeProvenance: val })}
+            />
+
+            <ToggleRow
+              label="File IDs"
+              description="Label each file with a short ID like [F017] and start each pack with an ID-to-path index, so answers can cite files compactly"
+              checked={options.fileIds}
+              onCheckedChange={(val) => update({ fileIds: val })}
+            />
+
+            <ToggleRow
+              label="Pack Notes"
+              description="Open each pack with the directories, languages, and exported symbols it covers, so a middle pack makes sense on its own"
+              checked={options.packNotes}
+              onCheckedChange={(val) => update({ packNotes: val })}
+            />
+
+            <ToggleRow
+              label="Line Numbers"
+              description="Prefix every line with its line number so answers that cite a line, or respond with patches, can be checked against the file"
+              checked={options.includeLineNumbers}
+              onCheckedChange={(val) => update({ includeLineNumbers: val })}
+            />
+
+            <ToggleRow
+              label="List Excluded Files"
+              description="End the last pack with the paths (no content) of files that were not selected, so the model can ask for them by name"
+              checked={options.listExcludedFiles}
+              onCheckedChange={(val) => update({ listExcludedFiles: val })}
+            />
+
+            <ToggleRow
+              label="Config Surface"
+              description="End the last pack with the environment variables and feature flags the packed code reads, and where"
+              checked={options.configSurface}
+              onCheckedChange={(val) => update({ configSurface: val })}
+            />
+
+            <ToggleRow
+              label="Tech Debt Index"
+              description="End the last pack with every TODO, FIXME, HACK and XXX comment in the packed files, with file and line"
+              checked={options.todoIndex}
+              onCheckedChange={(val) => update({ todoIndex: val })}
+            />
+
+            <ToggleRow
+              label="Error Message Index"
+              description="End the last pack with the error messages the packed code raises and where, so an error can be traced even to files left out"
+              checked={options.errorIndex}
+              onCheckedChange={(val) => update({ errorIndex: val })}
+            />
+
+            <ToggleRow
+              label="Referenced Types"
+              description="End the last pack with just the interfaces, type aliases, and enums that selected TypeScript files import from files left out"
+              checked={options.typeClosure}
+              onCheckedChange={(val) => update({ typeClosure: val })}
+            />
+
+            <ToggleRow
+              label="Include Schemas"
+              description="Pack the .proto and OpenAPI files behind generated code the selected files use, even when the schema and generated code were left out"
+              checked={options.includeSchemas}
+              onCheckedChange={(val) => update({ includeSchemas: val })}
+            />
+
+            <ToggleRow
+              label="Summarize Translations"
+              description="Pack one reference locale of each translation file (locales/*.json, .po) and replace the other languages with a key count and the keys they miss"
+              checked={options.summarizeLocales}
+              onCheckedChange={(val) => update({ summarizeLocales: val })}
+            />
+
+            <ToggleRow
+              label="Profile Data Files"
+              description="Pack CSV/TSV files as column names and types, row counts, and the first rows instead of every row"
+              checked={options.profileData}
+              onCheckedChange={(val) => update({ profileData: val })}
+            />
+
+            <ToggleRow
+              label="Config Schemas"
+              description="Replace large JSON/YAML files matching these globs with their inferred keys, types, and array lengths"
+              checked={options.schemaInference}
+              onCheckedChange={(val) => update({ schemaInference: val })}
+            >
+              {options.schemaInference && (
+                <div className="space-y-1.5 pl-2">
+                  <textarea
+                    value={options.schemaGlobs}
+                    onChange={(e) => update({ schemaGlobs: e.target.value })}
+                    placeholder={"**/*.json\n**/*.yaml\n**/*.yml"}
+                    className="w-full h-14 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <label className="flex items-center gap-2 text-[10px] text-muted-foreground">
+                    Larger than
+                    <input
+                      type="number"
+                      min={0}
+                      step={16}
+                      value={options.schemaMinKb}
+                      onChange={(e) => {
+                        const parsed = Number(e.target.value);
+                        update({ schemaMinKb: Number.isFinite(parsed) ? Math.max(0, parsed) : 0 });
+                      }}
+                      className="w-16 h-6 text-[11px] font-mono bg-muted/40 border border-border rounded px-1.5 focus:outline-none focus:ring-1 focus:ring-ring"
+                    />
+                    KB
+                  </label>
+                </div>
+              )}
+            </ToggleRow>
+
+            <ToggleRow
+              label="Pack Wrapper"
+              description="Frame each pack with delimiters and a metadata header (pack number, project, time)"
+              checked={options.wrapPacks}
+              onCheckedChange={(val) => update({ wrapPacks: val })}
+            >
+              {options.wrapPacks && (
+                <div className="space-y-1.5 pl-2">
+                  <input
+                    type="text"
+                    value={options.wrapperOpening}
+                    onChange={(e) => update({ wrapperOpening: e.target.value })}
+                    placeholder="Opening, e.g. === BEGIN {project} {index}/{total} ==="
+                    className="w-full text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <input
+                    type="text"
+                    value={options.wrapperClosing}
+                    onChange={(e) => update({ wrapperClosing: e.target.value })}
+                    placeholder="Closing, e.g. === END {index}/{total} ==="
+                    className="w-full text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <textarea
+                    value={options.wrapperPreamble}
+                    onChange={(e) => update({ wrapperPreamble: e.target.value })}
+                    placeholder="Preamble, e.g. You are reviewing pack {{pack_index}} of {{total_packs}} ({{file_count}} files) from {{project_name}}."
+                    className="w-full h-14 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <textarea
+                    value={options.wrapperEpilogue}
+                    onChange={(e) => update({ wrapperEpilogue: e.target.value })}
+                    placeholder="Epilogue, e.g. End of pack {{pack_index}}."
+                    className="w-full h-14 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <ToggleRow
+                    label="Pack Number"
+                    checked={options.wrapperIncludeIndex}
+                    onCheckedChange={(val) => update({ wrapperIncludeIndex: val })}
+                  />
+                  <ToggleRow
+                    label="Project Name"
+                    checked={options.wrapperIncludeProject}
+                    onCheckedChange={(val) => update({ wrapperIncludeProject: val })}
+                  />
+                  <ToggleRow
+                    label="Timestamp"
+                    checked={options.wrapperIncludeTimestamp}
+                    onCheckedChange={(val) => update({ wrapperIncludeTimestamp: val })}
+                  />
+                </div>
+              )}
+            </ToggleRow>
+
+            <ToggleRow
+              label="Custom File Boundaries"
+              description="Separate and mark file sections with your own sentinels, for chunkers that split on them"
+              checked={options.customBoundaries}
+              onCheckedChange={(val) => update({ customBoundaries: val })}
+            >
+              {options.customBoundaries && (
+                <div className="space-y-1.5 pl-2">
+                  <input
+                    type="text"
+                    value={options.sectionSeparator}
+                    onChange={(e) => update({ sectionSeparator: e.target.value })}
+                    placeholder="Separator, e.g. \n\n---\n\n"
+                    className="w-full text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <input
+                    type="text"
+                    value={options.fileStartMarker}
+                    onChange={(e) => update({ fileStartMarker: e.target.value })}
+                    placeholder="Before each file, e.g. <<<FILE_START {path}>>>"
+                    className="w-full text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <input
+                    type="text"
+                    value={options.fileEndMarker}
+                    onChange={(e) => update({ fileEndMarker: e.target.value })}
+                    placeholder="After each file, e.g. <<<FILE_END>>>"
+                    className="w-full text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                </div>
+              )}
+            </ToggleRow>
+
+            <ToggleRow
+              label="Custom Template"
+              description="Render packs with a Handlebars-style template; code2prompt templates work as-is"
+              checked={options.customTemplate}
+              onCheckedChange={(val) => update({ customTemplate: val })}
+            >
+              {options.customTemplate && (
+                <div className="space-y-1.5 pl-2">
+                  <textarea
+                    value={options.packTemplate}
+                    onChange={(e) => update({ packTemplate: e.target.value })}
+                    placeholder={"{{source_tree}}\n{{#each files}}\n`{{path}}`:\n\n{{code}}\n{{/each}}"}
+                    className="w-full h-28 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                </div>
+              )}
+            </ToggleRow>
+
+            <ToggleRow
+              label="Priority Rules"
+              description="Lead with high-priority files; low-priority ones go last and are dropped first over budget"
+              checked={options.prioritizeFiles}
+              onCheckedChange={(val) => update({ prioritizeFiles: val })}
+            >
+              {options.prioritizeFiles && (
+                <div className="space-y-1.5 pl-2">
+                  <textarea
+                    value={options.priorityRules}
+                    onChange={(e) => update({ priorityRules: e.target.value })}
+                    placeholder={"src/core/** = high\ntests/** = low"}
+                    className="w-full h-20 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <p className="text-[10px] text-muted-foreground/60">
+                    One <code>glob = high|normal|low</code> per line; the first match wins
+                  </p>
+                  <label className="flex items-center gap-1.5 cursor-pointer">
+                    <input
+                      type="checkbox"
+                      checked={options.outlineLowPriority}
+                      onChange={(e) => update({ outlineLowPriority: e.target.checked })}
+                      className="h-3 w-3 accent-primary"
+                    />
+                    <span className="text-[10px] text-muted-foreground">
+                      Pack low-priority files as signatures only
+                    </span>
+                  </label>
+                </div>
+              )}
+            </ToggleRow>
+
+            <ToggleRow
+              label="Focus Files"
+              description="Pack these files whole under a PRIMARY FILE UNDER DISCUSSION banner"
+              checked={options.focusFiles}
+              onCheckedChange={(val) => update({ focusFiles: val })}
+            >
+              {options.focusFiles && (
+                <div className="space-y-1.5 pl-2">
+                  <textarea
+                    value={options.focusPaths}
+                    onChange={(e) => update({ focusPaths: e.target.value })}
+                    placeholder={"src/core/engine.ts"}
+                    className="w-full h-16 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+                  />
+                  <p className="text-[10px] text-muted-foreground/60">
+                    One project-relative path per line; never stripped, compressed, split, or truncated
+                  </p>
+                </div>
+              )}
+            </ToggleRow>
+
+            {/* Export file names */}
+            <div className="space-y-1.5">
+              <span className="text-xs text-foreground/80">Export file names</span>
+              <input
+                type="text"
+                value={options.exportPattern}
+                onChange={(e) => update({ exportPattern: e.target.value })}
+                placeholder="{project}-pack-{index:02}-of-{total}.md"
+                className="w-full text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+              />
+              <p className="text-[10px] text-muted-foreground/60">
+                Used by "export all"; empty picks the extension from the output format
+              </p>
+            </div>
+
+            {/* Conversation reserve */}
+            <div className="space-y-1.5">
+              <div className="flex items-center justify-between">
+                <span className="text-xs text-foreground/80">Reserve for conversation</span>
+                <span className="text-xs font-mono font-semibold text-primary">
+                  {Math.round(options.conversationReserve * 100)}%
+                </span>
+              </div>
+              <input
+                type="range"
+                min={0}
+                max={MAX_CONVERSATION_RESERVE}
+                step={0.05}
+                value={options.conversationReserve}
+                onChange={(e) => update({ conversationReserve: Number(e.target.value) })}
+                className="w-full h-1.5 appearance-none bg-muted rounded-full cursor-pointer accent-primary"
+              />
+              <p className="text-[10px] text-muted-foreground">
+                Packs fit in {contextWindowTokens.toLocaleString()} tokens, leaving room for
+                follow-up turns.
+              </p>
+            </div>
+
             <div className="space-y-1.5">
               <div className="flex items-center justify-between">
                 <span className="text-xs text-foreground/80">Advisory max tokens per file</span>
@@ -234,6 +634,13 @@ export function PackOptions({
               onCheckedChange={(val) => update({ stripComments: val })}
             />
 
+            <ToggleRow
+              label="Strip Imports"
+              description="Drop top-level import/use statements; packs stay ordered by dependencies"
+              checked={options.stripImports}
+              onCheckedChange={(val) => update({ stripImports: val })}
+            />
+
             <ToggleRow
               label="Reduce Whitespace"
               description="Collapse multiple blank lines and trim trailing whitespace"
@@ -241,6 +648,13 @@ export function PackOptions({
               onCheckedChange={(val) => update({ reduceWhitespace: val })}
             />
 
+            <ToggleRow
+              label="Normalize Line Endings"
+              description="Convert CRLF to LF, drop BOMs, and trim trailing whitespace in every file"
+              checked={options.normalizeContent}
+              onCheckedChange={(val) => update({ normalizeContent: val })}
+            />
+
             <ToggleRow
               label="AST Dead-Code"
               description="Use Tree-sitter to remove unreachable functions/classes from JS/TS/Python/Rust/Go"
@@ -249,7 +663,10 @@ export function PackOptions({
                 update({
                   astDeadCode: val,
                   entryPoint: val
-                    ? (options.entryPoint ?? astEligibleFiles[0]?.path ?? null)
+                    ? (options.entryPoint ??
+                      astEligibleFiles.find((f) => f.relativePath === stack?.entryPoint)?.path ??
+                      astEligibleFiles[0]?.path ??
+                      null)
                     : null,
                 })
               }
@@ -331,6 +748,29 @@ export function PackOptions({
               onCheckedChange={(val) => update({ respectGitignore: val })}
             />
 
+            <ToggleRow
+              label="Natural Sort"
+              description="Order the tree like a file manager: File2 before File10, ignoring case"
+              checked={options.naturalSort}
+              onCheckedChange={(val) => update({ naturalSort: val })}
+            />
+
+            {stack && (
+              <div className="flex items-center justify-between gap-2 text-[10px] text-muted-foreground">
+                <span title={stack.ignorePatterns.join("\n")}>
+                  {stack.name} project: {stack.ignorePatterns.length} extra ignore pattern(s),{" "}
+                  {stack.strategy} packing
+                </span>
+                <button
+                  type="button"
+                  onClick={onClearStack}
+                  className="shrink-0 hover:text-foreground underline-offset-2 hover:underline"
+                >
+                  Turn off
+                </button>
+              </div>
+            )}
+
             <div className="space-y-1">
               <span className="text-xs text-foreground/80">Ignore Patterns</span>
               <p className="text-[10px] text-muted-foreground/60">One glob per line</p>
@@ -0,0 +1,94 @@
+import { ChevronDown, ChevronRight, ShieldCheck } from "lucide-react";
+import { useState } from "react";
+import { Collapsible, CollapsibleContent, CollapsibleTrigger } from "@/components/ui/collapsible";
+import type { RedactionReport } from "@/types";
+
+interface RedactionRulesProps {
+  /** Raw rule text for the open project, one regex per line */
+  value: string;
+  onChange: (value: string) => void;
+  /** Reports what the current rules would redact in the selected files */
+  onDryRun: () => Promise<RedactionReport>;
+}
+
+export function RedactionRules({ value, onChange, onDryRun }: RedactionRulesProps) {
+  const [isOpen, setIsOpen] = useState(value.trim() !== "");
+  const [report, setReport] = useState<RedactionReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [isRunning, setIsRunning] = useState(false);
+
+  const handleDryRun = async () => {
+    setIsRunning(true);
+    setError(null);
+    try {
+      setReport(await onDryRun());
+    } catch (err) {
+      setReport(null);
+      setError(String(err));
+    } finally {
+      setIsRunning(false);
+    }
+  };
+
+  return (
+    <Collapsible open={isOpen} onOpenChange={setIsOpen}>
+      <CollapsibleTrigger className="w-full hover:bg-muted/50 rounded transition-colors">
+        <div className="flex items-center justify-between py-1.5 px-2">
+          <span className="text-[10px] font-semibold text-muted-foreground uppercase tracking-widest">
+            Redaction Rules
+          </span>
+          {isOpen ? (
+            <ChevronDown className="h-3 w-3 text-muted-foreground/60" />
+          ) : (
+            <ChevronRight className="h-3 w-3 text-muted-foreground/60" />
+          )}
+        </div>
+      </CollapsibleTrigger>
+      <CollapsibleContent>
+        <div className="px-2 pb-2 space-y-1.5">
+          <textarea
+            value={value}
+            onChange={(e) => {
+              onChange(e.target.value);
+              setReport(null);
+            }}
+            placeholder={"# one regex per line, saved with this project\n[\\w-]+\\.corp\\.example\\.com\nAcme Corp => Client"}
+            className="w-full h-20 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1.5 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+          />
+          <button
+            type="button"
+            onClick={handleDryRun}
+            disabled={isRunning || value.trim() === ""}
+            className="inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-50"
+          >
+            <ShieldCheck className="h-3 w-3" />
+            {isRunning ? "Checking..." : "Dry Run"}
+          </button>
+          {error && <p className="text-[10px] text-red-500 dark:text-red-400 font-mono">{error}</p>}
+          {report && (
+            <div className="p-2 bg-muted/30 border border-border rounded text-[10px] font-mono space-y-0.5 max-h-40 overflow-y-auto">
+              <div className="text-muted-foreground">
+                {report.totalMatches} match(es) in {report.filesAffected} file(s)
+              </div>
+              {report.matches.map((match) => (
+                <div
+                  key={`${match.path}:${match.line}:${match.pattern}:${match.text}`}
+                  className="truncate text-foreground/70"
+                  title={`${match.path}:${match.line}`}
+                >
+                  {match.path}:{match.line} <span className="line-through">{match.text}</span> →{" "}
+                  {match.replacement}
+                </div>
+              ))}
+              {report.matches.length < report.totalMatches && (
+                <div className="text-muted-foreground">
+                  … {report.totalMatches - report.matches.length} more
+                </div>
+              )}
+            </div>
+          )}
+        </div>
+      </CollapsibleContent>
+    </Collapsible>
+  );
+}
@@ -0,0 +1,272 @@
+import { invoke } from "@tauri-apps/api/core";
+import { join } from "@tauri-apps/api/path";
+import { writeText } from "@tauri-apps/plugin-clipboard-manager";
+import { FileCode, Save } from "lucide-react";
+import { useState } from "react";
+import type { FileContent, UndoResult, WriteBackResult } from "@/types";
+
+interface ResponseFilesProps {
+  projectPath: string;
+  /** Content of each file as read for packing, by absolute path; merged against on write */
+  fileContents: Map<string, string>;
+  /** Called after files are written so the project can be re-read */
+  onWritten?: () => void;
+  onEventLog?: (level: "error" | "info" | "debug", message: string) => void;
+}
+
+function WriteStatus({
+  result,
+  disabled,
+  onWrite,
+  onUndo,
+  onCopyMerge,
+}: {
+  result?: WriteBackResult;
+  disabled: boolean;
+  onWrite: () => void;
+  onUndo: () => void;
+  onCopyMerge: (result: WriteBackResult) => void;
+}) {
+  if (!result) {
+    return (
+      <button
+        type="button"
+        onClick={onWrite}
+        disabled={disabled}
+        className="text-muted-foreground hover:text-foreground disabled:opacity-50"
+      >
+        Write
+      </button>
+    );
+  }
+  if (result.status !== "conflict") {
+    return (
+      <span className="inline-flex items-center gap-1">
+        <span className="text-green-600 dark:text-green-400">{result.status}</span>
+        <button
+          type="button"
+          onClick={onUndo}
+          disabled={disabled}
+          className="text-muted-foreground hover:text-foreground disabled:opacity-50"
+          title="Put back the content this write replaced"
+        >
+          undo
+        </button>
+      </span>
+    );
+  }
+  return (
+    <button
+      type="button"
+      onClick={() => onCopyMerge(result)}
+      disabled={!result.merged}
+      className="text-red-500 dark:text-red-400 hover:underline disabled:no-underline"
+      title={result.message ?? "Copy the merge with conflict markers to resolve by hand"}
+    >
+      {result.conflicts.length > 0 ? `${result.conflicts.length} conflict(s)` : "conflict"}
+    </button>
+  );
+}
+
+/**
+ * Paste a model's answer, pull out the files its code blocks name, and write them back.
+ * Files edited since packing are merged; conflicting ones are left alone and reported.
+ */
+export function ResponseFiles({
+  projectPath,
+  fileContents,
+  onWritten,
+  onEventLog,
+}: ResponseFilesProps) {
+  const [text, setText] = useState("");
+  const [files, setFiles] = useState<FileContent[] | null>(null);
+  const [results, setResults] = useState<Map<string, WriteBackResult>>(new Map());
+  const [status, setStatus] = useState<string | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const [diff, setDiff] = useState<{ path: string; text: string } | null>(null);
+
+  const handleExtract = async () => {
+    setStatus(null);
+    setResults(new Map());
+    setDiff(null);
+    try {
+      const extracted = await invoke<FileContent[]>("parse_model_response", { text });
+      setFiles(extracted);
+      if (extracted.length === 0) {
+        setStatus("No code blocks naming a file were found");
+      }
+      onEventLog?.("info", `parse-response files=${extracted.length}`);
+    } catch (err) {
+      setFiles(null);
+      setStatus(String(err));
+      onEventLog?.("error", `parse-response failed err=${String(err)}`);
+    }
+  };
+
+  const writeFiles = async (targets: FileContent[]) => {
+    setIsBusy(true);
+    setStatus(null);
+    const done = new Map(results);
+    let changed = 0;
+    try {
+      for (const file of targets) {
+        const path = await join(projectPath, file.path);
+        const result = await invoke<WriteBackResult>("write_back_file", {
+          base: fileContents.get(path),
+          content: file.content,
+          path,
+        });
+        done.set(file.path, result);
+        if (result.status !== "conflict") {
+          changed += 1;
+        }
+      }
+      const conflicts = targets.length - changed;
+      onEventLog?.("info", `write-back done files=${changed} conflicts=${conflicts}`);
+      if (conflicts > 0) {
+        setStatus(`${conflicts} file(s) changed on disk in overlapping places and were not written`);
+      }
+    } catch (err) {
+      setStatus(String(err));
+      onEventLog?.("error", `write-back failed err=${String(err)}`);
+    } finally {
+      setResults(done);
+      setIsBusy(false);
+      if (changed > 0) {
+        onWritten?.();
+      }
+    }
+  };
+
+  const toggleDiff = async (file: FileContent) => {
+    if (diff?.path === file.path) {
+      setDiff(null);
+      return;
+    }
+    try {
+      const text = await invoke<string>("diff_against_disk", {
+        newContent: file.content,
+        path: await join(projectPath, file.path),
+      });
+      setDiff({ path: file.path, text: text || "No changes" });
+    } catch (err) {
+      setStatus(String(err));
+      onEventLog?.("error", `diff-against-disk failed err=${String(err)}`);
+    }
+  };
+
+  const undoWrite = async (file: FileContent) => {
+    setIsBusy(true);
+    try {
+      const undo = await invoke<UndoResult>("undo_last_write", {
+        path: await join(projectPath, file.path),
+      });
+      const next = new Map(results);
+      next.delete(file.path);
+      setResults(next);
+      setStatus(undo.removed ? `Removed ${file.path}` : `Restored ${file.path}`);
+      onEventLog?.("info", `undo-write path=${file.path} remaining=${undo.remaining}`);
+      onWritten?.();
+    } catch (err) {
+      setStatus(String(err));
+      onEventLog?.("error", `undo-write failed err=${String(err)}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const copyMerge = async (result: WriteBackResult) => {
+    if (result.merged) {
+      await writeText(result.merged);
+      setStatus(`Copied ${result.path} with conflict markers`);
+    }
+  };
+
+  const buttonClass =
+    "inline-flex items-center gap-1 h-6 px-2 text-[11px] font-medium rounded border border-border bg-background text-muted-foreground hover:text-foreground hover:border-primary/50 transition-colors disabled:opacity-50";
+  const pending = files?.filter((file) => !results.has(file.path)) ?? [];
+
+  return (
+    <div className="px-3 py-2 border-t border-border shrink-0 space-y-1">
+      <div className="flex items-start gap-1">
+        <textarea
+          value={text}
+          onChange={(e) => {
+            setText(e.target.value);
+            setFiles(null);
+          }}
+          placeholder="Model answered with whole files? Paste the answer here"
+          className="flex-1 h-10 text-[11px] font-mono bg-muted/40 border border-border rounded px-2 py-1 resize-none focus:outline-none focus:ring-1 focus:ring-ring focus:bg-background placeholder:text-muted-foreground/40"
+        />
+        <div className="flex flex-col gap-1">
+          <button
+            type="button"
+            onClick={handleExtract}
+            disabled={isBusy || text.trim() === ""}
+            className={buttonClass}
+            title="List the files named by the answer's code blocks"
+          >
+            <FileCode className="h-3 w-3" />
+            Extract
+          </button>
+          <button
+            type="button"
+            onClick={() => writeFiles(pending)}
+            disabled={isBusy || pending.length === 0}
+            className={buttonClass}
+            title="Write every extracted file, merging with edits made since packing"
+          >
+            <Save className="h-3 w-3" />
+            Write all
+          </button>
+        </div>
+      </div>
+      {status && <p className="text-[10px] text-muted-foreground">{status}</p>}
+      {files && files.length > 0 && (
+        <div className="text-[10px] font-mono space-y-0.5 max-h-32 overflow-y-auto">
+          {files.map((file) => (
+            <div key={file.path} className="flex items-center gap-2">
+              <button
+                type="button"
+                onClick={() => toggleDiff(file)}
+                className="flex-1 truncate text-left hover:underline"
+                title="Show what writing this file would change"
+              >
+                {file.path}
+              </button>
+              <span className="text-muted-foreground">
+                {file.content.split("\n").length - 1} lines
+              </span>
+              <WriteStatus
+                result={results.get(file.path)}
+                disabled={isBusy}
+                onWrite={() => writeFiles([file])}
+                onUndo={() => undoWrite(file)}
+                onCopyMerge={copyMerge}
+              />
+            </div>
+          ))}
+        </div>
+      )}
+      {diff && (
+        <pre className="text-[10px] font-mono bg-muted/30 border border-border rounded p-2 max-h-48 overflow-auto whitespace-pre">
+          {diff.text.split("\n").map((line, i) => (
+            <div
+              // biome-ignore lint/suspicious/noArrayIndexKey: diff lines repeat and never reorder
+              key={i}
+              className={
+                line.startsWith("+") && !line.startsWith("+++")
+                  ? "text-green-600 dark:text-green-400"
+                  : line.startsWith("-") && !line.startsWith("---")
+                    ? "text-red-500 dark:text-red-400"
+                    : undefined
+              }
+            >
+              {line || " "}
+            </div>
+          ))}
+        </pre>
+      )}
+    </div>
+  );
+}
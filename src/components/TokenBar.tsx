@@ -13,6 +13,8 @@ interface TokenBarProps {
   onApplyOptimization?: (partial: Partial<PackOptions>) => void;
   onDeselectHeaviest?: (count: number) => void;
   advisoryMaxTokensPerFile?: number;
+  /** Tokens held back for the conversation; `maxTokens` already excludes them */
+  reservedTokens?: number;
 }
 
 export function TokenBar({
@@ -26,6 +28,7 @@ export function TokenBar({
   onApplyOptimization,
   onDeselectHeaviest,
   advisoryMaxTokensPerFile,
+  reservedTokens = 0,
 }: TokenBarProps) {
   const percentage = maxTokens > 0 ? Math.min((usedTokens / maxTokens) * 100, 100) : 0;
   const isWarning = percentage >= 85;
@@ -101,6 +104,7 @@ export function TokenBar({
             <span className="text-muted-foreground font-normal">
               {" "}
               / {formatTokenCount(maxTokens)}
+              {reservedTokens > 0 && ` (+${formatTokenCount(reservedTokens)} reserved)`}
             </span>
           </span>
           <span
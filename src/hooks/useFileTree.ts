@@ -97,6 +97,20 @@ function getSelectedFiles(nodes: FileTreeNode[]): FileTreeNode[] {
   return result;
 }
 
+/** Relative paths of files left unchecked */
+function getExcludedPaths(nodes: FileTreeNode[]): string[] {
+  const result: string[] = [];
+  for (const node of nodes) {
+    if (!node.isDir && node.checkState !== "checked") {
+      result.push(node.relativePath);
+    }
+    if (node.children) {
+      result.push(...getExcludedPaths(node.children));
+    }
+  }
+  return result;
+}
+
 function updateTokensInTree(nodes: FileTreeNode[], tokenMap: Map<string, number>): FileTreeNode[] {
   return nodes.map((node) => ({
     ...node,
@@ -248,6 +262,39 @@ function quickSelectInTree(nodes: FileTreeNode[], filter: QuickFilter): FileTree
   });
 }
 
+function indexTreeState(
+  nodes: FileTreeNode[],
+  index: Map<string, Pick<FileTreeNode, "checkState" | "isExpanded">> = new Map(),
+) {
+  for (const node of nodes) {
+    index.set(node.id, { checkState: node.checkState, isExpanded: node.isExpanded });
+    if (node.children) {
+      indexTreeState(node.children, index);
+    }
+  }
+  return index;
+}
+
+/** Carry check/expand state over to a re-walked tree; node ids are stable per relative path. */
+function restoreTreeState(
+  nodes: FileTreeNode[],
+  previous: Map<string, Pick<FileTreeNode, "checkState" | "isExpanded">>,
+): FileTreeNode[] {
+  return nodes.map((node) => {
+    const prior = previous.get(node.id);
+    const isExpanded = prior?.isExpanded ?? node.isExpanded;
+    if (node.children) {
+      const children = restoreTreeState(node.children, previous);
+      const checkState =
+        children.length > 0
+          ? computeParentState(children)
+          : (prior?.checkState ?? node.checkState);
+      return { ...node, checkState, children, isExpanded };
+    }
+    return { ...node, checkState: prior?.checkState ?? node.checkState, isExpanded };
+  });
+}
+
 function filterNodesByQuery(nodes: FileTreeNode[], query: string): FileTreeNode[] {
   const result: FileTreeNode[] = [];
   for (const node of nodes) {
@@ -271,8 +318,11 @@ export function useFileTree() {
   const [searchQuery, setSearchQuery] = useState("");
   const [highlightedPath, setHighlightedPath] = useState<string | null>(null);
 
-  const loadTree = (nodes: FileNode[]) => {
-    setRootNodes(buildTreeNodes(nodes, 0));
+  const loadTree = (nodes: FileNode[], options: { preserveState?: boolean } = {}) => {
+    setRootNodes((prev) => {
+      const next = buildTreeNodes(nodes, 0);
+      return options.preserveState ? restoreTreeState(next, indexTreeState(prev)) : next;
+    });
   };
 
   const toggleCheck = (nodeId: string) => {
@@ -318,6 +368,7 @@ export function useFileTree() {
     : rootNodes;
   const flatItems = flattenTree(filteredNodes);
   const selectedFiles = getSelectedFiles(rootNodes);
+  const excludedPaths = getExcludedPaths(rootNodes);
 
   const visibleFilePaths = new Set<string>();
   for (const item of flatItems) {
@@ -327,6 +378,7 @@ export function useFileTree() {
   }
 
   return {
+    excludedPaths,
     flatItems,
     highlightedPath,
     loadTree,
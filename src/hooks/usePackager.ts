@@ -1,12 +1,23 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useState } from "react";
-import { applyAstDeadCode } from "@/lib/ast-reachability";
+import { AST_SUPPORTED_EXTENSIONS, applyAstDeadCode } from "@/lib/ast-reachability";
+import { invokeCompressed } from "@/lib/compressed-ipc";
+import { resolveAdvisoryMaxTokensPerFile } from "@/lib/pack-strategy";
+import { parsePriorityRules } from "@/lib/priority-rules";
 import {
-  resolveAdvisoryMaxTokensPerFile,
-  splitOversizedFilesForPacking,
-} from "@/lib/pack-strategy";
-import { minifyMarkdown, reduceWhitespace, stripComments } from "@/lib/utils";
-import type { FileTreeNode, PackOptions, PackResponse } from "@/types";
+  minifyMarkdown,
+  stripComments,
+  unescapeControlSequences,
+} from "@/lib/utils";
+import type {
+  DeltaResponse,
+  FileTreeNode,
+  PackOptions,
+  PackRequest,
+  PackResponse,
+  ProjectMetadata,
+  RedactionRule,
+} from "@/types";
 
 export function usePackager(
   selectedFiles: FileTreeNode[],
@@ -15,13 +26,19 @@ export function usePackager(
   contextWindowTokens: number,
   tokenMap?: Map<string, number>,
   onLog?: (level: "error" | "info" | "debug", message: string) => void,
+  projectName?: string,
+  projectPath?: string | null,
+  excludedPaths?: string[],
+  redactions?: RedactionRule[],
+  strategy?: PackRequest["strategy"],
 ) {
   const [packResult, setPackResult] = useState<PackResponse | null>(null);
   const [isPacking, setIsPacking] = useState(false);
   const [packError, setPackError] = useState<string | null>(null);
   const [packWarnings, setPackWarnings] = useState<string[]>([]);
 
-  const pack = async (options: PackOptions) => {
+  /** Packs the selection, or only what changed since `previousFingerprint` when given */
+  const pack = async (options: PackOptions, previousFingerprint?: string) => {
     if (selectedFiles.length === 0) {
       return;
     }
@@ -64,21 +81,30 @@ export function usePackager(
         );
       }
 
+      // Focus files reach the backend verbatim, as it packs them.
+      const focusPaths = options.focusFiles
+        ? options.focusPaths
+            .split("\n")
+            .map((line) => line.trim())
+            .filter(Boolean)
+        : [];
       const contentMap = new Map<string, string>();
       for (const file of selectedFiles) {
         if (file.isDir) {
           continue;
         }
+        if (focusPaths.includes(file.relativePath)) {
+          contentMap.set(file.path, rawContentMap.get(file.path) ?? "");
+          continue;
+        }
         const baseContent = astProcessedContentMap.get(file.path) ?? "";
         let content = baseContent;
         const ext = file.extension.toLowerCase();
 
-        if (options.stripComments) {
+        // The backend strips comments with a parser for the languages it has grammars for.
+        if (options.stripComments && !AST_SUPPORTED_EXTENSIONS.has(ext)) {
           content = stripComments(content, ext);
         }
-        if (options.reduceWhitespace) {
-          content = reduceWhitespace(content, ext, file.relativePath);
-        }
         if (options.minifyMarkdown && (ext === "md" || ext === "mdx")) {
           content = minifyMarkdown(
             content,
@@ -101,26 +127,111 @@ export function usePackager(
         options.maxTokensPerPackFile,
         contextWindowTokens,
       );
-      const balanced = splitOversizedFilesForPacking(files, advisoryMaxTokensPerFile);
-      setPackWarnings(balanced.warnings);
-      if (balanced.warnings.length > 0) {
-        onLog?.("info", `pack warnings count=${balanced.warnings.length}`);
+
+      let provenance: ProjectMetadata | undefined;
+      if (options.includeProvenance && projectPath) {
+        try {
+          provenance = await invoke<ProjectMetadata>("get_project_metadata", { root: projectPath });
+        } catch (err) {
+          onLog?.("debug", `pack provenance unavailable err=${String(err)}`);
+        }
       }
-      onLog?.(
-        "debug",
-        `pack balancing filesIn=${files.length} filesOut=${balanced.files.length} splitFiles=${balanced.splitFileCount} generatedParts=${balanced.generatedPartCount}`,
-      );
 
-      const result = await invoke<PackResponse>("pack_files", {
-        request: {
-          files: balanced.files,
-          llmProfileId,
-          numPacks: options.numPacks,
-          outputFormat: options.outputFormat,
-        },
-      });
+      const request: PackRequest = {
+        boundaries: options.customBoundaries
+          ? {
+              fileEnd: unescapeControlSequences(options.fileEndMarker) || undefined,
+              fileStart: unescapeControlSequences(options.fileStartMarker) || undefined,
+              separator: unescapeControlSequences(options.sectionSeparator) || undefined,
+            }
+          : undefined,
+        compressWhitespace: options.reduceWhitespace ? { stripIndentation: true } : undefined,
+        configSurface: options.configSurface,
+        errorIndex: options.errorIndex,
+        excludedPaths: options.listExcludedFiles ? excludedPaths : undefined,
+        fileIds: options.fileIds,
+        files,
+        focusPaths: focusPaths.length > 0 ? focusPaths : undefined,
+        includeLineNumbers: options.includeLineNumbers,
+        includeSchemas: options.includeSchemas,
+        llmProfileId,
+        maxTokensPerFile: advisoryMaxTokensPerFile,
+        maxTokensPerPack: contextWindowTokens,
+        normalizeContent: options.normalizeContent,
+        numPacks: options.numPacks,
+        outlineLowPriority: options.prioritizeFiles && options.outlineLowPriority,
+        outputFormat: options.outputFormat,
+        packNotes: options.packNotes,
+        previousManifest: packResult?.manifest,
+        priorities: options.prioritizeFiles ? parsePriorityRules(options.priorityRules) : undefined,
+        profileData: options.profileData,
+        profileId: options.packProfileId ?? undefined,
+        promptTemplate: options.promptTemplate.trim() || undefined,
+        provenance,
+        redactions,
+        schemaInference: options.schemaInference
+          ? {
+              globs: options.schemaGlobs
+                .split("\n")
+                .map((p) => p.trim())
+                .filter(Boolean),
+              minBytes: Math.max(0, options.schemaMinKb) * 1024,
+            }
+          : undefined,
+        strategy,
+        stripComments: options.stripComments,
+        stripImports: options.stripImports,
+        suggestPrompts: options.suggestPrompts,
+        summarizeLocales: options.summarizeLocales,
+        template: (options.customTemplate && options.packTemplate.trim() && options.packTemplate) || undefined,
+        todoIndex: options.todoIndex,
+        typeClosure: options.typeClosure,
+        wrapper: options.wrapPacks
+          ? {
+              closing: options.wrapperClosing.trim() || undefined,
+              epilogue: options.wrapperEpilogue.trim() || undefined,
+              includeIndex: options.wrapperIncludeIndex,
+              includeTimestamp: options.wrapperIncludeTimestamp,
+              opening: options.wrapperOpening.trim() || undefined,
+              preamble: options.wrapperPreamble.trim() || undefined,
+              projectName: (options.wrapperIncludeProject && projectName) || undefined,
+            }
+          : undefined,
+      };
+      let result: PackResponse;
+      if (previousFingerprint) {
+        const delta = await invoke<DeltaResponse>("generate_delta", {
+          previousFingerprint,
+          request,
+        });
+        onLog?.(
+          "info",
+          `pack delta changed=${delta.changedPaths.length} removed=${delta.removedPaths.length}`,
+        );
+        result = {
+          contentHash: delta.pack.contentHash,
+          fingerprint: delta.fingerprint,
+          manifest: {},
+          packs: [delta.pack],
+          totalTokens: delta.pack.estimatedTokens,
+          warnings: delta.warnings,
+        };
+      } else {
+        result = await invokeCompressed<PackResponse>("pack_files", { request });
+      }
 
+      const unchanged = result.packs.filter((p) => !p.changed).length;
+      if (unchanged > 0) {
+        onLog?.("info", `pack incremental unchanged=${unchanged}/${result.packs.length}`);
+      }
+      if (packResult && packResult.contentHash === result.contentHash) {
+        onLog?.("info", `pack unchanged contentHash=${result.contentHash.slice(0, 16)}`);
+      }
       setPackResult(result);
+      if (result.warnings.length > 0) {
+        setPackWarnings(result.warnings);
+        onLog?.("info", `pack warnings count=${result.warnings.length}`);
+      }
       onLog?.(
         "info",
         `pack success packs=${result.packs.length} totalTokens=${result.totalTokens}`,
@@ -140,5 +251,49 @@ export function usePackager(
     setPackWarnings([]);
   };
 
-  return { clearResult, isPacking, pack, packError, packResult, packWarnings };
+  const packChanges = (options: PackOptions) => pack(options, packResult?.fingerprint);
+
+  /** Packs every project file containing `query`, or only the matching regions when `contextLines` is set */
+  const packMatches = async (options: PackOptions, query: string, contextLines: number | null) => {
+    if (!projectPath || !query) {
+      return;
+    }
+
+    setIsPacking(true);
+    setPackError(null);
+    setPackWarnings([]);
+    onLog?.("info", `pack-matches start query=${query} context=${contextLines ?? "file"}`);
+    try {
+      const result = await invoke<PackResponse>("pack_matches", {
+        contextLines: contextLines ?? undefined,
+        outputFormat: options.outputFormat,
+        query,
+        redactions,
+        root: projectPath,
+      });
+      setPackResult(result);
+      setPackWarnings(result.warnings);
+      onLog?.(
+        "info",
+        `pack-matches success files=${result.packs[0]?.fileCount ?? 0} totalTokens=${result.totalTokens}`,
+      );
+    } catch (err) {
+      const message = err instanceof Error ? err.message : String(err);
+      setPackError(message);
+      onLog?.("error", `pack-matches failed err=${message}`);
+    } finally {
+      setIsPacking(false);
+    }
+  };
+
+  return {
+    clearResult,
+    isPacking,
+    pack,
+    packChanges,
+    packError,
+    packMatches,
+    packResult,
+    packWarnings,
+  };
 }
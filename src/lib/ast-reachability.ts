@@ -156,6 +156,14 @@ export async function applyAstDeadCode(
       files: astFiles,
     });
 
+    const unhealthy = Object.keys(reachability.parse_health ?? {});
+    if (unhealthy.length > 0) {
+      console.warn(
+        `AST reachability: ${unhealthy.length} file(s) did not parse cleanly:`,
+        unhealthy,
+      );
+    }
+
     const next = new Map(contentMap);
     for (const file of selectedFiles) {
       if (file.isDir) {
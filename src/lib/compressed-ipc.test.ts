@@ -0,0 +1,20 @@
+import { describe, expect, it } from "bun:test";
+import { gzipSync } from "node:zlib";
+import { decodeGzipJson } from "./compressed-ipc";
+
+describe("decodeGzipJson", () => {
+  it("should decode gzipped JSON from an ArrayBuffer", async () => {
+    const value = { packs: [{ content: "a".repeat(1000), index: 0 }], totalTokens: 250 };
+    const compressed = gzipSync(JSON.stringify(value));
+    const buffer = compressed.buffer.slice(
+      compressed.byteOffset,
+      compressed.byteOffset + compressed.byteLength,
+    );
+    expect(await decodeGzipJson<typeof value>(buffer)).toEqual(value);
+  });
+
+  it("should accept a Uint8Array payload", async () => {
+    const compressed = new Uint8Array(gzipSync(JSON.stringify(["src/main.rs"])));
+    expect(await decodeGzipJson<string[]>(compressed)).toEqual(["src/main.rs"]);
+  });
+});
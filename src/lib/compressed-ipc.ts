@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Gunzip a raw IPC payload and parse the JSON inside it. */
+export async function decodeGzipJson<T>(payload: ArrayBuffer | Uint8Array): Promise<T> {
+  const bytes = payload instanceof Uint8Array ? payload : new Uint8Array(payload);
+  const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream("gzip"));
+  const text = await new Response(stream).text();
+  return JSON.parse(text) as T;
+}
+
+/**
+ * Invoke the gzip-compressed variant of a command (`<command>_compressed`), falling back
+ * to the plain command when the webview cannot decompress streams.
+ */
+export async function invokeCompressed<T>(
+  command: string,
+  args: Record<string, unknown>,
+): Promise<T> {
+  if (typeof DecompressionStream === "undefined") {
+    return invoke<T>(command, args);
+  }
+  const payload = await invoke<ArrayBuffer>(`${command}_compressed`, args);
+  return decodeGzipJson<T>(payload);
+}
@@ -0,0 +1,44 @@
+import { describe, expect, it } from "bun:test";
+import type { FlatFileNode } from "../types";
+import { buildTreeFromFlat } from "./flat-tree";
+
+function flatNode(
+  id: string,
+  name: string,
+  isDir: boolean,
+  parentIndex: number | null,
+): FlatFileNode {
+  return {
+    extension: isDir ? "" : (name.split(".").pop() ?? ""),
+    id,
+    isDir,
+    name,
+    parentIndex,
+    path: `/project/${name}`,
+    relativePath: name,
+    size: 0,
+  };
+}
+
+describe("buildTreeFromFlat", () => {
+  it("should nest children under their parent directories", () => {
+    const tree = buildTreeFromFlat([
+      flatNode("0", "src", true, null),
+      flatNode("1", "nested", true, 0),
+      flatNode("2", "a.ts", false, 1),
+      flatNode("3", "b.ts", false, 0),
+      flatNode("4", "README.md", false, null),
+    ]);
+
+    expect(tree.map((n) => n.name)).toEqual(["src", "README.md"]);
+    expect(tree[0].children?.map((n) => n.name)).toEqual(["nested", "b.ts"]);
+    expect(tree[0].children?.[0].children?.map((n) => n.name)).toEqual(["a.ts"]);
+    expect(tree[1].children).toBeUndefined();
+    expect("parentIndex" in tree[0]).toBe(false);
+  });
+
+  it("should give empty directories an empty children array", () => {
+    const tree = buildTreeFromFlat([flatNode("0", "empty", true, null)]);
+    expect(tree[0].children).toEqual([]);
+  });
+});
@@ -0,0 +1,24 @@
+import type { FileNode, FlatFileNode } from "@/types";
+
+/**
+ * Rebuild the nested tree from `walk_directory_flat` output.
+ * Parents always precede their children, so a single pass is enough.
+ */
+export function buildTreeFromFlat(flat: FlatFileNode[]): FileNode[] {
+  const nodes: FileNode[] = [];
+  const roots: FileNode[] = [];
+
+  for (const { parentIndex, ...entry } of flat) {
+    const node: FileNode = entry.isDir ? { ...entry, children: [] } : { ...entry };
+    nodes.push(node);
+
+    const parent = parentIndex === null ? undefined : nodes[parentIndex];
+    if (parent?.children) {
+      parent.children.push(node);
+    } else {
+      roots.push(node);
+    }
+  }
+
+  return roots;
+}
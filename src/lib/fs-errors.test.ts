@@ -0,0 +1,17 @@
+import { describe, expect, it } from "bun:test";
+import { describeError, unavailableRoot } from "./fs-errors";
+
+describe("fs errors", () => {
+  it("should recognize a vanished root", () => {
+    const err = { kind: "rootUnavailable", root: "/Volumes/usb" };
+    expect(unavailableRoot(err)).toBe("/Volumes/usb");
+    expect(describeError(err)).toContain("/Volumes/usb");
+  });
+
+  it("should pass other errors through as text", () => {
+    expect(unavailableRoot({ kind: "other", message: "denied" })).toBeNull();
+    expect(describeError({ kind: "other", message: "denied" })).toBe("denied");
+    expect(describeError("plain failure")).toBe("plain failure");
+    expect(unavailableRoot("plain failure")).toBeNull();
+  });
+});
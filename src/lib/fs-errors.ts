@@ -0,0 +1,21 @@
+import type { FsError } from "@/types";
+
+/** Whether `err` is the typed error file commands reject with (rather than a plain string) */
+export function isFsError(err: unknown): err is FsError {
+  return typeof err === "object" && err !== null && "kind" in err;
+}
+
+/** The root whose drive went away, when `err` says so */
+export function unavailableRoot(err: unknown): string | null {
+  return isFsError(err) && err.kind === "rootUnavailable" ? err.root : null;
+}
+
+/** One-line text for a command error, typed or not */
+export function describeError(err: unknown): string {
+  if (!isFsError(err)) {
+    return String(err);
+  }
+  return err.kind === "rootUnavailable"
+    ? `Project root is unavailable (was its drive disconnected?): ${err.root}`
+    : err.message;
+}
@@ -0,0 +1,18 @@
+import { describe, expect, it } from "bun:test";
+import { formatLanguageAssociations, parseLanguageAssociations } from "./language-associations";
+
+describe("parseLanguageAssociations", () => {
+  it("should read one association per line", () => {
+    const text = "# unusual extensions\n.mjsx => javascript\n\nJustfile => make\nincomplete =>\n";
+    expect(parseLanguageAssociations(text)).toEqual({ ".mjsx": "javascript", Justfile: "make" });
+  });
+});
+
+describe("formatLanguageAssociations", () => {
+  it("should write sorted lines that parse back to the same associations", () => {
+    const saved = { justfile: "make", gqls: "graphql" };
+    const text = formatLanguageAssociations(saved);
+    expect(text).toBe("gqls => graphql\njustfile => make");
+    expect(parseLanguageAssociations(text)).toEqual(saved);
+  });
+});
@@ -0,0 +1,27 @@
+/**
+ * Associations from the settings text: `extension-or-file-name => language` per line, e.g.
+ * `.mjsx => javascript` or `Justfile => make`. Blank lines, `#` comments, and lines
+ * without both sides are skipped.
+ */
+export function parseLanguageAssociations(text: string): Record<string, string> {
+  const associations: Record<string, string> = {};
+  for (const rawLine of text.split("\n")) {
+    const line = rawLine.trim();
+    if (!line || line.startsWith("#")) {
+      continue;
+    }
+    const [key, language] = line.split("=>").map((part) => part.trim());
+    if (key && language) {
+      associations[key] = language;
+    }
+  }
+  return associations;
+}
+
+/** Settings text for the saved associations, whose keys the backend stores lowercased and without a dot */
+export function formatLanguageAssociations(associations: Record<string, string>): string {
+  return Object.entries(associations)
+    .sort(([a], [b]) => a.localeCompare(b))
+    .map(([key, language]) => `${key} => ${language}`)
+    .join("\n");
+}
@@ -1,8 +1,12 @@
+import type { OutputFormat } from "@/types";
+
 export interface LLMProfile {
   id: string;
   name: string;
   contextWindowTokens: number;
   maxFileAttachments: number;
+  /** Format the backend packs with for this model unless the user picked another */
+  preferredFormat: OutputFormat;
   tokensPerFile?: number;
   tokenizer: "o200k" | "approx";
 }
@@ -13,6 +17,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "chatgpt-5-2",
     maxFileAttachments: 20,
     name: "ChatGPT 5.2",
+    preferredFormat: "markdown",
     tokenizer: "o200k",
   },
   {
@@ -20,6 +25,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "chatgpt-5-2-extended-thinking",
     maxFileAttachments: 20,
     name: "ChatGPT 5.2 Extended Thinking",
+    preferredFormat: "markdown",
     tokenizer: "o200k",
   },
   {
@@ -27,6 +33,15 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "chatgpt-5o-thinking-mini",
     maxFileAttachments: 20,
     name: "ChatGPT 5o Thinking Mini",
+    preferredFormat: "markdown",
+    tokenizer: "o200k",
+  },
+  {
+    contextWindowTokens: 128_000,
+    id: "gpt-4o",
+    maxFileAttachments: 20,
+    name: "GPT-4o",
+    preferredFormat: "markdown",
     tokenizer: "o200k",
   },
   {
@@ -34,6 +49,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "claude-sonnet-4-6-thinking",
     maxFileAttachments: 20,
     name: "Claude Sonnet 4.6 Thinking",
+    preferredFormat: "xml",
     tokenizer: "approx",
   },
   {
@@ -41,6 +57,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "gemini-3-1-pro",
     maxFileAttachments: 20,
     name: "Gemini 3.1 Pro",
+    preferredFormat: "markdown",
     tokenizer: "approx",
   },
   {
@@ -48,6 +65,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "glm-5",
     maxFileAttachments: 20,
     name: "GLM-5",
+    preferredFormat: "markdown",
     tokenizer: "approx",
   },
   {
@@ -55,6 +73,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "grok-4-20-beta",
     maxFileAttachments: 20,
     name: "Grok 4.20 Beta",
+    preferredFormat: "markdown",
     tokenizer: "approx",
   },
   {
@@ -62,6 +81,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "grok-4-expert",
     maxFileAttachments: 20,
     name: "Grok 4 Expert",
+    preferredFormat: "markdown",
     tokenizer: "approx",
   },
   {
@@ -69,6 +89,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "kimi-k2-5",
     maxFileAttachments: 20,
     name: "Kimi K2.5",
+    preferredFormat: "markdown",
     tokenizer: "approx",
   },
   {
@@ -76,6 +97,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "minimax-m2-5",
     maxFileAttachments: 20,
     name: "MiniMax M2.5",
+    preferredFormat: "markdown",
     tokenizer: "approx",
   },
   {
@@ -83,6 +105,7 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "nova-2-pro",
     maxFileAttachments: 20,
     name: "Nova 2 Pro",
+    preferredFormat: "markdown",
     tokenizer: "approx",
   },
   {
@@ -90,6 +113,23 @@ export const LLM_PROFILES: LLMProfile[] = [
     id: "qwen-3-5-plus",
     maxFileAttachments: 20,
     name: "Qwen 3.5 Plus",
+    preferredFormat: "markdown",
+    tokenizer: "approx",
+  },
+  {
+    contextWindowTokens: 8_192,
+    id: "local-llama-3-1-8b",
+    maxFileAttachments: 20,
+    name: "Llama 3.1 8B (local)",
+    preferredFormat: "plaintext",
+    tokenizer: "approx",
+  },
+  {
+    contextWindowTokens: 32_768,
+    id: "local-qwen-2-5-coder-32b",
+    maxFileAttachments: 20,
+    name: "Qwen 2.5 Coder 32B (local)",
+    preferredFormat: "plaintext",
     tokenizer: "approx",
   },
 ];
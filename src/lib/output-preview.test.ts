@@ -1,10 +1,13 @@
 import { describe, expect, it } from "bun:test";
-import { buildPackFileTokenMap } from "./output-preview";
+import { buildPackFileTokenMap, parseRequestedPaths } from "./output-preview";
 
 describe("buildPackFileTokenMap", () => {
   it("should use exact provided token values when keys match output file paths", () => {
     const packs = [
       {
+        id: "pack-0",
+        contentHash: "",
+        changed: true,
         index: 0,
         content: "",
         estimatedTokens: 5300,
@@ -34,6 +37,9 @@ describe("buildPackFileTokenMap", () => {
   it("should distribute remaining tokens only for unknown files", () => {
     const packs = [
       {
+        id: "pack-0",
+        contentHash: "",
+        changed: true,
         index: 0,
         content: "",
         estimatedTokens: 100,
@@ -56,6 +62,9 @@ describe("buildPackFileTokenMap", () => {
   it("should assign equal fallback when no per-file token map is provided", () => {
     const packs = [
       {
+        id: "pack-0",
+        contentHash: "",
+        changed: true,
         index: 0,
         content: "",
         estimatedTokens: 90,
@@ -73,6 +82,9 @@ describe("buildPackFileTokenMap", () => {
   it("should clamp unknown distribution to zero when known total exceeds pack estimate", () => {
     const packs = [
       {
+        id: "pack-0",
+        contentHash: "",
+        changed: true,
         index: 0,
         content: "",
         estimatedTokens: 10,
@@ -87,3 +99,14 @@ describe("buildPackFileTokenMap", () => {
     expect(out.get("b.ts")).toBe(0);
   });
 });
+
+describe("parseRequestedPaths", () => {
+  it("should accept lines, commas, bullets, and backticks", () => {
+    const text = "- `src/a.ts`\n2. src/b.ts, 'src/c.ts'\n\n* src/a.ts";
+    expect(parseRequestedPaths(text)).toEqual(["src/a.ts", "src/b.ts", "src/c.ts"]);
+  });
+
+  it("should return nothing for blank input", () => {
+    expect(parseRequestedPaths("  \n , ")).toEqual([]);
+  });
+});
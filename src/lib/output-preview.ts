@@ -32,3 +32,21 @@ export function buildPackFileTokenMap(
 
   return fileTokenMap;
 }
+
+/**
+ * Paths from a free-form list the model asked for: one per line or comma-separated,
+ * with surrounding backticks, quotes, and list bullets removed. Duplicates are dropped.
+ */
+export function parseRequestedPaths(text: string): string[] {
+  const paths = text
+    .split(/[\n,]/)
+    .map((entry) =>
+      entry
+        .trim()
+        .replace(/^(?:[-*•]|\d+\.)\s+/, "")
+        .replace(/^[`'"]+|[`'"]+$/g, "")
+        .trim(),
+    )
+    .filter(Boolean);
+  return [...new Set(paths)];
+}
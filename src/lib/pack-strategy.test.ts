@@ -1,5 +1,6 @@
 import { describe, expect, it } from "bun:test";
 import {
+  MAX_CONVERSATION_RESERVE,
   buildOversizedFilesWarning,
   deriveAdvisoryMaxTokensPerFile,
   estimateTokens,
@@ -9,8 +10,7 @@ import {
   getEffectiveTokenCount,
   type PackStrategyFile,
   resolveAdvisoryMaxTokensPerFile,
-  splitContentByTokenBudget,
-  splitOversizedFilesForPacking,
+  resolvePackingWindow,
 } from "./pack-strategy";
 
 describe("estimateTokens", () => {
@@ -82,39 +82,6 @@ describe("findOversizedFiles", () => {
   });
 });
 
-describe("splitContentByTokenBudget", () => {
-  it("should return original content when budget is non-positive", () => {
-    expect(splitContentByTokenBudget("hello", 0)).toEqual(["hello"]);
-  });
-
-  it("should return original content when no split is needed", () => {
-    expect(splitContentByTokenBudget("hello", 10)).toEqual(["hello"]);
-  });
-
-  it("should split at paragraph boundaries when possible", () => {
-    const content = `${"a".repeat(60)}\n\n${"b".repeat(60)}`;
-    const chunks = splitContentByTokenBudget(content, 20); // ~80 chars
-    expect(chunks.length).toBeGreaterThan(1);
-    expect(chunks.join("")).toBe(content);
-    expect(chunks[0].endsWith("\n\n")).toBe(true);
-  });
-
-  it("should split at line boundaries when paragraph boundaries are unavailable", () => {
-    const content = `${"a".repeat(50)}\n${"b".repeat(50)}\n${"c".repeat(50)}`;
-    const chunks = splitContentByTokenBudget(content, 20); // ~80 chars
-    expect(chunks.length).toBeGreaterThan(1);
-    expect(chunks.join("")).toBe(content);
-    expect(chunks.some((c) => c.endsWith("\n"))).toBe(true);
-  });
-
-  it("should hard-split long single-line content when no newline exists", () => {
-    const content = "x".repeat(150);
-    const chunks = splitContentByTokenBudget(content, 20); // ~80 chars
-    expect(chunks.length).toBe(2);
-    expect(chunks.join("")).toBe(content);
-  });
-});
-
 describe("buildOversizedFilesWarning", () => {
   it("should return null when there are no oversized files", () => {
     expect(buildOversizedFilesWarning([], 10_000)).toBeNull();
@@ -136,42 +103,6 @@ describe("buildOversizedFilesWarning", () => {
   });
 });
 
-describe("splitOversizedFilesForPacking", () => {
-  it("should return unchanged files and no warnings when nothing is oversized", () => {
-    const files: PackStrategyFile[] = [{ path: "ok.ts", content: "x".repeat(100), tokenCount: 20 }];
-    const result = splitOversizedFilesForPacking(files, 200);
-    expect(result.files).toEqual(files);
-    expect(result.oversizedFiles).toEqual([]);
-    expect(result.warnings).toEqual([]);
-    expect(result.splitFileCount).toBe(0);
-    expect(result.generatedPartCount).toBe(0);
-  });
-
-  it("should split oversized files into part paths and emit warnings", () => {
-    const files: PackStrategyFile[] = [
-      { path: "big.ts", content: "x".repeat(500), tokenCount: 200 },
-      { path: "small.ts", content: "ok", tokenCount: 1 },
-    ];
-    const result = splitOversizedFilesForPacking(files, 60);
-    expect(result.oversizedFiles).toEqual([{ path: "big.ts", tokenCount: 200 }]);
-    expect(result.splitFileCount).toBe(1);
-    expect(result.generatedPartCount).toBeGreaterThan(1);
-    expect(result.files.some((f) => /^big\.part-\d+-of-\d+\.ts$/.test(f.path))).toBe(true);
-    expect(result.files.some((f) => f.path === "small.ts")).toBe(true);
-    expect(result.warnings.length).toBe(2);
-  });
-
-  it("should keep file unchanged when tokenCount is oversized but content cannot split", () => {
-    const files: PackStrategyFile[] = [{ path: "weird.ts", content: "tiny", tokenCount: 999 }];
-    const result = splitOversizedFilesForPacking(files, 100);
-    expect(result.oversizedFiles).toEqual([{ path: "weird.ts", tokenCount: 999 }]);
-    expect(result.files).toEqual(files);
-    expect(result.splitFileCount).toBe(0);
-    expect(result.generatedPartCount).toBe(0);
-    expect(result.warnings.length).toBe(1);
-  });
-});
-
 describe("forecastSplitPartCounts", () => {
   it("should return empty map when max is non-positive", () => {
     const counts = forecastSplitPartCounts([{ path: "a.ts", content: "x".repeat(100) }], 0);
@@ -219,3 +150,21 @@ describe("evaluatePerPackAdvisory", () => {
     expect(status.avgTokensPerPack).toBe(10);
   });
 });
+
+describe("resolvePackingWindow", () => {
+  it("should hold back the reserved fraction of the window", () => {
+    expect(resolvePackingWindow(200_000, 0)).toBe(200_000);
+    expect(resolvePackingWindow(200_000, 0.4)).toBe(120_000);
+  });
+
+  it("should pack to the full window without a reserve and keep a tenth at the maximum", () => {
+    expect(resolvePackingWindow(128_000, 0)).toBe(128_000);
+    expect(resolvePackingWindow(128_000, MAX_CONVERSATION_RESERVE)).toBe(12_800);
+  });
+
+  it("should clamp out-of-range reserves", () => {
+    expect(resolvePackingWindow(100_000, -1)).toBe(100_000);
+    expect(resolvePackingWindow(100_000, 1)).toBe(10_000);
+    expect(resolvePackingWindow(100_000, Number.NaN)).toBe(100_000);
+  });
+});
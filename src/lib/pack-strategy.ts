@@ -9,15 +9,6 @@ export interface OversizedFileInfo {
   tokenCount: number;
 }
 
-export interface PackStrategyResult {
-  files: PackStrategyFile[];
-  oversizedFiles: OversizedFileInfo[];
-  warnings: string[];
-  advisoryMaxTokensPerFile: number;
-  splitFileCount: number;
-  generatedPartCount: number;
-}
-
 export interface PerPackAdvisoryStatus {
   avgTokensPerPack: number;
   advisoryMaxTokensPerFile: number;
@@ -30,7 +21,6 @@ const MIN_ADVISORY_TOKENS_PER_FILE = 4_000;
 const MAX_ADVISORY_TOKENS_PER_FILE = 20_000;
 const ADVISORY_WINDOW_RATIO = 0.08;
 const APPROX_CHARS_PER_TOKEN = 4;
-const MIN_BREAK_SCAN_RATIO = 0.35;
 const ADVISORY_WARN_RATIO = 0.85;
 
 function formatCompactTokenCount(tokens: number): string {
@@ -50,6 +40,18 @@ export function estimateTokens(content: string): number {
   return Math.max(Math.ceil(content.length / APPROX_CHARS_PER_TOKEN), 1);
 }
 
+/** The largest share of the context window the conversation reserve can hold back */
+export const MAX_CONVERSATION_RESERVE = 0.9;
+
+/** Tokens available to packs once `conversationReserve` (a 0–0.9 fraction) is held back */
+export function resolvePackingWindow(
+  contextWindowTokens: number,
+  conversationReserve: number,
+): number {
+  const reserve = Math.min(MAX_CONVERSATION_RESERVE, Math.max(0, conversationReserve || 0));
+  return Math.max(1, contextWindowTokens - Math.round(contextWindowTokens * reserve));
+}
+
 export function deriveAdvisoryMaxTokensPerFile(contextWindowTokens: number): number {
   const scaled = Math.round(contextWindowTokens * ADVISORY_WINDOW_RATIO);
   return Math.min(MAX_ADVISORY_TOKENS_PER_FILE, Math.max(MIN_ADVISORY_TOKENS_PER_FILE, scaled));
@@ -95,53 +97,6 @@ export function findOversizedFiles(
     .filter((entry) => entry.tokenCount > maxTokensPerFile);
 }
 
-export function splitContentByTokenBudget(content: string, maxTokensPerFile: number): string[] {
-  if (maxTokensPerFile <= 0) {
-    return [content];
-  }
-  const maxCharsPerChunk = Math.max(1, maxTokensPerFile * APPROX_CHARS_PER_TOKEN);
-  if (content.length <= maxCharsPerChunk) {
-    return [content];
-  }
-
-  const minBreakOffset = Math.floor(maxCharsPerChunk * MIN_BREAK_SCAN_RATIO);
-  const chunks: string[] = [];
-  let cursor = 0;
-
-  while (cursor < content.length) {
-    let splitAt = Math.min(cursor + maxCharsPerChunk, content.length);
-
-    if (splitAt < content.length) {
-      const lastParagraphBreak = content.lastIndexOf("\n\n", splitAt);
-      const lastLineBreak = content.lastIndexOf("\n", splitAt);
-
-      if (lastParagraphBreak >= cursor + minBreakOffset) {
-        splitAt = lastParagraphBreak + 2;
-      } else if (lastLineBreak >= cursor + minBreakOffset) {
-        splitAt = lastLineBreak + 1;
-      }
-    }
-
-    chunks.push(content.slice(cursor, splitAt));
-    cursor = splitAt;
-  }
-
-  return chunks;
-}
-
-function toPartPath(path: string, partIndex: number, partCount: number): string {
-  const slashIndex = Math.max(path.lastIndexOf("/"), path.lastIndexOf("\\"));
-  const dir = slashIndex >= 0 ? path.slice(0, slashIndex + 1) : "";
-  const fileName = slashIndex >= 0 ? path.slice(slashIndex + 1) : path;
-
-  const dotIndex = fileName.lastIndexOf(".");
-  const suffix = `.part-${partIndex + 1}-of-${partCount}`;
-  if (dotIndex <= 0) {
-    return `${dir}${fileName}${suffix}`;
-  }
-  return `${dir}${fileName.slice(0, dotIndex)}${suffix}${fileName.slice(dotIndex)}`;
-}
-
 export function buildOversizedFilesWarning(
   oversizedFiles: OversizedFileInfo[],
   maxTokensPerFile: number,
@@ -162,74 +117,6 @@ export function buildOversizedFilesWarning(
   );
 }
 
-export function splitOversizedFilesForPacking(
-  files: PackStrategyFile[],
-  maxTokensPerFile: number,
-): PackStrategyResult {
-  const advisoryMaxTokensPerFile = Math.max(1, maxTokensPerFile);
-  const oversizedFiles = findOversizedFiles(files, advisoryMaxTokensPerFile);
-
-  if (oversizedFiles.length === 0) {
-    return {
-      advisoryMaxTokensPerFile,
-      files,
-      generatedPartCount: 0,
-      oversizedFiles,
-      splitFileCount: 0,
-      warnings: [],
-    };
-  }
-
-  const oversizedByPath = new Map(oversizedFiles.map((f) => [f.path, f.tokenCount]));
-  const transformedFiles: PackStrategyFile[] = [];
-  let splitFileCount = 0;
-  let generatedPartCount = 0;
-
-  for (const file of files) {
-    const tokenCount = oversizedByPath.get(file.path);
-    if (tokenCount === undefined) {
-      transformedFiles.push(file);
-      continue;
-    }
-
-    const chunks = splitContentByTokenBudget(file.content, advisoryMaxTokensPerFile);
-    if (chunks.length <= 1) {
-      transformedFiles.push(file);
-      continue;
-    }
-
-    splitFileCount += 1;
-    generatedPartCount += chunks.length;
-
-    for (let i = 0; i < chunks.length; i++) {
-      transformedFiles.push({
-        content: chunks[i],
-        path: toPartPath(file.path, i, chunks.length),
-      });
-    }
-  }
-
-  const warnings: string[] = [];
-  const oversizedWarning = buildOversizedFilesWarning(oversizedFiles, advisoryMaxTokensPerFile);
-  if (oversizedWarning) {
-    warnings.push(oversizedWarning);
-  }
-  if (splitFileCount > 0) {
-    warnings.push(
-      `Auto-balance enabled: split ${splitFileCount} oversized file(s) into ${generatedPartCount} part(s) to better balance pack sizes.`,
-    );
-  }
-
-  return {
-    advisoryMaxTokensPerFile,
-    files: transformedFiles,
-    generatedPartCount,
-    oversizedFiles,
-    splitFileCount,
-    warnings,
-  };
-}
-
 export function forecastSplitPartCounts(
   files: PackStrategyFile[],
   maxTokensPerFile: number,
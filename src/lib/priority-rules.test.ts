@@ -0,0 +1,12 @@
+import { describe, expect, it } from "bun:test";
+import { parsePriorityRules } from "./priority-rules";
+
+describe("parsePriorityRules", () => {
+  it("should read one rule per line in order", () => {
+    const text = "# core first\nsrc/core/** = High\n\ntests/** = low\nvendor/** = urgent\n= low\n";
+    expect(parsePriorityRules(text)).toEqual([
+      { pattern: "src/core/**", weight: "high" },
+      { pattern: "tests/**", weight: "low" },
+    ]);
+  });
+});
@@ -0,0 +1,24 @@
+import type { PriorityRule } from "@/types";
+
+const WEIGHTS = new Set<string>(["high", "normal", "low"]);
+
+/**
+ * Rules from the settings text: `glob = weight` per line, e.g. `src/core/** = high` or
+ * `tests/** = low`. Blank lines, `#` comments, and lines without a known weight are skipped.
+ */
+export function parsePriorityRules(text: string): PriorityRule[] {
+  const rules: PriorityRule[] = [];
+  for (const rawLine of text.split("\n")) {
+    const line = rawLine.trim();
+    if (!line || line.startsWith("#")) {
+      continue;
+    }
+    const separator = line.lastIndexOf("=");
+    const pattern = line.slice(0, separator).trim();
+    const weight = line.slice(separator + 1).trim().toLowerCase();
+    if (separator > 0 && pattern && WEIGHTS.has(weight)) {
+      rules.push({ pattern, weight: weight as PriorityRule["weight"] });
+    }
+  }
+  return rules;
+}
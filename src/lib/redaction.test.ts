@@ -0,0 +1,22 @@
+import { describe, expect, it } from "bun:test";
+import { parseRedactionRules } from "./redaction";
+
+describe("parseRedactionRules", () => {
+  it("should read one pattern per line with optional replacements", () => {
+    const text = "# internal hosts\n[\\w-]+\\.corp\\.example\\.com\n\ncustomer-(\\d+) => customer-$1-anon\n";
+    expect(parseRedactionRules(text)).toEqual([
+      { pattern: "[\\w-]+\\.corp\\.example\\.com" },
+      { pattern: "customer-(\\d+)", replacement: "customer-$1-anon" },
+    ]);
+  });
+
+  it("should allow an empty replacement", () => {
+    expect(parseRedactionRules("secret-token =>")).toEqual([
+      { pattern: "secret-token", replacement: "" },
+    ]);
+  });
+
+  it("should treat a line starting with the separator as a plain pattern", () => {
+    expect(parseRedactionRules(" => x")).toEqual([{ pattern: "=> x" }]);
+  });
+});
@@ -0,0 +1,21 @@
+import type { RedactionRule } from "@/types";
+
+/** `pattern => replacement`; the first `=>` preceded by whitespace separates the two */
+const RULE_WITH_REPLACEMENT = /^(.+?)\s+=>\s*(.*)$/;
+
+/**
+ * Rules from the project's redaction text: one regex per line, optionally followed by
+ * ` => replacement` (`$1` references groups). Blank lines and `#` comments are skipped.
+ */
+export function parseRedactionRules(text: string): RedactionRule[] {
+  const rules: RedactionRule[] = [];
+  for (const rawLine of text.split("\n")) {
+    const line = rawLine.trim();
+    if (!line || line.startsWith("#")) {
+      continue;
+    }
+    const match = RULE_WITH_REPLACEMENT.exec(line);
+    rules.push(match ? { pattern: match[1], replacement: match[2] } : { pattern: line });
+  }
+  return rules;
+}
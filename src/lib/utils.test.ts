@@ -8,6 +8,7 @@ import {
   minifyMarkdown,
   reduceWhitespace,
   stripComments,
+  unescapeControlSequences,
 } from "./utils";
 
 describe("cn", () => {
@@ -431,3 +432,15 @@ describe("minifyMarkdown", () => {
     expect(minifyMarkdown("", false, false)).toBe("");
   });
 });
+
+describe("unescapeControlSequences", () => {
+  it("should expand newline and tab escapes", () => {
+    expect(unescapeControlSequences("\\n\\n---\\n\\n")).toBe("\n\n---\n\n");
+    expect(unescapeControlSequences("a\\tb")).toBe("a\tb");
+  });
+
+  it("should keep escaped backslashes and plain text", () => {
+    expect(unescapeControlSequences("C:\\\\new")).toBe("C:\\new");
+    expect(unescapeControlSequences("<<<FILE_END>>>")).toBe("<<<FILE_END>>>");
+  });
+});
@@ -61,6 +61,15 @@ export function debounce<T extends (...args: unknown[]) => unknown>(
   };
 }
 
+/**
+ * Expand the `\n`, `\t`, and `\\` escapes typed into single-line inputs, so a separator such
+ * as `\n\n---\n\n` can be entered as text.
+ */
+export function unescapeControlSequences(text: string): string {
+  const escapes: Record<string, string> = { "\\": "\\", n: "\n", t: "\t" };
+  return text.replace(/\\([nt\\])/g, (_, ch: string) => escapes[ch]);
+}
+
 /**
  * Count the number of consecutive backslashes immediately before position `pos` in `line`.
  */
@@ -9,6 +9,26 @@ export interface FileNode {
   children?: FileNode[];
 }
 
+export interface FlatFileNode extends Omit<FileNode, "children"> {
+  parentIndex: number | null;
+}
+
+/** Set when the project root answered like a network mount; the walk skipped content sniffing */
+export interface DegradedMode {
+  latencyMs: number;
+  notice: string;
+}
+
+/** Rejection from file commands that touch a project root */
+export type FsError =
+  | { kind: "rootUnavailable"; root: string }
+  | { kind: "other"; message: string };
+
+export interface FlatWalkResponse {
+  nodes: FlatFileNode[];
+  degraded?: DegradedMode;
+}
+
 export type CheckState = "checked" | "unchecked" | "indeterminate";
 
 export interface FileTreeNode extends Omit<FileNode, "children"> {
@@ -25,18 +45,97 @@ export interface FlatTreeItem {
   hasChildren: boolean;
 }
 
+/** Per-file value `get_heatmap` colors the tree by */
+export type HeatmapMetric = "tokens" | "complexity" | "churn";
+
+export type OutputFormat = "plaintext" | "markdown" | "xml" | "html" | "json";
+
 export interface PackOptions {
   numPacks: number;
   maxTokensPerPackFile: number;
-  outputFormat: "plaintext" | "markdown";
+  /** Fraction of the context window kept free for the conversation (0 packs to the full window) */
+  conversationReserve: number;
+  outputFormat: OutputFormat;
   stripComments: boolean;
+  stripImports: boolean;
   reduceWhitespace: boolean;
+  normalizeContent: boolean;
+  /** Backend pack profile whose settings were applied; cleared on manual edits */
+  packProfileId: string | null;
+  suggestPrompts: boolean;
+  /** Template using {index}, {total}, {summary}, {files}, {tokens}, {instruction}; "" = default */
+  promptTemplate: string;
+  /** Frame each pack with the delimiters and metadata below */
+  wrapPacks: boolean;
+  /** Delimiters using {index}, {total}, {project}; "" = none */
+  wrapperOpening: string;
+  wrapperClosing: string;
+  wrapperIncludeIndex: boolean;
+  wrapperIncludeProject: boolean;
+  wrapperIncludeTimestamp: boolean;
+  /** Text at the top and bottom of every pack using {{pack_index}}, {{total_packs}}, {{file_count}}, {{project_name}}; "" = none */
+  wrapperPreamble: string;
+  wrapperEpilogue: string;
+  /** Join and mark file sections with the text below instead of a blank line */
+  customBoundaries: boolean;
+  /** Typed with `\n`/`\t` escapes; "" = blank line */
+  sectionSeparator: string;
+  /** Lines placed before and after each file, using {path}; "" = none */
+  fileStartMarker: string;
+  fileEndMarker: string;
+  /** Render each pack through a Handlebars-style (code2prompt-compatible) template instead */
+  customTemplate: boolean;
+  packTemplate: string;
+  /** Lead with high-priority files and hold low-priority ones for the last pack (dropped first over budget) */
+  prioritizeFiles: boolean;
+  /** One `glob = high|normal|low` rule per line */
+  priorityRules: string;
+  /** Pack low-priority files as signatures and doc comments, bodies elided */
+  outlineLowPriority: boolean;
+  /** File name pattern for exporting each pack ({project}, {index}, {total}, widths like {index:02}); "" = per-format default */
+  exportPattern: string;
+  /** Pack the files in `focusPaths` whole and verbatim under a "PRIMARY FILE UNDER DISCUSSION" banner */
+  focusFiles: boolean;
+  /** One project-relative path per line */
+  focusPaths: string;
+  /** Prefix each pack with project name, version, and git commit */
+  includeProvenance: boolean;
+  /** Label sections `[F001] path` and open each pack with an ID → path index */
+  fileIds: boolean;
+  /** Open each pack with the directories, languages, and exported symbols it covers */
+  packNotes: boolean;
+  /** Prefix each packed line with its line number so cited lines can be checked */
+  includeLineNumbers: boolean;
+  /** Append the paths of unselected files (no content) so the model can ask for them */
+  listExcludedFiles: boolean;
+  /** End the last pack with the env vars and feature flags the packed code reads */
+  configSurface: boolean;
+  /** End the last pack with the TODO/FIXME/HACK comments found in the packed files */
+  todoIndex: boolean;
+  /** End the last pack with an index of the error messages the packed code raises */
+  errorIndex: boolean;
+  /** End the last pack with the type declarations selected TS files import from unselected files */
+  typeClosure: boolean;
+  /** Pack the .proto/OpenAPI files behind generated code the selection uses */
+  includeSchemas: boolean;
+  /** Pack one reference locale per translation resource and summarize the others by key count */
+  summarizeLocales: boolean;
+  /** Pack CSV/TSV files as column names, row counts, and a few sample rows */
+  profileData: boolean;
+  /** Pack large config files matching `schemaGlobs` as an inferred schema instead of content */
+  schemaInference: boolean;
+  /** One glob per line */
+  schemaGlobs: string;
+  /** Files at or under this size keep their content */
+  schemaMinKb: number;
   astDeadCode: boolean;
   entryPoint: string | null;
   minifyMarkdown: boolean;
   stripMarkdownHeadings: boolean;
   stripMarkdownBlockquotes: boolean;
   respectGitignore: boolean;
+  /** Sort the tree like a file manager (`File2` before `File10`, case-insensitive) instead of by raw bytes */
+  naturalSort: boolean;
   customIgnorePatterns: string;
 }
 
@@ -47,21 +146,191 @@ export interface PackRequest {
     tokenCount?: number;
   }>;
   numPacks: number;
-  outputFormat: "plaintext" | "markdown";
-  llmProfileId: string;
+  outputFormat: OutputFormat;
+  profileId?: string;
+  /** Selects the backend tokenizer for counts the request doesn't carry */
+  llmProfileId?: string;
+  stripImports?: boolean;
+  /** Backend strips comments from files with a tree-sitter grammar */
+  stripComments?: boolean;
+  normalizeContent?: boolean;
+  /** Collapse blank lines and trailing whitespace; `stripIndentation` also left-aligns brace languages */
+  compressWhitespace?: { stripIndentation?: boolean };
+  /** Short per-file IDs in section headers plus an ID → path index per pack */
+  fileIds?: boolean;
+  includeLineNumbers?: boolean;
+  maxTokensPerPack?: number;
+  /** Files over this are packed as `name.part-i-of-n.ext` fragments */
+  maxTokensPerFile?: number;
+  strategy?: "grouped" | "dependency";
+  suggestPrompts?: boolean;
+  promptTemplate?: string;
+  wrapper?: PackWrapper;
+  boundaries?: SectionBoundaries;
+  template?: string;
+  previousManifest?: Record<string, string>;
+  provenance?: ProjectMetadata;
+  excludedPaths?: string[];
+  configSurface?: boolean;
+  todoIndex?: boolean;
+  errorIndex?: boolean;
+  typeClosure?: boolean;
+  includeSchemas?: boolean;
+  summarizeLocales?: boolean;
+  profileData?: boolean;
+  schemaInference?: SchemaInference;
+  redactions?: RedactionRule[];
+  priorities?: PriorityRule[];
+  outlineLowPriority?: boolean;
+  packNotes?: boolean;
+  focusPaths?: string[];
+}
+
+export interface SchemaInference {
+  globs: string[];
+  minBytes: number;
+}
+
+export interface RedactionRule {
+  pattern: string;
+  replacement?: string;
+}
+
+/** Glob and weight; the first matching rule wins and unmatched files are normal */
+export interface PriorityRule {
+  pattern: string;
+  weight: "high" | "normal" | "low";
+}
+
+export interface RedactionMatch {
+  path: string;
+  line: number;
+  pattern: string;
+  text: string;
+  replacement: string;
+}
+
+export interface RedactionReport {
+  matches: RedactionMatch[];
+  totalMatches: number;
+  filesAffected: number;
+}
+
+export interface ProjectMetadata {
+  name?: string;
+  version?: string;
+  commit?: string;
+  dirty: boolean;
+}
+
+export interface PackWrapper {
+  opening?: string;
+  closing?: string;
+  includeIndex: boolean;
+  projectName?: string;
+  includeTimestamp: boolean;
+  preamble?: string;
+  epilogue?: string;
+}
+
+export interface SectionBoundaries {
+  separator?: string;
+  fileStart?: string;
+  fileEnd?: string;
+}
+
+export interface PackProfile {
+  id: string;
+  name: string;
+  description: string;
+  outputFormat: OutputFormat;
+  numPacks: number;
+  maxTokensPerPack?: number;
+  stripImports: boolean;
+  normalizeContent: boolean;
+  strategy: "grouped" | "dependency";
 }
 
 export interface PackItem {
+  id: string;
+  /** blake3 of the file sections, stable across runs that pack the same content */
+  contentHash: string;
+  /** False when the run given as `previousManifest` produced this same pack */
+  changed: boolean;
   index: number;
   content: string;
   estimatedTokens: number;
   fileCount: number;
   filePaths: string[];
+  suggestedPrompt?: string;
+  /** Entries of `filePaths` that are parts of a split file */
+  fragments?: FileFragment[];
+  /** Files that alone exceeded the pack budget and were cut to their head and tail */
+  truncations?: FileTruncation[];
+  /** Tokens whitespace compression removed from this pack; absent when it was off */
+  savedTokens?: number;
+}
+
+export interface FileFragment {
+  path: string;
+  originalPath: string;
+  part: number;
+  total: number;
+}
+
+export interface FileTruncation {
+  path: string;
+  originalTokens: number;
+  keptTokens: number;
+  omittedLines: number;
+}
+
+export interface ClipboardChunk {
+  content: string;
+  index: number;
+  total: number;
+  done: boolean;
+}
+
+export interface PackPreview {
+  content: string;
+  truncated: boolean;
+  totalChars: number;
+  omittedFiles: number;
 }
 
 export interface PackResponse {
   packs: PackItem[];
   totalTokens: number;
+  warnings: string[];
+  fingerprint: string;
+  /** blake3 over every pack's contentHash; equal hashes mean nothing changed since that pack */
+  contentHash: string;
+  /** Hex blake3 per packed path; send back as the next request's `previousManifest` */
+  manifest: Record<string, string>;
+  /** Why each file sits where it does; absent for delta packs */
+  placements?: FilePlacement[];
+}
+
+export interface FilePlacement {
+  path: string;
+  packIndex: number;
+  position: number;
+  dependencyRank: number;
+  docBucket?: number;
+  component?: number;
+  pinned: boolean;
+  spillover: boolean;
+  priority?: PriorityRule["weight"];
+  reason: string;
+}
+
+export interface DeltaResponse {
+  pack: PackItem;
+  fingerprint: string;
+  changedPaths: string[];
+  removedPaths: string[];
+  warnings: string[];
 }
 
 export interface FileContent {
@@ -70,9 +339,39 @@ export interface FileContent {
   tokenCount?: number;
 }
 
+export interface ParseHealth {
+  status: "partial" | "failed";
+  errorCount: number;
+  firstErrorLine?: number;
+}
+
+export interface DeadExport {
+  filePath: string;
+  name: string;
+  kind: string;
+  startLine: number;
+  endLine: number;
+  safeToDelete: boolean;
+}
+
+export interface SymbolTokens {
+  name: string;
+  kind: string;
+  startLine: number;
+  endLine: number;
+  tokens: number;
+}
+
+export interface FileSymbolTokens {
+  filePath: string;
+  totalTokens: number;
+  symbols: SymbolTokens[];
+}
+
 export interface ReachabilityResult {
   reachable_symbols: Record<string, string[]>;
   unreachable_symbols: Record<string, string[]>;
+  parse_health?: Record<string, ParseHealth>;
 }
 
 export interface AppSettings {
@@ -99,3 +398,98 @@ export interface WorkerResult {
   requestId: number;
   results: TokenCountResult[];
 }
+
+export interface ExportRecord {
+  /** Set by the backend when recorded */
+  timestamp?: string;
+  project?: string;
+  kind: "file" | "combined" | "encrypted" | "clipboard";
+  fileCount: number;
+  tokenCount: number;
+  /** Written path, or "clipboard" */
+  destination: string;
+}
+
+export interface PatchHunkResult {
+  /** The `@@ -a,b +c,d @@` line */
+  header: string;
+  applied: boolean;
+  /** Lines between the stated and the matched position */
+  offset: number;
+  message?: string;
+}
+
+export interface PatchFileResult {
+  path: string;
+  status: "modified" | "created" | "deleted" | "conflict";
+  hunks: PatchHunkResult[];
+  error?: string;
+}
+
+export interface PatchReport {
+  files: PatchFileResult[];
+  /** Every hunk matched */
+  clean: boolean;
+  /** Changes were written to disk */
+  written: boolean;
+}
+
+export interface MergeConflict {
+  /** 1-based line of the `<<<<<<<` marker in the merged text */
+  line: number;
+  disk: string;
+  proposed: string;
+}
+
+/** `read_file_content_hashed`: content plus the hex blake3 pack fingerprints are built from */
+export interface HashedFileContent {
+  content: string;
+  hash: string;
+}
+
+export interface WriteBackResult {
+  path: string;
+  status: "written" | "merged" | "conflict";
+  conflicts: MergeConflict[];
+  /** On conflict, the merge with conflict markers */
+  merged?: string;
+  message?: string;
+}
+
+export interface UndoResult {
+  path: string;
+  /** The undone write had created the file, so it was deleted */
+  removed: boolean;
+  /** Earlier writes that can still be undone */
+  remaining: number;
+}
+
+export interface AutoSelectedFile {
+  path: string;
+  relativePath: string;
+  tokens: number;
+  /** Weighted reachability, importance, and recency, from 0 to 1 */
+  score: number;
+  reason: string;
+}
+
+/** The highest-value files of a project that fit a token budget, best first */
+export interface AutoSelection {
+  files: AutoSelectedFile[];
+  totalTokens: number;
+  budget: number;
+  /** Relative paths of the files left out, best first */
+  skippedPaths: string[];
+  summary: string;
+}
+
+/** A project stack recognized on first open, with the defaults applied for it */
+export interface DetectedStack {
+  id: string;
+  name: string;
+  /** Ignore globs added to the custom patterns when walking the project */
+  ignorePatterns: string[];
+  /** Suggested dead-code entry point, relative to the project root */
+  entryPoint?: string;
+  strategy: "grouped" | "dependency";
+}